@@ -0,0 +1,352 @@
+//! Read-only JSON state endpoint.
+//!
+//! With `state_endpoint.port` set, the panel serves its state as JSON on
+//! `127.0.0.1:<port>`, separate from the Zellij web server: the workspaces,
+//! their projects with a git summary, and the panes open in the session, so
+//! dashboards, launcher scripts and status bars can query it. Nothing can be
+//! changed through it, not even by the configuration migration. Requests
+//! must name `127.0.0.1:<port>` or `localhost:<port>` as their host, so web
+//! pages can't read the state through DNS rebinding.
+//! Each connection is answered on its own thread, and git summaries come
+//! from a cache shared by them, so a slow repository or client doesn't hold
+//! the others up.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::config::{Config, GitInfoLevel, Project};
+use crate::error::Result;
+use crate::git::{get_git_info_compared, GitCache, GitCompare, GitInfo};
+use crate::session::Session;
+
+/// How long a request may take to arrive.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Starts serving the state on localhost in a background thread.
+///
+/// The configuration and the session are read again for every request, so
+/// the answer follows edits and panes opened since. The configuration file
+/// is only read: an older one is upgraded in memory, never rewritten. Git
+/// information is cached like in the panel: until the repository's metadata
+/// changes, or for `STATUS_POLL_INTERVAL` at most.
+///
+/// # Arguments
+///
+/// * `port` - The port to listen on
+/// * `zellij_session` - The session whose panes are listed
+/// * `git_info_level` - The detail level of the git information
+///
+/// # Errors
+///
+/// Returns an error if the port can't be bound.
+pub fn spawn(port: u16, zellij_session: String, git_info_level: GitInfoLevel) -> Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    let zellij_session = Arc::new(zellij_session);
+    let cache = Arc::new(Mutex::new(GitCache::with_watcher(git_info_level)));
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let zellij_session = Arc::clone(&zellij_session);
+            let cache = Arc::clone(&cache);
+            // A client that misbehaves only loses its own request
+            thread::spawn(move || {
+                let _ = serve(stream, port, &zellij_session, &cache);
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Returns the git information of a project from the cache, computing and
+/// caching it on a miss.
+///
+/// The cache is not locked while the information is computed, so requests
+/// for other projects are answered meanwhile.
+fn cached_git_info(cache: &Mutex<GitCache>, config: &Config, project: &Project) -> Option<GitInfo> {
+    {
+        let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache.process_watch_events();
+        if cache.contains(&project.path) {
+            return cache.peek(&project.path);
+        }
+    }
+
+    let compare = GitCompare::resolve(config, project);
+    let info = get_git_info_compared(&project.path, config.global.git_info_level, &compare);
+    cache
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(project.path.clone(), info.clone());
+    info
+}
+
+/// Answers one request.
+fn serve(
+    stream: TcpStream,
+    port: u16,
+    zellij_session: &str,
+    cache: &Mutex<GitCache>,
+) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Only the host is used, but every header is read so the client sees a
+    // clean close
+    let mut host = None;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            }
+        }
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        _ if !is_local_host(host.as_deref(), port) => (
+            "403 Forbidden",
+            error(&format!(
+                "The host must be 127.0.0.1:{} or localhost:{}",
+                port, port
+            )),
+        ),
+        (Some("GET"), Some(target)) => route(target, || {
+            let config = Config::read_from(&Config::default_path()).ok();
+            let session = Session::load(zellij_session);
+            state(config.as_ref(), session.as_ref(), |config, project| {
+                cached_git_info(cache, config, project)
+            })
+        }),
+        _ => ("405 Method Not Allowed", error("Only GET is supported")),
+    };
+
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        body.len()
+    );
+    let mut stream = &stream;
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body.as_bytes())?;
+    Ok(())
+}
+
+/// Returns whether a request's Host header names this endpoint, as
+/// `127.0.0.1:<port>` or `localhost:<port>`.
+///
+/// # Arguments
+///
+/// * `host` - The Host header, None if the request has none
+/// * `port` - The port the endpoint listens on
+pub fn is_local_host(host: Option<&str>, port: u16) -> bool {
+    let Some((name, host_port)) = host.and_then(|host| host.rsplit_once(':')) else {
+        return false;
+    };
+    host_port == port.to_string() && (name == "127.0.0.1" || name.eq_ignore_ascii_case("localhost"))
+}
+
+/// Answers a request for a target: `/` and `/state` return the state.
+///
+/// # Arguments
+///
+/// * `target` - The request target, e.g. "/state"
+/// * `state` - Returns the state to serve
+///
+/// # Returns
+///
+/// The HTTP status, without the version, and the JSON body.
+pub fn route(target: &str, state: impl FnOnce() -> Value) -> (&'static str, String) {
+    let path = target.split_once('?').map_or(target, |(path, _)| path);
+    match path {
+        "/" | "/state" => ("200 OK", state().to_string()),
+        _ => ("404 Not Found", error("There is no such resource")),
+    }
+}
+
+/// Returns the body of an error response.
+fn error(message: &str) -> String {
+    json!({ "error": message }).to_string()
+}
+
+/// Builds the state served by the endpoint.
+///
+/// Workspaces and their projects are in display order, as in the panel; panes are sorted by project directory. `focus` is
+/// the workspace and project shown in the panel.
+///
+/// # Arguments
+///
+/// * `config` - The configuration, None if it can't be loaded
+/// * `session` - The state of the Zellij session, None if it has none
+/// * `git` - Returns the git information of a project
+pub fn state(
+    config: Option<&Config>,
+    session: Option<&Session>,
+    git: impl Fn(&Config, &Project) -> Option<GitInfo>,
+) -> Value {
+    let has_pane = |project: &Project| session.is_some_and(|s| s.get_pane(&project.path).is_some());
+
+    let workspaces: Vec<Value> = config
+        .map(|config| {
+            config
+                .workspace_ids()
+                .into_iter()
+                .map(|id| {
+                    let workspace = &config.workspace[id];
+                    let projects: Vec<Value> = workspace
+                        .projects
+                        .iter()
+                        .map(|project| {
+                            json!({
+                                "name": project.name,
+                                "path": project.path,
+                                "git": git(config, project).map(|info| git_summary(&info)),
+                                "has_pane": has_pane(project),
+                            })
+                        })
+                        .collect();
+                    json!({ "id": id, "name": workspace.name, "projects": projects })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut panes: Vec<(&std::path::PathBuf, Value)> = session
+        .map(|session| {
+            session
+                .panes
+                .iter()
+                .map(|(path, pane)| {
+                    let value = json!({
+                        "path": path,
+                        "name": pane.pane_name,
+                        "claude": pane.is_claude(),
+                        "attention": pane.needs_attention,
+                        "floating": pane.floating,
+                    });
+                    (path, value)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    panes.sort_by(|a, b| a.0.cmp(b.0));
+
+    json!({
+        "session": session.map(|s| s.zellij_session.as_str()),
+        "focus": session.and_then(|s| s.focus.as_ref()).map(|focus| json!({
+            "workspace": focus.workspace_id,
+            "project_path": focus.project_path,
+        })),
+        "workspaces": workspaces,
+        "panes": panes.into_iter().map(|(_, pane)| pane).collect::<Vec<Value>>(),
+    })
+}
+
+/// Returns the git summary of a project, as served.
+fn git_summary(info: &GitInfo) -> Value {
+    json!({
+        "head": info.head_label(),
+        "dirty": info.is_dirty,
+        "conflicted": info.conflicted_count,
+        "operation": info.operation.map(|operation| operation.label()),
+        "ahead": info.ahead,
+        "behind": info.behind,
+        "large_repo": info.large_repo,
+        "summary": info.format_minimal(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        serde_json::from_value(json!({
+            "global": {},
+            "workspace": {
+                "work": {
+                    "name": "Work",
+                    "projects": [
+                        { "name": "api", "path": "/work/api" },
+                        { "name": "web", "path": "/work/web" }
+                    ]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn when_building_state_should_list_projects_with_git_and_panes() {
+        let config = config();
+        let mut session = Session::new("gz-claude".to_string());
+        session.register_pane(
+            "/work/api".into(),
+            "Work/api · Claude".to_string(),
+            "claude /work/api".to_string(),
+        );
+
+        let state = state(Some(&config), Some(&session), |_, project| {
+            (project.name == "api").then(|| GitInfo {
+                branch: Some("main".to_string()),
+                is_dirty: true,
+                ..Default::default()
+            })
+        });
+
+        assert_eq!(state["session"], "gz-claude");
+        let projects = &state["workspaces"][0]["projects"];
+        assert_eq!(projects[0]["git"]["summary"], "main *");
+        assert_eq!(projects[0]["has_pane"], true);
+        assert_eq!(projects[1]["git"], Value::Null);
+        assert_eq!(state["panes"][0]["claude"], true);
+        assert_eq!(state["panes"][0]["path"], "/work/api");
+    }
+
+    #[test]
+    fn when_host_is_not_this_endpoint_should_not_be_local() {
+        assert!(is_local_host(Some("127.0.0.1:7000"), 7000));
+        assert!(is_local_host(Some("LocalHost:7000"), 7000));
+        assert!(!is_local_host(Some("localhost:7001"), 7000));
+        assert!(!is_local_host(Some("localhost"), 7000));
+        assert!(!is_local_host(Some("evil.example:7000"), 7000));
+        assert!(!is_local_host(Some("127.0.0.1.evil.example:7000"), 7000));
+        assert!(!is_local_host(None, 7000));
+    }
+
+    #[test]
+    fn when_a_client_stalls_should_still_answer_others() {
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        spawn(port, "gz-claude".to_string(), GitInfoLevel::default()).unwrap();
+
+        let _stalled = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        let mut client = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        client.set_read_timeout(Some(READ_TIMEOUT / 5)).unwrap();
+        client
+            .write_all(b"GET /state HTTP/1.1\r\nHost: evil.example\r\n\r\n")
+            .unwrap();
+        let mut status = String::new();
+        BufReader::new(&client).read_line(&mut status).unwrap();
+
+        assert!(status.starts_with("HTTP/1.1 403"));
+    }
+
+    #[test]
+    fn when_target_is_unknown_should_answer_not_found() {
+        assert_eq!(route("/state?pretty", || json!({})).0, "200 OK");
+        assert_eq!(route("/", || json!({})).1, "{}");
+        assert_eq!(route("/panes", || json!({})).0, "404 Not Found");
+    }
+}
@@ -0,0 +1,222 @@
+//! Arguments asked for when a command runs.
+//!
+//! Action and command bar commands can contain `{prompt:Message}`
+//! placeholders, e.g. `git checkout {prompt:Branch name}`. The panel asks
+//! for each message before running the command and puts the quoted answer in
+//! place of the placeholder; a message used twice is asked once. Answers are
+//! remembered per message in `~/.gz-claude/arguments.json`, shared by all
+//! Zellij sessions, and recalled with the arrow keys.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::util::shell::quote;
+
+/// File storing the answers given to each message.
+const HISTORY_FILE: &str = "arguments.json";
+
+/// Opening of an argument placeholder.
+const PLACEHOLDER_START: &str = "{prompt:";
+
+/// Maximum number of answers remembered per message.
+pub const MAX_HISTORY: usize = 20;
+
+/// Returns the placeholders of a command, as `(start, end, message)` with
+/// `end` past the closing brace.
+fn placeholders(command: &str) -> Vec<(usize, usize, &str)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = command[offset..].find(PLACEHOLDER_START) {
+        let start = offset + start;
+        let after = start + PLACEHOLDER_START.len();
+        let Some(end) = command[after..].find('}') else {
+            break;
+        };
+        found.push((start, after + end + 1, command[after..after + end].trim()));
+        offset = after + end + 1;
+    }
+    found
+}
+
+/// Returns the messages a command asks for, in order and without repeats.
+///
+/// # Arguments
+///
+/// * `command` - The command, with `{prompt:Message}` placeholders
+pub fn messages(command: &str) -> Vec<String> {
+    let mut messages: Vec<String> = Vec::new();
+    for (_, _, message) in placeholders(command) {
+        if !messages.iter().any(|m| m == message) {
+            messages.push(message.to_string());
+        }
+    }
+    messages
+}
+
+/// Replaces the placeholders of a command with answers.
+///
+/// # Arguments
+///
+/// * `command` - The command, with `{prompt:Message}` placeholders
+/// * `answers` - The answer to each message, in the order of `messages`
+///
+/// # Returns
+///
+/// The command with each placeholder replaced by its quoted answer.
+/// Placeholders without an answer are left as they are.
+pub fn fill(command: &str, answers: &[String]) -> String {
+    let messages = messages(command);
+    let mut filled = String::new();
+    let mut rest = 0;
+    for (start, end, message) in placeholders(command) {
+        let answer = messages
+            .iter()
+            .position(|m| m == message)
+            .and_then(|index| answers.get(index));
+        if let Some(answer) = answer {
+            filled.push_str(&command[rest..start]);
+            filled.push_str(&quote(answer));
+            rest = end;
+        }
+    }
+    filled.push_str(&command[rest..]);
+    filled
+}
+
+/// Answers given to each message, most recent first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArgumentHistory {
+    #[serde(default)]
+    messages: HashMap<String, Vec<String>>,
+}
+
+impl ArgumentHistory {
+    /// Returns the path to the history store.
+    pub fn default_path() -> PathBuf {
+        Config::default_dir().join(HISTORY_FILE)
+    }
+
+    /// Loads the history from the default store.
+    ///
+    /// # Returns
+    ///
+    /// The history, empty if the store doesn't exist or is invalid.
+    pub fn load() -> Self {
+        Self::load_from(&Self::default_path())
+    }
+
+    /// Loads the history from a file.
+    fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the history to the default store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::default_path())
+    }
+
+    /// Saves the history to a file, replacing it atomically.
+    fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Records an answer as the most recent one of a message.
+    ///
+    /// The answer moves to the front if it was already listed; the oldest
+    /// answers are dropped beyond `MAX_HISTORY`. Empty answers are not kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message asked
+    /// * `answer` - The answer given
+    pub fn record(&mut self, message: &str, answer: &str) {
+        if answer.is_empty() {
+            return;
+        }
+        let answers = self.messages.entry(message.to_string()).or_default();
+        answers.retain(|a| a != answer);
+        answers.insert(0, answer.to_string());
+        answers.truncate(MAX_HISTORY);
+    }
+
+    /// Returns the answers given to a message, most recent first.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message asked
+    pub fn answers(&self, message: &str) -> &[String] {
+        self.messages.get(message).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn when_command_repeats_a_message_should_ask_it_once() {
+        let command =
+            "git checkout {prompt:Branch} && git push -u origin {prompt: Branch } {prompt:Remote}";
+
+        assert_eq!(messages(command), vec!["Branch", "Remote"]);
+        assert!(messages("git checkout {prompt:Branch").is_empty());
+    }
+
+    #[test]
+    fn when_filling_command_should_quote_each_answer() {
+        let command = "git checkout {prompt:Branch} && git push origin {prompt:Branch}";
+
+        let filled = fill(command, &["it's-mine".to_string()]);
+
+        assert_eq!(
+            filled,
+            r"git checkout 'it'\''s-mine' && git push origin 'it'\''s-mine'"
+        );
+    }
+
+    #[test]
+    fn when_answer_is_missing_should_keep_the_placeholder() {
+        assert_eq!(
+            fill("echo {prompt:A} {prompt:B}", &["a".to_string()]),
+            "echo 'a' {prompt:B}"
+        );
+    }
+
+    #[test]
+    fn when_recording_answers_should_keep_most_recent_first_and_persist() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(HISTORY_FILE);
+        let mut history = ArgumentHistory::default();
+
+        history.record("Branch", "main");
+        history.record("Branch", "");
+        history.record("Branch", "dev");
+        history.record("Branch", "main");
+        history.save_to(&path).unwrap();
+
+        let loaded = ArgumentHistory::load_from(&path);
+        assert_eq!(loaded.answers("Branch"), ["main", "dev"]);
+        assert!(loaded.answers("Remote").is_empty());
+    }
+}
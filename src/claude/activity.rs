@@ -0,0 +1,287 @@
+//! Latest Claude activity of each project, read from its transcripts.
+//!
+//! The newest transcript of a project is the conversation its Claude pane
+//! is having. Its last assistant entry tells what Claude said or started
+//! doing last: the last line of its text, or the tool it called. Only the
+//! end of the transcript is read, and only when the file changed.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde_json::Value;
+
+use super::{transcripts_dir, truncate};
+
+/// Bytes read from the end of a transcript to find its last entries.
+const TAIL_BYTES: u64 = 64 * 1024;
+
+/// Maximum length of a snippet.
+const SNIPPET_MAX_CHARS: usize = 60;
+
+/// Returns the newest transcript of a transcript directory and when it was
+/// last modified.
+///
+/// # Arguments
+///
+/// * `dir` - The directory containing `.jsonl` transcripts
+pub fn newest_transcript(dir: &Path) -> Option<(PathBuf, SystemTime)> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .max_by_key(|(_, modified)| *modified)
+}
+
+/// Reads the last thing Claude said or did in a transcript.
+///
+/// # Arguments
+///
+/// * `path` - Path to the `.jsonl` transcript
+///
+/// # Returns
+///
+/// A one-line snippet, or None if the transcript has no assistant entry
+/// near its end.
+pub fn read_snippet(path: &Path) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let start = len.saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).ok()?;
+    let tail = String::from_utf8_lossy(&bytes);
+
+    // A tail starting mid-file begins with a partial line
+    let skip = usize::from(start > 0);
+    tail.lines()
+        .skip(skip)
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|entry| snippet_of(&entry))
+        .last()
+}
+
+/// Describes an assistant entry: the last line of its text, or the last
+/// tool it called.
+fn snippet_of(entry: &Value) -> Option<String> {
+    if entry["type"] != "assistant" {
+        return None;
+    }
+    let snippet = match &entry["message"]["content"] {
+        Value::String(text) => last_line(text)?.to_string(),
+        Value::Array(blocks) => {
+            blocks
+                .iter()
+                .rev()
+                .find_map(|block| match block["type"].as_str()? {
+                    "text" => last_line(block["text"].as_str()?).map(String::from),
+                    "tool_use" => {
+                        let name = block["name"].as_str()?;
+                        Some(match block["input"]["description"].as_str() {
+                            Some(description) => format!("{}: {}", name, description),
+                            None => name.to_string(),
+                        })
+                    }
+                    _ => None,
+                })?
+        }
+        _ => return None,
+    };
+    Some(truncate(&snippet, SNIPPET_MAX_CHARS))
+}
+
+/// Returns the last non-blank line of a text, trimmed.
+fn last_line(text: &str) -> Option<&str> {
+    text.lines().map(str::trim).rfind(|line| !line.is_empty())
+}
+
+/// The snippet of a project and the transcript it was read from.
+#[derive(Debug)]
+struct Entry {
+    transcript: PathBuf,
+    modified: SystemTime,
+    snippet: Option<String>,
+}
+
+/// Keeps the latest Claude snippet of projects with a Claude pane.
+#[derive(Debug, Default)]
+pub struct TranscriptMonitor {
+    entries: HashMap<PathBuf, Entry>,
+    last_poll: Option<Instant>,
+}
+
+impl TranscriptMonitor {
+    /// Creates a monitor watching no projects.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the latest snippet of a project, if any.
+    pub fn snippet(&self, project_path: &Path) -> Option<&str> {
+        self.entries.get(project_path)?.snippet.as_deref()
+    }
+
+    /// Reads the snippets of projects whose newest transcript changed.
+    ///
+    /// Projects no longer listed are forgotten. Does nothing until
+    /// `interval` elapsed since the previous poll.
+    ///
+    /// # Arguments
+    ///
+    /// * `projects` - The directories of projects with a Claude pane
+    /// * `interval` - Time between two polls
+    ///
+    /// # Returns
+    ///
+    /// True if a snippet changed.
+    pub fn poll<'a>(
+        &mut self,
+        projects: impl IntoIterator<Item = &'a Path>,
+        interval: Duration,
+    ) -> bool {
+        let now = Instant::now();
+        if self
+            .last_poll
+            .is_some_and(|last| now.duration_since(last) < interval)
+        {
+            return false;
+        }
+        self.last_poll = Some(now);
+        self.refresh(projects, transcripts_dir)
+    }
+
+    /// Reads the snippets of projects whose newest transcript changed,
+    /// finding transcripts with `dir_of`.
+    fn refresh<'a>(
+        &mut self,
+        projects: impl IntoIterator<Item = &'a Path>,
+        dir_of: impl Fn(&Path) -> PathBuf,
+    ) -> bool {
+        let projects: Vec<&Path> = projects.into_iter().collect();
+        let before = self.entries.len();
+        self.entries
+            .retain(|path, _| projects.contains(&path.as_path()));
+        let mut changed = self.entries.len() != before;
+
+        for project in projects {
+            let Some((transcript, modified)) = newest_transcript(&dir_of(project)) else {
+                changed |= self.entries.remove(project).is_some();
+                continue;
+            };
+            let unchanged = self
+                .entries
+                .get(project)
+                .is_some_and(|e| e.transcript == transcript && e.modified == modified);
+            if unchanged {
+                continue;
+            }
+            let snippet = read_snippet(&transcript);
+            let previous = self.entries.insert(
+                project.to_path_buf(),
+                Entry {
+                    transcript,
+                    modified,
+                    snippet: snippet.clone(),
+                },
+            );
+            changed |= previous.map_or(true, |p| p.snippet != snippet);
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const TRANSCRIPT: &str = r#"{"type":"user","message":{"role":"user","content":"Fix the failing test"}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Let me look.\nRunning tests…"}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","content":"ok"}]}}
+not json
+"#;
+
+    #[test]
+    fn when_last_assistant_entry_is_text_should_return_its_last_line() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.jsonl");
+        fs::write(&path, TRANSCRIPT).unwrap();
+
+        assert_eq!(read_snippet(&path).as_deref(), Some("Running tests…"));
+    }
+
+    #[test]
+    fn when_last_assistant_entry_calls_tool_should_describe_it() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.jsonl");
+        let tool = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Checking."},{"type":"tool_use","name":"Bash","input":{"command":"cargo test","description":"Run the test suite"}}]}}"#;
+        fs::write(&path, format!("{}{}\n", TRANSCRIPT, tool)).unwrap();
+
+        assert_eq!(
+            read_snippet(&path).as_deref(),
+            Some("Bash: Run the test suite")
+        );
+    }
+
+    #[test]
+    fn when_transcript_is_long_should_read_only_its_end() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.jsonl");
+        let filler = r#"{"type":"user","message":{"content":"padding"}}"#;
+        let mut content = TRANSCRIPT.to_string();
+        while (content.len() as u64) < TAIL_BYTES * 2 {
+            content.push_str(filler);
+            content.push('\n');
+        }
+        content
+            .push_str(r#"{"type":"assistant","message":{"content":"Waiting for your approval"}}"#);
+        fs::write(&path, content).unwrap();
+
+        assert_eq!(
+            read_snippet(&path).as_deref(),
+            Some("Waiting for your approval")
+        );
+    }
+
+    #[test]
+    fn when_newest_transcript_changes_should_report_new_snippet() {
+        let dir = TempDir::new().unwrap();
+        let project = Path::new("/work/api");
+        fs::write(dir.path().join("old.jsonl"), TRANSCRIPT).unwrap();
+        let transcripts = dir.path().to_path_buf();
+        let mut monitor = TranscriptMonitor::new();
+
+        assert!(monitor.refresh([project], |_| transcripts.clone()));
+        assert_eq!(monitor.snippet(project), Some("Running tests…"));
+        assert!(!monitor.refresh([project], |_| transcripts.clone()));
+
+        let newer = dir.path().join("new.jsonl");
+        fs::write(
+            &newer,
+            r#"{"type":"assistant","message":{"content":"Done"}}"#,
+        )
+        .unwrap();
+        let later = SystemTime::now() + Duration::from_secs(60);
+        File::options()
+            .write(true)
+            .open(&newer)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        assert!(monitor.refresh([project], |_| transcripts.clone()));
+        assert_eq!(monitor.snippet(project), Some("Done"));
+
+        assert!(monitor.refresh([], |_| transcripts.clone()));
+        assert_eq!(monitor.snippet(project), None);
+    }
+}
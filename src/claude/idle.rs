@@ -0,0 +1,284 @@
+//! Detection of Claude Code panes waiting for input.
+//!
+//! The screens of registered Claude panes are read periodically with
+//! `zellij action dump-screen`. While Claude Code generates, its status line
+//! offers to interrupt it ("esc to interrupt") and the screen keeps changing;
+//! a pane showing neither, unchanged since the previous read, is waiting for
+//! input. A pane that goes from
+//! generating to waiting needs attention until the user looks at it or
+//! Claude starts generating again.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+
+/// Status line texts shown by Claude Code while it generates.
+const WORKING_MARKERS: &[&str] = &["esc to interrupt", "ctrl+c to interrupt"];
+
+/// What a Claude pane is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneActivity {
+    /// Claude is generating.
+    Working,
+    /// Claude is waiting for input.
+    Idle,
+}
+
+/// Classifies a pane from its screen.
+///
+/// # Arguments
+///
+/// * `screen` - The text shown in the pane
+/// * `previous` - The text shown on the previous read, if any
+pub fn classify(screen: &str, previous: Option<&str>) -> PaneActivity {
+    let lower = screen.to_lowercase();
+    let generating = WORKING_MARKERS.iter().any(|marker| lower.contains(marker));
+    let changed = previous.is_some_and(|previous| previous != screen);
+    if generating || changed {
+        PaneActivity::Working
+    } else {
+        PaneActivity::Idle
+    }
+}
+
+/// Reads the screens of Claude panes in background threads and tracks which
+/// ones need attention.
+#[derive(Debug, Default)]
+pub struct IdleMonitor {
+    screens: HashMap<PathBuf, String>,
+    activity: HashMap<PathBuf, PaneActivity>,
+    attention: HashSet<PathBuf>,
+    running: HashMap<PathBuf, JoinHandle<Result<String>>>,
+    last_started: HashMap<PathBuf, Instant>,
+}
+
+impl IdleMonitor {
+    /// Creates a monitor watching no panes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether a project's Claude pane went idle since the user
+    /// last looked at it.
+    pub fn needs_attention(&self, project_path: &Path) -> bool {
+        self.attention.contains(project_path)
+    }
+
+    /// Marks a project's Claude pane as seen.
+    ///
+    /// # Returns
+    ///
+    /// True if the pane needed attention.
+    pub fn acknowledge(&mut self, project_path: &Path) -> bool {
+        self.attention.remove(project_path)
+    }
+
+    /// Collects finished screen reads and starts the ones that are due.
+    ///
+    /// Panes no longer listed are forgotten. A failed read leaves the pane's
+    /// state unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `panes` - The project directories and ids of the Claude panes
+    /// * `interval` - Time between two reads of a pane
+    /// * `dump` - Reads the screen of a pane by id
+    ///
+    /// # Returns
+    ///
+    /// The projects whose pane went idle since the last poll.
+    pub fn poll<'a>(
+        &mut self,
+        panes: impl IntoIterator<Item = (&'a Path, &'a str)>,
+        interval: Duration,
+        dump: fn(&str) -> Result<String>,
+    ) -> Vec<PathBuf> {
+        let panes: Vec<(&Path, &str)> = panes.into_iter().collect();
+        let watched: HashSet<&Path> = panes.iter().map(|(path, _)| *path).collect();
+        self.forget(|path| watched.contains(path));
+
+        let mut went_idle = Vec::new();
+        for (path, screen) in self.collect_finished() {
+            if self.observe(&path, screen) {
+                went_idle.push(path);
+            }
+        }
+
+        let now = Instant::now();
+        for (path, pane_id) in panes {
+            if self.running.contains_key(path) {
+                continue;
+            }
+            let due = self
+                .last_started
+                .get(path)
+                .map_or(true, |started| now.duration_since(*started) >= interval);
+            if due {
+                let pane_id = pane_id.to_string();
+                self.last_started.insert(path.to_path_buf(), now);
+                self.running
+                    .insert(path.to_path_buf(), thread::spawn(move || dump(&pane_id)));
+            }
+        }
+
+        went_idle
+    }
+
+    /// Records a screen read from a project's Claude pane.
+    ///
+    /// # Returns
+    ///
+    /// True if the pane went from generating to waiting for input.
+    pub fn observe(&mut self, project_path: &Path, screen: String) -> bool {
+        let activity = classify(&screen, self.screens.get(project_path).map(String::as_str));
+        let previous = self.activity.insert(project_path.to_path_buf(), activity);
+        self.screens.insert(project_path.to_path_buf(), screen);
+
+        match activity {
+            PaneActivity::Working => {
+                self.attention.remove(project_path);
+                false
+            }
+            PaneActivity::Idle if previous == Some(PaneActivity::Working) => {
+                self.attention.insert(project_path.to_path_buf())
+            }
+            PaneActivity::Idle => false,
+        }
+    }
+
+    /// Drops the state of panes that are no longer watched.
+    fn forget(&mut self, keep: impl Fn(&Path) -> bool) {
+        self.screens.retain(|path, _| keep(path));
+        self.activity.retain(|path, _| keep(path));
+        self.attention.retain(|path| keep(path));
+        self.last_started.retain(|path, _| keep(path));
+    }
+
+    /// Returns the screens read by threads that have finished.
+    fn collect_finished(&mut self) -> Vec<(PathBuf, String)> {
+        let finished: Vec<PathBuf> = self
+            .running
+            .iter()
+            .filter(|(_, handle)| handle.is_finished())
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        finished
+            .into_iter()
+            .filter_map(|path| {
+                let handle = self.running.remove(&path)?;
+                let screen = handle.join().ok()?.ok()?;
+                Some((path, screen))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENERATING: &str = "✻ Thinking… (12s · esc to interrupt)\n> ";
+    const WAITING: &str = "● Done.\n╭────╮\n│ >  │\n╰────╯";
+
+    #[test]
+    fn when_status_line_offers_interrupt_should_be_working() {
+        assert_eq!(
+            classify(GENERATING, Some(GENERATING)),
+            PaneActivity::Working
+        );
+        assert_eq!(classify(WAITING, None), PaneActivity::Idle);
+        assert_eq!(classify(WAITING, Some(WAITING)), PaneActivity::Idle);
+    }
+
+    #[test]
+    fn when_screen_keeps_changing_should_be_working() {
+        assert_eq!(
+            classify(WAITING, Some("● Writing tests")),
+            PaneActivity::Working
+        );
+    }
+
+    #[test]
+    fn when_pane_stops_generating_should_need_attention_until_seen() {
+        let mut monitor = IdleMonitor::new();
+        let path = Path::new("/work/api");
+
+        assert!(!monitor.observe(path, GENERATING.to_string()));
+        // The screen just changed, so Claude may still be writing
+        assert!(!monitor.observe(path, WAITING.to_string()));
+        assert!(monitor.observe(path, WAITING.to_string()));
+        assert!(monitor.needs_attention(path));
+        // Still idle: reported once
+        assert!(!monitor.observe(path, WAITING.to_string()));
+
+        assert!(monitor.acknowledge(path));
+        assert!(!monitor.needs_attention(path));
+    }
+
+    #[test]
+    fn when_pane_was_idle_from_the_start_should_not_need_attention() {
+        let mut monitor = IdleMonitor::new();
+        let path = Path::new("/work/api");
+
+        assert!(!monitor.observe(path, WAITING.to_string()));
+        assert!(!monitor.observe(path, WAITING.to_string()));
+        assert!(!monitor.needs_attention(path));
+    }
+
+    #[test]
+    fn when_claude_resumes_should_clear_attention() {
+        let mut monitor = IdleMonitor::new();
+        let path = Path::new("/work/api");
+        monitor.observe(path, GENERATING.to_string());
+        monitor.observe(path, WAITING.to_string());
+        monitor.observe(path, WAITING.to_string());
+
+        monitor.observe(path, GENERATING.to_string());
+
+        assert!(!monitor.needs_attention(path));
+    }
+
+    #[test]
+    fn when_polling_should_read_panes_and_forget_closed_ones() {
+        fn dump(pane_id: &str) -> Result<String> {
+            Ok(match pane_id {
+                "terminal_1" => GENERATING.to_string(),
+                _ => WAITING.to_string(),
+            })
+        }
+        let mut monitor = IdleMonitor::new();
+        let api = Path::new("/work/api");
+        monitor.observe(api, GENERATING.to_string());
+        monitor.observe(api, WAITING.to_string());
+        monitor.observe(api, WAITING.to_string());
+        assert!(monitor.needs_attention(api));
+
+        monitor.poll(
+            [(Path::new("/work/web"), "terminal_1")],
+            Duration::ZERO,
+            dump,
+        );
+        assert!(!monitor.needs_attention(api));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !monitor.activity.contains_key(Path::new("/work/web")) {
+            assert!(Instant::now() < deadline, "screen was not read");
+            thread::sleep(Duration::from_millis(10));
+            monitor.poll(
+                [(Path::new("/work/web"), "terminal_1")],
+                Duration::ZERO,
+                dump,
+            );
+        }
+        assert_eq!(
+            monitor.activity[Path::new("/work/web")],
+            PaneActivity::Working
+        );
+    }
+}
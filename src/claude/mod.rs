@@ -0,0 +1,388 @@
+//! Claude Code conversation transcripts.
+//!
+//! Claude Code stores one JSONL transcript per conversation under
+//! `~/.claude/projects/<encoded-project-path>/<session-id>.jsonl`. This module
+//! lists and parses those transcripts for a project.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Deserialize;
+
+use crate::util::shell;
+
+pub mod activity;
+pub mod idle;
+
+/// Maximum number of trailing messages kept for previews.
+const PREVIEW_MESSAGES: usize = 6;
+
+/// Maximum length of a conversation title.
+const TITLE_MAX_CHARS: usize = 60;
+
+/// Role of a message within a conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// A message typed by the user.
+    User,
+    /// A message produced by Claude.
+    Assistant,
+}
+
+/// A single text message of a conversation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// Who sent the message.
+    pub role: Role,
+    /// The text content of the message.
+    pub text: String,
+}
+
+/// Summary of a Claude Code conversation transcript.
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    /// The session identifier (transcript file stem), used to resume.
+    pub session_id: String,
+    /// Path to the transcript file.
+    pub path: PathBuf,
+    /// Conversation title (summary, or first user message).
+    pub title: String,
+    /// Last modification time of the transcript.
+    pub modified: SystemTime,
+    /// Number of user turns in the conversation.
+    pub turn_count: usize,
+    /// The last few text messages, oldest first.
+    pub last_messages: Vec<Message>,
+}
+
+/// Returns the root directory where Claude Code stores project transcripts.
+pub fn projects_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".claude")
+        .join("projects")
+}
+
+/// Encodes a project path the way Claude Code names transcript directories.
+///
+/// Every character that is not alphanumeric or `-` is replaced by `-`,
+/// e.g. `/home/me/my.app` becomes `-home-me-my-app`.
+///
+/// # Arguments
+///
+/// * `project_path` - The project directory
+pub fn encode_project_path(project_path: &Path) -> String {
+    project_path
+        .to_string_lossy()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Returns the transcript directory for a project.
+///
+/// # Arguments
+///
+/// * `project_path` - The project directory
+pub fn transcripts_dir(project_path: &Path) -> PathBuf {
+    projects_dir().join(encode_project_path(project_path))
+}
+
+/// Lists the conversations of a project, newest first.
+///
+/// # Arguments
+///
+/// * `project_path` - The project directory
+///
+/// # Returns
+///
+/// The parsed conversations, or an empty list if the project has none.
+pub fn list_conversations(project_path: &Path) -> Vec<Conversation> {
+    list_conversations_in(&transcripts_dir(project_path))
+}
+
+/// Lists the conversations stored in a transcript directory, newest first.
+///
+/// # Arguments
+///
+/// * `dir` - The directory containing `.jsonl` transcripts
+pub fn list_conversations_in(dir: &Path) -> Vec<Conversation> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut conversations: Vec<Conversation> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|path| load_conversation(&path))
+        .collect();
+
+    conversations.sort_by_key(|c| std::cmp::Reverse(c.modified));
+    conversations
+}
+
+/// A transcript line, with only the fields a conversation summary uses;
+/// tool calls, tool results and metadata are skipped while parsing.
+#[derive(Debug, Deserialize)]
+struct Entry {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    summary: Option<String>,
+    message: Option<EntryMessage>,
+}
+
+/// The message of a transcript line.
+#[derive(Debug, Deserialize)]
+struct EntryMessage {
+    content: Option<Content>,
+}
+
+/// The content of a message: plain text or an array of blocks.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Content {
+    Text(String),
+    Blocks(Vec<Block>),
+}
+
+/// A content block; only `text` blocks carry text.
+#[derive(Debug, Deserialize)]
+struct Block {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    text: Option<String>,
+}
+
+/// Parses a single transcript file.
+///
+/// The file is read line by line, keeping only the title and the last
+/// `PREVIEW_MESSAGES` messages, so long transcripts don't have to fit in
+/// memory. Lines that are not valid JSON or carry no text are skipped.
+/// Transcripts without any user or assistant message are ignored.
+///
+/// # Arguments
+///
+/// * `path` - Path to the `.jsonl` transcript
+///
+/// # Returns
+///
+/// The conversation summary, or None if the file can't be read or is empty.
+pub fn load_conversation(path: &Path) -> Option<Conversation> {
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let session_id = path.file_stem()?.to_string_lossy().to_string();
+
+    let mut summary: Option<String> = None;
+    let mut first_user: Option<String> = None;
+    let mut turn_count = 0;
+    let mut last_messages: VecDeque<Message> = VecDeque::with_capacity(PREVIEW_MESSAGES + 1);
+
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let Ok(entry) = serde_json::from_slice::<Entry>(&line) else {
+            continue;
+        };
+
+        if entry.kind.as_deref() == Some("summary") {
+            if entry.summary.is_some() {
+                summary = entry.summary;
+            }
+            continue;
+        }
+
+        let Some(message) = parse_message(entry) else {
+            continue;
+        };
+
+        if message.role == Role::User {
+            turn_count += 1;
+            first_user.get_or_insert_with(|| message.text.clone());
+        }
+        last_messages.push_back(message);
+        if last_messages.len() > PREVIEW_MESSAGES {
+            last_messages.pop_front();
+        }
+    }
+
+    if last_messages.is_empty() {
+        return None;
+    }
+
+    let title = summary
+        .or(first_user)
+        .map(|t| truncate(t.lines().next().unwrap_or(""), TITLE_MAX_CHARS))
+        .unwrap_or_else(|| session_id.clone());
+
+    Some(Conversation {
+        session_id,
+        path: path.to_path_buf(),
+        title,
+        modified,
+        turn_count,
+        last_messages: last_messages.into(),
+    })
+}
+
+/// Extracts the text message from a transcript entry, if any.
+///
+/// Content may be a plain string or an array of blocks; only `text` blocks are
+/// kept, so tool calls and tool results are skipped.
+fn parse_message(entry: Entry) -> Option<Message> {
+    let role = match entry.kind.as_deref()? {
+        "user" => Role::User,
+        "assistant" => Role::Assistant,
+        _ => return None,
+    };
+
+    let text = match entry.message?.content? {
+        Content::Text(text) => text,
+        Content::Blocks(blocks) => blocks
+            .into_iter()
+            .filter(|block| block.kind.as_deref() == Some("text"))
+            .filter_map(|block| block.text)
+            .collect::<Vec<String>>()
+            .join("\n"),
+    };
+
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    Some(Message {
+        role,
+        text: text.to_string(),
+    })
+}
+
+/// Truncates text to a maximum number of characters, adding an ellipsis.
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", truncated)
+}
+
+/// Builds the command that resumes a conversation inside the project directory.
+///
+/// # Arguments
+///
+/// * `project_path` - The project directory
+/// * `session_id` - The conversation's session identifier
+pub fn resume_command(project_path: &Path, session_id: &str) -> String {
+    format!(
+        "cd {} && claude --resume {}",
+        shell::quote(&project_path.to_string_lossy()),
+        shell::quote(session_id)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const TRANSCRIPT: &str = r#"{"type":"user","message":{"role":"user","content":"Fix the failing test"}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Running tests…"},{"type":"tool_use","name":"Bash"}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","content":"ok"}]}}
+not json
+{"type":"user","message":{"role":"user","content":"Thanks"}}
+"#;
+
+    #[test]
+    fn when_building_resume_command_should_quote_path_and_session() {
+        assert_eq!(
+            resume_command(Path::new("/home/me/it's here"), "abc-123"),
+            r"cd '/home/me/it'\''s here' && claude --resume 'abc-123'"
+        );
+    }
+
+    #[test]
+    fn when_encoding_project_path_should_replace_separators() {
+        assert_eq!(
+            encode_project_path(Path::new("/home/me/my.app")),
+            "-home-me-my-app"
+        );
+    }
+
+    #[test]
+    fn when_loading_transcript_should_parse_text_messages() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("abc-123.jsonl");
+        fs::write(&path, TRANSCRIPT).unwrap();
+
+        let conversation = load_conversation(&path).unwrap();
+
+        assert_eq!(conversation.session_id, "abc-123");
+        assert_eq!(conversation.title, "Fix the failing test");
+        assert_eq!(conversation.turn_count, 2);
+        assert_eq!(conversation.last_messages.len(), 3);
+        assert_eq!(conversation.last_messages[1].role, Role::Assistant);
+        assert_eq!(conversation.last_messages[1].text, "Running tests…");
+    }
+
+    #[test]
+    fn when_transcript_has_summary_should_use_it_as_title() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("s.jsonl");
+        let content = format!(
+            "{}\n{}",
+            r#"{"type":"summary","summary":"Test suite repair"}"#, TRANSCRIPT
+        );
+        fs::write(&path, content).unwrap();
+
+        let conversation = load_conversation(&path).unwrap();
+
+        assert_eq!(conversation.title, "Test suite repair");
+    }
+
+    #[test]
+    fn when_transcript_is_long_should_keep_only_the_last_messages() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("long.jsonl");
+        let content: String = (1..=20)
+            .map(|n| {
+                format!(
+                    "{{\"type\":\"user\",\"message\":{{\"content\":\"Step {}\"}}}}\n",
+                    n
+                )
+            })
+            .collect();
+        fs::write(&path, content).unwrap();
+
+        let conversation = load_conversation(&path).unwrap();
+
+        assert_eq!(conversation.title, "Step 1");
+        assert_eq!(conversation.turn_count, 20);
+        assert_eq!(conversation.last_messages.len(), PREVIEW_MESSAGES);
+        assert_eq!(conversation.last_messages[0].text, "Step 15");
+        assert_eq!(conversation.last_messages[5].text, "Step 20");
+    }
+
+    #[test]
+    fn when_listing_missing_directory_should_return_empty() {
+        let dir = TempDir::new().unwrap();
+
+        assert!(list_conversations_in(&dir.path().join("missing")).is_empty());
+    }
+}
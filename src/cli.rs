@@ -2,7 +2,10 @@
 //!
 //! @author waabox(waabox[at]gmail[dot]com)
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::config::{RemoteProvider, SessionPolicy};
 
 /// TUI for orchestrating Zellij workspaces with Claude Code.
 #[derive(Parser, Debug)]
@@ -17,6 +20,20 @@ pub struct Cli {
     #[arg(long, conflicts_with = "web")]
     pub no_web: bool,
 
+    /// What to do when the gz-claude Zellij session already exists
+    /// (defaults to `zellij.session_policy` in the config)
+    #[arg(long, value_enum)]
+    pub session_policy: Option<SessionPolicy>,
+
+    /// Preview what actions and commands would run instead of running them
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Configuration file to use instead of ~/.gz-claude/config.* (also
+    /// read from GZ_CLAUDE_CONFIG)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -24,7 +41,177 @@ pub struct Cli {
 #[derive(Subcommand, Debug)]
 pub enum Command {
     /// Run the TUI panel (inside Zellij)
-    Panel,
+    Panel {
+        /// Check project paths and git in the background after the first frame
+        #[arg(long)]
+        fast: bool,
+    },
     /// Run the top bar (inside Zellij)
     TopBar,
+    /// Open the working set of a workspace, one tab per project, in its own
+    /// Zellij session
+    Up {
+        /// The workspace id, as in the configuration
+        workspace: String,
+    },
+    /// Clone the repositories of projects whose directory doesn't exist
+    Clone {
+        /// Only clone the project with this name
+        project: Option<String>,
+    },
+    /// Create a workspace from the repositories of a GitHub organization or
+    /// GitLab group, cloning the ones selected
+    Import(ImportArgs),
+    /// Share workspace definitions as TOML snippets with relative paths
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceAction,
+    },
+    /// Manage the configuration file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Print, compare or regenerate the Zellij layout file
+    Layout {
+        #[command(subcommand)]
+        action: LayoutAction,
+    },
+    /// Export or import the panes of a session
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+    /// Manage secrets referenced as {{secret:NAME}} in command bar items
+    Secret {
+        #[command(subcommand)]
+        action: SecretAction,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// The code host
+    #[arg(value_enum)]
+    pub provider: RemoteProvider,
+    /// The organization or user (GitHub), or the group (GitLab)
+    #[arg(long, alias = "group")]
+    pub org: String,
+    /// Directory the repositories are cloned into (defaults to ./<org>)
+    #[arg(long)]
+    pub root: Option<PathBuf>,
+    /// Id of the new workspace (defaults to the organization)
+    #[arg(long)]
+    pub workspace: Option<String>,
+    /// Import every repository instead of asking which ones
+    #[arg(long)]
+    pub all: bool,
+    /// List archived repositories too
+    #[arg(long)]
+    pub archived: bool,
+    /// Clone over HTTPS instead of SSH
+    #[arg(long)]
+    pub https: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WorkspaceAction {
+    /// Print a workspace as a TOML snippet, its paths relative to a root
+    Export {
+        /// The workspace id, as in the configuration
+        workspace: String,
+        /// Directory paths are written relative to (defaults to the
+        /// workspace's discover directory, or the one containing its projects)
+        #[arg(long)]
+        root: Option<PathBuf>,
+        /// Write the snippet to this file instead of printing it
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Merge the workspaces of a snippet into the configuration, asking
+    /// which side to keep where they differ
+    Import {
+        /// The snippet to read
+        file: PathBuf,
+        /// Directory relative paths are resolved against (defaults to the
+        /// current directory)
+        #[arg(long)]
+        root: Option<PathBuf>,
+        /// Take the imported definitions without asking
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Write the configuration in another format (JSON, YAML or TOML),
+    /// detected by the output extension
+    Convert {
+        /// The file to write, e.g. ~/.gz-claude/config.yaml
+        output: PathBuf,
+        /// The configuration to convert (defaults to the one in use)
+        #[arg(long)]
+        from: Option<PathBuf>,
+    },
+    /// Upgrade the configuration file to the current schema, keeping the
+    /// original as a backup
+    Migrate {
+        /// The configuration to upgrade (defaults to the one in use)
+        file: Option<PathBuf>,
+    },
+    /// Report likely mistakes that don't make the configuration invalid
+    Lint {
+        /// The configuration to lint (defaults to the one in use)
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LayoutAction {
+    /// Print the layout gz-claude generates from the configuration
+    Dump {
+        /// Write the layout to this file instead of printing it
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Show how the installed layout differs from the generated one
+    Inspect,
+    /// Write the generated layout over the installed one
+    Regenerate {
+        /// Overwrite the installed layout even if it was edited
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SessionAction {
+    /// Write the panes of a session to a JSON snapshot
+    Export {
+        /// The snapshot file to write
+        file: PathBuf,
+        /// The Zellij session to export (defaults to the current one)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Open the panes of a snapshot again in the current Zellij session
+    Import {
+        /// The snapshot file to read
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SecretAction {
+    /// Store a secret in the OS keychain (value read from stdin)
+    Set {
+        /// Name used in {{secret:NAME}}
+        name: String,
+    },
+    /// Remove a secret from the OS keychain
+    Delete {
+        /// Name used in {{secret:NAME}}
+        name: String,
+    },
 }
@@ -0,0 +1,137 @@
+//! Project bookmarks.
+//!
+//! A bookmark names a subdirectory of a project, e.g. `api = "services/api"`
+//! in a monorepo. Bookmarks are listed in the file browser, and actions can
+//! run in one with `"cwd": "{bookmark:api}"` or reference it in their command.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::path::PathBuf;
+
+use super::{Action, Project};
+use crate::error::ConfigError;
+use crate::util::shell::quote;
+
+/// Opening of a bookmark placeholder.
+const PLACEHOLDER_START: &str = "{bookmark:";
+
+impl Project {
+    /// Returns the directory of a bookmark.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the bookmark
+    ///
+    /// # Returns
+    ///
+    /// The bookmarked directory within the project, or None if there is no
+    /// bookmark with the name.
+    pub fn bookmark_path(&self, name: &str) -> Option<PathBuf> {
+        self.bookmarks
+            .get(name)
+            .map(|relative| self.path.join(relative.trim()))
+    }
+
+    /// Returns the bookmarks with their directories, sorted by name.
+    pub fn bookmark_paths(&self) -> Vec<(&str, PathBuf)> {
+        self.bookmarks
+            .keys()
+            .filter_map(|name| Some((name.as_str(), self.bookmark_path(name)?)))
+            .collect()
+    }
+
+    /// Replaces the `{bookmark:NAME}` placeholders of a command.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The shell command
+    ///
+    /// # Returns
+    ///
+    /// The command with each placeholder replaced by the quoted directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::UnknownBookmark` if a placeholder names no
+    /// bookmark of the project.
+    pub fn expand_bookmarks(&self, command: &str) -> Result<String, ConfigError> {
+        let mut expanded = String::new();
+        let mut rest = command;
+        while let Some(start) = rest.find(PLACEHOLDER_START) {
+            let after = &rest[start + PLACEHOLDER_START.len()..];
+            let Some(end) = after.find('}') else {
+                break;
+            };
+            let name = &after[..end];
+            let path = self.bookmark_path(name).ok_or_else(|| self.unknown(name))?;
+            expanded.push_str(&rest[..start]);
+            expanded.push_str(&quote(&path.to_string_lossy()));
+            rest = &after[end + 1..];
+        }
+        expanded.push_str(rest);
+        Ok(expanded)
+    }
+
+    /// Returns the directory an action runs in.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action
+    ///
+    /// # Returns
+    ///
+    /// The action's `cwd`, as a bookmark or a path relative to the project,
+    /// or the project path if it has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::UnknownBookmark` if `cwd` names no bookmark of
+    /// the project.
+    pub fn action_dir(&self, action: &Action) -> Result<PathBuf, ConfigError> {
+        let Some(cwd) = action
+            .cwd
+            .as_deref()
+            .map(str::trim)
+            .filter(|cwd| !cwd.is_empty())
+        else {
+            return Ok(self.path.clone());
+        };
+        match cwd
+            .strip_prefix(PLACEHOLDER_START)
+            .and_then(|name| name.strip_suffix('}'))
+        {
+            Some(name) => self.bookmark_path(name).ok_or_else(|| self.unknown(name)),
+            None => Ok(self.path.join(cwd)),
+        }
+    }
+
+    /// Checks that bookmark names can be used in placeholders and that
+    /// their paths are set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidBookmark` for the first invalid bookmark.
+    pub(super) fn validate_bookmarks(&self) -> Result<(), ConfigError> {
+        for (name, relative) in &self.bookmarks {
+            let invalid = |reason: &str| ConfigError::InvalidBookmark {
+                project_name: self.name.clone(),
+                name: name.clone(),
+                reason: reason.to_string(),
+            };
+            if name.trim().is_empty() || name.contains(['{', '}']) {
+                return Err(invalid("names can't be empty or contain braces"));
+            }
+            if relative.trim().is_empty() {
+                return Err(invalid("the path is empty"));
+            }
+        }
+        Ok(())
+    }
+
+    fn unknown(&self, name: &str) -> ConfigError {
+        ConfigError::UnknownBookmark {
+            project_name: self.name.clone(),
+            name: name.to_string(),
+        }
+    }
+}
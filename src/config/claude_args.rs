@@ -0,0 +1,128 @@
+//! Claude flags of an action.
+//!
+//! An action running `claude` can pass it flags with `claude_args`, e.g. a
+//! permission mode. Flags are checked against those of Claude Code, and the
+//! ones skipping its permission prompts are only passed in projects marked
+//! `sandboxed`, such as throwaway worktrees or containers.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use super::{Action, ConfigError, Project};
+use crate::mcp::is_claude_command;
+use crate::util::shell::quote;
+
+/// The flags of Claude Code an action can pass, with whether they take a
+/// value.
+const CLAUDE_FLAGS: &[(&str, bool)] = &[
+    ("--permission-mode", true),
+    ("--dangerously-skip-permissions", false),
+    ("--model", true),
+    ("--fallback-model", true),
+    ("--allowedTools", true),
+    ("--allowed-tools", true),
+    ("--disallowedTools", true),
+    ("--disallowed-tools", true),
+    ("--add-dir", true),
+    ("--append-system-prompt", true),
+    ("--settings", true),
+    ("--continue", false),
+    ("--verbose", false),
+    ("--debug", false),
+    ("--ide", false),
+];
+
+/// The values of `--permission-mode`.
+const PERMISSION_MODES: &[&str] = &["default", "acceptEdits", "plan", "bypassPermissions"];
+
+impl Action {
+    /// Checks the action's `claude_args`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidClaudeArgs` if they are set on an action
+    /// that doesn't run `claude`, name an unknown flag, miss a flag's value
+    /// or give an unknown permission mode.
+    pub fn validate_claude_args(&self) -> Result<(), ConfigError> {
+        if self.claude_args.is_empty() {
+            return Ok(());
+        }
+        let invalid = |reason: String| ConfigError::InvalidClaudeArgs {
+            action_name: self.name.clone(),
+            reason,
+        };
+        if !is_claude_command(&self.command) {
+            return Err(invalid("its command doesn't run claude".to_string()));
+        }
+        let mut args = self.claude_args.iter();
+        while let Some(flag) = args.next() {
+            let Some((_, takes_value)) = CLAUDE_FLAGS.iter().find(|(known, _)| known == flag)
+            else {
+                return Err(invalid(format!("'{}' is not a known Claude flag", flag)));
+            };
+            if !takes_value {
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| invalid(format!("'{}' needs a value", flag)))?;
+            if flag == "--permission-mode" && !PERMISSION_MODES.contains(&value.as_str()) {
+                return Err(invalid(format!(
+                    "'{}' is not a permission mode; use one of {}",
+                    value,
+                    PERMISSION_MODES.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether the action's `claude_args` skip Claude's permission
+    /// prompts.
+    pub fn skips_permissions(&self) -> bool {
+        self.claude_args
+            .windows(2)
+            .any(|pair| pair[0] == "--permission-mode" && pair[1] == "bypassPermissions")
+            || self
+                .claude_args
+                .iter()
+                .any(|arg| arg == "--dangerously-skip-permissions")
+    }
+}
+
+impl Project {
+    /// Adds an action's `claude_args` to its command.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action
+    /// * `command` - Its command, expanded
+    ///
+    /// # Returns
+    ///
+    /// The command with the quoted flags after `claude`, or unchanged if the
+    /// action has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::UnsafeClaudeArgs` if the flags skip Claude's
+    /// permission prompts and the project isn't `sandboxed`.
+    pub fn claude_command(&self, action: &Action, command: &str) -> Result<String, ConfigError> {
+        if action.claude_args.is_empty() || !is_claude_command(command) {
+            return Ok(command.to_string());
+        }
+        if action.skips_permissions() && !self.sandboxed {
+            return Err(ConfigError::UnsafeClaudeArgs {
+                action_name: action.name.clone(),
+                project: self.name.clone(),
+            });
+        }
+        let command = command.trim_start();
+        let (program, rest) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        let flags: Vec<String> = action.claude_args.iter().map(|arg| quote(arg)).collect();
+        Ok(format!("{} {} {}", program, flags.join(" "), rest)
+            .trim_end()
+            .to_string())
+    }
+}
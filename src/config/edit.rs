@@ -0,0 +1,389 @@
+//! Write-back editing of the configuration file.
+//!
+//! Edits operate on the document rather than on `Config`, so keys keep
+//! their order and settings unknown to this version survive the round trip.
+//! The file keeps its format (JSON, YAML or TOML). TOML files are patched
+//! in place, so their comments and layout survive; YAML files are rewritten,
+//! so edits are refused when they have comments to lose.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::patch::update_toml;
+use super::{Config, ConfigFormat};
+use crate::error::{ConfigError, Result};
+
+/// A project added along with a new workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewProject {
+    /// The project name, unique within the workspace.
+    pub name: String,
+    /// The project directory.
+    pub path: PathBuf,
+    /// What the project is.
+    pub description: Option<String>,
+    /// The repository cloned into `path`.
+    pub repo: String,
+}
+
+impl Config {
+    /// Appends a project to a workspace in the configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_path` - The path to the configuration file
+    /// * `workspace_id` - The identifier of the workspace to add the project to
+    /// * `name` - The project name, unique within the workspace
+    /// * `project_path` - The project directory
+    ///
+    /// # Returns
+    ///
+    /// Ok(()) once the file has been written.
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::EmptyProjectName` if the name is empty or whitespace
+    /// - `ConfigError::PathNotFound` if the project path does not exist
+    /// - `ConfigError::PathNotDirectory` if the project path is not a directory
+    /// - `ConfigError::WorkspaceNotFound` if the workspace is not configured
+    /// - `ConfigError::DuplicateProject` if the workspace has a project with that name
+    /// - `ConfigError::CommentsWouldBeLost` if the file is YAML with comments
+    /// - `ConfigError::ReadError` or `ConfigError::ParseError` if the file can't be loaded
+    pub fn add_project(
+        config_path: &Path,
+        workspace_id: &str,
+        name: &str,
+        project_path: &Path,
+    ) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ConfigError::EmptyProjectName.into());
+        }
+        if !project_path.exists() {
+            return Err(ConfigError::PathNotFound {
+                path: project_path.to_path_buf(),
+            }
+            .into());
+        }
+        if !project_path.is_dir() {
+            return Err(ConfigError::PathNotDirectory {
+                path: project_path.to_path_buf(),
+            }
+            .into());
+        }
+
+        let mut document = read_document(config_path)?;
+        let projects = projects_mut(&mut document, workspace_id)?;
+        if projects
+            .iter()
+            .any(|p| p.get("name").and_then(Value::as_str) == Some(name))
+        {
+            return Err(ConfigError::DuplicateProject {
+                name: name.to_string(),
+            }
+            .into());
+        }
+
+        let mut project = Map::new();
+        project.insert("name".to_string(), Value::from(name));
+        project.insert(
+            "path".to_string(),
+            Value::from(project_path.to_string_lossy().into_owned()),
+        );
+        projects.push(Value::Object(project));
+
+        update_document(config_path, &document)
+    }
+
+    /// Removes a project from a workspace in the configuration file.
+    ///
+    /// The project is looked up by name and path in the file as it is now,
+    /// so edits made to it since it was loaded can't remove the wrong entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_path` - The path to the configuration file
+    /// * `workspace_id` - The identifier of the workspace containing the project
+    /// * `name` - The project name
+    /// * `project_path` - The project directory, as written in the file
+    ///
+    /// # Returns
+    ///
+    /// Ok(()) once the file has been written.
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::WorkspaceNotFound` if the workspace is not configured
+    /// - `ConfigError::ProjectNotFound` if the workspace has no such project
+    /// - `ConfigError::CommentsWouldBeLost` if the file is YAML with comments
+    /// - `ConfigError::ReadError` or `ConfigError::ParseError` if the file can't be loaded
+    pub fn remove_project(
+        config_path: &Path,
+        workspace_id: &str,
+        name: &str,
+        project_path: &Path,
+    ) -> Result<()> {
+        let mut document = read_document(config_path)?;
+        let projects = projects_mut(&mut document, workspace_id)?;
+        let path = project_path.to_string_lossy();
+        let Some(index) = projects.iter().position(|project| {
+            project.get("name").and_then(Value::as_str) == Some(name)
+                && project.get("path").and_then(Value::as_str) == Some(path.as_ref())
+        }) else {
+            return Err(ConfigError::ProjectNotFound {
+                workspace_id: workspace_id.to_string(),
+                name: name.to_string(),
+            }
+            .into());
+        };
+        projects.remove(index);
+
+        update_document(config_path, &document)
+    }
+
+    /// Adds a workspace with its projects to the configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_path` - The path to the configuration file
+    /// * `workspace_id` - The identifier of the new workspace
+    /// * `name` - The workspace name
+    /// * `projects` - The projects, cloned from their `repo` when their
+    ///   directory doesn't exist
+    ///
+    /// # Returns
+    ///
+    /// Ok(()) once the file has been written.
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::DuplicateWorkspace` if the workspace is already configured
+    /// - `ConfigError::CommentsWouldBeLost` if the file is YAML with comments
+    /// - `ConfigError::ReadError` or `ConfigError::ParseError` if the file can't be loaded
+    pub fn add_workspace(
+        config_path: &Path,
+        workspace_id: &str,
+        name: &str,
+        projects: &[NewProject],
+    ) -> Result<()> {
+        let mut document = read_document(config_path)?;
+        let Some(workspaces) = document
+            .as_object_mut()
+            .map(|root| {
+                root.entry("workspace")
+                    .or_insert_with(|| Value::Object(Map::new()))
+            })
+            .and_then(Value::as_object_mut)
+        else {
+            return Err(ConfigError::FormatParseError {
+                format: ConfigFormat::of_file(config_path).name(),
+                message: "'workspace' is not a table of workspaces".to_string(),
+            }
+            .into());
+        };
+        if workspaces.contains_key(workspace_id) {
+            return Err(ConfigError::DuplicateWorkspace {
+                workspace_id: workspace_id.to_string(),
+            }
+            .into());
+        }
+
+        let projects: Vec<Value> = projects
+            .iter()
+            .map(|project| {
+                let mut entry = Map::new();
+                entry.insert("name".to_string(), Value::from(project.name.as_str()));
+                entry.insert(
+                    "path".to_string(),
+                    Value::from(project.path.to_string_lossy().into_owned()),
+                );
+                if let Some(description) = &project.description {
+                    entry.insert("description".to_string(), Value::from(description.as_str()));
+                }
+                entry.insert("repo".to_string(), Value::from(project.repo.as_str()));
+                Value::Object(entry)
+            })
+            .collect();
+        let mut workspace = Map::new();
+        workspace.insert("name".to_string(), Value::from(name));
+        workspace.insert("projects".to_string(), Value::Array(projects));
+        workspaces.insert(workspace_id.to_string(), Value::Object(workspace));
+
+        update_document(config_path, &document)
+    }
+
+    /// Returns the line where a project of a workspace is named in the
+    /// configuration file, for opening the file at its entry.
+    ///
+    /// The text is searched rather than parsed, so it works for every
+    /// format: the first `name` key holding the project's name after the
+    /// first mention of the workspace. Projects written on a single line
+    /// are not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_path` - The path to the configuration file
+    /// * `workspace_id` - The identifier of the workspace
+    /// * `name` - The project name
+    ///
+    /// # Returns
+    ///
+    /// The 1-based line number, or None if the file can't be read or the
+    /// project isn't found.
+    pub fn project_line(config_path: &Path, workspace_id: &str, name: &str) -> Option<u32> {
+        let content = fs::read_to_string(config_path).ok()?;
+        let lines: Vec<&str> = content.lines().collect();
+        let start = lines
+            .iter()
+            .position(|line| line.contains(workspace_id))
+            .unwrap_or(0);
+        let index = (start..lines.len()).find(|&i| is_name_line(lines[i], name))?;
+        u32::try_from(index + 1).ok()
+    }
+
+    /// Writes a configuration file in another format.
+    ///
+    /// The formats are detected by the extensions. Every setting is kept,
+    /// including the ones unknown to this version; comments are not.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The configuration file to convert
+    /// * `output` - The file to write, which must not exist
+    ///
+    /// # Returns
+    ///
+    /// Ok(()) once the file has been written.
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::AlreadyExists` if the output file exists
+    /// - `ConfigError::UnsupportedFormat` if an extension is not a known format
+    /// - `ConfigError::FormatWriteError` if the output format can't represent a setting
+    /// - `ConfigError::ReadError` or `ConfigError::ParseError` if the input can't be loaded
+    pub fn convert(input: &Path, output: &Path) -> Result<()> {
+        if output.exists() {
+            return Err(ConfigError::AlreadyExists {
+                path: output.to_path_buf(),
+            }
+            .into());
+        }
+        ConfigFormat::from_path(output)?;
+
+        let document = read_document(input)?;
+        write_document(output, &document)
+    }
+}
+
+/// Reads the configuration file as a JSON document, whatever its format.
+pub(super) fn read_document(path: &Path) -> Result<Value> {
+    if !path.exists() {
+        return Err(ConfigError::NotFound(path.to_path_buf()).into());
+    }
+    let format = ConfigFormat::of_file(path);
+    let content = fs::read_to_string(path).map_err(ConfigError::ReadError)?;
+    format.parse(&content)
+}
+
+/// Writes an edited document back to the configuration file it was read
+/// from, in the file's format.
+///
+/// TOML files are patched with the edit only, keeping their comments and
+/// layout. JSON has no comments and is rewritten as a whole, and so is YAML
+/// as long as it has none.
+///
+/// # Errors
+///
+/// - `ConfigError::CommentsWouldBeLost` if the file is YAML with comments
+/// - `ConfigError::ReadError` or `ConfigError::ParseError` if the file can't be loaded
+pub(super) fn update_document(path: &Path, document: &Value) -> Result<()> {
+    let format = ConfigFormat::of_file(path);
+    let content = fs::read_to_string(path).map_err(ConfigError::ReadError)?;
+    let updated = match format {
+        ConfigFormat::Toml => {
+            let original = format.parse(&content)?;
+            update_toml(&content, &original, document)?
+        }
+        ConfigFormat::Yaml if has_yaml_comments(&content) => {
+            return Err(ConfigError::CommentsWouldBeLost {
+                path: path.to_path_buf(),
+            }
+            .into());
+        }
+        _ => format.write(document)?,
+    };
+    write_atomically(path, &updated)
+}
+
+/// Writes a JSON document to a configuration file, in the file's format.
+///
+/// The document is written to a temporary file first and then renamed, so a
+/// failed write never leaves a truncated configuration behind.
+pub(super) fn write_document(path: &Path, document: &Value) -> Result<()> {
+    let content = ConfigFormat::of_file(path).write(document)?;
+    write_atomically(path, &content)
+}
+
+/// Writes a file through a temporary one, renamed over it once complete.
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let temp_path = with_suffix(path, "tmp");
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Returns whether YAML text has comments: lines starting with `#`, or a `#`
+/// after a blank. `#` inside quoted strings counts too, which errs on the
+/// side of refusing an edit.
+fn has_yaml_comments(content: &str) -> bool {
+    content.lines().any(|line| {
+        line.trim_start().starts_with('#') || line.contains(" #") || line.contains("\t#")
+    })
+}
+
+/// Returns a path next to a file, named after it with a suffix appended
+/// (`config.json` and `tmp` give `config.json.tmp`).
+pub(super) fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Returns whether a line sets `name` to a value, in JSON (`"name": "api",`),
+/// YAML (`- name: api`) or TOML (`name = "api"`).
+fn is_name_line(line: &str, name: &str) -> bool {
+    let line = line
+        .trim()
+        .trim_start_matches('-')
+        .trim()
+        .trim_end_matches(',');
+    let Some((key, value)) = line.split_once([':', '=']) else {
+        return false;
+    };
+    let unquote = |text: &str| text.trim().trim_matches(['"', '\'']).to_string();
+    unquote(key) == "name" && unquote(value) == name
+}
+
+/// Returns the projects array of a workspace, creating it if missing.
+fn projects_mut<'a>(document: &'a mut Value, workspace_id: &str) -> Result<&'a mut Vec<Value>> {
+    document
+        .get_mut("workspace")
+        .and_then(|w| w.get_mut(workspace_id))
+        .and_then(Value::as_object_mut)
+        .and_then(|workspace| {
+            workspace
+                .entry("projects")
+                .or_insert_with(|| Value::Array(Vec::new()))
+                .as_array_mut()
+        })
+        .ok_or_else(|| {
+            ConfigError::WorkspaceNotFound {
+                workspace_id: workspace_id.to_string(),
+            }
+            .into()
+        })
+}
@@ -0,0 +1,62 @@
+//! Shell environment of a project's panes.
+//!
+//! A project with `env` set runs the commands of its panes through a wrapper
+//! that loads its environment first: `direnv exec {path}` by default, so
+//! Claude and shells start with the toolchain versions of its `.envrc`, or a
+//! command of its own such as `mise exec -C {path} --`.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde::Deserialize;
+
+use super::Project;
+use crate::util::shell;
+
+/// Wrapper used with `"env": true`.
+pub const DEFAULT_ENV_COMMAND: &str = "direnv exec {path}";
+
+/// How the commands of a project's panes get its environment.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum ProjectEnv {
+    /// `true` wraps the commands with `direnv exec {path}`, `false` doesn't.
+    Enabled(bool),
+    /// A wrapper of its own, `{path}` being replaced by the project directory.
+    Command(String),
+}
+
+impl Project {
+    /// Returns the wrapper loading the project's environment, if `env` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - What `{path}` is replaced with: the quoted project
+    ///   directory, or `.` for commands started in it
+    pub fn env_wrapper(&self, path: &str) -> Option<String> {
+        let template = match self.env.as_ref()? {
+            ProjectEnv::Enabled(true) => DEFAULT_ENV_COMMAND,
+            ProjectEnv::Enabled(false) => return None,
+            ProjectEnv::Command(command) if command.trim().is_empty() => return None,
+            ProjectEnv::Command(command) => command.trim(),
+        };
+        Some(template.replace("{path}", path))
+    }
+
+    /// Wraps a pane command so it runs in the project's environment.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The shell command of the pane
+    ///
+    /// # Returns
+    ///
+    /// `<wrapper> sh -c '<command>'`, or the command unchanged when `env`
+    /// isn't set.
+    pub fn wrap_env(&self, command: &str) -> String {
+        let path = shell::quote(&self.path.to_string_lossy());
+        match self.env_wrapper(&path) {
+            Some(wrapper) => format!("{} sh -c {}", wrapper, shell::quote(command)),
+            None => command.to_string(),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Configuration file formats.
+//!
+//! The configuration can be written as JSON, YAML or TOML; the format is
+//! detected by the file extension, JSON when the extension is none of them,
+//! and all of them share the same schema.
+//! Documents are handled as JSON values, so migrations and write-back edits
+//! work the same regardless of the format on disk.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::error::{ConfigError, Result};
+
+/// Configuration file names looked up in the configuration directory, in
+/// order of precedence.
+pub const CONFIG_FILE_NAMES: &[&str] = &["config.json", "config.yaml", "config.yml", "config.toml"];
+
+/// The format of a configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Detects the format of a configuration file from its extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The configuration file
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::UnsupportedFormat` if the extension is not
+    /// `.json`, `.yaml`, `.yml` or `.toml`.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("json") => Ok(Self::Json),
+            Some("yaml") | Some("yml") => Ok(Self::Yaml),
+            Some("toml") => Ok(Self::Toml),
+            _ => Err(ConfigError::UnsupportedFormat {
+                path: path.to_path_buf(),
+            }
+            .into()),
+        }
+    }
+
+    /// Returns the format a configuration file is read and written in: the
+    /// one of its extension, or JSON when the extension is missing or not a
+    /// known format (e.g. `~/gz.conf`).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The configuration file
+    pub fn of_file(path: &Path) -> Self {
+        Self::from_path(path).unwrap_or(Self::Json)
+    }
+
+    /// Returns the name of the format, as shown in errors.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Yaml => "YAML",
+            Self::Toml => "TOML",
+        }
+    }
+
+    /// Parses a configuration, or a configuration document as a JSON value.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The text of the configuration file
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::ParseError` if the JSON content is invalid
+    /// - `ConfigError::FormatParseError` if the YAML or TOML content is invalid
+    pub fn parse<T: DeserializeOwned>(self, content: &str) -> Result<T> {
+        let parsed = match self {
+            Self::Json => {
+                return Ok(serde_json::from_str(content).map_err(ConfigError::ParseError)?)
+            }
+            Self::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+            Self::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+        };
+        parsed.map_err(|message| {
+            ConfigError::FormatParseError {
+                format: self.name(),
+                message: message.trim_end().to_string(),
+            }
+            .into()
+        })
+    }
+
+    /// Writes a configuration document in this format.
+    ///
+    /// TOML has no null: null settings are left out, which reads back as
+    /// unset.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - The configuration document
+    ///
+    /// # Returns
+    ///
+    /// The text of the configuration file, ending with a newline.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::FormatWriteError` if the document can't be
+    /// represented in this format (e.g. a null inside a TOML array).
+    pub fn write(self, document: &Value) -> Result<String> {
+        let written = match self {
+            Self::Json => serde_json::to_string_pretty(document)
+                .map(|mut content| {
+                    content.push('\n');
+                    content
+                })
+                .map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::to_string(document).map_err(|e| e.to_string()),
+            Self::Toml => {
+                toml::to_string_pretty(&without_nulls(document)).map_err(|e| e.to_string())
+            }
+        };
+        written.map_err(|message| {
+            ConfigError::FormatWriteError {
+                format: self.name(),
+                message,
+            }
+            .into()
+        })
+    }
+}
+
+/// Returns a copy of a document without null object members.
+fn without_nulls(document: &Value) -> Value {
+    match document {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key.clone(), without_nulls(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(without_nulls).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document() -> Value {
+        json!({
+            "version": 1,
+            "global": { "editor": "vim", "git_info_level": null },
+            "workspace": {
+                "helios": {
+                    "name": "Helios",
+                    "projects": [{ "name": "gateway", "path": "/tmp" }]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn when_extension_is_known_should_detect_format() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.json")).unwrap(),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.YML")).unwrap(),
+            ConfigFormat::Yaml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.toml")).unwrap(),
+            ConfigFormat::Toml
+        );
+        assert!(ConfigFormat::from_path(Path::new("config.ini")).is_err());
+        assert!(ConfigFormat::from_path(Path::new("config")).is_err());
+    }
+
+    #[test]
+    fn when_extension_is_unknown_or_missing_should_read_file_as_json() {
+        assert_eq!(
+            ConfigFormat::of_file(Path::new("gz.conf")),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::of_file(Path::new("config")),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::of_file(Path::new("config.yaml")),
+            ConfigFormat::Yaml
+        );
+    }
+
+    #[test]
+    fn when_round_tripping_through_yaml_should_keep_document() {
+        let content = ConfigFormat::Yaml.write(&document()).unwrap();
+        let parsed: Value = ConfigFormat::Yaml.parse(&content).unwrap();
+
+        assert_eq!(parsed, document());
+    }
+
+    #[test]
+    fn when_writing_toml_should_leave_nulls_out() {
+        let content = ConfigFormat::Toml.write(&document()).unwrap();
+        let parsed: Value = ConfigFormat::Toml.parse(&content).unwrap();
+
+        assert!(content.contains("[workspace.helios]"), "{}", content);
+        assert_eq!(parsed["global"], json!({ "editor": "vim" }));
+        assert_eq!(parsed["workspace"], document()["workspace"]);
+    }
+
+    #[test]
+    fn when_toml_is_invalid_should_name_the_format() {
+        let message = ConfigFormat::Toml
+            .parse::<Value>("[global\neditor = 1")
+            .unwrap_err()
+            .to_string();
+
+        assert!(
+            message.contains("Failed to parse TOML configuration"),
+            "{}",
+            message
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Action key conflict detection.
+//!
+//! Resolves the effective keymap of every project and reports action keys that
+//! are swallowed by built-in bindings or that shadow an inherited action.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{Action, Config, NavigationMode};
+
+/// Keys handled by the TUI before custom actions are looked up.
+///
+/// Must stay in sync with `tui::terminal::key_to_event`.
+pub const RESERVED_KEYS: &[char] = &[
+    'j', 'k', 'h', 'l', 'q', 'r', ':', 'y', 'Y', 'C', 'H', 'E', 'G', 'w', 'a', 'D', 'x', 'R', 'f',
+    'z', 'e', 'A', 'N', 'n', 's', 'L', '/', 'o', 'P', 'O', 'b', 'B', 'F', 'M', 'm', '1', '2', '3',
+    '4', '5', '6', '7', '8', '9',
+];
+
+/// Keys additionally reserved with `"navigation": "vim"` (`gg`, `gs`).
+pub const VIM_RESERVED_KEYS: &[char] = &['g'];
+
+/// Where an action key is defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySource {
+    /// `global.actions`
+    Global,
+    /// `workspace.<id>.actions`
+    Workspace(String),
+    /// `actions` of a project within a workspace.
+    Project {
+        /// The identifier of the workspace.
+        workspace_id: String,
+        /// The project's name.
+        project: String,
+    },
+}
+
+impl fmt::Display for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeySource::Global => write!(f, "global"),
+            KeySource::Workspace(id) => write!(f, "workspace '{}'", id),
+            KeySource::Project {
+                workspace_id,
+                project,
+            } => write!(f, "project '{}' in workspace '{}'", project, workspace_id),
+        }
+    }
+}
+
+/// Why an action key is in conflict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// The key is bound to a built-in command, so the action is unreachable.
+    Reserved,
+    /// The key replaces an action inherited from a less specific level.
+    Shadows(KeySource),
+}
+
+/// An action key conflict found while resolving keymaps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyConflict {
+    /// The conflicting key.
+    pub key: String,
+    /// The name of the action defined with the key.
+    pub action_name: String,
+    /// Where the conflicting action is defined.
+    pub source: KeySource,
+    /// The kind of conflict.
+    pub kind: ConflictKind,
+}
+
+impl fmt::Display for KeyConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ConflictKind::Reserved => write!(
+                f,
+                "key '{}' of action '{}' ({}) is reserved by a built-in binding",
+                self.key, self.action_name, self.source
+            ),
+            ConflictKind::Shadows(inherited) => write!(
+                f,
+                "key '{}' of action '{}' ({}) shadows the action defined in {}",
+                self.key, self.action_name, self.source, inherited
+            ),
+        }
+    }
+}
+
+impl Config {
+    /// Finds action key conflicts across all levels of the configuration.
+    ///
+    /// Reports keys reserved by built-in bindings and keys that shadow an
+    /// inherited action. Shadowing is not reported for actions marked with
+    /// `"override": true`. Conflicts are returned in a stable order: global
+    /// first, then workspaces and their projects in workspace display order.
+    ///
+    /// # Returns
+    ///
+    /// The list of conflicts, empty if the keymaps are consistent.
+    pub fn key_conflicts(&self) -> Vec<KeyConflict> {
+        let mut conflicts = Vec::new();
+        let extra_reserved = match self.global.navigation {
+            NavigationMode::Vim => VIM_RESERVED_KEYS,
+            NavigationMode::Default => &[],
+        };
+
+        // Effective keymap at the current level, with the source of each key
+        let mut global_keys: HashMap<&str, KeySource> = HashMap::new();
+        check_level(
+            &self.global.actions,
+            &KeySource::Global,
+            extra_reserved,
+            &mut global_keys,
+            &mut conflicts,
+        );
+
+        for workspace_id in self.workspace_ids() {
+            let workspace = &self.workspace[workspace_id];
+            let mut workspace_keys = global_keys.clone();
+            check_level(
+                &workspace.actions,
+                &KeySource::Workspace(workspace_id.clone()),
+                extra_reserved,
+                &mut workspace_keys,
+                &mut conflicts,
+            );
+
+            for project in &workspace.projects {
+                let mut project_keys = workspace_keys.clone();
+                check_level(
+                    &project.actions,
+                    &KeySource::Project {
+                        workspace_id: workspace_id.clone(),
+                        project: project.name.clone(),
+                    },
+                    extra_reserved,
+                    &mut project_keys,
+                    &mut conflicts,
+                );
+            }
+        }
+
+        conflicts
+    }
+}
+
+/// Checks the actions of one level against reserved keys and inherited keys,
+/// then adds them to the effective keymap.
+fn check_level<'a>(
+    actions: &'a HashMap<String, Action>,
+    source: &KeySource,
+    extra_reserved: &[char],
+    keymap: &mut HashMap<&'a str, KeySource>,
+    conflicts: &mut Vec<KeyConflict>,
+) {
+    let mut keys: Vec<&String> = actions.keys().collect();
+    keys.sort();
+
+    for key in keys {
+        let action = &actions[key];
+        let conflict = |kind| KeyConflict {
+            key: key.clone(),
+            action_name: action.name.clone(),
+            source: source.clone(),
+            kind,
+        };
+
+        let mut chars = key.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if RESERVED_KEYS.contains(&c) || extra_reserved.contains(&c) {
+                conflicts.push(conflict(ConflictKind::Reserved));
+            }
+        }
+
+        if let Some(inherited) = keymap.get(key.as_str()) {
+            if !action.overrides {
+                conflicts.push(conflict(ConflictKind::Shadows(inherited.clone())));
+            }
+        }
+
+        keymap.insert(key.as_str(), source.clone());
+    }
+}
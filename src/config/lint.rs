@@ -0,0 +1,244 @@
+//! Configuration linting.
+//!
+//! Reports issues that don't make the configuration invalid but are likely
+//! mistakes: unreachable or shadowed action keys, icons too wide for the
+//! panel's columns, default actions and bookmarks a project doesn't have,
+//! projects sharing a directory and empty workspaces.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{Config, KeyConflict, KeySource};
+use crate::error::ConfigError;
+
+/// Graphemes an icon may have before it breaks the alignment of lists.
+pub const MAX_ICON_GRAPHEMES: usize = 2;
+
+/// A non-fatal configuration issue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintWarning {
+    /// An action key is reserved or shadows an inherited action.
+    KeyConflict(KeyConflict),
+    /// An icon is longer than `MAX_ICON_GRAPHEMES` graphemes.
+    WideIcon {
+        /// Where the action or command bar item is defined.
+        source: KeySource,
+        /// The name of the action or command bar item.
+        name: String,
+        /// The icon.
+        icon: String,
+    },
+    /// An action inherited by a project uses a bookmark the project lacks.
+    UnknownBookmark {
+        /// The identifier of the workspace.
+        workspace_id: String,
+        /// The project's name.
+        project: String,
+        /// The name of the action.
+        action_name: String,
+        /// The missing bookmark.
+        bookmark: String,
+    },
+    /// A project's default action is not one of its action keys.
+    UnknownDefaultAction {
+        /// The identifier of the workspace.
+        workspace_id: String,
+        /// The project's name.
+        project: String,
+        /// The key of the default action.
+        key: String,
+    },
+    /// Several projects point to the same directory, so they share panes.
+    DuplicatePath {
+        /// The shared directory.
+        path: PathBuf,
+        /// The projects, as "workspace/project".
+        projects: Vec<String>,
+    },
+    /// A workspace has no projects.
+    EmptyWorkspace {
+        /// The identifier of the workspace.
+        workspace_id: String,
+    },
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintWarning::KeyConflict(conflict) => conflict.fmt(f),
+            LintWarning::WideIcon { source, name, icon } => write!(
+                f,
+                "icon '{}' of '{}' ({}) is longer than {} characters and breaks alignment",
+                icon, name, source, MAX_ICON_GRAPHEMES
+            ),
+            LintWarning::UnknownBookmark {
+                workspace_id,
+                project,
+                action_name,
+                bookmark,
+            } => write!(
+                f,
+                "action '{}' uses the bookmark '{}', which project '{}' in workspace '{}' doesn't have",
+                action_name, bookmark, project, workspace_id
+            ),
+            LintWarning::UnknownDefaultAction {
+                workspace_id,
+                project,
+                key,
+            } => write!(
+                f,
+                "the default action '{}' of project '{}' in workspace '{}' is not one of its action keys",
+                key, project, workspace_id
+            ),
+            LintWarning::DuplicatePath { path, projects } => write!(
+                f,
+                "projects {} share the directory {}",
+                projects.join(", "),
+                path.display()
+            ),
+            LintWarning::EmptyWorkspace { workspace_id } => {
+                write!(f, "workspace '{}' has no projects", workspace_id)
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Reports non-fatal issues of the configuration.
+    ///
+    /// Key conflicts are included when `global.key_conflicts` is "warn".
+    /// Warnings are returned in a stable order: key conflicts, icons, unknown
+    /// default actions and missing bookmarks, shared directories, then empty
+    /// workspaces, each in workspace display order.
+    ///
+    /// # Returns
+    ///
+    /// The warnings, empty if nothing looks wrong.
+    pub fn lint(&self) -> Vec<LintWarning> {
+        let mut warnings: Vec<LintWarning> = self
+            .key_conflict_warnings()
+            .into_iter()
+            .map(LintWarning::KeyConflict)
+            .collect();
+
+        let workspace_ids = self.workspace_ids();
+
+        let mut levels = vec![(
+            KeySource::Global,
+            &self.global.actions,
+            &self.global.command_bar,
+        )];
+        for workspace_id in &workspace_ids {
+            let workspace = &self.workspace[*workspace_id];
+            levels.push((
+                KeySource::Workspace((*workspace_id).clone()),
+                &workspace.actions,
+                &workspace.command_bar,
+            ));
+            for project in &workspace.projects {
+                levels.push((
+                    KeySource::Project {
+                        workspace_id: (*workspace_id).clone(),
+                        project: project.name.clone(),
+                    },
+                    &project.actions,
+                    &project.command_bar,
+                ));
+            }
+        }
+        for (source, actions, command_bar) in levels {
+            let mut keys: Vec<&String> = actions.keys().collect();
+            keys.sort();
+            let named_icons = keys
+                .into_iter()
+                .map(|key| (&actions[key].name, &actions[key].icon))
+                .chain(command_bar.iter().map(|item| (&item.name, &item.icon)));
+            for (name, icon) in named_icons {
+                let Some(icon) = icon else {
+                    continue;
+                };
+                if icon.graphemes(true).count() > MAX_ICON_GRAPHEMES {
+                    warnings.push(LintWarning::WideIcon {
+                        source: source.clone(),
+                        name: name.clone(),
+                        icon: icon.clone(),
+                    });
+                }
+            }
+        }
+
+        for workspace_id in &workspace_ids {
+            for (index, project) in self.workspace[*workspace_id].projects.iter().enumerate() {
+                let actions = self.resolve_actions(workspace_id, index);
+                if let Some(key) = self.resolve_default_action(workspace_id, index) {
+                    if !actions.contains_key(key) {
+                        warnings.push(LintWarning::UnknownDefaultAction {
+                            workspace_id: (*workspace_id).clone(),
+                            project: project.name.clone(),
+                            key: key.to_string(),
+                        });
+                    }
+                }
+                let mut keys: Vec<&String> = actions.keys().collect();
+                keys.sort();
+                for key in keys {
+                    let action = &actions[key];
+                    let missing = project
+                        .action_dir(action)
+                        .err()
+                        .into_iter()
+                        .chain(project.expand_bookmarks(&action.command).err())
+                        .chain(
+                            action
+                                .panes
+                                .iter()
+                                .filter_map(|pane| project.expand_bookmarks(&pane.command).err()),
+                        );
+                    for error in missing {
+                        if let ConfigError::UnknownBookmark { name, .. } = error {
+                            warnings.push(LintWarning::UnknownBookmark {
+                                workspace_id: (*workspace_id).clone(),
+                                project: project.name.clone(),
+                                action_name: action.name.clone(),
+                                bookmark: name,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut by_path: BTreeMap<&PathBuf, Vec<String>> = BTreeMap::new();
+        for workspace_id in &workspace_ids {
+            for project in &self.workspace[*workspace_id].projects {
+                by_path
+                    .entry(&project.path)
+                    .or_default()
+                    .push(format!("{}/{}", workspace_id, project.name));
+            }
+        }
+        for (path, projects) in by_path {
+            if projects.len() > 1 {
+                warnings.push(LintWarning::DuplicatePath {
+                    path: path.clone(),
+                    projects,
+                });
+            }
+        }
+
+        for workspace_id in workspace_ids {
+            if self.workspace[workspace_id].projects.is_empty() {
+                warnings.push(LintWarning::EmptyWorkspace {
+                    workspace_id: workspace_id.clone(),
+                });
+            }
+        }
+
+        warnings
+    }
+}
@@ -0,0 +1,148 @@
+//! MCP servers given to Claude.
+//!
+//! `mcp` lists the MCP servers Claude can use, globally, per workspace and per
+//! project, e.g. `mcp.github = { command = "github-mcp", env = { ... } }`.
+//! A more specific level replaces a server with the same name. The servers
+//! are toggled in the panel and written to the `--mcp-config` file of the
+//! next Claude launch.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use super::{default_true, Config};
+use crate::error::ConfigError;
+
+/// An MCP server Claude can use.
+///
+/// Exactly one of `command` (a local server spoken to over stdio) or `url`
+/// (a remote server) must be set.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct McpServer {
+    /// Program started for a local server.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Arguments of `command`.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment variables of `command`.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Address of a remote server.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Protocol spoken with `url`.
+    #[serde(default)]
+    pub transport: McpTransport,
+    /// HTTP headers sent to `url`, e.g. an authorization token.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Whether Claude gets the server until it is toggled in the panel.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// Protocol spoken with a remote MCP server.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransport {
+    /// Streamable HTTP.
+    #[default]
+    Http,
+    /// Server-sent events.
+    Sse,
+}
+
+impl McpServer {
+    /// Returns the server as an entry of Claude's `mcpServers` settings.
+    pub fn to_settings(&self) -> serde_json::Value {
+        match (&self.command, &self.url) {
+            (Some(command), _) => serde_json::json!({
+                "type": "stdio",
+                "command": command,
+                "args": self.args,
+                "env": self.env,
+            }),
+            (None, url) => serde_json::json!({
+                "type": match self.transport {
+                    McpTransport::Http => "http",
+                    McpTransport::Sse => "sse",
+                },
+                "url": url.as_deref().unwrap_or_default(),
+                "headers": self.headers,
+            }),
+        }
+    }
+}
+
+impl Config {
+    /// Resolves the MCP servers of a project, applying inheritance:
+    /// global -> workspace -> project.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The identifier of the workspace
+    /// * `project_index` - The index of the project within the workspace
+    ///
+    /// # Returns
+    ///
+    /// The servers by name; a more specific level replaces a server with the
+    /// same name.
+    pub fn resolve_mcp_servers(
+        &self,
+        workspace_id: &str,
+        project_index: usize,
+    ) -> BTreeMap<String, McpServer> {
+        let mut servers = self.global.mcp.clone();
+        if let Some(workspace) = self.workspace.get(workspace_id) {
+            servers.extend(workspace.mcp.clone());
+            if let Some(project) = workspace.projects.get(project_index) {
+                servers.extend(project.mcp.clone());
+            }
+        }
+        servers
+    }
+
+    /// Returns true if any level of the configuration lists MCP servers.
+    pub fn has_mcp_servers(&self) -> bool {
+        !self.global.mcp.is_empty()
+            || self
+                .workspace
+                .values()
+                .any(|w| !w.mcp.is_empty() || w.projects.iter().any(|p| !p.mcp.is_empty()))
+    }
+
+    /// Checks that every MCP server is either local or remote.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidMcpServer` for the first invalid server.
+    pub(super) fn validate_mcp_servers(&self) -> Result<(), ConfigError> {
+        let levels = std::iter::once(&self.global.mcp).chain(
+            self.workspace
+                .values()
+                .flat_map(|w| std::iter::once(&w.mcp).chain(w.projects.iter().map(|p| &p.mcp))),
+        );
+        for servers in levels {
+            for (name, server) in servers {
+                let invalid = |reason: &str| ConfigError::InvalidMcpServer {
+                    name: name.clone(),
+                    reason: reason.to_string(),
+                };
+                if name.trim().is_empty() {
+                    return Err(invalid("names can't be empty"));
+                }
+                let command = server.command.as_deref().map(str::trim);
+                let url = server.url.as_deref().map(str::trim);
+                match (command, url) {
+                    (Some(_), Some(_)) => return Err(invalid("set either command or url")),
+                    (None, None) => return Err(invalid("command or url is required")),
+                    (Some(""), _) | (_, Some("")) => return Err(invalid("it is empty")),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! Configuration schema versioning and migration.
+//!
+//! The configuration carries a top-level `version`. Files without one are
+//! version 0. Older shapes are upgraded step by step on load; files written
+//! by a newer gz-claude are rejected instead of being misread.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde_json::{Map, Value};
+
+use crate::error::{ConfigError, Result};
+
+/// Schema version written by this version of gz-claude.
+pub const CONFIG_VERSION: u64 = 1;
+
+/// A migration upgrades a document by one version.
+///
+/// Returns true if it changed the document's shape.
+type Migration = fn(&mut Map<String, Value>) -> bool;
+
+/// Migrations in order; entry `i` upgrades version `i` to `i + 1`.
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
+
+/// Upgrades a configuration document to the current schema version.
+///
+/// The version is only stamped into the document when a migration changed
+/// its shape, so configurations that are already compatible are left as is.
+///
+/// # Arguments
+///
+/// * `document` - The parsed configuration file
+///
+/// # Returns
+///
+/// The version the document was upgraded from, or None if nothing changed.
+///
+/// # Errors
+///
+/// - `ConfigError::UnsupportedVersion` if the document is from a newer gz-claude
+pub fn migrate(document: &mut Value) -> Result<Option<u64>> {
+    let Some(root) = document.as_object_mut() else {
+        return Ok(None);
+    };
+
+    let version = root.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > CONFIG_VERSION {
+        return Err(ConfigError::UnsupportedVersion {
+            found: version,
+            supported: CONFIG_VERSION,
+        }
+        .into());
+    }
+
+    let mut changed = false;
+    for migration in &MIGRATIONS[version as usize..] {
+        changed |= migration(root);
+    }
+    if !changed {
+        return Ok(None);
+    }
+
+    // Keep the version as the first key
+    let mut upgraded = Map::new();
+    upgraded.insert("version".to_string(), Value::from(CONFIG_VERSION));
+    upgraded.extend(
+        std::mem::take(root)
+            .into_iter()
+            .filter(|(key, _)| key != "version"),
+    );
+    *root = upgraded;
+
+    Ok(Some(version))
+}
+
+/// Version 0 to 1.
+///
+/// - `workspaces` is renamed to `workspace`
+/// - `global.web_client` moves to the top level
+fn migrate_v0_to_v1(root: &mut Map<String, Value>) -> bool {
+    let mut changed = false;
+
+    if !root.contains_key("workspace") {
+        if let Some(workspaces) = root.remove("workspaces") {
+            root.insert("workspace".to_string(), workspaces);
+            changed = true;
+        }
+    }
+
+    if !root.contains_key("web_client") {
+        let web_client = root
+            .get_mut("global")
+            .and_then(Value::as_object_mut)
+            .and_then(|global| global.remove("web_client"));
+        if let Some(web_client) = web_client {
+            root.insert("web_client".to_string(), web_client);
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn when_config_has_legacy_shape_should_upgrade_it() {
+        let mut document = json!({
+            "global": { "editor": "vim", "web_client": { "port": 9000 } },
+            "workspaces": { "work": { "name": "Work" } }
+        });
+
+        assert_eq!(migrate(&mut document).unwrap(), Some(0));
+
+        assert_eq!(
+            document,
+            json!({
+                "version": CONFIG_VERSION,
+                "global": { "editor": "vim" },
+                "workspace": { "work": { "name": "Work" } },
+                "web_client": { "port": 9000 }
+            })
+        );
+        let keys: Vec<&String> = document.as_object().unwrap().keys().collect();
+        assert_eq!(keys[0], "version");
+    }
+
+    #[test]
+    fn when_config_is_compatible_should_leave_it_unchanged() {
+        let mut document = json!({ "global": {}, "workspace": {} });
+        let original = document.clone();
+
+        assert_eq!(migrate(&mut document).unwrap(), None);
+        assert_eq!(document, original);
+    }
+
+    #[test]
+    fn when_config_is_from_newer_version_should_fail() {
+        let mut document = json!({ "version": CONFIG_VERSION + 1, "global": {} });
+
+        let err = migrate(&mut document).unwrap_err().to_string();
+
+        assert!(err.contains("newer"));
+    }
+}
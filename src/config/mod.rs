@@ -4,25 +4,68 @@
 
 #![allow(dead_code)]
 
-use serde::Deserialize;
-use std::collections::HashMap;
+use chrono::format::{Item, StrftimeItems};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crate::error::{ConfigError, Result};
+use crate::git::StatusLimits;
+
+mod bookmarks;
+mod claude_args;
+mod edit;
+mod env;
+mod format;
+mod keys;
+mod lint;
+mod mcp;
+mod migrate;
+mod patch;
+mod project_id;
+mod roots;
+mod share;
+
+pub use edit::NewProject;
+#[allow(unused_imports)]
+pub use env::{ProjectEnv, DEFAULT_ENV_COMMAND};
+#[allow(unused_imports)]
+pub use format::{ConfigFormat, CONFIG_FILE_NAMES};
+#[allow(unused_imports)]
+pub use keys::{ConflictKind, KeyConflict, KeySource, RESERVED_KEYS, VIM_RESERVED_KEYS};
+#[allow(unused_imports)]
+pub use lint::{LintWarning, MAX_ICON_GRAPHEMES};
+#[allow(unused_imports)]
+pub use mcp::{McpServer, McpTransport};
+#[allow(unused_imports)]
+pub use migrate::CONFIG_VERSION;
+pub use project_id::ProjectId;
+#[allow(unused_imports)]
+pub use share::{ConflictChoice, ImportConflict, ImportSummary};
 
 const EXAMPLE_CONFIG: &str = r#"{
+  "version": 1,
   "global": {
     "editor": "$EDITOR",
     "git_info_level": "minimal",
     "actions": {
       "c": { "name": "Claude", "command": "claude", "icon": "🤖" },
-      "b": { "name": "Bash", "command": "bash", "icon": "💻" },
+      "i": { "name": "Bash", "command": "bash", "icon": "💻" },
       "g": { "name": "Lazygit", "command": "lazygit", "icon": "󰊢" }
     },
     "command_bar": [
       { "key": "p", "name": "Pipeline", "command": "echo 'Pipeline running'", "icon": "🚀" }
-    ]
+    ],
+    "file_browser": {
+      "ignore": ["target", "node_modules", "*.lock"]
+    },
+    "git": {
+      "base_branch": "main"
+    }
   },
   "web_client": {
     "auto_start": false,
@@ -52,8 +95,24 @@ pub struct Config {
     pub global: GlobalConfig,
     #[serde(default)]
     pub web_client: WebClientConfig,
+    /// Content of the bar above the panel.
+    #[serde(default)]
+    pub top_bar: TopBarConfig,
+    /// The generated Zellij layout.
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    /// Bell, OSC 9 and command notifications of panel events.
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    /// Appearance of the panel and the top bar.
+    #[serde(default)]
+    pub ui: UiConfig,
+    /// The read-only JSON endpoint serving the panel's state.
+    #[serde(default)]
+    pub state_endpoint: StateEndpointConfig,
     #[serde(default)]
-    pub workspace: HashMap<String, Workspace>,
+    /// The workspaces, in definition order.
+    pub workspace: IndexMap<String, Workspace>,
 }
 
 /// Global settings that apply to all workspaces.
@@ -61,18 +120,93 @@ pub struct Config {
 pub struct GlobalConfig {
     #[serde(default = "default_editor")]
     pub editor: String,
+    /// The shell opened with the built-in 's' binding.
+    #[serde(default = "default_shell")]
+    pub shell: String,
     #[serde(default)]
     pub git_info_level: GitInfoLevel,
     #[serde(default)]
     pub actions: HashMap<String, Action>,
     #[serde(default)]
     pub command_bar: Vec<CommandBarItem>,
+    #[serde(default)]
+    pub file_browser: FileBrowserConfig,
+    /// How action key conflicts are reported.
+    #[serde(default)]
+    pub key_conflicts: KeyConflictMode,
+    /// The git UI opened with the built-in 'G' binding.
+    #[serde(default)]
+    pub git_ui: GitUiConfig,
+    /// Git settings shared by all projects.
+    #[serde(default)]
+    pub git: GitConfig,
+    /// Pull request and pipeline status from the code host, off when unset.
+    #[serde(default)]
+    pub remote: Option<RemoteConfig>,
+    /// How gz-claude drives Zellij.
+    #[serde(default)]
+    pub zellij: ZellijConfig,
+    /// Key bindings used to navigate lists.
+    #[serde(default)]
+    pub navigation: NavigationMode,
+    /// Detection of Claude panes waiting for input.
+    #[serde(default)]
+    pub claude_idle: ClaudeIdleConfig,
+    /// The new task flow opened with the built-in 'n' binding.
+    #[serde(default)]
+    pub task: TaskConfig,
+    /// What Enter does on a project; Alt-Enter does the other.
+    #[serde(default)]
+    pub project_enter: ProjectEnter,
+    /// MCP servers given to Claude in every workspace.
+    #[serde(default)]
+    pub mcp: BTreeMap<String, McpServer>,
+}
+
+/// What Enter does on a project in the Projects view.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectEnter {
+    /// Opens the file browser.
+    #[default]
+    Browse,
+    /// Runs the project's `default_action`, or opens the file browser if it
+    /// has none.
+    Action,
+}
+
+/// Key bindings used to navigate lists.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NavigationMode {
+    /// Arrows and `j`/`k` move one item; `1`-`9` switch workspaces.
+    #[default]
+    Default,
+    /// Adds vim count prefixes (`5j`), `gg` and `G`; the git UI moves to `gs`.
+    Vim,
 }
 
 fn default_editor() -> String {
     "$EDITOR".to_string()
 }
 
+fn default_shell() -> String {
+    "$SHELL".to_string()
+}
+
+impl GlobalConfig {
+    /// Returns the shell to run.
+    ///
+    /// `$SHELL` uses the environment variable, falling back to `sh`.
+    pub fn shell_program(&self) -> String {
+        if self.shell == "$SHELL" {
+            std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string())
+        } else {
+            self.shell.clone()
+        }
+    }
+}
+
 /// Git information detail level.
 #[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -83,6 +217,323 @@ pub enum GitInfoLevel {
     Detailed,
 }
 
+/// Built-in git UI integration.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct GitUiConfig {
+    /// The git UI command, run in the project directory.
+    #[serde(default = "default_git_ui_command")]
+    pub command: String,
+    /// Flag used to pass the file selected in the file browser as a filter.
+    /// Set to null to never pass the selection.
+    #[serde(default = "default_git_ui_filter_flag")]
+    pub filter_flag: Option<String>,
+}
+
+fn default_git_ui_command() -> String {
+    "lazygit".to_string()
+}
+
+fn default_git_ui_filter_flag() -> Option<String> {
+    Some("-f".to_string())
+}
+
+impl Default for GitUiConfig {
+    fn default() -> Self {
+        Self {
+            command: default_git_ui_command(),
+            filter_flag: default_git_ui_filter_flag(),
+        }
+    }
+}
+
+/// Strictness of action key conflict validation.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyConflictMode {
+    /// Conflicts are printed as warnings at startup.
+    #[default]
+    Warn,
+    /// Conflicts make the configuration invalid.
+    Error,
+    /// Conflicts are not reported.
+    Ignore,
+}
+
+/// Commits behind upstream tolerated when `git.behind_warn` is not set.
+pub const DEFAULT_BEHIND_WARN: u32 = 10;
+
+/// Environment variable naming the configuration file to use instead of the
+/// one in `~/.gz-claude`.
+pub const CONFIG_ENV: &str = "GZ_CLAUDE_CONFIG";
+
+/// Git settings.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct GitConfig {
+    /// Branch that ahead/behind counts are also computed against (e.g. "main").
+    #[serde(default)]
+    pub base_branch: Option<String>,
+    /// Second remote that ahead/behind counts are also computed against
+    /// (e.g. "upstream" in a fork).
+    #[serde(default)]
+    pub compare_remote: Option<String>,
+    /// Minutes between background fetches of the remote; 0 or unset disables them.
+    #[serde(default)]
+    pub auto_fetch_minutes: Option<u64>,
+    /// Commits behind upstream tolerated before a project is shown in red.
+    #[serde(default)]
+    pub behind_warn: Option<u32>,
+    /// Milliseconds the status of a repository's files may take before only
+    /// its branch is shown; unset waits for it.
+    #[serde(default)]
+    pub status_timeout_ms: Option<u64>,
+    /// Changed files tolerated before only a repository's branch is shown;
+    /// unset tolerates any number.
+    #[serde(default)]
+    pub max_status_entries: Option<usize>,
+}
+
+/// Zellij integration settings.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct ZellijConfig {
+    /// Whether panes are driven through the CLI or the gz-claude plugin.
+    #[serde(default)]
+    pub integration: ZellijIntegration,
+    /// Location of the gz-claude plugin, as accepted by `zellij pipe --plugin`.
+    #[serde(default = "default_zellij_plugin")]
+    pub plugin: String,
+    /// Name of the Zellij session started by `gz-claude`.
+    #[serde(default = "default_session_name")]
+    pub session_name: String,
+    /// What to do when the session already exists.
+    #[serde(default)]
+    pub session_policy: SessionPolicy,
+    /// Milliseconds a Zellij CLI call may take before it's killed; 0 waits forever.
+    #[serde(default = "default_command_timeout_ms")]
+    pub command_timeout_ms: u64,
+    /// Times a Zellij CLI call that timed out is retried.
+    #[serde(default = "default_command_retries")]
+    pub command_retries: u32,
+    /// Milliseconds between two checks of the focused pane; 0 turns them off.
+    #[serde(default = "default_focus_interval_ms")]
+    pub focus_interval_ms: u64,
+    /// Selects the project whose pane gets the focus, not only marks it.
+    #[serde(default)]
+    pub follow_focus: bool,
+}
+
+impl ZellijConfig {
+    /// Returns the time between two checks of the focused pane, or None if
+    /// the focus isn't tracked.
+    pub fn focus_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(self.focus_interval_ms)).filter(|i| !i.is_zero())
+    }
+
+    /// Returns how long a Zellij CLI call may take, or None to wait forever.
+    pub fn command_timeout(&self) -> Option<Duration> {
+        Some(Duration::from_millis(self.command_timeout_ms)).filter(|t| !t.is_zero())
+    }
+}
+
+fn default_zellij_plugin() -> String {
+    "file:~/.gz-claude/plugins/gz-claude.wasm".to_string()
+}
+
+fn default_session_name() -> String {
+    "gz-claude".to_string()
+}
+
+fn default_command_timeout_ms() -> u64 {
+    5_000
+}
+
+fn default_command_retries() -> u32 {
+    1
+}
+
+fn default_focus_interval_ms() -> u64 {
+    1_000
+}
+
+impl Default for ZellijConfig {
+    fn default() -> Self {
+        Self {
+            integration: ZellijIntegration::default(),
+            plugin: default_zellij_plugin(),
+            session_name: default_session_name(),
+            session_policy: SessionPolicy::default(),
+            command_timeout_ms: default_command_timeout_ms(),
+            command_retries: default_command_retries(),
+            focus_interval_ms: default_focus_interval_ms(),
+            follow_focus: false,
+        }
+    }
+}
+
+/// Detection of Claude panes that stopped generating and wait for input.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct ClaudeIdleConfig {
+    /// Whether Claude panes are watched.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Seconds between two reads of each Claude pane's screen.
+    #[serde(default = "default_idle_interval")]
+    pub interval_secs: u64,
+    /// Sends a desktop notification when a pane goes idle.
+    #[serde(default)]
+    pub desktop_notification: bool,
+    /// Blinks the top bar's Claude panes widget while panes wait for input.
+    #[serde(default)]
+    pub blink_top_bar: bool,
+}
+
+pub(super) fn default_true() -> bool {
+    true
+}
+
+fn default_idle_interval() -> u64 {
+    3
+}
+
+impl Default for ClaudeIdleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: default_idle_interval(),
+            desktop_notification: false,
+            blink_top_bar: false,
+        }
+    }
+}
+
+/// The new task flow: a branch per task, and Claude launched on it.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct TaskConfig {
+    /// Prefix of the branch created for a task, followed by its slug.
+    #[serde(default = "default_task_branch_prefix")]
+    pub branch_prefix: String,
+    /// Command launched in the task's directory, followed by the prompt.
+    #[serde(default = "default_task_command")]
+    pub command: String,
+    /// Initial prompt; `{task}`, `{branch}` and `{project}` are replaced.
+    #[serde(default = "default_task_prompt")]
+    pub prompt: String,
+}
+
+fn default_task_branch_prefix() -> String {
+    "claude/".to_string()
+}
+
+fn default_task_command() -> String {
+    "claude".to_string()
+}
+
+fn default_task_prompt() -> String {
+    "{task}".to_string()
+}
+
+impl Default for TaskConfig {
+    fn default() -> Self {
+        Self {
+            branch_prefix: default_task_branch_prefix(),
+            command: default_task_command(),
+            prompt: default_task_prompt(),
+        }
+    }
+}
+
+impl TaskConfig {
+    /// Returns the initial prompt of a task.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The task name, as typed
+    /// * `branch` - The branch created for the task
+    /// * `project` - The project's name
+    pub fn render_prompt(&self, task: &str, branch: &str, project: &str) -> String {
+        self.prompt
+            .replace("{task}", task)
+            .replace("{branch}", branch)
+            .replace("{project}", project)
+    }
+}
+
+/// What `gz-claude` does when its Zellij session already exists.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionPolicy {
+    /// Ask on the terminal; attach when there is no terminal.
+    #[default]
+    Ask,
+    /// Attach to the existing session.
+    Attach,
+    /// Kill the existing session and start a fresh one with the same name.
+    Restart,
+    /// Start another session with a unique name (`gz-claude-2`, ...).
+    New,
+}
+
+/// How gz-claude drives Zellij.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ZellijIntegration {
+    /// `zellij action` commands (focus by direction, write-chars).
+    #[default]
+    Cli,
+    /// Messages to the gz-claude plugin over `zellij pipe`, falling back to
+    /// the CLI when the plugin does not answer.
+    Plugin,
+}
+
+/// Code host integration showing open pull requests and CI status.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct RemoteConfig {
+    /// Forces the provider, for self-hosted hosts whose name does not tell.
+    #[serde(default)]
+    pub provider: Option<RemoteProvider>,
+    /// Environment variable holding the API token. Defaults to `GITHUB_TOKEN`
+    /// or `GITLAB_TOKEN` depending on the provider.
+    #[serde(default)]
+    pub token_env: Option<String>,
+    /// API base URL, derived from the `origin` host when unset.
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Seconds a fetched status is reused before asking the API again.
+    #[serde(default = "default_remote_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+fn default_remote_ttl_secs() -> u64 {
+    300
+}
+
+/// A code host API.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteProvider {
+    #[value(name = "github")]
+    GitHub,
+    #[value(name = "gitlab")]
+    GitLab,
+}
+
+impl RemoteProvider {
+    /// Environment variable read for the token when `token_env` is unset.
+    pub fn default_token_env(&self) -> &'static str {
+        match self {
+            RemoteProvider::GitHub => "GITHUB_TOKEN",
+            RemoteProvider::GitLab => "GITLAB_TOKEN",
+        }
+    }
+}
+
+/// File browser settings.
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct FileBrowserConfig {
+    /// Name patterns hidden from the file tree (`*` and `?` wildcards).
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
 /// Web client configuration.
 #[derive(Debug, Deserialize)]
 pub struct WebClientConfig {
@@ -92,6 +543,328 @@ pub struct WebClientConfig {
     pub bind_address: String,
     #[serde(default = "default_port")]
     pub port: u16,
+    /// How the web server is secured.
+    #[serde(default)]
+    pub tls: WebTls,
+    /// Port of the pane picker page served by the top bar, which lets the
+    /// web client choose the pane it shows. Off when unset.
+    #[serde(default)]
+    pub picker_port: Option<u16>,
+}
+
+/// Settings of the JSON state endpoint.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct StateEndpointConfig {
+    /// Port the endpoint listens on, on 127.0.0.1. Off when unset.
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+/// Settings of the generated Zellij layout.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct LayoutConfig {
+    /// Command started in the main pane, with its arguments split on
+    /// whitespace (defaults to "bash").
+    #[serde(default)]
+    pub main_command: Option<String>,
+    /// Directory the main pane starts in (`~` is expanded).
+    #[serde(default)]
+    pub main_cwd: Option<String>,
+}
+
+/// Shells that actions can be typed into.
+const SHELLS: &[&str] = &[
+    "bash", "zsh", "fish", "sh", "dash", "ksh", "tcsh", "csh", "nu", "elvish", "xonsh", "pwsh",
+];
+
+impl LayoutConfig {
+    /// Returns the command of the main pane.
+    pub fn main_command(&self) -> &str {
+        self.main_command
+            .as_deref()
+            .map(str::trim)
+            .filter(|command| !command.is_empty())
+            .unwrap_or("bash")
+    }
+
+    /// Returns whether the main pane runs a shell.
+    ///
+    /// Actions are typed into the main pane, so they only go there when it
+    /// runs a shell; otherwise (e.g. "claude") they open floating panes.
+    pub fn main_runs_shell(&self) -> bool {
+        self.main_command()
+            .split_whitespace()
+            .next()
+            .and_then(|program| Path::new(program).file_name())
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| SHELLS.contains(&name))
+    }
+}
+
+/// Top bar settings.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct TopBarConfig {
+    /// Widgets shown from left to right.
+    #[serde(default = "default_top_bar_widgets")]
+    pub widgets: Vec<TopBarWidget>,
+    /// Text placed between two non-empty widgets.
+    #[serde(default = "default_top_bar_separator")]
+    pub separator: String,
+}
+
+fn default_top_bar_widgets() -> Vec<TopBarWidget> {
+    vec![TopBarWidget::new(TopBarWidgetKind::WebUrl)]
+}
+
+fn default_top_bar_separator() -> String {
+    " │ ".to_string()
+}
+
+impl Default for TopBarConfig {
+    fn default() -> Self {
+        Self {
+            widgets: default_top_bar_widgets(),
+            separator: default_top_bar_separator(),
+        }
+    }
+}
+
+/// Notification settings.
+///
+/// Nothing is emitted unless at least one of `bell`, `osc9` or `command` is
+/// set.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct NotificationsConfig {
+    /// Rings the terminal bell (BEL).
+    #[serde(default)]
+    pub bell: bool,
+    /// Sends an OSC 9 notification, shown by terminals such as iTerm2,
+    /// WezTerm and kitty.
+    #[serde(default)]
+    pub osc9: bool,
+    /// Shell command run for each notification (e.g.
+    /// "terminal-notifier -message {message}"); `{event}` and `{message}`
+    /// are replaced with quoted values.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Events that notify.
+    #[serde(default = "default_notification_events")]
+    pub events: Vec<NotificationEvent>,
+}
+
+fn default_notification_events() -> Vec<NotificationEvent> {
+    vec![
+        NotificationEvent::CommandFinished,
+        NotificationEvent::ClaudeIdle,
+        NotificationEvent::GitBehind,
+        NotificationEvent::ConfigReloadFailed,
+    ]
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            bell: false,
+            osc9: false,
+            command: None,
+            events: default_notification_events(),
+        }
+    }
+}
+
+impl NotificationsConfig {
+    /// Returns the configured notification command, if not blank.
+    pub fn command(&self) -> Option<&str> {
+        self.command
+            .as_deref()
+            .map(str::trim)
+            .filter(|command| !command.is_empty())
+    }
+
+    /// Returns whether an event notifies through at least one channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event that occurred
+    pub fn notifies(&self, event: NotificationEvent) -> bool {
+        (self.bell || self.osc9 || self.command().is_some()) && self.events.contains(&event)
+    }
+}
+
+/// Panel events that can trigger a notification.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// A captured command bar command finished.
+    CommandFinished,
+    /// A Claude pane started waiting for input.
+    ClaudeIdle,
+    /// A fetch left a project more than `behind_warn` commits behind.
+    GitBehind,
+    /// The edited configuration could not be loaded.
+    ConfigReloadFailed,
+}
+
+impl NotificationEvent {
+    /// Returns the name of the event as written in the configuration.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationEvent::CommandFinished => "command_finished",
+            NotificationEvent::ClaudeIdle => "claude_idle",
+            NotificationEvent::GitBehind => "git_behind",
+            NotificationEvent::ConfigReloadFailed => "config_reload_failed",
+        }
+    }
+}
+
+/// Appearance settings.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct UiConfig {
+    /// Replaces emoji and symbol icons with ASCII, for terminal fonts that
+    /// lack them.
+    #[serde(default)]
+    pub ascii_only: bool,
+    /// Color preset of the panel.
+    #[serde(default)]
+    pub theme: ThemePreset,
+    /// When the project list drops action icons and shows git status as a
+    /// single glyph.
+    #[serde(default)]
+    pub compact: CompactMode,
+    /// Shows runtime stats of the panel on the status bar: panes, projects,
+    /// git cache and memory.
+    #[serde(default)]
+    pub stats: bool,
+}
+
+/// When the project list is drawn compactly.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactMode {
+    /// Only in panels narrower than a threshold.
+    #[default]
+    Auto,
+    /// At every width.
+    Always,
+    /// Never, narrow panels truncate the rows instead.
+    Never,
+}
+
+/// Color presets of the panel.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemePreset {
+    /// The regular colors.
+    #[default]
+    Default,
+    /// Bright colors on the terminal background, without dim text.
+    HighContrast,
+}
+
+/// A piece of content in the top bar.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct TopBarWidget {
+    /// What the widget shows.
+    #[serde(rename = "type")]
+    pub kind: TopBarWidgetKind,
+    /// Text color, the terminal's default when unset.
+    #[serde(default)]
+    pub color: Option<TopBarColor>,
+    /// Seconds between two refreshes, a per-kind default when unset.
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+    /// strftime format of a `clock` widget (default `%H:%M`).
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Shell command of a `command` widget; the first line of its output is shown.
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+impl TopBarWidget {
+    /// Creates a widget with default settings.
+    pub fn new(kind: TopBarWidgetKind) -> Self {
+        Self {
+            kind,
+            color: None,
+            interval_secs: None,
+            format: None,
+            command: None,
+        }
+    }
+}
+
+/// The kinds of top bar widgets.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TopBarWidgetKind {
+    /// The web client URL, copied with 'c'.
+    WebUrl,
+    /// The current local time.
+    Clock,
+    /// The workspace shown in the panel.
+    Workspace,
+    /// Branch and dirty state of the project selected in the panel.
+    Git,
+    /// Number of panes running Claude Code.
+    ClaudePanes,
+    /// Output of a shell command.
+    Command,
+}
+
+/// Colors available to top bar widgets.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TopBarColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+/// TLS setup of the web server.
+///
+/// Deserialized from `"self-signed"`, `"off"` or `{ "cert": ..., "key": ... }`.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+#[serde(from = "WebTlsRepr")]
+pub enum WebTls {
+    /// A certificate generated in `~/.gz-claude/ssl/`.
+    #[default]
+    SelfSigned,
+    /// Plain http.
+    Off,
+    /// A user-provided certificate and key (e.g. from mkcert or a LAN CA).
+    Custom { cert: PathBuf, key: PathBuf },
+}
+
+/// The JSON shapes accepted for `web_client.tls`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WebTlsRepr {
+    Mode(WebTlsMode),
+    Custom { cert: PathBuf, key: PathBuf },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum WebTlsMode {
+    SelfSigned,
+    Off,
+}
+
+impl From<WebTlsRepr> for WebTls {
+    fn from(repr: WebTlsRepr) -> Self {
+        match repr {
+            WebTlsRepr::Mode(WebTlsMode::SelfSigned) => WebTls::SelfSigned,
+            WebTlsRepr::Mode(WebTlsMode::Off) => WebTls::Off,
+            WebTlsRepr::Custom { cert, key } => WebTls::Custom { cert, key },
+        }
+    }
 }
 
 fn default_bind_address() -> String {
@@ -102,26 +875,125 @@ fn default_port() -> u16 {
     8082
 }
 
-impl Default for WebClientConfig {
-    fn default() -> Self {
-        Self {
-            auto_start: false,
-            bind_address: default_bind_address(),
-            port: default_port(),
+impl Default for WebClientConfig {
+    fn default() -> Self {
+        Self {
+            auto_start: false,
+            bind_address: default_bind_address(),
+            port: default_port(),
+            tls: WebTls::default(),
+            picker_port: None,
+        }
+    }
+}
+
+/// A workspace containing multiple projects.
+#[derive(Debug, Deserialize)]
+pub struct Workspace {
+    pub name: String,
+    /// Position in the workspace list: lower first, and workspaces without
+    /// one after those with one, in definition order.
+    #[serde(default)]
+    pub order: Option<i32>,
+    /// What the workspace is for, shown under it when selected.
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub actions: HashMap<String, Action>,
+    #[serde(default)]
+    pub command_bar: Vec<CommandBarItem>,
+    /// Key of the action run with Enter (or Alt-Enter) on its projects.
+    #[serde(default)]
+    pub default_action: Option<String>,
+    /// MCP servers given to Claude in the workspace's projects.
+    #[serde(default)]
+    pub mcp: BTreeMap<String, McpServer>,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    /// Projects opened together, one tab each, by `gz-claude up`.
+    #[serde(default)]
+    pub working_set: Vec<WorkingSetEntry>,
+    /// Directory whose subdirectories created while the panel runs are
+    /// offered as projects of the workspace.
+    #[serde(default)]
+    pub discover: Option<String>,
+}
+
+impl Workspace {
+    /// Resolves the working set to the workspace's projects.
+    ///
+    /// # Returns
+    ///
+    /// The projects of the working set, in order, with what their tab runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidWorkingSet` if an entry names no project of
+    /// the workspace or a project is listed twice.
+    pub fn working_set_projects(
+        &self,
+    ) -> std::result::Result<Vec<(&Project, WorkingSetRun)>, ConfigError> {
+        let invalid = |reason: String| ConfigError::InvalidWorkingSet {
+            workspace: self.name.clone(),
+            reason,
+        };
+        let mut resolved: Vec<(&Project, WorkingSetRun)> = Vec::new();
+        for entry in &self.working_set {
+            let project = self
+                .projects
+                .iter()
+                .find(|p| p.name == entry.project())
+                .ok_or_else(|| invalid(format!("no project named '{}'", entry.project())))?;
+            if resolved.iter().any(|(p, _)| p.name == project.name) {
+                return Err(invalid(format!("'{}' is listed twice", project.name)));
+            }
+            resolved.push((project, entry.run()));
+        }
+        Ok(resolved)
+    }
+}
+
+/// A project of a workspace's working set.
+///
+/// Written either as the project name, which runs Claude, or as
+/// `{ "project": "api", "run": "shell" }`.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum WorkingSetEntry {
+    Name(String),
+    Detailed {
+        project: String,
+        #[serde(default)]
+        run: WorkingSetRun,
+    },
+}
+
+impl WorkingSetEntry {
+    /// Returns the name of the project.
+    pub fn project(&self) -> &str {
+        match self {
+            WorkingSetEntry::Name(project) | WorkingSetEntry::Detailed { project, .. } => project,
+        }
+    }
+
+    /// Returns what the project's tab runs.
+    pub fn run(&self) -> WorkingSetRun {
+        match self {
+            WorkingSetEntry::Name(_) => WorkingSetRun::default(),
+            WorkingSetEntry::Detailed { run, .. } => *run,
         }
     }
 }
 
-/// A workspace containing multiple projects.
-#[derive(Debug, Deserialize)]
-pub struct Workspace {
-    pub name: String,
-    #[serde(default)]
-    pub actions: HashMap<String, Action>,
-    #[serde(default)]
-    pub command_bar: Vec<CommandBarItem>,
-    #[serde(default)]
-    pub projects: Vec<Project>,
+/// What a working set tab runs.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkingSetRun {
+    /// Claude Code.
+    #[default]
+    Claude,
+    /// The shell of `global.shell`.
+    Shell,
 }
 
 /// A project within a workspace.
@@ -129,19 +1001,170 @@ pub struct Workspace {
 pub struct Project {
     pub name: String,
     pub path: PathBuf,
+    /// What the project is, shown under it when selected.
+    #[serde(default)]
+    pub description: Option<String>,
     #[serde(default)]
     pub actions: HashMap<String, Action>,
     #[serde(default)]
     pub command_bar: Vec<CommandBarItem>,
+    /// Overrides the global file browser settings for this project.
+    #[serde(default)]
+    pub file_browser: Option<FileBrowserConfig>,
+    /// Health check run periodically in the background.
+    #[serde(default)]
+    pub check: Option<HealthCheck>,
+    /// Overrides the global git settings for this project.
+    #[serde(default)]
+    pub git: Option<GitConfig>,
+    /// Repository cloned into `path` when the directory doesn't exist.
+    #[serde(default)]
+    pub repo: Option<String>,
+    /// Named subdirectories, relative to `path`, listed in the file browser
+    /// and usable as `{bookmark:NAME}` in actions.
+    #[serde(default)]
+    pub bookmarks: BTreeMap<String, String>,
+    /// Key of the action run with Enter (or Alt-Enter) on the project,
+    /// overriding the workspace's.
+    #[serde(default)]
+    pub default_action: Option<String>,
+    /// Runs the default action in a tab of its own when `gz-claude up`
+    /// starts the workspace's session.
+    #[serde(default)]
+    pub autostart: bool,
+    /// Marks the project as sandboxed, e.g. a throwaway worktree or a
+    /// container, so actions may skip Claude's permission prompts in it.
+    #[serde(default)]
+    pub sandboxed: bool,
+    /// Directories, relative to `path`, shown as the top-level entries of
+    /// the file browser instead of the project directory, e.g. the
+    /// subprojects of a monorepo.
+    #[serde(default)]
+    pub roots: Vec<String>,
+    /// MCP servers given to Claude in the project.
+    #[serde(default)]
+    pub mcp: BTreeMap<String, McpServer>,
+    /// Runs the commands of the project's panes in its environment: `true`
+    /// for `direnv exec {path}`, or a wrapper of its own.
+    #[serde(default)]
+    pub env: Option<ProjectEnv>,
+}
+
+impl Project {
+    /// Returns true if the project directory doesn't exist and can be cloned
+    /// from its `repo`.
+    pub fn needs_clone(&self) -> bool {
+        self.repo.is_some() && !self.path.exists()
+    }
+}
+
+/// A lightweight project health check.
+///
+/// Exactly one of `command` or `port` must be set.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct HealthCheck {
+    /// Shell command run in the project directory; passes on exit status 0.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Local TCP port that must accept connections.
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Seconds between two runs of the check.
+    #[serde(default = "default_check_interval")]
+    pub interval_secs: u64,
+    /// Seconds a command check may run before it is killed and fails.
+    #[serde(default = "default_check_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_check_interval() -> u64 {
+    300
+}
+
+fn default_check_timeout() -> u64 {
+    60
 }
 
 /// An action that can be triggered from the TUI.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Clone)]
 pub struct Action {
     pub name: String,
+    /// The command run with the project path appended. Left empty by
+    /// composite actions, which open `panes` instead.
+    #[serde(default)]
     pub command: String,
+    /// Directory used instead of the project path: `{bookmark:NAME}` or a
+    /// path relative to the project.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Panes opened together, as a group, instead of running `command`.
+    #[serde(default)]
+    pub panes: Vec<PaneTemplate>,
     #[serde(default)]
     pub icon: Option<String>,
+    /// Hook command executed before the main command (e.g. "git pull").
+    #[serde(default)]
+    pub pre: Option<String>,
+    /// Hook command executed after the main command (e.g. "notify-send done").
+    #[serde(default)]
+    pub post: Option<String>,
+    /// Where the pre/post hooks are executed.
+    #[serde(default)]
+    pub hook_mode: HookMode,
+    /// Copies the output of the pane to a log file under
+    /// `~/.gz-claude/logs/<project>/`.
+    #[serde(default)]
+    pub log: bool,
+    /// Runs the command headlessly in the project directory instead of in a
+    /// pane; its output is written to the project's logs and its exit status
+    /// shown as a toast.
+    #[serde(default)]
+    pub background: bool,
+    /// Flags passed to `claude` when the command runs it, e.g.
+    /// `["--permission-mode", "acceptEdits"]`.
+    #[serde(default)]
+    pub claude_args: Vec<String>,
+    /// Marks an intentional override of an inherited action with the same key.
+    #[serde(default, rename = "override")]
+    pub overrides: bool,
+}
+
+/// A pane opened by a composite action.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct PaneTemplate {
+    /// Name of the pane, appended to the action name in the pane title.
+    pub name: String,
+    /// Shell command run in the project directory.
+    pub command: String,
+    /// Where the pane opens.
+    #[serde(default)]
+    pub placement: PanePlacement,
+}
+
+/// Where a pane of a composite action opens.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PanePlacement {
+    /// A floating pane over the layout.
+    #[default]
+    Floating,
+    /// A split below the focused pane.
+    Down,
+    /// A split to the right of the focused pane.
+    Right,
+    /// A tab of its own, named after the pane.
+    Tab,
+}
+
+/// Execution mode for action hooks.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookMode {
+    /// Hooks are chained around the command inside the same pane.
+    #[default]
+    Pane,
+    /// Hooks run silently in the project directory, outside of Zellij.
+    Background,
 }
 
 /// An item in the command bar.
@@ -159,13 +1182,17 @@ pub struct CommandBarItem {
     /// Optional icon to display next to the name.
     #[serde(default)]
     pub icon: Option<String>,
+    /// Runs the command headlessly and shows its output in the panel
+    /// instead of opening a floating pane.
+    #[serde(default)]
+    pub capture: bool,
 }
 
 impl Config {
-    /// Load configuration from the default path (~/.gz-claude/config.json).
+    /// Load configuration from the default path (see `default_path`).
     ///
     /// Reads the configuration file from the user's config directory and parses
-    /// it as JSON into the Config structure.
+    /// it, as JSON, YAML or TOML, into the Config structure.
     ///
     /// # Returns
     ///
@@ -175,7 +1202,7 @@ impl Config {
     ///
     /// - `ConfigError::NotFound` if the configuration file doesn't exist
     /// - `ConfigError::ReadError` if the file cannot be read
-    /// - `ConfigError::ParseError` if the JSON content is invalid
+    /// - `ConfigError::ParseError` or `ConfigError::FormatParseError` if the content is invalid
     pub fn load() -> Result<Self> {
         let config_path = Self::default_path();
         Self::load_from(&config_path)
@@ -183,6 +1210,12 @@ impl Config {
 
     /// Load configuration from a specific path.
     ///
+    /// The format is detected by the extension: `.json`, `.yaml`/`.yml` or
+    /// `.toml`. Configurations written for an older schema are upgraded
+    /// first. A JSON file is upgraded on disk, as `migrate_file` does; YAML
+    /// and TOML files are only upgraded in memory, since rewriting them could
+    /// lose their comments, until `gz-claude config migrate` is run.
+    ///
     /// # Arguments
     ///
     /// * `path` - The path to the configuration file
@@ -195,23 +1228,101 @@ impl Config {
     ///
     /// - `ConfigError::NotFound` if the configuration file doesn't exist
     /// - `ConfigError::ReadError` if the file cannot be read
-    /// - `ConfigError::ParseError` if the JSON content is invalid
-    pub fn load_from(path: &PathBuf) -> Result<Self> {
+    /// - `ConfigError::ParseError` or `ConfigError::FormatParseError` if the content is invalid
+    /// - `ConfigError::UnsupportedVersion` if the file is from a newer gz-claude
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(ConfigError::NotFound(path.to_path_buf()).into());
+        }
+
+        if ConfigFormat::of_file(path) == ConfigFormat::Json {
+            Self::migrate_file(path)?;
+        }
+        Self::read_from(path)
+    }
+
+    /// Upgrades a configuration file written for an older schema, in place.
+    ///
+    /// The original file is kept as `<name>.v<old version>.bak` and the
+    /// upgraded one is written in its place, in the same format. TOML files
+    /// are patched, keeping the comments of the settings that don't move.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the configuration file
+    ///
+    /// # Returns
+    ///
+    /// The version the file was upgraded from, None if it was current.
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::NotFound` if the configuration file doesn't exist
+    /// - `ConfigError::ReadError` if the file cannot be read
+    /// - `ConfigError::ParseError` or `ConfigError::FormatParseError` if the content is invalid
+    /// - `ConfigError::UnsupportedVersion` if the file is from a newer gz-claude
+    pub fn migrate_file(path: &Path) -> Result<Option<u64>> {
+        let mut document = edit::read_document(path)?;
+        let Some(old_version) = migrate::migrate(&mut document)? else {
+            return Ok(None);
+        };
+
+        let backup = edit::with_suffix(path, &format!("v{}.bak", old_version));
+        fs::copy(path, backup)?;
+        if ConfigFormat::of_file(path) == ConfigFormat::Toml {
+            edit::update_document(path, &document)?;
+        } else {
+            edit::write_document(path, &document)?;
+        }
+        Ok(Some(old_version))
+    }
+
+    /// Reads a configuration file without writing anything.
+    ///
+    /// Unlike `load_from`, a configuration written for an older schema is
+    /// only upgraded in memory, whatever its format: the file and its
+    /// backups are left as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the configuration file
+    ///
+    /// # Errors
+    ///
+    /// The same as `load_from`.
+    pub fn read_from(path: &Path) -> Result<Self> {
         if !path.exists() {
-            return Err(ConfigError::NotFound(path.clone()).into());
+            return Err(ConfigError::NotFound(path.to_path_buf()).into());
         }
 
+        let format = ConfigFormat::of_file(path);
         let content = fs::read_to_string(path)?;
-        let config: Config = serde_json::from_str(&content).map_err(ConfigError::ParseError)?;
-        Ok(config)
+        let mut document: serde_json::Value = format.parse(&content)?;
+        if migrate::migrate(&mut document)?.is_none() {
+            // Parse the text rather than the document to keep line numbers in errors
+            return format.parse(&content);
+        }
+        Ok(serde_json::from_value(document).map_err(ConfigError::ParseError)?)
     }
 
     /// Returns the default configuration file path.
     ///
-    /// The default path is `~/.gz-claude/config.json`.
-    /// Falls back to `./.gz-claude/config.json` if the home directory cannot be determined.
+    /// The file named by `GZ_CLAUDE_CONFIG` when it is set (`--config` sets
+    /// it, so panes started by the layout use the same file). Otherwise the
+    /// first of `config.json`, `config.yaml`, `config.yml` and
+    /// `config.toml` found in the default directory, or `config.json` when
+    /// there is none.
+    /// Falls back to `./.gz-claude/` if the home directory cannot be determined.
     pub fn default_path() -> PathBuf {
-        Self::default_dir().join("config.json")
+        if let Some(path) = std::env::var_os(CONFIG_ENV).filter(|path| !path.is_empty()) {
+            return PathBuf::from(path);
+        }
+        let dir = Self::default_dir();
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.exists())
+            .unwrap_or_else(|| dir.join(CONFIG_FILE_NAMES[0]))
     }
 
     /// Returns the default configuration directory.
@@ -224,14 +1335,21 @@ impl Config {
             .join(".gz-claude")
     }
 
-    /// Create an example configuration file at the default path.
+    /// Create an example configuration file at the default path, in the
+    /// format of its extension.
     /// Returns the path where the file was created.
     pub fn create_example() -> Result<PathBuf> {
-        let config_dir = Self::default_dir();
-        fs::create_dir_all(&config_dir)?;
-
         let config_path = Self::default_path();
-        fs::write(&config_path, EXAMPLE_CONFIG)?;
+        if let Some(config_dir) = config_path.parent() {
+            fs::create_dir_all(config_dir)?;
+        }
+        match ConfigFormat::of_file(&config_path) {
+            ConfigFormat::Json => fs::write(&config_path, EXAMPLE_CONFIG)?,
+            format => {
+                let document: serde_json::Value = ConfigFormat::Json.parse(EXAMPLE_CONFIG)?;
+                fs::write(&config_path, format.write(&document)?)?;
+            }
+        }
 
         Ok(config_path)
     }
@@ -256,6 +1374,11 @@ impl Config {
     /// - At least one workspace exists
     /// - All action keys are single characters
     /// - All action commands are non-empty
+    /// - All action hooks, when present, are non-empty
+    /// - All project health checks define exactly one of command or port
+    /// - The web client bind address is an IP address
+    /// - Custom web client TLS files exist
+    /// - No action key conflicts, when `global.key_conflicts` is "error"
     /// - All project paths exist and are directories
     ///
     /// # Returns
@@ -267,9 +1390,40 @@ impl Config {
     /// - `ConfigError::NoWorkspaces` if no workspaces are defined
     /// - `ConfigError::InvalidActionKey` if an action key is not a single character
     /// - `ConfigError::EmptyCommand` if an action command is empty or whitespace
-    /// - `ConfigError::PathNotFound` if a project path does not exist
+    /// - `ConfigError::EmptyHook` if an action hook is empty or whitespace
+    /// - `ConfigError::InvalidHealthCheck` if a health check is ambiguous, empty
+    ///   or has a zero interval or timeout
+    /// - `ConfigError::InvalidBindAddress` if the bind address is not an IP address
+    /// - `ConfigError::TlsFileNotFound` if a custom TLS file does not exist
+    /// - `ConfigError::InvalidTopBarWidget` if a top bar widget is missing its command or is malformed
+    /// - `ConfigError::InvalidBookmark` if a bookmark has no path or a name with braces
+    /// - `ConfigError::InvalidRoot` if a file browser root is empty or outside the project
+    /// - `ConfigError::InvalidMcpServer` if an MCP server is neither local nor remote
+    /// - `ConfigError::InvalidWorkingSet` if a working set names an unknown or repeated project
+    /// - `ConfigError::KeyConflicts` if action keys conflict in strict mode
+    /// - `ConfigError::PathNotFound` if a project path does not exist and has no `repo` to clone
     /// - `ConfigError::PathNotDirectory` if a project path is not a directory
     pub fn validate(&self) -> Result<()> {
+        self.validate_structure()?;
+
+        for project in self.projects() {
+            if !project.needs_clone() {
+                check_project_path(&project.path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates the configuration except for project paths.
+    ///
+    /// Checking project paths can be slow on network filesystems; they can be
+    /// checked separately with `check_project_path` on `project_paths`.
+    ///
+    /// # Errors
+    ///
+    /// Same as `validate`, except `PathNotFound` and `PathNotDirectory`.
+    pub fn validate_structure(&self) -> Result<()> {
         if self.workspace.is_empty() {
             return Err(ConfigError::NoWorkspaces.into());
         }
@@ -278,15 +1432,95 @@ impl Config {
         self.validate_actions(&self.global.actions)?;
 
         // Validate each workspace
-        for workspace in self.workspace.values() {
+        for (workspace_id, workspace) in &self.workspace {
             self.validate_actions(&workspace.actions)?;
 
             for project in &workspace.projects {
                 self.validate_actions(&project.actions)?;
-                self.validate_project_path(project)?;
+                self.validate_health_check(project)?;
+                project.validate_bookmarks()?;
+                project.validate_roots()?;
+            }
+            workspace.working_set_projects()?;
+            self.autostart_actions(workspace_id)?;
+        }
+
+        self.validate_mcp_servers()?;
+        self.validate_web_client()?;
+        self.validate_top_bar()?;
+
+        if self.global.key_conflicts == KeyConflictMode::Error {
+            let conflicts = self.key_conflicts();
+            if !conflicts.is_empty() {
+                return Err(ConfigError::KeyConflicts(
+                    conflicts
+                        .iter()
+                        .map(|c| format!("  - {}", c))
+                        .collect::<Vec<String>>()
+                        .join("\n"),
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the action key conflicts to print as warnings.
+    ///
+    /// # Returns
+    ///
+    /// The conflicts when `global.key_conflicts` is "warn", empty otherwise.
+    pub fn key_conflict_warnings(&self) -> Vec<KeyConflict> {
+        if self.global.key_conflicts == KeyConflictMode::Warn {
+            self.key_conflicts()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn validate_web_client(&self) -> Result<()> {
+        let web_client = &self.web_client;
+        if web_client.bind_address.parse::<IpAddr>().is_err() {
+            return Err(ConfigError::InvalidBindAddress {
+                address: web_client.bind_address.clone(),
+            }
+            .into());
+        }
+        if let WebTls::Custom { cert, key } = &web_client.tls {
+            for path in [cert, key] {
+                if !path.is_file() {
+                    return Err(ConfigError::TlsFileNotFound { path: path.clone() }.into());
+                }
             }
         }
+        Ok(())
+    }
 
+    fn validate_top_bar(&self) -> Result<()> {
+        for (index, widget) in self.top_bar.widgets.iter().enumerate() {
+            let invalid = |reason: &str| ConfigError::InvalidTopBarWidget {
+                index,
+                reason: reason.to_string(),
+            };
+            if widget.kind == TopBarWidgetKind::Command
+                && widget
+                    .command
+                    .as_ref()
+                    .map_or(true, |c| c.trim().is_empty())
+            {
+                return Err(invalid("a command widget needs a command").into());
+            }
+            if widget.interval_secs == Some(0) {
+                return Err(invalid("interval_secs must be at least 1").into());
+            }
+            let bad_format = widget.format.as_deref().is_some_and(|format| {
+                StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+            });
+            if bad_format {
+                return Err(invalid("the clock format is not a valid strftime format").into());
+            }
+        }
         Ok(())
     }
 
@@ -295,30 +1529,99 @@ impl Config {
             if key.chars().count() != 1 {
                 return Err(ConfigError::InvalidActionKey { key: key.clone() }.into());
             }
-            if action.command.trim().is_empty() {
+            if action.panes.is_empty() && action.command.trim().is_empty() {
                 return Err(ConfigError::EmptyCommand {
                     action_name: action.name.clone(),
                 }
                 .into());
             }
+            if !action.panes.is_empty() && !action.command.trim().is_empty() {
+                return Err(ConfigError::InvalidPaneTemplate {
+                    action_name: action.name.clone(),
+                    reason: "set either command or panes, not both".to_string(),
+                }
+                .into());
+            }
+            if !action.panes.is_empty() && action.background {
+                return Err(ConfigError::InvalidPaneTemplate {
+                    action_name: action.name.clone(),
+                    reason: "panes can't run in the background".to_string(),
+                }
+                .into());
+            }
+            for pane in &action.panes {
+                if pane.name.trim().is_empty() || pane.command.trim().is_empty() {
+                    return Err(ConfigError::InvalidPaneTemplate {
+                        action_name: action.name.clone(),
+                        reason: "every pane needs a name and a command".to_string(),
+                    }
+                    .into());
+                }
+            }
+            action.validate_claude_args()?;
+            for (hook, command) in [("pre", &action.pre), ("post", &action.post)] {
+                if command.as_ref().is_some_and(|c| c.trim().is_empty()) {
+                    return Err(ConfigError::EmptyHook {
+                        action_name: action.name.clone(),
+                        hook: hook.to_string(),
+                    }
+                    .into());
+                }
+            }
         }
         Ok(())
     }
 
-    fn validate_project_path(&self, project: &Project) -> Result<()> {
-        if !project.path.exists() {
-            return Err(ConfigError::PathNotFound {
-                path: project.path.clone(),
-            }
-            .into());
+    fn validate_health_check(&self, project: &Project) -> Result<()> {
+        let Some(check) = &project.check else {
+            return Ok(());
+        };
+        let has_command = check.command.as_ref().is_some_and(|c| !c.trim().is_empty());
+        let reason = if has_command == check.port.is_some() {
+            "it must set exactly one of command or port"
+        } else if check.interval_secs == 0 {
+            "interval_secs must be at least 1"
+        } else if check.timeout_secs == 0 {
+            "timeout_secs must be at least 1"
+        } else {
+            return Ok(());
+        };
+        Err(ConfigError::InvalidHealthCheck {
+            project_name: project.name.clone(),
+            reason: reason.to_string(),
         }
-        if !project.path.is_dir() {
-            return Err(ConfigError::PathNotDirectory {
-                path: project.path.clone(),
-            }
-            .into());
-        }
-        Ok(())
+        .into())
+    }
+
+    /// Returns the workspace ids in display order.
+    ///
+    /// Workspaces with an `order` come first, lowest first; the rest follow.
+    /// Ties keep the order the workspaces are defined in.
+    pub fn workspace_ids(&self) -> Vec<&String> {
+        let mut ids: Vec<(&String, Option<i32>)> = self
+            .workspace
+            .iter()
+            .map(|(id, workspace)| (id, workspace.order))
+            .collect();
+        // The sort is stable, so ties stay in definition order
+        ids.sort_by_key(|(_, order)| (order.is_none(), *order));
+        ids.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Returns the paths of all projects, in workspace display order.
+    pub fn project_paths(&self) -> Vec<PathBuf> {
+        self.projects()
+            .into_iter()
+            .map(|p| p.path.clone())
+            .collect()
+    }
+
+    /// Returns all projects, in workspace display order.
+    pub fn projects(&self) -> Vec<&Project> {
+        self.workspace_ids()
+            .into_iter()
+            .flat_map(|id| &self.workspace[id].projects)
+            .collect()
     }
 
     /// Resolve actions for a specific project, applying inheritance:
@@ -363,6 +1666,72 @@ impl Config {
         actions
     }
 
+    /// Returns the key of the action run with Enter (or Alt-Enter) on a
+    /// project: its own `default_action`, or its workspace's.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The identifier of the workspace
+    /// * `project_index` - The index of the project within the workspace
+    pub fn resolve_default_action(&self, workspace_id: &str, project_index: usize) -> Option<&str> {
+        let workspace = self.workspace.get(workspace_id)?;
+        workspace
+            .projects
+            .get(project_index)?
+            .default_action
+            .as_deref()
+            .or(workspace.default_action.as_deref())
+    }
+
+    /// Resolves the default actions of a workspace's projects marked
+    /// `autostart`.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The identifier of the workspace
+    ///
+    /// # Returns
+    ///
+    /// The projects, in order, with their index and their default action.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidAutostart` if a project has no default
+    /// action, or one that opens a pane group or asks for arguments.
+    pub fn autostart_actions(
+        &self,
+        workspace_id: &str,
+    ) -> std::result::Result<Vec<(usize, &Project, Action)>, ConfigError> {
+        let Some(workspace) = self.workspace.get(workspace_id) else {
+            return Ok(Vec::new());
+        };
+        let mut resolved = Vec::new();
+        for (index, project) in workspace.projects.iter().enumerate() {
+            if !project.autostart {
+                continue;
+            }
+            let invalid = |reason: String| ConfigError::InvalidAutostart {
+                project: project.name.clone(),
+                reason,
+            };
+            let key = self
+                .resolve_default_action(workspace_id, index)
+                .ok_or_else(|| invalid("it has no default_action".to_string()))?;
+            let action = self
+                .resolve_actions(workspace_id, index)
+                .remove(key)
+                .ok_or_else(|| invalid(format!("its default action '{}' is not defined", key)))?;
+            if !action.panes.is_empty() {
+                return Err(invalid(format!("'{}' opens a pane group", action.name)));
+            }
+            if !crate::arguments::messages(&action.command).is_empty() {
+                return Err(invalid(format!("'{}' asks for arguments", action.name)));
+            }
+            resolved.push((index, project, action));
+        }
+        Ok(resolved)
+    }
+
     /// Resolve command bar items for a specific project, applying inheritance:
     /// global -> workspace -> project
     ///
@@ -409,6 +1778,152 @@ impl Config {
         result.sort_by(|a, b| a.key.cmp(&b.key));
         result
     }
+
+    /// Resolve the file browser ignore patterns for a specific project.
+    ///
+    /// A project's `file_browser` section replaces the global one entirely.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The identifier of the workspace
+    /// * `project_index` - The index of the project within the workspace
+    ///
+    /// # Returns
+    ///
+    /// The project's ignore patterns, or the global ones if it doesn't override them.
+    pub fn resolve_ignore_patterns(&self, workspace_id: &str, project_index: usize) -> Vec<String> {
+        self.workspace
+            .get(workspace_id)
+            .and_then(|w| w.projects.get(project_index))
+            .and_then(|p| p.file_browser.as_ref())
+            .unwrap_or(&self.global.file_browser)
+            .ignore
+            .clone()
+    }
+
+    /// Resolve the base branch a project's current branch is compared to.
+    ///
+    /// A base branch set on the project overrides the global one.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The project to resolve the base branch for
+    ///
+    /// # Returns
+    ///
+    /// The base branch, or None if neither the project nor the global
+    /// settings define one.
+    pub fn resolve_base_branch<'a>(&'a self, project: &'a Project) -> Option<&'a str> {
+        project
+            .git
+            .as_ref()
+            .and_then(|git| git.base_branch.as_deref())
+            .or(self.global.git.base_branch.as_deref())
+    }
+
+    /// Resolve the second remote of a project, the project's taking
+    /// precedence over the global one.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The project to resolve the remote for
+    ///
+    /// # Returns
+    ///
+    /// The remote name, or None if neither the project nor the global
+    /// settings define one.
+    pub fn resolve_compare_remote<'a>(&'a self, project: &'a Project) -> Option<&'a str> {
+        project
+            .git
+            .as_ref()
+            .and_then(|git| git.compare_remote.as_deref())
+            .or(self.global.git.compare_remote.as_deref())
+    }
+
+    /// Resolve how many commits behind upstream a project may be before its
+    /// git status is shown as risky.
+    ///
+    /// A threshold set on the project overrides the global one.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The project to resolve the threshold for
+    ///
+    /// # Returns
+    ///
+    /// The threshold, `DEFAULT_BEHIND_WARN` if none is set.
+    pub fn resolve_behind_warn(&self, project: &Project) -> u32 {
+        project
+            .git
+            .as_ref()
+            .and_then(|git| git.behind_warn)
+            .or(self.global.git.behind_warn)
+            .unwrap_or(DEFAULT_BEHIND_WARN)
+    }
+
+    /// Resolve the limits of the status of a project's files, each setting
+    /// of the project taking precedence over the global one.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The project to resolve the limits for
+    pub fn resolve_status_limits(&self, project: &Project) -> StatusLimits {
+        let project_git = project.git.as_ref();
+        let timeout_ms = project_git
+            .and_then(|git| git.status_timeout_ms)
+            .or(self.global.git.status_timeout_ms);
+        StatusLimits {
+            timeout: timeout_ms.map(Duration::from_millis),
+            max_entries: project_git
+                .and_then(|git| git.max_status_entries)
+                .or(self.global.git.max_status_entries),
+        }
+    }
+
+    /// Resolve how often a project's remote is fetched in the background.
+    ///
+    /// An interval set on the project overrides the global one.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The project to resolve the interval for
+    ///
+    /// # Returns
+    ///
+    /// The interval, or None if periodic fetches are disabled.
+    pub fn resolve_auto_fetch(&self, project: &Project) -> Option<Duration> {
+        project
+            .git
+            .as_ref()
+            .and_then(|git| git.auto_fetch_minutes)
+            .or(self.global.git.auto_fetch_minutes)
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| Duration::from_secs(minutes * 60))
+    }
+}
+
+/// Checks that a project path exists and is a directory.
+///
+/// # Arguments
+///
+/// * `path` - The project path
+///
+/// # Errors
+///
+/// - `ConfigError::PathNotFound` if the path does not exist
+/// - `ConfigError::PathNotDirectory` if the path is not a directory
+pub fn check_project_path(path: &Path) -> std::result::Result<(), ConfigError> {
+    if !path.exists() {
+        return Err(ConfigError::PathNotFound {
+            path: path.to_path_buf(),
+        });
+    }
+    if !path.is_dir() {
+        return Err(ConfigError::PathNotDirectory {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(())
 }
 
 #[cfg(test)]
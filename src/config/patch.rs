@@ -0,0 +1,240 @@
+//! Comment-preserving updates of TOML configuration files.
+//!
+//! An edit is applied as the difference between the document read from the
+//! file and the edited one, onto the file's own syntax tree: settings that
+//! didn't change keep their comments, layout and position, and new ones are
+//! added next to their siblings.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde_json::{Map, Value};
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, TableLike};
+
+use crate::error::{ConfigError, Result};
+
+/// Applies an edit to the text of a TOML configuration.
+///
+/// # Arguments
+///
+/// * `content` - The text of the configuration file
+/// * `original` - The document the text parses to
+/// * `edited` - The edited document
+///
+/// # Returns
+///
+/// The text of the edited configuration.
+///
+/// # Errors
+///
+/// - `ConfigError::FormatParseError` if the text is not valid TOML
+pub(super) fn update_toml(content: &str, original: &Value, edited: &Value) -> Result<String> {
+    let mut document: DocumentMut =
+        content
+            .parse()
+            .map_err(|e: toml_edit::TomlError| ConfigError::FormatParseError {
+                format: "TOML",
+                message: e.to_string().trim_end().to_string(),
+            })?;
+    if let (Value::Object(original), Value::Object(edited)) = (original, edited) {
+        patch_table(document.as_table_mut(), original, edited);
+    }
+    Ok(document.to_string())
+}
+
+/// Applies the difference between two objects to a table.
+fn patch_table(
+    table: &mut dyn TableLike,
+    original: &Map<String, Value>,
+    edited: &Map<String, Value>,
+) {
+    for key in original.keys() {
+        if edited.get(key).map_or(true, Value::is_null) {
+            table.remove(key);
+        }
+    }
+    for (key, value) in edited {
+        match (original.get(key), table.get_mut(key)) {
+            (Some(old), _) if old == value => {}
+            (Some(old), Some(item)) => patch_item(item, old, value),
+            _ => {
+                if let Some(item) = to_item(value) {
+                    table.insert(key, item);
+                }
+            }
+        }
+    }
+}
+
+/// Applies the difference between two values to an item.
+fn patch_item(item: &mut Item, original: &Value, edited: &Value) {
+    match (original, edited) {
+        (Value::Object(old), Value::Object(new)) if item.is_table_like() => {
+            if let Some(table) = item.as_table_like_mut() {
+                patch_table(table, old, new);
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => match item {
+            Item::ArrayOfTables(tables)
+                if tables.len() == old.len()
+                    && !new.is_empty()
+                    && new.iter().all(Value::is_object) =>
+            {
+                patch_tables(tables, old, new)
+            }
+            Item::Value(toml_edit::Value::Array(array)) if array.len() == old.len() => {
+                patch_array(array, old, new)
+            }
+            _ => replace_item(item, edited),
+        },
+        _ => replace_item(item, edited),
+    }
+}
+
+/// Applies the difference between two arrays of objects to an array of
+/// tables.
+fn patch_tables(tables: &mut ArrayOfTables, original: &[Value], edited: &[Value]) {
+    let mut items: Vec<Table> = tables.iter().cloned().collect();
+    patch_list(
+        &mut items,
+        original,
+        edited,
+        |table, old, new| {
+            if let (Value::Object(old), Value::Object(new)) = (old, new) {
+                patch_table(table, old, new);
+            }
+        },
+        |value| value.as_object().map(to_table),
+    );
+    tables.clear();
+    for table in items {
+        tables.push(table);
+    }
+}
+
+/// Applies the difference between two arrays to an inline array.
+fn patch_array(array: &mut Array, original: &[Value], edited: &[Value]) {
+    let decor = array.iter().last().map(|value| value.decor().clone());
+    let mut items: Vec<toml_edit::Value> = array.iter().cloned().collect();
+    patch_list(
+        &mut items,
+        original,
+        edited,
+        |value, old, new| {
+            let mut item = Item::Value(value.clone());
+            patch_item(&mut item, old, new);
+            if let Ok(patched) = item.into_value() {
+                *value = patched;
+            }
+        },
+        |value| {
+            let mut created = to_value(value)?;
+            if let Some(decor) = &decor {
+                *created.decor_mut() = decor.clone();
+            }
+            Some(created)
+        },
+    );
+    array.clear();
+    for value in items {
+        array.push_formatted(value);
+    }
+}
+
+/// Applies the difference between two lists to their syntax nodes.
+///
+/// Elements kept at both ends are left alone; the ones in between are
+/// patched one by one when as many remain, and replaced otherwise.
+fn patch_list<T>(
+    items: &mut Vec<T>,
+    original: &[Value],
+    edited: &[Value],
+    patch: impl Fn(&mut T, &Value, &Value),
+    create: impl Fn(&Value) -> Option<T>,
+) {
+    let prefix = original
+        .iter()
+        .zip(edited)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = original[prefix..]
+        .iter()
+        .rev()
+        .zip(edited[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let old_middle = &original[prefix..original.len() - suffix];
+    let new_middle = &edited[prefix..edited.len() - suffix];
+
+    if old_middle.len() == new_middle.len() {
+        for (index, (old, new)) in old_middle.iter().zip(new_middle).enumerate() {
+            patch(&mut items[prefix + index], old, new);
+        }
+    } else {
+        let created: Vec<T> = new_middle.iter().filter_map(&create).collect();
+        items.splice(prefix..prefix + old_middle.len(), created);
+    }
+}
+
+/// Replaces an item with a new value, inline if the item was.
+fn replace_item(item: &mut Item, value: &Value) {
+    match item {
+        Item::Value(old) => match to_value(value) {
+            Some(mut new) => {
+                *new.decor_mut() = old.decor().clone();
+                *old = new;
+            }
+            None => *item = Item::None,
+        },
+        _ => *item = to_item(value).unwrap_or(Item::None),
+    }
+}
+
+/// Converts a value to an item: objects become tables and arrays of
+/// objects arrays of tables. None for null, which TOML can't represent.
+fn to_item(value: &Value) -> Option<Item> {
+    match value {
+        Value::Object(map) => Some(Item::Table(to_table(map))),
+        Value::Array(values) if !values.is_empty() && values.iter().all(Value::is_object) => {
+            let mut tables = ArrayOfTables::new();
+            for map in values.iter().filter_map(Value::as_object) {
+                tables.push(to_table(map));
+            }
+            Some(Item::ArrayOfTables(tables))
+        }
+        _ => to_value(value).map(Item::Value),
+    }
+}
+
+/// Converts an object to a table, leaving null members out.
+fn to_table(map: &Map<String, Value>) -> Table {
+    let mut table = Table::new();
+    for (key, value) in map {
+        if let Some(item) = to_item(value) {
+            table.insert(key, item);
+        }
+    }
+    table
+}
+
+/// Converts a value to an inline value. None for null.
+fn to_value(value: &Value) -> Option<toml_edit::Value> {
+    match value {
+        Value::Null => None,
+        Value::Bool(flag) => Some((*flag).into()),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => Some(integer.into()),
+            None => number.as_f64().map(Into::into),
+        },
+        Value::String(text) => Some(text.as_str().into()),
+        Value::Array(values) => Some(values.iter().filter_map(to_value).collect::<Array>().into()),
+        Value::Object(map) => {
+            let mut table = InlineTable::new();
+            for (key, value) in map {
+                if let Some(value) = to_value(value) {
+                    table.insert(key, value);
+                }
+            }
+            Some(table.into())
+        }
+    }
+}
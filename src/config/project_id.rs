@@ -0,0 +1,87 @@
+//! Stable project ids.
+//!
+//! The panel refers to the project it shows by an id derived from its
+//! workspace and name rather than by its position, so reordering, adding or
+//! removing projects while a session runs keeps it on the same project, and
+//! positions remembered across runs follow the project.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::fmt;
+
+use super::Config;
+
+/// FNV-1a offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a prime.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Identifies a project by its workspace and name.
+///
+/// The id is a hash, computed the same way on every run and platform, so it
+/// can be persisted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProjectId(u64);
+
+impl ProjectId {
+    /// Returns the id of a project.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The identifier of the workspace containing it
+    /// * `name` - The name of the project
+    pub fn new(workspace_id: &str, name: &str) -> Self {
+        // The separator keeps "ab"/"c" and "a"/"bc" apart
+        let bytes = workspace_id.bytes().chain([0]).chain(name.bytes());
+        Self(bytes.fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        }))
+    }
+}
+
+impl fmt::Display for ProjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl Config {
+    /// Returns the id of the project at a position of a workspace.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The identifier of the workspace
+    /// * `project_index` - The index of the project within the workspace
+    ///
+    /// # Returns
+    ///
+    /// The id, or None if the workspace has no project at that position.
+    pub fn project_id(&self, workspace_id: &str, project_index: usize) -> Option<ProjectId> {
+        let project = self
+            .workspace
+            .get(workspace_id)?
+            .projects
+            .get(project_index)?;
+        Some(ProjectId::new(workspace_id, &project.name))
+    }
+
+    /// Returns the current position of a project within its workspace.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The identifier of the workspace
+    /// * `project_id` - The id of the project
+    ///
+    /// # Returns
+    ///
+    /// The index of the first project of the workspace with that id, or None
+    /// if it was removed or renamed.
+    pub fn project_index(&self, workspace_id: &str, project_id: ProjectId) -> Option<usize> {
+        self.workspace
+            .get(workspace_id)?
+            .projects
+            .iter()
+            .position(|project| ProjectId::new(workspace_id, &project.name) == project_id)
+    }
+}
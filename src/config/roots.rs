@@ -0,0 +1,52 @@
+//! File browser roots of a project.
+//!
+//! A monorepo project can list the directories it is worked on through, e.g.
+//! `roots = ["services/api", "apps/web"]`. The file browser then shows each
+//! of them as a top-level entry instead of the repository root.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::path::{Component, Path, PathBuf};
+
+use super::Project;
+use crate::error::ConfigError;
+
+impl Project {
+    /// Returns the directories of the project's roots, in order.
+    ///
+    /// # Returns
+    ///
+    /// The roots joined to the project path, empty if the file browser shows
+    /// the project directory.
+    pub fn root_paths(&self) -> Vec<PathBuf> {
+        self.roots
+            .iter()
+            .map(|root| self.path.join(root.trim()))
+            .collect()
+    }
+
+    /// Checks that roots are set and stay within the project directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidRoot` for the first invalid root.
+    pub(super) fn validate_roots(&self) -> Result<(), ConfigError> {
+        for root in &self.roots {
+            let invalid = |reason: &str| ConfigError::InvalidRoot {
+                project_name: self.name.clone(),
+                root: root.clone(),
+                reason: reason.to_string(),
+            };
+            if root.trim().is_empty() {
+                return Err(invalid("the path is empty"));
+            }
+            let inside = Path::new(root.trim())
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+            if !inside {
+                return Err(invalid("the path must be relative to the project"));
+            }
+        }
+        Ok(())
+    }
+}
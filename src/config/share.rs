@@ -0,0 +1,411 @@
+//! Sharing workspace definitions.
+//!
+//! `gz-claude workspace export` writes a workspace as a TOML snippet whose
+//! project paths are relative to a root, so a team can keep its standard
+//! workspaces in a repository. `gz-claude workspace import` resolves the
+//! paths against the importer's root and merges the workspace into the local
+//! configuration, asking which side to keep where the two differ.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::edit::{read_document, update_document};
+use super::{Config, ConfigFormat};
+use crate::error::{ConfigError, Result};
+use crate::util::path::expand_tilde;
+
+/// First line of an exported snippet.
+const SNIPPET_HEADER: &str =
+    "# gz-claude workspace: paths are relative to the root given to `gz-claude workspace import`";
+
+/// Workspace keys holding a directory, written relative to the root.
+const WORKSPACE_PATH_KEYS: &[&str] = &["discover"];
+
+/// A difference between an imported workspace and the local one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportConflict {
+    /// Settings of the workspace other than its projects differ.
+    Settings { workspace_id: String },
+    /// A project with the same name is defined differently.
+    Project { workspace_id: String, name: String },
+}
+
+impl fmt::Display for ImportConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportConflict::Settings { workspace_id } => {
+                write!(f, "Settings of workspace '{}' differ", workspace_id)
+            }
+            ImportConflict::Project { workspace_id, name } => write!(
+                f,
+                "Project '{}' of workspace '{}' is defined differently",
+                name, workspace_id
+            ),
+        }
+    }
+}
+
+/// Which side of a conflict is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictChoice {
+    /// Keeps the local definition.
+    KeepLocal,
+    /// Replaces it with the imported one.
+    TakeImported,
+}
+
+/// What importing a workspace changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// The identifier of the workspace.
+    pub workspace_id: String,
+    /// Whether the workspace was not configured before.
+    pub created: bool,
+    /// Whether its settings were replaced by the imported ones.
+    pub settings_replaced: bool,
+    /// The projects added.
+    pub added: Vec<String>,
+    /// The projects replaced by the imported ones.
+    pub replaced: Vec<String>,
+    /// The projects kept as they were, though defined differently.
+    pub kept: Vec<String>,
+}
+
+impl ImportSummary {
+    /// Returns whether the import changes the configuration.
+    pub fn changes(&self) -> bool {
+        self.created
+            || self.settings_replaced
+            || !self.added.is_empty()
+            || !self.replaced.is_empty()
+    }
+}
+
+impl Config {
+    /// Exports a workspace of the configuration file as a TOML snippet.
+    ///
+    /// Project paths, and the workspace's `discover` directory, are written
+    /// relative to the root.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_path` - The path to the configuration file
+    /// * `workspace_id` - The identifier of the workspace
+    /// * `root` - The directory paths are relative to; defaults to the
+    ///   workspace's `discover` directory, or else the deepest directory
+    ///   containing all its projects
+    ///
+    /// # Returns
+    ///
+    /// The snippet, ending with a newline.
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::WorkspaceNotFound` if the workspace is not configured
+    /// - `ConfigError::PathOutsideRoot` if a path is not under the root
+    /// - `ConfigError::ReadError` or `ConfigError::ParseError` if the file can't be loaded
+    pub fn export_workspace(
+        config_path: &Path,
+        workspace_id: &str,
+        root: Option<&Path>,
+    ) -> Result<String> {
+        let document = read_document(config_path)?;
+        let Some(mut workspace) = document
+            .get("workspace")
+            .and_then(|w| w.get(workspace_id))
+            .and_then(Value::as_object)
+            .cloned()
+        else {
+            return Err(ConfigError::WorkspaceNotFound {
+                workspace_id: workspace_id.to_string(),
+            }
+            .into());
+        };
+
+        let root = match root {
+            Some(root) => expand_tilde(&root.to_string_lossy()),
+            None => default_root(&workspace),
+        };
+        for key in WORKSPACE_PATH_KEYS {
+            relativize(&mut workspace, key, &root)?;
+        }
+        if let Some(projects) = workspace.get_mut("projects").and_then(Value::as_array_mut) {
+            for project in projects.iter_mut().filter_map(Value::as_object_mut) {
+                relativize(project, "path", &root)?;
+            }
+        }
+
+        let mut workspaces = Map::new();
+        workspaces.insert(workspace_id.to_string(), Value::Object(workspace));
+        let mut snippet = Map::new();
+        snippet.insert("workspace".to_string(), Value::Object(workspaces));
+        let content = ConfigFormat::Toml.write(&Value::Object(snippet))?;
+        Ok(format!("{}\n\n{}", SNIPPET_HEADER, content))
+    }
+
+    /// Merges the workspaces of a snippet into the configuration file.
+    ///
+    /// Relative paths of the snippet are resolved against the root. A
+    /// workspace that isn't configured is added as it is; otherwise its new
+    /// projects are appended, and `resolve` is asked which side to keep for
+    /// its settings and for every project defined differently. Local
+    /// projects the snippet doesn't list are left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_path` - The path to the configuration file
+    /// * `snippet_path` - The snippet, as written by `export_workspace`
+    /// * `root` - The directory relative paths are resolved against
+    /// * `dry_run` - Whether to leave the configuration file unchanged
+    /// * `resolve` - Chooses the side kept for a conflict
+    ///
+    /// # Returns
+    ///
+    /// What changed, per workspace of the snippet.
+    ///
+    /// # Errors
+    ///
+    /// - `ConfigError::FormatParseError` if the snippet is not TOML or has no workspace
+    /// - `ConfigError::CommentsWouldBeLost` if the file is YAML with comments
+    /// - `ConfigError::ReadError` or `ConfigError::ParseError` if a file can't be loaded
+    pub fn import_workspace(
+        config_path: &Path,
+        snippet_path: &Path,
+        root: &Path,
+        dry_run: bool,
+        resolve: impl FnMut(&ImportConflict) -> ConflictChoice,
+    ) -> Result<Vec<ImportSummary>> {
+        let content = fs::read_to_string(snippet_path).map_err(ConfigError::ReadError)?;
+        let snippet: Value = ConfigFormat::Toml.parse(&content)?;
+        let mut document = read_document(config_path)?;
+
+        let summaries = merge_workspaces(&mut document, snippet, root, resolve)?;
+        if !dry_run && summaries.iter().any(ImportSummary::changes) {
+            update_document(config_path, &document)?;
+        }
+        Ok(summaries)
+    }
+}
+
+/// Returns the root a workspace's paths are exported relative to.
+fn default_root(workspace: &Map<String, Value>) -> PathBuf {
+    if let Some(discover) = workspace.get("discover").and_then(Value::as_str) {
+        return expand_tilde(discover);
+    }
+    let mut paths = workspace
+        .get("projects")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|project| project.get("path")?.as_str())
+        .map(expand_tilde);
+    let Some(first) = paths.next() else {
+        return PathBuf::new();
+    };
+    // Projects are directories, so the root is at least their parent
+    let first = first.parent().map(Path::to_path_buf).unwrap_or(first);
+    paths.fold(first, |root, path| {
+        root.components()
+            .zip(path.components())
+            .take_while(|(a, b)| a == b)
+            .map(|(component, _)| component)
+            .collect()
+    })
+}
+
+/// Rewrites a path member of an object relative to the root.
+fn relativize(object: &mut Map<String, Value>, key: &str, root: &Path) -> Result<()> {
+    let Some(path) = object.get(key).and_then(Value::as_str) else {
+        return Ok(());
+    };
+    let path = expand_tilde(path);
+    let Ok(relative) = path.strip_prefix(root) else {
+        return Err(ConfigError::PathOutsideRoot {
+            path,
+            root: root.to_path_buf(),
+        }
+        .into());
+    };
+    let relative = if relative.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        relative.to_string_lossy().into_owned()
+    };
+    object.insert(key.to_string(), Value::from(relative));
+    Ok(())
+}
+
+/// Resolves a relative path member of an object against the root.
+fn resolve_path(object: &mut Map<String, Value>, key: &str, root: &Path) {
+    let Some(path) = object.get(key).and_then(Value::as_str) else {
+        return;
+    };
+    let path = Path::new(path);
+    if path.is_absolute() || path.starts_with("~") {
+        return;
+    }
+    let resolved = path
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .fold(root.to_path_buf(), |resolved, component| {
+            resolved.join(component)
+        });
+    object.insert(
+        key.to_string(),
+        Value::from(resolved.to_string_lossy().into_owned()),
+    );
+}
+
+/// Returns whether two definitions are the same, once their paths are
+/// expanded.
+fn same_definition(local: &Value, imported: &Value, path_keys: &[&str]) -> bool {
+    let expanded = |value: &Value| {
+        let mut value = value.clone();
+        if let Some(object) = value.as_object_mut() {
+            for key in path_keys {
+                if let Some(path) = object.get(*key).and_then(Value::as_str) {
+                    let path = expand_tilde(path).to_string_lossy().into_owned();
+                    object.insert(key.to_string(), Value::from(path));
+                }
+            }
+        }
+        value
+    };
+    expanded(local) == expanded(imported)
+}
+
+/// Merges the workspaces of a snippet into a configuration document.
+fn merge_workspaces(
+    document: &mut Value,
+    snippet: Value,
+    root: &Path,
+    mut resolve: impl FnMut(&ImportConflict) -> ConflictChoice,
+) -> Result<Vec<ImportSummary>> {
+    let not_a_workspace = |message: &str| ConfigError::FormatParseError {
+        format: ConfigFormat::Toml.name(),
+        message: message.to_string(),
+    };
+    let Some(Value::Object(imported)) = snippet.get("workspace").cloned() else {
+        return Err(not_a_workspace("the snippet has no [workspace] table").into());
+    };
+    let Some(workspaces) = document
+        .as_object_mut()
+        .map(|root| {
+            root.entry("workspace")
+                .or_insert_with(|| Value::Object(Map::new()))
+        })
+        .and_then(Value::as_object_mut)
+    else {
+        return Err(not_a_workspace("'workspace' is not a table of workspaces").into());
+    };
+
+    let mut summaries = Vec::new();
+    for (workspace_id, workspace) in imported {
+        let Value::Object(mut workspace) = workspace else {
+            return Err(not_a_workspace(&format!("'{}' is not a workspace", workspace_id)).into());
+        };
+        for key in WORKSPACE_PATH_KEYS {
+            resolve_path(&mut workspace, key, root);
+        }
+        let projects = match workspace.remove("projects") {
+            Some(Value::Array(projects)) => projects,
+            _ => Vec::new(),
+        };
+        let projects: Vec<Value> = projects
+            .into_iter()
+            .map(|mut project| {
+                if let Some(project) = project.as_object_mut() {
+                    resolve_path(project, "path", root);
+                }
+                project
+            })
+            .collect();
+
+        let mut summary = ImportSummary {
+            workspace_id: workspace_id.clone(),
+            ..ImportSummary::default()
+        };
+        let Some(local) = workspaces
+            .get_mut(&workspace_id)
+            .and_then(Value::as_object_mut)
+        else {
+            summary.created = true;
+            summary.added = projects
+                .iter()
+                .filter_map(|p| p.get("name")?.as_str().map(String::from))
+                .collect();
+            workspace.insert("projects".to_string(), Value::Array(projects));
+            workspaces.insert(workspace_id, Value::Object(workspace));
+            summaries.push(summary);
+            continue;
+        };
+
+        // Settings only set locally are not a difference
+        let local_settings: Map<String, Value> = workspace
+            .keys()
+            .filter_map(|key| Some((key.clone(), local.get(key)?.clone())))
+            .collect();
+        let differs = !same_definition(
+            &Value::Object(local_settings),
+            &Value::Object(workspace.clone()),
+            WORKSPACE_PATH_KEYS,
+        );
+        if differs {
+            let conflict = ImportConflict::Settings {
+                workspace_id: workspace_id.clone(),
+            };
+            if resolve(&conflict) == ConflictChoice::TakeImported {
+                local.extend(workspace);
+                summary.settings_replaced = true;
+            }
+        }
+
+        let local_projects = local
+            .entry("projects")
+            .or_insert_with(|| Value::Array(Vec::new()));
+        let Some(local_projects) = local_projects.as_array_mut() else {
+            return Err(not_a_workspace(&format!(
+                "the projects of '{}' are not a list",
+                workspace_id
+            ))
+            .into());
+        };
+        for project in projects {
+            let Some(name) = project
+                .get("name")
+                .and_then(Value::as_str)
+                .map(String::from)
+            else {
+                continue;
+            };
+            let existing = local_projects
+                .iter()
+                .position(|p| p.get("name").and_then(Value::as_str) == Some(name.as_str()));
+            match existing {
+                None => {
+                    local_projects.push(project);
+                    summary.added.push(name);
+                }
+                Some(index) if same_definition(&local_projects[index], &project, &["path"]) => {}
+                Some(index) => {
+                    let conflict = ImportConflict::Project {
+                        workspace_id: workspace_id.clone(),
+                        name: name.clone(),
+                    };
+                    if resolve(&conflict) == ConflictChoice::TakeImported {
+                        local_projects[index] = project;
+                        summary.replaced.push(name);
+                    } else {
+                        summary.kept.push(name);
+                    }
+                }
+            }
+        }
+        summaries.push(summary);
+    }
+    Ok(summaries)
+}
@@ -3,6 +3,7 @@
 //! @author waabox(waabox[at]gmail[dot]com)
 
 use super::*;
+use crate::error::GzClaudeError;
 use std::io::Write;
 use tempfile::NamedTempFile;
 
@@ -37,7 +38,7 @@ fn when_parsing_valid_config_should_succeed() {
     }"#;
 
     let file = create_temp_config(content);
-    let config = Config::load_from(&file.path().to_path_buf()).unwrap();
+    let config = Config::load_from(file.path()).unwrap();
 
     assert_eq!(config.global.editor, "vim");
     assert_eq!(config.global.git_info_level, GitInfoLevel::Standard);
@@ -61,12 +62,15 @@ fn when_parsing_minimal_config_should_use_defaults() {
     }"#;
 
     let file = create_temp_config(content);
-    let config = Config::load_from(&file.path().to_path_buf()).unwrap();
+    let config = Config::load_from(file.path()).unwrap();
 
     assert_eq!(config.global.editor, "$EDITOR");
+    assert_eq!(config.global.shell, "$SHELL");
     assert_eq!(config.global.git_info_level, GitInfoLevel::Minimal);
     assert!(!config.web_client.auto_start);
     assert_eq!(config.web_client.port, 8082);
+    assert_eq!(config.web_client.picker_port, None);
+    assert_eq!(config.state_endpoint.port, None);
 }
 
 #[test]
@@ -88,7 +92,7 @@ fn when_validating_config_with_invalid_action_key_should_fail() {
     }"#;
 
     let file = create_temp_config(content);
-    let config = Config::load_from(&file.path().to_path_buf()).unwrap();
+    let config = Config::load_from(file.path()).unwrap();
     let result = config.validate();
 
     assert!(result.is_err());
@@ -115,7 +119,7 @@ fn when_validating_config_with_empty_command_should_fail() {
     }"#;
 
     let file = create_temp_config(content);
-    let config = Config::load_from(&file.path().to_path_buf()).unwrap();
+    let config = Config::load_from(file.path()).unwrap();
     let result = config.validate();
 
     assert!(result.is_err());
@@ -138,12 +142,13 @@ fn when_validating_config_with_nonexistent_path_should_fail() {
     }"#;
 
     let file = create_temp_config(content);
-    let config = Config::load_from(&file.path().to_path_buf()).unwrap();
+    let config = Config::load_from(file.path()).unwrap();
     let result = config.validate();
 
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(err.contains("does not exist"));
+    assert!(config.validate_structure().is_ok());
 }
 
 #[test]
@@ -153,7 +158,7 @@ fn when_validating_config_with_no_workspaces_should_fail() {
     }"#;
 
     let file = create_temp_config(content);
-    let config = Config::load_from(&file.path().to_path_buf()).unwrap();
+    let config = Config::load_from(file.path()).unwrap();
     let result = config.validate();
 
     assert!(result.is_err());
@@ -192,7 +197,7 @@ fn when_resolving_actions_should_apply_inheritance() {
     }"#;
 
     let file = create_temp_config(content);
-    let config = Config::load_from(&file.path().to_path_buf()).unwrap();
+    let config = Config::load_from(file.path()).unwrap();
     let actions = config.resolve_actions("test", 0);
 
     // Project level overrides workspace which overrides global
@@ -204,3 +209,2163 @@ fn when_resolving_actions_should_apply_inheritance() {
     // Project only
     assert_eq!(actions.get("p").unwrap().command, "project-cmd");
 }
+
+#[test]
+fn when_validating_config_with_empty_hook_should_fail() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "c": { "name": "Claude", "command": "claude", "pre": "  " }
+            }
+        },
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "P1", "path": "/tmp" }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let result = config.validate();
+
+    assert!(result.is_err());
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("Empty pre hook"));
+}
+
+#[test]
+fn when_parsing_action_hooks_should_default_to_pane_mode() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "c": { "name": "Claude", "command": "claude", "pre": "git pull" },
+                "b": { "name": "Build", "command": "make", "post": "echo done", "hook_mode": "background" }
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    let claude = config.global.actions.get("c").unwrap();
+    assert_eq!(claude.pre.as_deref(), Some("git pull"));
+    assert_eq!(claude.hook_mode, HookMode::Pane);
+
+    let build = config.global.actions.get("b").unwrap();
+    assert_eq!(build.post.as_deref(), Some("echo done"));
+    assert_eq!(build.hook_mode, HookMode::Background);
+}
+
+#[test]
+fn when_parsing_composite_action_should_read_its_panes() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "d": {
+                    "name": "dev",
+                    "panes": [
+                        { "name": "Claude", "command": "claude" },
+                        { "name": "Watch", "command": "cargo watch", "placement": "down" },
+                        { "name": "Git", "command": "lazygit", "placement": "tab" }
+                    ]
+                }
+            }
+        },
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "P1", "path": "/tmp" }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert!(config.validate().is_ok());
+    let dev = config.global.actions.get("d").unwrap();
+    let placements: Vec<PanePlacement> = dev.panes.iter().map(|p| p.placement).collect();
+    assert_eq!(
+        placements,
+        vec![
+            PanePlacement::Floating,
+            PanePlacement::Down,
+            PanePlacement::Tab
+        ]
+    );
+    assert!(dev.command.is_empty());
+}
+
+#[test]
+fn when_composite_action_also_sets_command_should_fail() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "d": {
+                    "name": "dev",
+                    "command": "claude",
+                    "panes": [{ "name": "Watch", "command": "cargo watch" }]
+                }
+            }
+        },
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "P1", "path": "/tmp" }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let err = config.validate().unwrap_err().to_string();
+
+    assert!(err.contains("Invalid panes for action 'dev'"));
+}
+
+#[test]
+fn when_composite_action_runs_in_background_should_fail() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "f": { "name": "fetch", "command": "git fetch", "background": true },
+                "d": {
+                    "name": "dev",
+                    "background": true,
+                    "panes": [{ "name": "Watch", "command": "cargo watch" }]
+                }
+            }
+        },
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "P1", "path": "/tmp" }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let err = config.validate().unwrap_err().to_string();
+
+    assert!(config.global.actions["f"].background);
+    assert!(err.contains("Invalid panes for action 'dev'"));
+    assert!(err.contains("background"));
+}
+
+#[test]
+fn when_resolving_ignore_patterns_should_prefer_project_override() {
+    let content = r#"{
+        "global": {
+            "file_browser": { "ignore": ["target", "*.lock"] }
+        },
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "P1", "path": "/tmp" },
+                    { "name": "P2", "path": "/tmp", "file_browser": { "ignore": ["dist"] } }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert_eq!(
+        config.resolve_ignore_patterns("test", 0),
+        vec!["target".to_string(), "*.lock".to_string()]
+    );
+    assert_eq!(
+        config.resolve_ignore_patterns("test", 1),
+        vec!["dist".to_string()]
+    );
+}
+
+#[test]
+fn when_validating_health_check_with_command_and_port_should_fail() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let content = format!(
+        r#"{{
+        "global": {{}},
+        "workspace": {{
+            "test": {{
+                "name": "Test",
+                "projects": [
+                    {{
+                        "name": "P1",
+                        "path": "{}",
+                        "check": {{ "command": "cargo check -q", "port": 5432 }}
+                    }}
+                ]
+            }}
+        }}
+    }}"#,
+        temp_dir.path().display()
+    );
+
+    let file = create_temp_config(&content);
+    let config = Config::load_from(file.path()).unwrap();
+    let result = config.validate();
+
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("exactly one of command or port"));
+}
+
+#[test]
+fn when_health_check_interval_is_zero_should_fail_validation() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "P1", "path": "/tmp", "check": { "port": 5432, "interval_secs": 0 } }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let result = config.validate();
+
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("interval_secs must be at least 1"));
+}
+
+#[test]
+fn when_parsing_health_check_should_default_interval() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "P1", "path": "/tmp", "check": { "port": 5432 } }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    let check = config.workspace["test"].projects[0].check.as_ref().unwrap();
+    assert_eq!(check.port, Some(5432));
+    assert_eq!(check.interval_secs, 300);
+    assert_eq!(check.timeout_secs, 60);
+}
+
+#[test]
+fn when_action_uses_reserved_key_should_report_conflict() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "q": { "name": "Quick", "command": "echo quick" }
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let conflicts = config.key_conflicts();
+
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].kind, ConflictKind::Reserved);
+    assert_eq!(conflicts[0].source, KeySource::Global);
+}
+
+#[test]
+fn when_action_shadows_inherited_key_should_report_source_locations() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "c": { "name": "Claude", "command": "claude" },
+                "i": { "name": "Bash", "command": "bash" }
+            }
+        },
+        "workspace": {
+            "work": {
+                "name": "Work",
+                "actions": {
+                    "c": { "name": "Cargo", "command": "cargo build" }
+                },
+                "projects": [
+                    {
+                        "name": "P1",
+                        "path": "/tmp",
+                        "actions": {
+                            "i": { "name": "Build", "command": "make", "override": true }
+                        }
+                    }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let conflicts = config.key_conflicts();
+
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].key, "c");
+    assert_eq!(
+        conflicts[0].source,
+        KeySource::Workspace("work".to_string())
+    );
+    assert_eq!(conflicts[0].kind, ConflictKind::Shadows(KeySource::Global));
+    assert!(conflicts[0]
+        .to_string()
+        .contains("shadows the action defined in global"));
+}
+
+#[test]
+fn when_key_conflicts_are_errors_should_fail_validation() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let content = format!(
+        r#"{{
+        "global": {{
+            "key_conflicts": "error",
+            "actions": {{ "r": {{ "name": "Run", "command": "make run" }} }}
+        }},
+        "workspace": {{
+            "test": {{
+                "name": "Test",
+                "projects": [ {{ "name": "P1", "path": "{}" }} ]
+            }}
+        }}
+    }}"#,
+        temp_dir.path().display()
+    );
+
+    let file = create_temp_config(&content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("reserved by a built-in binding"));
+    assert!(config.key_conflict_warnings().is_empty());
+}
+
+#[test]
+fn when_adding_project_should_append_it_and_keep_other_settings() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let content = r#"{
+  "global": { "editor": "vim", "future_setting": true },
+  "workspace": {
+    "test": { "name": "Test", "projects": [] }
+  }
+}
+"#;
+    let file = create_temp_config(content);
+
+    Config::add_project(file.path(), "test", " api ", temp_dir.path()).unwrap();
+
+    let written = fs::read_to_string(file.path()).unwrap();
+    assert!(written.find("\"global\"").unwrap() < written.find("\"workspace\"").unwrap());
+    assert!(written.find("\"editor\"").unwrap() < written.find("\"future_setting\"").unwrap());
+
+    let config = Config::load_from(file.path()).unwrap();
+    let projects = &config.workspace["test"].projects;
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].name, "api");
+    assert_eq!(projects[0].path, temp_dir.path());
+}
+
+#[test]
+fn when_finding_project_line_should_search_after_its_workspace() {
+    let content = r#"{
+  "workspace": {
+    "first": {
+      "name": "First",
+      "projects": [ { "name": "api", "path": "/tmp/first-api" } ]
+    },
+    "second": {
+      "name": "Second",
+      "projects": [
+        {
+          "name": "api",
+          "path": "/tmp/second-api"
+        }
+      ]
+    }
+  }
+}
+"#;
+    let file = create_temp_config(content);
+
+    assert_eq!(Config::project_line(file.path(), "second", "api"), Some(11));
+    assert_eq!(Config::project_line(file.path(), "first", "web"), None);
+}
+
+#[test]
+fn when_adding_invalid_project_should_leave_config_unchanged() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let content = format!(
+        r#"{{
+  "global": {{}},
+  "workspace": {{
+    "test": {{ "name": "Test", "projects": [ {{ "name": "api", "path": "{}" }} ] }}
+  }}
+}}
+"#,
+        temp_dir.path().display()
+    );
+    let file = create_temp_config(&content);
+    let missing = temp_dir.path().join("missing");
+
+    let duplicate = Config::add_project(file.path(), "test", "api", temp_dir.path());
+    let not_found = Config::add_project(file.path(), "test", "web", &missing);
+    let no_workspace = Config::add_project(file.path(), "other", "web", temp_dir.path());
+    let empty_name = Config::add_project(file.path(), "test", "  ", temp_dir.path());
+
+    assert!(matches!(
+        duplicate,
+        Err(GzClaudeError::Config(ConfigError::DuplicateProject { .. }))
+    ));
+    assert!(matches!(
+        not_found,
+        Err(GzClaudeError::Config(ConfigError::PathNotFound { .. }))
+    ));
+    assert!(matches!(
+        no_workspace,
+        Err(GzClaudeError::Config(ConfigError::WorkspaceNotFound { .. }))
+    ));
+    assert!(matches!(
+        empty_name,
+        Err(GzClaudeError::Config(ConfigError::EmptyProjectName))
+    ));
+    assert_eq!(fs::read_to_string(file.path()).unwrap(), content);
+}
+
+#[test]
+fn when_removing_project_should_drop_it_from_workspace() {
+    let content = r#"{
+  "global": {},
+  "workspace": {
+    "test": {
+      "name": "Test",
+      "projects": [
+        { "name": "api", "path": "/work/api" },
+        { "name": "web", "path": "/work/web" }
+      ]
+    }
+  }
+}
+"#;
+    let file = create_temp_config(content);
+
+    Config::remove_project(file.path(), "test", "api", Path::new("/work/api")).unwrap();
+    let missing = Config::remove_project(file.path(), "test", "api", Path::new("/work/api"));
+
+    let config = Config::load_from(file.path()).unwrap();
+    let projects = &config.workspace["test"].projects;
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].name, "web");
+    assert!(matches!(
+        missing,
+        Err(GzClaudeError::Config(ConfigError::ProjectNotFound { .. }))
+    ));
+}
+
+#[test]
+fn when_projects_were_reordered_since_loading_should_remove_the_named_one() {
+    let file = create_temp_config(
+        r#"{
+  "global": {},
+  "workspace": {
+    "test": {
+      "name": "Test",
+      "projects": [
+        { "name": "web", "path": "/work/web" },
+        { "name": "api", "path": "/work/api" },
+        { "name": "api", "path": "/other/api" }
+      ]
+    }
+  }
+}
+"#,
+    );
+
+    Config::remove_project(file.path(), "test", "api", Path::new("/work/api")).unwrap();
+
+    let config = Config::load_from(file.path()).unwrap();
+    let projects = &config.workspace["test"].projects;
+    assert_eq!(projects.len(), 2);
+    assert_eq!(projects[0].name, "web");
+    assert_eq!(projects[1].path, PathBuf::from("/other/api"));
+}
+
+#[test]
+fn when_project_sets_base_branch_should_override_global() {
+    let content = r#"{
+        "global": { "git": { "base_branch": "main" } },
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "P1", "path": "/tmp" },
+                    { "name": "P2", "path": "/tmp", "git": { "base_branch": "develop" } }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let projects = &config.workspace["test"].projects;
+
+    assert_eq!(config.resolve_base_branch(&projects[0]), Some("main"));
+    assert_eq!(config.resolve_base_branch(&projects[1]), Some("develop"));
+}
+
+#[test]
+fn when_project_sets_compare_remote_should_override_global() {
+    let content = r#"{
+        "global": { "git": { "compare_remote": "upstream" } },
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "P1", "path": "/tmp" },
+                    { "name": "P2", "path": "/tmp", "git": { "compare_remote": "vendor" } }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let projects = &config.workspace["test"].projects;
+
+    assert_eq!(
+        config.resolve_compare_remote(&projects[0]),
+        Some("upstream")
+    );
+    assert_eq!(config.resolve_compare_remote(&projects[1]), Some("vendor"));
+}
+
+#[test]
+fn when_project_sets_auto_fetch_should_override_global() {
+    let content = r#"{
+        "global": { "git": { "auto_fetch_minutes": 30 } },
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "P1", "path": "/tmp" },
+                    { "name": "P2", "path": "/tmp", "git": { "auto_fetch_minutes": 5 } },
+                    { "name": "P3", "path": "/tmp", "git": { "auto_fetch_minutes": 0 } }
+                ]
+            }
+        }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let projects = &config.workspace["test"].projects;
+
+    assert_eq!(
+        config.resolve_auto_fetch(&projects[0]),
+        Some(Duration::from_secs(1800))
+    );
+    assert_eq!(
+        config.resolve_auto_fetch(&projects[1]),
+        Some(Duration::from_secs(300))
+    );
+    assert_eq!(config.resolve_auto_fetch(&projects[2]), None);
+}
+
+#[test]
+fn when_remote_is_configured_should_parse_provider_and_default_ttl() {
+    let content = r#"{
+        "global": { "remote": { "provider": "gitlab", "token_env": "ACME_TOKEN" } },
+        "workspace": {}
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let remote = config.global.remote.unwrap();
+
+    assert_eq!(remote.provider, Some(RemoteProvider::GitLab));
+    assert_eq!(remote.token_env.as_deref(), Some("ACME_TOKEN"));
+    assert_eq!(remote.api_url, None);
+    assert_eq!(remote.ttl_secs, 300);
+}
+
+#[test]
+fn when_remote_is_missing_should_be_disabled() {
+    let file = create_temp_config(r#"{ "global": {}, "workspace": {} }"#);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert_eq!(config.global.remote, None);
+}
+
+#[test]
+fn when_zellij_section_is_missing_should_use_cli_integration() {
+    let file = create_temp_config(r#"{ "global": {}, "workspace": {} }"#);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert_eq!(config.global.zellij.integration, ZellijIntegration::Cli);
+    assert_eq!(
+        config.global.zellij.plugin,
+        "file:~/.gz-claude/plugins/gz-claude.wasm"
+    );
+    assert_eq!(
+        config.global.zellij.command_timeout(),
+        Some(Duration::from_secs(5))
+    );
+    assert_eq!(config.global.zellij.command_retries, 1);
+    assert_eq!(
+        config.global.zellij.focus_interval(),
+        Some(Duration::from_secs(1))
+    );
+    assert!(!config.global.zellij.follow_focus);
+}
+
+#[test]
+fn when_command_timeout_is_zero_should_wait_forever() {
+    let content = r#"{
+        "global": { "zellij": { "command_timeout_ms": 0, "command_retries": 3 } },
+        "workspace": {}
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert_eq!(config.global.zellij.command_timeout(), None);
+    assert_eq!(config.global.zellij.command_retries, 3);
+}
+
+#[test]
+fn when_focus_interval_is_zero_should_not_track_focus() {
+    let content = r#"{
+        "global": { "zellij": { "focus_interval_ms": 0, "follow_focus": true } },
+        "workspace": {}
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert_eq!(config.global.zellij.focus_interval(), None);
+    assert!(config.global.zellij.follow_focus);
+}
+
+#[test]
+fn when_plugin_integration_is_set_should_parse_plugin_location() {
+    let content = r#"{
+        "global": { "zellij": { "integration": "plugin", "plugin": "file:/opt/gz.wasm" } },
+        "workspace": {}
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert_eq!(config.global.zellij.integration, ZellijIntegration::Plugin);
+    assert_eq!(config.global.zellij.plugin, "file:/opt/gz.wasm");
+}
+
+#[test]
+fn when_top_bar_is_missing_should_show_only_web_url() {
+    let file = create_temp_config(r#"{ "global": {}, "workspace": {} }"#);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert_eq!(
+        config.top_bar.widgets,
+        vec![TopBarWidget::new(TopBarWidgetKind::WebUrl)]
+    );
+    assert_eq!(config.top_bar.separator, " │ ");
+}
+
+#[test]
+fn when_top_bar_lists_widgets_should_keep_their_order_and_settings() {
+    let content = r#"{
+        "global": {},
+        "top_bar": {
+            "separator": " | ",
+            "widgets": [
+                { "type": "clock", "format": "%H:%M:%S", "color": "gray" },
+                { "type": "command", "command": "date", "interval_secs": 60 }
+            ]
+        },
+        "workspace": {}
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+    let widgets = &config.top_bar.widgets;
+
+    assert_eq!(config.top_bar.separator, " | ");
+    assert_eq!(widgets[0].kind, TopBarWidgetKind::Clock);
+    assert_eq!(widgets[0].format.as_deref(), Some("%H:%M:%S"));
+    assert_eq!(widgets[0].color, Some(TopBarColor::Gray));
+    assert_eq!(widgets[1].kind, TopBarWidgetKind::Command);
+    assert_eq!(widgets[1].interval_secs, Some(60));
+}
+
+#[test]
+fn when_command_widget_has_no_command_should_fail_validation() {
+    let content = r#"{
+        "global": {},
+        "top_bar": { "widgets": [{ "type": "web_url" }, { "type": "command" }] },
+        "workspace": { "w": { "name": "W", "projects": [] } }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert!(matches!(
+        config.validate_structure(),
+        Err(GzClaudeError::Config(ConfigError::InvalidTopBarWidget {
+            index: 1,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn when_clock_format_is_invalid_should_fail_validation() {
+    let content = r#"{
+        "global": {},
+        "top_bar": { "widgets": [{ "type": "clock", "format": "%Q" }] },
+        "workspace": { "w": { "name": "W", "projects": [] } }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert!(matches!(
+        config.validate_structure(),
+        Err(GzClaudeError::Config(ConfigError::InvalidTopBarWidget {
+            index: 0,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn when_loading_legacy_config_should_migrate_it_and_keep_backup() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.json");
+    let legacy = r#"{
+        "global": { "web_client": { "port": 9100 } },
+        "workspaces": { "test": { "name": "Test", "projects": [] } }
+    }"#;
+    fs::write(&path, legacy).unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+
+    assert_eq!(config.web_client.port, 9100);
+    assert!(config.workspace.contains_key("test"));
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("config.json.v0.bak")).unwrap(),
+        legacy
+    );
+    let migrated = fs::read_to_string(&path).unwrap();
+    assert!(migrated.contains(&format!("\"version\": {}", CONFIG_VERSION)));
+}
+
+#[test]
+fn when_reading_legacy_config_should_upgrade_it_without_writing() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.json");
+    let legacy = r#"{
+        "global": { "web_client": { "port": 9100 } },
+        "workspaces": { "test": { "name": "Test", "projects": [] } }
+    }"#;
+    fs::write(&path, legacy).unwrap();
+
+    let config = Config::read_from(&path).unwrap();
+
+    assert_eq!(config.web_client.port, 9100);
+    assert!(config.workspace.contains_key("test"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), legacy);
+    assert!(!temp_dir.path().join("config.json.v0.bak").exists());
+}
+
+#[test]
+fn when_config_is_yaml_should_load_it() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.yaml");
+    let content = r#"
+version: 1
+global:
+  editor: vim
+  actions:
+    c: { name: Claude, command: claude }
+workspace:
+  test:
+    name: Test
+    projects:
+      - name: api
+        path: /tmp
+"#;
+    fs::write(&path, content).unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+
+    assert_eq!(config.global.editor, "vim");
+    assert_eq!(config.global.actions["c"].command, "claude");
+    assert_eq!(config.workspace["test"].projects[0].name, "api");
+}
+
+#[test]
+fn when_config_extension_is_not_a_known_format_should_load_it_as_json() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("gz.conf");
+    fs::write(
+        &path,
+        r#"{ "version": 1, "global": { "editor": "vim" }, "workspace": {} }"#,
+    )
+    .unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+
+    assert_eq!(config.global.editor, "vim");
+}
+
+#[test]
+fn when_legacy_config_is_toml_should_migrate_it_in_memory_only() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.toml");
+    let legacy = r#"
+[global.web_client]
+port = 9100
+
+# Day to day work
+[workspaces.test]
+name = "Test"
+projects = []
+"#;
+    fs::write(&path, legacy).unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+
+    assert_eq!(config.web_client.port, 9100);
+    assert!(config.workspace.contains_key("test"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), legacy);
+    assert!(!temp_dir.path().join("config.toml.v0.bak").exists());
+}
+
+#[test]
+fn when_migrating_toml_config_file_should_rewrite_it_and_keep_backup() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.toml");
+    let legacy = r#"# My setup
+[global]
+editor = "vim" # the only one
+
+[workspaces.test]
+name = "Test"
+projects = []
+"#;
+    fs::write(&path, legacy).unwrap();
+
+    assert_eq!(Config::migrate_file(&path).unwrap(), Some(0));
+    assert_eq!(Config::migrate_file(&path).unwrap(), None);
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("config.toml.v0.bak")).unwrap(),
+        legacy
+    );
+    let migrated = fs::read_to_string(&path).unwrap();
+    assert!(migrated.contains(&format!("version = {}", CONFIG_VERSION)));
+    assert!(migrated.contains("# My setup\n[global]\neditor = \"vim\" # the only one"));
+    let config = Config::load_from(&path).unwrap();
+    assert!(config.workspace.contains_key("test"));
+}
+
+#[test]
+fn when_editing_toml_config_should_keep_its_comments() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.toml");
+    let project_dir = temp_dir.path().to_string_lossy().into_owned();
+    fs::write(
+        &path,
+        format!(
+            r#"version = 1 # schema
+
+# The editor opened by `e`
+[global]
+editor = "vim"
+
+[workspace.test]
+name = "Test"
+
+# The backend
+[[workspace.test.projects]]
+name = "api"
+path = "/work/api" # moved last year
+
+# The frontend
+[[workspace.test.projects]]
+name = "web"
+path = "{}"
+"#,
+            project_dir
+        ),
+    )
+    .unwrap();
+
+    Config::add_project(&path, "test", "docs", temp_dir.path()).unwrap();
+    Config::remove_project(&path, "test", "web", temp_dir.path()).unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.contains("version = 1 # schema"));
+    assert!(content.contains("# The editor opened by `e`\n[global]"));
+    assert!(content.contains("# The backend\n[[workspace.test.projects]]"));
+    assert!(content.contains("path = \"/work/api\" # moved last year"));
+    assert!(!content.contains("# The frontend"));
+    let config = Config::load_from(&path).unwrap();
+    let names: Vec<_> = config.workspace["test"]
+        .projects
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(names, ["api", "docs"]);
+}
+
+#[test]
+fn when_editing_yaml_config_with_comments_should_refuse() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.yaml");
+    let content = r#"version: 1
+global: {}
+workspace:
+  test:
+    name: Test # the main one
+    projects: []
+"#;
+    fs::write(&path, content).unwrap();
+
+    let result = Config::add_project(&path, "test", "api", temp_dir.path());
+
+    assert!(matches!(
+        result,
+        Err(GzClaudeError::Config(
+            ConfigError::CommentsWouldBeLost { .. }
+        ))
+    ));
+    assert_eq!(fs::read_to_string(&path).unwrap(), content);
+
+    fs::write(&path, content.replace(" # the main one", "")).unwrap();
+    Config::add_project(&path, "test", "api", temp_dir.path()).unwrap();
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.workspace["test"].projects[0].name, "api");
+}
+
+#[test]
+fn when_converting_config_should_keep_every_setting() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let json = temp_dir.path().join("config.json");
+    let toml = temp_dir.path().join("config.toml");
+    fs::write(
+        &json,
+        r#"{
+            "version": 1,
+            "global": { "editor": "vim", "future_setting": true },
+            "workspace": { "test": { "name": "Test", "projects": [] } }
+        }"#,
+    )
+    .unwrap();
+
+    Config::convert(&json, &toml).unwrap();
+
+    assert_eq!(
+        edit::read_document(&toml).unwrap(),
+        edit::read_document(&json).unwrap()
+    );
+    assert!(matches!(
+        Config::convert(&json, &toml),
+        Err(GzClaudeError::Config(ConfigError::AlreadyExists { .. }))
+    ));
+    assert!(matches!(
+        Config::convert(&json, &temp_dir.path().join("config.ini")),
+        Err(GzClaudeError::Config(ConfigError::UnsupportedFormat { .. }))
+    ));
+}
+
+#[test]
+fn when_loading_config_from_newer_version_should_fail() {
+    let file = create_temp_config(r#"{ "version": 99, "global": {} }"#);
+
+    let result = Config::load_from(file.path());
+
+    assert!(matches!(
+        result,
+        Err(GzClaudeError::Config(ConfigError::UnsupportedVersion {
+            found: 99,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn when_parsing_web_tls_should_accept_modes_and_custom_files() {
+    let parse = |tls: &str| {
+        let content = format!(
+            r#"{{ "global": {{}}, "web_client": {{ "tls": {} }} }}"#,
+            tls
+        );
+        let file = create_temp_config(&content);
+        Config::load_from(file.path()).unwrap().web_client.tls
+    };
+
+    assert_eq!(WebClientConfig::default().tls, WebTls::SelfSigned);
+    assert_eq!(parse(r#""self-signed""#), WebTls::SelfSigned);
+    assert_eq!(parse(r#""off""#), WebTls::Off);
+    assert_eq!(
+        parse(r#"{ "cert": "/certs/lan.pem", "key": "/certs/lan-key.pem" }"#),
+        WebTls::Custom {
+            cert: PathBuf::from("/certs/lan.pem"),
+            key: PathBuf::from("/certs/lan-key.pem"),
+        }
+    );
+}
+
+#[test]
+fn when_validating_config_with_missing_tls_file_should_fail() {
+    let content = r#"{
+        "global": {},
+        "web_client": { "tls": { "cert": "/nonexistent/cert.pem", "key": "/nonexistent/key.pem" } },
+        "workspace": { "test": { "name": "Test", "projects": [] } }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert!(matches!(
+        config.validate(),
+        Err(GzClaudeError::Config(ConfigError::TlsFileNotFound { .. }))
+    ));
+}
+
+#[test]
+fn when_validating_config_with_invalid_bind_address_should_fail() {
+    let content = r#"{
+        "global": {},
+        "web_client": { "bind_address": "everywhere" },
+        "workspace": { "test": { "name": "Test", "projects": [] } }
+    }"#;
+
+    let file = create_temp_config(content);
+    let config = Config::load_from(file.path()).unwrap();
+
+    assert!(matches!(
+        config.validate(),
+        Err(GzClaudeError::Config(
+            ConfigError::InvalidBindAddress { .. }
+        ))
+    ));
+}
+
+#[test]
+fn when_command_bar_item_sets_capture_should_parse_it() {
+    let content = r#"{
+        "global": {
+            "command_bar": [
+                { "key": "k", "name": "Pods", "command": "kubectl get pods", "capture": true },
+                { "key": "d", "name": "Deploy", "command": "./deploy.sh" }
+            ]
+        },
+        "workspace": {}
+    }"#;
+
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    assert!(config.global.command_bar[0].capture);
+    assert!(!config.global.command_bar[1].capture);
+}
+
+#[test]
+fn when_missing_project_has_repo_should_need_clone_and_validate() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    {
+                        "name": "P1",
+                        "path": "/nonexistent/gz-claude/clone-target",
+                        "repo": "git@github.com:org/name.git"
+                    },
+                    { "name": "P2", "path": "/tmp" }
+                ]
+            }
+        }
+    }"#;
+
+    let config: Config = serde_json::from_str(content).unwrap();
+    let projects = &config.workspace["test"].projects;
+
+    assert!(projects[0].needs_clone());
+    assert!(!projects[1].needs_clone());
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn when_vim_navigation_is_enabled_should_reserve_g() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "g": { "name": "Lazygit", "command": "lazygit" }
+            }
+        }
+    }"#;
+
+    let mut config: Config = serde_json::from_str(content).unwrap();
+    assert_eq!(config.global.navigation, NavigationMode::Default);
+    assert!(config.key_conflicts().is_empty());
+
+    config.global.navigation = NavigationMode::Vim;
+    let conflicts = config.key_conflicts();
+
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].key, "g");
+    assert_eq!(conflicts[0].kind, ConflictKind::Reserved);
+}
+
+#[test]
+fn when_parsing_vim_navigation_should_succeed() {
+    let content = r#"{ "global": { "navigation": "vim" } }"#;
+
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    assert_eq!(config.global.navigation, NavigationMode::Vim);
+}
+
+#[test]
+fn when_workspace_and_project_have_descriptions_should_parse_them() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "helios": {
+                "name": "Helios",
+                "description": "Payments platform",
+                "projects": [
+                    { "name": "helios-gateway", "path": "/tmp", "description": "Public API gateway" },
+                    { "name": "helios-worker", "path": "/tmp" }
+                ]
+            }
+        }
+    }"#;
+
+    let config: Config = serde_json::from_str(content).unwrap();
+    let workspace = &config.workspace["helios"];
+
+    assert_eq!(workspace.description.as_deref(), Some("Payments platform"));
+    assert_eq!(
+        workspace.projects[0].description.as_deref(),
+        Some("Public API gateway")
+    );
+    assert!(workspace.projects[1].description.is_none());
+}
+
+#[test]
+fn when_session_settings_are_missing_should_use_defaults() {
+    let config: Config = serde_json::from_str(r#"{ "global": {} }"#).unwrap();
+
+    assert_eq!(config.global.zellij.session_name, "gz-claude");
+    assert_eq!(config.global.zellij.session_policy, SessionPolicy::Ask);
+}
+
+#[test]
+fn when_claude_idle_settings_are_missing_should_watch_without_notifying() {
+    let config: Config = serde_json::from_str(r#"{ "global": {} }"#).unwrap();
+
+    assert_eq!(config.global.claude_idle, ClaudeIdleConfig::default());
+    assert!(config.global.claude_idle.enabled);
+    assert_eq!(config.global.claude_idle.interval_secs, 3);
+    assert!(!config.global.claude_idle.desktop_notification);
+
+    let content = r#"{
+        "global": { "claude_idle": { "interval_secs": 10, "blink_top_bar": true } }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    assert_eq!(config.global.claude_idle.interval_secs, 10);
+    assert!(config.global.claude_idle.blink_top_bar);
+    assert!(config.global.claude_idle.enabled);
+}
+
+#[test]
+fn when_main_pane_is_configured_should_tell_whether_it_runs_a_shell() {
+    let config: Config = serde_json::from_str(r#"{ "global": {} }"#).unwrap();
+    assert_eq!(config.layout, LayoutConfig::default());
+    assert_eq!(config.layout.main_command(), "bash");
+    assert!(config.layout.main_runs_shell());
+
+    let content = r#"{
+        "global": {},
+        "layout": { "main_command": "/usr/bin/fish -l", "main_cwd": "~/work" }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    assert_eq!(config.layout.main_cwd.as_deref(), Some("~/work"));
+    assert!(config.layout.main_runs_shell());
+
+    let layout = LayoutConfig {
+        main_command: Some("claude --continue".to_string()),
+        main_cwd: None,
+    };
+    assert!(!layout.main_runs_shell());
+}
+
+#[test]
+fn when_resolving_behind_warn_should_prefer_project_setting() {
+    let content = r#"{
+        "global": { "git": { "behind_warn": 25 } },
+        "workspace": {
+            "helios": {
+                "name": "Helios",
+                "projects": [
+                    { "name": "api", "path": "/tmp", "git": { "behind_warn": 3 } },
+                    { "name": "web", "path": "/tmp" }
+                ]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    let projects = &config.workspace["helios"].projects;
+
+    assert_eq!(config.resolve_behind_warn(&projects[0]), 3);
+    assert_eq!(config.resolve_behind_warn(&projects[1]), 25);
+
+    let config: Config = serde_json::from_str(r#"{ "global": {} }"#).unwrap();
+    assert_eq!(
+        config.resolve_behind_warn(&projects[1]),
+        DEFAULT_BEHIND_WARN
+    );
+}
+
+#[test]
+fn when_session_policy_is_set_should_parse_it() {
+    let content = r#"{
+        "global": { "zellij": { "session_name": "work", "session_policy": "restart" } }
+    }"#;
+
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    assert_eq!(config.global.zellij.session_name, "work");
+    assert_eq!(config.global.zellij.session_policy, SessionPolicy::Restart);
+}
+
+#[test]
+fn when_workspace_has_working_set_should_resolve_its_projects() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "helios": {
+                "name": "Helios",
+                "projects": [
+                    { "name": "api", "path": "/tmp" },
+                    { "name": "web", "path": "/tmp" },
+                    { "name": "docs", "path": "/tmp" }
+                ],
+                "working_set": ["web", { "project": "api", "run": "shell" }]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    let resolved = config.workspace["helios"].working_set_projects().unwrap();
+    let names: Vec<(&str, WorkingSetRun)> = resolved
+        .iter()
+        .map(|(project, run)| (project.name.as_str(), *run))
+        .collect();
+
+    assert_eq!(
+        names,
+        vec![
+            ("web", WorkingSetRun::Claude),
+            ("api", WorkingSetRun::Shell)
+        ]
+    );
+    assert!(config.validate_structure().is_ok());
+}
+
+#[test]
+fn when_working_set_names_unknown_project_should_fail_validation() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "helios": {
+                "name": "Helios",
+                "projects": [{ "name": "api", "path": "/tmp" }],
+                "working_set": ["api", "missing"]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    let result = config.validate_structure();
+
+    assert!(matches!(
+        result,
+        Err(GzClaudeError::Config(ConfigError::InvalidWorkingSet { ref reason, .. }))
+            if reason == "no project named 'missing'"
+    ));
+}
+
+#[test]
+fn when_projects_are_autostarted_should_resolve_their_default_actions() {
+    let content = r#"{
+        "global": {
+            "actions": { "c": { "name": "Claude", "command": "claude" } }
+        },
+        "workspace": {
+            "helios": {
+                "name": "Helios",
+                "default_action": "c",
+                "projects": [
+                    { "name": "api", "path": "/tmp", "autostart": true },
+                    { "name": "web", "path": "/tmp" },
+                    {
+                        "name": "docs", "path": "/tmp", "autostart": true, "default_action": "s",
+                        "actions": { "s": { "name": "Serve", "command": "mkdocs serve" } }
+                    }
+                ]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    let resolved = config.autostart_actions("helios").unwrap();
+    let names: Vec<(usize, &str, &str)> = resolved
+        .iter()
+        .map(|(index, project, action)| (*index, project.name.as_str(), action.name.as_str()))
+        .collect();
+
+    assert_eq!(names, vec![(0, "api", "Claude"), (2, "docs", "Serve")]);
+    assert!(config.validate_structure().is_ok());
+}
+
+#[test]
+fn when_autostarted_action_asks_for_arguments_should_fail_validation() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "helios": {
+                "name": "Helios",
+                "projects": [{
+                    "name": "api", "path": "/tmp", "autostart": true, "default_action": "r",
+                    "actions": { "r": { "name": "Run", "command": "run {prompt:Target}" } }
+                }]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    let result = config.validate_structure();
+
+    assert!(matches!(
+        result,
+        Err(GzClaudeError::Config(ConfigError::InvalidAutostart { ref reason, .. }))
+            if reason == "'Run' asks for arguments"
+    ));
+}
+
+#[test]
+fn when_notifications_are_configured_should_parse_channels_and_events() {
+    let content = r#"{
+        "global": {},
+        "notifications": {
+            "bell": true,
+            "command": "terminal-notifier -message {message}",
+            "events": ["claude_idle", "config_reload_failed"]
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    let notifications = &config.notifications;
+
+    assert!(notifications.bell);
+    assert!(!notifications.osc9);
+    assert_eq!(
+        notifications.command(),
+        Some("terminal-notifier -message {message}")
+    );
+    assert!(notifications.notifies(NotificationEvent::ClaudeIdle));
+    assert!(!notifications.notifies(NotificationEvent::GitBehind));
+
+    let config: Config = serde_json::from_str(r#"{ "global": {} }"#).unwrap();
+    assert_eq!(config.notifications.events.len(), 4);
+    assert!(!config.notifications.notifies(NotificationEvent::ClaudeIdle));
+}
+
+#[test]
+fn when_adding_workspace_should_write_projects_with_their_repo() {
+    let file = create_temp_config(
+        r#"{ "global": {}, "workspace": { "test": { "name": "Test", "projects": [] } } }"#,
+    );
+    let projects = vec![NewProject {
+        name: "api".to_string(),
+        path: PathBuf::from("/src/acme/api"),
+        description: Some("The API".to_string()),
+        repo: "git@github.com:acme/api.git".to_string(),
+    }];
+
+    Config::add_workspace(file.path(), "acme", "Acme", &projects).unwrap();
+    let duplicate = Config::add_workspace(file.path(), "acme", "Acme", &projects);
+
+    let config = Config::load_from(file.path()).unwrap();
+    let workspace = &config.workspace["acme"];
+    assert_eq!(workspace.name, "Acme");
+    assert_eq!(workspace.projects[0].path, PathBuf::from("/src/acme/api"));
+    assert_eq!(
+        workspace.projects[0].description.as_deref(),
+        Some("The API")
+    );
+    assert_eq!(
+        workspace.projects[0].repo.as_deref(),
+        Some("git@github.com:acme/api.git")
+    );
+    assert!(config.workspace.contains_key("test"));
+    assert!(matches!(
+        duplicate,
+        Err(GzClaudeError::Config(
+            ConfigError::DuplicateWorkspace { .. }
+        ))
+    ));
+}
+
+#[test]
+fn when_ui_is_configured_should_parse_ascii_only_theme_and_compact() {
+    let content = r#"{
+        "global": {},
+        "ui": { "ascii_only": true, "theme": "high_contrast", "compact": "always" }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    assert!(config.ui.ascii_only);
+    assert_eq!(config.ui.theme, ThemePreset::HighContrast);
+    assert_eq!(config.ui.compact, CompactMode::Always);
+
+    let config: Config = serde_json::from_str(r#"{ "global": {} }"#).unwrap();
+    assert_eq!(config.ui, UiConfig::default());
+    assert_eq!(config.ui.theme, ThemePreset::Default);
+    assert_eq!(config.ui.compact, CompactMode::Auto);
+}
+
+#[test]
+fn when_linting_config_should_report_non_fatal_issues_in_order() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "q": { "name": "Quick", "command": "echo quick", "icon": "🚀" }
+            },
+            "command_bar": [
+                { "key": "t", "name": "Tests", "command": "cargo test", "icon": "TEST" }
+            ]
+        },
+        "workspace": {
+            "work": {
+                "name": "Work",
+                "projects": [{ "name": "api", "path": "/tmp" }]
+            },
+            "oss": {
+                "name": "OSS",
+                "projects": [{ "name": "fork", "path": "/tmp" }]
+            },
+            "later": { "name": "Later" }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    let warnings: Vec<String> = config.lint().iter().map(|w| w.to_string()).collect();
+
+    assert_eq!(
+        warnings,
+        vec![
+            "key 'q' of action 'Quick' (global) is reserved by a built-in binding".to_string(),
+            "icon 'TEST' of 'Tests' (global) is longer than 2 characters and breaks alignment"
+                .to_string(),
+            "projects work/api, oss/fork share the directory /tmp".to_string(),
+            "workspace 'later' has no projects".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn when_key_conflicts_are_ignored_should_not_lint_them() {
+    let content = r#"{
+        "global": {
+            "key_conflicts": "ignore",
+            "actions": { "q": { "name": "Quick", "command": "echo quick" } }
+        },
+        "workspace": {
+            "work": { "name": "Work", "projects": [{ "name": "api", "path": "/tmp" }] }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    assert!(config.lint().is_empty());
+}
+
+#[test]
+fn when_project_has_bookmarks_should_resolve_action_dirs_and_placeholders() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "mono": {
+                "name": "Mono",
+                "projects": [{
+                    "name": "repo",
+                    "path": "/work/repo",
+                    "bookmarks": { "api": "services/api", "web": "apps/web" },
+                    "actions": {
+                        "t": { "name": "Test API", "command": "npm test", "cwd": "{bookmark:api}" },
+                        "d": { "name": "Docs", "command": "ls", "cwd": "docs" },
+                        "m": { "name": "Missing", "command": "ls", "cwd": "{bookmark:cli}" }
+                    }
+                }]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    let project = &config.workspace["mono"].projects[0];
+
+    assert_eq!(
+        project.action_dir(&project.actions["t"]).unwrap(),
+        PathBuf::from("/work/repo/services/api")
+    );
+    assert_eq!(
+        project.action_dir(&project.actions["d"]).unwrap(),
+        PathBuf::from("/work/repo/docs")
+    );
+    assert!(matches!(
+        project.action_dir(&project.actions["m"]),
+        Err(ConfigError::UnknownBookmark { ref name, .. }) if name == "cli"
+    ));
+    assert_eq!(
+        project
+            .expand_bookmarks("diff {bookmark:api} {bookmark:web}")
+            .unwrap(),
+        "diff '/work/repo/services/api' '/work/repo/apps/web'"
+    );
+    let names: Vec<&str> = project
+        .bookmark_paths()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(names, vec!["api", "web"]);
+}
+
+#[test]
+fn when_bookmark_path_is_empty_should_fail_validation() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "mono": {
+                "name": "Mono",
+                "projects": [{ "name": "repo", "path": "/tmp", "bookmarks": { "api": " " } }]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    assert!(matches!(
+        config.validate_structure(),
+        Err(GzClaudeError::Config(ConfigError::InvalidBookmark { ref name, .. })) if name == "api"
+    ));
+}
+
+#[test]
+fn when_project_has_roots_should_resolve_them_within_the_project() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "mono": {
+                "name": "Mono",
+                "projects": [
+                    { "name": "repo", "path": "/work/repo", "roots": ["services/api", " apps/web "] },
+                    { "name": "plain", "path": "/work/plain" }
+                ]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    let projects = &config.workspace["mono"].projects;
+
+    assert!(config.validate_structure().is_ok());
+    assert_eq!(
+        projects[0].root_paths(),
+        vec![
+            PathBuf::from("/work/repo/services/api"),
+            PathBuf::from("/work/repo/apps/web")
+        ]
+    );
+    assert!(projects[1].root_paths().is_empty());
+}
+
+#[test]
+fn when_root_leaves_the_project_should_fail_validation() {
+    for root in ["../other", "/etc", " "] {
+        let content = format!(
+            r#"{{
+                "global": {{}},
+                "workspace": {{
+                    "mono": {{
+                        "name": "Mono",
+                        "projects": [{{ "name": "repo", "path": "/tmp", "roots": ["{}"] }}]
+                    }}
+                }}
+            }}"#,
+            root
+        );
+        let config: Config = serde_json::from_str(&content).unwrap();
+
+        assert!(
+            matches!(
+                config.validate_structure(),
+                Err(GzClaudeError::Config(ConfigError::InvalidRoot { .. }))
+            ),
+            "root {:?} should be rejected",
+            root
+        );
+    }
+}
+
+#[test]
+fn when_inherited_action_uses_missing_bookmark_should_lint_it() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "t": { "name": "Test API", "command": "npm test", "cwd": "{bookmark:api}" }
+            }
+        },
+        "workspace": {
+            "mono": {
+                "name": "Mono",
+                "projects": [
+                    { "name": "repo", "path": "/work/repo", "bookmarks": { "api": "services/api" } },
+                    { "name": "tool", "path": "/work/tool" }
+                ]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    let warnings: Vec<String> = config.lint().iter().map(|w| w.to_string()).collect();
+
+    assert_eq!(
+        warnings,
+        vec![
+            "action 'Test API' uses the bookmark 'api', which project 'tool' in workspace 'mono' doesn't have"
+                .to_string()
+        ]
+    );
+}
+
+#[test]
+fn when_task_settings_are_set_should_render_the_initial_prompt() {
+    let config: Config = serde_json::from_str(r#"{ "global": {} }"#).unwrap();
+    assert_eq!(config.global.task.branch_prefix, "claude/");
+    assert_eq!(config.global.task.command, "claude");
+
+    let content = r#"{
+        "global": { "task": { "prompt": "Work on {task} in {project}, on {branch}" } }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    assert_eq!(
+        config
+            .global
+            .task
+            .render_prompt("Fix login", "claude/fix-login", "api"),
+        "Work on Fix login in api, on claude/fix-login"
+    );
+}
+
+#[test]
+fn when_project_sets_default_action_should_override_workspace() {
+    let content = r#"{
+        "global": { "project_enter": "action" },
+        "workspace": {
+            "mono": {
+                "name": "Mono",
+                "default_action": "c",
+                "projects": [
+                    { "name": "api", "path": "/work/api", "default_action": "t" },
+                    { "name": "web", "path": "/work/web" }
+                ]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    assert_eq!(config.global.project_enter, ProjectEnter::Action);
+    assert_eq!(config.resolve_default_action("mono", 0), Some("t"));
+    assert_eq!(config.resolve_default_action("mono", 1), Some("c"));
+    assert_eq!(config.resolve_default_action("other", 0), None);
+}
+
+#[test]
+fn when_default_action_is_not_an_action_key_should_lint_it() {
+    let content = r#"{
+        "global": {
+            "actions": { "c": { "name": "Claude", "command": "claude" } }
+        },
+        "workspace": {
+            "mono": {
+                "name": "Mono",
+                "projects": [
+                    { "name": "api", "path": "/work/api", "default_action": "c" },
+                    { "name": "web", "path": "/work/web", "default_action": "x" }
+                ]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    let warnings: Vec<String> = config.lint().iter().map(|w| w.to_string()).collect();
+
+    assert_eq!(
+        warnings,
+        vec![
+            "the default action 'x' of project 'web' in workspace 'mono' is not one of its action keys"
+                .to_string()
+        ]
+    );
+}
+
+#[test]
+fn when_picker_port_is_set_should_parse_it() {
+    let content = r#"{ "global": {}, "web_client": { "picker_port": 8090 } }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    assert_eq!(config.web_client.picker_port, Some(8090));
+}
+
+#[test]
+fn when_workspaces_have_order_should_list_them_first_then_as_defined() {
+    let content = r#"
+[global]
+
+[workspace.zeta]
+name = "Zeta"
+
+[workspace.beta]
+name = "Beta"
+order = 20
+
+[workspace.alpha]
+name = "Alpha"
+
+[workspace.gamma]
+name = "Gamma"
+order = 10
+"#;
+    let config: Config = toml::from_str(content).unwrap();
+
+    assert_eq!(
+        config.workspace_ids(),
+        vec!["gamma", "beta", "zeta", "alpha"]
+    );
+}
+
+#[test]
+fn when_state_endpoint_port_is_set_should_parse_it() {
+    let content = r#"{ "global": {}, "state_endpoint": { "port": 8091 } }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    assert_eq!(config.state_endpoint.port, Some(8091));
+}
+
+#[test]
+fn when_resolving_status_limits_should_let_projects_override_each_setting() {
+    let content = r#"{
+        "global": { "git": { "status_timeout_ms": 500, "max_status_entries": 10000 } },
+        "workspace": {
+            "work": {
+                "name": "Work",
+                "projects": [
+                    { "name": "chromium", "path": "/work/chromium", "git": { "max_status_entries": 100 } },
+                    { "name": "api", "path": "/work/api" }
+                ]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    let projects = &config.workspace["work"].projects;
+
+    let limits = config.resolve_status_limits(&projects[0]);
+    assert_eq!(limits.timeout, Some(Duration::from_millis(500)));
+    assert_eq!(limits.max_entries, Some(100));
+    assert_eq!(
+        config.resolve_status_limits(&projects[1]).max_entries,
+        Some(10000)
+    );
+}
+
+#[test]
+fn when_resolving_mcp_servers_should_let_projects_replace_inherited_ones() {
+    let content = r#"{
+        "global": {
+            "mcp": {
+                "github": { "command": "github-mcp" },
+                "docs": { "url": "https://docs.example.com/mcp" }
+            }
+        },
+        "workspace": {
+            "work": {
+                "name": "Work",
+                "mcp": { "sentry": { "url": "https://sentry.example.com/sse", "transport": "sse" } },
+                "projects": [
+                    {
+                        "name": "api",
+                        "path": "/work/api",
+                        "mcp": { "github": { "command": "gh-mcp", "enabled": false } }
+                    }
+                ]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+
+    let servers = config.resolve_mcp_servers("work", 0);
+
+    assert!(config.validate_structure().is_ok());
+    assert!(config.has_mcp_servers());
+    assert_eq!(
+        servers.keys().collect::<Vec<_>>(),
+        vec!["docs", "github", "sentry"]
+    );
+    assert_eq!(servers["github"].command.as_deref(), Some("gh-mcp"));
+    assert!(!servers["github"].enabled);
+    assert!(servers["docs"].enabled);
+    assert_eq!(servers["sentry"].transport, McpTransport::Sse);
+}
+
+#[test]
+fn when_project_env_is_set_should_wrap_its_pane_commands() {
+    let content = r#"{
+        "global": {},
+        "workspace": {
+            "work": {
+                "name": "Work",
+                "projects": [
+                    { "name": "api", "path": "/work/my api", "env": true },
+                    { "name": "web", "path": "/work/web", "env": "mise exec -C {path} --" },
+                    { "name": "cli", "path": "/work/cli", "env": false },
+                    { "name": "docs", "path": "/work/docs" }
+                ]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    let projects = &config.workspace["work"].projects;
+
+    assert_eq!(
+        projects[0].wrap_env("claude"),
+        "direnv exec '/work/my api' sh -c 'claude'"
+    );
+    assert_eq!(
+        projects[1].wrap_env("cd '/work/web' && npm test"),
+        "mise exec -C '/work/web' -- sh -c 'cd '\\''/work/web'\\'' && npm test'"
+    );
+    assert_eq!(projects[2].wrap_env("claude"), "claude");
+    assert_eq!(projects[3].wrap_env("claude"), "claude");
+    assert_eq!(
+        projects[0].env_wrapper(".").as_deref(),
+        Some("direnv exec .")
+    );
+}
+
+#[test]
+fn when_mcp_server_is_neither_local_nor_remote_should_fail_validation() {
+    for server in [
+        r#"{}"#,
+        r#"{ "command": "github-mcp", "url": "https://example.com" }"#,
+        r#"{ "command": " " }"#,
+    ] {
+        let content = format!(
+            r#"{{
+                "global": {{ "mcp": {{ "github": {} }} }},
+                "workspace": {{ "work": {{ "name": "Work" }} }}
+            }}"#,
+            server
+        );
+        let config: Config = serde_json::from_str(&content).unwrap();
+
+        assert!(
+            matches!(
+                config.validate_structure(),
+                Err(GzClaudeError::Config(ConfigError::InvalidMcpServer { .. }))
+            ),
+            "server {} should be rejected",
+            server
+        );
+    }
+}
+
+#[test]
+fn when_action_has_claude_args_should_pass_them_after_claude() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "c": {
+                    "name": "Claude",
+                    "command": "claude --verbose",
+                    "claude_args": ["--permission-mode", "acceptEdits", "--append-system-prompt", "Be brief"]
+                }
+            }
+        },
+        "workspace": {
+            "work": { "name": "Work", "projects": [{ "name": "api", "path": "/tmp" }] }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    assert!(config.validate_structure().is_ok());
+
+    let action = &config.global.actions["c"];
+    let project = &config.workspace["work"].projects[0];
+
+    assert_eq!(
+        project.claude_command(action, &action.command).unwrap(),
+        "claude '--permission-mode' 'acceptEdits' '--append-system-prompt' 'Be brief' --verbose"
+    );
+}
+
+#[test]
+fn when_claude_args_are_unknown_should_fail_validation() {
+    let cases = [
+        (r#""command": "claude", "claude_args": ["--yolo"]"#, "'--yolo' is not a known Claude flag"),
+        (r#""command": "claude", "claude_args": ["--model"]"#, "'--model' needs a value"),
+        (
+            r#""command": "claude", "claude_args": ["--permission-mode", "auto"]"#,
+            "'auto' is not a permission mode; use one of default, acceptEdits, plan, bypassPermissions",
+        ),
+        (r#""command": "lazygit", "claude_args": ["--verbose"]"#, "its command doesn't run claude"),
+    ];
+    for (action, expected) in cases {
+        let content = format!(
+            r#"{{ "global": {{ "actions": {{ "c": {{ "name": "Claude", {} }} }} }},
+                "workspace": {{ "work": {{ "name": "Work" }} }} }}"#,
+            action
+        );
+        let config: Config = serde_json::from_str(&content).unwrap();
+
+        let result = config.validate_structure();
+
+        assert!(
+            matches!(
+                result,
+                Err(GzClaudeError::Config(ConfigError::InvalidClaudeArgs { ref reason, .. }))
+                    if reason == expected
+            ),
+            "{:?}",
+            result
+        );
+    }
+}
+
+#[test]
+fn when_claude_args_skip_permissions_should_only_run_in_sandboxed_projects() {
+    let content = r#"{
+        "global": {
+            "actions": {
+                "y": {
+                    "name": "Unattended",
+                    "command": "claude",
+                    "claude_args": ["--dangerously-skip-permissions"]
+                }
+            }
+        },
+        "workspace": {
+            "work": {
+                "name": "Work",
+                "projects": [
+                    { "name": "api", "path": "/tmp" },
+                    { "name": "scratch", "path": "/tmp", "sandboxed": true }
+                ]
+            }
+        }
+    }"#;
+    let config: Config = serde_json::from_str(content).unwrap();
+    let action = &config.global.actions["y"];
+    let projects = &config.workspace["work"].projects;
+
+    assert!(action.skips_permissions());
+    assert!(matches!(
+        projects[0].claude_command(action, "claude"),
+        Err(ConfigError::UnsafeClaudeArgs { .. })
+    ));
+    assert_eq!(
+        projects[1].claude_command(action, "claude").unwrap(),
+        "claude '--dangerously-skip-permissions'"
+    );
+}
+
+#[test]
+fn when_projects_are_reordered_should_find_a_project_by_its_id() {
+    let config = |projects: &str| -> Config {
+        let content = format!(
+            r#"{{ "global": {{}}, "workspace": {{ "work": {{ "name": "Work", "projects": [{}] }} }} }}"#,
+            projects
+        );
+        serde_json::from_str(&content).unwrap()
+    };
+    let before = config(r#"{ "name": "api", "path": "/tmp" }, { "name": "web", "path": "/tmp" }"#);
+    let after = config(r#"{ "name": "web", "path": "/tmp" }, { "name": "api", "path": "/tmp" }"#);
+
+    let web = before.project_id("work", 1).unwrap();
+
+    assert_eq!(web, ProjectId::new("work", "web"));
+    assert_eq!(web.to_string().len(), 16);
+    assert_ne!(web, ProjectId::new("wor", "kweb"));
+    assert_eq!(after.project_index("work", web), Some(0));
+    assert_eq!(after.project_index("other", web), None);
+    assert_eq!(before.project_id("work", 2), None);
+}
+
+#[test]
+fn when_exporting_workspace_should_write_paths_relative_to_root() {
+    let file = create_temp_config(
+        r#"{ "global": {}, "workspace": { "helios": { "name": "Helios", "projects": [
+            { "name": "api", "path": "/src/helios/api", "repo": "git@github.com:helios/api.git" },
+            { "name": "web", "path": "/src/helios/apps/web" }
+        ] } } }"#,
+    );
+
+    let snippet = Config::export_workspace(file.path(), "helios", None).unwrap();
+    let outside =
+        Config::export_workspace(file.path(), "helios", Some(Path::new("/src/helios/apps")));
+    let missing = Config::export_workspace(file.path(), "billing", None);
+
+    let document: serde_json::Value = ConfigFormat::Toml.parse(&snippet).unwrap();
+    let projects = &document["workspace"]["helios"]["projects"];
+    assert_eq!(projects[0]["path"], "api");
+    assert_eq!(projects[0]["repo"], "git@github.com:helios/api.git");
+    assert_eq!(projects[1]["path"], "apps/web");
+    assert!(snippet.starts_with("# gz-claude workspace"));
+    assert!(matches!(
+        outside,
+        Err(GzClaudeError::Config(ConfigError::PathOutsideRoot { .. }))
+    ));
+    assert!(matches!(
+        missing,
+        Err(GzClaudeError::Config(ConfigError::WorkspaceNotFound { .. }))
+    ));
+}
+
+#[test]
+fn when_importing_workspace_should_resolve_paths_and_ask_about_conflicts() {
+    let file = create_temp_config(
+        r#"{ "global": {}, "workspace": { "helios": { "name": "Helios", "projects": [
+            { "name": "api", "path": "/home/ana/helios/api" },
+            { "name": "web", "path": "/home/ana/helios/web", "description": "Local" },
+            { "name": "notes", "path": "/home/ana/notes" }
+        ] } } }"#,
+    );
+    let mut snippet = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    snippet
+        .write_all(
+            br#"
+[workspace.helios]
+name = "Helios"
+
+[[workspace.helios.projects]]
+name = "api"
+path = "api"
+
+[[workspace.helios.projects]]
+name = "web"
+path = "web"
+description = "Shared"
+
+[[workspace.helios.projects]]
+name = "billing"
+path = "billing"
+
+[workspace.tools]
+name = "Tools"
+discover = "."
+projects = []
+"#,
+        )
+        .unwrap();
+    let mut conflicts = Vec::new();
+
+    let summaries = Config::import_workspace(
+        file.path(),
+        snippet.path(),
+        Path::new("/home/ana/helios"),
+        false,
+        |conflict| {
+            conflicts.push(conflict.clone());
+            ConflictChoice::TakeImported
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        conflicts,
+        vec![ImportConflict::Project {
+            workspace_id: "helios".to_string(),
+            name: "web".to_string()
+        }]
+    );
+    assert_eq!(summaries[0].added, vec!["billing"]);
+    assert_eq!(summaries[0].replaced, vec!["web"]);
+    assert!(summaries[1].created);
+    let config = Config::load_from(file.path()).unwrap();
+    let projects = &config.workspace["helios"].projects;
+    let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["api", "web", "notes", "billing"]);
+    assert_eq!(projects[1].description.as_deref(), Some("Shared"));
+    assert_eq!(projects[3].path, PathBuf::from("/home/ana/helios/billing"));
+    assert_eq!(
+        config.workspace["tools"].discover.as_deref(),
+        Some("/home/ana/helios")
+    );
+}
+
+#[test]
+fn when_keeping_local_definitions_should_leave_config_unchanged() {
+    let file = create_temp_config(
+        r#"{ "global": {}, "workspace": { "helios": { "name": "Helios", "projects": [
+            { "name": "api", "path": "/home/ana/helios/api" }
+        ] } } }"#,
+    );
+    let before = std::fs::read_to_string(file.path()).unwrap();
+    let mut snippet = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    snippet
+        .write_all(
+            br#"
+[workspace.helios]
+name = "Helios (shared)"
+
+[[workspace.helios.projects]]
+name = "api"
+path = "services/api"
+"#,
+        )
+        .unwrap();
+
+    let summaries = Config::import_workspace(
+        file.path(),
+        snippet.path(),
+        Path::new("/home/ana/helios"),
+        false,
+        |_| ConflictChoice::KeepLocal,
+    )
+    .unwrap();
+
+    assert_eq!(summaries[0].kept, vec!["api"]);
+    assert!(!summaries[0].changes());
+    assert_eq!(std::fs::read_to_string(file.path()).unwrap(), before);
+}
@@ -13,15 +13,123 @@ pub enum GzClaudeError {
     Config(#[from] ConfigError),
 
     #[error("Zellij error: {0}")]
-    Zellij(String),
+    Zellij(#[from] ZellijError),
+
+    #[error("Secret error: {0}")]
+    Secret(#[from] SecretError),
+
+    #[error("Session error: {0}")]
+    Session(#[from] SessionError),
 
     #[error("Git error: {0}")]
     Git(String),
 
+    #[error("Hook error: {0}")]
+    Hook(String),
+
+    #[error("Clipboard error: {0}")]
+    Clipboard(String),
+
+    #[error("Open error: {0}")]
+    Opener(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
 
+#[derive(Error, Debug)]
+pub enum ZellijError {
+    #[error("zellij is not installed or not in PATH")]
+    NotInstalled,
+
+    #[error("not running inside a Zellij session")]
+    NotInSession,
+
+    #[error("{action} failed ({status}){}", stderr_suffix(stderr))]
+    CommandFailed {
+        action: String,
+        status: String,
+        stderr: String,
+    },
+
+    #[error("{action} did not answer within {millis} ms; Zellij may be busy")]
+    TimedOut { action: String, millis: u64 },
+
+    #[error("this Zellij version does not support the web client")]
+    WebUnsupported,
+
+    #[error("cannot run an empty command")]
+    EmptyCommand,
+
+    #[error("no free port for the web server in {from}-{to}")]
+    NoFreePort { from: u16, to: u16 },
+
+    #[error("unexpected output from {action}: {output}")]
+    UnexpectedOutput { action: String, output: String },
+
+    #[error("Zellij did not report the id of pane '{pane_name}'; close it from Zellij")]
+    UnknownPaneId { pane_name: String },
+
+    #[error("the main pane is unknown; open the panel from the gz-claude layout")]
+    MainPaneUnknown,
+
+    #[error(
+        "the main pane ({pane_id}) was not found; it may have been closed, renamed or left out of the layout"
+    )]
+    MainPaneNotFound { pane_id: String },
+
+    #[error("pane {pane_id} was not found; it may have been closed")]
+    PaneNotFound { pane_id: String },
+
+    #[error("the pane operations stopped; restart the panel")]
+    ExecutorStopped,
+
+    #[error("the gz-claude plugin could not {operation}: {message}")]
+    PluginFailed { operation: String, message: String },
+
+    #[error("cannot generate the TLS certificate: {message}")]
+    CertificateFailed { message: String },
+}
+
+#[derive(Error, Debug)]
+pub enum SecretError {
+    #[error("secret '{name}' not found in the keychain or {}", path.display())]
+    NotFound { name: String, path: PathBuf },
+
+    #[error("invalid secret name '{name}' (use letters, digits, '_', '-' and '.')")]
+    InvalidName { name: String },
+
+    #[error("{} must only be accessible by its owner (chmod 600), found mode {mode:o}", path.display())]
+    InsecureFile { path: PathBuf, mode: u32 },
+
+    #[error("cannot read {}: {message}", path.display())]
+    InvalidFile { path: PathBuf, message: String },
+
+    #[error("keychain error for secret '{name}': {message}")]
+    Keychain { name: String, message: String },
+}
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("{} is not a session snapshot: {message}", path.display())]
+    InvalidSnapshot { path: PathBuf, message: String },
+
+    #[error("Session snapshot version {found} is newer than this gz-claude supports ({supported}); please upgrade gz-claude")]
+    UnsupportedSnapshotVersion { found: u32, supported: u32 },
+
+    #[error("Session state version {found} is newer than this gz-claude supports ({supported}); please upgrade gz-claude")]
+    UnsupportedSessionVersion { found: u64, supported: u64 },
+}
+
+/// Formats captured stderr for display after an error message.
+fn stderr_suffix(stderr: &str) -> String {
+    if stderr.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", stderr)
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("Configuration file not found at {0}")]
@@ -33,20 +141,123 @@ pub enum ConfigError {
     #[error("Failed to parse configuration: {0}")]
     ParseError(#[from] serde_json::Error),
 
+    #[error("Failed to parse {format} configuration: {message}")]
+    FormatParseError {
+        format: &'static str,
+        message: String,
+    },
+
+    #[error("Cannot write the configuration as {format}: {message}")]
+    FormatWriteError {
+        format: &'static str,
+        message: String,
+    },
+
+    #[error(
+        "Unsupported configuration file {path}: expected a .json, .yaml, .yml or .toml extension"
+    )]
+    UnsupportedFormat { path: PathBuf },
+
+    #[error("{path} already exists")]
+    AlreadyExists { path: PathBuf },
+
+    #[error("{path} has comments that would be lost by editing it from gz-claude; edit it by hand, or convert it to TOML with `gz-claude config convert`")]
+    CommentsWouldBeLost { path: PathBuf },
+
+    #[error("Configuration version {found} is newer than this gz-claude supports ({supported}); please upgrade gz-claude")]
+    UnsupportedVersion { found: u64, supported: u64 },
+
     #[error("Invalid action key '{key}': must be a single character")]
     InvalidActionKey { key: String },
 
     #[error("Empty command for action '{action_name}'")]
     EmptyCommand { action_name: String },
 
+    #[error("Invalid panes for action '{action_name}': {reason}")]
+    InvalidPaneTemplate { action_name: String, reason: String },
+
+    #[error("Empty {hook} hook for action '{action_name}'")]
+    EmptyHook { action_name: String, hook: String },
+
+    #[error("Invalid health check of project '{project_name}': {reason}")]
+    InvalidHealthCheck {
+        project_name: String,
+        reason: String,
+    },
+
+    #[error("Invalid working set of workspace '{workspace}': {reason}")]
+    InvalidWorkingSet { workspace: String, reason: String },
+
+    #[error("Invalid claude_args of action '{action_name}': {reason}")]
+    InvalidClaudeArgs { action_name: String, reason: String },
+
+    #[error("Action '{action_name}' skips Claude's permission prompts; mark project '{project}' as sandboxed to run it")]
+    UnsafeClaudeArgs {
+        action_name: String,
+        project: String,
+    },
+
+    #[error("Project '{project}' can't be autostarted: {reason}")]
+    InvalidAutostart { project: String, reason: String },
+
+    #[error("Invalid top bar widget #{}: {reason}", index + 1)]
+    InvalidTopBarWidget { index: usize, reason: String },
+
+    #[error("Conflicting action keys:\n{0}")]
+    KeyConflicts(String),
+
     #[error("Project path does not exist: {path}")]
     PathNotFound { path: PathBuf },
 
     #[error("Project path is not a directory: {path}")]
     PathNotDirectory { path: PathBuf },
 
+    #[error("Invalid web client bind address '{address}': must be an IP address")]
+    InvalidBindAddress { address: String },
+
+    #[error("Web client TLS file not found: {path}")]
+    TlsFileNotFound { path: PathBuf },
+
     #[error("No workspaces configured")]
     NoWorkspaces,
+
+    #[error("Workspace '{workspace_id}' is not configured")]
+    WorkspaceNotFound { workspace_id: String },
+
+    #[error("{path} is not under {root}; choose another root with --root")]
+    PathOutsideRoot { path: PathBuf, root: PathBuf },
+
+    #[error("Workspace '{workspace_id}' already exists")]
+    DuplicateWorkspace { workspace_id: String },
+
+    #[error("Workspace '{workspace_id}' has no project '{name}' at that path")]
+    ProjectNotFound { workspace_id: String, name: String },
+
+    #[error("Project name cannot be empty")]
+    EmptyProjectName,
+
+    #[error("A project named '{name}' already exists in this workspace")]
+    DuplicateProject { name: String },
+
+    #[error("Invalid bookmark '{name}' of project '{project_name}': {reason}")]
+    InvalidBookmark {
+        project_name: String,
+        name: String,
+        reason: String,
+    },
+
+    #[error("Project '{project_name}' has no bookmark named '{name}'")]
+    UnknownBookmark { project_name: String, name: String },
+
+    #[error("Invalid root '{root}' of project '{project_name}': {reason}")]
+    InvalidRoot {
+        project_name: String,
+        root: String,
+        reason: String,
+    },
+
+    #[error("Invalid MCP server '{name}': {reason}")]
+    InvalidMcpServer { name: String, reason: String },
 }
 
 pub type Result<T> = std::result::Result<T, GzClaudeError>;
@@ -0,0 +1,244 @@
+//! Blame summary of a file.
+//!
+//! `b` in the file browser shows who last changed each part of the selected
+//! file: the contributors by number of lines, then every hunk with its
+//! commit, author and age. The file is blamed as it is on disk, so lines
+//! changed since the last commit, e.g. by Claude, show as not committed yet.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use git2::Repository;
+
+use crate::error::{GzClaudeError, Result};
+
+/// Length of the abbreviated commit ids shown.
+const SHORT_ID_LEN: usize = 7;
+
+/// A run of lines last changed by the same commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameHunk {
+    /// The first line of the hunk, starting at 1.
+    pub start_line: usize,
+    /// The number of lines of the hunk.
+    pub lines: usize,
+    /// The abbreviated id of the commit, None for uncommitted lines.
+    pub commit: Option<String>,
+    /// The author of the commit.
+    pub author: String,
+    /// When the commit was authored, None for uncommitted lines.
+    pub time: Option<SystemTime>,
+    /// The first line of the commit message.
+    pub summary: String,
+}
+
+/// A contributor of a file and the number of lines they last changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contributor {
+    /// The author name.
+    pub name: String,
+    /// The number of lines last changed by them.
+    pub lines: usize,
+}
+
+/// The blame of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blame {
+    /// The path of the file, relative to the repository.
+    pub path: PathBuf,
+    /// The contributors, most lines first.
+    pub contributors: Vec<Contributor>,
+    /// The hunks, in file order.
+    pub hunks: Vec<BlameHunk>,
+}
+
+impl Blame {
+    /// Returns the number of lines of the file.
+    pub fn line_count(&self) -> usize {
+        self.hunks.iter().map(|hunk| hunk.lines).sum()
+    }
+}
+
+/// Blames a file as it is on disk.
+///
+/// # Arguments
+///
+/// * `file_path` - The file, inside a repository
+///
+/// # Errors
+///
+/// Returns `GzClaudeError::Git` if the file is not in a repository, is not
+/// tracked or git fails to blame it, and an I/O error if it can't be read.
+pub fn blame_file(file_path: &Path) -> Result<Blame> {
+    let git_error = |e: git2::Error| GzClaudeError::Git(e.message().to_string());
+
+    let dir = file_path.parent().unwrap_or(Path::new("."));
+    let repo = Repository::discover(dir).map_err(git_error)?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| GzClaudeError::Git("The repository has no working tree".to_string()))?;
+    // The working tree is canonical, so the file path is too
+    let file_path = file_path.canonicalize()?;
+    let relative = file_path
+        .strip_prefix(workdir.canonicalize()?)
+        .map_err(|_| GzClaudeError::Git("The file is outside the repository".to_string()))?
+        .to_path_buf();
+    let content = fs::read(&file_path)?;
+
+    let committed = repo.blame_file(&relative, None).map_err(git_error)?;
+    let blame = committed.blame_buffer(&content).map_err(git_error)?;
+
+    let mut hunks = Vec::new();
+    for hunk in blame.iter() {
+        let id = hunk.final_commit_id();
+        let commit = if id.is_zero() {
+            None
+        } else {
+            repo.find_commit(id).ok()
+        };
+        let hunk = match commit {
+            Some(commit) => {
+                let signature = hunk.final_signature();
+                BlameHunk {
+                    start_line: hunk.final_start_line(),
+                    lines: hunk.lines_in_hunk(),
+                    commit: Some(short_id(&id.to_string())),
+                    author: signature.name().unwrap_or("unknown").to_string(),
+                    time: Some(commit_time(signature.when().seconds())),
+                    summary: commit.summary().unwrap_or_default().to_string(),
+                }
+            }
+            None => BlameHunk {
+                start_line: hunk.final_start_line(),
+                lines: hunk.lines_in_hunk(),
+                commit: None,
+                author: "Not committed yet".to_string(),
+                time: None,
+                summary: String::new(),
+            },
+        };
+        hunks.push(hunk);
+    }
+
+    Ok(Blame {
+        path: relative,
+        contributors: contributors(&hunks),
+        hunks,
+    })
+}
+
+/// Returns the contributors of hunks, most lines first, then by name.
+fn contributors(hunks: &[BlameHunk]) -> Vec<Contributor> {
+    let mut lines: HashMap<&str, usize> = HashMap::new();
+    for hunk in hunks {
+        *lines.entry(hunk.author.as_str()).or_default() += hunk.lines;
+    }
+    let mut contributors: Vec<Contributor> = lines
+        .into_iter()
+        .map(|(name, lines)| Contributor {
+            name: name.to_string(),
+            lines,
+        })
+        .collect();
+    contributors.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.name.cmp(&b.name)));
+    contributors
+}
+
+/// Returns the abbreviated form of a commit id.
+fn short_id(id: &str) -> String {
+    id.chars().take(SHORT_ID_LEN).collect()
+}
+
+/// Converts a commit time, in seconds since the epoch, to a system time.
+fn commit_time(seconds: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(seconds.max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn commit_file(repo: &Repository, name: &str, content: &str, author: &str, message: &str) {
+        let workdir = repo.workdir().unwrap();
+        fs::write(workdir.join(name), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now(author, "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn when_blaming_file_should_summarize_hunks_and_contributors() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "main.rs", "a\nb\nc\n", "Ana", "Start");
+        commit_file(
+            &repo,
+            "main.rs",
+            "a\nB\nc\nd\n",
+            "Bo",
+            "Change b\n\nDetails",
+        );
+        fs::write(dir.path().join("main.rs"), "a\nB\nc\nd\ne\n").unwrap();
+
+        let blame = blame_file(&dir.path().join("main.rs")).unwrap();
+
+        assert_eq!(blame.path, PathBuf::from("main.rs"));
+        assert_eq!(blame.line_count(), 5);
+        let authors: Vec<(&str, usize, usize)> = blame
+            .hunks
+            .iter()
+            .map(|h| (h.author.as_str(), h.start_line, h.lines))
+            .collect();
+        assert_eq!(
+            authors,
+            vec![
+                ("Ana", 1, 1),
+                ("Bo", 2, 1),
+                ("Ana", 3, 1),
+                ("Bo", 4, 1),
+                ("Not committed yet", 5, 1)
+            ]
+        );
+        assert_eq!(blame.hunks[1].summary, "Change b");
+        assert_eq!(blame.hunks[1].commit.as_ref().map(String::len), Some(7));
+        assert!(blame.hunks[4].commit.is_none() && blame.hunks[4].time.is_none());
+        assert_eq!(
+            blame.contributors[0],
+            Contributor {
+                name: "Ana".to_string(),
+                lines: 2
+            }
+        );
+    }
+
+    #[test]
+    fn when_file_is_not_tracked_should_fail() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "main.rs", "a\n", "Ana", "Start");
+        fs::write(dir.path().join("notes.txt"), "draft\n").unwrap();
+
+        assert!(matches!(
+            blame_file(&dir.path().join("notes.txt")),
+            Err(GzClaudeError::Git(_))
+        ));
+    }
+}
@@ -0,0 +1,192 @@
+//! Cache of Git information per project.
+//!
+//! Git information is computed on first access and reused until the project's
+//! Git metadata changes (reported by the watcher) or the cache is invalidated.
+//! Edits in the working tree don't touch the metadata, so a watching cache
+//! also recomputes entries once they're older than `STATUS_POLL_INTERVAL`.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::watcher::GitWatcher;
+use super::{get_git_info_compared, GitCompare, GitInfo};
+use crate::config::GitInfoLevel;
+
+/// How old an entry of a watching cache gets before it's recomputed, so
+/// files edited (but not staged) in the working tree show up as changes.
+pub const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Cached Git information keyed by project path.
+pub struct GitCache {
+    level: GitInfoLevel,
+    /// The information of each project, with when it was computed.
+    entries: HashMap<PathBuf, (Option<GitInfo>, Instant)>,
+    watcher: Option<GitWatcher>,
+}
+
+impl GitCache {
+    /// Creates a cache without filesystem watching.
+    ///
+    /// Entries are only refreshed through explicit invalidation.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The detail level used when computing Git information
+    pub fn new(level: GitInfoLevel) -> Self {
+        Self {
+            level,
+            entries: HashMap::new(),
+            watcher: None,
+        }
+    }
+
+    /// Creates a cache that watches each cached project's `.git` directory.
+    ///
+    /// Falls back to a non-watching cache if the watcher cannot be created.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The detail level used when computing Git information
+    pub fn with_watcher(level: GitInfoLevel) -> Self {
+        Self {
+            watcher: GitWatcher::new().ok(),
+            ..Self::new(level)
+        }
+    }
+
+    /// Returns the Git information for a project, computing it on a cache miss.
+    ///
+    /// Projects are registered with the watcher the first time they're accessed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The project directory
+    ///
+    /// # Returns
+    ///
+    /// The Git information, or None if the path is not a Git repository.
+    pub fn get(&mut self, path: &Path) -> Option<GitInfo> {
+        self.get_compared(path, &GitCompare::default())
+    }
+
+    /// Returns the Git information for a project compared to a base branch
+    /// and a second remote.
+    ///
+    /// What it's compared to is only used when the entry is computed; it's
+    /// expected to stay the same for a project while the cache lives.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The project directory
+    /// * `compare` - What the current branch is compared to
+    ///
+    /// # Returns
+    ///
+    /// The Git information, or None if the path is not a Git repository.
+    pub fn get_compared(&mut self, path: &Path, compare: &GitCompare) -> Option<GitInfo> {
+        if let Some((info, _)) = self.entries.get(path) {
+            return info.clone();
+        }
+
+        let info = get_git_info_compared(path, self.level, compare);
+        self.entries
+            .insert(path.to_path_buf(), (info.clone(), Instant::now()));
+
+        if let Some(watcher) = self.watcher.as_mut() {
+            watcher.watch(path);
+        }
+
+        info
+    }
+
+    /// Returns the cached Git information for a project without computing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The project directory
+    ///
+    /// # Returns
+    ///
+    /// The cached information, or None on a cache miss or if the path is not
+    /// a Git repository.
+    pub fn peek(&self, path: &Path) -> Option<GitInfo> {
+        self.entries.get(path).and_then(|(info, _)| info.clone())
+    }
+
+    /// Stores Git information computed elsewhere, e.g. on a background thread.
+    ///
+    /// The project is registered with the watcher like on a cache miss.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The project directory
+    /// * `info` - The Git information, None if the path is not a Git repository
+    pub fn insert(&mut self, path: PathBuf, info: Option<GitInfo>) {
+        if let Some(watcher) = self.watcher.as_mut() {
+            watcher.watch(&path);
+        }
+        self.entries.insert(path, (info, Instant::now()));
+    }
+
+    /// Returns whether a project has a cached entry.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    /// Returns the number of cached entries.
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns how long ago the oldest cached entry was computed, None if
+    /// the cache is empty.
+    pub fn oldest_entry_age(&self) -> Option<Duration> {
+        self.entries
+            .values()
+            .map(|(_, computed)| computed.elapsed())
+            .max()
+    }
+
+    /// Drops the cached entry for a project so it's recomputed on next access.
+    pub fn invalidate(&mut self, path: &Path) {
+        self.entries.remove(path);
+    }
+
+    /// Drops all cached entries.
+    pub fn invalidate_all(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Drops the entries computed at least `max_age` ago.
+    ///
+    /// # Returns
+    ///
+    /// True if at least one cached entry was dropped.
+    pub fn expire(&mut self, max_age: Duration) -> bool {
+        let count = self.entries.len();
+        self.entries
+            .retain(|_, (_, computed)| computed.elapsed() < max_age);
+        self.entries.len() != count
+    }
+
+    /// Invalidates entries for projects whose Git metadata changed, and the
+    /// ones older than `STATUS_POLL_INTERVAL`.
+    ///
+    /// # Returns
+    ///
+    /// True if at least one cached entry was invalidated.
+    pub fn process_watch_events(&mut self) -> bool {
+        let Some(watcher) = self.watcher.as_ref() else {
+            return false;
+        };
+
+        let mut changed = false;
+        for path in watcher.drain_changes() {
+            changed |= self.entries.remove(&path).is_some();
+        }
+        changed | self.expire(STATUS_POLL_INTERVAL)
+    }
+}
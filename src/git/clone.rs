@@ -0,0 +1,306 @@
+//! Cloning repositories of projects whose directory doesn't exist.
+//!
+//! Clones run `git clone --progress`, so they use the same credentials
+//! (SSH agent, credential helpers) as the user's shell, and report progress
+//! parsed from git's stderr.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::error::{GzClaudeError, Result};
+use crate::util::glyphs::glyph;
+
+/// Progress of a clone, as last reported by git.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CloneProgress {
+    /// The current phase (e.g. "Receiving objects").
+    pub phase: String,
+    /// Completion of the phase, if reported.
+    pub percent: Option<u8>,
+}
+
+impl CloneProgress {
+    /// Returns a short label such as "Receiving objects 45%".
+    pub fn label(&self) -> String {
+        match self.percent {
+            Some(percent) => format!("{} {}%", self.phase, percent),
+            None => self.phase.clone(),
+        }
+    }
+}
+
+/// The clone status of a project displayed in the Projects view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloneStatus {
+    /// The project directory doesn't exist and the repository can be cloned.
+    NotCloned,
+    /// The repository is being cloned.
+    Cloning(CloneProgress),
+}
+
+impl CloneStatus {
+    /// Returns the badge displayed next to the project name.
+    pub fn badge(&self) -> String {
+        match self {
+            CloneStatus::NotCloned => format!("{} not cloned", glyph("⇣", "v")),
+            CloneStatus::Cloning(progress) if progress.phase.is_empty() => {
+                format!("{} cloning", glyph("⇣", "v"))
+            }
+            CloneStatus::Cloning(progress) => format!("{} {}", glyph("⇣", "v"), progress.label()),
+        }
+    }
+}
+
+/// Parses a progress line printed by `git clone --progress`.
+///
+/// # Arguments
+///
+/// * `line` - A line of git's stderr, e.g. "Receiving objects:  45% (45/100)"
+///
+/// # Returns
+///
+/// The phase and percentage, or None if the line doesn't report progress.
+pub fn parse_progress(line: &str) -> Option<CloneProgress> {
+    let line = line.trim().trim_start_matches("remote:").trim();
+    let (phase, rest) = line.split_once(':')?;
+    let phase = phase.trim();
+    if phase.is_empty() || phase.contains('\'') {
+        return None;
+    }
+    let percent = rest
+        .split_whitespace()
+        .next()
+        .and_then(|word| word.strip_suffix('%'))
+        .and_then(|number| number.parse::<u8>().ok())?;
+    Some(CloneProgress {
+        phase: phase.to_string(),
+        percent: Some(percent.min(100)),
+    })
+}
+
+/// Clones a repository into a directory, reporting progress as it goes.
+///
+/// # Arguments
+///
+/// * `repo` - The repository URL
+/// * `path` - The directory to clone into; its parent is created if needed
+/// * `on_progress` - Called with each progress update reported by git
+///
+/// # Errors
+///
+/// Returns `GzClaudeError::Git` if the directory already exists, git cannot
+/// be started or the clone fails; the message includes git's last error line.
+pub fn clone_repo(
+    repo: &str,
+    path: &Path,
+    mut on_progress: impl FnMut(&CloneProgress),
+) -> Result<()> {
+    if path.exists() {
+        return Err(GzClaudeError::Git(format!(
+            "{} already exists",
+            path.display()
+        )));
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut child = Command::new("git")
+        .args(["clone", "--progress", "--", repo])
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| GzClaudeError::Git(format!("Failed to run git clone: {}", e)))?;
+
+    // git rewrites progress lines with '\r', so split on both line endings
+    let mut last_message = String::new();
+    if let Some(stderr) = child.stderr.take() {
+        let mut line = Vec::new();
+        for byte in BufReader::new(stderr).bytes() {
+            let Ok(byte) = byte else { break };
+            if byte != b'\r' && byte != b'\n' {
+                line.push(byte);
+                continue;
+            }
+            let text = String::from_utf8_lossy(&line).trim().to_string();
+            line.clear();
+            if text.is_empty() {
+                continue;
+            }
+            match parse_progress(&text) {
+                Some(progress) => on_progress(&progress),
+                None => last_message = text,
+            }
+        }
+    }
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(GzClaudeError::Git(if last_message.is_empty() {
+            format!("git clone of {} exited with {}", repo, status)
+        } else {
+            format!("git clone of {} failed: {}", repo, last_message)
+        }));
+    }
+    Ok(())
+}
+
+/// A clone running on a background thread.
+pub struct CloneJob {
+    progress: Arc<Mutex<CloneProgress>>,
+    handle: Option<JoinHandle<Result<()>>>,
+}
+
+impl CloneJob {
+    /// Starts cloning a repository into a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `repo` - The repository URL
+    /// * `path` - The directory to clone into
+    pub fn spawn(repo: String, path: PathBuf) -> Self {
+        let progress = Arc::new(Mutex::new(CloneProgress::default()));
+        let shared = Arc::clone(&progress);
+        let handle = thread::spawn(move || {
+            clone_repo(&repo, &path, |update| {
+                if let Ok(mut progress) = shared.lock() {
+                    *progress = update.clone();
+                }
+            })
+        });
+        Self {
+            progress,
+            handle: Some(handle),
+        }
+    }
+
+    /// Returns the latest progress reported by git.
+    pub fn progress(&self) -> CloneProgress {
+        self.progress.lock().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    /// Takes the outcome of the clone once it finished.
+    ///
+    /// # Returns
+    ///
+    /// The outcome the first time it's called after the clone finished,
+    /// None otherwise.
+    pub fn poll(&mut self) -> Option<Result<()>> {
+        if !self.handle.as_ref()?.is_finished() {
+            return None;
+        }
+        Some(
+            self.handle
+                .take()?
+                .join()
+                .unwrap_or_else(|_| Err(GzClaudeError::Git("git clone crashed".to_string()))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn create_repo(dir: &Path) -> PathBuf {
+        let path = dir.join("origin");
+        let repo = git2::Repository::init(&path).unwrap();
+        std::fs::write(path.join("README.md"), "hello").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn when_parsing_receiving_objects_should_return_percent() {
+        let progress =
+            parse_progress("Receiving objects:  45% (45/100), 1.2 MiB | 2 MiB/s").unwrap();
+
+        assert_eq!(progress.phase, "Receiving objects");
+        assert_eq!(progress.percent, Some(45));
+        assert_eq!(progress.label(), "Receiving objects 45%");
+    }
+
+    #[test]
+    fn when_parsing_remote_progress_should_strip_prefix() {
+        let progress = parse_progress("remote: Counting objects: 100% (12/12), done.").unwrap();
+
+        assert_eq!(progress.phase, "Counting objects");
+        assert_eq!(progress.percent, Some(100));
+    }
+
+    #[test]
+    fn when_parsing_non_progress_line_should_return_none() {
+        assert!(parse_progress("Cloning into 'project'...").is_none());
+        assert!(parse_progress("fatal: repository 'x' does not exist").is_none());
+        assert!(parse_progress("remote: Enumerating objects: 12, done.").is_none());
+    }
+
+    #[test]
+    fn when_cloning_should_badge_progress() {
+        let status = CloneStatus::Cloning(CloneProgress {
+            phase: "Resolving deltas".to_string(),
+            percent: Some(7),
+        });
+
+        assert_eq!(status.badge(), "⇣ Resolving deltas 7%");
+        assert_eq!(CloneStatus::NotCloned.badge(), "⇣ not cloned");
+    }
+
+    #[test]
+    fn when_cloning_local_repo_should_create_directory() {
+        let dir = TempDir::new().unwrap();
+        let origin = create_repo(dir.path());
+        let target = dir.path().join("nested").join("clone");
+
+        clone_repo(&origin.to_string_lossy(), &target, |_| {}).unwrap();
+
+        assert!(target.join("README.md").exists());
+    }
+
+    #[test]
+    fn when_target_exists_should_fail() {
+        let dir = TempDir::new().unwrap();
+        let origin = create_repo(dir.path());
+
+        let result = clone_repo(&origin.to_string_lossy(), dir.path(), |_| {});
+
+        assert!(matches!(result, Err(GzClaudeError::Git(_))));
+    }
+
+    #[test]
+    fn when_repo_is_missing_should_report_git_error() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("clone");
+
+        let mut job = CloneJob::spawn(
+            dir.path().join("missing").to_string_lossy().to_string(),
+            target.clone(),
+        );
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let result = loop {
+            if let Some(result) = job.poll() {
+                break result;
+            }
+            assert!(Instant::now() < deadline, "clone did not finish");
+            thread::sleep(Duration::from_millis(10));
+        };
+
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("failed"), "{}", message);
+        assert!(!target.exists());
+    }
+}
@@ -0,0 +1,363 @@
+//! Fetching project remotes in the background.
+//!
+//! Ahead/behind counts are computed against the remote-tracking branches,
+//! which are only as fresh as the last fetch. Fetches are triggered per
+//! project (`F`) or periodically with `git.auto_fetch_minutes`, and run with
+//! git2 using the SSH agent, the default SSH keys and git's credential
+//! helpers for authentication.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use git2::{Cred, CredentialType, FetchOptions, RemoteCallbacks, Repository};
+
+use crate::error::{GzClaudeError, Result};
+
+/// Credentials offered before giving up, so a rejected credential doesn't
+/// make libgit2 ask forever.
+const MAX_CREDENTIAL_ATTEMPTS: usize = 5;
+
+/// SSH keys tried, in order, after the SSH agent.
+const DEFAULT_SSH_KEYS: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// Returns when the repository was last fetched.
+///
+/// Git rewrites `FETCH_HEAD` on every fetch, including the ones run from a
+/// shell, so its modification time is used.
+///
+/// # Returns
+///
+/// The time of the last fetch, or None if the repository was never fetched.
+pub fn last_fetch(repo: &Repository) -> Option<SystemTime> {
+    std::fs::metadata(repo.path().join("FETCH_HEAD"))
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+/// Fetches the remote of the current branch's upstream, or `origin`.
+///
+/// # Arguments
+///
+/// * `path` - The repository directory
+///
+/// # Returns
+///
+/// The name of the fetched remote.
+///
+/// # Errors
+///
+/// Returns `GzClaudeError::Git` if the path is not a repository, has no
+/// remote to fetch, or the fetch fails (e.g. authentication was rejected).
+pub fn fetch(path: &Path) -> Result<String> {
+    let repo = Repository::open(path).map_err(|e| GzClaudeError::Git(e.message().to_string()))?;
+    let remote_name = upstream_remote(&repo).unwrap_or_else(|| "origin".to_string());
+    let mut remote = repo.find_remote(&remote_name).map_err(|_| {
+        GzClaudeError::Git(format!(
+            "{} has no remote '{}' to fetch",
+            path.display(),
+            remote_name
+        ))
+    })?;
+
+    let config = repo.config().ok();
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        attempts += 1;
+        credentials(config.as_ref(), url, username, allowed, attempts)
+    });
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks);
+
+    // No refspecs: the remote's configured ones are used, like `git fetch`
+    remote
+        .fetch(&[] as &[&str], Some(&mut options), None)
+        .map_err(|e| {
+            GzClaudeError::Git(format!("git fetch {} failed: {}", remote_name, e.message()))
+        })?;
+    Ok(remote_name)
+}
+
+/// Returns the remote of the current branch's upstream, if it has one.
+fn upstream_remote(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    let refname = head.name()?;
+    let remote = repo.branch_upstream_remote(refname).ok()?;
+    remote.as_str().map(String::from)
+}
+
+/// Answers a credentials request from libgit2.
+///
+/// SSH tries the agent first, then the default keys in `~/.ssh`; HTTPS
+/// goes through git's credential helpers.
+fn credentials(
+    config: Option<&git2::Config>,
+    url: &str,
+    username: Option<&str>,
+    allowed: CredentialType,
+    attempt: usize,
+) -> std::result::Result<Cred, git2::Error> {
+    if attempt > MAX_CREDENTIAL_ATTEMPTS {
+        return Err(git2::Error::from_str("authentication failed"));
+    }
+    let user = username.unwrap_or("git");
+
+    if allowed.contains(CredentialType::USERNAME) {
+        return Cred::username(user);
+    }
+    if allowed.contains(CredentialType::SSH_KEY) {
+        if attempt == 1 {
+            return Cred::ssh_key_from_agent(user);
+        }
+        let ssh_dir = dirs::home_dir().map(|home| home.join(".ssh"));
+        let key = ssh_dir.and_then(|dir| {
+            DEFAULT_SSH_KEYS
+                .iter()
+                .map(|name| dir.join(name))
+                .filter(|key| key.exists())
+                .nth(attempt - 2)
+        });
+        if let Some(key) = key {
+            return Cred::ssh_key(user, None, &key, None);
+        }
+    }
+    if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+        if let Some(config) = config {
+            return Cred::credential_helper(config, url, username);
+        }
+    }
+    if allowed.contains(CredentialType::DEFAULT) {
+        return Cred::default();
+    }
+    Err(git2::Error::from_str("no usable credentials"))
+}
+
+/// The outcome of a finished fetch.
+#[derive(Debug)]
+pub struct FinishedFetch {
+    /// The project directory.
+    pub path: PathBuf,
+    /// Whether the user asked for the fetch, as opposed to a periodic one.
+    pub requested: bool,
+    /// The fetched remote, or why the fetch failed.
+    pub result: Result<String>,
+}
+
+/// Runs fetches on background threads and schedules the periodic ones.
+#[derive(Debug, Default)]
+pub struct FetchMonitor {
+    running: HashMap<PathBuf, (bool, JoinHandle<Result<String>>)>,
+    last_started: HashMap<PathBuf, Instant>,
+}
+
+impl FetchMonitor {
+    /// Creates a monitor with no fetches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether a project is being fetched.
+    pub fn is_fetching(&self, path: &Path) -> bool {
+        self.running.contains_key(path)
+    }
+
+    /// Starts fetching a project at the user's request.
+    ///
+    /// # Returns
+    ///
+    /// False if the project is already being fetched.
+    pub fn request(&mut self, path: &Path) -> bool {
+        if self.is_fetching(path) {
+            return false;
+        }
+        self.start(path, true);
+        true
+    }
+
+    /// Collects finished fetches and starts the periodic ones that are due.
+    ///
+    /// A project is due when neither gz-claude nor anything else fetched it
+    /// within its interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `periodic` - The project directories fetched periodically, with
+    ///   their interval
+    ///
+    /// # Returns
+    ///
+    /// The fetches that finished since the last poll.
+    pub fn poll<'a>(
+        &mut self,
+        periodic: impl IntoIterator<Item = (&'a Path, Duration)>,
+    ) -> Vec<FinishedFetch> {
+        let finished = self.collect_finished();
+
+        let now = Instant::now();
+        for (path, interval) in periodic {
+            if self.is_fetching(path) {
+                continue;
+            }
+            let started_recently = self
+                .last_started
+                .get(path)
+                .is_some_and(|started| now.duration_since(*started) < interval);
+            let fetched_recently = Repository::open(path)
+                .ok()
+                .and_then(|repo| last_fetch(&repo))
+                .and_then(|time| SystemTime::now().duration_since(time).ok())
+                .is_some_and(|age| age < interval);
+            if !started_recently && !fetched_recently {
+                self.start(path, false);
+            }
+        }
+
+        finished
+    }
+
+    fn start(&mut self, path: &Path, requested: bool) {
+        let target = path.to_path_buf();
+        let handle = thread::spawn(move || fetch(&target));
+        self.last_started.insert(path.to_path_buf(), Instant::now());
+        self.running.insert(path.to_path_buf(), (requested, handle));
+    }
+
+    fn collect_finished(&mut self) -> Vec<FinishedFetch> {
+        let done: Vec<PathBuf> = self
+            .running
+            .iter()
+            .filter(|(_, (_, handle))| handle.is_finished())
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        done.into_iter()
+            .filter_map(|path| {
+                let (requested, handle) = self.running.remove(&path)?;
+                let result = handle
+                    .join()
+                    .unwrap_or_else(|_| Err(GzClaudeError::Git("git fetch crashed".to_string())));
+                Some(FinishedFetch {
+                    path,
+                    requested,
+                    result,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn commit(repo: &Repository, file: &str) {
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join(file), file).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, file, &tree, &parents)
+            .unwrap();
+    }
+
+    /// Creates an origin repository with one commit and a clone of it.
+    fn origin_and_clone(dir: &Path) -> (Repository, PathBuf) {
+        let origin = Repository::init(dir.join("origin")).unwrap();
+        commit(&origin, "README.md");
+        let clone = dir.join("clone");
+        Repository::clone(&dir.join("origin").to_string_lossy(), &clone).unwrap();
+        (origin, clone)
+    }
+
+    fn wait_for(monitor: &mut FetchMonitor) -> FinishedFetch {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            if let Some(finished) = monitor.poll([]).pop() {
+                return finished;
+            }
+            assert!(Instant::now() < deadline, "fetch did not finish");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn when_origin_has_new_commits_should_fetch_them() {
+        let dir = TempDir::new().unwrap();
+        let (origin, clone) = origin_and_clone(dir.path());
+        commit(&origin, "CHANGELOG.md");
+
+        assert_eq!(fetch(&clone).unwrap(), "origin");
+
+        let repo = Repository::open(&clone).unwrap();
+        let local = repo.head().unwrap().target().unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let remote = repo
+            .find_branch(&format!("origin/{}", branch), git2::BranchType::Remote)
+            .unwrap()
+            .get()
+            .target()
+            .unwrap();
+        assert_eq!(repo.graph_ahead_behind(local, remote).unwrap(), (0, 1));
+        assert!(last_fetch(&repo).is_some());
+    }
+
+    #[test]
+    fn when_repo_has_no_remote_should_fail() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit(&repo, "README.md");
+
+        let message = fetch(dir.path()).unwrap_err().to_string();
+
+        assert!(message.contains("no remote 'origin'"), "{}", message);
+    }
+
+    #[test]
+    fn when_fetch_is_requested_should_report_it_once_finished() {
+        let dir = TempDir::new().unwrap();
+        let (_origin, clone) = origin_and_clone(dir.path());
+        let mut monitor = FetchMonitor::new();
+
+        assert!(monitor.request(&clone));
+        assert!(!monitor.request(&clone));
+
+        let finished = wait_for(&mut monitor);
+        assert!(finished.requested);
+        assert_eq!(finished.path, clone);
+        assert!(finished.result.is_ok());
+        assert!(!monitor.is_fetching(&clone));
+    }
+
+    #[test]
+    fn when_polling_periodic_fetches_should_skip_recently_fetched() {
+        let dir = TempDir::new().unwrap();
+        let (_origin, clone) = origin_and_clone(dir.path());
+        let mut monitor = FetchMonitor::new();
+        let hour = Duration::from_secs(3600);
+
+        // Cloning counts as a fetch, like a `git fetch` run from a shell
+        let fetch_head = clone.join(".git").join("FETCH_HEAD");
+        assert!(fetch_head.exists());
+        monitor.poll([(clone.as_path(), hour)]);
+        assert!(!monitor.is_fetching(&clone));
+
+        std::fs::remove_file(&fetch_head).unwrap();
+        monitor.poll([(clone.as_path(), hour)]);
+        assert!(monitor.is_fetching(&clone));
+        let finished = wait_for(&mut monitor);
+        assert!(!finished.requested);
+
+        // Started within the interval
+        std::fs::remove_file(&fetch_head).unwrap();
+        monitor.poll([(clone.as_path(), hour)]);
+        assert!(!monitor.is_fetching(&clone));
+    }
+}
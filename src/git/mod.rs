@@ -5,56 +5,313 @@
 #![allow(dead_code)]
 
 use std::path::Path;
+use std::time::SystemTime;
 
-use git2::{Repository, Status, StatusOptions};
+use git2::{Repository, RepositoryState};
 
-use crate::config::GitInfoLevel;
+use crate::config::{Config, GitInfoLevel, Project};
+use crate::util::format_age;
+use crate::util::glyphs::glyph;
+
+pub mod blame;
+mod cache;
+pub mod clone;
+pub mod fetch;
+pub mod refresh;
+mod status;
+pub mod task;
+mod watcher;
+
+pub use cache::GitCache;
+pub use status::StatusLimits;
 
 /// Information about a Git repository.
 #[derive(Debug, Clone, Default)]
 pub struct GitInfo {
     /// Current branch name (None if detached HEAD).
     pub branch: Option<String>,
+    /// Short hash of the checked-out commit when HEAD is detached.
+    pub detached: Option<String>,
+    /// Tag pointing at the detached HEAD, if any.
+    pub tag: Option<String>,
     /// Whether there are uncommitted changes.
     pub is_dirty: bool,
+    /// Number of files with unresolved merge conflicts.
+    pub conflicted_count: u32,
+    /// The merge, rebase or other operation in progress, if any.
+    pub operation: Option<GitOperation>,
     /// Number of commits ahead of upstream.
     pub ahead: u32,
     /// Number of commits behind upstream.
     pub behind: u32,
+    /// Whether the current branch has an upstream branch.
+    pub has_upstream: bool,
+    /// When the remote-tracking branches were last fetched, if ever.
+    pub last_fetch: Option<SystemTime>,
     /// Number of staged files.
     pub staged_count: u32,
     /// Number of unstaged modified files.
     pub unstaged_count: u32,
     /// List of modified files (only populated for detailed level).
     pub modified_files: Vec<String>,
+    /// Base branch the current branch is compared to, if configured and found.
+    pub base_branch: Option<String>,
+    /// Number of commits ahead of the base branch.
+    pub base_ahead: u32,
+    /// Number of commits behind the base branch.
+    pub base_behind: u32,
+    /// Branch of the second remote the current branch is compared to, if
+    /// configured and found (e.g. "upstream/main").
+    pub compare_ref: Option<String>,
+    /// Number of commits ahead of the second remote's branch.
+    pub remote_ahead: u32,
+    /// Number of commits behind the second remote's branch.
+    pub remote_behind: u32,
+    /// Whether the status of the files was left out, the repository
+    /// exceeding `status_timeout_ms` or `max_status_entries`.
+    pub large_repo: bool,
+}
+
+/// An operation left in progress in a repository, e.g. a merge stopped on
+/// conflicts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitOperation {
+    /// A merge.
+    Merge,
+    /// A rebase.
+    Rebase,
+    /// A cherry-pick.
+    CherryPick,
+    /// A revert.
+    Revert,
+    /// A bisect.
+    Bisect,
+    /// Patches applied with `git am`.
+    Apply,
+}
+
+impl GitOperation {
+    /// Returns the operation a repository state stands for, None when the
+    /// repository is clean.
+    pub fn from_state(state: RepositoryState) -> Option<Self> {
+        match state {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some(Self::Merge),
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge
+            | RepositoryState::ApplyMailboxOrRebase => Some(Self::Rebase),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                Some(Self::CherryPick)
+            }
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some(Self::Revert),
+            RepositoryState::Bisect => Some(Self::Bisect),
+            RepositoryState::ApplyMailbox => Some(Self::Apply),
+        }
+    }
+
+    /// Returns the label of the operation, e.g. "MERGING".
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Merge => "MERGING",
+            Self::Rebase => "REBASING",
+            Self::CherryPick => "CHERRY-PICKING",
+            Self::Revert => "REVERTING",
+            Self::Bisect => "BISECTING",
+            Self::Apply => "APPLYING",
+        }
+    }
+}
+
+/// What a repository is compared to, besides its upstream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitCompare {
+    /// The branch to compare the current branch to, if any.
+    pub base_branch: Option<String>,
+    /// The second remote to compare the current branch to, if any.
+    pub compare_remote: Option<String>,
+    /// The limits of the status of the files.
+    pub limits: StatusLimits,
+}
+
+impl GitCompare {
+    /// Resolves what a project is compared to from the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Reference to the application configuration
+    /// * `project` - The project
+    pub fn resolve(config: &Config, project: &Project) -> Self {
+        Self {
+            base_branch: config.resolve_base_branch(project).map(String::from),
+            compare_remote: config.resolve_compare_remote(project).map(String::from),
+            limits: config.resolve_status_limits(project),
+        }
+    }
+
+    /// Compares to a base branch only.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_branch` - The branch to compare the current branch to, if any
+    pub fn with_base(base_branch: Option<&str>) -> Self {
+        Self {
+            base_branch: base_branch.map(String::from),
+            ..Default::default()
+        }
+    }
+}
+
+/// How much attention a repository needs, from its git status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GitSeverity {
+    /// Nothing to commit, not far behind.
+    Clean,
+    /// Uncommitted changes.
+    Dirty,
+    /// Merge conflicts, or far behind upstream.
+    Risky,
 }
 
 impl GitInfo {
-    /// Format as minimal string: "main *" or "main".
+    /// Returns how much attention the repository needs.
+    ///
+    /// # Arguments
+    ///
+    /// * `behind_warn` - Commits behind upstream tolerated before the
+    ///   repository counts as risky
+    pub fn severity(&self, behind_warn: u32) -> GitSeverity {
+        if self.conflicted_count > 0 || self.operation.is_some() || self.behind > behind_warn {
+            GitSeverity::Risky
+        } else if self.is_dirty {
+            GitSeverity::Dirty
+        } else {
+            GitSeverity::Clean
+        }
+    }
+
+    /// Returns the badge of an operation in progress or of conflicts, e.g.
+    /// "MERGING (3 conflicts)", "REBASING" or "1 conflict"; None when
+    /// there are neither.
+    pub fn operation_badge(&self) -> Option<String> {
+        let conflicts = match self.conflicted_count {
+            0 => None,
+            1 => Some("1 conflict".to_string()),
+            n => Some(format!("{} conflicts", n)),
+        };
+        match (self.operation, conflicts) {
+            (Some(operation), Some(conflicts)) => {
+                Some(format!("{} ({})", operation.label(), conflicts))
+            }
+            (Some(operation), None) => Some(operation.label().to_string()),
+            (None, conflicts) => conflicts,
+        }
+    }
+
+    /// Returns what is checked out: the branch, "detached a1b2c3d" or
+    /// "v1.2.0 (detached a1b2c3d)" for a detached HEAD, or "HEAD" when
+    /// nothing is committed yet.
+    pub fn head_label(&self) -> String {
+        match (&self.branch, &self.detached, &self.tag) {
+            (Some(branch), _, _) => branch.clone(),
+            (None, Some(commit), Some(tag)) => format!("{} (detached {})", tag, commit),
+            (None, Some(commit), None) => format!("detached {}", commit),
+            (None, None, _) => "HEAD".to_string(),
+        }
+    }
+
+    /// Format as minimal string: "main *", "main", or "main (large repo)"
+    /// when the status of the files was left out.
     pub fn format_minimal(&self) -> String {
-        let branch = self.branch.as_deref().unwrap_or("HEAD");
-        if self.is_dirty {
+        let branch = self.head_label();
+        if self.large_repo {
+            format!("{} (large repo)", branch)
+        } else if self.is_dirty {
             format!("{} *", branch)
         } else {
-            branch.to_string()
+            branch
+        }
+    }
+
+    /// Format as a single glyph for narrow panels: ● with uncommitted
+    /// changes, otherwise ↓ when behind upstream or ↑ when ahead of it, and
+    /// nothing when clean and up to date; ◌ when the status of the files was
+    /// left out.
+    pub fn format_glyph(&self) -> &'static str {
+        if self.large_repo {
+            glyph("◌", "~")
+        } else if self.is_dirty {
+            glyph("●", "*")
+        } else if self.behind > 0 {
+            glyph("↓", "v")
+        } else if self.ahead > 0 {
+            glyph("↑", "^")
+        } else {
+            ""
         }
     }
 
-    /// Format as standard string: "main * | +2 -1 | 3S 2U".
+    /// Format as standard string:
+    /// "feature * | +2 -1 fetched 3h ago | ↑0 ↓4 vs upstream/feature | ↑2 ↓5 vs main | 3S 2U".
+    ///
+    /// With an upstream, the counts are always shown along with the age of
+    /// the last fetch, so "+0 -0" isn't mistaken for being up to date; so are
+    /// the counts against a second remote. A detached HEAD has no upstream,
+    /// so its counts against the base branch are always shown instead.
     pub fn format_standard(&self) -> String {
-        let branch = self.branch.as_deref().unwrap_or("HEAD");
-        let dirty = if self.is_dirty { " *" } else { "" };
-        let ahead_behind = if self.ahead > 0 || self.behind > 0 {
-            format!(" | +{} -{}", self.ahead, self.behind)
+        let branch = self.head_label();
+        let dirty = if self.large_repo {
+            " (large repo)"
+        } else if self.is_dirty {
+            " *"
+        } else {
+            ""
+        };
+        let ahead_behind = if self.has_upstream || self.ahead > 0 || self.behind > 0 {
+            let fetched = match self.last_fetch {
+                Some(time) => format!(" fetched {}", format_age(time)),
+                None if self.has_upstream => " never fetched".to_string(),
+                None => String::new(),
+            };
+            format!(" | +{} -{}{}", self.ahead, self.behind, fetched)
         } else {
             String::new()
         };
+        let vs_remote = match &self.compare_ref {
+            Some(remote_ref) => format!(
+                " | {}{} {}{} vs {}",
+                glyph("↑", "^"),
+                self.remote_ahead,
+                glyph("↓", "v"),
+                self.remote_behind,
+                remote_ref
+            ),
+            None => String::new(),
+        };
+        let vs_base = match &self.base_branch {
+            Some(base)
+                if self.base_ahead > 0 || self.base_behind > 0 || self.detached.is_some() =>
+            {
+                format!(
+                    " | {}{} {}{} vs {}",
+                    glyph("↑", "^"),
+                    self.base_ahead,
+                    glyph("↓", "v"),
+                    self.base_behind,
+                    base
+                )
+            }
+            _ => String::new(),
+        };
         let staged_unstaged = if self.staged_count > 0 || self.unstaged_count > 0 {
             format!(" | {}S {}U", self.staged_count, self.unstaged_count)
         } else {
             String::new()
         };
-        format!("{}{}{}{}", branch, dirty, ahead_behind, staged_unstaged)
+        format!(
+            "{}{}{}{}{}{}",
+            branch, dirty, ahead_behind, vs_remote, vs_base, staged_unstaged
+        )
     }
 }
 
@@ -69,112 +326,118 @@ fn get_current_branch(repo: &Repository) -> Option<String> {
     }
 }
 
-/// Check if the repository has uncommitted changes.
-fn is_repo_dirty(repo: &Repository) -> bool {
-    let mut opts = StatusOptions::new();
-    opts.include_untracked(true).recurse_untracked_dirs(false);
-
-    match repo.statuses(Some(&mut opts)) {
-        Ok(statuses) => !statuses.is_empty(),
-        Err(_) => false,
+/// Get the short hash of the checked-out commit and the tag pointing at it,
+/// when HEAD is detached.
+///
+/// Returns None if HEAD is on a branch or unborn.
+fn get_detached_head(repo: &Repository) -> Option<(String, Option<String>)> {
+    if !repo.head_detached().ok()? {
+        return None;
     }
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    let short_id = commit.as_object().short_id().ok()?;
+    let short_id = short_id.as_str()?.to_string();
+
+    let mut tags: Vec<String> = repo
+        .references_glob("refs/tags/*")
+        .ok()?
+        .flatten()
+        .filter(|tag| {
+            tag.peel_to_commit()
+                .is_ok_and(|tagged| tagged.id() == commit.id())
+        })
+        .filter_map(|tag| tag.shorthand().map(String::from))
+        .collect();
+    tags.sort();
+
+    Some((short_id, tags.into_iter().next()))
 }
 
-/// Get the number of commits ahead and behind the upstream branch.
-fn get_ahead_behind(repo: &Repository) -> (u32, u32) {
-    let head = match repo.head() {
-        Ok(h) => h,
-        Err(_) => return (0, 0),
-    };
-
-    let local_oid = match head.target() {
-        Some(oid) => oid,
-        None => return (0, 0),
-    };
-
-    // Get the upstream branch
-    let branch_name = match head.shorthand() {
-        Some(name) => name,
-        None => return (0, 0),
-    };
-
-    let branch = match repo.find_branch(branch_name, git2::BranchType::Local) {
-        Ok(b) => b,
-        Err(_) => return (0, 0),
-    };
-
-    let upstream = match branch.upstream() {
-        Ok(u) => u,
-        Err(_) => return (0, 0), // No upstream configured
-    };
-
-    let upstream_oid = match upstream.get().target() {
-        Some(oid) => oid,
-        None => return (0, 0),
-    };
-
-    match repo.graph_ahead_behind(local_oid, upstream_oid) {
-        Ok((ahead, behind)) => (ahead as u32, behind as u32),
-        Err(_) => (0, 0),
-    }
+/// Get the default branch of `origin` (e.g. "main"), from `origin/HEAD`.
+fn get_default_branch(repo: &Repository) -> Option<String> {
+    get_remote_default_branch(repo, "origin")
 }
 
-/// Count staged and unstaged files.
-fn count_staged_unstaged(repo: &Repository) -> (u32, u32) {
-    let mut opts = StatusOptions::new();
-    opts.include_untracked(true).recurse_untracked_dirs(false);
-
-    let statuses = match repo.statuses(Some(&mut opts)) {
-        Ok(s) => s,
-        Err(_) => return (0, 0),
-    };
-
-    let mut staged = 0u32;
-    let mut unstaged = 0u32;
-
-    for entry in statuses.iter() {
-        let status = entry.status();
-
-        // Staged changes (index)
-        if status.intersects(
-            Status::INDEX_NEW
-                | Status::INDEX_MODIFIED
-                | Status::INDEX_DELETED
-                | Status::INDEX_RENAMED
-                | Status::INDEX_TYPECHANGE,
-        ) {
-            staged += 1;
-        }
+/// Get the default branch of a remote, from `refs/remotes/<remote>/HEAD`.
+fn get_remote_default_branch(repo: &Repository, remote: &str) -> Option<String> {
+    let prefix = format!("refs/remotes/{}/", remote);
+    let reference = repo.find_reference(&format!("{}HEAD", prefix)).ok()?;
+    let target = reference.symbolic_target()?;
+    target.strip_prefix(&prefix).map(String::from)
+}
 
-        // Unstaged changes (workdir)
-        if status.intersects(
-            Status::WT_NEW
-                | Status::WT_MODIFIED
-                | Status::WT_DELETED
-                | Status::WT_RENAMED
-                | Status::WT_TYPECHANGE,
-        ) {
-            unstaged += 1;
-        }
-    }
+/// Get the number of commits ahead and behind the upstream branch.
+///
+/// Returns None if the current branch has no upstream.
+fn get_ahead_behind(repo: &Repository) -> Option<(u32, u32)> {
+    let head = repo.head().ok()?;
+    let local_oid = head.target()?;
 
-    (staged, unstaged)
+    // Get the upstream branch
+    let branch_name = head.shorthand()?;
+    let branch = repo
+        .find_branch(branch_name, git2::BranchType::Local)
+        .ok()?;
+    let upstream = branch.upstream().ok()?;
+    let upstream_oid = upstream.get().target()?;
+
+    let counts = repo
+        .graph_ahead_behind(local_oid, upstream_oid)
+        .map(|(ahead, behind)| (ahead as u32, behind as u32))
+        .unwrap_or((0, 0));
+    Some(counts)
 }
 
-/// Get list of modified files (for detailed level).
-fn get_modified_files(repo: &Repository) -> Vec<String> {
-    let mut opts = StatusOptions::new();
-    opts.include_untracked(true).recurse_untracked_dirs(false);
-
-    let statuses = match repo.statuses(Some(&mut opts)) {
-        Ok(s) => s,
-        Err(_) => return Vec::new(),
-    };
+/// Get the number of commits ahead and behind a base branch.
+///
+/// The base is looked up as a local branch first, then as `origin/<base>`,
+/// so it works on branches that have no upstream yet.
+///
+/// Returns None if HEAD or the base branch can't be resolved.
+fn get_base_ahead_behind(repo: &Repository, base_branch: &str) -> Option<(u32, u32)> {
+    let local_oid = repo.head().ok()?.target()?;
+
+    let base_oid = repo
+        .find_branch(base_branch, git2::BranchType::Local)
+        .or_else(|_| repo.find_branch(&format!("origin/{}", base_branch), git2::BranchType::Remote))
+        .ok()?
+        .get()
+        .target()?;
+
+    repo.graph_ahead_behind(local_oid, base_oid)
+        .ok()
+        .map(|(ahead, behind)| (ahead as u32, behind as u32))
+}
 
-    statuses
-        .iter()
-        .filter_map(|entry| entry.path().map(String::from))
-        .collect()
+/// Get the number of commits ahead and behind a branch of a second remote.
+///
+/// The branch of the same name is used if the remote has one, otherwise
+/// the remote's default branch, so feature branches of a fork are compared
+/// to the branch they'll be merged into.
+///
+/// Returns the remote branch compared to and the counts, or None if HEAD or
+/// no branch of the remote can be resolved.
+fn get_remote_ahead_behind(
+    repo: &Repository,
+    remote: &str,
+    branch: Option<&str>,
+) -> Option<(String, (u32, u32))> {
+    let local_oid = repo.head().ok()?.target()?;
+
+    branch
+        .map(String::from)
+        .into_iter()
+        .chain(get_remote_default_branch(repo, remote))
+        .find_map(|name| {
+            let remote_ref = format!("{}/{}", remote, name);
+            let oid = repo
+                .find_branch(&remote_ref, git2::BranchType::Remote)
+                .ok()?
+                .get()
+                .target()?;
+            let (ahead, behind) = repo.graph_ahead_behind(local_oid, oid).ok()?;
+            Some((remote_ref, (ahead as u32, behind as u32)))
+        })
 }
 
 /// Open a Git repository at the given path.
@@ -183,56 +446,173 @@ pub fn open_repo(path: &Path) -> Option<Repository> {
     Repository::open(path).ok()
 }
 
+/// Returns whether a directory is the top of a repository of its own: a
+/// clone, a submodule or a worktree, not a directory inside one.
+pub fn is_repository_root(path: &Path) -> bool {
+    path.join(".git").exists()
+}
+
+/// Get the URL of the `origin` remote of a repository.
+///
+/// Returns None if the path is not a Git repository or there is no `origin` remote.
+pub fn origin_url(path: &Path) -> Option<String> {
+    let repo = open_repo(path)?;
+    let remote = repo.find_remote("origin").ok()?;
+    remote.url().map(String::from)
+}
+
+/// Get the current branch and the URL of the `origin` remote of a repository.
+///
+/// Returns None if the path is not a Git repository, HEAD is detached or
+/// there is no `origin` remote.
+pub fn branch_and_origin(path: &Path) -> Option<(String, String)> {
+    let repo = open_repo(path)?;
+    let branch = get_current_branch(&repo)?;
+    let origin = repo.find_remote("origin").ok()?.url()?.to_string();
+    Some((branch, origin))
+}
+
 /// Get Git information for a repository at the given path.
 ///
 /// The amount of information collected depends on the level:
 /// - Minimal: branch + dirty status
-/// - Standard: + ahead/behind + staged/unstaged counts
+/// - Standard: + ahead/behind, last fetch + staged/unstaged counts
 /// - Detailed: + list of modified files
 ///
 /// Returns None if the path is not a Git repository.
 pub fn get_git_info(path: &Path, level: GitInfoLevel) -> Option<GitInfo> {
+    get_git_info_with_base(path, level, None)
+}
+
+/// Get Git information for a repository, compared to a base branch.
+///
+/// Like `get_git_info`, and from the standard level on also counts the
+/// commits ahead and behind `base_branch` when it is set and exists. A
+/// detached HEAD is compared to `base_branch`, or to the default branch of
+/// `origin` when none is set.
+///
+/// # Arguments
+///
+/// * `path` - The repository directory
+/// * `level` - The amount of information to collect
+/// * `base_branch` - The branch to compare the current branch to, if any
+///
+/// # Returns
+///
+/// The Git information, or None if the path is not a Git repository.
+pub fn get_git_info_with_base(
+    path: &Path,
+    level: GitInfoLevel,
+    base_branch: Option<&str>,
+) -> Option<GitInfo> {
+    get_git_info_compared(path, level, &GitCompare::with_base(base_branch))
+}
+
+/// Get Git information for a repository, compared to a base branch and a
+/// second remote.
+///
+/// Like `get_git_info_with_base`, and from the standard level on also
+/// counts the commits ahead and behind the branch of `compare_remote` with
+/// the same name, or its default branch.
+///
+/// # Arguments
+///
+/// * `path` - The repository directory
+/// * `level` - The amount of information to collect
+/// * `compare` - What the current branch is compared to
+///
+/// # Returns
+///
+/// The Git information, or None if the path is not a Git repository.
+pub fn get_git_info_compared(
+    path: &Path,
+    level: GitInfoLevel,
+    compare: &GitCompare,
+) -> Option<GitInfo> {
     let repo = open_repo(path)?;
 
     let branch = get_current_branch(&repo);
-    let is_dirty = is_repo_dirty(&repo);
-
-    // For minimal level, we're done
-    if level == GitInfoLevel::Minimal {
+    let (detached, tag) = match get_detached_head(&repo) {
+        Some((commit, tag)) => (Some(commit), tag),
+        None => (None, None),
+    };
+    let operation = GitOperation::from_state(repo.state());
+    // Too large a repository shows its branch only
+    let Some(status) = status::collect(path, &repo, compare.limits) else {
         return Some(GitInfo {
             branch,
-            is_dirty,
+            detached,
+            tag,
+            operation,
+            large_repo: true,
             ..Default::default()
         });
-    }
-
-    // Standard level adds ahead/behind and staged/unstaged
-    let (ahead, behind) = get_ahead_behind(&repo);
-    let (staged_count, unstaged_count) = count_staged_unstaged(&repo);
+    };
 
-    if level == GitInfoLevel::Standard {
+    // For minimal level, we're done
+    if level == GitInfoLevel::Minimal {
         return Some(GitInfo {
             branch,
-            is_dirty,
-            ahead,
-            behind,
-            staged_count,
-            unstaged_count,
-            modified_files: Vec::new(),
+            detached,
+            tag,
+            is_dirty: status.is_dirty,
+            conflicted_count: status.conflicted_count,
+            operation,
+            ..Default::default()
         });
     }
 
+    // Standard level adds ahead/behind (upstream and base) and staged/unstaged
+    let upstream = get_ahead_behind(&repo);
+    let (ahead, behind) = upstream.unwrap_or((0, 0));
+    let base_branch = match &compare.base_branch {
+        Some(base) => Some(base.clone()),
+        None if detached.is_some() => get_default_branch(&repo),
+        None => None,
+    };
+    let base = base_branch
+        .and_then(|base| get_base_ahead_behind(&repo, &base).map(|counts| (base, counts)));
+    let (base_branch, (base_ahead, base_behind)) = match base {
+        Some((base, counts)) => (Some(base), counts),
+        None => (None, (0, 0)),
+    };
+    let remote = compare
+        .compare_remote
+        .as_deref()
+        .and_then(|remote| get_remote_ahead_behind(&repo, remote, branch.as_deref()));
+    let (compare_ref, (remote_ahead, remote_behind)) = match remote {
+        Some((remote_ref, counts)) => (Some(remote_ref), counts),
+        None => (None, (0, 0)),
+    };
+
     // Detailed level adds modified files list
-    let modified_files = get_modified_files(&repo);
+    let modified_files = if level == GitInfoLevel::Detailed {
+        status.files
+    } else {
+        Vec::new()
+    };
 
     Some(GitInfo {
         branch,
-        is_dirty,
+        detached,
+        tag,
+        is_dirty: status.is_dirty,
+        conflicted_count: status.conflicted_count,
+        operation,
         ahead,
         behind,
-        staged_count,
-        unstaged_count,
+        has_upstream: upstream.is_some(),
+        last_fetch: fetch::last_fetch(&repo),
+        staged_count: status.staged_count,
+        unstaged_count: status.unstaged_count,
         modified_files,
+        base_branch,
+        base_ahead,
+        base_behind,
+        compare_ref,
+        remote_ahead,
+        remote_behind,
+        large_repo: false,
     })
 }
 
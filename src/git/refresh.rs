@@ -0,0 +1,154 @@
+//! Recomputing the git information of many projects in parallel.
+//!
+//! A refresh hands the projects to a bounded number of worker threads and
+//! reports each result as it arrives, so the git cache fills in while the
+//! slowest repositories are still being read.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use super::{get_git_info_compared, GitCompare, GitInfo};
+use crate::config::GitInfoLevel;
+
+/// Upper bound of worker threads, whatever the number of CPUs.
+const MAX_WORKERS: usize = 8;
+
+/// A project to refresh: its directory and what it's compared to.
+pub type RefreshTarget = (PathBuf, GitCompare);
+
+/// A refresh of several projects running in the background.
+#[derive(Debug)]
+pub struct GitRefresh {
+    total: usize,
+    done: usize,
+    results: Receiver<(PathBuf, Option<GitInfo>)>,
+    workers_gone: bool,
+}
+
+impl GitRefresh {
+    /// Starts recomputing the git information of projects.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - The projects to refresh, with what they're compared to
+    /// * `level` - The detail level of the git information
+    /// * `limit` - The maximum number of projects read at the same time
+    pub fn spawn(targets: Vec<RefreshTarget>, level: GitInfoLevel, limit: usize) -> Self {
+        let total = targets.len();
+        let workers = limit.clamp(1, MAX_WORKERS).min(total);
+        let queue = Arc::new(Mutex::new(VecDeque::from(targets)));
+        let (sender, results) = mpsc::channel();
+
+        for _ in 0..workers {
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+            thread::spawn(move || loop {
+                let next = queue.lock().ok().and_then(|mut queue| queue.pop_front());
+                let Some((path, compare)) = next else {
+                    break;
+                };
+                let info = get_git_info_compared(&path, level, &compare);
+                if sender.send((path, info)).is_err() {
+                    break;
+                }
+            });
+        }
+
+        Self {
+            total,
+            done: 0,
+            results,
+            workers_gone: false,
+        }
+    }
+
+    /// Returns the default concurrency limit: the number of CPUs.
+    pub fn default_limit() -> usize {
+        thread::available_parallelism().map_or(4, |n| n.get())
+    }
+
+    /// Collects the results that arrived since the last call.
+    ///
+    /// # Returns
+    ///
+    /// The projects refreshed since the last call with their git information,
+    /// None for directories that aren't repositories.
+    pub fn poll(&mut self) -> Vec<(PathBuf, Option<GitInfo>)> {
+        let mut results = Vec::new();
+        loop {
+            match self.results.try_recv() {
+                Ok(result) => results.push(result),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.workers_gone = true;
+                    break;
+                }
+            }
+        }
+        self.done += results.len();
+        results
+    }
+
+    /// Returns how many projects were refreshed, out of how many.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.done, self.total)
+    }
+
+    /// Returns whether every project was refreshed.
+    ///
+    /// A worker that panicked leaves its project unreported; the refresh is
+    /// also finished once `poll` found every worker gone.
+    pub fn is_finished(&self) -> bool {
+        self.done >= self.total || self.workers_gone
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    #[test]
+    fn when_refreshing_projects_should_report_each_one() {
+        let dir = TempDir::new().unwrap();
+        let mut targets = Vec::new();
+        for name in ["api", "web", "db"] {
+            let path = dir.path().join(name);
+            git2::Repository::init(&path).unwrap();
+            targets.push((path, GitCompare::default()));
+        }
+        let not_a_repo = dir.path().join("notes");
+        std::fs::create_dir(&not_a_repo).unwrap();
+        targets.push((not_a_repo.clone(), GitCompare::with_base(Some("main"))));
+
+        let mut refresh = GitRefresh::spawn(targets, GitInfoLevel::Standard, 2);
+        let mut results = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while refresh.progress().0 < 4 {
+            assert!(Instant::now() < deadline, "refresh did not finish");
+            results.extend(refresh.poll());
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert!(refresh.is_finished());
+        assert_eq!(refresh.progress(), (4, 4));
+        assert_eq!(results.iter().filter(|(_, info)| info.is_some()).count(), 3);
+        assert!(results
+            .iter()
+            .any(|(path, info)| path == &not_a_repo && info.is_none()));
+    }
+
+    #[test]
+    fn when_there_is_nothing_to_refresh_should_be_finished() {
+        let refresh = GitRefresh::spawn(Vec::new(), GitInfoLevel::Minimal, 4);
+
+        assert!(refresh.is_finished());
+        assert_eq!(refresh.progress(), (0, 0));
+    }
+}
@@ -0,0 +1,147 @@
+//! Status of the files of a repository, within limits.
+//!
+//! On very large repositories `git status` takes seconds. The status can be
+//! bounded in time and in number of changed files; a repository exceeding
+//! either is remembered as large, and only its branch is shown from then on,
+//! until the panel restarts.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use git2::{Repository, Status, StatusOptions};
+
+/// Repositories that exceeded their limits.
+static LARGE_REPOS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Limits on collecting the status of a repository's files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatusLimits {
+    /// Time the status may take; None waits for it.
+    pub timeout: Option<Duration>,
+    /// Changed files tolerated; None tolerates any number.
+    pub max_entries: Option<usize>,
+}
+
+/// The status of a repository's files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusSummary {
+    /// Whether there are uncommitted changes.
+    pub is_dirty: bool,
+    /// Number of files with unresolved merge conflicts.
+    pub conflicted_count: u32,
+    /// Number of staged files.
+    pub staged_count: u32,
+    /// Number of unstaged modified files.
+    pub unstaged_count: u32,
+    /// The changed files.
+    pub files: Vec<String>,
+}
+
+/// Collects the status of a repository's files within limits.
+///
+/// Without a timeout the status is collected from `repo`; with one, from
+/// the repository opened again on a thread, which is left to finish on its
+/// own when the time is up.
+///
+/// # Arguments
+///
+/// * `path` - The repository directory
+/// * `repo` - The repository
+/// * `limits` - The limits of the status
+///
+/// # Returns
+///
+/// The status, or None if the repository exceeded the limits, now or before.
+pub fn collect(path: &Path, repo: &Repository, limits: StatusLimits) -> Option<StatusSummary> {
+    if is_large(path) {
+        return None;
+    }
+    let summary = match limits.timeout {
+        None => summarize(repo, limits.max_entries),
+        Some(timeout) => {
+            let (tx, rx) = mpsc::channel();
+            let owned = path.to_path_buf();
+            thread::spawn(move || {
+                let summary = match Repository::open(&owned) {
+                    Ok(repo) => summarize(&repo, limits.max_entries),
+                    Err(_) => Some(StatusSummary::default()),
+                };
+                let _ = tx.send(summary);
+            });
+            rx.recv_timeout(timeout).unwrap_or(None)
+        }
+    };
+    if summary.is_none() {
+        let mut large = LARGE_REPOS.lock().unwrap_or_else(|e| e.into_inner());
+        if !large.iter().any(|p| p == path) {
+            large.push(path.to_path_buf());
+        }
+    }
+    summary
+}
+
+/// Returns whether a repository exceeded its status limits.
+pub fn is_large(path: &Path) -> bool {
+    LARGE_REPOS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .any(|p| p == path)
+}
+
+/// Summarizes the status of a repository's files.
+///
+/// A status that can't be read counts as clean.
+///
+/// # Returns
+///
+/// The status, or None if more than `max_entries` files changed.
+fn summarize(repo: &Repository, max_entries: Option<usize>) -> Option<StatusSummary> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true).recurse_untracked_dirs(false);
+    let Ok(statuses) = repo.statuses(Some(&mut opts)) else {
+        return Some(StatusSummary::default());
+    };
+    if max_entries.is_some_and(|max| statuses.len() > max) {
+        return None;
+    }
+
+    let mut summary = StatusSummary {
+        is_dirty: !statuses.is_empty(),
+        ..Default::default()
+    };
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status.is_conflicted() {
+            summary.conflicted_count += 1;
+        }
+        // Staged changes (index)
+        if status.intersects(
+            Status::INDEX_NEW
+                | Status::INDEX_MODIFIED
+                | Status::INDEX_DELETED
+                | Status::INDEX_RENAMED
+                | Status::INDEX_TYPECHANGE,
+        ) {
+            summary.staged_count += 1;
+        }
+        // Unstaged changes (workdir)
+        if status.intersects(
+            Status::WT_NEW
+                | Status::WT_MODIFIED
+                | Status::WT_DELETED
+                | Status::WT_RENAMED
+                | Status::WT_TYPECHANGE,
+        ) {
+            summary.unstaged_count += 1;
+        }
+        if let Some(path) = entry.path() {
+            summary.files.push(path.to_string());
+        }
+    }
+    Some(summary)
+}
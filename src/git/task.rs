@@ -0,0 +1,201 @@
+//! Branches and worktrees of Claude tasks.
+//!
+//! The new task flow (`n` on a project) creates a branch named after the
+//! task from the project's HEAD, checks it out in the project or in a
+//! worktree next to it, and launches Claude there. The branch starts at HEAD,
+//! so checking it out in the project keeps uncommitted changes, like
+//! `git switch -c`.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::path::{Path, PathBuf};
+
+use git2::{Branch, Repository, WorktreeAddOptions};
+
+use crate::error::{GzClaudeError, Result};
+
+/// Turns a task name into the part of a branch name after the prefix:
+/// lowercase ASCII letters and digits, words joined by single `-`.
+///
+/// # Arguments
+///
+/// * `task` - The task name, e.g. "Fix login redirect"
+///
+/// # Returns
+///
+/// The slug (e.g. "fix-login-redirect"), or None if the name has no letters
+/// or digits.
+pub fn slug(task: &str) -> Option<String> {
+    let words: Vec<String> = task
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    Some(words.join("-")).filter(|slug| !slug.is_empty())
+}
+
+/// Returns the directory of a task's worktree: a sibling of the project
+/// named `<project dir>-<slug>`.
+///
+/// # Arguments
+///
+/// * `project_path` - The project directory
+/// * `slug` - The slug of the task
+pub fn worktree_path(project_path: &Path, slug: &str) -> PathBuf {
+    let name = project_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string());
+    project_path.with_file_name(format!("{}-{}", name, slug))
+}
+
+/// Creates a task branch from HEAD and checks it out.
+///
+/// # Arguments
+///
+/// * `project_path` - The project directory
+/// * `branch` - The branch to create, e.g. "claude/fix-login-redirect"
+/// * `worktree` - The worktree the branch is checked out in; None checks it
+///   out in the project
+///
+/// # Returns
+///
+/// The directory the task runs in: the worktree, or the project.
+///
+/// # Errors
+///
+/// Returns `GzClaudeError::Git` if the project is not a repository or has no
+/// commits, the branch name is invalid or taken, the worktree directory
+/// exists, or git fails to create the branch or worktree.
+pub fn start_task(project_path: &Path, branch: &str, worktree: Option<&Path>) -> Result<PathBuf> {
+    let git_error = |e: git2::Error| GzClaudeError::Git(e.message().to_string());
+
+    if !Branch::name_is_valid(branch).map_err(git_error)? {
+        return Err(GzClaudeError::Git(format!(
+            "'{}' is not a valid branch name",
+            branch
+        )));
+    }
+    let repo = Repository::open(project_path).map_err(git_error)?;
+    if repo.find_branch(branch, git2::BranchType::Local).is_ok() {
+        return Err(GzClaudeError::Git(format!(
+            "branch '{}' already exists",
+            branch
+        )));
+    }
+    if let Some(worktree) = worktree {
+        if worktree.exists() {
+            return Err(GzClaudeError::Git(format!(
+                "{} already exists",
+                worktree.display()
+            )));
+        }
+    }
+
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|_| GzClaudeError::Git(format!("{} has no commits", project_path.display())))?;
+    let created = repo.branch(branch, &head, false).map_err(git_error)?;
+
+    match worktree {
+        None => {
+            // Same commit as HEAD, so the working tree is left as it is
+            let refname = created.get().name().unwrap_or_default().to_string();
+            repo.set_head(&refname).map_err(git_error)?;
+            Ok(project_path.to_path_buf())
+        }
+        Some(worktree) => {
+            // Worktrees are named after their directory, unique next to the project
+            let name = worktree
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| branch.replace('/', "-"));
+            let mut options = WorktreeAddOptions::new();
+            options.reference(Some(created.get()));
+            repo.worktree(&name, worktree, Some(&options))
+                .map_err(git_error)?;
+            Ok(worktree.to_path_buf())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn repo_with_commit(path: &Path) -> Repository {
+        let repo = Repository::init(path).unwrap();
+        std::fs::write(path.join("README.md"), "readme").unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("README.md")).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+            repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+                .unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn when_naming_task_should_join_lowercase_words() {
+        assert_eq!(
+            slug("  Fix login: redirect!! ").as_deref(),
+            Some("fix-login-redirect")
+        );
+        assert_eq!(slug("--- ?"), None);
+    }
+
+    #[test]
+    fn when_placing_worktree_should_use_a_sibling_of_the_project() {
+        assert_eq!(
+            worktree_path(Path::new("/work/api"), "fix-login"),
+            PathBuf::from("/work/api-fix-login")
+        );
+    }
+
+    #[test]
+    fn when_starting_task_in_place_should_check_out_new_branch() {
+        let dir = TempDir::new().unwrap();
+        let repo = repo_with_commit(dir.path());
+        std::fs::write(dir.path().join("wip.txt"), "uncommitted").unwrap();
+
+        let task_dir = start_task(dir.path(), "claude/fix-login", None).unwrap();
+
+        assert_eq!(task_dir, dir.path());
+        assert_eq!(repo.head().unwrap().shorthand(), Some("claude/fix-login"));
+        assert!(dir.path().join("wip.txt").exists());
+    }
+
+    #[test]
+    fn when_starting_task_in_worktree_should_check_out_branch_there() {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("api");
+        let repo = repo_with_commit(&project);
+        let worktree = worktree_path(&project, "fix-login");
+
+        let task_dir = start_task(&project, "claude/fix-login", Some(&worktree)).unwrap();
+
+        assert_eq!(task_dir, worktree);
+        assert!(worktree.join("README.md").exists());
+        let checked_out = Repository::open(&worktree).unwrap();
+        assert_eq!(
+            checked_out.head().unwrap().shorthand(),
+            Some("claude/fix-login")
+        );
+        assert_ne!(repo.head().unwrap().shorthand(), Some("claude/fix-login"));
+    }
+
+    #[test]
+    fn when_branch_exists_should_fail() {
+        let dir = TempDir::new().unwrap();
+        repo_with_commit(dir.path());
+        start_task(dir.path(), "claude/fix-login", None).unwrap();
+
+        let result = start_task(dir.path(), "claude/fix-login", None);
+
+        assert!(matches!(result, Err(GzClaudeError::Git(message)) if message.contains("exists")));
+    }
+}
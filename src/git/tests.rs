@@ -100,6 +100,77 @@ fn when_repo_has_untracked_should_be_dirty() {
     assert!(info.is_dirty);
 }
 
+#[test]
+fn when_merge_has_conflicts_should_count_them_and_be_risky() {
+    let dir = create_test_repo();
+    create_file(&dir, "file.txt", "base");
+    git_add(&dir, "file.txt");
+    git_commit(&dir, "Initial commit");
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap()
+    };
+    git(&["checkout", "-b", "other"]);
+    create_file(&dir, "file.txt", "theirs");
+    git_add(&dir, "file.txt");
+    git_commit(&dir, "Theirs");
+    git(&["checkout", "-"]);
+    create_file(&dir, "file.txt", "ours");
+    git_add(&dir, "file.txt");
+    git_commit(&dir, "Ours");
+
+    git(&["merge", "other"]);
+
+    let info = get_git_info(dir.path(), GitInfoLevel::Minimal).unwrap();
+    assert_eq!(info.conflicted_count, 1);
+    assert_eq!(info.operation, Some(GitOperation::Merge));
+    assert_eq!(
+        info.operation_badge().as_deref(),
+        Some("MERGING (1 conflict)")
+    );
+    assert_eq!(info.severity(10), GitSeverity::Risky);
+}
+
+#[test]
+fn when_formatting_operation_badge_should_name_operation_and_conflicts() {
+    let rebasing = GitInfo {
+        operation: Some(GitOperation::Rebase),
+        ..Default::default()
+    };
+    let conflicted = GitInfo {
+        conflicted_count: 3,
+        ..Default::default()
+    };
+
+    assert_eq!(GitInfo::default().operation_badge(), None);
+    assert_eq!(rebasing.operation_badge().as_deref(), Some("REBASING"));
+    assert_eq!(rebasing.severity(10), GitSeverity::Risky);
+    assert_eq!(conflicted.operation_badge().as_deref(), Some("3 conflicts"));
+    assert_eq!(
+        GitOperation::from_state(git2::RepositoryState::CherryPickSequence),
+        Some(GitOperation::CherryPick)
+    );
+    assert_eq!(GitOperation::from_state(git2::RepositoryState::Clean), None);
+}
+
+#[test]
+fn when_grading_git_status_should_flag_dirty_and_far_behind() {
+    let clean = GitInfo::default();
+    let dirty = GitInfo {
+        is_dirty: true,
+        behind: 3,
+        ..Default::default()
+    };
+
+    assert_eq!(clean.severity(10), GitSeverity::Clean);
+    assert_eq!(dirty.severity(10), GitSeverity::Dirty);
+    assert_eq!(dirty.severity(3), GitSeverity::Dirty);
+    assert_eq!(dirty.severity(2), GitSeverity::Risky);
+}
+
 #[test]
 fn when_getting_branch_should_return_current_branch() {
     let dir = create_test_repo();
@@ -155,6 +226,37 @@ fn when_detailed_level_should_include_modified_files() {
     assert!(info.modified_files.contains(&"file.txt".to_string()));
 }
 
+#[test]
+fn when_status_exceeds_max_entries_should_show_branch_only() {
+    let dir = create_test_repo();
+    create_file(&dir, "file.txt", "content");
+    git_add(&dir, "file.txt");
+    git_commit(&dir, "Initial commit");
+    create_file(&dir, "a.txt", "a");
+    create_file(&dir, "b.txt", "b");
+    let compare = |max_entries| GitCompare {
+        limits: StatusLimits {
+            max_entries: Some(max_entries),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let info = get_git_info_compared(dir.path(), GitInfoLevel::Standard, &compare(2)).unwrap();
+    assert!(!info.large_repo);
+    assert_eq!(info.unstaged_count, 2);
+
+    let info = get_git_info_compared(dir.path(), GitInfoLevel::Standard, &compare(1)).unwrap();
+    assert!(info.large_repo);
+    assert!(!info.is_dirty);
+    assert!(info.branch.is_some());
+    assert!(info.format_minimal().ends_with(" (large repo)"));
+
+    // Once too large, the repository stays so
+    let info = get_git_info_compared(dir.path(), GitInfoLevel::Standard, &compare(2)).unwrap();
+    assert!(info.large_repo);
+}
+
 #[test]
 fn when_formatting_minimal_dirty_should_show_asterisk() {
     let info = GitInfo {
@@ -175,6 +277,29 @@ fn when_formatting_minimal_clean_should_not_show_asterisk() {
     assert_eq!(info.format_minimal(), "main");
 }
 
+#[test]
+fn when_formatting_glyph_should_prefer_changes_then_behind_then_ahead() {
+    let dirty_and_behind = GitInfo {
+        is_dirty: true,
+        behind: 2,
+        ..Default::default()
+    };
+    let behind_and_ahead = GitInfo {
+        ahead: 1,
+        behind: 2,
+        ..Default::default()
+    };
+    let ahead = GitInfo {
+        ahead: 1,
+        ..Default::default()
+    };
+
+    assert_eq!(dirty_and_behind.format_glyph(), "●");
+    assert_eq!(behind_and_ahead.format_glyph(), "↓");
+    assert_eq!(ahead.format_glyph(), "↑");
+    assert_eq!(GitInfo::default().format_glyph(), "");
+}
+
 #[test]
 fn when_formatting_standard_should_include_all_info() {
     let info = GitInfo {
@@ -194,3 +319,255 @@ fn when_formatting_standard_should_include_all_info() {
     assert!(formatted.contains("3S"));
     assert!(formatted.contains("2U"));
 }
+
+#[test]
+fn when_getting_cached_info_should_reuse_entry_until_invalidated() {
+    let dir = create_test_repo();
+    create_file(&dir, "file.txt", "content");
+    git_add(&dir, "file.txt");
+    git_commit(&dir, "Initial commit");
+
+    let mut cache = GitCache::new(GitInfoLevel::Minimal);
+    assert!(!cache.get(dir.path()).unwrap().is_dirty);
+
+    // Changes are not visible until the entry is invalidated
+    create_file(&dir, "file.txt", "modified");
+    assert!(!cache.get(dir.path()).unwrap().is_dirty);
+
+    cache.invalidate(dir.path());
+    assert!(cache.get(dir.path()).unwrap().is_dirty);
+}
+
+#[test]
+fn when_git_metadata_changes_should_invalidate_watched_entry() {
+    let dir = create_test_repo();
+    create_file(&dir, "file.txt", "content");
+    git_add(&dir, "file.txt");
+    git_commit(&dir, "Initial commit");
+
+    let mut cache = GitCache::with_watcher(GitInfoLevel::Minimal);
+    cache.get(dir.path());
+    assert!(cache.contains(dir.path()));
+
+    create_file(&dir, "other.txt", "content");
+    git_add(&dir, "other.txt");
+
+    // Filesystem events are delivered asynchronously
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while cache.contains(dir.path()) && std::time::Instant::now() < deadline {
+        cache.process_watch_events();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    assert!(!cache.contains(dir.path()));
+}
+
+#[test]
+fn when_watched_entry_gets_old_should_recompute_working_tree_changes() {
+    let dir = create_test_repo();
+    create_file(&dir, "file.txt", "content");
+    git_add(&dir, "file.txt");
+    git_commit(&dir, "Initial commit");
+
+    let mut cache = GitCache::with_watcher(GitInfoLevel::Minimal);
+    assert!(!cache.get(dir.path()).unwrap().is_dirty);
+
+    // An unstaged edit leaves .git untouched, so only the age expires it
+    create_file(&dir, "file.txt", "modified");
+    assert!(!cache.expire(std::time::Duration::from_secs(3600)));
+    assert!(cache.expire(std::time::Duration::ZERO));
+
+    assert!(cache.get(dir.path()).unwrap().is_dirty);
+}
+
+fn git(dir: &TempDir, args: &[&str]) {
+    Command::new("git")
+        .args(args)
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+}
+
+#[test]
+fn when_base_branch_is_set_should_count_commits_against_it() {
+    let dir = create_test_repo();
+    git(&dir, &["checkout", "-b", "main"]);
+    create_file(&dir, "file.txt", "content");
+    git_add(&dir, "file.txt");
+    git_commit(&dir, "Initial commit");
+
+    git(&dir, &["checkout", "-b", "feature"]);
+    for name in ["a.txt", "b.txt"] {
+        create_file(&dir, name, "feature");
+        git_add(&dir, name);
+        git_commit(&dir, name);
+    }
+    git(&dir, &["checkout", "main"]);
+    create_file(&dir, "main.txt", "main");
+    git_add(&dir, "main.txt");
+    git_commit(&dir, "Main commit");
+    git(&dir, &["checkout", "feature"]);
+
+    let info = get_git_info_with_base(dir.path(), GitInfoLevel::Standard, Some("main")).unwrap();
+    assert_eq!(info.base_branch.as_deref(), Some("main"));
+    assert_eq!((info.base_ahead, info.base_behind), (2, 1));
+    assert!(info.format_standard().contains("↑2 ↓1 vs main"));
+
+    let missing = get_git_info_with_base(dir.path(), GitInfoLevel::Standard, Some("develop"));
+    assert_eq!(missing.unwrap().base_branch, None);
+
+    let minimal = get_git_info_with_base(dir.path(), GitInfoLevel::Minimal, Some("main"));
+    assert_eq!(minimal.unwrap().base_branch, None);
+}
+
+#[test]
+fn when_formatting_standard_level_with_base_in_sync_should_omit_base() {
+    let info = GitInfo {
+        branch: Some("main".to_string()),
+        base_branch: Some("main".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(info.format_standard(), "main");
+}
+
+#[test]
+fn when_branch_has_upstream_should_show_counts_with_fetch_age() {
+    let fetched = GitInfo {
+        branch: Some("main".to_string()),
+        has_upstream: true,
+        last_fetch: Some(SystemTime::now() - std::time::Duration::from_secs(3 * 3600)),
+        ..Default::default()
+    };
+    let never = GitInfo {
+        branch: Some("main".to_string()),
+        has_upstream: true,
+        ..Default::default()
+    };
+    let no_upstream = GitInfo {
+        branch: Some("main".to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(fetched.format_standard(), "main | +0 -0 fetched 3h ago");
+    assert_eq!(never.format_standard(), "main | +0 -0 never fetched");
+    assert_eq!(no_upstream.format_standard(), "main");
+}
+
+#[test]
+fn when_head_is_detached_at_tag_should_show_commit_and_count_against_base() {
+    let dir = create_test_repo();
+    git(&dir, &["checkout", "-b", "main"]);
+    for name in ["a.txt", "b.txt"] {
+        create_file(&dir, name, name);
+        git_add(&dir, name);
+        git_commit(&dir, name);
+    }
+    git(&dir, &["tag", "v1.0.0", "HEAD~1"]);
+    git(&dir, &["checkout", "--detach", "v1.0.0"]);
+
+    let info = get_git_info_with_base(dir.path(), GitInfoLevel::Standard, Some("main")).unwrap();
+
+    let commit = info.detached.clone().unwrap();
+    assert_eq!(info.branch, None);
+    assert_eq!(info.tag.as_deref(), Some("v1.0.0"));
+    assert_eq!((info.base_ahead, info.base_behind), (0, 1));
+    assert_eq!(
+        info.format_standard(),
+        format!("v1.0.0 (detached {}) | ↑0 ↓1 vs main", commit)
+    );
+}
+
+#[test]
+fn when_detached_without_base_should_compare_to_origin_default_branch() {
+    let origin = create_test_repo();
+    git(&origin, &["checkout", "-b", "main"]);
+    create_file(&origin, "a.txt", "a");
+    git_add(&origin, "a.txt");
+    git_commit(&origin, "a");
+    let clone = TempDir::new().unwrap();
+    Command::new("git")
+        .args(["clone", "-q"])
+        .arg(origin.path())
+        .arg(clone.path())
+        .output()
+        .unwrap();
+    git(&clone, &["checkout", "--detach", "HEAD"]);
+
+    let info = get_git_info_with_base(clone.path(), GitInfoLevel::Standard, None).unwrap();
+
+    assert!(info.detached.is_some());
+    assert_eq!(info.tag, None);
+    assert_eq!(info.base_branch.as_deref(), Some("main"));
+    assert!(info.format_standard().ends_with("| ↑0 ↓0 vs main"));
+    assert!(info.format_minimal().starts_with("detached "));
+}
+
+#[test]
+fn when_compare_remote_is_set_should_count_commits_against_its_branch() {
+    let fork_of = create_test_repo();
+    git(&fork_of, &["checkout", "-b", "main"]);
+    create_file(&fork_of, "a.txt", "a");
+    git_add(&fork_of, "a.txt");
+    git_commit(&fork_of, "a");
+    let clone = TempDir::new().unwrap();
+    Command::new("git")
+        .args(["clone", "-q"])
+        .arg(fork_of.path())
+        .arg(clone.path())
+        .output()
+        .unwrap();
+    let upstream = fork_of.path().to_string_lossy().to_string();
+    git(&clone, &["remote", "add", "upstream", &upstream]);
+    create_file(&fork_of, "b.txt", "b");
+    git_add(&fork_of, "b.txt");
+    git_commit(&fork_of, "b");
+    git(&clone, &["fetch", "-q", "upstream"]);
+    git(&clone, &["checkout", "-q", "-b", "feature"]);
+    git(&clone, &["config", "user.email", "test@test.com"]);
+    git(&clone, &["config", "user.name", "Test User"]);
+    create_file(&clone, "c.txt", "c");
+    git_add(&clone, "c.txt");
+    git_commit(&clone, "c");
+
+    let compare = GitCompare {
+        base_branch: None,
+        compare_remote: Some("upstream".to_string()),
+        ..Default::default()
+    };
+    let info = get_git_info_compared(clone.path(), GitInfoLevel::Standard, &compare).unwrap();
+
+    // Neither upstream/feature nor the default branch of upstream are known
+    assert_eq!(info.compare_ref, None);
+
+    git(&clone, &["remote", "set-head", "upstream", "main"]);
+    let info = get_git_info_compared(clone.path(), GitInfoLevel::Standard, &compare).unwrap();
+
+    assert_eq!(info.compare_ref.as_deref(), Some("upstream/main"));
+    assert_eq!((info.remote_ahead, info.remote_behind), (1, 1));
+    assert!(info.format_standard().contains("| ↑1 ↓1 vs upstream/main"));
+
+    let missing = GitCompare {
+        base_branch: None,
+        compare_remote: Some("nowhere".to_string()),
+        ..Default::default()
+    };
+    let info = get_git_info_compared(clone.path(), GitInfoLevel::Standard, &missing).unwrap();
+    assert_eq!(info.compare_ref, None);
+}
+
+#[test]
+fn when_formatting_standard_level_with_second_remote_should_show_both_pairs() {
+    let info = GitInfo {
+        branch: Some("feature".to_string()),
+        ahead: 2,
+        has_upstream: true,
+        compare_ref: Some("upstream/feature".to_string()),
+        remote_behind: 4,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        info.format_standard(),
+        "feature | +2 -0 never fetched | ↑0 ↓4 vs upstream/feature"
+    );
+}
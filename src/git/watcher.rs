@@ -0,0 +1,166 @@
+//! Filesystem watcher for Git metadata.
+//!
+//! Watches the `.git` directory of each project (HEAD, index, refs) and reports
+//! which projects changed, so cached Git information is only recomputed when
+//! something actually changed. The working tree isn't watched; the cache
+//! recomputes old entries to catch unstaged edits.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::error::{GzClaudeError, Result};
+
+/// Watches `.git` directories and reports changed project roots.
+pub struct GitWatcher {
+    watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    /// Canonical project roots mapped to the paths they were registered with.
+    watched: HashMap<PathBuf, PathBuf>,
+}
+
+impl GitWatcher {
+    /// Creates a new watcher with no watched projects.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GzClaudeError::Git` if the platform watcher cannot be created.
+    pub fn new() -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let watcher = notify::recommended_watcher(tx)
+            .map_err(|e| GzClaudeError::Git(format!("Failed to create watcher: {}", e)))?;
+
+        Ok(Self {
+            watcher,
+            events: rx,
+            watched: HashMap::new(),
+        })
+    }
+
+    /// Starts watching the Git metadata of a project.
+    ///
+    /// Watches `.git` itself (HEAD, index) non-recursively and `.git/refs`
+    /// recursively. Does nothing if the project is already watched or has no
+    /// `.git` directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The root directory of the project
+    pub fn watch(&mut self, project_path: &Path) {
+        if self.is_watching(project_path) {
+            return;
+        }
+
+        // Watchers report canonical paths, so register the canonical root
+        let Ok(root) = project_path.canonicalize() else {
+            return;
+        };
+        let git_dir = root.join(".git");
+        if !git_dir.is_dir() {
+            return;
+        }
+
+        let _ = self.watcher.watch(&git_dir, RecursiveMode::NonRecursive);
+        let refs_dir = git_dir.join("refs");
+        if refs_dir.is_dir() {
+            let _ = self.watcher.watch(&refs_dir, RecursiveMode::Recursive);
+        }
+
+        self.watched.insert(root, project_path.to_path_buf());
+    }
+
+    /// Returns whether a project is currently being watched.
+    pub fn is_watching(&self, project_path: &Path) -> bool {
+        self.watched.values().any(|path| path == project_path)
+    }
+
+    /// Drains pending filesystem events without blocking.
+    ///
+    /// # Returns
+    ///
+    /// The set of project paths (as registered) whose Git metadata changed
+    /// since the last call.
+    pub fn drain_changes(&self) -> HashSet<PathBuf> {
+        self.events
+            .try_iter()
+            .filter_map(|event| event.ok())
+            .flat_map(|event| event.paths)
+            .filter(|path| is_relevant_git_path(path))
+            .filter_map(|path| project_root_for(&path))
+            .filter_map(|root| self.watched.get(&root).cloned())
+            .collect()
+    }
+}
+
+/// Checks whether a path inside `.git` affects the displayed Git information.
+///
+/// Only HEAD, the index, and refs are relevant; lock files and objects are ignored.
+///
+/// # Arguments
+///
+/// * `path` - A path reported by the filesystem watcher
+pub fn is_relevant_git_path(path: &Path) -> bool {
+    if path.extension().is_some_and(|ext| ext == "lock") {
+        return false;
+    }
+
+    let mut after_git = path
+        .components()
+        .skip_while(|c| *c != Component::Normal(".git".as_ref()))
+        .skip(1);
+
+    match after_git.next() {
+        Some(Component::Normal(name)) => {
+            name == "HEAD" || name == "index" || name == "refs" || name == "packed-refs"
+        }
+        _ => false,
+    }
+}
+
+/// Returns the project root owning a path inside a `.git` directory.
+///
+/// # Arguments
+///
+/// * `path` - A path inside a project's `.git` directory
+///
+/// # Returns
+///
+/// The parent of the `.git` directory, or None if the path is not inside one.
+pub fn project_root_for(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|ancestor| ancestor.file_name().is_some_and(|name| name == ".git"))
+        .and_then(|git_dir| git_dir.parent())
+        .map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_path_is_head_or_index_should_be_relevant() {
+        assert!(is_relevant_git_path(Path::new("/p/.git/HEAD")));
+        assert!(is_relevant_git_path(Path::new("/p/.git/index")));
+        assert!(is_relevant_git_path(Path::new("/p/.git/refs/heads/main")));
+    }
+
+    #[test]
+    fn when_path_is_lock_or_object_should_not_be_relevant() {
+        assert!(!is_relevant_git_path(Path::new("/p/.git/index.lock")));
+        assert!(!is_relevant_git_path(Path::new("/p/.git/objects/ab/cdef")));
+        assert!(!is_relevant_git_path(Path::new("/p/src/main.rs")));
+    }
+
+    #[test]
+    fn when_resolving_project_root_should_return_parent_of_git_dir() {
+        assert_eq!(
+            project_root_for(Path::new("/work/app/.git/refs/heads/main")),
+            Some(PathBuf::from("/work/app"))
+        );
+        assert_eq!(project_root_for(Path::new("/work/app/src/main.rs")), None);
+    }
+}
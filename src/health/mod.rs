@@ -0,0 +1,346 @@
+//! Project health checks.
+//!
+//! Runs each project's configured check (a shell command or a local port probe)
+//! in a background thread on a timer and keeps the latest result for display.
+//! A command that runs past the check's timeout is killed and fails the check.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::config::HealthCheck;
+use crate::util::glyphs::glyph;
+use crate::util::shell;
+
+/// Maximum number of output lines kept from a failing check.
+const MAX_OUTPUT_LINES: usize = 40;
+
+/// How long a port probe waits for a connection.
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often a running check command is checked for having exited.
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Latest known result of a project's health check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// The check has not completed yet.
+    Pending,
+    /// The last run succeeded.
+    Passing,
+    /// The last run failed, with its output.
+    Failing(String),
+}
+
+impl HealthStatus {
+    /// Returns the badge displayed next to the project name.
+    pub fn badge(&self) -> &'static str {
+        match self {
+            HealthStatus::Pending => glyph("⏳", ".."),
+            HealthStatus::Passing => glyph("✅", "ok"),
+            HealthStatus::Failing(_) => glyph("❌", "!!"),
+        }
+    }
+}
+
+/// Schedules health checks and tracks their results per project.
+#[derive(Debug, Default)]
+pub struct HealthMonitor {
+    statuses: HashMap<PathBuf, HealthStatus>,
+    running: HashMap<PathBuf, JoinHandle<Result<(), String>>>,
+    last_started: HashMap<PathBuf, Instant>,
+}
+
+impl HealthMonitor {
+    /// Creates a monitor with no results.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the latest status of a project's check, if it has been scheduled.
+    pub fn status(&self, project_path: &Path) -> Option<&HealthStatus> {
+        self.statuses.get(project_path)
+    }
+
+    /// Collects finished checks and starts the ones that are due.
+    ///
+    /// A check is due when it has never run or its interval has elapsed since
+    /// it was last started. A check is never started twice concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `checks` - The project directories and their health checks
+    ///
+    /// # Returns
+    ///
+    /// True if a displayed status changed.
+    pub fn poll<'a>(
+        &mut self,
+        checks: impl IntoIterator<Item = (&'a Path, &'a HealthCheck)>,
+    ) -> bool {
+        let mut changed = self.collect_finished();
+
+        let now = Instant::now();
+        for (path, check) in checks {
+            if self.running.contains_key(path) {
+                continue;
+            }
+            let interval = Duration::from_secs(check.interval_secs);
+            let due = self
+                .last_started
+                .get(path)
+                .map_or(true, |started| now.duration_since(*started) >= interval);
+            if !due {
+                continue;
+            }
+
+            if !self.statuses.contains_key(path) {
+                self.statuses
+                    .insert(path.to_path_buf(), HealthStatus::Pending);
+                changed = true;
+            }
+            self.last_started.insert(path.to_path_buf(), now);
+            self.running.insert(
+                path.to_path_buf(),
+                spawn_check(path.to_path_buf(), check.clone()),
+            );
+        }
+
+        changed
+    }
+
+    /// Returns how many checks are running.
+    pub fn running_count(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Makes every check due on the next poll.
+    pub fn reschedule_all(&mut self) {
+        self.last_started.clear();
+    }
+
+    /// Stores the results of checks whose threads have finished.
+    ///
+    /// Returns true if any stored status changed.
+    fn collect_finished(&mut self) -> bool {
+        let finished: Vec<PathBuf> = self
+            .running
+            .iter()
+            .filter(|(_, handle)| handle.is_finished())
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let mut changed = false;
+        for path in finished {
+            let Some(handle) = self.running.remove(&path) else {
+                continue;
+            };
+            let status = match handle.join() {
+                Ok(Ok(())) => HealthStatus::Passing,
+                Ok(Err(output)) => HealthStatus::Failing(output),
+                Err(_) => HealthStatus::Failing("Health check panicked".to_string()),
+            };
+            changed |= self.statuses.insert(path, status.clone()) != Some(status);
+        }
+        changed
+    }
+}
+
+/// Runs a health check in a background thread.
+///
+/// # Arguments
+///
+/// * `cwd` - The project directory
+/// * `check` - The check to run
+pub fn spawn_check(cwd: PathBuf, check: HealthCheck) -> JoinHandle<Result<(), String>> {
+    thread::spawn(move || run_check(&cwd, &check))
+}
+
+/// Runs a health check synchronously.
+///
+/// # Arguments
+///
+/// * `cwd` - The project directory
+/// * `check` - The check to run
+///
+/// # Returns
+///
+/// Ok(()) if the check passes.
+///
+/// # Errors
+///
+/// The failure output: the tail of the command's stdout and stderr, or a
+/// description of why the command timed out or the port probe failed.
+pub fn run_check(cwd: &Path, check: &HealthCheck) -> Result<(), String> {
+    if let Some(port) = check.port {
+        return probe_port(port);
+    }
+
+    let command = check.command.as_deref().unwrap_or_default();
+    let mut child = shell::command(command)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+
+    // Wait for the command to exit and close its output, up to the timeout
+    let deadline = Instant::now() + Duration::from_secs(check.timeout_secs);
+    let mut exit = None;
+    let status = loop {
+        if exit.is_none() {
+            exit = child.try_wait().ok().flatten();
+        }
+        let closed = [&stdout, &stderr]
+            .iter()
+            .all(|reader| reader.as_ref().map_or(true, |r| r.is_finished()));
+        if let Some(status) = exit.filter(|_| closed) {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            shell::kill(&mut child);
+            return Err(format!(
+                "'{}' timed out after {}s",
+                command, check.timeout_secs
+            ));
+        }
+        thread::sleep(EXIT_POLL_INTERVAL);
+    };
+
+    if status.success() {
+        return Ok(());
+    }
+
+    let collect = |reader: Option<JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    };
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&collect(stdout)),
+        String::from_utf8_lossy(&collect(stderr))
+    );
+    let lines: Vec<&str> = combined.trim_end().lines().collect();
+    let tail = lines[lines.len().saturating_sub(MAX_OUTPUT_LINES)..].join("\n");
+
+    if tail.is_empty() {
+        Err(format!("'{}' exited with {}", command, status))
+    } else {
+        Err(tail)
+    }
+}
+
+/// Reads a pipe to its end on a thread of its own.
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+/// Checks that a local TCP port accepts connections.
+fn probe_port(port: u16) -> Result<(), String> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    TcpStream::connect_timeout(&address, PORT_PROBE_TIMEOUT)
+        .map(|_| ())
+        .map_err(|e| format!("Port {} is not accepting connections: {}", port, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use tempfile::TempDir;
+
+    fn command_check(command: &str) -> HealthCheck {
+        HealthCheck {
+            command: Some(command.to_string()),
+            port: None,
+            interval_secs: 300,
+            timeout_secs: 60,
+        }
+    }
+
+    #[test]
+    fn when_command_succeeds_should_pass() {
+        let dir = TempDir::new().unwrap();
+
+        assert!(run_check(dir.path(), &command_check("true")).is_ok());
+    }
+
+    #[test]
+    fn when_command_fails_should_return_its_output() {
+        let dir = TempDir::new().unwrap();
+
+        let result = run_check(
+            dir.path(),
+            &command_check("echo 'error: broken' >&2; exit 1"),
+        );
+
+        assert_eq!(result.unwrap_err(), "error: broken");
+    }
+
+    #[test]
+    fn when_command_runs_past_timeout_should_kill_it_and_fail() {
+        let dir = TempDir::new().unwrap();
+        let check = HealthCheck {
+            timeout_secs: 1,
+            ..command_check("sleep 30")
+        };
+        let started = Instant::now();
+
+        let result = run_check(dir.path(), &check);
+
+        assert_eq!(result.unwrap_err(), "'sleep 30' timed out after 1s");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn when_port_is_listening_should_pass() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let check = HealthCheck {
+            command: None,
+            port: Some(listener.local_addr().unwrap().port()),
+            interval_secs: 300,
+            timeout_secs: 60,
+        };
+
+        assert!(run_check(Path::new("."), &check).is_ok());
+    }
+
+    #[test]
+    fn when_polling_should_record_result_once_finished() {
+        let dir = TempDir::new().unwrap();
+        let check = command_check("exit 2");
+        let mut monitor = HealthMonitor::new();
+
+        monitor.poll([(dir.path(), &check)]);
+        assert_eq!(monitor.status(dir.path()), Some(&HealthStatus::Pending));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while monitor.status(dir.path()) == Some(&HealthStatus::Pending)
+            && Instant::now() < deadline
+        {
+            thread::sleep(Duration::from_millis(20));
+            monitor.poll([(dir.path(), &check)]);
+        }
+
+        assert!(matches!(
+            monitor.status(dir.path()),
+            Some(HealthStatus::Failing(_))
+        ));
+    }
+}
@@ -0,0 +1,186 @@
+//! Pre/post hook execution for actions.
+//!
+//! Hooks can either be chained around the main command so they run inside the
+//! same Zellij pane, or executed silently in the project directory.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread::{self, JoinHandle};
+
+use crate::config::{Action, HookMode};
+use crate::error::{GzClaudeError, Result};
+
+/// Wraps a command with the action's hooks for in-pane execution.
+///
+/// In `HookMode::Pane` the resulting shell command is `pre && command; post`,
+/// so the main command only starts when the pre hook succeeds and the post hook
+/// always runs once the main command exits. In `HookMode::Background` the
+/// command is returned unchanged since hooks run outside of the pane.
+///
+/// # Arguments
+///
+/// * `action` - The action whose hooks should be applied
+/// * `command` - The fully expanded main command
+///
+/// # Returns
+///
+/// The shell command to execute in the pane.
+pub fn wrap_command(action: &Action, command: &str) -> String {
+    if action.hook_mode == HookMode::Background {
+        return command.to_string();
+    }
+    chain_hooks(action, command)
+}
+
+/// Chains the action's hooks around a command, whatever its hook mode.
+///
+/// Used for actions run in the background, whose command already runs
+/// outside of a pane.
+///
+/// # Arguments
+///
+/// * `action` - The action whose hooks should be applied
+/// * `command` - The fully expanded main command
+///
+/// # Returns
+///
+/// The shell command `pre && command; post`, without the hooks not set.
+pub fn chain_hooks(action: &Action, command: &str) -> String {
+    let mut wrapped = match action.pre.as_deref() {
+        Some(pre) => format!("{} && {}", pre, command),
+        None => command.to_string(),
+    };
+
+    if let Some(post) = action.post.as_deref() {
+        wrapped = format!("{}; {}", wrapped, post);
+    }
+
+    wrapped
+}
+
+/// Runs a hook command synchronously in the given directory.
+///
+/// The hook is executed through `sh -c` with its output captured, so nothing
+/// leaks into the TUI.
+///
+/// # Arguments
+///
+/// * `cwd` - The working directory for the hook (usually the project path)
+/// * `hook` - The shell command to execute
+///
+/// # Returns
+///
+/// Ok(()) if the hook exits successfully.
+///
+/// # Errors
+///
+/// Returns `GzClaudeError::Hook` if the hook cannot be started or exits with a
+/// non-zero status. The error message includes the last line of stderr.
+pub fn run_hook(cwd: &Path, hook: &str) -> Result<()> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(hook)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| GzClaudeError::Hook(format!("Failed to run '{}': {}", hook, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.lines().last().unwrap_or("").trim();
+        return Err(GzClaudeError::Hook(if detail.is_empty() {
+            format!("'{}' exited with {}", hook, output.status)
+        } else {
+            format!("'{}' failed: {}", hook, detail)
+        }));
+    }
+
+    Ok(())
+}
+
+/// Runs a hook command on a background thread.
+///
+/// # Arguments
+///
+/// * `cwd` - The working directory for the hook
+/// * `hook` - The shell command to execute
+///
+/// # Returns
+///
+/// A handle that yields the hook result once the command finishes.
+pub fn spawn_hook(cwd: PathBuf, hook: String) -> JoinHandle<Result<()>> {
+    thread::spawn(move || run_hook(&cwd, &hook))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_action(pre: Option<&str>, post: Option<&str>, hook_mode: HookMode) -> Action {
+        Action {
+            name: "Claude".to_string(),
+            command: "claude".to_string(),
+            pre: pre.map(String::from),
+            post: post.map(String::from),
+            hook_mode,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn when_wrapping_command_without_hooks_should_return_command() {
+        let action = create_action(None, None, HookMode::Pane);
+
+        assert_eq!(wrap_command(&action, "claude /tmp"), "claude /tmp");
+    }
+
+    #[test]
+    fn when_wrapping_command_with_pane_hooks_should_chain_them() {
+        let action = create_action(Some("git pull"), Some("notify-send done"), HookMode::Pane);
+
+        assert_eq!(
+            wrap_command(&action, "claude /tmp"),
+            "git pull && claude /tmp; notify-send done"
+        );
+    }
+
+    #[test]
+    fn when_wrapping_command_with_background_hooks_should_not_chain_them() {
+        let action = create_action(
+            Some("git pull"),
+            Some("notify-send done"),
+            HookMode::Background,
+        );
+
+        assert_eq!(wrap_command(&action, "claude /tmp"), "claude /tmp");
+    }
+
+    #[test]
+    fn when_chaining_hooks_should_ignore_hook_mode() {
+        let action = create_action(Some("git pull"), None, HookMode::Background);
+
+        assert_eq!(chain_hooks(&action, "make"), "git pull && make");
+    }
+
+    #[test]
+    fn when_running_successful_hook_should_return_ok() {
+        let dir = TempDir::new().unwrap();
+
+        assert!(run_hook(dir.path(), "touch marker").is_ok());
+        assert!(dir.path().join("marker").exists());
+    }
+
+    #[test]
+    fn when_running_failing_hook_should_report_stderr() {
+        let dir = TempDir::new().unwrap();
+
+        let err = run_hook(dir.path(), "echo boom >&2; exit 3").unwrap_err();
+
+        assert!(err.to_string().contains("boom"));
+    }
+}
@@ -0,0 +1,224 @@
+//! Log files of pane output.
+//!
+//! Actions with `log` enabled run their command under `script`, which keeps
+//! the pane a terminal while copying everything it shows to
+//! `~/.gz-claude/logs/<workspace>/<project>/<action>-<timestamp>.log`, next to
+//! the rest of gz-claude's state. The timestamp is
+//! taken by the shell when the command starts, so a restarted pane writes a
+//! new file. Actions run in the background write their output to a file of
+//! the same directory once they finish.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::util::shell::quote;
+
+/// Directory holding the logs of all projects.
+const LOGS_DIR: &str = "logs";
+
+/// Returns the directory holding the logs of a project.
+///
+/// Projects are only unique by name within their workspace, so the
+/// directory is keyed by both.
+///
+/// # Arguments
+///
+/// * `workspace_id` - The identifier of the workspace containing the project
+/// * `project_name` - The project's name
+pub fn project_logs_dir(workspace_id: &str, project_name: &str) -> PathBuf {
+    Config::default_dir()
+        .join(LOGS_DIR)
+        .join(slug(workspace_id))
+        .join(slug(project_name))
+}
+
+/// Turns a name into a file name: lowercase ASCII letters and digits, with
+/// anything else replaced by `-`.
+pub(crate) fn slug(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if slug.is_empty() {
+        "unnamed".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Wraps a command so that its output is also written to a log file.
+///
+/// # Arguments
+///
+/// * `command` - The shell command run in the pane
+/// * `dir` - The directory of the log files
+/// * `name` - What runs, e.g. the action name, used as the file name prefix
+///
+/// # Returns
+///
+/// A shell command creating `dir` and running `command` under `script`.
+pub fn wrap_command(command: &str, dir: &Path, name: &str) -> String {
+    let file = format!(
+        "{}\"$(date +%Y%m%d-%H%M%S)\".log",
+        quote(&format!("{}/{}-", dir.to_string_lossy(), slug(name)))
+    );
+    format!(
+        "mkdir -p {} && {}",
+        quote(&dir.to_string_lossy()),
+        script_command(cfg!(target_os = "macos"), command, &file)
+    )
+}
+
+/// Returns the `script` invocation recording a command to a file.
+///
+/// BSD `script` (macOS) takes the command as arguments, util-linux `script`
+/// takes it with `-c`.
+///
+/// # Arguments
+///
+/// * `bsd` - Whether the BSD syntax is used
+/// * `command` - The shell command to record
+/// * `file` - The log file, as a shell word
+fn script_command(bsd: bool, command: &str, file: &str) -> String {
+    if bsd {
+        format!("script -q -F {} sh -c {}", file, quote(command))
+    } else {
+        format!("script -q -f -e -c {} {}", quote(command), file)
+    }
+}
+
+/// Writes the output of a command run in the background to a new log file.
+///
+/// # Arguments
+///
+/// * `dir` - The directory of the log files
+/// * `name` - What ran, e.g. the action name, used as the file name prefix
+/// * `lines` - The output of the command
+///
+/// # Returns
+///
+/// The path of the log file, `<dir>/<name>-<timestamp>.log`.
+///
+/// # Errors
+///
+/// Returns an error if the directory or the file cannot be written.
+pub fn write_log(dir: &Path, name: &str, lines: &[String]) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let timestamp = Local::now().format("%Y%m%d-%H%M%S");
+    let path = dir.join(format!("{}-{}.log", slug(name), timestamp));
+    let mut content = lines.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Returns the most recently written log file of a directory.
+///
+/// # Arguments
+///
+/// * `dir` - The directory of a project's logs
+///
+/// # Returns
+///
+/// The newest `.log` file, None if there are none.
+pub fn latest_log(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log") && path.is_file())
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max()
+        .map(|(_, path)| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn when_naming_log_files_should_slug_names() {
+        assert_eq!(slug("Dev Server"), "dev-server");
+        assert_eq!(slug("api/v2"), "api-v2");
+        assert_eq!(slug("  "), "unnamed");
+    }
+
+    #[test]
+    fn when_projects_share_a_name_should_keep_their_logs_apart() {
+        let work = project_logs_dir("work", "API");
+        let personal = project_logs_dir("personal", "API");
+
+        assert_ne!(work, personal);
+        assert!(work.ends_with("logs/work/api"));
+    }
+
+    #[test]
+    fn when_wrapping_command_should_record_it_with_script() {
+        let command = wrap_command("npm run dev", Path::new("/logs/api"), "Dev Server");
+
+        assert!(command.starts_with("mkdir -p '/logs/api' && script "));
+        assert!(command.contains("'npm run dev'"));
+        assert!(command.contains("'/logs/api/dev-server-'\"$(date +%Y%m%d-%H%M%S)\".log"));
+    }
+
+    #[test]
+    fn when_using_bsd_or_linux_script_should_pass_command_accordingly() {
+        assert_eq!(
+            script_command(false, "make test", "out.log"),
+            "script -q -f -e -c 'make test' out.log"
+        );
+        assert_eq!(
+            script_command(true, "make test", "out.log"),
+            "script -q -F out.log sh -c 'make test'"
+        );
+    }
+
+    #[test]
+    fn when_writing_log_should_create_a_file_named_after_the_action() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = dir.path().join("api");
+
+        let path = write_log(&logs, "Git Fetch", &["one".to_string(), "two".to_string()]).unwrap();
+
+        assert!(path.starts_with(&logs));
+        let file_name = path.file_name().unwrap().to_string_lossy();
+        assert!(file_name.starts_with("git-fetch-") && file_name.ends_with(".log"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+    }
+
+    #[test]
+    fn when_looking_for_latest_log_should_return_newest_log_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(latest_log(dir.path()), None);
+
+        let old = dir.path().join("dev-20240101-100000.log");
+        let new = dir.path().join("tests-20240102-100000.log");
+        fs::write(&old, "old").unwrap();
+        fs::write(&new, "new").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a log").unwrap();
+        let file = fs::File::options().write(true).open(&old).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+
+        assert_eq!(latest_log(dir.path()), Some(new));
+        assert_eq!(latest_log(&dir.path().join("missing")), None);
+    }
+}
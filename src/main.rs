@@ -2,35 +2,182 @@
 //!
 //! @author waabox(waabox[at]gmail[dot]com)
 
+mod api;
+mod arguments;
+mod claude;
 mod cli;
 mod config;
 mod error;
 mod git;
+mod health;
+mod hooks;
+mod logs;
+mod mcp;
+mod notify;
+mod recent;
+mod remote;
+mod secrets;
 mod session;
+mod top_bar;
 mod tui;
+mod util;
 mod zellij;
 
 use clap::Parser;
-use cli::{Cli, Command};
-use config::Config;
+use cli::{
+    Cli, Command, ConfigAction, ImportArgs, LayoutAction, SecretAction, SessionAction,
+    WorkspaceAction,
+};
+use config::{Config, SessionPolicy, WebClientConfig};
+use std::net::IpAddr;
+
+/// Environment variable telling the panel started by the layout to dry run.
+const DRY_RUN_ENV: &str = "GZ_CLAUDE_DRY_RUN";
 
 fn main() {
     let cli = Cli::parse();
 
+    // Config::default_path reads the environment, and so do the panel and top
+    // bar started by the layout; relative paths would move with their cwd
+    if let Some(path) = &cli.config {
+        let path = std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.clone());
+        std::env::set_var(config::CONFIG_ENV, path);
+    }
+
     match cli.command {
-        Some(Command::Panel) => {
-            run_panel();
+        Some(Command::Panel { fast }) => {
+            run_panel(fast, cli.dry_run);
         }
         Some(Command::TopBar) => {
             run_top_bar();
         }
+        Some(Command::Up { workspace }) => {
+            run_up(&workspace, cli.dry_run);
+        }
+        Some(Command::Clone { project }) => {
+            run_clone(project.as_deref());
+        }
+        Some(Command::Import(args)) => {
+            run_import(args, cli.dry_run);
+        }
+        Some(Command::Workspace { action }) => {
+            run_workspace(action, cli.dry_run);
+        }
+        Some(Command::Config { action }) => {
+            run_config(action);
+        }
+        Some(Command::Layout { action }) => {
+            run_layout(action, cli.dry_run);
+        }
+        Some(Command::Session { action }) => {
+            run_session(action);
+        }
+        Some(Command::Secret { action }) => {
+            run_secret(action);
+        }
         None => {
-            run_main(cli.web, cli.no_web);
+            run_main(cli.web, cli.no_web, cli.session_policy, cli.dry_run);
+        }
+    }
+}
+
+/// Starts the Zellij web server (or reuses a running one) and saves its URL.
+///
+/// If the configured port is busy, a running Zellij web server on it is reused;
+/// otherwise the next free port is used and reflected in the saved URL.
+///
+/// # Arguments
+///
+/// * `web_client` - The `web_client` configuration
+///
+/// # Returns
+///
+/// The child process of a newly started server, or None if reused or failed.
+fn start_web_client(web_client: &WebClientConfig) -> Option<std::process::Child> {
+    let preferred_port = web_client.port;
+    // Validated with the rest of the configuration
+    let Ok(mut bind_address) = web_client.bind_address.parse::<IpAddr>() else {
+        return None;
+    };
+
+    let tls = match zellij::resolve_tls(&web_client.tls) {
+        Ok(tls) => tls,
+        Err(e) => {
+            // The token would go out unencrypted, so keep it off the network
+            bind_address = zellij::plain_http_address(bind_address);
+            eprintln!(
+                "Warning: Failed to set up TLS for the web client, serving plain http on {}: {}",
+                bind_address, e
+            );
+            None
+        }
+    };
+    let use_ssl = tls.is_some();
+
+    let web_port = match zellij::find_web_port(bind_address, preferred_port, use_ssl) {
+        Ok(web_port) => web_port,
+        Err(e) => {
+            eprintln!("Warning: Failed to start web server: {}", e);
+            return None;
+        }
+    };
+
+    if web_port.port != preferred_port && !web_port.reuse_existing {
+        eprintln!(
+            "Warning: Port {} is in use, using port {} for the web client",
+            preferred_port, web_port.port
+        );
+    }
+
+    let child = if web_port.reuse_existing {
+        println!("Reusing Zellij web server on port {}", web_port.port);
+        None
+    } else {
+        match zellij::start_web_server(bind_address, web_port.port, tls.as_ref()) {
+            Ok(child) => {
+                // Wait a moment for the server to be ready
+                std::thread::sleep(std::time::Duration::from_millis(500));
+                Some(child)
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to start web server: {}", e);
+                return None;
+            }
+        }
+    };
+
+    // Create a token for the running server
+    match zellij::create_web_token() {
+        Ok(token) => {
+            let url = zellij::web_url(bind_address, web_port.port, &token, use_ssl);
+            // Save URL for top bar to display
+            if let Err(e) = zellij::save_web_url(&url) {
+                eprintln!("Warning: Failed to save web URL: {}", e);
+            }
+            println!("Web client: {}", url);
+        }
+        Err(e) => {
+            eprintln!("Warning: Failed to create web token: {}", e);
         }
     }
+
+    child
 }
 
-fn run_main(force_web: bool, force_no_web: bool) {
+fn run_main(
+    force_web: bool,
+    force_no_web: bool,
+    session_policy: Option<SessionPolicy>,
+    dry_run: bool,
+) {
+    // Starting Zellij from inside Zellij would nest sessions
+    if std::env::var("ZELLIJ").is_ok() {
+        open_panel_in_current_session(dry_run);
+        return;
+    }
+
     // Check if Zellij is installed
     if !zellij::is_zellij_installed() {
         eprintln!(
@@ -79,6 +226,38 @@ fn run_main(force_web: bool, force_no_web: bool) {
         std::process::exit(1);
     }
 
+    for warning in config.lint() {
+        eprintln!("Warning: {}", warning);
+    }
+
+    let uncloned = config.projects().iter().filter(|p| p.needs_clone()).count();
+    if uncloned > 0 {
+        println!(
+            "{} project(s) not cloned yet: select them in the panel or run 'gz-claude clone'",
+            uncloned
+        );
+    }
+
+    // Decide how to join or start the session before starting anything
+    let zellij_config = &config.global.zellij;
+    let existing = zellij::list_sessions().unwrap_or_default();
+    let policy = session_policy.unwrap_or(zellij_config.session_policy);
+    let policy = if policy == SessionPolicy::Ask && existing.contains(&zellij_config.session_name) {
+        ask_session_policy(&zellij_config.session_name)
+    } else {
+        policy
+    };
+    let plan = zellij::plan_session(policy, &zellij_config.session_name, &existing);
+
+    if let zellij::SessionPlan::Attach(name) = &plan {
+        println!("Attaching to the running session '{}'", name);
+        if let Err(e) = zellij::attach_session(name) {
+            eprintln!("Error attaching to Zellij session: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Determine web client behavior
     let start_web = if force_web {
         true
@@ -91,62 +270,167 @@ fn run_main(force_web: bool, force_no_web: bool) {
     // Clear any previous web URL
     let _ = zellij::clear_web_url();
 
-    // Ensure SSL certificates exist for network access
-    if let Err(e) = zellij::ensure_ssl_certs() {
-        eprintln!("Warning: Failed to generate SSL certificates: {}", e);
-    }
-
     // Start web server if enabled
     let _web_child = if start_web {
-        match zellij::start_web_server(&config.web_client.bind_address, config.web_client.port) {
-            Ok((child, use_ssl)) => {
-                // Wait a moment for the server to be ready
-                std::thread::sleep(std::time::Duration::from_millis(500));
+        start_web_client(&config.web_client)
+    } else {
+        None
+    };
 
-                // Create a token after starting the server
-                match zellij::create_web_token() {
-                    Ok(token) => {
-                        let url = zellij::web_url(config.web_client.port, &token, use_ssl);
-                        // Save URL for top bar to display
-                        if let Err(e) = zellij::save_web_url(&url) {
-                            eprintln!("Warning: Failed to save web URL: {}", e);
-                        }
-                        println!("Web client: {}", url);
-                    }
-                    Err(e) => {
-                        eprintln!("Warning: Failed to create web token: {}", e);
-                    }
-                }
-                Some(child)
-            }
-            Err(e) => {
-                eprintln!("Warning: Failed to start web server: {}", e);
-                None
+    // Generate the Zellij layout, keeping one edited by hand
+    match zellij::generate_layout(&config.layout) {
+        Ok(zellij::LayoutStatus::Edited) => println!(
+            "Keeping the edited layout {}; 'gz-claude layout inspect' shows what gz-claude \
+             would change",
+            zellij::layout_path().display()
+        ),
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Error generating Zellij layout: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let session_name = match plan {
+        zellij::SessionPlan::Restart(name) => {
+            println!("Restarting the session '{}'", name);
+            if let Err(e) = zellij::kill_session(&name) {
+                eprintln!("Error killing Zellij session: {}", e);
+                std::process::exit(1);
             }
+            name
         }
-    } else {
-        None
+        zellij::SessionPlan::Create(name) | zellij::SessionPlan::Attach(name) => name,
     };
 
-    // Generate the Zellij layout
-    if let Err(e) = zellij::generate_layout() {
-        eprintln!("Error generating Zellij layout: {}", e);
-        std::process::exit(1);
+    // The panel started by the layout inherits the dry run from the environment
+    if dry_run {
+        std::env::set_var(DRY_RUN_ENV, "1");
     }
 
     // Start Zellij with the gz-claude layout
     // Web server cleanup handled by process exit
-    if let Err(e) = zellij::start_zellij() {
+    if let Err(e) = zellij::start_zellij(&session_name) {
         eprintln!("Error starting Zellij: {}", e);
         std::process::exit(1);
     }
 }
 
+/// Opens the panel in the Zellij session gz-claude was started from.
+///
+/// Starting Zellij there would nest sessions, so the panel is offered in a
+/// new pane or tab of the current session instead. Without a terminal to ask
+/// on, the process exits with an error.
+///
+/// # Arguments
+///
+/// * `dry_run` - Whether the panel only shows what it would run
+fn open_panel_in_current_session(dry_run: bool) {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    let current = std::env::var("ZELLIJ_SESSION_NAME").unwrap_or_default();
+    eprintln!(
+        "Warning: already inside the Zellij session '{}'; starting gz-claude \
+         would nest a session in it.",
+        current
+    );
+    if !std::io::stdin().is_terminal() {
+        eprintln!("\nDetach first (Ctrl-o d) and run gz-claude again.");
+        std::process::exit(1);
+    }
+
+    let command = panel_command(dry_run);
+    loop {
+        print!("Open the panel in a new [p]ane, a new [t]ab, or [q]uit? ");
+        let _ = std::io::stdout().flush();
+
+        let mut answer = String::new();
+        if std::io::stdin().lock().read_line(&mut answer).unwrap_or(0) == 0 {
+            std::process::exit(1);
+        }
+        let opened = match answer.trim().to_lowercase().as_str() {
+            "" | "p" | "pane" => zellij::run_in_split_pane("gz-claude", &command, "right"),
+            "t" | "tab" => zellij::run_in_new_tab("gz-claude", &command),
+            "q" | "quit" => std::process::exit(0),
+            _ => continue,
+        };
+        if let Err(e) = opened {
+            eprintln!("Error opening the panel: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+}
+
+/// Builds the shell command running the panel in a pane of the session.
+///
+/// Panes get the environment of the Zellij server rather than this process,
+/// so the configuration path and dry run are passed on the command line.
+///
+/// # Arguments
+///
+/// * `dry_run` - Whether the panel only shows what it would run
+fn panel_command(dry_run: bool) -> String {
+    let program = std::env::current_exe()
+        .map(|path| util::shell::quote(&path.to_string_lossy()))
+        .unwrap_or_else(|_| "gz-claude".to_string());
+    let mut command = String::new();
+    if let Some(path) = std::env::var_os(config::CONFIG_ENV) {
+        command.push_str(&format!(
+            "{}={} ",
+            config::CONFIG_ENV,
+            util::shell::quote(&path.to_string_lossy())
+        ));
+    }
+    if dry_run {
+        command.push_str(&format!("{}=1 ", DRY_RUN_ENV));
+    }
+    command.push_str(&format!("{} panel", program));
+    command
+}
+
+/// Asks what to do with an existing gz-claude session.
+///
+/// Without a terminal to ask on, the session is attached.
+///
+/// # Arguments
+///
+/// * `name` - The name of the existing session
+///
+/// # Returns
+///
+/// The chosen policy; quitting exits the process.
+fn ask_session_policy(name: &str) -> SessionPolicy {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        return SessionPolicy::Attach;
+    }
+
+    loop {
+        print!(
+            "The Zellij session '{}' is already running.\n\
+             [a]ttach, [r]estart, start a [n]ew session, or [q]uit? ",
+            name
+        );
+        let _ = std::io::stdout().flush();
+
+        let mut answer = String::new();
+        if std::io::stdin().lock().read_line(&mut answer).unwrap_or(0) == 0 {
+            std::process::exit(1);
+        }
+        match answer.trim().to_lowercase().as_str() {
+            "" | "a" | "attach" => return SessionPolicy::Attach,
+            "r" | "restart" => return SessionPolicy::Restart,
+            "n" | "new" => return SessionPolicy::New,
+            "q" | "quit" => std::process::exit(0),
+            _ => {}
+        }
+    }
+}
+
 fn run_top_bar() {
-    use crossterm::{
-        event::{self, Event, KeyCode, KeyEvent},
-        terminal::{disable_raw_mode, enable_raw_mode},
-    };
+    use crossterm::event::{self, Event, KeyCode, KeyEvent};
     use std::io::Write;
     use std::time::Duration;
 
@@ -159,93 +443,67 @@ fn run_top_bar() {
         std::process::exit(1);
     }
 
-    // Get session name from environment
+    // The panel reports configuration errors; the bar falls back to its defaults
+    let config = Config::load().ok();
+    let top_bar_config = config
+        .as_ref()
+        .map(|c| c.top_bar.clone())
+        .unwrap_or_default();
+    util::glyphs::configure(config.as_ref().is_some_and(|c| c.ui.ascii_only));
     let session_name = std::env::var("ZELLIJ_SESSION_NAME").ok();
+    let mut bar = top_bar::TopBar::new(&top_bar_config, session_name.clone());
 
-    // Load URL once at startup, with a few retries
-    let mut url: Option<String> = None;
-    for _ in 0..10 {
-        if let Some(u) = zellij::load_web_url() {
-            // Insert session name into URL if available
-            if let Some(ref session) = session_name {
-                // URL format: https://host:port/?token=xxx
-                // Change to: https://host:port/session?token=xxx
-                if let Some(pos) = u.find("/?token=") {
-                    let (base, token_part) = u.split_at(pos);
-                    url = Some(format!("{}/{}?token={}", base, session, &token_part[8..]));
-                } else {
-                    url = Some(u);
-                }
-            } else {
-                url = Some(u);
+    // The pane picker lets the web client choose the pane it shows
+    let web_client = config.as_ref().map(|c| &c.web_client);
+    let picker = web_client.and_then(|web_client| {
+        let port = web_client.picker_port?;
+        let bind_address = web_client.bind_address.parse::<std::net::IpAddr>().ok()?;
+        Some((bind_address, port))
+    });
+    if let (Some((bind_address, port)), Some(session)) = (picker, session_name) {
+        match top_bar::picker::spawn(bind_address, port, session) {
+            // Only shown when it's reachable from where the web client is
+            Ok(address) if address.ip() == bind_address => {
+                bar = bar.with_picker(address.ip(), port)
             }
-            break;
+            Ok(address) => eprintln!(
+                "Warning: the pane picker has no TLS, so it only listens on {}; the top bar keeps the web client's URL",
+                address
+            ),
+            Err(e) => eprintln!("Warning: could not start the pane picker: {}", e),
         }
-        std::thread::sleep(Duration::from_millis(500));
-    }
-
-    // Save the updated URL with session name
-    if let Some(ref u) = url {
-        let _ = zellij::save_web_url(u);
     }
 
-    // Enable raw mode for keyboard input
-    let _ = enable_raw_mode();
+    // Raw mode for keyboard input, disabled when the guard drops: on quit,
+    // and if the loop panics
+    let _guard = tui::TerminalGuard::raw_mode().ok();
 
-    let mut copied_message_until: Option<std::time::Instant> = None;
     let mut needs_redraw = true;
 
     loop {
+        needs_redraw |= bar.refresh(config.as_ref());
+
         if needs_redraw {
             // Clear screen and move to beginning
-            print!("\x1B[2J\x1B[H");
-
-            // Check if we should show "Copied!" message
-            let show_copied = copied_message_until
-                .map(|t| std::time::Instant::now() < t)
-                .unwrap_or(false);
-
-            if let Some(ref u) = url {
-                if show_copied {
-                    print!(" 🌐 {}  ✅ Copied! ", u);
-                } else {
-                    print!(" 🌐 {}  [c] copy ", u);
-                }
-            } else {
-                print!(" gz-claude ");
-            }
-
-            // Flush output
+            print!("\x1B[2J\x1B[H {} ", bar.render());
             let _ = std::io::stdout().flush();
             needs_redraw = false;
         }
 
-        // Check if copied message should expire
-        if let Some(t) = copied_message_until {
-            if std::time::Instant::now() >= t {
-                copied_message_until = None;
-                needs_redraw = true;
-            }
-        }
-
         // Poll for keyboard events (non-blocking with 200ms timeout)
         if event::poll(Duration::from_millis(200)).unwrap_or(false) {
             if let Ok(Event::Key(KeyEvent { code, .. })) = event::read() {
                 match code {
-                    KeyCode::Char('c') => {
+                    KeyCode::Char('c') | KeyCode::Char('y') => {
                         // Copy URL to clipboard
-                        if let Some(ref u) = url {
-                            if zellij::copy_to_clipboard(u).is_ok() {
-                                copied_message_until =
-                                    Some(std::time::Instant::now() + Duration::from_secs(2));
+                        if let Some(url) = bar.web_url() {
+                            if util::copy_to_clipboard(url).is_ok() {
+                                bar.show_copied();
                                 needs_redraw = true;
                             }
                         }
                     }
-                    KeyCode::Char('q') => {
-                        let _ = disable_raw_mode();
-                        break;
-                    }
+                    KeyCode::Char('q') => break,
                     _ => {}
                 }
             }
@@ -253,7 +511,792 @@ fn run_top_bar() {
     }
 }
 
-fn run_panel() {
+/// Opens the working set of a workspace in its own Zellij session.
+///
+/// Generates `gz-claude-<workspace>.kdl` with one tab per project of the
+/// working set and starts the session `<session_name>-<workspace>` with it,
+/// or attaches to that session if it is already running.
+///
+/// # Arguments
+///
+/// * `workspace_id` - The workspace id, as in the configuration
+/// * `dry_run` - Print the layout instead of starting Zellij
+fn run_up(workspace_id: &str, dry_run: bool) {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error loading configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let Some(workspace) = config.workspace.get(workspace_id) else {
+        eprintln!("Error: no workspace '{}'", workspace_id);
+        std::process::exit(1);
+    };
+    let projects = match workspace.working_set_projects() {
+        Ok(projects) => projects,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let autostart = match config.autostart_actions(workspace_id) {
+        Ok(autostart) => autostart,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if projects.is_empty() && autostart.is_empty() {
+        eprintln!(
+            "Error: workspace '{}' has no working set; list its projects under 'working_set' \
+             or set 'autostart' on them",
+            workspace_id
+        );
+        std::process::exit(1);
+    }
+
+    let mut tabs: Vec<zellij::LayoutTab> = projects
+        .into_iter()
+        .map(|(project, run)| {
+            let program = match run {
+                config::WorkingSetRun::Claude => "claude".to_string(),
+                config::WorkingSetRun::Shell => config.global.shell_program(),
+            };
+            // Tabs start in the project, so the wrapper is given `.`
+            let command = match project.env_wrapper(".") {
+                Some(wrapper) => format!("{} {}", wrapper, program),
+                None => program,
+            };
+            zellij::LayoutTab {
+                name: project.name.clone(),
+                cwd: project.path.clone(),
+                command,
+                script: None,
+            }
+        })
+        .collect();
+    for (index, project, action) in autostart {
+        match autostart_tab(&config, workspace_id, index, project, &action, !dry_run) {
+            Ok(tab) => tabs.push(tab),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if dry_run {
+        print!("{}", zellij::render_working_set_layout(&tabs));
+        return;
+    }
+
+    if std::env::var("ZELLIJ").is_ok() {
+        eprintln!("Error: already inside a Zellij session; detach first (Ctrl-o d)");
+        std::process::exit(1);
+    }
+
+    let session_name = format!("{}-{}", config.global.zellij.session_name, workspace_id);
+    let running = zellij::list_sessions().unwrap_or_default();
+    if running.contains(&session_name) {
+        println!("Attaching to the running session '{}'", session_name);
+        if let Err(e) = zellij::attach_session(&session_name) {
+            eprintln!("Error attaching to Zellij session: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let layout = match zellij::generate_working_set_layout(workspace_id, &tabs) {
+        Ok(layout) => layout,
+        Err(e) => {
+            eprintln!("Error generating Zellij layout: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = zellij::start_zellij_with_layout(&session_name, &layout) {
+        eprintln!("Error starting Zellij: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Builds the tab running a project's default action for `gz-claude up`.
+///
+/// The command is built as the panel runs it in a pane, with its hooks
+/// chained around it since nothing runs them outside of the tab.
+///
+/// # Arguments
+///
+/// * `config` - The configuration
+/// * `workspace_id` - The workspace of the project
+/// * `index` - The index of the project within the workspace
+/// * `project` - The project
+/// * `action` - Its default action
+/// * `write` - Whether to write Claude's `--mcp-config` file, false in a dry run
+fn autostart_tab(
+    config: &Config,
+    workspace_id: &str,
+    index: usize,
+    project: &config::Project,
+    action: &config::Action,
+    write: bool,
+) -> error::Result<zellij::LayoutTab> {
+    let dir = project.action_dir(action)?;
+    let command = project.expand_bookmarks(&action.command)?;
+    let command = project.claude_command(action, &command)?;
+    let command = mcp::prepare_command(config, workspace_id, index, &command, write)?;
+    let mut script = hooks::chain_hooks(action, &format!("{} {}", command, dir.display()));
+    if action.log {
+        let logs_dir = logs::project_logs_dir(workspace_id, &project.name);
+        script = logs::wrap_command(&script, &logs_dir, &action.name);
+    }
+    // Tabs start in the project, so the wrapper is given `.`
+    let command = match project.env_wrapper(".") {
+        Some(wrapper) => format!("{} sh -c", wrapper),
+        None => "sh -c".to_string(),
+    };
+    Ok(zellij::LayoutTab {
+        name: format!("{} · {}", project.name, action.name),
+        cwd: dir,
+        command,
+        script: Some(script),
+    })
+}
+
+/// Clones the projects whose directory doesn't exist, then validates the
+/// configuration again.
+///
+/// # Arguments
+///
+/// * `name` - Only clone the project with this name
+fn run_clone(name: Option<&str>) {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error loading configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let projects: Vec<&config::Project> = config
+        .projects()
+        .into_iter()
+        .filter(|p| name.map_or(true, |name| p.name == name))
+        .collect();
+    if let Some(name) = name {
+        if projects.is_empty() {
+            eprintln!("Error: no project named '{}'", name);
+            std::process::exit(1);
+        }
+    }
+
+    let mut failed = false;
+    let mut cloned = 0;
+    for project in projects.into_iter().filter(|p| p.needs_clone()) {
+        let Some(repo) = project.repo.as_deref() else {
+            continue;
+        };
+        if clone_project(&project.name, repo, &project.path) {
+            cloned += 1;
+        } else {
+            failed = true;
+        }
+    }
+
+    if cloned == 0 && !failed {
+        println!("Nothing to clone");
+    }
+
+    // Refresh validation now that the directories exist
+    if let Err(e) = config.validate() {
+        eprintln!("Error: Invalid configuration\n\n{}", e);
+        failed = true;
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Clones a project's repository, showing git's progress.
+///
+/// # Returns
+///
+/// True if the clone succeeded; the error is printed otherwise.
+fn clone_project(name: &str, repo: &str, path: &std::path::Path) -> bool {
+    println!("Cloning {} ({}) into {}", name, repo, path.display());
+    let result = git::clone::clone_repo(repo, path, |progress| {
+        eprint!("\r  {:<40}", progress.label());
+    });
+    eprint!("\r{:<42}\r", "");
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("  Error: {}", e);
+            false
+        }
+    }
+}
+
+/// Creates a workspace from the repositories of an organization or group.
+///
+/// Lists the repositories, asks which ones to import unless `--all` is
+/// given, writes the workspace with one project per repository, then clones
+/// the ones whose directory doesn't exist. Projects keep their `repo`, so
+/// failed clones can be retried with `gz-claude clone`.
+fn run_import(args: ImportArgs, dry_run: bool) {
+    use std::io::{BufRead, Write};
+
+    let config_path = Config::default_path();
+    let config = match Config::load_from(&config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error loading configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let org = args.org.trim_matches('/');
+    let workspace_id = args.workspace.unwrap_or_else(|| org.to_lowercase());
+    if config.workspace.contains_key(&workspace_id) {
+        eprintln!(
+            "Error: workspace '{}' already exists; choose another id with --workspace",
+            workspace_id
+        );
+        std::process::exit(1);
+    }
+
+    let repos = match remote::import::list_repos(args.provider, org) {
+        Ok(repos) => repos,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let repos: Vec<_> = repos
+        .into_iter()
+        .filter(|repo| args.archived || !repo.archived)
+        .collect();
+    if repos.is_empty() {
+        println!("No repositories found for '{}'", org);
+        return;
+    }
+
+    let selected = if args.all {
+        (0..repos.len()).collect()
+    } else {
+        for (number, repo) in repos.iter().enumerate() {
+            if repo.description.is_empty() {
+                println!("{:>4}. {}", number + 1, repo.name);
+            } else {
+                println!("{:>4}. {} - {}", number + 1, repo.name, repo.description);
+            }
+        }
+        eprint!("Repositories to import (e.g. 1 3 5-7, all): ");
+        let _ = std::io::stderr().flush();
+        let mut input = String::new();
+        if let Err(e) = std::io::stdin().lock().read_line(&mut input) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        match remote::import::parse_selection(&input, repos.len()) {
+            Ok(selected) => selected,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    };
+    if selected.is_empty() {
+        println!("Nothing imported");
+        return;
+    }
+
+    let root = match args.root {
+        Some(root) => util::path::expand_tilde(&root.to_string_lossy()),
+        None => std::env::current_dir()
+            .unwrap_or_default()
+            .join(org.rsplit('/').next().unwrap_or(org)),
+    };
+    let projects: Vec<config::NewProject> = selected
+        .into_iter()
+        .map(|index| {
+            let repo = &repos[index];
+            let url = if args.https || repo.ssh_url.is_empty() {
+                &repo.https_url
+            } else {
+                &repo.ssh_url
+            };
+            config::NewProject {
+                name: repo.name.clone(),
+                path: root.join(&repo.name),
+                description: Some(repo.description.clone()).filter(|d| !d.is_empty()),
+                repo: url.clone(),
+            }
+        })
+        .collect();
+
+    if dry_run {
+        println!(
+            "Would add workspace '{}' with {} project(s) to {}:",
+            workspace_id,
+            projects.len(),
+            config_path.display()
+        );
+        for project in &projects {
+            let source = if project.path.exists() {
+                "existing directory".to_string()
+            } else {
+                format!("clone {}", project.repo)
+            };
+            println!(
+                "  {}: {} ({})",
+                project.name,
+                project.path.display(),
+                source
+            );
+        }
+        return;
+    }
+
+    if let Err(e) = Config::add_workspace(&config_path, &workspace_id, org, &projects) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    println!(
+        "Added workspace '{}' with {} project(s) to {}",
+        workspace_id,
+        projects.len(),
+        config_path.display()
+    );
+
+    let mut failed = false;
+    for project in projects.iter().filter(|p| !p.path.exists()) {
+        failed |= !clone_project(&project.name, &project.repo, &project.path);
+    }
+    if failed {
+        eprintln!("Run 'gz-claude clone' to retry the failed clones.");
+        std::process::exit(1);
+    }
+}
+
+/// Exports a workspace as a snippet to share, or merges a shared snippet
+/// into the configuration.
+///
+/// Conflicts are asked about one by one, keeping the local definition unless
+/// answered `y`; `--yes` takes every imported definition.
+fn run_workspace(action: WorkspaceAction, dry_run: bool) {
+    use std::io::{BufRead, Write};
+
+    let config_path = Config::default_path();
+    match action {
+        WorkspaceAction::Export {
+            workspace,
+            root,
+            output,
+        } => {
+            let snippet = match Config::export_workspace(&config_path, &workspace, root.as_deref())
+            {
+                Ok(snippet) => snippet,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            match output {
+                None => print!("{}", snippet),
+                Some(output) => {
+                    if let Err(e) = std::fs::write(&output, &snippet) {
+                        eprintln!("Error writing {}: {}", output.display(), e);
+                        std::process::exit(1);
+                    }
+                    println!("Exported workspace '{}' to {}", workspace, output.display());
+                }
+            }
+        }
+        WorkspaceAction::Import { file, root, yes } => {
+            let root = match root {
+                Some(root) => util::path::expand_tilde(&root.to_string_lossy()),
+                None => std::env::current_dir().unwrap_or_default(),
+            };
+            let resolve = |conflict: &config::ImportConflict| {
+                if yes {
+                    return config::ConflictChoice::TakeImported;
+                }
+                eprint!("{}. Take the imported one? [y/N] ", conflict);
+                let _ = std::io::stderr().flush();
+                let mut answer = String::new();
+                let _ = std::io::stdin().lock().read_line(&mut answer);
+                if answer.trim().eq_ignore_ascii_case("y") {
+                    config::ConflictChoice::TakeImported
+                } else {
+                    config::ConflictChoice::KeepLocal
+                }
+            };
+            let summaries =
+                match Config::import_workspace(&config_path, &file, &root, dry_run, resolve) {
+                    Ok(summaries) => summaries,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+            let verb = if dry_run { "Would import" } else { "Imported" };
+            for summary in &summaries {
+                let mut changes = Vec::new();
+                if summary.created {
+                    changes.push("new workspace".to_string());
+                }
+                if summary.settings_replaced {
+                    changes.push("settings replaced".to_string());
+                }
+                for (label, projects) in [
+                    ("added", &summary.added),
+                    ("replaced", &summary.replaced),
+                    ("kept", &summary.kept),
+                ] {
+                    if !projects.is_empty() {
+                        changes.push(format!("{} {}", label, projects.join(", ")));
+                    }
+                }
+                if changes.is_empty() {
+                    changes.push("already up to date".to_string());
+                }
+                println!(
+                    "{} workspace '{}': {}",
+                    verb,
+                    summary.workspace_id,
+                    changes.join("; ")
+                );
+            }
+        }
+    }
+}
+
+fn run_config(action: ConfigAction) {
+    match action {
+        ConfigAction::Convert { output, from } => {
+            let input = from.unwrap_or_else(Config::default_path);
+            if let Err(e) = Config::convert(&input, &output) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            println!("Converted {} to {}", input.display(), output.display());
+
+            // Only one configuration in the default directory is used
+            let in_use = Config::default_path();
+            let overridden = std::env::var_os(config::CONFIG_ENV).is_some();
+            if !overridden && output.parent() == in_use.parent() && output != in_use {
+                println!(
+                    "{} takes precedence; remove it to use the converted file",
+                    in_use.display()
+                );
+            }
+        }
+        ConfigAction::Migrate { file } => {
+            let path = file.unwrap_or_else(Config::default_path);
+            match Config::migrate_file(&path) {
+                Ok(Some(old_version)) => println!(
+                    "Upgraded {} from version {} to {}",
+                    path.display(),
+                    old_version,
+                    config::CONFIG_VERSION
+                ),
+                Ok(None) => println!("{} is up to date", path.display()),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        ConfigAction::Lint { file } => {
+            let path = file.unwrap_or_else(Config::default_path);
+            let config = match Config::load_from(&path) {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("Error loading configuration: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            if let Err(e) = config.validate_structure() {
+                eprintln!("Error: Invalid configuration\n\n{}", e);
+                std::process::exit(1);
+            }
+
+            let warnings = config.lint();
+            for warning in &warnings {
+                println!("warning: {}", warning);
+            }
+            if warnings.is_empty() {
+                println!("No issues found in {}", path.display());
+            } else {
+                println!("{} warning(s) in {}", warnings.len(), path.display());
+            }
+        }
+    }
+}
+
+fn run_layout(action: LayoutAction, dry_run: bool) {
+    use zellij::LayoutStatus;
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error loading configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let generated = zellij::layout_file(&config.layout);
+    let path = zellij::layout_path();
+    let installed = std::fs::read_to_string(&path).ok();
+    let status = zellij::layout_status(installed.as_deref(), &config.layout);
+    let print_diff = || {
+        if let Some(diff) = installed
+            .as_deref()
+            .and_then(|installed| util::diff::unified(installed, &generated, 3))
+        {
+            println!("--- {}\n+++ generated", path.display());
+            print!("{}", diff);
+        }
+    };
+
+    match action {
+        LayoutAction::Dump { output: None } => {
+            print!("{}", generated);
+        }
+        LayoutAction::Dump {
+            output: Some(output),
+        } => {
+            if let Err(e) = std::fs::write(&output, &generated) {
+                eprintln!("Error writing {}: {}", output.display(), e);
+                std::process::exit(1);
+            }
+            println!("Wrote the layout to {}", output.display());
+        }
+        LayoutAction::Inspect => match status {
+            LayoutStatus::Missing => println!(
+                "{} is not installed; 'gz-claude layout regenerate' writes it",
+                path.display()
+            ),
+            LayoutStatus::Current => {
+                println!("{} matches the generated layout", path.display())
+            }
+            LayoutStatus::Outdated => {
+                println!(
+                    "{} is outdated and is regenerated on the next start",
+                    path.display()
+                );
+                print_diff();
+            }
+            LayoutStatus::Edited => {
+                println!(
+                    "{} was edited and is kept; 'gz-claude layout regenerate --force' \
+                     overwrites it",
+                    path.display()
+                );
+                print_diff();
+            }
+        },
+        LayoutAction::Regenerate { force } => {
+            if status == LayoutStatus::Current {
+                println!("{} is up to date", path.display());
+                return;
+            }
+            if status == LayoutStatus::Edited && !force {
+                print_diff();
+                eprintln!(
+                    "Error: {} was edited; use --force to overwrite it",
+                    path.display()
+                );
+                std::process::exit(1);
+            }
+            if dry_run {
+                print_diff();
+                println!("Would write {}", path.display());
+                return;
+            }
+            match zellij::write_layout(&config.layout) {
+                Ok(path) => println!("Wrote {}", path.display()),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+fn run_session(action: SessionAction) {
+    use session::{PaneTarget, ReplayStep, Session, SessionSnapshot};
+
+    match action {
+        SessionAction::Export { file, session } => {
+            let name = session.unwrap_or_else(Session::current_name);
+            let state = Session::load(&name).unwrap_or_else(|| Session::new(name.clone()));
+            let snapshot = SessionSnapshot::of(&state);
+            if let Err(e) = snapshot.write(&file) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            println!(
+                "Exported {} panes of session '{}' to {}",
+                snapshot.panes.len(),
+                name,
+                file.display()
+            );
+        }
+        SessionAction::Import { file } => {
+            let snapshot = match SessionSnapshot::read(&file)
+                .and_then(|snapshot| zellij::ensure_in_session().map(|()| snapshot))
+            {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let name = Session::current_name();
+            let mut state = Session::load(&name).unwrap_or_else(|| Session::new(name));
+            zellij::configure_main_pane(
+                state.main_pane.clone(),
+                state.main_pane_name().map(str::to_string),
+            );
+            let main_runs_shell = Config::load()
+                .map(|config| config.layout.main_runs_shell())
+                .unwrap_or(true);
+            let main_free = main_runs_shell && state.panes.values().all(|pane| pane.floating);
+
+            let mut failed = false;
+            for step in snapshot.replay_plan(&state, main_free, |path| path.is_dir()) {
+                let (pane, target) = match step {
+                    ReplayStep::Open(pane, target) => (pane, target),
+                    ReplayStep::MissingProject(pane) => {
+                        eprintln!(
+                            "Skipped {}: {} does not exist",
+                            pane.pane_name,
+                            pane.project_path.display()
+                        );
+                        continue;
+                    }
+                    ReplayStep::AlreadyOpen(pane) => {
+                        println!("Skipped {}: its project already has a pane", pane.pane_name);
+                        continue;
+                    }
+                };
+                let opened = match target {
+                    PaneTarget::Main => zellij::run_in_main_pane(&pane.pane_name, &pane.command)
+                        .map(|()| {
+                            state.register_pane(
+                                pane.project_path.clone(),
+                                pane.pane_name.clone(),
+                                pane.command.clone(),
+                            )
+                        }),
+                    PaneTarget::Floating => {
+                        zellij::run_in_floating_pane(&pane.pane_name, &pane.command).map(|id| {
+                            state.register_floating_pane(
+                                pane.project_path.clone(),
+                                pane.pane_name.clone(),
+                                pane.command.clone(),
+                                id,
+                            )
+                        })
+                    }
+                };
+                match opened {
+                    Ok(()) => println!("Opened {}", pane.pane_name),
+                    Err(e) => {
+                        eprintln!("Failed to open {}: {}", pane.pane_name, e);
+                        failed = true;
+                    }
+                }
+            }
+
+            if state.focus.is_none() {
+                state.focus = snapshot.focus;
+            }
+            if let Err(e) = state.save() {
+                eprintln!("Error: {}", e);
+                failed = true;
+            }
+            if failed {
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+fn run_secret(action: SecretAction) {
+    let result = match action {
+        SecretAction::Set { name } => read_secret_value(&name)
+            .and_then(|value| secrets::set_in_keychain(&name, &value).map(|()| name)),
+        SecretAction::Delete { name } => secrets::delete_from_keychain(&name).map(|()| name),
+    };
+
+    match result {
+        Ok(name) => println!("Updated secret '{}' in the keychain", name),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reads a secret value from stdin, without echo when stdin is a terminal.
+fn read_secret_value(name: &str) -> error::Result<String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+    use std::io::{BufRead, IsTerminal};
+
+    if !secrets::is_valid_name(name) {
+        return Err(error::SecretError::InvalidName {
+            name: name.to_string(),
+        }
+        .into());
+    }
+
+    if !std::io::stdin().is_terminal() {
+        let mut value = String::new();
+        std::io::stdin().lock().read_line(&mut value)?;
+        return Ok(value.trim_end_matches(['\r', '\n']).to_string());
+    }
+
+    eprint!("Value for '{}': ", name);
+    let guard = tui::TerminalGuard::raw_mode()?;
+    let mut value = String::new();
+    let outcome = loop {
+        match event::read() {
+            Ok(Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            })) => match code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Esc => break Err(()),
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => break Err(()),
+                KeyCode::Backspace => {
+                    value.pop();
+                }
+                KeyCode::Char(c) => value.push(c),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => return Err(e.into()),
+        }
+    };
+    drop(guard);
+    eprintln!();
+
+    match outcome {
+        Ok(()) => Ok(value),
+        Err(()) => {
+            eprintln!("Cancelled");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_panel(fast: bool, dry_run: bool) {
     // Check if running inside Zellij
     if std::env::var("ZELLIJ").is_err() {
         eprintln!(
@@ -291,8 +1334,13 @@ fn run_panel() {
         }
     };
 
-    // Validate configuration
-    if let Err(e) = config.validate() {
+    // Validate configuration; with --fast, project paths are checked by the TUI
+    let validated = if fast {
+        config.validate_structure()
+    } else {
+        config.validate()
+    };
+    if let Err(e) = validated {
         eprintln!("Error: Invalid configuration\n\n{}", e);
         eprintln!(
             "\nPlease fix the configuration at {}",
@@ -302,7 +1350,8 @@ fn run_panel() {
     }
 
     // Run the TUI
-    if let Err(e) = tui::run(&config) {
+    let dry_run = dry_run || std::env::var_os(DRY_RUN_ENV).is_some();
+    if let Err(e) = tui::run(&config, fast, dry_run) {
         eprintln!("Error running TUI: {}", e);
         std::process::exit(1);
     }
@@ -0,0 +1,423 @@
+//! MCP servers of the next Claude launch.
+//!
+//! The servers of a project come from the `mcp` sections of the
+//! configuration; the panel toggles them per project and remembers the
+//! choice in `~/.gz-claude/mcp.json`, shared by all Zellij sessions. When a
+//! pane running `claude` opens, the enabled servers are written to
+//! `~/.gz-claude/mcp/<project>.json` and Claude is started with
+//! `--mcp-config <file> --strict-mcp-config`, so it gets exactly those.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, McpServer};
+use crate::error::Result;
+use crate::logs;
+use crate::util::shell::quote;
+
+/// File storing the servers toggled in each project.
+const SELECTION_FILE: &str = "mcp.json";
+
+/// Directory holding the generated `--mcp-config` files.
+const CONFIG_DIR: &str = "mcp";
+
+/// Returns whether a command runs Claude Code.
+///
+/// A command counts when its first word is `claude`, as for panes, also
+/// when run through the `sh -c` of a project's env wrapper.
+///
+/// # Arguments
+///
+/// * `command` - The shell command
+pub fn is_claude_command(command: &str) -> bool {
+    let command = command
+        .split_once(" sh -c ")
+        .map_or(command, |(_, wrapped)| wrapped.trim_start_matches('\''));
+    command
+        .split_whitespace()
+        .next()
+        .and_then(|program| Path::new(program).file_name())
+        .is_some_and(|name| name == "claude")
+}
+
+/// Returns the path of the `--mcp-config` file of a project.
+///
+/// # Arguments
+///
+/// * `project_name` - The project's name
+pub fn config_path(project_name: &str) -> PathBuf {
+    Config::default_dir()
+        .join(CONFIG_DIR)
+        .join(format!("{}.json", logs::slug(project_name)))
+}
+
+/// Returns the Claude settings giving the enabled servers.
+///
+/// # Arguments
+///
+/// * `servers` - The enabled servers, by name
+pub fn settings(servers: &[(&str, &McpServer)]) -> serde_json::Value {
+    let entries: serde_json::Map<String, serde_json::Value> = servers
+        .iter()
+        .map(|(name, server)| (name.to_string(), server.to_settings()))
+        .collect();
+    serde_json::json!({ "mcpServers": entries })
+}
+
+/// Writes the settings giving the enabled servers to a file.
+///
+/// # Arguments
+///
+/// * `path` - The file, see `config_path`
+/// * `servers` - The enabled servers, by name
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn write_config(path: &Path, servers: &[(&str, &McpServer)]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content =
+        serde_json::to_string_pretty(&settings(servers)).map_err(std::io::Error::other)?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Passes an `--mcp-config` file to a Claude command.
+///
+/// # Arguments
+///
+/// * `command` - The command, starting with `claude`
+/// * `path` - The file, see `config_path`
+///
+/// # Returns
+///
+/// The command with `--mcp-config <file> --strict-mcp-config` after the
+/// program, or the command unchanged if it doesn't run Claude.
+pub fn with_config(command: &str, path: &Path) -> String {
+    if !is_claude_command(command) {
+        return command.to_string();
+    }
+    let command = command.trim_start();
+    let (program, rest) = command
+        .split_once(char::is_whitespace)
+        .unwrap_or((command, ""));
+    let flags = format!(
+        "--mcp-config {} --strict-mcp-config",
+        quote(&path.to_string_lossy())
+    );
+    if rest.is_empty() {
+        format!("{} {}", program, flags)
+    } else {
+        format!("{} {} {}", program, flags, rest)
+    }
+}
+
+/// Gives a Claude command the MCP servers enabled in a project.
+///
+/// # Arguments
+///
+/// * `config` - The configuration listing the servers
+/// * `workspace_id` - The identifier of the workspace
+/// * `project_index` - The index of the project within the workspace
+/// * `command` - The command about to run in a pane
+/// * `write` - Whether to write the `--mcp-config` file, false in a dry run
+///
+/// # Returns
+///
+/// The command with the project's `--mcp-config` file, or unchanged if it
+/// doesn't run Claude or the project has no servers.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn prepare_command(
+    config: &Config,
+    workspace_id: &str,
+    project_index: usize,
+    command: &str,
+    write: bool,
+) -> Result<String> {
+    let Some(project) = config
+        .workspace
+        .get(workspace_id)
+        .and_then(|w| w.projects.get(project_index))
+    else {
+        return Ok(command.to_string());
+    };
+    let servers = config.resolve_mcp_servers(workspace_id, project_index);
+    if servers.is_empty() || !is_claude_command(command) {
+        return Ok(command.to_string());
+    }
+    let path = config_path(&project.name);
+    if write {
+        let selection = McpSelection::load();
+        write_config(&path, &selection.enabled(&project.path, &servers))?;
+    }
+    Ok(with_config(command, &path))
+}
+
+/// Rewrites the `--mcp-config` file a pane passes to Claude, so a restarted
+/// pane gets the servers enabled since it was launched.
+///
+/// # Arguments
+///
+/// * `config` - The configuration listing the servers
+/// * `workspace_id` - The identifier of the workspace
+/// * `project_index` - The index of the project within the workspace
+/// * `command` - The command of the pane; nothing is written unless it
+///   passes the project's file
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn refresh_config(
+    config: &Config,
+    workspace_id: &str,
+    project_index: usize,
+    command: &str,
+) -> Result<()> {
+    let Some(project) = config
+        .workspace
+        .get(workspace_id)
+        .and_then(|w| w.projects.get(project_index))
+    else {
+        return Ok(());
+    };
+    let path = config_path(&project.name);
+    if !command.contains(&quote(&path.to_string_lossy())) {
+        return Ok(());
+    }
+    let servers = config.resolve_mcp_servers(workspace_id, project_index);
+    let selection = McpSelection::load();
+    write_config(&path, &selection.enabled(&project.path, &servers))
+}
+
+/// Servers toggled in each project, by project path.
+///
+/// Servers that were never toggled keep the `enabled` of the configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpSelection {
+    #[serde(default)]
+    projects: HashMap<String, BTreeMap<String, bool>>,
+}
+
+impl McpSelection {
+    /// Returns the path to the selection store.
+    pub fn default_path() -> PathBuf {
+        Config::default_dir().join(SELECTION_FILE)
+    }
+
+    /// Loads the selection from the default store.
+    ///
+    /// # Returns
+    ///
+    /// The selection, empty if the store doesn't exist or is invalid.
+    pub fn load() -> Self {
+        Self::load_from(&Self::default_path())
+    }
+
+    /// Loads the selection from a file.
+    fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the selection to the default store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::default_path())
+    }
+
+    /// Saves the selection to a file, replacing it atomically.
+    fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Returns whether a server is enabled in a project.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project's directory
+    /// * `name` - The server's name
+    /// * `server` - The server, whose `enabled` applies until it is toggled
+    pub fn is_enabled(&self, project_path: &Path, name: &str, server: &McpServer) -> bool {
+        self.projects
+            .get(project_path.to_string_lossy().as_ref())
+            .and_then(|servers| servers.get(name))
+            .copied()
+            .unwrap_or(server.enabled)
+    }
+
+    /// Enables or disables a server in a project.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project's directory
+    /// * `name` - The server's name
+    /// * `enabled` - Whether Claude gets the server
+    pub fn set(&mut self, project_path: &Path, name: &str, enabled: bool) {
+        self.projects
+            .entry(project_path.to_string_lossy().into_owned())
+            .or_default()
+            .insert(name.to_string(), enabled);
+    }
+
+    /// Returns the servers enabled in a project, by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project's directory
+    /// * `servers` - The project's servers, see `Config::resolve_mcp_servers`
+    pub fn enabled<'a>(
+        &self,
+        project_path: &Path,
+        servers: &'a BTreeMap<String, McpServer>,
+    ) -> Vec<(&'a str, &'a McpServer)> {
+        servers
+            .iter()
+            .filter(|(name, server)| self.is_enabled(project_path, name, server))
+            .map(|(name, server)| (name.as_str(), server))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::McpTransport;
+    use tempfile::TempDir;
+
+    fn local(command: &str) -> McpServer {
+        McpServer {
+            command: Some(command.to_string()),
+            args: vec!["--stdio".to_string()],
+            env: BTreeMap::from([("TOKEN".to_string(), "secret".to_string())]),
+            url: None,
+            transport: McpTransport::Http,
+            headers: BTreeMap::new(),
+            enabled: true,
+        }
+    }
+
+    fn remote(url: &str) -> McpServer {
+        McpServer {
+            command: None,
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            url: Some(url.to_string()),
+            transport: McpTransport::Sse,
+            headers: BTreeMap::new(),
+            enabled: false,
+        }
+    }
+
+    #[test]
+    fn when_command_runs_claude_should_pass_the_config_after_the_program() {
+        let path = Path::new("/home/me/.gz-claude/mcp/api.json");
+
+        assert_eq!(
+            with_config("claude /work/api", path),
+            "claude --mcp-config '/home/me/.gz-claude/mcp/api.json' --strict-mcp-config /work/api"
+        );
+        assert_eq!(
+            with_config("/usr/bin/claude", path),
+            "/usr/bin/claude --mcp-config '/home/me/.gz-claude/mcp/api.json' --strict-mcp-config"
+        );
+        assert_eq!(with_config("lazygit /work/api", path), "lazygit /work/api");
+    }
+
+    #[test]
+    fn when_command_is_wrapped_by_env_should_still_run_claude() {
+        assert!(is_claude_command(
+            "direnv exec '/work/api' sh -c 'claude /work/api'"
+        ));
+        assert!(!is_claude_command(
+            "direnv exec '/work/api' sh -c 'lazygit'"
+        ));
+    }
+
+    #[test]
+    fn when_writing_settings_should_describe_local_and_remote_servers() {
+        let github = local("github-mcp");
+        let docs = remote("https://docs.example.com/sse");
+
+        let settings = settings(&[("github", &github), ("docs", &docs)]);
+
+        assert_eq!(
+            settings,
+            serde_json::json!({
+                "mcpServers": {
+                    "github": {
+                        "type": "stdio",
+                        "command": "github-mcp",
+                        "args": ["--stdio"],
+                        "env": { "TOKEN": "secret" },
+                    },
+                    "docs": {
+                        "type": "sse",
+                        "url": "https://docs.example.com/sse",
+                        "headers": {},
+                    },
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn when_server_is_toggled_should_override_the_configured_default() {
+        let servers = BTreeMap::from([
+            ("docs".to_string(), remote("https://docs.example.com/sse")),
+            ("github".to_string(), local("github-mcp")),
+        ]);
+        let api = Path::new("/work/api");
+        let web = Path::new("/work/web");
+        let mut selection = McpSelection::default();
+
+        selection.set(api, "docs", true);
+        selection.set(api, "github", false);
+
+        let names = |path| {
+            selection
+                .enabled(path, &servers)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(api), vec!["docs"]);
+        assert_eq!(names(web), vec!["github"]);
+    }
+
+    #[test]
+    fn when_selection_is_saved_should_load_it_back() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mcp.json");
+        let mut selection = McpSelection::default();
+        selection.set(Path::new("/work/api"), "github", false);
+
+        selection.save_to(&path).unwrap();
+        let loaded = McpSelection::load_from(&path);
+
+        assert!(!loaded.is_enabled(Path::new("/work/api"), "github", &local("github-mcp")));
+        assert!(McpSelection::load_from(&dir.path().join("missing.json"))
+            .projects
+            .is_empty());
+    }
+}
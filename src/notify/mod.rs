@@ -0,0 +1,140 @@
+//! Notifications of panel events.
+//!
+//! Depending on the `notifications` configuration, an event rings the
+//! terminal bell, sends an OSC 9 notification to the terminal, and/or runs a
+//! custom command such as `terminal-notifier`.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::config::{NotificationEvent, NotificationsConfig};
+use crate::util::shell::quote;
+
+/// Returns the escape sequences written to the terminal for a notification.
+///
+/// # Arguments
+///
+/// * `config` - The `notifications` configuration
+/// * `message` - The notification text
+///
+/// # Returns
+///
+/// BEL and/or the OSC 9 sequence, empty if neither is enabled.
+pub fn terminal_sequence(config: &NotificationsConfig, message: &str) -> String {
+    let mut sequence = String::new();
+    if config.bell {
+        sequence.push('\x07');
+    }
+    if config.osc9 {
+        // Control characters would end the sequence early
+        let text: String = message.chars().filter(|c| !c.is_control()).collect();
+        sequence.push_str(&format!("\x1B]9;{}\x07", text));
+    }
+    sequence
+}
+
+/// Expands the `{event}` and `{message}` placeholders of a notification
+/// command.
+///
+/// # Arguments
+///
+/// * `template` - The configured command
+/// * `event` - The event that occurred
+/// * `message` - The notification text
+///
+/// # Returns
+///
+/// The shell command, with the values quoted as single shell words.
+pub fn expand_command(template: &str, event: NotificationEvent, message: &str) -> String {
+    template
+        .replace("{event}", &quote(event.as_str()))
+        .replace("{message}", &quote(message))
+}
+
+/// Notifies an event through the configured channels.
+///
+/// Does nothing for events that are not listed in `events`. Failures are
+/// ignored: a missing notifier must not disturb the TUI.
+///
+/// # Arguments
+///
+/// * `config` - The `notifications` configuration
+/// * `event` - The event that occurred
+/// * `message` - The notification text
+pub fn notify(config: &NotificationsConfig, event: NotificationEvent, message: &str) {
+    if !config.notifies(event) {
+        return;
+    }
+
+    let sequence = terminal_sequence(config, message);
+    if !sequence.is_empty() {
+        let mut stdout = std::io::stdout();
+        let _ = stdout
+            .write_all(sequence.as_bytes())
+            .and_then(|_| stdout.flush());
+    }
+
+    if let Some(template) = config.command() {
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg(expand_command(template, event, message))
+            .env("GZ_CLAUDE_EVENT", event.as_str())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        // Reap the command in the background so it doesn't linger as a zombie
+        if let Ok(mut child) = child {
+            std::thread::spawn(move || child.wait());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(bell: bool, osc9: bool, command: Option<&str>) -> NotificationsConfig {
+        NotificationsConfig {
+            bell,
+            osc9,
+            command: command.map(String::from),
+            ..NotificationsConfig::default()
+        }
+    }
+
+    #[test]
+    fn when_bell_and_osc9_are_enabled_should_write_both_sequences() {
+        let sequence = terminal_sequence(&config(true, true, None), "api\nis waiting");
+
+        assert_eq!(sequence, "\x07\x1B]9;apiis waiting\x07");
+        assert_eq!(terminal_sequence(&config(false, false, None), "hi"), "");
+    }
+
+    #[test]
+    fn when_expanding_command_should_quote_event_and_message() {
+        let command = expand_command(
+            "terminal-notifier -title {event} -message {message}",
+            NotificationEvent::ClaudeIdle,
+            "it's waiting",
+        );
+
+        assert_eq!(
+            command,
+            r"terminal-notifier -title 'claude_idle' -message 'it'\''s waiting'"
+        );
+    }
+
+    #[test]
+    fn when_no_channel_is_enabled_or_event_is_not_listed_should_not_notify() {
+        assert!(!config(false, false, Some("  ")).notifies(NotificationEvent::GitBehind));
+
+        let mut only_idle = config(true, false, None);
+        only_idle.events = vec![NotificationEvent::ClaudeIdle];
+
+        assert!(only_idle.notifies(NotificationEvent::ClaudeIdle));
+        assert!(!only_idle.notifies(NotificationEvent::CommandFinished));
+    }
+}
@@ -0,0 +1,162 @@
+//! Recently opened files per project.
+//!
+//! Files opened in the editor from the file browser are recorded in a most
+//! recently used list per project, stored in `~/.gz-claude/recent.json` and
+//! shared by all Zellij sessions.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::Result;
+
+/// File storing the recent files of all projects.
+const RECENT_FILE: &str = "recent.json";
+
+/// Maximum number of files remembered per project.
+pub const MAX_RECENT_FILES: usize = 20;
+
+/// Most recently used files of each project, most recent first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecentFiles {
+    #[serde(default)]
+    projects: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl RecentFiles {
+    /// Returns the path to the recent files store.
+    pub fn default_path() -> PathBuf {
+        Config::default_dir().join(RECENT_FILE)
+    }
+
+    /// Loads the recent files from the default store.
+    ///
+    /// # Returns
+    ///
+    /// The recent files, empty if the store doesn't exist or is invalid.
+    pub fn load() -> Self {
+        Self::load_from(&Self::default_path())
+    }
+
+    /// Loads the recent files from a file.
+    fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the recent files to the default store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::default_path())
+    }
+
+    /// Saves the recent files to a file, replacing it atomically.
+    fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Records a file as the most recently opened one of a project.
+    ///
+    /// The file moves to the front if it was already listed; the oldest
+    /// entries are dropped beyond `MAX_RECENT_FILES`.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project directory
+    /// * `file_path` - The opened file
+    pub fn record(&mut self, project_path: &Path, file_path: &Path) {
+        let files = self.projects.entry(project_path.to_path_buf()).or_default();
+        files.retain(|f| f != file_path);
+        files.insert(0, file_path.to_path_buf());
+        files.truncate(MAX_RECENT_FILES);
+    }
+
+    /// Returns the recent files of a project, most recent first.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project directory
+    pub fn files(&self, project_path: &Path) -> &[PathBuf] {
+        self.projects
+            .get(project_path)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn when_recording_file_should_move_it_to_front() {
+        let project = Path::new("/project");
+        let mut recent = RecentFiles::default();
+
+        recent.record(project, Path::new("/project/a.rs"));
+        recent.record(project, Path::new("/project/b.rs"));
+        recent.record(project, Path::new("/project/a.rs"));
+
+        assert_eq!(
+            recent.files(project),
+            [
+                PathBuf::from("/project/a.rs"),
+                PathBuf::from("/project/b.rs")
+            ]
+        );
+        assert!(recent.files(Path::new("/other")).is_empty());
+    }
+
+    #[test]
+    fn when_recording_many_files_should_keep_the_most_recent() {
+        let project = Path::new("/project");
+        let mut recent = RecentFiles::default();
+
+        for i in 0..MAX_RECENT_FILES + 5 {
+            recent.record(project, &project.join(format!("{}.rs", i)));
+        }
+
+        let files = recent.files(project);
+        assert_eq!(files.len(), MAX_RECENT_FILES);
+        assert_eq!(
+            files[0],
+            project.join(format!("{}.rs", MAX_RECENT_FILES + 4))
+        );
+    }
+
+    #[test]
+    fn when_saving_recent_files_should_round_trip_through_its_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("recent.json");
+        let project = Path::new("/project");
+        let mut recent = RecentFiles::default();
+        recent.record(project, Path::new("/project/a.rs"));
+
+        recent.save_to(&path).unwrap();
+        let loaded = RecentFiles::load_from(&path);
+
+        assert_eq!(loaded.files(project), [PathBuf::from("/project/a.rs")]);
+        assert!(RecentFiles::load_from(&dir.path().join("missing.json"))
+            .files(project)
+            .is_empty());
+    }
+}
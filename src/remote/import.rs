@@ -0,0 +1,335 @@
+//! Listing the repositories of a GitHub organization or GitLab group.
+//!
+//! Used by `gz-claude import` to create a workspace from a code host. The
+//! `gh` and `glab` CLIs are preferred, since they know the user's
+//! credentials and private repositories; without them, the REST API is
+//! queried through `curl`, with the token of `GITHUB_TOKEN` or
+//! `GITLAB_TOKEN` when set.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::env;
+use std::process::{Command, Stdio};
+
+use serde_json::Value;
+
+use super::{api_get, encode_component, string_at};
+use crate::config::RemoteProvider;
+
+/// Repositories requested per page of the REST API.
+const PAGE_SIZE: usize = 100;
+
+/// A repository listed by a code host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostedRepo {
+    /// The project name: the repository name, prefixed with its subgroups
+    /// on GitLab (e.g. `tools-cli` for `group/tools/cli`).
+    pub name: String,
+    /// What the repository is, empty if not described.
+    pub description: String,
+    /// Whether the repository is archived.
+    pub archived: bool,
+    /// The SSH clone URL.
+    pub ssh_url: String,
+    /// The HTTPS clone URL.
+    pub https_url: String,
+}
+
+/// Lists the repositories of an organization, user or group.
+///
+/// # Arguments
+///
+/// * `provider` - The code host
+/// * `owner` - The GitHub organization or user, or the GitLab group
+///
+/// # Returns
+///
+/// The repositories, sorted by name.
+///
+/// # Errors
+///
+/// A description of why the repositories could not be listed.
+pub fn list_repos(provider: RemoteProvider, owner: &str) -> Result<Vec<HostedRepo>, String> {
+    let mut repos = match provider {
+        RemoteProvider::GitHub if has_program("gh") => {
+            let output = run_cli(Command::new("gh").args([
+                "repo",
+                "list",
+                owner,
+                "--limit",
+                "1000",
+                "--json",
+                "name,description,isArchived,sshUrl,url",
+            ]))?;
+            parse_gh_repo_list(&parse_json_stream(&output)?)
+        }
+        RemoteProvider::GitHub => {
+            let mut headers = vec!["Accept: application/vnd.github+json".to_string()];
+            if let Some(token) = token(provider) {
+                headers.push(format!("Authorization: Bearer {}", token));
+            }
+            let url = format!(
+                "https://api.github.com/users/{}/repos?per_page={}",
+                encode_component(owner),
+                PAGE_SIZE
+            );
+            parse_github_repos(&get_all_pages(&url, &headers)?)
+        }
+        RemoteProvider::GitLab => {
+            let path = format!(
+                "groups/{}/projects?include_subgroups=true&per_page={}",
+                encode_component(owner),
+                PAGE_SIZE
+            );
+            let projects = if has_program("glab") {
+                let output = run_cli(Command::new("glab").args(["api", "--paginate", &path]))?;
+                parse_json_stream(&output)?
+            } else {
+                let headers: Vec<String> = token(provider)
+                    .map(|token| format!("PRIVATE-TOKEN: {}", token))
+                    .into_iter()
+                    .collect();
+                get_all_pages(&format!("https://gitlab.com/api/v4/{}", path), &headers)?
+            };
+            parse_gitlab_projects(&projects, owner)
+        }
+    };
+    repos.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(repos)
+}
+
+/// Returns the provider's token from its default environment variable.
+fn token(provider: RemoteProvider) -> Option<String> {
+    env::var(provider.default_token_env())
+        .ok()
+        .filter(|token| !token.is_empty())
+}
+
+/// Returns true if a program runs.
+fn has_program(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Runs a code host CLI and returns its standard output.
+fn run_cli(command: &mut Command) -> Result<Vec<u8>, String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", program, stderr.trim()));
+    }
+    Ok(output.stdout)
+}
+
+/// Requests the pages of a REST list endpoint until one comes back short.
+fn get_all_pages(url: &str, headers: &[String]) -> Result<Vec<Value>, String> {
+    let mut items = Vec::new();
+    for page in 1.. {
+        let value = api_get(&format!("{}&page={}", url, page), headers)?;
+        let entries = value.as_array().cloned().unwrap_or_default();
+        let last = entries.len() < PAGE_SIZE;
+        items.extend(entries);
+        if last {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+/// Parses CLI output made of one or more JSON arrays, as printed by
+/// `--paginate`, into their entries.
+fn parse_json_stream(output: &[u8]) -> Result<Vec<Value>, String> {
+    let mut items = Vec::new();
+    for value in serde_json::Deserializer::from_slice(output).into_iter::<Value>() {
+        match value.map_err(|e| format!("Unexpected response: {}", e))? {
+            Value::Array(entries) => items.extend(entries),
+            other => items.push(other),
+        }
+    }
+    Ok(items)
+}
+
+/// Parses the entries of `gh repo list --json`.
+fn parse_gh_repo_list(entries: &[Value]) -> Vec<HostedRepo> {
+    entries
+        .iter()
+        .filter_map(|repo| {
+            let url = repo.get("url")?.as_str()?;
+            Some(HostedRepo {
+                name: repo.get("name")?.as_str()?.to_string(),
+                description: string_at(repo, "/description"),
+                archived: repo.get("isArchived").and_then(Value::as_bool) == Some(true),
+                ssh_url: string_at(repo, "/sshUrl"),
+                https_url: format!("{}.git", url),
+            })
+        })
+        .collect()
+}
+
+/// Parses the entries of GitHub's list repositories endpoint.
+fn parse_github_repos(entries: &[Value]) -> Vec<HostedRepo> {
+    entries
+        .iter()
+        .filter_map(|repo| {
+            Some(HostedRepo {
+                name: repo.get("name")?.as_str()?.to_string(),
+                description: string_at(repo, "/description"),
+                archived: repo.get("archived").and_then(Value::as_bool) == Some(true),
+                ssh_url: string_at(repo, "/ssh_url"),
+                https_url: string_at(repo, "/clone_url"),
+            })
+        })
+        .collect()
+}
+
+/// Parses the entries of GitLab's list group projects endpoint.
+///
+/// Projects of subgroups are named after their path within the group, with
+/// `/` replaced by `-`, so names stay unique.
+fn parse_gitlab_projects(entries: &[Value], group: &str) -> Vec<HostedRepo> {
+    let prefix = format!("{}/", group.trim_matches('/'));
+    entries
+        .iter()
+        .filter_map(|project| {
+            let path = project.get("path_with_namespace")?.as_str()?;
+            let name = path.strip_prefix(&prefix).unwrap_or(path).replace('/', "-");
+            Some(HostedRepo {
+                name,
+                description: string_at(project, "/description"),
+                archived: project.get("archived").and_then(Value::as_bool) == Some(true),
+                ssh_url: string_at(project, "/ssh_url_to_repo"),
+                https_url: string_at(project, "/http_url_to_repo"),
+            })
+        })
+        .collect()
+}
+
+/// Parses a selection of numbered items, e.g. "1 3 5-7" or "all".
+///
+/// Numbers start at 1 and may be separated by spaces or commas.
+///
+/// # Arguments
+///
+/// * `input` - The selection typed by the user
+/// * `count` - The number of items listed
+///
+/// # Returns
+///
+/// The zero-based indexes selected, in order and without duplicates.
+///
+/// # Errors
+///
+/// A description of the first number or range that is not valid.
+pub fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>, String> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("all") || input == "*" {
+        return Ok((0..count).collect());
+    }
+
+    let number = |text: &str| -> Result<usize, String> {
+        match text.trim().parse::<usize>() {
+            Ok(n) if (1..=count).contains(&n) => Ok(n - 1),
+            _ => Err(format!(
+                "'{}' is not a number between 1 and {}",
+                text, count
+            )),
+        }
+    };
+    let mut selected = Vec::new();
+    for part in input.split([' ', ',']).filter(|part| !part.is_empty()) {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (number(first)?, number(last)?),
+            None => (number(part)?, number(part)?),
+        };
+        if first > last {
+            return Err(format!("'{}' is not an ascending range", part));
+        }
+        for index in first..=last {
+            if !selected.contains(&index) {
+                selected.push(index);
+            }
+        }
+    }
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn when_parsing_gh_repo_list_should_derive_https_clone_url() {
+        let output = br#"[{"name":"api","description":"The API","isArchived":false,
+            "sshUrl":"git@github.com:acme/api.git","url":"https://github.com/acme/api"}]"#;
+
+        let repos = parse_gh_repo_list(&parse_json_stream(output).unwrap());
+
+        assert_eq!(
+            repos,
+            vec![HostedRepo {
+                name: "api".to_string(),
+                description: "The API".to_string(),
+                archived: false,
+                ssh_url: "git@github.com:acme/api.git".to_string(),
+                https_url: "https://github.com/acme/api.git".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn when_parsing_paginated_output_should_concatenate_arrays() {
+        let items = parse_json_stream(b"[1, 2]\n[3]").unwrap();
+
+        assert_eq!(items, vec![json!(1), json!(2), json!(3)]);
+        assert!(parse_json_stream(b"[1, ").is_err());
+    }
+
+    #[test]
+    fn when_parsing_rest_responses_should_read_names_and_clone_urls() {
+        let github = parse_github_repos(&[json!({
+            "name": "web", "description": null, "archived": true,
+            "ssh_url": "git@github.com:acme/web.git",
+            "clone_url": "https://github.com/acme/web.git"
+        })]);
+        let gitlab = parse_gitlab_projects(
+            &[json!({
+                "path_with_namespace": "acme/tools/cli",
+                "archived": false,
+                "ssh_url_to_repo": "git@gitlab.com:acme/tools/cli.git",
+                "http_url_to_repo": "https://gitlab.com/acme/tools/cli.git"
+            })],
+            "acme",
+        );
+
+        assert_eq!(github[0].name, "web");
+        assert_eq!(github[0].description, "");
+        assert!(github[0].archived);
+        assert_eq!(gitlab[0].name, "tools-cli");
+        assert_eq!(gitlab[0].ssh_url, "git@gitlab.com:acme/tools/cli.git");
+    }
+
+    #[test]
+    fn when_parsing_selection_should_accept_numbers_ranges_and_all() {
+        assert_eq!(parse_selection("3, 1 2-4", 5).unwrap(), vec![2, 0, 1, 3]);
+        assert_eq!(parse_selection(" all ", 3).unwrap(), vec![0, 1, 2]);
+        assert_eq!(parse_selection("", 3).unwrap(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn when_selection_is_out_of_range_should_fail() {
+        assert!(parse_selection("0", 3).is_err());
+        assert!(parse_selection("2-9", 3).is_err());
+        assert!(parse_selection("3-1", 3).is_err());
+        assert!(parse_selection("x", 3).is_err());
+    }
+}
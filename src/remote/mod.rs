@@ -0,0 +1,728 @@
+//! Code host integration.
+//!
+//! Fetches the open pull requests (GitHub) or merge requests (GitLab) of each
+//! project's `origin` repository and the latest CI pipeline of its current
+//! branch. Requests run in background threads through `curl`, and results are
+//! reused until their TTL expires.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+pub mod import;
+
+use std::collections::HashMap;
+use std::env;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::config::{RemoteConfig, RemoteProvider};
+use crate::git;
+use crate::util::glyphs::glyph;
+
+/// How long a single API request may take.
+const REQUEST_TIMEOUT_SECS: &str = "10";
+
+/// Maximum number of pull requests fetched per project.
+const MAX_PULL_REQUESTS: usize = 100;
+
+/// A repository on a code host, parsed from a git remote URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteRepo {
+    /// The code host API.
+    pub provider: RemoteProvider,
+    /// The host name, e.g. `github.com`.
+    pub host: String,
+    /// The repository path, e.g. `owner/repo` or `group/subgroup/repo`.
+    pub path: String,
+}
+
+impl RemoteRepo {
+    /// Returns the API base URL of the repository's host.
+    ///
+    /// The API of a self-hosted instance isn't guessed from its host name, so
+    /// the token only goes to a URL the configuration names.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_url` - The configured base URL, used as is when set
+    ///
+    /// # Returns
+    ///
+    /// The base URL, or None for a host other than github.com or gitlab.com
+    /// without a configured one.
+    pub fn api_base(&self, api_url: Option<&str>) -> Option<String> {
+        if let Some(url) = api_url {
+            return Some(url.trim_end_matches('/').to_string());
+        }
+        match (self.provider, self.host.as_str()) {
+            (RemoteProvider::GitHub, "github.com") => Some("https://api.github.com".to_string()),
+            (RemoteProvider::GitLab, "gitlab.com") => Some("https://gitlab.com/api/v4".to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a git remote URL into its host and repository path.
+///
+/// Accepts scp-like (`git@host:owner/repo.git`) and URL (`https://host/owner/repo`,
+/// `ssh://git@host:22/owner/repo.git`) forms.
+///
+/// # Arguments
+///
+/// * `url` - The remote URL
+/// * `provider` - The configured provider; detected when None, for
+///   github.com and gitlab.com only
+///
+/// # Returns
+///
+/// The repository, or None if the URL cannot be parsed or the provider is unknown.
+pub fn parse_remote_url(url: &str, provider: Option<RemoteProvider>) -> Option<RemoteRepo> {
+    let (host, path) = split_remote_url(url)?;
+
+    let provider = provider.or(match host.as_str() {
+        "github.com" => Some(RemoteProvider::GitHub),
+        "gitlab.com" => Some(RemoteProvider::GitLab),
+        _ => None,
+    })?;
+
+    Some(RemoteRepo {
+        provider,
+        host,
+        path,
+    })
+}
+
+/// Returns the web page of the repository behind a git remote URL.
+///
+/// Works for any host serving repositories at `https://<host>/<path>`
+/// (GitHub, GitLab, Bitbucket, Gitea, ...).
+///
+/// # Arguments
+///
+/// * `url` - The remote URL
+///
+/// # Returns
+///
+/// The `https` URL of the repository, or None if the remote URL cannot be parsed.
+pub fn web_url(url: &str) -> Option<String> {
+    let (host, path) = split_remote_url(url)?;
+    Some(format!("https://{}/{}", host, path))
+}
+
+/// Splits a git remote URL into its lowercased host and its repository path
+/// without the `.git` suffix.
+fn split_remote_url(url: &str) -> Option<(String, String)> {
+    let url = url.trim();
+    let (authority, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit_once(':').map_or(authority, |(h, _)| h);
+            (host, path)
+        }
+        None => url.split_once(':')?,
+    };
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, h)| h)
+        .to_ascii_lowercase();
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || !path.contains('/') {
+        return None;
+    }
+    Some((host, path.to_string()))
+}
+
+/// An open pull request or merge request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequest {
+    /// The number shown on the code host (`#12`, `!12`).
+    pub number: u64,
+    pub title: String,
+    pub author: String,
+    /// The source branch.
+    pub branch: String,
+    /// The page of the pull request.
+    pub url: String,
+}
+
+/// Outcome of the latest CI pipeline of a branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStatus {
+    Success,
+    Failed,
+    Running,
+    /// Queued or waiting for a manual step.
+    Pending,
+    /// Canceled or skipped.
+    Canceled,
+}
+
+impl PipelineStatus {
+    /// Returns the icon displayed next to the project.
+    pub fn badge(&self) -> &'static str {
+        match self {
+            PipelineStatus::Success => glyph("✔", "+"),
+            PipelineStatus::Failed => glyph("✘", "x"),
+            PipelineStatus::Running => glyph("⟳", "~"),
+            PipelineStatus::Pending => glyph("…", "."),
+            PipelineStatus::Canceled => glyph("⊘", "-"),
+        }
+    }
+
+    /// Returns a short description of the status.
+    pub fn label(&self) -> &'static str {
+        match self {
+            PipelineStatus::Success => "passed",
+            PipelineStatus::Failed => "failed",
+            PipelineStatus::Running => "running",
+            PipelineStatus::Pending => "pending",
+            PipelineStatus::Canceled => "canceled",
+        }
+    }
+}
+
+/// Pull requests and pipeline status of a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteStatus {
+    /// The branch the pipeline belongs to.
+    pub branch: String,
+    /// Open pull requests, those from `branch` first.
+    pub pull_requests: Vec<PullRequest>,
+    /// The latest pipeline of `branch`, None if it never ran.
+    pub pipeline: Option<PipelineStatus>,
+}
+
+impl RemoteStatus {
+    /// Returns the badge displayed after the project's git info.
+    pub fn badge(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.pull_requests.is_empty() {
+            parts.push(format!("{}{}", glyph("⇄", "pr"), self.pull_requests.len()));
+        }
+        if let Some(pipeline) = self.pipeline {
+            parts.push(pipeline.badge().to_string());
+        }
+        parts.join(" ")
+    }
+}
+
+/// Latest known remote state of a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteState {
+    /// The first fetch has not completed yet.
+    Loading,
+    Ready(RemoteStatus),
+    /// The last fetch failed, with the reason.
+    Failed(String),
+}
+
+/// Schedules remote fetches and caches their results per project.
+#[derive(Debug, Default)]
+pub struct RemoteMonitor {
+    states: HashMap<PathBuf, RemoteState>,
+    running: HashMap<PathBuf, JoinHandle<Result<RemoteStatus, String>>>,
+    last_started: HashMap<PathBuf, Instant>,
+}
+
+impl RemoteMonitor {
+    /// Creates a monitor with no results.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the latest state of a project, if it has been fetched.
+    pub fn state(&self, project_path: &Path) -> Option<&RemoteState> {
+        self.states.get(project_path)
+    }
+
+    /// Collects finished fetches and starts the ones whose TTL expired.
+    ///
+    /// # Arguments
+    ///
+    /// * `projects` - The project directories
+    /// * `config` - The remote integration settings
+    ///
+    /// # Returns
+    ///
+    /// True if a displayed state changed.
+    pub fn poll<'a>(
+        &mut self,
+        projects: impl IntoIterator<Item = &'a Path>,
+        config: &RemoteConfig,
+    ) -> bool {
+        let mut changed = self.collect_finished();
+
+        let now = Instant::now();
+        let ttl = Duration::from_secs(config.ttl_secs);
+        for path in projects {
+            if self.running.contains_key(path) {
+                continue;
+            }
+            let due = self
+                .last_started
+                .get(path)
+                .map_or(true, |started| now.duration_since(*started) >= ttl);
+            if !due {
+                continue;
+            }
+
+            if !self.states.contains_key(path) {
+                self.states.insert(path.to_path_buf(), RemoteState::Loading);
+                changed = true;
+            }
+            self.last_started.insert(path.to_path_buf(), now);
+            let cwd = path.to_path_buf();
+            let config = config.clone();
+            self.running.insert(
+                path.to_path_buf(),
+                thread::spawn(move || fetch_status(&cwd, &config)),
+            );
+        }
+
+        changed
+    }
+
+    /// Makes every fetch due on the next poll.
+    pub fn reschedule_all(&mut self) {
+        self.last_started.clear();
+    }
+
+    /// Stores the results of fetches whose threads have finished.
+    ///
+    /// Returns true if any stored state changed.
+    fn collect_finished(&mut self) -> bool {
+        let finished: Vec<PathBuf> = self
+            .running
+            .iter()
+            .filter(|(_, handle)| handle.is_finished())
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let mut changed = false;
+        for path in finished {
+            let Some(handle) = self.running.remove(&path) else {
+                continue;
+            };
+            let state = match handle.join() {
+                Ok(Ok(status)) => RemoteState::Ready(status),
+                Ok(Err(reason)) => RemoteState::Failed(reason),
+                Err(_) => RemoteState::Failed("Remote fetch panicked".to_string()),
+            };
+            changed |= self.states.insert(path, state.clone()) != Some(state);
+        }
+        changed
+    }
+}
+
+/// Fetches the pull requests and pipeline status of a project synchronously.
+///
+/// # Arguments
+///
+/// * `project_path` - The project directory
+/// * `config` - The remote integration settings
+///
+/// # Returns
+///
+/// The status of the project's `origin` repository and current branch.
+///
+/// # Errors
+///
+/// A description of what is missing (branch, remote, token) or why the API
+/// request failed.
+pub fn fetch_status(project_path: &Path, config: &RemoteConfig) -> Result<RemoteStatus, String> {
+    let (branch, origin) = git::branch_and_origin(project_path)
+        .ok_or_else(|| "No current branch or 'origin' remote".to_string())?;
+    let repo = parse_remote_url(&origin, config.provider).ok_or_else(|| {
+        format!(
+            "'{}' is not a github.com or gitlab.com remote; set provider and api_url for a self-hosted one",
+            origin
+        )
+    })?;
+    let base = repo
+        .api_base(config.api_url.as_deref())
+        .ok_or_else(|| format!("Set api_url to fetch pull requests from {}", repo.host))?;
+
+    let token_env = config
+        .token_env
+        .as_deref()
+        .unwrap_or(repo.provider.default_token_env());
+    let token = env::var(token_env)
+        .ok()
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| format!("Set {} to show pull requests", token_env))?;
+
+    let (mut pull_requests, pipeline) = match repo.provider {
+        RemoteProvider::GitHub => {
+            let headers = [
+                format!("Authorization: Bearer {}", token),
+                "Accept: application/vnd.github+json".to_string(),
+            ];
+            let repo_url = format!("{}/repos/{}", base, repo.path);
+            let pulls = api_get(
+                &format!(
+                    "{}/pulls?state=open&per_page={}",
+                    repo_url, MAX_PULL_REQUESTS
+                ),
+                &headers,
+            )?;
+            let runs = api_get(
+                &format!(
+                    "{}/actions/runs?branch={}&per_page=1",
+                    repo_url,
+                    encode_component(&branch)
+                ),
+                &headers,
+            )?;
+            (parse_github_pulls(&pulls), parse_github_pipeline(&runs))
+        }
+        RemoteProvider::GitLab => {
+            let headers = [format!("PRIVATE-TOKEN: {}", token)];
+            let project_url = format!("{}/projects/{}", base, encode_component(&repo.path));
+            let merge_requests = api_get(
+                &format!(
+                    "{}/merge_requests?state=opened&per_page={}",
+                    project_url, MAX_PULL_REQUESTS
+                ),
+                &headers,
+            )?;
+            let pipelines = api_get(
+                &format!(
+                    "{}/pipelines?ref={}&per_page=1",
+                    project_url,
+                    encode_component(&branch)
+                ),
+                &headers,
+            )?;
+            (
+                parse_gitlab_merge_requests(&merge_requests),
+                parse_gitlab_pipeline(&pipelines),
+            )
+        }
+    };
+
+    // Stable sort: the branch's own pull requests first, API order otherwise
+    pull_requests.sort_by_key(|pr| pr.branch != branch);
+
+    Ok(RemoteStatus {
+        branch,
+        pull_requests,
+        pipeline,
+    })
+}
+
+/// Performs a GET request with curl and parses the JSON response.
+///
+/// Headers are passed on stdin so the token does not appear in the process list.
+fn api_get(url: &str, headers: &[String]) -> Result<Value, String> {
+    let mut child = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--location",
+            "--max-time",
+            REQUEST_TIMEOUT_SECS,
+            "-H",
+            "@-",
+            url,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(headers.join("\n").as_bytes())
+            .map_err(|e| format!("Failed to pass headers to curl: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Request failed: {}", stderr.trim()));
+    }
+
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Unexpected API response: {}", e))
+}
+
+/// Percent-encodes a URL path segment or query value.
+fn encode_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Returns the string at a JSON pointer, or an empty string.
+fn string_at(value: &Value, pointer: &str) -> String {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Parses the response of GitHub's list pull requests endpoint.
+fn parse_github_pulls(value: &Value) -> Vec<PullRequest> {
+    let Some(pulls) = value.as_array() else {
+        return Vec::new();
+    };
+    pulls
+        .iter()
+        .filter_map(|pr| {
+            Some(PullRequest {
+                number: pr.get("number")?.as_u64()?,
+                title: string_at(pr, "/title"),
+                author: string_at(pr, "/user/login"),
+                branch: string_at(pr, "/head/ref"),
+                url: string_at(pr, "/html_url"),
+            })
+        })
+        .collect()
+}
+
+/// Parses the response of GitHub's list workflow runs endpoint.
+fn parse_github_pipeline(value: &Value) -> Option<PipelineStatus> {
+    let run = value.pointer("/workflow_runs/0")?;
+    let status = run.get("status").and_then(Value::as_str)?;
+    if status != "completed" {
+        return Some(match status {
+            "in_progress" => PipelineStatus::Running,
+            _ => PipelineStatus::Pending,
+        });
+    }
+    match run.get("conclusion").and_then(Value::as_str)? {
+        "success" | "neutral" => Some(PipelineStatus::Success),
+        "cancelled" | "skipped" | "stale" => Some(PipelineStatus::Canceled),
+        "action_required" => Some(PipelineStatus::Pending),
+        _ => Some(PipelineStatus::Failed),
+    }
+}
+
+/// Parses the response of GitLab's list merge requests endpoint.
+fn parse_gitlab_merge_requests(value: &Value) -> Vec<PullRequest> {
+    let Some(merge_requests) = value.as_array() else {
+        return Vec::new();
+    };
+    merge_requests
+        .iter()
+        .filter_map(|mr| {
+            Some(PullRequest {
+                number: mr.get("iid")?.as_u64()?,
+                title: string_at(mr, "/title"),
+                author: string_at(mr, "/author/username"),
+                branch: string_at(mr, "/source_branch"),
+                url: string_at(mr, "/web_url"),
+            })
+        })
+        .collect()
+}
+
+/// Parses the response of GitLab's list pipelines endpoint.
+fn parse_gitlab_pipeline(value: &Value) -> Option<PipelineStatus> {
+    match value.pointer("/0/status").and_then(Value::as_str)? {
+        "success" => Some(PipelineStatus::Success),
+        "failed" => Some(PipelineStatus::Failed),
+        "running" => Some(PipelineStatus::Running),
+        "canceled" | "skipped" => Some(PipelineStatus::Canceled),
+        _ => Some(PipelineStatus::Pending),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn when_parsing_scp_like_github_url_should_extract_owner_and_repo() {
+        let repo = parse_remote_url("git@github.com:waabox/gz-claude.git", None).unwrap();
+
+        assert_eq!(repo.provider, RemoteProvider::GitHub);
+        assert_eq!(repo.host, "github.com");
+        assert_eq!(repo.path, "waabox/gz-claude");
+        assert_eq!(
+            repo.api_base(None).as_deref(),
+            Some("https://api.github.com")
+        );
+    }
+
+    #[test]
+    fn when_parsing_ssh_url_with_port_should_strip_user_and_port() {
+        let repo = parse_remote_url(
+            "ssh://git@gitlab.example.com:2222/group/sub/app.git",
+            Some(RemoteProvider::GitLab),
+        )
+        .unwrap();
+
+        assert_eq!(repo.provider, RemoteProvider::GitLab);
+        assert_eq!(repo.host, "gitlab.example.com");
+        assert_eq!(repo.path, "group/sub/app");
+    }
+
+    #[test]
+    fn when_host_only_looks_like_a_code_host_should_not_detect_it() {
+        for url in [
+            "ssh://git@gitlab.example.com:2222/group/app.git",
+            "https://notgithub.evil.io/owner/app",
+            "git@github.com.evil.io:owner/app.git",
+        ] {
+            assert_eq!(parse_remote_url(url, None), None, "{}", url);
+        }
+        let gitlab = parse_remote_url("https://gitlab.com/group/app", None).unwrap();
+        assert_eq!(
+            gitlab.api_base(None).as_deref(),
+            Some("https://gitlab.com/api/v4")
+        );
+    }
+
+    #[test]
+    fn when_parsing_https_url_of_unknown_host_should_use_configured_provider() {
+        assert_eq!(
+            parse_remote_url("https://code.acme.io/team/app", None),
+            None
+        );
+
+        let repo = parse_remote_url(
+            "https://code.acme.io/team/app",
+            Some(RemoteProvider::GitHub),
+        )
+        .unwrap();
+        // The token only goes to an API the configuration names
+        assert_eq!(repo.api_base(None), None);
+        assert_eq!(
+            repo.api_base(Some("https://api.acme.io/")).as_deref(),
+            Some("https://api.acme.io")
+        );
+    }
+
+    #[test]
+    fn when_parsing_url_without_repository_path_should_return_none() {
+        assert_eq!(parse_remote_url("https://github.com/waabox", None), None);
+        assert_eq!(parse_remote_url("/srv/git/repo.git", None), None);
+    }
+
+    #[test]
+    fn when_building_web_url_should_work_for_any_host() {
+        assert_eq!(
+            web_url("git@bitbucket.org:team/app.git").as_deref(),
+            Some("https://bitbucket.org/team/app")
+        );
+        assert_eq!(
+            web_url("ssh://git@gitlab.example.com:2222/group/sub/app.git").as_deref(),
+            Some("https://gitlab.example.com/group/sub/app")
+        );
+        assert_eq!(web_url("/srv/git/repo.git"), None);
+    }
+
+    #[test]
+    fn when_encoding_component_should_escape_reserved_characters() {
+        assert_eq!(encode_component("group/app"), "group%2Fapp");
+        assert_eq!(encode_component("feat/ü x"), "feat%2F%C3%BC%20x");
+        assert_eq!(encode_component("v1.2-rc_1~"), "v1.2-rc_1~");
+    }
+
+    #[test]
+    fn when_parsing_github_pulls_should_read_number_title_author_and_branch() {
+        let value = json!([{
+            "number": 42,
+            "title": "Add remote status",
+            "html_url": "https://github.com/o/r/pull/42",
+            "user": { "login": "octocat" },
+            "head": { "ref": "feature" }
+        }]);
+
+        let pulls = parse_github_pulls(&value);
+
+        assert_eq!(
+            pulls,
+            vec![PullRequest {
+                number: 42,
+                title: "Add remote status".to_string(),
+                author: "octocat".to_string(),
+                branch: "feature".to_string(),
+                url: "https://github.com/o/r/pull/42".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn when_parsing_github_runs_should_map_status_and_conclusion() {
+        let run = |status: &str, conclusion: Value| json!({ "workflow_runs": [{ "status": status, "conclusion": conclusion }] });
+
+        assert_eq!(
+            parse_github_pipeline(&run("completed", json!("success"))),
+            Some(PipelineStatus::Success)
+        );
+        assert_eq!(
+            parse_github_pipeline(&run("completed", json!("timed_out"))),
+            Some(PipelineStatus::Failed)
+        );
+        assert_eq!(
+            parse_github_pipeline(&run("in_progress", Value::Null)),
+            Some(PipelineStatus::Running)
+        );
+        assert_eq!(
+            parse_github_pipeline(&run("queued", Value::Null)),
+            Some(PipelineStatus::Pending)
+        );
+        assert_eq!(parse_github_pipeline(&json!({ "workflow_runs": [] })), None);
+    }
+
+    #[test]
+    fn when_parsing_gitlab_responses_should_read_merge_requests_and_pipeline() {
+        let merge_requests = json!([{
+            "iid": 7,
+            "title": "Fix login",
+            "web_url": "https://gitlab.com/g/p/-/merge_requests/7",
+            "author": { "username": "dev" },
+            "source_branch": "fix-login"
+        }]);
+        let pipelines = json!([{ "status": "failed" }]);
+
+        let parsed = parse_gitlab_merge_requests(&merge_requests);
+
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].number, 7);
+        assert_eq!(parsed[0].author, "dev");
+        assert_eq!(parsed[0].branch, "fix-login");
+        assert_eq!(
+            parse_gitlab_pipeline(&pipelines),
+            Some(PipelineStatus::Failed)
+        );
+        assert_eq!(parse_gitlab_pipeline(&json!([])), None);
+    }
+
+    #[test]
+    fn when_formatting_badge_should_combine_pull_request_count_and_pipeline() {
+        let mut status = RemoteStatus {
+            branch: "main".to_string(),
+            pull_requests: Vec::new(),
+            pipeline: None,
+        };
+        assert_eq!(status.badge(), "");
+
+        status.pipeline = Some(PipelineStatus::Success);
+        assert_eq!(status.badge(), "✔");
+
+        status.pull_requests = parse_github_pulls(&json!([{ "number": 1 }, { "number": 2 }]));
+        assert_eq!(status.badge(), "⇄2 ✔");
+    }
+}
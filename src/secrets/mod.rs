@@ -0,0 +1,521 @@
+//! Secrets referenced from command bar items.
+//!
+//! Commands reference secrets as `{{secret:NAME}}`. References are resolved
+//! right before execution, from the OS keychain (service `gz-claude`, account
+//! `NAME`) or from `~/.gz-claude/secrets.json`, which must only be readable by
+//! its owner. Resolved commands are handed to the pane through a private
+//! script that deletes itself when it starts, so secret values never appear
+//! in pane commands, process arguments or session files.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::{Result, SecretError};
+use crate::util::shell;
+
+/// Keychain service under which secrets are stored.
+pub const KEYRING_SERVICE: &str = "gz-claude";
+
+/// Opening marker of a secret reference.
+const REFERENCE_START: &str = "{{secret:";
+
+/// Closing marker of a secret reference.
+const REFERENCE_END: &str = "}}";
+
+/// Age after which an unexecuted secret script is deleted.
+const STALE_SCRIPT_AGE: Duration = Duration::from_secs(60);
+
+/// Returns the path of the secrets file (`~/.gz-claude/secrets.json`).
+pub fn secrets_file_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".gz-claude")
+        .join("secrets.json")
+}
+
+/// Returns the directory holding secret scripts (`~/.gz-claude/run/`).
+pub fn scripts_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".gz-claude")
+        .join("run")
+}
+
+/// Returns true if a secret name is valid: ASCII letters, digits, `_`, `-` and `.`.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// A `{{secret:NAME}}` reference found in a command.
+struct Reference<'a> {
+    /// Byte range of the whole reference.
+    start: usize,
+    end: usize,
+    name: &'a str,
+}
+
+/// Finds the secret references of a command, in order.
+///
+/// Markers around an invalid name are not references and are left as is.
+fn find_references(command: &str) -> Vec<Reference<'_>> {
+    let mut references = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = command[offset..].find(REFERENCE_START) {
+        let start = offset + found;
+        let name_start = start + REFERENCE_START.len();
+        let Some(name_len) = command[name_start..].find(REFERENCE_END) else {
+            break;
+        };
+        let name = &command[name_start..name_start + name_len];
+        if is_valid_name(name) {
+            let end = name_start + name_len + REFERENCE_END.len();
+            references.push(Reference { start, end, name });
+            offset = end;
+        } else {
+            offset = name_start;
+        }
+    }
+    references
+}
+
+/// Returns the names of the secrets referenced by a command, without duplicates.
+pub fn referenced_names(command: &str) -> Vec<&str> {
+    let mut names: Vec<&str> = Vec::new();
+    for reference in find_references(command) {
+        if !names.contains(&reference.name) {
+            names.push(reference.name);
+        }
+    }
+    names
+}
+
+/// Returns true if a command references at least one secret.
+pub fn has_references(command: &str) -> bool {
+    !find_references(command).is_empty()
+}
+
+/// Replaces the secret references of a command.
+///
+/// Values are shell-quoted, closing and reopening the quotes a reference is
+/// written in, so spaces, `;`, `$()` or quotes in a value stay part of it.
+///
+/// # Arguments
+///
+/// * `command` - The command with `{{secret:NAME}}` references
+/// * `lookup` - Returns the value of a secret, called once per distinct name
+///
+/// # Returns
+///
+/// The command with every reference replaced by its quoted value.
+///
+/// # Errors
+///
+/// The first error returned by `lookup`.
+pub fn resolve_with(
+    command: &str,
+    mut lookup: impl FnMut(&str) -> std::result::Result<String, SecretError>,
+) -> std::result::Result<String, SecretError> {
+    let mut values: HashMap<&str, String> = HashMap::new();
+    let mut resolved = String::with_capacity(command.len());
+    let mut copied = 0;
+    let mut open = None;
+
+    for reference in find_references(command) {
+        if !values.contains_key(reference.name) {
+            values.insert(reference.name, lookup(reference.name)?);
+        }
+        let before = &command[copied..reference.start];
+        open = quote_after(before, open);
+        let reopen = open.map(String::from).unwrap_or_default();
+        resolved.push_str(before);
+        resolved.push_str(&reopen);
+        resolved.push_str(&shell::quote(&values[reference.name]));
+        resolved.push_str(&reopen);
+        copied = reference.end;
+    }
+    resolved.push_str(&command[copied..]);
+
+    Ok(resolved)
+}
+
+/// Returns the quote left open at the end of a piece of shell command.
+///
+/// # Arguments
+///
+/// * `text` - The piece of command
+/// * `open` - The quote open at its start, `'` or `"`
+fn quote_after(text: &str, mut open: Option<char>) -> Option<char> {
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (open, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => open = None,
+            (Some('\''), _) => {}
+            (_, '\\') => {
+                chars.next();
+            }
+            (None, '\'' | '"') => open = Some(c),
+            _ => {}
+        }
+    }
+    open
+}
+
+/// Looks secrets up in the OS keychain, then in the secrets file.
+#[derive(Debug, Clone)]
+pub struct SecretStore {
+    file: PathBuf,
+    use_keychain: bool,
+}
+
+impl SecretStore {
+    /// Creates a store reading the keychain and `~/.gz-claude/secrets.json`.
+    pub fn new() -> Self {
+        Self {
+            file: secrets_file_path(),
+            use_keychain: true,
+        }
+    }
+
+    /// Creates a store reading only the given secrets file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The secrets file
+    pub fn from_file(file: PathBuf) -> Self {
+        Self {
+            file,
+            use_keychain: false,
+        }
+    }
+
+    /// Returns the value of a secret.
+    ///
+    /// # Errors
+    ///
+    /// - `SecretError::NotFound` if neither the keychain nor the file has it
+    /// - `SecretError::InsecureFile` if the file is readable by others
+    /// - `SecretError::InvalidFile` if the file cannot be read or parsed
+    pub fn get(&self, name: &str) -> std::result::Result<String, SecretError> {
+        if self.use_keychain {
+            // Keychain failures (no keychain, locked, denied) fall through to the file
+            let entry = keyring::Entry::new(KEYRING_SERVICE, name);
+            if let Ok(value) = entry.and_then(|e| e.get_password()) {
+                return Ok(value);
+            }
+        }
+
+        if !self.file.exists() {
+            return Err(self.not_found(name));
+        }
+        read_secrets_file(&self.file)?
+            .remove(name)
+            .ok_or_else(|| self.not_found(name))
+    }
+
+    /// Replaces the secret references of a command with their values.
+    ///
+    /// # Errors
+    ///
+    /// Same as `get`, for the first secret that cannot be read.
+    pub fn resolve(&self, command: &str) -> std::result::Result<String, SecretError> {
+        resolve_with(command, |name| self.get(name))
+    }
+
+    fn not_found(&self, name: &str) -> SecretError {
+        SecretError::NotFound {
+            name: name.to_string(),
+            path: self.file.clone(),
+        }
+    }
+}
+
+impl Default for SecretStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stores a secret in the OS keychain.
+///
+/// # Errors
+///
+/// - `SecretError::InvalidName` if the name is not a valid secret name
+/// - `SecretError::Keychain` if the keychain rejects the value
+pub fn set_in_keychain(name: &str, value: &str) -> Result<()> {
+    keychain_entry(name)?
+        .set_password(value)
+        .map_err(|e| keychain_error(name, e))?;
+    Ok(())
+}
+
+/// Removes a secret from the OS keychain.
+///
+/// # Errors
+///
+/// - `SecretError::InvalidName` if the name is not a valid secret name
+/// - `SecretError::Keychain` if the secret does not exist or cannot be removed
+pub fn delete_from_keychain(name: &str) -> Result<()> {
+    keychain_entry(name)?
+        .delete_credential()
+        .map_err(|e| keychain_error(name, e))?;
+    Ok(())
+}
+
+fn keychain_entry(name: &str) -> Result<keyring::Entry> {
+    if !is_valid_name(name) {
+        return Err(SecretError::InvalidName {
+            name: name.to_string(),
+        }
+        .into());
+    }
+    keyring::Entry::new(KEYRING_SERVICE, name).map_err(|e| keychain_error(name, e).into())
+}
+
+fn keychain_error(name: &str, error: keyring::Error) -> SecretError {
+    SecretError::Keychain {
+        name: name.to_string(),
+        message: error.to_string(),
+    }
+}
+
+/// Reads the secrets file, a JSON object of names to values.
+///
+/// On Unix the file must not be accessible by the group or others.
+fn read_secrets_file(path: &Path) -> std::result::Result<HashMap<String, String>, SecretError> {
+    let invalid = |message: String| SecretError::InvalidFile {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)
+            .map_err(|e| invalid(e.to_string()))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            return Err(SecretError::InsecureFile {
+                path: path.to_path_buf(),
+                mode: mode & 0o777,
+            });
+        }
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+    serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))
+}
+
+/// Writes a command to a private script that deletes itself when run.
+///
+/// Scripts left over from commands that never ran are removed first.
+///
+/// # Arguments
+///
+/// * `command` - The resolved command
+///
+/// # Returns
+///
+/// The path of the script, to run with `sh`.
+///
+/// # Errors
+///
+/// Returns an error if the script directory or file cannot be written.
+pub fn write_script(command: &str) -> Result<PathBuf> {
+    write_script_in(&scripts_dir(), command)
+}
+
+fn write_script_in(dir: &Path, command: &str) -> Result<PathBuf> {
+    create_private_dir(dir)?;
+    remove_stale_scripts(dir);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let path = dir.join(format!("command-{}-{}.sh", std::process::id(), nanos));
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)?;
+    file.write_all(format!("rm -f -- \"$0\"\n{}\n", command).as_bytes())?;
+
+    Ok(path)
+}
+
+fn create_private_dir(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+fn remove_stale_scripts(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= STALE_SCRIPT_AGE);
+        if stale {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_secrets(dir: &TempDir, content: &str, mode: u32) -> PathBuf {
+        let path = dir.path().join("secrets.json");
+        fs::write(&path, content).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+        }
+        #[cfg(not(unix))]
+        let _ = mode;
+        path
+    }
+
+    #[test]
+    fn when_finding_references_should_skip_invalid_names() {
+        let command = "curl -H 'X: {{secret:API_TOKEN}}' {{secret:}} {{secret:a b}} {{secret:API_TOKEN}} {{secret:db.pass}}";
+
+        assert_eq!(referenced_names(command), vec!["API_TOKEN", "db.pass"]);
+        assert!(has_references(command));
+        assert!(!has_references("echo {{secret:}}"));
+        assert!(!has_references("echo {{secret:TOKEN"));
+    }
+
+    #[test]
+    fn when_resolving_should_replace_every_reference_and_look_up_once() {
+        let mut lookups = Vec::new();
+
+        let resolved = resolve_with("a={{secret:A}} b={{secret:B}} again={{secret:A}}", |name| {
+            lookups.push(name.to_string());
+            Ok(format!("<{}>", name.to_lowercase()))
+        })
+        .unwrap();
+
+        assert_eq!(resolved, "a='<a>' b='<b>' again='<a>'");
+        assert_eq!(lookups, vec!["A", "B"]);
+    }
+
+    #[test]
+    fn when_secret_is_missing_should_report_its_name() {
+        let dir = TempDir::new().unwrap();
+        let path = write_secrets(&dir, r#"{ "OTHER": "x" }"#, 0o600);
+        let store = SecretStore::from_file(path);
+
+        let err = store.resolve("echo {{secret:TOKEN}}").unwrap_err();
+
+        assert!(matches!(err, SecretError::NotFound { ref name, .. } if name == "TOKEN"));
+    }
+
+    #[test]
+    fn when_secrets_file_is_private_should_resolve_from_it() {
+        let dir = TempDir::new().unwrap();
+        let path = write_secrets(&dir, r#"{ "TOKEN": "s3cr3t" }"#, 0o600);
+        let store = SecretStore::from_file(path);
+
+        assert_eq!(
+            store
+                .resolve("curl -H 'Authorization: Bearer {{secret:TOKEN}}'")
+                .unwrap(),
+            "curl -H 'Authorization: Bearer ''s3cr3t'''"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn when_secret_has_shell_metacharacters_should_keep_them_in_the_value() {
+        let value = r#"a b; echo injected $(id) `id` 'x' "y" \z"#;
+        let resolve = |command| resolve_with(command, |_| Ok(value.to_string())).unwrap();
+
+        for command in [
+            "printf %s {{secret:V}}",
+            "printf %s '{{secret:V}}'",
+            "printf %s \"{{secret:V}}\"",
+            "printf %s \"it's \\\"\"{{secret:V}}",
+        ] {
+            let output = std::process::Command::new("sh")
+                .args(["-c", &resolve(command)])
+                .output()
+                .unwrap();
+            let expected = if command.contains("it's") {
+                format!("it's \"{}", value)
+            } else {
+                value.to_string()
+            };
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                expected,
+                "{}",
+                command
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn when_secrets_file_is_readable_by_others_should_refuse_it() {
+        let dir = TempDir::new().unwrap();
+        let path = write_secrets(&dir, r#"{ "TOKEN": "s3cr3t" }"#, 0o644);
+        let store = SecretStore::from_file(path);
+
+        let err = store.get("TOKEN").unwrap_err();
+
+        assert!(matches!(err, SecretError::InsecureFile { mode: 0o644, .. }));
+    }
+
+    #[test]
+    fn when_writing_script_should_delete_itself_and_be_private() {
+        let dir = TempDir::new().unwrap();
+        let scripts = dir.path().join("run");
+
+        let path = write_script_in(&scripts, "echo s3cr3t").unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "rm -f -- \"$0\"\necho s3cr3t\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let status = std::process::Command::new("sh")
+            .arg(&path)
+            .stdout(std::process::Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,105 @@
+//! Session state file versioning and migration.
+//!
+//! State files carry a top-level `version`; files without one are version 0.
+//! Older shapes are upgraded step by step when read, and files written by a
+//! newer gz-claude are refused instead of being misread.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde_json::{Map, Value};
+
+use crate::error::SessionError;
+
+/// State file version written by this version of gz-claude.
+pub const SESSION_VERSION: u64 = 1;
+
+/// A migration upgrades a document by one version.
+type Migration = fn(&mut Map<String, Value>);
+
+/// Migrations in order; entry `i` upgrades version `i` to `i + 1`.
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
+
+/// Upgrades a state document to the current version.
+///
+/// # Arguments
+///
+/// * `document` - The parsed state file
+///
+/// # Errors
+///
+/// - `SessionError::UnsupportedSessionVersion` if the file is from a newer gz-claude
+pub fn migrate(document: &mut Value) -> Result<(), SessionError> {
+    let Some(root) = document.as_object_mut() else {
+        return Ok(());
+    };
+
+    let version = root.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > SESSION_VERSION {
+        return Err(SessionError::UnsupportedSessionVersion {
+            found: version,
+            supported: SESSION_VERSION,
+        });
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(root);
+    }
+    root.remove("version");
+    Ok(())
+}
+
+/// Returns a state document stamped with the current version, first.
+///
+/// # Arguments
+///
+/// * `document` - The serialized session
+pub fn stamp(document: Value) -> Value {
+    let Value::Object(fields) = document else {
+        return document;
+    };
+    let mut stamped = Map::new();
+    stamped.insert("version".to_string(), Value::from(SESSION_VERSION));
+    stamped.extend(fields.into_iter().filter(|(key, _)| key != "version"));
+    Value::Object(stamped)
+}
+
+/// Version 0 to 1.
+///
+/// Unversioned files have the shape of version 1, except for the legacy
+/// global `session.json`, which had no session name.
+fn migrate_v0_to_v1(root: &mut Map<String, Value>) {
+    root.entry("zellij_session")
+        .or_insert_with(|| Value::from(""));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn when_state_is_unversioned_should_upgrade_it() {
+        let mut document = json!({ "panes": {} });
+
+        migrate(&mut document).unwrap();
+
+        assert_eq!(document, json!({ "zellij_session": "", "panes": {} }));
+    }
+
+    #[test]
+    fn when_state_is_from_newer_version_should_fail() {
+        let mut document = json!({ "version": SESSION_VERSION + 1, "panes": {} });
+
+        let err = migrate(&mut document).unwrap_err().to_string();
+
+        assert!(err.contains("newer"), "{}", err);
+    }
+
+    #[test]
+    fn when_stamping_state_should_put_version_first() {
+        let document = stamp(json!({ "zellij_session": "work", "version": 0 }));
+
+        let keys: Vec<&String> = document.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["version", "zellij_session"]);
+        assert_eq!(document["version"], json!(SESSION_VERSION));
+    }
+}
@@ -3,27 +3,148 @@
 //! Tracks Zellij panes associated with projects, enabling:
 //! - Focus existing panes instead of creating duplicates
 //! - Session restoration on restart
+//! - Exporting the panes to a snapshot and replaying it elsewhere
+//!
+//! State is stored per Zellij session in `~/.gz-claude/sessions/<name>.json`,
+//! so instances running in different sessions don't overwrite each other.
+//! Files are locked while read or written, carry a version, and the last
+//! readable state is kept as `<name>.json.bak` to recover from a damaged file.
+//!
+//! Several panels (one per Zellij tab) can share a session file. Saving
+//! merges the panes and groups with the ones on disk: those another panel
+//! opened, closed or updated since this one last read or wrote the file are
+//! kept that way, while the ones this panel opened or updated keep its
+//! values.
 //!
 //! @author waabox(waabox[at]gmail[dot]com)
 
+#![allow(dead_code)]
+
+mod migrate;
+mod snapshot;
+
+pub use snapshot::{PaneTarget, ReplayStep, SessionSnapshot};
+
+use fs4::FileExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use crate::config::Config;
-use crate::error::Result;
+use crate::config::{Config, PanePlacement};
+use crate::error::{Result, SessionError};
 
-/// Session state file name.
-const SESSION_FILE: &str = "session.json";
+/// Directory holding one state file per Zellij session.
+const SESSIONS_DIR: &str = "sessions";
+
+/// Legacy global session state file, replaced by per-session files.
+const LEGACY_SESSION_FILE: &str = "session.json";
+
+/// Session name used when not running inside Zellij.
+const DEFAULT_SESSION_NAME: &str = "gz-claude";
+
+/// Maximum length of a pane name, in characters.
+const MAX_PANE_NAME_CHARS: usize = 48;
 
 /// Information about an open pane.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PaneInfo {
     /// Unique name for the pane (used for Zellij identification).
     pub pane_name: String,
     /// The command running in the pane.
     pub command: String,
+    /// Whether the pane is floating rather than the main pane.
+    #[serde(default)]
+    pub floating: bool,
+    /// The Zellij pane id (e.g. "terminal_3"), when reported by Zellij.
+    #[serde(default)]
+    pub pane_id: Option<String>,
+    /// Whether Claude went idle in the pane and the user hasn't looked yet.
+    #[serde(default)]
+    pub needs_attention: bool,
+    /// Whether a floating pane was embedded (tiled) or moved to a tab.
+    #[serde(default)]
+    pub embedded: bool,
+}
+
+impl PaneInfo {
+    /// Returns whether the pane runs Claude Code.
+    ///
+    /// A pane counts when the first word of its command is `claude`.
+    pub fn is_claude(&self) -> bool {
+        crate::mcp::is_claude_command(&self.command)
+    }
+}
+
+/// A pane opened as part of a group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupPane {
+    /// Unique name for the pane (used for Zellij identification).
+    pub pane_name: String,
+    /// The command running in the pane.
+    pub command: String,
+    /// Where the pane was opened.
+    pub placement: PanePlacement,
+    /// The Zellij pane id, when reported by Zellij.
+    #[serde(default)]
+    pub pane_id: Option<String>,
+}
+
+/// The panes opened together by a composite action, closed together too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaneGroup {
+    /// The name of the action that opened the group.
+    pub action: String,
+    /// The panes, in the order they were opened.
+    pub panes: Vec<GroupPane>,
+}
+
+/// The outcome of reading the state file of a Zellij session.
+#[derive(Debug)]
+pub enum SessionLoad {
+    /// There is no state file.
+    Missing,
+    /// The state was read, upgraded to the current version if needed.
+    Loaded(Session),
+    /// The state file could not be parsed; it was moved aside.
+    Damaged {
+        /// Why the file could not be read.
+        error: String,
+        /// Where the damaged file was moved.
+        kept_as: PathBuf,
+        /// The last readable state, if a backup exists.
+        backup: Option<Session>,
+    },
+    /// The state file could not be opened or is from a newer gz-claude; it
+    /// was left in place.
+    Unreadable {
+        /// Why the file could not be read.
+        error: String,
+    },
+}
+
+/// Why a state file could not be read.
+#[derive(Debug)]
+enum ReadError {
+    /// The file isn't a state file gz-claude can parse.
+    Damaged(String),
+    /// The file was written by a newer gz-claude.
+    Newer(SessionError),
+    /// The file could not be opened, locked or read.
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Damaged(message) => f.write_str(message),
+            ReadError::Newer(e) => e.fmt(f),
+            ReadError::Io(e) => e.fmt(f),
+        }
+    }
 }
 
 /// Session state tracking open panes and Zellij session.
@@ -33,6 +154,55 @@ pub struct Session {
     pub zellij_session: String,
     /// Map of project paths to their pane info.
     pub panes: HashMap<PathBuf, PaneInfo>,
+    /// Map of project paths to the pane group opened for them.
+    #[serde(default)]
+    pub groups: HashMap<PathBuf, PaneGroup>,
+    /// What the panel shows, for the top bar.
+    #[serde(default)]
+    pub focus: Option<SessionFocus>,
+    /// Where the panel was in each view, restored on its next start.
+    #[serde(default)]
+    pub panel: PanelState,
+    /// The Zellij pane id of the main pane, recorded by the panel.
+    #[serde(default)]
+    pub main_pane: Option<String>,
+    /// The panes when the file was last read or written.
+    #[serde(skip)]
+    synced_panes: HashMap<PathBuf, PaneInfo>,
+    /// The pane groups when the file was last read or written.
+    #[serde(skip)]
+    synced_groups: HashMap<PathBuf, PaneGroup>,
+}
+
+/// The selection and scroll offset of a view.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewPosition {
+    /// The index of the selected item.
+    pub selected: usize,
+    /// The index of the first item shown.
+    #[serde(default)]
+    pub scroll: usize,
+}
+
+/// What the panel remembers across navigations and restarts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanelState {
+    /// The position of each visited view, by view key (e.g. `projects:work`).
+    #[serde(default)]
+    pub positions: HashMap<String, ViewPosition>,
+    /// The directories expanded in the file browser, sorted.
+    #[serde(default)]
+    pub expanded_dirs: Vec<PathBuf>,
+}
+
+/// The workspace and project currently shown in the panel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionFocus {
+    /// The identifier of the workspace.
+    pub workspace_id: String,
+    /// The selected or browsed project, if any.
+    #[serde(default)]
+    pub project_path: Option<PathBuf>,
 }
 
 impl Session {
@@ -41,35 +211,201 @@ impl Session {
         Self {
             zellij_session,
             panes: HashMap::new(),
+            groups: HashMap::new(),
+            focus: None,
+            panel: PanelState::default(),
+            main_pane: None,
+            synced_panes: HashMap::new(),
+            synced_groups: HashMap::new(),
         }
     }
 
-    /// Returns the path to the session file.
-    pub fn session_path() -> PathBuf {
-        Config::default_dir().join(SESSION_FILE)
+    /// Returns the name of the current Zellij session.
+    ///
+    /// Reads `ZELLIJ_SESSION_NAME`, falling back to "gz-claude" outside Zellij.
+    pub fn current_name() -> String {
+        std::env::var("ZELLIJ_SESSION_NAME").unwrap_or_else(|_| DEFAULT_SESSION_NAME.to_string())
+    }
+
+    /// Returns the directory holding the per-session state files.
+    pub fn sessions_dir() -> PathBuf {
+        Config::default_dir().join(SESSIONS_DIR)
+    }
+
+    /// Returns the path to the state file of a Zellij session.
+    ///
+    /// Characters that are not safe in file names are replaced by '_'.
+    ///
+    /// # Arguments
+    ///
+    /// * `zellij_session` - The Zellij session name
+    pub fn session_path(zellij_session: &str) -> PathBuf {
+        Self::path_in(&Self::sessions_dir(), zellij_session)
+    }
+
+    /// Returns the path to a session's state file within a directory.
+    fn path_in(dir: &Path, zellij_session: &str) -> PathBuf {
+        let file_name: String = zellij_session
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        dir.join(format!("{}.json", file_name))
     }
 
-    /// Check if a session file exists.
-    pub fn exists() -> bool {
-        Self::session_path().exists()
+    /// Check if a state file exists for a Zellij session.
+    pub fn exists(zellij_session: &str) -> bool {
+        Self::session_path(zellij_session).exists()
     }
 
-    /// Load session from file.
+    /// Returns when the state file of a Zellij session was last written.
+    ///
+    /// # Returns
+    ///
+    /// The modification time, or None if the file doesn't exist.
+    pub fn modified(zellij_session: &str) -> Option<SystemTime> {
+        fs::metadata(Self::session_path(zellij_session))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Load the state of a Zellij session from its file.
+    ///
+    /// Use `open` where a damaged file must be reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `zellij_session` - The Zellij session name
     ///
     /// # Returns
     ///
     /// The loaded session, or None if file doesn't exist or is invalid.
-    pub fn load() -> Option<Self> {
-        let path = Self::session_path();
-        if !path.exists() {
-            return None;
+    pub fn load(zellij_session: &str) -> Option<Self> {
+        Self::load_from(&Self::session_path(zellij_session))
+    }
+
+    /// Reads the state of a Zellij session, reporting a damaged file.
+    ///
+    /// A file that can't be parsed is renamed to `<name>.json.corrupt`, so
+    /// the next save doesn't overwrite it, and the backup is read instead. A
+    /// file that can't be opened, or that a newer gz-claude wrote, is left
+    /// where it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `zellij_session` - The Zellij session name
+    pub fn open(zellij_session: &str) -> SessionLoad {
+        Self::open_at(&Self::session_path(zellij_session))
+    }
+
+    /// Reads a state file, reporting and moving it aside when damaged.
+    fn open_at(path: &Path) -> SessionLoad {
+        let error = match Self::read_from(path) {
+            Ok(Some(session)) => return SessionLoad::Loaded(session),
+            Ok(None) => return SessionLoad::Missing,
+            Err(ReadError::Damaged(error)) => error,
+            Err(error) => {
+                return SessionLoad::Unreadable {
+                    error: error.to_string(),
+                }
+            }
+        };
+        let kept_as = path.with_extension("json.corrupt");
+        let kept_as = match fs::rename(path, &kept_as) {
+            Ok(()) => kept_as,
+            Err(_) => path.to_path_buf(),
+        };
+        let backup = Self::read_from(&Self::backup_path(path)).ok().flatten();
+        SessionLoad::Damaged {
+            error,
+            kept_as,
+            backup,
         }
+    }
+
+    /// Returns the path of the backup of a state file.
+    fn backup_path(path: &Path) -> PathBuf {
+        path.with_extension("json.bak")
+    }
 
-        let content = fs::read_to_string(&path).ok()?;
-        serde_json::from_str(&content).ok()
+    /// Load session state from a file, holding a shared lock while reading.
+    fn load_from(path: &Path) -> Option<Self> {
+        Self::read_from(path).ok().flatten()
     }
 
-    /// Save session to file.
+    /// Reads a state file, holding a shared lock while reading.
+    ///
+    /// # Returns
+    ///
+    /// The session, or None if the file doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Why the file could not be read, parsed or upgraded.
+    fn read_from(path: &Path) -> std::result::Result<Option<Self>, ReadError> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(ReadError::Io(e)),
+        };
+        FileExt::lock_shared(&file).map_err(ReadError::Io)?;
+
+        let mut content = String::new();
+        let read = file.read_to_string(&mut content);
+        let _ = FileExt::unlock(&file);
+        match read {
+            Ok(_) => {}
+            // Not UTF-8, so not JSON either
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                return Err(ReadError::Damaged(e.to_string()))
+            }
+            Err(e) => return Err(ReadError::Io(e)),
+        }
+
+        Self::parse(&content).map(Some)
+    }
+
+    /// Parses the content of a state file, upgrading older versions.
+    fn parse(content: &str) -> std::result::Result<Self, ReadError> {
+        let damaged = |e: serde_json::Error| ReadError::Damaged(e.to_string());
+        let mut document: serde_json::Value = serde_json::from_str(content).map_err(damaged)?;
+        migrate::migrate(&mut document).map_err(ReadError::Newer)?;
+        let mut session: Self = serde_json::from_value(document).map_err(damaged)?;
+        session.mark_synced();
+        Ok(session)
+    }
+
+    /// Records the panes and groups as matching the file.
+    fn mark_synced(&mut self) {
+        self.synced_panes = self.panes.clone();
+        self.synced_groups = self.groups.clone();
+    }
+
+    /// Merges the panes and groups of the state on disk into this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_disk` - The state another panel may have written since
+    fn merge_from(&mut self, on_disk: Session) {
+        self.panes = merge_entries(
+            std::mem::take(&mut self.panes),
+            on_disk.panes,
+            &self.synced_panes,
+        );
+        self.groups = merge_entries(
+            std::mem::take(&mut self.groups),
+            on_disk.groups,
+            &self.synced_groups,
+        );
+    }
+
+    /// Save session to its file, merged with the panes other panels
+    /// registered or removed in the meantime.
     ///
     /// # Returns
     ///
@@ -77,32 +413,123 @@ impl Session {
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be written.
-    pub fn save(&self) -> Result<()> {
-        let path = Self::session_path();
+    /// Returns an error if the file cannot be locked or written.
+    pub fn save(&mut self) -> Result<()> {
+        self.save_to(&Self::session_path(&self.zellij_session))
+    }
 
+    /// Save session state to a file, holding an exclusive lock while the
+    /// file is read, merged and written.
+    fn save_to(&mut self, path: &Path) -> Result<()> {
         // Ensure directory exists
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        let content = serde_json::to_string_pretty(self)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-        fs::write(&path, content)?;
+        // Truncate only once the lock is held, so readers never see a partial file
+        let mut file = OpenOptions::new()
+            .write(true)
+            .read(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        FileExt::lock(&file)?;
+        let written = self.merge_and_write(&mut file, path);
+        let _ = FileExt::unlock(&file);
+        written?;
+
+        self.mark_synced();
+        Ok(())
+    }
+
+    /// Merges the locked state file into this session and overwrites it.
+    ///
+    /// The previous state is kept as the backup, unless it's damaged.
+    ///
+    /// # Errors
+    ///
+    /// `SessionError::UnsupportedSessionVersion` if a newer gz-claude wrote
+    /// the file, which is then left untouched.
+    fn merge_and_write(&mut self, file: &mut File, path: &Path) -> Result<()> {
+        let mut previous = String::new();
+        if file.read_to_string(&mut previous).is_ok() {
+            match Self::parse(&previous) {
+                Ok(on_disk) => {
+                    let _ = fs::write(Self::backup_path(path), &previous);
+                    self.merge_from(on_disk);
+                }
+                Err(ReadError::Newer(e)) => return Err(e.into()),
+                Err(_) => {}
+            }
+        }
 
+        let document = serde_json::to_value(&*self).map_err(std::io::Error::other)?;
+        let content = serde_json::to_string_pretty(&migrate::stamp(document))
+            .map_err(std::io::Error::other)?;
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(content.as_bytes())?;
         Ok(())
     }
 
-    /// Delete the session file.
-    pub fn delete() -> Result<()> {
-        let path = Self::session_path();
-        if path.exists() {
-            fs::remove_file(&path)?;
+    /// Delete the state file of a Zellij session and its backup.
+    pub fn delete(zellij_session: &str) -> Result<()> {
+        let path = Self::session_path(zellij_session);
+        for path in [Self::backup_path(&path), path] {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
         }
         Ok(())
     }
 
-    /// Register a pane for a project.
+    /// Removes state files of Zellij sessions that no longer exist.
+    ///
+    /// Also removes the legacy global `session.json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `active_sessions` - Names of the Zellij sessions that still exist
+    ///
+    /// # Returns
+    ///
+    /// The number of state files removed.
+    pub fn garbage_collect(active_sessions: &[String]) -> usize {
+        let _ = fs::remove_file(Config::default_dir().join(LEGACY_SESSION_FILE));
+        Self::garbage_collect_in(&Self::sessions_dir(), active_sessions)
+    }
+
+    /// Removes state files in a directory that don't belong to an active session.
+    fn garbage_collect_in(dir: &Path, active_sessions: &[String]) -> usize {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return 0;
+        };
+
+        let keep: Vec<PathBuf> = active_sessions
+            .iter()
+            .map(|name| Self::path_in(dir, name))
+            .collect();
+
+        // Backups and damaged files go along with their session's state file
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                [".json", ".json.bak", ".json.corrupt"]
+                    .iter()
+                    .find_map(|suffix| name.strip_suffix(suffix))
+                    .is_some_and(|stem| !keep.contains(&dir.join(format!("{}.json", stem))))
+            })
+            .filter(|path| fs::remove_file(path).is_ok())
+            .count()
+    }
+
+    /// Register the main pane as running a project's command.
+    ///
+    /// The pane gets the id of the main pane, when it was recorded; commands
+    /// are only written to the main pane once Zellij shows that id focused
+    /// under the main pane's name, so a registered pane has the right id.
     ///
     /// # Arguments
     ///
@@ -115,6 +542,38 @@ impl Session {
             PaneInfo {
                 pane_name,
                 command,
+                floating: false,
+                pane_id: self.main_pane.clone(),
+                needs_attention: false,
+                embedded: false,
+            },
+        );
+    }
+
+    /// Register a floating pane running a project's command.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project directory path
+    /// * `pane_name` - Unique name for the Zellij pane
+    /// * `command` - The command running in the pane
+    /// * `pane_id` - The Zellij pane id, if known
+    pub fn register_floating_pane(
+        &mut self,
+        project_path: PathBuf,
+        pane_name: String,
+        command: String,
+        pane_id: Option<String>,
+    ) {
+        self.panes.insert(
+            project_path,
+            PaneInfo {
+                pane_name,
+                command,
+                floating: true,
+                pane_id,
+                needs_attention: false,
+                embedded: false,
             },
         );
     }
@@ -132,6 +591,51 @@ impl Session {
         self.panes.get(project_path)
     }
 
+    /// Returns the name gz-claude gave the main pane.
+    ///
+    /// # Returns
+    ///
+    /// The name of the pane registered in the main pane, or None if no
+    /// project runs in it.
+    pub fn main_pane_name(&self) -> Option<&str> {
+        self.panes
+            .values()
+            .find(|pane| !pane.floating)
+            .map(|pane| pane.pane_name.as_str())
+    }
+
+    /// Returns the project a Zellij pane was opened for.
+    ///
+    /// # Arguments
+    ///
+    /// * `pane_id` - The Zellij pane id, e.g. "terminal_3"
+    ///
+    /// # Returns
+    ///
+    /// The project whose pane, or pane of a group, has the id; the main pane
+    /// belongs to the project running in it. None for other panes.
+    pub fn project_of_pane(&self, pane_id: &str) -> Option<&Path> {
+        let id = Some(pane_id);
+        let pane = self
+            .panes
+            .iter()
+            .find(|(_, pane)| pane.pane_id.as_deref() == id)
+            .or_else(|| {
+                // The main pane keeps its id while projects take turns in it
+                (self.main_pane.as_deref() == id)
+                    .then(|| self.panes.iter().find(|(_, pane)| !pane.floating))
+                    .flatten()
+            });
+        let group = || {
+            self.groups
+                .iter()
+                .find(|(_, group)| group.panes.iter().any(|p| p.pane_id.as_deref() == id))
+        };
+        pane.map(|(path, _)| path)
+            .or_else(|| group().map(|(path, _)| path))
+            .map(PathBuf::as_path)
+    }
+
     /// Remove a pane registration.
     ///
     /// # Arguments
@@ -141,7 +645,30 @@ impl Session {
         self.panes.remove(project_path);
     }
 
-    /// Generate a unique pane name for a project.
+    /// Register the pane group opened for a project, replacing any previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project directory path
+    /// * `group` - The panes opened together
+    pub fn register_group(&mut self, project_path: PathBuf, group: PaneGroup) {
+        self.groups.insert(project_path, group);
+    }
+
+    /// Get the pane group of a project.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project directory path
+    ///
+    /// # Returns
+    ///
+    /// The group if one is open for the project.
+    pub fn get_group(&self, project_path: &Path) -> Option<&PaneGroup> {
+        self.groups.get(project_path)
+    }
+
+    /// Remove a pane group registration.
     ///
     /// # Arguments
     ///
@@ -149,22 +676,116 @@ impl Session {
     ///
     /// # Returns
     ///
-    /// A unique pane name based on the project path.
-    pub fn generate_pane_name(project_path: &PathBuf) -> String {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
+    /// The removed group, if there was one.
+    pub fn remove_group(&mut self, project_path: &Path) -> Option<PaneGroup> {
+        self.groups.remove(project_path)
+    }
+
+    /// Returns how many registered panes run Claude Code.
+    pub fn claude_pane_count(&self) -> usize {
+        self.panes.values().filter(|pane| pane.is_claude()).count()
+    }
+
+    /// Returns how many Claude panes wait for input unseen.
+    pub fn attention_count(&self) -> usize {
+        self.panes
+            .values()
+            .filter(|pane| pane.is_claude() && pane.needs_attention)
+            .count()
+    }
+
+    /// Builds the name shown for a pane in Zellij and the web client.
+    ///
+    /// The name reads `{workspace}/{project} · {action}`. Each part is
+    /// sanitized and the result is capped at 48 characters, ending with '…'
+    /// when cut.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace` - The workspace name
+    /// * `project` - The project name
+    /// * `action` - What runs in the pane (e.g. the action name)
+    ///
+    /// # Returns
+    ///
+    /// The pane name.
+    pub fn pane_name(workspace: &str, project: &str, action: &str) -> String {
+        let name = format!(
+            "{}/{} · {}",
+            sanitize_pane_name_part(workspace),
+            sanitize_pane_name_part(project),
+            sanitize_pane_name_part(action)
+        );
+
+        if name.chars().count() <= MAX_PANE_NAME_CHARS {
+            return name;
+        }
+        let mut truncated: String = name.chars().take(MAX_PANE_NAME_CHARS - 1).collect();
+        truncated.truncate(truncated.trim_end().len());
+        truncated.push('…');
+        truncated
+    }
+}
 
-        let mut hasher = DefaultHasher::new();
-        project_path.hash(&mut hasher);
-        let hash = hasher.finish();
+/// Cleans one part of a pane name.
+///
+/// Control characters become spaces, '/' becomes '-' so it can't be
+/// mistaken for the separator, and runs of whitespace collapse to one space.
+fn sanitize_pane_name_part(part: &str) -> String {
+    let cleaned: String = part
+        .chars()
+        .map(|c| match c {
+            '/' => '-',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
 
-        format!("gz-{:x}", hash)
+/// Merges the entries of a map with the ones written by another panel.
+///
+/// Entries only on disk were added by another panel unless this one had
+/// them when it last synced, in which case it removed them. Entries only
+/// here were added by this panel unless they were synced, in which case
+/// another panel removed them. Entries in both keep this panel's value if
+/// it added or updated them since it last synced, and the value on disk
+/// otherwise, which another panel may have updated.
+///
+/// # Arguments
+///
+/// * `ours` - The entries of this panel
+/// * `on_disk` - The entries of the file
+/// * `synced` - The entries when this panel last read or wrote the file
+fn merge_entries<V: PartialEq>(
+    ours: HashMap<PathBuf, V>,
+    mut on_disk: HashMap<PathBuf, V>,
+    synced: &HashMap<PathBuf, V>,
+) -> HashMap<PathBuf, V> {
+    let mut merged = HashMap::new();
+    for (key, value) in ours {
+        let value = match (synced.get(&key), on_disk.remove(&key)) {
+            // Removed by another panel
+            (Some(_), None) => continue,
+            // Unchanged here, so the file has the latest value
+            (Some(before), Some(theirs)) if *before == value => theirs,
+            // Added or updated here since the last sync
+            _ => value,
+        };
+        merged.insert(key, value);
     }
+    merged.extend(
+        on_disk
+            .into_iter()
+            .filter(|(key, _)| !synced.contains_key(key)),
+    );
+    merged
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::GzClaudeError;
     use tempfile::TempDir;
 
     #[test]
@@ -174,6 +795,35 @@ mod tests {
         assert!(session.panes.is_empty());
     }
 
+    #[test]
+    fn when_counting_claude_panes_should_match_program_name() {
+        let mut session = Session::new("test-session".to_string());
+        session.register_pane(
+            PathBuf::from("/a"),
+            "a".to_string(),
+            "claude --resume 1234".to_string(),
+        );
+        session.register_floating_pane(
+            PathBuf::from("/b"),
+            "b".to_string(),
+            "/usr/local/bin/claude".to_string(),
+            None,
+        );
+        session.register_pane(
+            PathBuf::from("/c"),
+            "c".to_string(),
+            "cargo test claude".to_string(),
+        );
+
+        assert_eq!(session.claude_pane_count(), 2);
+        assert_eq!(session.attention_count(), 0);
+
+        for pane in session.panes.values_mut() {
+            pane.needs_attention = true;
+        }
+        assert_eq!(session.attention_count(), 2);
+    }
+
     #[test]
     fn when_registering_pane_should_store_info() {
         let mut session = Session::new("test-session".to_string());
@@ -187,12 +837,446 @@ mod tests {
     }
 
     #[test]
-    fn when_generating_pane_name_should_be_deterministic() {
+    fn when_registering_floating_pane_should_store_its_id() {
+        let mut session = Session::new("test-session".to_string());
         let path = PathBuf::from("/test/project");
-        let name1 = Session::generate_pane_name(&path);
-        let name2 = Session::generate_pane_name(&path);
-        assert_eq!(name1, name2);
-        assert!(name1.starts_with("gz-"));
+
+        session.register_floating_pane(
+            path.clone(),
+            "gz-abc123".to_string(),
+            "claude".to_string(),
+            Some("terminal_3".to_string()),
+        );
+
+        let pane = session.get_pane(&path).unwrap();
+        assert!(pane.floating);
+        assert_eq!(pane.pane_id.as_deref(), Some("terminal_3"));
+    }
+
+    #[test]
+    fn when_pane_has_focus_should_find_the_project_it_was_opened_for() {
+        let mut session = Session::new("work".to_string());
+        session.main_pane = Some("terminal_2".to_string());
+        session.register_pane(
+            PathBuf::from("/work/api"),
+            "api".to_string(),
+            "claude".to_string(),
+        );
+        session.register_floating_pane(
+            PathBuf::from("/work/web"),
+            "web".to_string(),
+            "claude".to_string(),
+            Some("terminal_5".to_string()),
+        );
+        session.register_group(
+            PathBuf::from("/work/ops"),
+            PaneGroup {
+                action: "Dev".to_string(),
+                panes: vec![GroupPane {
+                    pane_name: "server".to_string(),
+                    command: "npm start".to_string(),
+                    placement: PanePlacement::Floating,
+                    pane_id: Some("terminal_7".to_string()),
+                }],
+            },
+        );
+
+        assert_eq!(
+            session.project_of_pane("terminal_2"),
+            Some(Path::new("/work/api"))
+        );
+        assert_eq!(
+            session.project_of_pane("terminal_5"),
+            Some(Path::new("/work/web"))
+        );
+        assert_eq!(
+            session.project_of_pane("terminal_7"),
+            Some(Path::new("/work/ops"))
+        );
+        assert_eq!(session.project_of_pane("terminal_1"), None);
+    }
+
+    #[test]
+    fn when_registering_main_pane_should_store_its_id() {
+        let mut session = Session::new("work".to_string());
+        let path = PathBuf::from("/work/api");
+        session.register_pane(path.clone(), "api".to_string(), "claude".to_string());
+        assert_eq!(session.get_pane(&path).unwrap().pane_id, None);
+
+        session.main_pane = Some("terminal_2".to_string());
+        session.register_pane(path.clone(), "api".to_string(), "claude".to_string());
+
+        assert_eq!(
+            session.get_pane(&path).unwrap().pane_id.as_deref(),
+            Some("terminal_2")
+        );
+    }
+
+    #[test]
+    fn when_building_pane_name_should_join_workspace_project_and_action() {
+        assert_eq!(
+            Session::pane_name("Work", "api", "Claude"),
+            "Work/api · Claude"
+        );
+    }
+
+    #[test]
+    fn when_building_pane_name_should_sanitize_parts() {
+        assert_eq!(
+            Session::pane_name("Side/Projects", "my\tapi\n", "  Run   tests "),
+            "Side-Projects/my api · Run tests"
+        );
+    }
+
+    #[test]
+    fn when_building_long_pane_name_should_cap_its_length() {
+        let name = Session::pane_name("Workspace", &"project".repeat(10), "Claude");
+
+        assert_eq!(name.chars().count(), MAX_PANE_NAME_CHARS);
+        assert!(name.starts_with("Workspace/projectproject"));
+        assert!(name.ends_with('…'));
+    }
+
+    #[test]
+    fn when_saving_session_should_round_trip_through_its_file() {
+        let dir = TempDir::new().unwrap();
+        let path = Session::path_in(dir.path(), "work");
+        let mut session = Session::new("work".to_string());
+        session.register_pane(
+            PathBuf::from("/test/project"),
+            "gz-1".to_string(),
+            "claude".to_string(),
+        );
+
+        session.save_to(&path).unwrap();
+        let loaded = Session::load_from(&path).unwrap();
+
+        assert_eq!(loaded.zellij_session, "work");
+        assert_eq!(
+            loaded
+                .get_pane(&PathBuf::from("/test/project"))
+                .unwrap()
+                .pane_name,
+            "gz-1"
+        );
+    }
+
+    #[test]
+    fn when_saving_session_should_write_version_and_keep_previous_as_backup() {
+        let dir = TempDir::new().unwrap();
+        let path = Session::path_in(dir.path(), "work");
+        let mut session = Session::new("work".to_string());
+        session.save_to(&path).unwrap();
+        session.register_pane(
+            PathBuf::from("/test/project"),
+            "gz-1".to_string(),
+            "claude".to_string(),
+        );
+
+        session.save_to(&path).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains(&format!("\"version\": {}", migrate::SESSION_VERSION)));
+        let backup = Session::load_from(&Session::backup_path(&path)).unwrap();
+        assert!(backup.panes.is_empty());
+        assert_eq!(Session::load_from(&path).unwrap().panes.len(), 1);
+    }
+
+    #[test]
+    fn when_loading_unversioned_session_should_migrate_it() {
+        let dir = TempDir::new().unwrap();
+        let path = Session::path_in(dir.path(), "work");
+        fs::write(&path, r#"{"panes": {}}"#).unwrap();
+
+        let loaded = Session::load_from(&path).unwrap();
+
+        assert_eq!(loaded.zellij_session, "");
+    }
+
+    #[test]
+    fn when_session_file_is_damaged_should_move_it_aside_and_offer_backup() {
+        let dir = TempDir::new().unwrap();
+        let path = Session::path_in(dir.path(), "work");
+        let mut session = Session::new("work".to_string());
+        session.register_pane(
+            PathBuf::from("/test/project"),
+            "gz-1".to_string(),
+            "claude".to_string(),
+        );
+        session.save_to(&path).unwrap();
+        session.save_to(&path).unwrap();
+        fs::write(&path, "{ not json").unwrap();
+
+        let SessionLoad::Damaged {
+            kept_as, backup, ..
+        } = Session::open_at(&path)
+        else {
+            panic!("expected a damaged session");
+        };
+
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(&kept_as).unwrap(), "{ not json");
+        assert_eq!(backup.unwrap().panes.len(), 1);
+    }
+
+    #[test]
+    fn when_session_file_is_from_newer_version_should_leave_it_in_place() {
+        let dir = TempDir::new().unwrap();
+        let path = Session::path_in(dir.path(), "work");
+        let newer = r#"{"version": 99, "zellij_session": "work", "panes": {}}"#;
+        fs::write(&path, newer).unwrap();
+
+        let load = Session::open_at(&path);
+        let saved = Session::new("work".to_string()).save_to(&path);
+
+        assert!(matches!(load, SessionLoad::Unreadable { .. }), "{:?}", load);
+        assert!(matches!(
+            saved,
+            Err(GzClaudeError::Session(
+                SessionError::UnsupportedSessionVersion { found: 99, .. }
+            ))
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+        assert!(!path.with_extension("json.corrupt").exists());
+    }
+
+    #[test]
+    fn when_saving_over_damaged_file_should_keep_the_backup() {
+        let dir = TempDir::new().unwrap();
+        let path = Session::path_in(dir.path(), "work");
+        let mut session = Session::new("work".to_string());
+        session.save_to(&path).unwrap();
+        session.save_to(&path).unwrap();
+        fs::write(&path, "garbage").unwrap();
+
+        session.save_to(&path).unwrap();
+
+        assert!(Session::load_from(&Session::backup_path(&path)).is_some());
+        assert!(matches!(Session::open_at(&path), SessionLoad::Loaded(_)));
+        assert!(matches!(
+            Session::open_at(&dir.path().join("missing.json")),
+            SessionLoad::Missing
+        ));
+    }
+
+    #[test]
+    fn when_panels_save_concurrently_should_keep_every_pane() {
+        let dir = TempDir::new().unwrap();
+        let path = Session::path_in(dir.path(), "work");
+        Session::new("work".to_string()).save_to(&path).unwrap();
+
+        let writers: Vec<_> = (0..8)
+            .map(|writer| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let mut session = Session::load_from(&path).unwrap();
+                    for pane in 0..5 {
+                        session.register_pane(
+                            PathBuf::from(format!("/project/{}-{}", writer, pane)),
+                            format!("gz-{}-{}", writer, pane),
+                            "claude".to_string(),
+                        );
+                        session.save_to(&path).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let session = Session::load_from(&path).unwrap();
+        assert_eq!(session.panes.len(), 40);
+    }
+
+    #[test]
+    fn when_another_panel_removed_pane_should_not_restore_it() {
+        let dir = TempDir::new().unwrap();
+        let path = Session::path_in(dir.path(), "work");
+        let mut session = Session::new("work".to_string());
+        session.register_pane(
+            PathBuf::from("/a"),
+            "gz-a".to_string(),
+            "claude".to_string(),
+        );
+        session.save_to(&path).unwrap();
+        let mut first = Session::load_from(&path).unwrap();
+        let mut second = Session::load_from(&path).unwrap();
+
+        first.remove_pane(&PathBuf::from("/a"));
+        first.save_to(&path).unwrap();
+        second.register_pane(
+            PathBuf::from("/b"),
+            "gz-b".to_string(),
+            "claude".to_string(),
+        );
+        second.save_to(&path).unwrap();
+
+        let saved = Session::load_from(&path).unwrap();
+        let mut projects: Vec<&PathBuf> = saved.panes.keys().collect();
+        projects.sort();
+        assert_eq!(projects, [&PathBuf::from("/b")]);
+        assert_eq!(second.panes.len(), 1);
+    }
+
+    #[test]
+    fn when_merging_entries_should_keep_changes_of_both_panels() {
+        let synced: HashMap<PathBuf, &str> = [
+            ("/kept", "before"),
+            ("/removed-here", "before"),
+            ("/removed-there", "ours"),
+            ("/updated-there", "before"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (PathBuf::from(k), v))
+        .collect();
+        let ours: HashMap<PathBuf, &str> = [
+            ("/kept", "ours"),
+            ("/removed-there", "ours"),
+            ("/updated-there", "before"),
+            ("/added-here", "ours"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (PathBuf::from(k), v))
+        .collect();
+        let on_disk: HashMap<PathBuf, &str> = [
+            ("/kept", "theirs"),
+            ("/removed-here", "theirs"),
+            ("/updated-there", "theirs"),
+            ("/added-there", "theirs"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (PathBuf::from(k), v))
+        .collect();
+
+        let merged = merge_entries(ours, on_disk, &synced);
+
+        let mut entries: Vec<(&str, &str)> = merged
+            .iter()
+            .map(|(k, v)| (k.to_str().unwrap(), *v))
+            .collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            [
+                ("/added-here", "ours"),
+                ("/added-there", "theirs"),
+                ("/kept", "ours"),
+                ("/updated-there", "theirs")
+            ]
+        );
+    }
+
+    #[test]
+    fn when_panels_update_the_same_pane_should_keep_the_latest_update() {
+        let dir = TempDir::new().unwrap();
+        let path = Session::path_in(dir.path(), "work");
+        let mut session = Session::new("work".to_string());
+        for project in ["/a", "/b"] {
+            session.register_pane(
+                PathBuf::from(project),
+                format!("gz-{}", project),
+                "claude".to_string(),
+            );
+        }
+        session.save_to(&path).unwrap();
+        let mut first = Session::load_from(&path).unwrap();
+        let mut second = Session::load_from(&path).unwrap();
+        let a = PathBuf::from("/a");
+        let b = PathBuf::from("/b");
+
+        first.panes.get_mut(&a).unwrap().needs_attention = true;
+        first.save_to(&path).unwrap();
+        second.panes.get_mut(&b).unwrap().pane_id = Some("terminal_4".to_string());
+        second.save_to(&path).unwrap();
+
+        let saved = Session::load_from(&path).unwrap();
+        assert!(saved.panes[&a].needs_attention);
+        assert_eq!(saved.panes[&b].pane_id.as_deref(), Some("terminal_4"));
+        assert!(second.panes[&a].needs_attention);
+
+        first.panes.get_mut(&a).unwrap().needs_attention = false;
+        first.save_to(&path).unwrap();
+        second.panes.get_mut(&a).unwrap().pane_id = Some("terminal_2".to_string());
+        second.save_to(&path).unwrap();
+
+        let saved = Session::load_from(&path).unwrap();
+        assert_eq!(saved.panes[&a].pane_id.as_deref(), Some("terminal_2"));
+        assert_eq!(saved.panes[&b].pane_id.as_deref(), Some("terminal_4"));
+    }
+
+    #[test]
+    fn when_session_names_differ_should_use_separate_files() {
+        let dir = TempDir::new().unwrap();
+
+        let a = Session::path_in(dir.path(), "alpha");
+        let b = Session::path_in(dir.path(), "beta/../x");
+
+        assert_ne!(a, b);
+        assert_eq!(b.parent().unwrap(), dir.path());
+    }
+
+    #[test]
+    fn when_garbage_collecting_should_remove_only_inactive_sessions() {
+        let dir = TempDir::new().unwrap();
+        for name in ["alive", "dead"] {
+            Session::new(name.to_string())
+                .save_to(&Session::path_in(dir.path(), name))
+                .unwrap();
+        }
+
+        let removed = Session::garbage_collect_in(dir.path(), &["alive".to_string()]);
+
+        assert_eq!(removed, 1);
+        assert!(Session::path_in(dir.path(), "alive").exists());
+        assert!(!Session::path_in(dir.path(), "dead").exists());
+    }
+
+    #[test]
+    fn when_garbage_collecting_should_remove_backups_of_inactive_sessions() {
+        let dir = TempDir::new().unwrap();
+        for name in ["alive", "dead"] {
+            let path = Session::path_in(dir.path(), name);
+            let mut session = Session::new(name.to_string());
+            session.save_to(&path).unwrap();
+            session.save_to(&path).unwrap();
+        }
+
+        let removed = Session::garbage_collect_in(dir.path(), &["alive".to_string()]);
+
+        assert_eq!(removed, 2);
+        let alive = Session::path_in(dir.path(), "alive");
+        assert!(Session::backup_path(&alive).exists());
+        assert!(!Session::backup_path(&Session::path_in(dir.path(), "dead")).exists());
+    }
+
+    #[test]
+    fn when_registering_group_should_round_trip_and_remove_it() {
+        let dir = TempDir::new().unwrap();
+        let path = Session::path_in(dir.path(), "work");
+        let project = PathBuf::from("/test/project");
+        let mut session = Session::new("work".to_string());
+        session.register_group(
+            project.clone(),
+            PaneGroup {
+                action: "dev".to_string(),
+                panes: vec![GroupPane {
+                    pane_name: "Work/project · dev: Watch".to_string(),
+                    command: "cargo watch".to_string(),
+                    placement: PanePlacement::Down,
+                    pane_id: Some("terminal_5".to_string()),
+                }],
+            },
+        );
+
+        session.save_to(&path).unwrap();
+        let mut loaded = Session::load_from(&path).unwrap();
+
+        let group = loaded.get_group(&project).unwrap();
+        assert_eq!(group.action, "dev");
+        assert_eq!(group.panes[0].placement, PanePlacement::Down);
+        assert!(loaded.remove_group(&project).is_some());
+        assert!(loaded.get_group(&project).is_none());
     }
 
     #[test]
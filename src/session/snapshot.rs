@@ -0,0 +1,300 @@
+//! Portable snapshots of a session's panes.
+//!
+//! A snapshot lists the panes of a session in the order they were opened,
+//! the main pane first, with the workspace and project the panel showed.
+//! Importing it into another Zellij session, on this machine or another one,
+//! runs the same commands in the same kind of panes again.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Session, SessionFocus};
+use crate::error::{Result, SessionError};
+
+/// Version of the snapshot format written by this gz-claude.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A pane recorded in a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotPane {
+    /// The project directory the pane belongs to.
+    pub project_path: PathBuf,
+    /// The name shown for the pane in Zellij.
+    pub pane_name: String,
+    /// The command running in the pane.
+    pub command: String,
+    /// Whether the pane is floating rather than the main pane.
+    #[serde(default)]
+    pub floating: bool,
+}
+
+/// The panes of a session, in the order they were opened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// The snapshot format version.
+    pub version: u32,
+    /// The Zellij session the snapshot was taken from.
+    pub zellij_session: String,
+    /// What the panel showed.
+    #[serde(default)]
+    pub focus: Option<SessionFocus>,
+    /// The panes, main pane first.
+    #[serde(default)]
+    pub panes: Vec<SnapshotPane>,
+}
+
+/// Where an imported pane is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneTarget {
+    /// The main pane of the layout.
+    Main,
+    /// A new floating pane.
+    Floating,
+}
+
+/// What importing a snapshot does with one of its panes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayStep {
+    /// Run the pane's command again.
+    Open(SnapshotPane, PaneTarget),
+    /// Leave the pane out: its project directory doesn't exist here.
+    MissingProject(SnapshotPane),
+    /// Leave the pane out: its project already has a pane in the session.
+    AlreadyOpen(SnapshotPane),
+}
+
+impl SessionSnapshot {
+    /// Takes a snapshot of a session.
+    ///
+    /// The main pane comes first, then floating panes in the order Zellij
+    /// created them (by pane id), panes without an id last, by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to snapshot
+    pub fn of(session: &Session) -> Self {
+        let mut panes: Vec<_> = session.panes.iter().collect();
+        panes.sort_by(|(_, a), (_, b)| {
+            let order = |pane: &super::PaneInfo| {
+                let id = pane
+                    .pane_id
+                    .as_deref()
+                    .and_then(|id| id.rsplit('_').next())
+                    .and_then(|n| n.parse::<u64>().ok());
+                (pane.floating, id.is_none(), id)
+            };
+            order(a)
+                .cmp(&order(b))
+                .then_with(|| a.pane_name.cmp(&b.pane_name))
+        });
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            zellij_session: session.zellij_session.clone(),
+            focus: session.focus.clone(),
+            panes: panes
+                .into_iter()
+                .map(|(path, pane)| SnapshotPane {
+                    project_path: path.clone(),
+                    pane_name: pane.pane_name.clone(),
+                    command: pane.command.clone(),
+                    floating: pane.floating,
+                })
+                .collect(),
+        }
+    }
+
+    /// Reads a snapshot file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The snapshot file
+    ///
+    /// # Errors
+    ///
+    /// - `GzClaudeError::Io` if the file can't be read
+    /// - `SessionError::InvalidSnapshot` if the file isn't a snapshot
+    /// - `SessionError::UnsupportedSnapshotVersion` if it was written by a
+    ///   newer gz-claude
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let snapshot: Self =
+            serde_json::from_str(&content).map_err(|e| SessionError::InvalidSnapshot {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(SessionError::UnsupportedSnapshotVersion {
+                found: snapshot.version,
+                supported: SNAPSHOT_VERSION,
+            }
+            .into());
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot as JSON, replacing the file if it exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GzClaudeError::Io` if the file can't be written.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        content.push('\n');
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Decides how each pane is imported into a session.
+    ///
+    /// Panes of projects missing on this machine or already open in the
+    /// session are left out. The recorded main pane goes to the main pane
+    /// only if it is free; every other pane opens floating.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session the snapshot is imported into
+    /// * `main_free` - Whether commands can be run in the main pane
+    /// * `exists` - Tells whether a project directory exists
+    pub fn replay_plan(
+        &self,
+        session: &Session,
+        mut main_free: bool,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Vec<ReplayStep> {
+        self.panes
+            .iter()
+            .cloned()
+            .map(|pane| {
+                if session.panes.contains_key(&pane.project_path) {
+                    ReplayStep::AlreadyOpen(pane)
+                } else if !exists(&pane.project_path) {
+                    ReplayStep::MissingProject(pane)
+                } else if !pane.floating && main_free {
+                    main_free = false;
+                    ReplayStep::Open(pane, PaneTarget::Main)
+                } else {
+                    ReplayStep::Open(pane, PaneTarget::Floating)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn session() -> Session {
+        let mut session = Session::new("work".to_string());
+        session.register_floating_pane(
+            PathBuf::from("/work/web"),
+            "helios/web · Test".to_string(),
+            "cargo test /work/web".to_string(),
+            Some("terminal_12".to_string()),
+        );
+        session.register_floating_pane(
+            PathBuf::from("/work/db"),
+            "helios/db · Claude".to_string(),
+            "claude /work/db".to_string(),
+            Some("terminal_3".to_string()),
+        );
+        session.register_pane(
+            PathBuf::from("/work/api"),
+            "helios/api · Claude".to_string(),
+            "claude /work/api".to_string(),
+        );
+        session.focus = Some(SessionFocus {
+            workspace_id: "helios".to_string(),
+            project_path: Some(PathBuf::from("/work/api")),
+        });
+        session
+    }
+
+    fn paths(snapshot: &SessionSnapshot) -> Vec<&Path> {
+        snapshot
+            .panes
+            .iter()
+            .map(|p| p.project_path.as_path())
+            .collect()
+    }
+
+    #[test]
+    fn when_taking_snapshot_should_order_main_pane_then_creation_order() {
+        let snapshot = SessionSnapshot::of(&session());
+
+        assert_eq!(
+            paths(&snapshot),
+            vec![
+                Path::new("/work/api"),
+                Path::new("/work/db"),
+                Path::new("/work/web")
+            ]
+        );
+        assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+        assert_eq!(snapshot.focus, session().focus);
+    }
+
+    #[test]
+    fn when_writing_and_reading_snapshot_should_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("snapshot.json");
+        let snapshot = SessionSnapshot::of(&session());
+
+        snapshot.write(&path).unwrap();
+
+        assert_eq!(SessionSnapshot::read(&path).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn when_snapshot_is_newer_or_invalid_should_fail() {
+        let dir = TempDir::new().unwrap();
+        let newer = dir.path().join("newer.json");
+        fs::write(&newer, r#"{ "version": 2, "zellij_session": "work" }"#).unwrap();
+        let invalid = dir.path().join("invalid.json");
+        fs::write(&invalid, "{}").unwrap();
+
+        let message = SessionSnapshot::read(&newer).unwrap_err().to_string();
+        assert!(message.contains("version 2"), "{}", message);
+        let message = SessionSnapshot::read(&invalid).unwrap_err().to_string();
+        assert!(message.contains("not a session snapshot"), "{}", message);
+    }
+
+    #[test]
+    fn when_planning_replay_should_skip_open_and_missing_projects() {
+        let snapshot = SessionSnapshot::of(&session());
+        let mut target = Session::new("home".to_string());
+        target.register_floating_pane(
+            PathBuf::from("/work/web"),
+            "web".to_string(),
+            "bash".to_string(),
+            None,
+        );
+
+        let plan = snapshot.replay_plan(&target, true, |path| path != Path::new("/work/db"));
+
+        assert_eq!(
+            plan,
+            vec![
+                ReplayStep::Open(snapshot.panes[0].clone(), PaneTarget::Main),
+                ReplayStep::MissingProject(snapshot.panes[1].clone()),
+                ReplayStep::AlreadyOpen(snapshot.panes[2].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn when_main_pane_is_taken_should_open_everything_floating() {
+        let snapshot = SessionSnapshot::of(&session());
+
+        let plan = snapshot.replay_plan(&Session::new("home".to_string()), false, |_| true);
+
+        assert!(plan
+            .iter()
+            .all(|step| matches!(step, ReplayStep::Open(_, PaneTarget::Floating))));
+    }
+}
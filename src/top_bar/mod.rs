@@ -0,0 +1,487 @@
+//! The bar shown above the panel.
+//!
+//! Renders the widgets configured in `top_bar` on a single line: the web
+//! client URL, a clock, the panel's workspace, the git summary of its selected
+//! project, the number of Claude Code panes and the output of custom commands.
+//! Each widget refreshes on its own interval; command widgets run in
+//! background threads so a slow command never blocks the bar.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+pub mod picker;
+
+use std::fmt::Write as _;
+use std::net::IpAddr;
+use std::path::Path;
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+
+use crate::config::{
+    Config, GitInfoLevel, TopBarColor, TopBarConfig, TopBarWidget, TopBarWidgetKind,
+};
+use crate::git;
+use crate::session::Session;
+use crate::util::glyphs::{self, glyph};
+use crate::zellij;
+
+/// Clock format used when a clock widget sets none.
+pub const DEFAULT_CLOCK_FORMAT: &str = "%H:%M";
+
+/// How long the "Copied!" confirmation replaces the copy hint.
+const COPIED_MESSAGE_DURATION: Duration = Duration::from_secs(2);
+
+/// Returns the refresh interval of a widget kind when none is configured.
+pub fn default_interval(kind: TopBarWidgetKind) -> Duration {
+    match kind {
+        TopBarWidgetKind::WebUrl | TopBarWidgetKind::Clock | TopBarWidgetKind::Workspace => {
+            Duration::from_secs(1)
+        }
+        TopBarWidgetKind::ClaudePanes => Duration::from_secs(2),
+        TopBarWidgetKind::Git => Duration::from_secs(5),
+        TopBarWidgetKind::Command => Duration::from_secs(10),
+    }
+}
+
+/// Returns the ANSI SGR code of a color.
+pub fn ansi_code(color: TopBarColor) -> u8 {
+    match color {
+        TopBarColor::Black => 30,
+        TopBarColor::Red => 31,
+        TopBarColor::Green => 32,
+        TopBarColor::Yellow => 33,
+        TopBarColor::Blue => 34,
+        TopBarColor::Magenta => 35,
+        TopBarColor::Cyan => 36,
+        TopBarColor::White => 37,
+        TopBarColor::Gray => 90,
+    }
+}
+
+/// Joins widget texts into the bar line.
+///
+/// Empty texts are skipped so no separator is doubled; colored texts are
+/// wrapped in ANSI escape sequences.
+///
+/// # Arguments
+///
+/// * `parts` - The text and color of each widget, in display order
+/// * `separator` - Text placed between two widgets
+pub fn render_line(parts: &[(String, Option<TopBarColor>)], separator: &str) -> String {
+    let mut line = String::new();
+    for (text, color) in parts.iter().filter(|(text, _)| !text.is_empty()) {
+        if !line.is_empty() {
+            line.push_str(separator);
+        }
+        match color {
+            Some(color) => {
+                let _ = write!(line, "\x1B[{}m{}\x1B[0m", ansi_code(*color), text);
+            }
+            None => line.push_str(text),
+        }
+    }
+    line
+}
+
+/// Formats the Claude panes widget: the pane count, then how many wait for
+/// input, blinking when `blink` is set.
+///
+/// # Arguments
+///
+/// * `count` - The number of Claude panes
+/// * `attention` - The number of them waiting for input unseen
+/// * `blink` - Whether the waiting count blinks
+pub fn claude_panes_text(count: usize, attention: usize, blink: bool) -> String {
+    let times = glyph("×", "x");
+    let bell = glyph("🔔", "(!)");
+    match (count, attention) {
+        (0, _) => String::new(),
+        (count, 0) => format!("claude {}{}", times, count),
+        (count, attention) if blink => {
+            format!(
+                "claude {}{} \x1B[5m{} {}\x1B[25m",
+                times, count, bell, attention
+            )
+        }
+        (count, attention) => format!("claude {}{} {} {}", times, count, bell, attention),
+    }
+}
+
+/// Formats the current local time.
+///
+/// Returns an empty string for an invalid format instead of failing.
+pub fn format_clock(format: &str) -> String {
+    let mut text = String::new();
+    if write!(text, "{}", Local::now().format(format)).is_err() {
+        text.clear();
+    }
+    text
+}
+
+/// Inserts the Zellij session name into a saved web client URL.
+///
+/// `https://host:port/?token=xxx` becomes `https://host:port/session?token=xxx`
+/// so the URL opens the session directly. Other URLs are returned unchanged.
+pub fn session_web_url(url: &str, session: &str) -> String {
+    match url.split_once("/?token=") {
+        Some((base, token)) => format!("{}/{}?token={}", base, session, token),
+        None => url.to_string(),
+    }
+}
+
+/// Returns the name and `branch *` summary of a project.
+///
+/// The name comes from the configuration, or the directory name when the
+/// project is no longer configured.
+fn git_summary(config: &Config, workspace_id: &str, path: &Path) -> Option<String> {
+    let project = config
+        .workspace
+        .get(workspace_id)
+        .and_then(|w| w.projects.iter().find(|p| p.path == path));
+    let base = project.and_then(|p| config.resolve_base_branch(p));
+    let info = git::get_git_info_with_base(path, GitInfoLevel::Minimal, base)?;
+    let name = match project {
+        Some(project) => project.name.clone(),
+        None => path.file_name()?.to_string_lossy().to_string(),
+    };
+    Some(format!("{} {}", name, info.format_minimal()))
+}
+
+/// Returns the last output line of a command, trimmed.
+fn last_output_line(output: &str) -> String {
+    output
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// A widget with its last rendered text.
+struct WidgetState {
+    widget: TopBarWidget,
+    text: String,
+    refreshed: Option<Instant>,
+    pending: Option<JoinHandle<String>>,
+}
+
+impl WidgetState {
+    fn interval(&self) -> Duration {
+        self.widget
+            .interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| default_interval(self.widget.kind))
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        self.pending.is_none()
+            && self
+                .refreshed
+                .map_or(true, |at| now.duration_since(at) >= self.interval())
+    }
+}
+
+/// The top bar and the state of its widgets.
+pub struct TopBar {
+    widgets: Vec<WidgetState>,
+    separator: String,
+    session_name: Option<String>,
+    web_url: Option<String>,
+    copied_until: Option<Instant>,
+    picker: Option<(IpAddr, u16)>,
+}
+
+impl TopBar {
+    /// Creates a top bar from its configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The `top_bar` settings
+    /// * `session_name` - The Zellij session, used for the web URL and panel state
+    pub fn new(config: &TopBarConfig, session_name: Option<String>) -> Self {
+        Self {
+            widgets: config
+                .widgets
+                .iter()
+                .map(|widget| WidgetState {
+                    widget: widget.clone(),
+                    text: String::new(),
+                    refreshed: None,
+                    pending: None,
+                })
+                .collect(),
+            separator: config.separator.clone(),
+            session_name,
+            web_url: None,
+            copied_until: None,
+            picker: None,
+        }
+    }
+
+    /// Shows the URL of the pane picker instead of the web client's, once
+    /// the web client is running.
+    ///
+    /// # Arguments
+    ///
+    /// * `bind_address` - The address the picker listens on
+    /// * `port` - The port the picker listens on
+    pub fn with_picker(mut self, bind_address: IpAddr, port: u16) -> Self {
+        self.picker = Some((bind_address, port));
+        self
+    }
+
+    /// Returns the web client URL, or the pane picker's, once it is known.
+    pub fn web_url(&self) -> Option<&str> {
+        self.web_url.as_deref()
+    }
+
+    /// Shows the "Copied!" confirmation next to the web URL for a while.
+    pub fn show_copied(&mut self) {
+        self.copied_until = Some(Instant::now() + COPIED_MESSAGE_DURATION);
+    }
+
+    /// Refreshes the widgets that are due.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration, used to name workspaces and read git
+    ///   settings; the workspace and git widgets stay empty without it
+    ///
+    /// # Returns
+    ///
+    /// True if the rendered line may have changed.
+    pub fn refresh(&mut self, config: Option<&Config>) -> bool {
+        let now = Instant::now();
+        let mut changed = false;
+
+        if self.copied_until.is_some_and(|until| now >= until) {
+            self.copied_until = None;
+            changed = true;
+        }
+
+        // The panel's state is only read when a widget showing it is due
+        let needs_session = self.widgets.iter().any(|state| {
+            state.is_due(now)
+                && matches!(
+                    state.widget.kind,
+                    TopBarWidgetKind::Workspace
+                        | TopBarWidgetKind::Git
+                        | TopBarWidgetKind::ClaudePanes
+                )
+        });
+        let session = match &self.session_name {
+            Some(name) if needs_session => Session::load(name).unwrap_or_default(),
+            _ => Session::default(),
+        };
+
+        for index in 0..self.widgets.len() {
+            changed |= self.collect_command(index);
+            if !self.widgets[index].is_due(now) {
+                continue;
+            }
+            self.widgets[index].refreshed = Some(now);
+
+            let text = match self.widgets[index].widget.kind {
+                TopBarWidgetKind::WebUrl => self.load_web_url(),
+                TopBarWidgetKind::Clock => format_clock(
+                    self.widgets[index]
+                        .widget
+                        .format
+                        .as_deref()
+                        .unwrap_or(DEFAULT_CLOCK_FORMAT),
+                ),
+                TopBarWidgetKind::Workspace => session
+                    .focus
+                    .as_ref()
+                    .and_then(|focus| config?.workspace.get(&focus.workspace_id))
+                    .map(|workspace| workspace.name.clone())
+                    .unwrap_or_default(),
+                TopBarWidgetKind::Git => session
+                    .focus
+                    .as_ref()
+                    .and_then(|focus| {
+                        git_summary(config?, &focus.workspace_id, focus.project_path.as_deref()?)
+                    })
+                    .unwrap_or_default(),
+                TopBarWidgetKind::ClaudePanes => claude_panes_text(
+                    session.claude_pane_count(),
+                    session.attention_count(),
+                    config.is_some_and(|c| c.global.claude_idle.blink_top_bar),
+                ),
+                TopBarWidgetKind::Command => {
+                    let command = self.widgets[index]
+                        .widget
+                        .command
+                        .clone()
+                        .unwrap_or_default();
+                    self.widgets[index].pending = Some(thread::spawn(move || {
+                        Command::new("sh")
+                            .args(["-c", &command])
+                            .output()
+                            .map(|output| {
+                                last_output_line(&String::from_utf8_lossy(&output.stdout))
+                            })
+                            .unwrap_or_default()
+                    }));
+                    continue;
+                }
+            };
+
+            if self.widgets[index].text != text {
+                self.widgets[index].text = text;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    /// Stores the output of a command widget whose thread has finished.
+    fn collect_command(&mut self, index: usize) -> bool {
+        let state = &mut self.widgets[index];
+        if !state.pending.as_ref().is_some_and(|h| h.is_finished()) {
+            return false;
+        }
+        let Some(handle) = state.pending.take() else {
+            return false;
+        };
+        let text = handle.join().unwrap_or_default();
+        let changed = state.text != text;
+        state.text = text;
+        changed
+    }
+
+    /// Returns the web client URL, loading it until it has been saved.
+    fn load_web_url(&mut self) -> String {
+        if self.web_url.is_none() {
+            self.web_url = zellij::load_web_url().map(|url| {
+                let url = match &self.session_name {
+                    Some(session) => session_web_url(&url, session),
+                    None => url,
+                };
+                // Save the URL with the session so the panel copies the same one
+                let _ = zellij::save_web_url(&url);
+                match (self.picker, zellij::web_token(&url)) {
+                    // The picker takes the same token and redirects to the session
+                    (Some((bind_address, port)), Some(token)) => {
+                        zellij::web_url(bind_address, port, token, false)
+                    }
+                    _ => url,
+                }
+            });
+        }
+        self.web_url.clone().unwrap_or_default()
+    }
+
+    /// Renders the bar line.
+    pub fn render(&self) -> String {
+        let globe = glyph("🌐", "web:");
+        let parts: Vec<(String, Option<TopBarColor>)> = self
+            .widgets
+            .iter()
+            .map(|state| {
+                // The copy confirmation shows immediately, not on the widget's interval
+                let text = match state.widget.kind {
+                    TopBarWidgetKind::WebUrl if state.text.is_empty() => String::new(),
+                    TopBarWidgetKind::WebUrl if self.copied_until.is_some() => {
+                        format!("{} {}  {} Copied!", globe, state.text, glyph("✅", "ok"))
+                    }
+                    TopBarWidgetKind::WebUrl => format!("{} {}  [c] copy", globe, state.text),
+                    _ => state.text.clone(),
+                };
+                (text, state.widget.color)
+            })
+            .collect();
+
+        let separator = if glyphs::ascii_only() && !self.separator.is_ascii() {
+            " | "
+        } else {
+            self.separator.as_str()
+        };
+        let line = render_line(&parts, separator);
+        if line.is_empty() {
+            "gz-claude".to_string()
+        } else {
+            line
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_rendering_line_should_skip_empty_widgets_and_color_text() {
+        let parts = vec![
+            ("main".to_string(), None),
+            (String::new(), Some(TopBarColor::Red)),
+            ("12:30".to_string(), Some(TopBarColor::Cyan)),
+        ];
+
+        assert_eq!(render_line(&parts, " | "), "main | \x1B[36m12:30\x1B[0m");
+        assert_eq!(render_line(&[], " | "), "");
+    }
+
+    #[test]
+    fn when_inserting_session_into_web_url_should_keep_token() {
+        assert_eq!(
+            session_web_url("https://10.0.0.2:8082/?token=abc", "dev"),
+            "https://10.0.0.2:8082/dev?token=abc"
+        );
+        assert_eq!(
+            session_web_url("https://10.0.0.2:8082/", "dev"),
+            "https://10.0.0.2:8082/"
+        );
+    }
+
+    #[test]
+    fn when_claude_panes_wait_for_input_should_show_and_blink_their_count() {
+        assert_eq!(claude_panes_text(0, 0, true), "");
+        assert_eq!(claude_panes_text(2, 0, true), "claude ×2");
+        assert_eq!(claude_panes_text(2, 1, false), "claude ×2 🔔 1");
+        assert_eq!(
+            claude_panes_text(2, 1, true),
+            "claude ×2 \x1B[5m🔔 1\x1B[25m"
+        );
+    }
+
+    #[test]
+    fn when_formatting_clock_with_invalid_format_should_return_empty() {
+        assert_eq!(format_clock("%Q"), "");
+        assert_eq!(format_clock("%H:%M").len(), 5);
+    }
+
+    #[test]
+    fn when_reading_command_output_should_keep_last_line() {
+        assert_eq!(last_output_line("first\n  load 0.42  \n\n"), "load 0.42");
+        assert_eq!(last_output_line(""), "");
+    }
+
+    #[test]
+    fn when_widget_has_no_interval_should_use_kind_default() {
+        let mut widget = TopBarWidget::new(TopBarWidgetKind::Command);
+        let state = WidgetState {
+            widget: widget.clone(),
+            text: String::new(),
+            refreshed: None,
+            pending: None,
+        };
+        assert_eq!(state.interval(), Duration::from_secs(10));
+        assert!(state.is_due(Instant::now()));
+
+        widget.interval_secs = Some(30);
+        let state = WidgetState {
+            widget,
+            text: String::new(),
+            refreshed: Some(Instant::now()),
+            pending: None,
+        };
+        assert_eq!(state.interval(), Duration::from_secs(30));
+        assert!(!state.is_due(Instant::now()));
+    }
+}
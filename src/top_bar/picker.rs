@@ -0,0 +1,428 @@
+//! Pane picker for the web client.
+//!
+//! The web client shows whatever pane is fullscreened, which a phone user
+//! can't change. With `web_client.picker_port` set, the top bar serves a
+//! small page on that port listing the project panes of the session; the
+//! page needs the web client's token. Picking a pane leaves it in the
+//! session's pick file, redirects the phone to the session, and the panel
+//! fullscreens the pane when it finds the file. The page is plain HTTP, so
+//! it only listens on loopback, where the token can't be sniffed.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::session::Session;
+use crate::zellij;
+
+/// How long a request may take to arrive.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A pane listed on the picker page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickerEntry {
+    /// The project's name.
+    pub name: String,
+    /// The name of the project's workspace, empty when not configured.
+    pub workspace: String,
+    /// The project directory, which identifies the pane.
+    pub path: PathBuf,
+    /// Whether the pane runs Claude Code.
+    pub claude: bool,
+    /// Whether Claude waits for input in the pane.
+    pub attention: bool,
+}
+
+/// A response of the picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// The HTTP status line, without the version (e.g. "200 OK").
+    pub status: &'static str,
+    /// The redirect target, for "303 See Other".
+    pub location: Option<String>,
+    /// The HTML body.
+    pub body: String,
+}
+
+impl Response {
+    fn page(status: &'static str, body: String) -> Self {
+        Self {
+            status,
+            location: None,
+            body,
+        }
+    }
+
+    fn message(status: &'static str, message: &str) -> Self {
+        Self::page(
+            status,
+            page("gz-claude", &format!("<p>{}</p>", escape(message))),
+        )
+    }
+}
+
+/// Starts serving the picker page in a background thread.
+///
+/// The page takes the web client's token over plain HTTP, so it listens on
+/// loopback even when the web server listens beyond it.
+///
+/// # Arguments
+///
+/// * `bind_address` - The web server's address
+/// * `port` - The port to listen on
+/// * `zellij_session` - The session whose panes are listed
+///
+/// # Returns
+///
+/// The address the picker listens on.
+///
+/// # Errors
+///
+/// Returns an error if the port can't be bound.
+pub fn spawn(bind_address: IpAddr, port: u16, zellij_session: String) -> Result<SocketAddr> {
+    let listener = TcpListener::bind((zellij::plain_http_address(bind_address), port))?;
+    let address = listener.local_addr()?;
+    thread::spawn(move || {
+        // Only used to name the projects; the directory names do otherwise
+        let config = Config::load().ok();
+        for stream in listener.incoming().flatten() {
+            // A client that misbehaves only loses its own request
+            let _ = serve(stream, &zellij_session, config.as_ref());
+        }
+    });
+    Ok(address)
+}
+
+/// Answers one request.
+fn serve(stream: TcpStream, zellij_session: &str, config: Option<&Config>) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers are not used, but are read so the client sees a clean close
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => {
+            let web_url = zellij::load_web_url();
+            route(
+                target,
+                web_url.as_deref(),
+                |path| zellij::save_web_pick(zellij_session, path),
+                || entries(zellij_session, config),
+            )
+        }
+        _ => Response::message("405 Method Not Allowed", "Only GET is supported."),
+    };
+
+    let mut head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    if let Some(location) = &response.location {
+        let _ = write!(head, "Location: {}\r\n", location);
+    }
+    head.push_str("\r\n");
+    let mut stream = &stream;
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(response.body.as_bytes())?;
+    Ok(())
+}
+
+/// Answers a request for a target.
+///
+/// Every page needs the token of the web client: `/` lists the panes and
+/// `/pick?project=<path>` picks one, then redirects to the session.
+///
+/// # Arguments
+///
+/// * `target` - The request target, e.g. "/pick?token=t&project=%2Fwork%2Fapi"
+/// * `web_url` - The web client URL of the session, None until it is known
+/// * `pick` - Records the pick of a project's pane
+/// * `entries` - Returns the panes to list
+pub fn route(
+    target: &str,
+    web_url: Option<&str>,
+    pick: impl FnOnce(&Path) -> Result<()>,
+    entries: impl FnOnce() -> Vec<PickerEntry>,
+) -> Response {
+    let Some((web_url, token)) = web_url.and_then(|url| Some((url, zellij::web_token(url)?)))
+    else {
+        return Response::message("503 Service Unavailable", "The web client is not running.");
+    };
+    let (path, query) = parse_target(target);
+    if query.get("token").map(String::as_str) != Some(token) {
+        return Response::message("403 Forbidden", "Open the link shown in the top bar.");
+    }
+
+    match path.as_str() {
+        "/" => Response::page("200 OK", render_index(&entries(), token)),
+        "/pick" => {
+            let Some(project) = query.get("project").filter(|p| !p.is_empty()) else {
+                return Response::message("400 Bad Request", "No pane was picked.");
+            };
+            match pick(Path::new(project)) {
+                Ok(()) => Response {
+                    status: "303 See Other",
+                    location: Some(web_url.to_string()),
+                    body: String::new(),
+                },
+                Err(e) => Response::message("500 Internal Server Error", &e.to_string()),
+            }
+        }
+        _ => Response::message("404 Not Found", "There is no such page."),
+    }
+}
+
+/// Returns the project panes of a session, by workspace and project name.
+fn entries(zellij_session: &str, config: Option<&Config>) -> Vec<PickerEntry> {
+    let Some(session) = Session::load(zellij_session) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<PickerEntry> = session
+        .panes
+        .iter()
+        .map(|(path, pane)| {
+            let configured = config.and_then(|config| {
+                config.workspace.values().find_map(|workspace| {
+                    workspace
+                        .projects
+                        .iter()
+                        .find(|project| &project.path == path)
+                        .map(|project| (project.name.clone(), workspace.name.clone()))
+                })
+            });
+            let (name, workspace) = configured.unwrap_or_else(|| {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.display().to_string());
+                (name, String::new())
+            });
+            PickerEntry {
+                name,
+                workspace,
+                path: path.clone(),
+                claude: pane.is_claude(),
+                attention: pane.needs_attention,
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| (&a.workspace, &a.name).cmp(&(&b.workspace, &b.name)));
+    entries
+}
+
+/// Renders the picker page: one link per pane.
+///
+/// # Arguments
+///
+/// * `entries` - The panes to list
+/// * `token` - The web client token, carried by the links
+pub fn render_index(entries: &[PickerEntry], token: &str) -> String {
+    if entries.is_empty() {
+        return page("gz-claude", "<p>No project panes are open.</p>");
+    }
+    let mut list = String::from("<ul>");
+    for entry in entries {
+        let mut label = escape(&entry.name);
+        if !entry.workspace.is_empty() {
+            let _ = write!(label, " <small>{}</small>", escape(&entry.workspace));
+        }
+        if entry.claude {
+            label.push_str(" <small>Claude</small>");
+        }
+        if entry.attention {
+            label.push_str(" <b>waiting</b>");
+        }
+        let _ = write!(
+            list,
+            "<li><a href=\"/pick?token={}&amp;project={}\">{}</a></li>",
+            percent_encode(token),
+            percent_encode(&entry.path.to_string_lossy()),
+            label
+        );
+    }
+    list.push_str("</ul>");
+    page("gz-claude panes", &list)
+}
+
+/// Wraps a body in a page sized for phones.
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>{title}</title><style>\
+         body{{font-family:sans-serif;margin:1em;background:#111;color:#eee}}\
+         ul{{list-style:none;padding:0}}\
+         a{{display:block;padding:1em;margin:.5em 0;background:#222;color:#eee;\
+         border-radius:.5em;text-decoration:none}}\
+         small{{color:#999}}b{{color:#fc3}}\
+         </style></head><body><h1>{title}</h1>{body}</body></html>",
+        title = escape(title),
+        body = body
+    )
+}
+
+/// Splits a request target into its path and decoded query parameters.
+fn parse_target(target: &str) -> (String, HashMap<String, String>) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params = query
+        .split('&')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            Some((percent_decode(key), percent_decode(value)))
+        })
+        .collect();
+    (path.to_string(), params)
+}
+
+/// Encodes a query parameter value.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            let _ = write!(encoded, "%{:02X}", byte);
+        }
+    }
+    encoded
+}
+
+/// Decodes a query parameter value; `+` stands for a space.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Escapes text for HTML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    const WEB_URL: &str = "https://host:8082/gz-claude?token=secret";
+
+    #[test]
+    fn when_web_server_listens_beyond_loopback_should_keep_picker_on_loopback() {
+        let address = spawn("0.0.0.0".parse().unwrap(), 0, "gz".to_string()).unwrap();
+
+        assert!(address.ip().is_loopback());
+    }
+
+    fn api_entry() -> PickerEntry {
+        PickerEntry {
+            name: "api & co".to_string(),
+            workspace: "Work".to_string(),
+            path: PathBuf::from("/work/my api"),
+            claude: true,
+            attention: true,
+        }
+    }
+
+    #[test]
+    fn when_encoding_path_should_decode_to_the_same_path() {
+        let encoded = percent_encode("/work/my api+ñ");
+        assert_eq!(encoded, "%2Fwork%2Fmy%20api%2B%C3%B1");
+        assert_eq!(percent_decode(&encoded), "/work/my api+ñ");
+        assert_eq!(percent_decode("a+b%2"), "a b%2");
+    }
+
+    #[test]
+    fn when_rendering_index_should_link_each_pane_with_the_token() {
+        let html = render_index(&[api_entry()], "secret");
+
+        assert!(html.contains(
+            "<a href=\"/pick?token=secret&amp;project=%2Fwork%2Fmy%20api\">api &amp; co <small>Work</small>"
+        ));
+        assert!(html.contains("<b>waiting</b>"));
+    }
+
+    #[test]
+    fn when_token_is_wrong_should_refuse_the_request() {
+        let response = route("/?token=guess", Some(WEB_URL), |_| Ok(()), Vec::new);
+
+        assert_eq!(response.status, "403 Forbidden");
+    }
+
+    #[test]
+    fn when_web_client_is_not_running_should_be_unavailable() {
+        let response = route("/?token=secret", None, |_| Ok(()), Vec::new);
+
+        assert_eq!(response.status, "503 Service Unavailable");
+    }
+
+    #[test]
+    fn when_listing_panes_should_render_the_index() {
+        let response = route(
+            "/?token=secret",
+            Some(WEB_URL),
+            |_| Ok(()),
+            || vec![api_entry()],
+        );
+
+        assert_eq!(response.status, "200 OK");
+        assert!(response.body.contains("api &amp; co"));
+    }
+
+    #[test]
+    fn when_picking_pane_should_record_it_and_redirect_to_the_session() {
+        let picked = RefCell::new(None);
+
+        let response = route(
+            "/pick?token=secret&project=%2Fwork%2Fmy%20api",
+            Some(WEB_URL),
+            |path| {
+                *picked.borrow_mut() = Some(path.to_path_buf());
+                Ok(())
+            },
+            Vec::new,
+        );
+
+        assert_eq!(response.status, "303 See Other");
+        assert_eq!(response.location.as_deref(), Some(WEB_URL));
+        assert_eq!(picked.into_inner(), Some(PathBuf::from("/work/my api")));
+    }
+}
@@ -4,8 +4,136 @@
 
 #![allow(dead_code)]
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::config::ProjectId;
+use crate::session::{PanelState, ViewPosition};
+use crate::tui::vim::VimKeys;
+
+/// How long a toast notification stays visible.
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// Severity of a toast notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    /// Informational message (e.g. confirmation of an action).
+    Info,
+    /// Error message (e.g. a failed hook or command).
+    Error,
+}
+
+/// A short-lived notification displayed over the current view.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    /// The message to display.
+    pub message: String,
+    /// The severity of the message.
+    pub level: ToastLevel,
+    /// When the toast should disappear.
+    pub expires_at: Instant,
+}
+
+/// A modal text panel displayed over the current view until dismissed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Popup {
+    /// Title shown in the popup border.
+    pub title: String,
+    /// The text content of the popup.
+    pub body: String,
+}
+
+/// What a prompt asks for, and the answers collected before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptKind {
+    /// Name of a project to add to the current workspace.
+    ProjectName,
+    /// Directory of a project to add to the current workspace.
+    ProjectPath {
+        /// The name entered in the previous step.
+        name: String,
+    },
+    /// Confirmation before removing a project from the current workspace.
+    ConfirmRemove {
+        /// The index of the project to remove.
+        project_index: usize,
+        /// The name of the project, to find it in the configuration file.
+        name: String,
+        /// The directory of the project, to find it in the configuration file.
+        path: PathBuf,
+    },
+    /// Confirmation before cloning the repository of a missing project.
+    ConfirmClone {
+        /// The index of the project to clone.
+        project_index: usize,
+    },
+    /// Text to search for in the files of the current project.
+    SearchQuery,
+    /// Choice between the backup of a damaged session state and a fresh one.
+    ConfirmRestoreSession,
+    /// Name of a Claude task to start on a new branch of a project.
+    TaskName {
+        /// The index of the project.
+        project_index: usize,
+    },
+    /// Choice between a worktree and the project's checkout for a new task.
+    TaskWorktree {
+        /// The index of the project.
+        project_index: usize,
+        /// The name entered in the previous step.
+        task: String,
+    },
+    /// Answer to a `{prompt:Message}` placeholder of a command.
+    Argument {
+        /// The command asking.
+        target: ArgumentTarget,
+        /// The message asked.
+        message: String,
+        /// The answers to the messages asked before.
+        answers: Vec<String>,
+        /// The position of the recalled answer in the message's history.
+        recalled: Option<usize>,
+    },
+}
+
+/// A command whose `{prompt:Message}` placeholders are being answered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgumentTarget {
+    /// The action bound to a key.
+    Action(char),
+    /// The command bar item at an index.
+    CommandBarItem(usize),
+}
+
+/// A single-line input displayed over the bottom of the current view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prompt {
+    /// What the prompt asks for.
+    pub kind: PromptKind,
+    /// The question shown before the input.
+    pub label: String,
+    /// The text typed so far.
+    pub input: String,
+}
+
+/// The jump-to-file popup: the fuzzy query typed and the selected match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileFinder {
+    /// The fuzzy query typed so far.
+    pub query: String,
+    /// The selected row of the ranked files.
+    pub selected: usize,
+}
+
+/// The command palette: the fuzzy query typed and the selected entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandPalette {
+    /// The fuzzy query typed so far.
+    pub query: String,
+    /// The selected row of the ranked entries.
+    pub selected: usize,
+}
 
 /// Represents the current view in the TUI application.
 ///
@@ -13,6 +141,9 @@ use std::path::{Path, PathBuf};
 /// - Workspaces: displays the list of available workspaces
 /// - Projects: displays projects within a selected workspace
 /// - FileBrowser: displays files within a selected project
+///
+/// Conversations is a sibling of FileBrowser that lists the Claude Code
+/// conversations of a project.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum View {
     /// List of workspaces.
@@ -26,8 +157,17 @@ pub enum View {
     FileBrowser {
         /// The identifier of the workspace containing the project.
         workspace_id: String,
-        /// The index of the selected project within the workspace.
-        project_index: usize,
+        /// The id of the selected project, which follows it when projects
+        /// are reordered.
+        project_id: ProjectId,
+    },
+    /// Claude Code conversations for a specific project within a workspace.
+    Conversations {
+        /// The identifier of the workspace containing the project.
+        workspace_id: String,
+        /// The id of the selected project, which follows it when projects
+        /// are reordered.
+        project_id: ProjectId,
     },
 }
 
@@ -40,14 +180,63 @@ pub struct AppState {
     current_view: View,
     /// The index of the currently selected item in the list.
     selected_index: usize,
+    /// The path of the file tree node selected in the file browser, so the
+    /// selection follows the node when the tree above it changes.
+    selected_node: Option<PathBuf>,
+    /// The index of the first item shown in the list.
+    scroll_offset: usize,
     /// Whether the application should quit.
     should_quit: bool,
     /// Set of expanded directory paths in the file browser.
     expanded_dirs: HashSet<PathBuf>,
+    /// Whether hidden and ignored entries are shown in the file browser.
+    show_ignored: bool,
+    /// Whether the file browser shows a preview of the selected file.
+    show_preview: bool,
     /// Whether the command bar is currently visible.
     command_bar_visible: bool,
     /// The index of the currently selected command in the command bar.
     command_bar_selected: usize,
+    /// The toast notification currently displayed, if any.
+    toast: Option<Toast>,
+    /// The message and level of the last toast, kept after it expires.
+    last_notification: Option<(String, ToastLevel)>,
+    /// The popup currently displayed, if any.
+    popup: Option<Popup>,
+    /// The prompt currently reading text input, if any.
+    prompt: Option<Prompt>,
+    /// Whether the configuration file changed and must be reloaded.
+    config_changed: bool,
+    /// The selected row of the workspace switcher, if it is open.
+    workspace_switcher: Option<usize>,
+    /// The selected row of the recent files popup, if it is open.
+    recent_files: Option<usize>,
+    /// The selected row of the MCP servers popup, if it is open.
+    mcp_servers: Option<usize>,
+    /// The selected row of the project menu, if it is open.
+    project_menu: Option<usize>,
+    /// The selected row of the pull requests popup, if it is open.
+    pull_requests: Option<usize>,
+    /// The selected row of the search results popup, if it is open.
+    search_results: Option<usize>,
+    /// The jump-to-file popup, if it is open.
+    file_finder: Option<FileFinder>,
+    /// The command palette, if it is open.
+    command_palette: Option<CommandPalette>,
+    /// The first line shown by the captured output popup, if it is open.
+    captured_output: Option<usize>,
+    /// The first line shown by the blame popup, if it is open.
+    blame: Option<usize>,
+    /// Vim-style count and `g` prefix typed so far.
+    vim_keys: VimKeys,
+    /// The selection and scroll offset of each visited view, by view key.
+    positions: HashMap<String, ViewPosition>,
+    /// Whether actions and commands are previewed instead of run.
+    dry_run: bool,
+    /// Whether the screen must be redrawn.
+    dirty: bool,
+    /// Frame of the spinner shown while work runs in the background.
+    spinner_tick: u64,
 }
 
 impl AppState {
@@ -55,17 +244,46 @@ impl AppState {
     ///
     /// # Returns
     ///
-    /// A new AppState initialized with the Workspaces view, selection at index 0,
-    /// should_quit set to false, an empty set of expanded directories, and
-    /// command bar hidden.
+    /// A new AppState initialized with the Workspaces view, selection and scroll at index 0,
+    /// no selected file tree node, should_quit set to false, an empty set of expanded directories,
+    /// ignored entries and file preview hidden, command bar hidden, no toast, last
+    /// notification, popup, prompt,
+    /// workspace switcher, recent files, project menu, pull requests, search results, file finder or
+    /// captured output popup, no pending
+    /// vim prefix, no remembered positions, dry run off, the spinner at its
+    /// first frame, and marked for an initial draw.
     pub fn new() -> Self {
         Self {
             current_view: View::Workspaces,
             selected_index: 0,
+            selected_node: None,
+            scroll_offset: 0,
             should_quit: false,
             expanded_dirs: HashSet::new(),
+            show_ignored: false,
+            show_preview: false,
             command_bar_visible: false,
             command_bar_selected: 0,
+            toast: None,
+            last_notification: None,
+            popup: None,
+            prompt: None,
+            config_changed: false,
+            workspace_switcher: None,
+            recent_files: None,
+            mcp_servers: None,
+            project_menu: None,
+            pull_requests: None,
+            search_results: None,
+            file_finder: None,
+            command_palette: None,
+            captured_output: None,
+            blame: None,
+            vim_keys: VimKeys::new(),
+            positions: HashMap::new(),
+            dry_run: false,
+            dirty: true,
+            spinner_tick: 0,
         }
     }
 
@@ -98,6 +316,20 @@ impl AppState {
         self.selected_index = index;
     }
 
+    /// Returns the path of the file tree node selected in the file browser.
+    pub fn selected_node(&self) -> Option<&Path> {
+        self.selected_node.as_deref()
+    }
+
+    /// Records the path of the file tree node selected in the file browser.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The selected node, or None if the selection isn't a tree node
+    pub fn set_selected_node(&mut self, path: Option<PathBuf>) {
+        self.selected_node = path;
+    }
+
     /// Toggles the expanded state of a directory.
     ///
     /// If the directory is currently expanded, it will be collapsed.
@@ -114,6 +346,22 @@ impl AppState {
         }
     }
 
+    /// Expands a directory and its ancestors up to a root, so that it's
+    /// visible in the file tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The root of the file tree, which is always expanded
+    /// * `path` - The directory to expand
+    pub fn expand_dir_path(&mut self, root: &Path, path: &Path) {
+        for dir in path.ancestors().take_while(|dir| *dir != root) {
+            if !dir.starts_with(root) {
+                break;
+            }
+            self.expanded_dirs.insert(dir.to_path_buf());
+        }
+    }
+
     /// Checks if a directory is currently expanded.
     ///
     /// # Arguments
@@ -136,6 +384,36 @@ impl AppState {
         &self.expanded_dirs
     }
 
+    /// Toggles whether hidden and ignored entries are shown in the file browser.
+    pub fn toggle_show_ignored(&mut self) {
+        self.show_ignored = !self.show_ignored;
+    }
+
+    /// Returns whether hidden and ignored entries are shown in the file browser.
+    pub fn show_ignored(&self) -> bool {
+        self.show_ignored
+    }
+
+    /// Toggles the file preview in the file browser.
+    pub fn toggle_preview(&mut self) {
+        self.show_preview = !self.show_preview;
+    }
+
+    /// Returns whether the file browser shows a preview of the selected file.
+    pub fn show_preview(&self) -> bool {
+        self.show_preview
+    }
+
+    /// Returns whether actions and commands are previewed instead of run.
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Turns dry run mode on or off.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
     /// Toggles the visibility of the command bar.
     ///
     /// When showing the command bar, resets the selection to 0.
@@ -192,57 +470,577 @@ impl AppState {
         }
     }
 
+    /// Shows a toast notification, replacing any toast currently displayed.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to display
+    /// * `level` - The severity of the message
+    pub fn show_toast(&mut self, message: impl Into<String>, level: ToastLevel) {
+        let message = message.into();
+        self.last_notification = Some((message.clone(), level));
+        self.toast = Some(Toast {
+            message,
+            level,
+            expires_at: Instant::now() + TOAST_DURATION,
+        });
+        self.dirty = true;
+    }
+
+    /// Returns the message and level of the last toast, even once it has
+    /// expired.
+    pub fn last_notification(&self) -> Option<(&str, ToastLevel)> {
+        self.last_notification
+            .as_ref()
+            .map(|(message, level)| (message.as_str(), *level))
+    }
+
+    /// Returns the toast to display, if any and not yet expired.
+    pub fn toast(&self) -> Option<&Toast> {
+        self.toast
+            .as_ref()
+            .filter(|toast| Instant::now() < toast.expires_at)
+    }
+
+    /// Drops the current toast once it has expired.
+    pub fn clear_expired_toast(&mut self) {
+        if self.toast.is_some() && self.toast().is_none() {
+            self.toast = None;
+            self.dirty = true;
+        }
+    }
+
+    /// Returns the frame of the spinner shown while work runs in the background.
+    pub fn spinner_tick(&self) -> u64 {
+        self.spinner_tick
+    }
+
+    /// Moves the spinner to its next frame and marks the screen for redraw.
+    pub fn advance_spinner(&mut self) {
+        self.spinner_tick = self.spinner_tick.wrapping_add(1);
+        self.dirty = true;
+    }
+
+    /// Marks the screen as needing a redraw.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Returns whether a redraw is needed and resets the flag.
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// Opens a popup over the current view.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - Title shown in the popup border
+    /// * `body` - The text content of the popup
+    pub fn show_popup(&mut self, title: impl Into<String>, body: impl Into<String>) {
+        self.popup = Some(Popup {
+            title: title.into(),
+            body: body.into(),
+        });
+    }
+
+    /// Returns the popup currently displayed, if any.
+    pub fn popup(&self) -> Option<&Popup> {
+        self.popup.as_ref()
+    }
+
+    /// Closes the popup, if any.
+    pub fn close_popup(&mut self) {
+        self.popup = None;
+    }
+
+    /// Opens a prompt, replacing any prompt currently displayed.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - What the prompt asks for
+    /// * `label` - The question shown before the input
+    /// * `input` - The initial text of the input
+    pub fn open_prompt(
+        &mut self,
+        kind: PromptKind,
+        label: impl Into<String>,
+        input: impl Into<String>,
+    ) {
+        self.prompt = Some(Prompt {
+            kind,
+            label: label.into(),
+            input: input.into(),
+        });
+    }
+
+    /// Returns the prompt currently displayed, if any.
+    pub fn prompt(&self) -> Option<&Prompt> {
+        self.prompt.as_ref()
+    }
+
+    /// Returns the prompt currently displayed for editing its input, if any.
+    pub fn prompt_mut(&mut self) -> Option<&mut Prompt> {
+        self.prompt.as_mut()
+    }
+
+    /// Closes the prompt, if any.
+    pub fn close_prompt(&mut self) {
+        self.prompt = None;
+    }
+
+    /// Records that the configuration file changed on disk.
+    pub fn mark_config_changed(&mut self) {
+        self.config_changed = true;
+    }
+
+    /// Returns whether the configuration must be reloaded and resets the flag.
+    pub fn take_config_changed(&mut self) -> bool {
+        std::mem::take(&mut self.config_changed)
+    }
+
+    /// Opens the workspace switcher over the current view.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - The initially selected row
+    pub fn open_workspace_switcher(&mut self, selected: usize) {
+        self.workspace_switcher = Some(selected);
+    }
+
+    /// Returns the selected row of the workspace switcher, if it is open.
+    pub fn workspace_switcher(&self) -> Option<usize> {
+        self.workspace_switcher
+    }
+
+    /// Selects a row of the workspace switcher, if it is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - The row to select
+    pub fn set_workspace_switcher_selected(&mut self, selected: usize) {
+        if let Some(current) = self.workspace_switcher.as_mut() {
+            *current = selected;
+        }
+    }
+
+    /// Closes the workspace switcher, if open.
+    pub fn close_workspace_switcher(&mut self) {
+        self.workspace_switcher = None;
+    }
+
+    /// Opens the recent files popup over the current view.
+    pub fn open_recent_files(&mut self) {
+        self.recent_files = Some(0);
+    }
+
+    /// Returns the selected row of the recent files popup, if it is open.
+    pub fn recent_files(&self) -> Option<usize> {
+        self.recent_files
+    }
+
+    /// Selects a row of the recent files popup, if it is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - The row to select
+    pub fn set_recent_files_selected(&mut self, selected: usize) {
+        if let Some(current) = self.recent_files.as_mut() {
+            *current = selected;
+        }
+    }
+
+    /// Closes the recent files popup, if open.
+    pub fn close_recent_files(&mut self) {
+        self.recent_files = None;
+    }
+
+    /// Opens the MCP servers popup over the current view.
+    pub fn open_mcp_servers(&mut self) {
+        self.mcp_servers = Some(0);
+    }
+
+    /// Returns the selected row of the MCP servers popup, if it is open.
+    pub fn mcp_servers(&self) -> Option<usize> {
+        self.mcp_servers
+    }
+
+    /// Selects a row of the MCP servers popup, if it is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - The row to select
+    pub fn set_mcp_servers_selected(&mut self, selected: usize) {
+        if let Some(current) = self.mcp_servers.as_mut() {
+            *current = selected;
+        }
+    }
+
+    /// Closes the MCP servers popup, if open.
+    pub fn close_mcp_servers(&mut self) {
+        self.mcp_servers = None;
+    }
+
+    /// Opens the menu of the selected project over the current view.
+    pub fn open_project_menu(&mut self) {
+        self.project_menu = Some(0);
+    }
+
+    /// Returns the selected row of the project menu, if it is open.
+    pub fn project_menu(&self) -> Option<usize> {
+        self.project_menu
+    }
+
+    /// Selects a row of the project menu, if it is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - The row to select
+    pub fn set_project_menu_selected(&mut self, selected: usize) {
+        if let Some(current) = self.project_menu.as_mut() {
+            *current = selected;
+        }
+    }
+
+    /// Closes the project menu, if open.
+    pub fn close_project_menu(&mut self) {
+        self.project_menu = None;
+    }
+
+    /// Opens the pull requests popup over the current view.
+    pub fn open_pull_requests(&mut self) {
+        self.pull_requests = Some(0);
+    }
+
+    /// Returns the selected row of the pull requests popup, if it is open.
+    pub fn pull_requests(&self) -> Option<usize> {
+        self.pull_requests
+    }
+
+    /// Selects a row of the pull requests popup, if it is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - The row to select
+    pub fn set_pull_requests_selected(&mut self, selected: usize) {
+        if let Some(current) = self.pull_requests.as_mut() {
+            *current = selected;
+        }
+    }
+
+    /// Closes the pull requests popup, if open.
+    pub fn close_pull_requests(&mut self) {
+        self.pull_requests = None;
+    }
+
+    /// Opens the search results popup over the current view.
+    pub fn open_search_results(&mut self) {
+        self.search_results = Some(0);
+    }
+
+    /// Returns the selected row of the search results popup, if it is open.
+    pub fn search_results(&self) -> Option<usize> {
+        self.search_results
+    }
+
+    /// Selects a row of the search results popup, if it is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - The row to select
+    pub fn set_search_results_selected(&mut self, selected: usize) {
+        if let Some(current) = self.search_results.as_mut() {
+            *current = selected;
+        }
+    }
+
+    /// Closes the search results popup, if open.
+    pub fn close_search_results(&mut self) {
+        self.search_results = None;
+    }
+
+    /// Opens the jump-to-file popup over the current view, with an empty query.
+    pub fn open_file_finder(&mut self) {
+        self.file_finder = Some(FileFinder::default());
+    }
+
+    /// Returns the jump-to-file popup, if it is open.
+    pub fn file_finder(&self) -> Option<&FileFinder> {
+        self.file_finder.as_ref()
+    }
+
+    /// Returns the jump-to-file popup for editing its query, if it is open.
+    pub fn file_finder_mut(&mut self) -> Option<&mut FileFinder> {
+        self.file_finder.as_mut()
+    }
+
+    /// Closes the jump-to-file popup, if open.
+    pub fn close_file_finder(&mut self) {
+        self.file_finder = None;
+    }
+
+    /// Opens the command palette over the current view, with an empty query.
+    pub fn open_command_palette(&mut self) {
+        self.command_palette = Some(CommandPalette::default());
+    }
+
+    /// Returns the command palette, if it is open.
+    pub fn command_palette(&self) -> Option<&CommandPalette> {
+        self.command_palette.as_ref()
+    }
+
+    /// Returns the command palette for editing its query, if it is open.
+    pub fn command_palette_mut(&mut self) -> Option<&mut CommandPalette> {
+        self.command_palette.as_mut()
+    }
+
+    /// Closes the command palette, if open.
+    pub fn close_command_palette(&mut self) {
+        self.command_palette = None;
+    }
+
+    /// Returns the vim-style prefix typed so far, for mutation.
+    pub fn vim_keys_mut(&mut self) -> &mut VimKeys {
+        &mut self.vim_keys
+    }
+
+    /// Returns the vim-style prefix typed so far (e.g. "12g"), empty if none.
+    pub fn pending_keys(&self) -> String {
+        self.vim_keys.pending()
+    }
+
+    /// Opens the captured output popup over the current view.
+    pub fn open_captured_output(&mut self) {
+        self.captured_output = Some(0);
+    }
+
+    /// Returns the first line shown by the captured output popup, if it is open.
+    pub fn captured_output(&self) -> Option<usize> {
+        self.captured_output
+    }
+
+    /// Scrolls the captured output popup, if it is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `scroll` - The first line to show
+    pub fn set_captured_output_scroll(&mut self, scroll: usize) {
+        if let Some(current) = self.captured_output.as_mut() {
+            *current = scroll;
+        }
+    }
+
+    /// Closes the captured output popup, if open.
+    pub fn close_captured_output(&mut self) {
+        self.captured_output = None;
+    }
+
+    /// Opens the blame popup over the current view.
+    pub fn open_blame(&mut self) {
+        self.blame = Some(0);
+    }
+
+    /// Returns the first line shown by the blame popup, if it is open.
+    pub fn blame(&self) -> Option<usize> {
+        self.blame
+    }
+
+    /// Scrolls the blame popup, if it is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `scroll` - The first line to show
+    pub fn set_blame_scroll(&mut self, scroll: usize) {
+        if let Some(current) = self.blame.as_mut() {
+            *current = scroll;
+        }
+    }
+
+    /// Closes the blame popup, if open.
+    pub fn close_blame(&mut self) {
+        self.blame = None;
+    }
+
+    /// Returns the index of the first item shown in the list.
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset
+    }
+
+    /// Sets the index of the first item shown in the list.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The index of the first item shown
+    pub fn set_scroll_offset(&mut self, offset: usize) {
+        self.scroll_offset = offset;
+    }
+
+    /// Returns the key under which the position of a view is remembered.
+    ///
+    /// # Arguments
+    ///
+    /// * `view` - The view
+    pub fn view_key(view: &View) -> String {
+        match view {
+            View::Workspaces => "workspaces".to_string(),
+            View::Projects { workspace_id } => format!("projects:{}", workspace_id),
+            View::FileBrowser {
+                workspace_id,
+                project_id,
+            } => format!("files:{}:{}", workspace_id, project_id),
+            View::Conversations {
+                workspace_id,
+                project_id,
+            } => format!("conversations:{}:{}", workspace_id, project_id),
+        }
+    }
+
+    /// Records the selection and scroll offset of the current view.
+    pub fn remember_position(&mut self) {
+        self.positions.insert(
+            Self::view_key(&self.current_view),
+            ViewPosition {
+                selected: self.selected_index,
+                scroll: self.scroll_offset,
+            },
+        );
+    }
+
+    /// Shows a view at the position it was left at, or at the top on the
+    /// first visit.
+    fn enter_view(&mut self, view: View) {
+        let position = self
+            .positions
+            .get(&Self::view_key(&view))
+            .copied()
+            .unwrap_or_default();
+        self.selected_index = position.selected;
+        self.selected_node = None;
+        self.scroll_offset = position.scroll;
+        self.current_view = view;
+    }
+
+    /// Returns what the panel remembers, to be persisted.
+    ///
+    /// Only positions recorded when leaving views are included; call
+    /// `remember_position` first to include the current view.
+    pub fn panel_state(&self) -> PanelState {
+        let mut expanded_dirs: Vec<PathBuf> = self.expanded_dirs.iter().cloned().collect();
+        expanded_dirs.sort();
+        PanelState {
+            positions: self.positions.clone(),
+            expanded_dirs,
+        }
+    }
+
+    /// Restores what the panel remembered in a previous run, including the
+    /// position of the current view.
+    ///
+    /// # Arguments
+    ///
+    /// * `panel` - The persisted panel state
+    pub fn restore_panel_state(&mut self, panel: PanelState) {
+        self.positions = panel.positions;
+        self.expanded_dirs = panel.expanded_dirs.into_iter().collect();
+        self.enter_view(self.current_view.clone());
+    }
+
     /// Navigates to the Projects view for the specified workspace.
     ///
-    /// Resets the selected index to 0.
+    /// Restores the position the workspace's projects were left at, or 0 on
+    /// the first visit.
     ///
     /// # Arguments
     ///
     /// * `workspace_id` - The identifier of the workspace to navigate to
     pub fn navigate_to_workspace(&mut self, workspace_id: String) {
-        self.current_view = View::Projects { workspace_id };
-        self.selected_index = 0;
+        self.remember_position();
+        self.enter_view(View::Projects { workspace_id });
+    }
+
+    /// Jumps to the Projects view of another workspace from any view.
+    ///
+    /// Remembers the position in the current view, so switching back
+    /// restores the selected project.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The identifier of the workspace to switch to
+    pub fn switch_workspace(&mut self, workspace_id: String) {
+        self.workspace_switcher = None;
+        self.navigate_to_workspace(workspace_id);
     }
 
     /// Navigates to the FileBrowser view for the specified project.
     ///
-    /// Requires being in the Projects view. Resets the selected index to 0.
+    /// Requires being in the Projects view. Restores the position the
+    /// project's files were left at, or 0 on the first visit.
     ///
     /// # Arguments
     ///
-    /// * `project_index` - The index of the project to navigate to
+    /// * `project_id` - The id of the project to navigate to
     ///
     /// # Panics
     ///
     /// Panics if called when not in the Projects view.
-    pub fn navigate_to_project(&mut self, project_index: usize) {
+    pub fn navigate_to_project(&mut self, project_id: ProjectId) {
         let workspace_id = match &self.current_view {
             View::Projects { workspace_id } => workspace_id.clone(),
             _ => panic!("Cannot navigate to project from non-Projects view"),
         };
-        self.current_view = View::FileBrowser {
+        self.remember_position();
+        self.enter_view(View::FileBrowser {
             workspace_id,
-            project_index,
+            project_id,
+        });
+    }
+
+    /// Navigates to the Conversations view for the specified project.
+    ///
+    /// Requires being in the Projects view. Restores the position the
+    /// project's conversations were left at, or 0 on the first visit.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - The id of the project whose conversations to show
+    ///
+    /// # Panics
+    ///
+    /// Panics if called when not in the Projects view.
+    pub fn navigate_to_conversations(&mut self, project_id: ProjectId) {
+        let workspace_id = match &self.current_view {
+            View::Projects { workspace_id } => workspace_id.clone(),
+            _ => panic!("Cannot navigate to conversations from non-Projects view"),
         };
-        self.selected_index = 0;
+        self.remember_position();
+        self.enter_view(View::Conversations {
+            workspace_id,
+            project_id,
+        });
     }
 
     /// Navigates back one level in the view hierarchy.
     ///
     /// - FileBrowser -> Projects (same workspace)
+    /// - Conversations -> Projects (same workspace)
     /// - Projects -> Workspaces
     /// - Workspaces -> no change
     ///
-    /// Resets the selected index to 0 on navigation.
+    /// The position of the view left is remembered, and the parent view is
+    /// shown at the position it was left at. Back from a project, the
+    /// caller selects the project, whose position may have changed since.
     pub fn navigate_back(&mut self) {
-        self.current_view = match &self.current_view {
+        self.remember_position();
+        let parent = match &self.current_view {
             View::Workspaces => View::Workspaces,
             View::Projects { .. } => View::Workspaces,
-            View::FileBrowser { workspace_id, .. } => View::Projects {
-                workspace_id: workspace_id.clone(),
-            },
+            View::FileBrowser { workspace_id, .. } | View::Conversations { workspace_id, .. } => {
+                View::Projects {
+                    workspace_id: workspace_id.clone(),
+                }
+            }
         };
-        self.selected_index = 0;
+        self.enter_view(parent);
     }
 }
 
@@ -256,6 +1054,10 @@ impl Default for AppState {
 mod tests {
     use super::*;
 
+    fn project_id(index: usize) -> ProjectId {
+        ProjectId::new("my-workspace", &format!("project-{}", index))
+    }
+
     #[test]
     fn when_creating_app_state_should_start_at_workspaces_view() {
         let app_state = AppState::new();
@@ -293,19 +1095,57 @@ mod tests {
         assert_eq!(app_state.selected_index(), 0);
     }
 
+    #[test]
+    fn when_returning_to_workspace_should_restore_its_selection() {
+        let mut app_state = AppState::new();
+        app_state.navigate_to_workspace("my-workspace".to_string());
+        app_state.set_selected_index(3);
+        app_state.navigate_back();
+
+        app_state.navigate_to_workspace("my-workspace".to_string());
+
+        assert_eq!(app_state.selected_index(), 3);
+    }
+
+    #[test]
+    fn when_switching_workspace_from_file_browser_should_remember_project() {
+        let mut app_state = AppState::new();
+        app_state.navigate_to_workspace("first".to_string());
+        app_state.set_selected_index(2);
+        app_state.navigate_to_project(project_id(2));
+        app_state.open_workspace_switcher(0);
+
+        app_state.switch_workspace("second".to_string());
+        assert_eq!(
+            *app_state.current_view(),
+            View::Projects {
+                workspace_id: "second".to_string()
+            }
+        );
+        assert_eq!(app_state.selected_index(), 0);
+        assert_eq!(app_state.workspace_switcher(), None);
+
+        app_state.set_selected_index(1);
+        app_state.switch_workspace("first".to_string());
+        assert_eq!(app_state.selected_index(), 2);
+
+        app_state.switch_workspace("second".to_string());
+        assert_eq!(app_state.selected_index(), 1);
+    }
+
     #[test]
     fn when_navigating_to_project_should_change_view_to_file_browser() {
         let mut app_state = AppState::new();
         app_state.navigate_to_workspace("my-workspace".to_string());
         app_state.set_selected_index(1);
 
-        app_state.navigate_to_project(2);
+        app_state.navigate_to_project(project_id(2));
 
         assert_eq!(
             *app_state.current_view(),
             View::FileBrowser {
                 workspace_id: "my-workspace".to_string(),
-                project_index: 2
+                project_id: project_id(2)
             }
         );
         assert_eq!(app_state.selected_index(), 0);
@@ -315,7 +1155,8 @@ mod tests {
     fn when_navigating_back_from_file_browser_should_return_to_projects() {
         let mut app_state = AppState::new();
         app_state.navigate_to_workspace("my-workspace".to_string());
-        app_state.navigate_to_project(1);
+        app_state.set_selected_index(1);
+        app_state.navigate_to_project(project_id(1));
         app_state.set_selected_index(5);
 
         app_state.navigate_back();
@@ -326,7 +1167,117 @@ mod tests {
                 workspace_id: "my-workspace".to_string()
             }
         );
+        assert_eq!(app_state.selected_index(), 1);
+    }
+
+    #[test]
+    fn when_returning_to_file_browser_should_restore_its_position() {
+        let mut app_state = AppState::new();
+        app_state.navigate_to_workspace("my-workspace".to_string());
+        app_state.navigate_to_project(project_id(1));
+        app_state.set_selected_index(7);
+        app_state.set_scroll_offset(4);
+        app_state.navigate_back();
+
+        app_state.navigate_to_project(project_id(2));
         assert_eq!(app_state.selected_index(), 0);
+        assert_eq!(app_state.scroll_offset(), 0);
+        app_state.navigate_back();
+        app_state.navigate_to_project(project_id(1));
+
+        assert_eq!(app_state.selected_index(), 7);
+        assert_eq!(app_state.scroll_offset(), 4);
+    }
+
+    #[test]
+    fn when_restoring_panel_state_should_apply_positions_and_expanded_dirs() {
+        let mut first = AppState::new();
+        first.set_selected_index(2);
+        first.navigate_to_workspace("my-workspace".to_string());
+        first.set_selected_index(3);
+        first.toggle_dir_expanded(PathBuf::from("/work/api/src"));
+        first.remember_position();
+        let panel = first.panel_state();
+
+        let mut second = AppState::new();
+        second.restore_panel_state(panel);
+
+        assert_eq!(second.selected_index(), 2);
+        assert!(second.is_dir_expanded(Path::new("/work/api/src")));
+        second.navigate_to_workspace("my-workspace".to_string());
+        assert_eq!(second.selected_index(), 3);
+    }
+
+    #[test]
+    fn when_navigating_back_from_conversations_should_return_to_projects() {
+        let mut app_state = AppState::new();
+        app_state.navigate_to_workspace("my-workspace".to_string());
+        app_state.navigate_to_conversations(project_id(1));
+
+        assert_eq!(
+            *app_state.current_view(),
+            View::Conversations {
+                workspace_id: "my-workspace".to_string(),
+                project_id: project_id(1)
+            }
+        );
+
+        app_state.navigate_back();
+
+        assert_eq!(
+            *app_state.current_view(),
+            View::Projects {
+                workspace_id: "my-workspace".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn when_taking_dirty_flag_should_reset_it() {
+        let mut state = AppState::new();
+
+        assert!(state.take_dirty());
+        assert!(!state.take_dirty());
+
+        state.mark_dirty();
+        assert!(state.take_dirty());
+    }
+
+    #[test]
+    fn when_toast_expires_should_mark_dirty() {
+        let mut state = AppState::new();
+        state.show_toast("Copied", ToastLevel::Info);
+        state.take_dirty();
+        state.toast.as_mut().unwrap().expires_at = Instant::now() - Duration::from_millis(1);
+
+        state.clear_expired_toast();
+
+        assert!(state.take_dirty());
+        state.clear_expired_toast();
+        assert!(!state.take_dirty());
+    }
+
+    #[test]
+    fn when_closing_popup_should_remove_it() {
+        let mut state = AppState::new();
+        state.show_popup("Health check", "error: boom");
+
+        assert_eq!(state.popup().unwrap().body, "error: boom");
+
+        state.close_popup();
+        assert!(state.popup().is_none());
+    }
+
+    #[test]
+    fn when_toggling_show_ignored_should_flip_state() {
+        let mut state = AppState::new();
+        assert!(!state.show_ignored());
+
+        state.toggle_show_ignored();
+        assert!(state.show_ignored());
+
+        state.toggle_show_ignored();
+        assert!(!state.show_ignored());
     }
 
     #[test]
@@ -385,4 +1336,27 @@ mod tests {
         assert!(!app_state.is_command_bar_visible());
         assert_eq!(app_state.command_bar_selected(), 0);
     }
+
+    #[test]
+    fn when_showing_toast_should_be_visible() {
+        let mut app_state = AppState::new();
+
+        app_state.show_toast("Hook failed", ToastLevel::Error);
+
+        let toast = app_state.toast().unwrap();
+        assert_eq!(toast.message, "Hook failed");
+        assert_eq!(toast.level, ToastLevel::Error);
+    }
+
+    #[test]
+    fn when_toast_expired_should_be_cleared() {
+        let mut app_state = AppState::new();
+        app_state.show_toast("Done", ToastLevel::Info);
+        app_state.toast.as_mut().unwrap().expires_at = Instant::now();
+
+        app_state.clear_expired_toast();
+
+        assert!(app_state.toast().is_none());
+        assert!(app_state.toast.is_none());
+    }
 }
@@ -0,0 +1,475 @@
+//! Captured output of command bar items.
+//!
+//! Command bar items with `capture` set run headlessly in the project
+//! directory instead of in a floating pane. `CommandCapture` runs the command
+//! on a background thread and keeps the tail of its stdout and stderr so the
+//! panel can show them in a scrollable popup. The command is killed when the
+//! capture is stopped or dropped, or when it runs past its timeout.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::VecDeque;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::util::shell;
+
+/// Maximum number of output lines kept; earlier lines are dropped.
+const MAX_LINES: usize = 10_000;
+
+/// Maximum number of bytes kept of a single output line; the rest is dropped.
+const MAX_LINE_BYTES: usize = 4_096;
+
+/// How often the running command is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How long output is still read after the command was killed.
+const DRAIN_GRACE: Duration = Duration::from_millis(500);
+
+/// The outcome of a captured command.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaptureResult {
+    /// Whether the command exited successfully.
+    pub success: bool,
+    /// Exit status shown in the popup (e.g. "exit 0", "killed by signal").
+    pub status: String,
+    /// The lines of stdout followed by the lines of stderr.
+    pub lines: Vec<String>,
+}
+
+/// A command bar item running, or run, with its output captured.
+///
+/// Dropping a capture whose command is still running kills the command.
+pub struct CommandCapture {
+    title: String,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<CaptureResult>>,
+    result: Option<CaptureResult>,
+}
+
+impl CommandCapture {
+    /// Starts running a command with its output captured.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - Title shown in the popup
+    /// * `cwd` - The working directory of the command
+    /// * `command` - The shell command to execute
+    /// * `timeout` - How long the command may run before it is killed, or
+    ///   None to let it run until it exits or the capture is stopped
+    pub fn spawn(
+        title: impl Into<String>,
+        cwd: PathBuf,
+        command: String,
+        timeout: Option<Duration>,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let handle = thread::spawn(move || run_captured(&cwd, &command, timeout, &stopped));
+        Self {
+            title: title.into(),
+            stop,
+            handle: Some(handle),
+            result: None,
+        }
+    }
+
+    /// Returns the title shown in the popup.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns whether the command is still running.
+    pub fn is_running(&self) -> bool {
+        self.result.is_none()
+    }
+
+    /// Returns the outcome of the command once it finished.
+    pub fn result(&self) -> Option<&CaptureResult> {
+        self.result.as_ref()
+    }
+
+    /// Kills the command if it is still running; `poll` then reports it as
+    /// stopped.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Collects the outcome of the command if it just finished.
+    ///
+    /// # Returns
+    ///
+    /// True the first time it's called after the command finished.
+    pub fn poll(&mut self) -> bool {
+        let finished = self.handle.as_ref().is_some_and(|h| h.is_finished());
+        if !finished {
+            return false;
+        }
+        let result = self
+            .handle
+            .take()
+            .and_then(|h| h.join().ok())
+            .unwrap_or_else(|| CaptureResult {
+                success: false,
+                status: "crashed".to_string(),
+                lines: Vec::new(),
+            });
+        self.result = Some(result);
+        true
+    }
+}
+
+impl Drop for CommandCapture {
+    fn drop(&mut self) {
+        if self.handle.is_some() {
+            self.stop();
+        }
+    }
+}
+
+/// The tail of one output stream of a command, cleaned line by line.
+#[derive(Default)]
+struct OutputTail {
+    lines: VecDeque<String>,
+    partial: Vec<u8>,
+}
+
+impl OutputTail {
+    /// Appends a chunk of raw output, completing lines at each newline.
+    fn push(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            if byte == b'\n' {
+                self.finish_line();
+            } else if self.partial.len() < MAX_LINE_BYTES {
+                self.partial.push(byte);
+            }
+        }
+    }
+
+    /// Completes the line being read, if any.
+    fn finish_line(&mut self) {
+        let line = clean_line(&String::from_utf8_lossy(&self.partial));
+        self.partial.clear();
+        if self.lines.len() == MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Returns the lines read, the last one included even without a newline.
+    fn into_lines(mut self) -> VecDeque<String> {
+        if !self.partial.is_empty() {
+            self.finish_line();
+        }
+        self.lines
+    }
+}
+
+/// Makes a line of output safe to draw: drops escape sequences and control
+/// characters, keeps what follows the last carriage return (the way a
+/// progress bar ends up on a terminal) and expands tabs.
+///
+/// # Arguments
+///
+/// * `line` - A line of output, without its newline
+fn clean_line(line: &str) -> String {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let line = line.rsplit('\r').next().unwrap_or(line);
+    let mut cleaned = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                // CSI: parameters up to a final byte in '@'..='~'
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC and other strings: up to BEL or ESC '\'
+                Some(']' | 'P' | 'X' | '^' | '_') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' {
+                            break;
+                        }
+                        if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\t' => cleaned.push_str("    "),
+            c if c.is_control() => {}
+            c => cleaned.push(c),
+        }
+    }
+    cleaned
+}
+
+/// Reads a stream of a command into a shared tail until it closes.
+fn read_into(
+    mut stream: impl Read + Send + 'static,
+    tail: Arc<Mutex<OutputTail>>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut chunk = [0u8; 8192];
+        loop {
+            match stream.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => {
+                    if let Ok(mut tail) = tail.lock() {
+                        tail.push(&chunk[..read]);
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Runs a shell command in a directory and captures its output.
+///
+/// # Arguments
+///
+/// * `cwd` - The working directory of the command
+/// * `command` - The shell command to execute
+/// * `timeout` - How long the command may run, or None for no limit
+/// * `stop` - Set to kill the command
+///
+/// # Returns
+///
+/// The exit status and output lines; a command that cannot be started is
+/// reported as a failed result with the error as its only line.
+fn run_captured(
+    cwd: &std::path::Path,
+    command: &str,
+    timeout: Option<Duration>,
+    stop: &AtomicBool,
+) -> CaptureResult {
+    let mut cmd = shell::command(command);
+    cmd.current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return CaptureResult {
+                success: false,
+                status: "failed to start".to_string(),
+                lines: vec![e.to_string()],
+            }
+        }
+    };
+
+    let stdout = Arc::new(Mutex::new(OutputTail::default()));
+    let stderr = Arc::new(Mutex::new(OutputTail::default()));
+    let mut readers = Vec::new();
+    if let Some(stream) = child.stdout.take() {
+        readers.push(read_into(stream, stdout.clone()));
+    }
+    if let Some(stream) = child.stderr.take() {
+        readers.push(read_into(stream, stderr.clone()));
+    }
+
+    // Wait for the command to exit and its output to be read, unless it's
+    // stopped or runs out of time first
+    let started = Instant::now();
+    let mut exit = None;
+    let killed = loop {
+        if exit.is_none() {
+            exit = child.try_wait().ok().flatten();
+        }
+        if exit.is_some() && readers.iter().all(|r| r.is_finished()) {
+            break None;
+        }
+        if stop.load(Ordering::Relaxed) {
+            break Some("stopped".to_string());
+        }
+        if let Some(timeout) = timeout.filter(|t| started.elapsed() >= *t) {
+            break Some(format!("timed out after {}s", timeout.as_secs()));
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+    if killed.is_some() {
+        shell::kill(&mut child);
+        let deadline = Instant::now() + DRAIN_GRACE;
+        while Instant::now() < deadline && !readers.iter().all(|r| r.is_finished()) {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    let take = |tail: &Arc<Mutex<OutputTail>>| {
+        tail.lock()
+            .map(|mut tail| std::mem::take(&mut *tail).into_lines())
+            .unwrap_or_default()
+    };
+    let mut lines: Vec<String> = take(&stdout).into_iter().chain(take(&stderr)).collect();
+    if lines.len() > MAX_LINES {
+        lines.drain(..lines.len() - MAX_LINES);
+    }
+
+    let (success, status) = match (killed, exit) {
+        (Some(reason), _) => (false, reason),
+        (None, Some(exit)) => (
+            exit.success(),
+            match exit.code() {
+                Some(code) => format!("exit {}", code),
+                None => "killed by signal".to_string(),
+            },
+        ),
+        (None, None) => (false, "killed by signal".to_string()),
+    };
+
+    CaptureResult {
+        success,
+        status,
+        lines,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn wait_for_result(capture: &mut CommandCapture) -> CaptureResult {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !capture.poll() {
+            assert!(Instant::now() < deadline, "captured command did not finish");
+            thread::sleep(Duration::from_millis(10));
+        }
+        capture.result().cloned().unwrap()
+    }
+
+    fn spawn(title: &str, dir: &TempDir, command: &str) -> CommandCapture {
+        CommandCapture::spawn(title, dir.path().to_path_buf(), command.to_string(), None)
+    }
+
+    #[test]
+    fn when_command_succeeds_should_capture_stdout_then_stderr() {
+        let dir = TempDir::new().unwrap();
+        let mut capture = spawn("Pods", &dir, "echo out; echo err >&2");
+
+        let result = wait_for_result(&mut capture);
+
+        assert!(!capture.is_running());
+        assert!(result.success);
+        assert_eq!(result.status, "exit 0");
+        assert_eq!(result.lines, vec!["out", "err"]);
+        assert!(!capture.poll());
+    }
+
+    #[test]
+    fn when_command_fails_should_report_exit_code() {
+        let dir = TempDir::new().unwrap();
+        let mut capture = spawn("Fail", &dir, "exit 3");
+
+        let result = wait_for_result(&mut capture);
+
+        assert!(!result.success);
+        assert_eq!(result.status, "exit 3");
+        assert!(result.lines.is_empty());
+    }
+
+    #[test]
+    fn when_running_command_should_use_project_directory() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("marker.txt"), "").unwrap();
+        let mut capture = spawn("Ls", &dir, "ls");
+
+        let result = wait_for_result(&mut capture);
+
+        assert_eq!(result.lines, vec!["marker.txt"]);
+    }
+
+    #[test]
+    fn when_directory_is_missing_should_fail_to_start() {
+        let mut capture = CommandCapture::spawn(
+            "Gone",
+            PathBuf::from("/nonexistent/gz-claude/project"),
+            "true".to_string(),
+            None,
+        );
+
+        let result = wait_for_result(&mut capture);
+
+        assert!(!result.success);
+        assert_eq!(result.status, "failed to start");
+        assert_eq!(result.lines.len(), 1);
+    }
+
+    #[test]
+    fn when_command_runs_past_timeout_should_kill_it() {
+        let dir = TempDir::new().unwrap();
+        let mut capture = CommandCapture::spawn(
+            "Hang",
+            dir.path().to_path_buf(),
+            "echo started; sleep 30".to_string(),
+            Some(Duration::from_millis(200)),
+        );
+
+        let result = wait_for_result(&mut capture);
+
+        assert!(!result.success);
+        assert_eq!(result.status, "timed out after 0s");
+        assert_eq!(result.lines, vec!["started"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn when_capture_is_stopped_should_kill_the_command_and_its_children() {
+        let dir = TempDir::new().unwrap();
+        let mut capture = spawn("Hang", &dir, "sleep 30 & echo $! > child.pid; wait");
+        let pid_file = dir.path().join("child.pid");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while std::fs::read_to_string(&pid_file).map_or(true, |pid| !pid.ends_with('\n')) {
+            assert!(Instant::now() < deadline, "captured command did not start");
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        capture.stop();
+        let result = wait_for_result(&mut capture);
+
+        assert!(!result.success);
+        assert_eq!(result.status, "stopped");
+        // Killed children may linger as zombies until they are reaped
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim()));
+        assert!(stat.map_or(true, |stat| stat.contains(") Z ")));
+    }
+
+    #[test]
+    fn when_output_has_control_sequences_should_strip_them() {
+        let dir = TempDir::new().unwrap();
+        let mut capture = spawn(
+            "Colors",
+            &dir,
+            r"printf '\033[1;31mred\033[0m\tok\a\n\033]0;title\007plain\r\n10%%\r100%%\n'",
+        );
+
+        let result = wait_for_result(&mut capture);
+
+        assert_eq!(result.lines, vec!["red    ok", "plain", "100%"]);
+    }
+
+    #[test]
+    fn when_output_is_long_should_keep_only_the_tail() {
+        let dir = TempDir::new().unwrap();
+        let mut capture = spawn("Seq", &dir, "seq 1 20000");
+
+        let result = wait_for_result(&mut capture);
+
+        assert_eq!(result.lines.len(), MAX_LINES);
+        assert_eq!(result.lines.first().map(String::as_str), Some("10001"));
+        assert_eq!(result.lines.last().map(String::as_str), Some("20000"));
+    }
+}
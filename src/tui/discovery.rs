@@ -0,0 +1,160 @@
+//! Discovery of new projects in a workspace's `discover` directory.
+//!
+//! Watches the directory each workspace discovers projects in, and reports
+//! the subdirectories created while the panel runs, for example repositories
+//! cloned by Claude, so they can be offered as projects.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// A directory watched for new projects.
+struct DiscoveryRoot {
+    /// The workspace the new projects are offered to.
+    workspace_id: String,
+    /// The directory, as configured with `~` expanded.
+    path: PathBuf,
+    /// Its subdirectories already seen.
+    known: HashSet<PathBuf>,
+}
+
+/// Watches the `discover` directories of workspaces for new subdirectories.
+pub struct DiscoveryWatcher {
+    watcher: Option<RecommendedWatcher>,
+    events: Receiver<notify::Result<Event>>,
+    /// The watched directories, by canonical path.
+    roots: HashMap<PathBuf, DiscoveryRoot>,
+}
+
+impl DiscoveryWatcher {
+    /// Creates a watcher with no watched directories.
+    ///
+    /// If the platform watcher cannot be created, nothing is ever reported.
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            watcher: notify::recommended_watcher(tx).ok(),
+            events: rx,
+            roots: HashMap::new(),
+        }
+    }
+
+    /// Starts watching the directory a workspace discovers projects in.
+    ///
+    /// Its current subdirectories count as seen. Does nothing if it is
+    /// already watched or doesn't exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The workspace the new projects are offered to
+    /// * `root` - The directory to watch
+    pub fn watch(&mut self, workspace_id: &str, root: &Path) {
+        if self.roots.values().any(|r| r.path == root) {
+            return;
+        }
+        // Watchers report canonical paths, so register the canonical root
+        let Ok(canonical) = root.canonicalize() else {
+            return;
+        };
+        let Some(watcher) = self.watcher.as_mut() else {
+            return;
+        };
+        if watcher
+            .watch(&canonical, RecursiveMode::NonRecursive)
+            .is_err()
+        {
+            return;
+        }
+        self.roots.insert(
+            canonical,
+            DiscoveryRoot {
+                workspace_id: workspace_id.to_string(),
+                path: root.to_path_buf(),
+                known: subdirectories(root),
+            },
+        );
+    }
+
+    /// Drains pending filesystem events without blocking.
+    ///
+    /// # Returns
+    ///
+    /// The subdirectories created since the last call, with the workspace
+    /// they are offered to, sorted by path.
+    pub fn drain_new(&mut self) -> Vec<(String, PathBuf)> {
+        let changed: HashSet<PathBuf> = self
+            .events
+            .try_iter()
+            .filter_map(|event| event.ok())
+            .flat_map(|event| event.paths)
+            .filter_map(|path| path.parent().map(Path::to_path_buf))
+            .collect();
+
+        let mut found = Vec::new();
+        for canonical in changed {
+            let Some(root) = self.roots.get_mut(&canonical) else {
+                continue;
+            };
+            for dir in new_directories(&root.path, &mut root.known) {
+                found.push((root.workspace_id.clone(), dir));
+            }
+        }
+        found.sort_by(|a, b| a.1.cmp(&b.1));
+        found
+    }
+}
+
+/// Returns the subdirectories of a directory not seen yet, and marks them
+/// as seen.
+///
+/// # Arguments
+///
+/// * `root` - The directory to list
+/// * `known` - The subdirectories already seen
+pub fn new_directories(root: &Path, known: &mut HashSet<PathBuf>) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = subdirectories(root)
+        .into_iter()
+        .filter(|dir| known.insert(dir.clone()))
+        .collect();
+    found.sort();
+    found
+}
+
+/// Lists the subdirectories of a directory, leaving out hidden ones.
+fn subdirectories(root: &Path) -> HashSet<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return HashSet::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| root.join(entry.file_name()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn when_directory_is_created_should_report_it_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("api")).unwrap();
+        let mut known = subdirectories(root);
+
+        fs::create_dir(root.join("web")).unwrap();
+        fs::create_dir(root.join(".cache")).unwrap();
+        fs::write(root.join("notes.md"), "").unwrap();
+
+        assert_eq!(new_directories(root, &mut known), vec![root.join("web")]);
+        assert!(new_directories(root, &mut known).is_empty());
+    }
+}
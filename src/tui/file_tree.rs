@@ -11,6 +11,81 @@ use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Decides which directory entries are hidden from the file tree.
+///
+/// Hidden entries (starting with '.') and entries whose name matches one of
+/// the ignore patterns are filtered out unless `show_ignored` is set.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreFilter {
+    /// Name patterns to hide, supporting `*` and `?` wildcards.
+    pub patterns: Vec<String>,
+    /// Whether hidden and ignored entries are shown anyway.
+    pub show_ignored: bool,
+}
+
+impl IgnoreFilter {
+    /// Creates a filter from ignore patterns.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Name patterns to hide (e.g. "target", "*.lock")
+    /// * `show_ignored` - Whether hidden and ignored entries are shown anyway
+    pub fn new(patterns: Vec<String>, show_ignored: bool) -> Self {
+        Self {
+            patterns,
+            show_ignored,
+        }
+    }
+
+    /// Checks whether an entry with the given name should be hidden.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The file or directory name (not the full path)
+    pub fn is_ignored(&self, name: &str) -> bool {
+        if self.show_ignored {
+            return false;
+        }
+        name.starts_with('.')
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| glob_matches(pattern, name))
+    }
+}
+
+/// Matches a name against a pattern with `*` (any run) and `?` (one char) wildcards.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 /// Represents a node in the file tree.
 ///
 /// Each node corresponds to either a file or a directory in the file system.
@@ -78,6 +153,26 @@ impl FileNode {
     ///
     /// * `expanded_dirs` - Set of directory paths that should be expanded
     pub fn load_children_with_expanded(&mut self, expanded_dirs: &HashSet<PathBuf>) {
+        self.load_children_filtered(expanded_dirs, &IgnoreFilter::default());
+    }
+
+    /// Loads children for this directory node, hiding entries rejected by a filter.
+    ///
+    /// Sorts children with directories first, then alphabetically (case-insensitive).
+    /// Directories in the `expanded_dirs` set will be expanded and have their children
+    /// loaded with the same filter.
+    ///
+    /// Does nothing if this node is not a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `expanded_dirs` - Set of directory paths that should be expanded
+    /// * `filter` - Decides which entries are hidden
+    pub fn load_children_filtered(
+        &mut self,
+        expanded_dirs: &HashSet<PathBuf>,
+        filter: &IgnoreFilter,
+    ) {
         if !self.is_dir {
             return;
         }
@@ -93,7 +188,7 @@ impl FileNode {
                 entry
                     .file_name()
                     .to_str()
-                    .map(|name| !name.starts_with('.'))
+                    .map(|name| !filter.is_ignored(name))
                     .unwrap_or(false)
             })
             .filter_map(|entry| FileNode::new(&entry.path(), self.depth + 1))
@@ -109,7 +204,7 @@ impl FileNode {
         for child in &mut children {
             if child.is_dir && expanded_dirs.contains(&child.path) {
                 child.expanded = true;
-                child.load_children_with_expanded(expanded_dirs);
+                child.load_children_filtered(expanded_dirs, filter);
             }
         }
 
@@ -143,6 +238,8 @@ pub struct FileTree {
     pub root: FileNode,
     /// Flattened indices for visible nodes (used for navigation).
     pub flat_list: Vec<FlatNodeRef>,
+    /// Whether the root only holds the project's roots, and isn't listed.
+    multi_root: bool,
 }
 
 /// Reference to a node in the flattened view.
@@ -184,6 +281,25 @@ impl FileTree {
     ///
     /// Some(FileTree) if the root path exists and is a directory, None otherwise.
     pub fn with_expanded(root_path: &Path, expanded_dirs: &HashSet<PathBuf>) -> Option<Self> {
+        Self::with_filter(root_path, expanded_dirs, &IgnoreFilter::default())
+    }
+
+    /// Creates a new FileTree with pre-expanded directories and an ignore filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_path` - The path to use as the root of the tree
+    /// * `expanded_dirs` - Set of directory paths that should be expanded
+    /// * `filter` - Decides which entries are hidden
+    ///
+    /// # Returns
+    ///
+    /// Some(FileTree) if the root path exists and is a directory, None otherwise.
+    pub fn with_filter(
+        root_path: &Path,
+        expanded_dirs: &HashSet<PathBuf>,
+        filter: &IgnoreFilter,
+    ) -> Option<Self> {
         let mut root = FileNode::new(root_path, 0)?;
 
         if !root.is_dir {
@@ -191,11 +307,69 @@ impl FileTree {
         }
 
         root.expanded = true;
-        root.load_children_with_expanded(expanded_dirs);
+        root.load_children_filtered(expanded_dirs, filter);
 
         let mut tree = Self {
             root,
             flat_list: Vec::new(),
+            multi_root: false,
+        };
+
+        tree.rebuild_flat_list();
+
+        Some(tree)
+    }
+
+    /// Creates a FileTree listing several directories as its top-level
+    /// entries, instead of the project directory itself.
+    ///
+    /// Each root is named by its path relative to the project and is
+    /// expanded only if it is in `expanded_dirs`. Roots that don't exist or
+    /// aren't directories are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_path` - The project directory, holding the roots
+    /// * `roots` - The directories listed, in order
+    /// * `expanded_dirs` - Set of directory paths that should be expanded
+    /// * `filter` - Decides which entries are hidden
+    ///
+    /// # Returns
+    ///
+    /// Some(FileTree) if the project directory exists and is a directory,
+    /// None otherwise.
+    pub fn with_roots(
+        root_path: &Path,
+        roots: &[PathBuf],
+        expanded_dirs: &HashSet<PathBuf>,
+        filter: &IgnoreFilter,
+    ) -> Option<Self> {
+        let mut root = FileNode::new(root_path, 0)?;
+
+        if !root.is_dir {
+            return None;
+        }
+
+        root.expanded = true;
+        root.children = roots
+            .iter()
+            .filter_map(|path| {
+                let mut node = FileNode::new(path, 0).filter(|node| node.is_dir)?;
+                if let Ok(relative) = path.strip_prefix(root_path) {
+                    node.name = relative.display().to_string();
+                }
+                if expanded_dirs.contains(path) {
+                    node.expanded = true;
+                    node.load_children_filtered(expanded_dirs, filter);
+                }
+                Some(node)
+            })
+            .collect();
+
+        let mut tree = Self {
+            root,
+            flat_list: Vec::new(),
+            multi_root: true,
         };
 
         tree.rebuild_flat_list();
@@ -209,8 +383,10 @@ impl FileTree {
     fn rebuild_flat_list(&mut self) {
         let mut flat_list = Vec::new();
 
-        // Add root
-        flat_list.push(FlatNodeRef { path: vec![] });
+        // Add root, unless it only holds the project's roots
+        if !self.multi_root {
+            flat_list.push(FlatNodeRef { path: vec![] });
+        }
 
         // Recursively add visible children
         if self.root.expanded {
@@ -259,6 +435,22 @@ impl FileTree {
         self.get_node_by_path(&node_ref.path)
     }
 
+    /// Returns the index of the visible node with a file system path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file or directory
+    ///
+    /// # Returns
+    ///
+    /// The index in the flattened visible list, or None if no visible node has the path.
+    pub fn index_of(&self, path: &Path) -> Option<usize> {
+        (0..self.visible_count()).find(|&index| {
+            self.get_visible_node(index)
+                .is_some_and(|node| node.path == path)
+        })
+    }
+
     /// Gets a node by its path from the root.
     fn get_node_by_path(&self, path: &[usize]) -> Option<&FileNode> {
         if path.is_empty() {
@@ -382,6 +574,66 @@ mod tests {
         assert!(tree.get_visible_node(3).is_none());
     }
 
+    #[test]
+    fn when_toggling_directory_should_find_nodes_by_path_at_their_new_index() {
+        let temp_dir = setup_test_dir();
+        let readme = temp_dir.path().join("README.md");
+        let main = temp_dir.path().join("src/main.rs");
+
+        let mut tree = FileTree::new(temp_dir.path()).unwrap();
+        assert_eq!(tree.index_of(&readme), Some(2));
+        assert_eq!(tree.index_of(&main), None);
+
+        tree.toggle_at(1);
+
+        assert_eq!(tree.index_of(&main), Some(2));
+        assert_eq!(tree.index_of(&readme), Some(3));
+    }
+
+    #[test]
+    fn when_creating_tree_with_roots_should_list_them_instead_of_the_root() {
+        let temp_dir = setup_test_dir();
+        let root_path = temp_dir.path();
+        fs::create_dir_all(root_path.join("apps/web")).unwrap();
+        fs::write(root_path.join("apps/web/index.ts"), "").unwrap();
+        let roots = vec![
+            root_path.join("apps/web"),
+            root_path.join("src"),
+            root_path.join("missing"),
+        ];
+        let expanded: HashSet<PathBuf> = [root_path.join("apps/web")].into_iter().collect();
+
+        let tree =
+            FileTree::with_roots(root_path, &roots, &expanded, &IgnoreFilter::default()).unwrap();
+
+        let names: Vec<&str> = (0..tree.visible_count())
+            .filter_map(|i| tree.get_visible_node(i))
+            .map(|node| node.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["apps/web", "index.ts", "src"]);
+        assert_eq!(tree.get_visible_node(0).unwrap().depth, 0);
+        assert_eq!(tree.get_visible_node(1).unwrap().depth, 1);
+    }
+
+    #[test]
+    fn when_toggling_a_root_should_expand_it_independently() {
+        let temp_dir = setup_test_dir();
+        let root_path = temp_dir.path();
+        let roots = vec![root_path.join("src"), root_path.join("tests")];
+        fs::create_dir_all(root_path.join("tests")).unwrap();
+        fs::write(root_path.join("tests/it.rs"), "").unwrap();
+        let mut tree =
+            FileTree::with_roots(root_path, &roots, &HashSet::new(), &IgnoreFilter::default())
+                .unwrap();
+        assert_eq!(tree.visible_count(), 2);
+
+        tree.toggle_at(1);
+
+        assert_eq!(tree.visible_count(), 3);
+        assert_eq!(tree.get_visible_node(2).unwrap().name, "it.rs");
+        assert!(!tree.get_visible_node(0).unwrap().expanded);
+    }
+
     #[test]
     fn when_sorting_children_should_put_directories_first() {
         let temp_dir = TempDir::new().unwrap();
@@ -415,4 +667,42 @@ mod tests {
         assert_eq!(children[3].name, "banana.txt");
         assert!(!children[3].is_dir);
     }
+
+    #[test]
+    fn when_matching_glob_patterns_should_support_wildcards() {
+        assert!(glob_matches("target", "target"));
+        assert!(glob_matches("*.lock", "Cargo.lock"));
+        assert!(glob_matches("file?.txt", "file1.txt"));
+        assert!(glob_matches("*test*", "my_test_file"));
+        assert!(!glob_matches("*.lock", "Cargo.toml"));
+        assert!(!glob_matches("target", "targets"));
+    }
+
+    #[test]
+    fn when_loading_with_ignore_patterns_should_hide_matching_entries() {
+        let temp_dir = setup_test_dir();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("target")).unwrap();
+        fs::File::create(root.join("Cargo.lock")).unwrap();
+        fs::File::create(root.join(".env")).unwrap();
+        let filter = IgnoreFilter::new(vec!["target".to_string(), "*.lock".to_string()], false);
+
+        let tree = FileTree::with_filter(root, &HashSet::new(), &filter).unwrap();
+
+        let names: Vec<&str> = tree.root.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["src", "README.md"]);
+    }
+
+    #[test]
+    fn when_showing_ignored_should_include_hidden_and_ignored_entries() {
+        let temp_dir = setup_test_dir();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("target")).unwrap();
+        fs::File::create(root.join(".env")).unwrap();
+        let filter = IgnoreFilter::new(vec!["target".to_string()], true);
+
+        let tree = FileTree::with_filter(root, &HashSet::new(), &filter).unwrap();
+
+        assert_eq!(tree.root.children.len(), 4);
+    }
 }
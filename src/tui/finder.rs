@@ -0,0 +1,307 @@
+//! Fuzzy jump-to-file for a project.
+//!
+//! `FileIndex` lists every file of a project on a background thread,
+//! skipping the entries the file browser hides. `rank` orders the indexed
+//! paths by how well they match a fuzzy query, so a deeply nested file can
+//! be opened by typing a few characters of its path.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+
+use crate::tui::file_tree::IgnoreFilter;
+
+/// Maximum number of files indexed in a project.
+pub const MAX_FILES: usize = 50_000;
+
+/// Maximum number of ranked files shown.
+pub const MAX_RESULTS: usize = 200;
+
+/// Score of each matched character.
+const MATCH_SCORE: i64 = 16;
+
+/// Bonus of a character matched right after the previous one.
+const CONSECUTIVE_BONUS: i64 = 16;
+
+/// Bonus of a character matched at the start of a word (`src/ma` in `src/main.rs`).
+const WORD_START_BONUS: i64 = 24;
+
+/// Bonus of a query matched entirely within the file name.
+const FILE_NAME_BONUS: i64 = 48;
+
+/// The files of a project, indexed in the background.
+pub struct FileIndex {
+    root: PathBuf,
+    filter: IgnoreFilter,
+    files: Vec<String>,
+    pending: Option<JoinHandle<Vec<String>>>,
+}
+
+impl FileIndex {
+    /// Starts indexing the files of a project.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The project directory
+    /// * `filter` - Decides which entries are skipped, like in the file browser
+    pub fn spawn(root: &Path, filter: IgnoreFilter) -> Self {
+        let mut index = Self {
+            root: root.to_path_buf(),
+            filter,
+            files: Vec::new(),
+            pending: None,
+        };
+        index.refresh();
+        index
+    }
+
+    /// Returns whether the index covers a project with a filter.
+    pub fn covers(&self, root: &Path, filter: &IgnoreFilter) -> bool {
+        self.root == root
+            && self.filter.show_ignored == filter.show_ignored
+            && self.filter.patterns == filter.patterns
+    }
+
+    /// Indexes the project again in the background, keeping the current
+    /// files until the new list is ready.
+    pub fn refresh(&mut self) {
+        if self.pending.is_some() {
+            return;
+        }
+        let root = self.root.clone();
+        let filter = self.filter.clone();
+        self.pending = Some(thread::spawn(move || list_files(&root, &filter)));
+    }
+
+    /// Returns the project directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the indexed files, relative to the project, sorted.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// Returns whether the project is being indexed.
+    pub fn is_indexing(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Collects the files once indexing finished.
+    ///
+    /// # Returns
+    ///
+    /// True the first time it's called after indexing finished.
+    pub fn poll(&mut self) -> bool {
+        if !self.pending.as_ref().is_some_and(|h| h.is_finished()) {
+            return false;
+        }
+        if let Some(files) = self.pending.take().and_then(|h| h.join().ok()) {
+            self.files = files;
+        }
+        true
+    }
+}
+
+/// Lists the files under a directory, relative to it and sorted.
+///
+/// Entries hidden by the filter are skipped, and so are symbolic links to
+/// directories, which could loop. At most `MAX_FILES` files are listed.
+///
+/// # Arguments
+///
+/// * `root` - The directory to list
+/// * `filter` - Decides which entries are skipped
+pub fn list_files(root: &Path, filter: &IgnoreFilter) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if filter.is_ignored(&name) {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                dirs.push(path);
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                let relative = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push(relative);
+                if files.len() >= MAX_FILES {
+                    files.sort();
+                    return files;
+                }
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Matches a fuzzy query against a path.
+///
+/// Every character of the query must appear in the path in order, ignoring
+/// case. Matches at the start of words, consecutive matches and queries
+/// matching within the file name score higher; longer paths score lower.
+///
+/// # Arguments
+///
+/// * `query` - The characters typed, spaces ignored
+/// * `path` - The path to match, with `/` separators
+///
+/// # Returns
+///
+/// The score and the char positions of the matched characters, or None if
+/// the path doesn't match.
+pub fn fuzzy_match(query: &str, path: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let chars: Vec<char> = path.chars().collect();
+    let name_start = chars.iter().rposition(|&c| c == '/').map_or(0, |i| i + 1);
+
+    // Prefer matching within the file name, then anywhere in the path
+    let (positions, in_name) = match match_from(&query, &chars, name_start) {
+        Some(positions) => (positions, true),
+        None => (match_from(&query, &chars, 0)?, false),
+    };
+
+    let mut score = -(chars.len() as i64) / 4;
+    if in_name && !query.is_empty() {
+        score += FILE_NAME_BONUS;
+    }
+    for (i, &position) in positions.iter().enumerate() {
+        score += MATCH_SCORE;
+        let word_start = position == 0 || matches!(chars[position - 1], '/' | '_' | '-' | '.');
+        if word_start {
+            score += WORD_START_BONUS;
+        }
+        if i > 0 && positions[i - 1] + 1 == position {
+            score += CONSECUTIVE_BONUS;
+        } else if i > 0 {
+            score -= (position - positions[i - 1] - 1) as i64;
+        }
+    }
+    Some((score, positions))
+}
+
+/// Finds the characters of a lowercase query in order, from a position.
+fn match_from(query: &[char], chars: &[char], start: usize) -> Option<Vec<usize>> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut next = start;
+    for &wanted in query {
+        let found = (next..chars.len()).find(|&i| chars[i].to_lowercase().eq([wanted]))?;
+        positions.push(found);
+        next = found + 1;
+    }
+    Some(positions)
+}
+
+/// A file matching a fuzzy query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedFile<'a> {
+    /// The path, relative to the project.
+    pub path: &'a str,
+    /// The char positions of the matched characters.
+    pub positions: Vec<usize>,
+}
+
+/// Orders files by how well they match a fuzzy query, best first.
+///
+/// An empty query keeps the files in order. At most `MAX_RESULTS` files
+/// are returned.
+///
+/// # Arguments
+///
+/// * `query` - The characters typed
+/// * `files` - The files to rank
+pub fn rank<'a>(query: &str, files: &'a [String]) -> Vec<RankedFile<'a>> {
+    let mut ranked: Vec<(i64, RankedFile<'a>)> = files
+        .iter()
+        .filter_map(|path| {
+            let (score, positions) = fuzzy_match(query, path)?;
+            Some((score, RankedFile { path, positions }))
+        })
+        .collect();
+    ranked.sort_by(|(a, file_a), (b, file_b)| b.cmp(a).then_with(|| file_a.path.cmp(file_b.path)));
+    ranked.truncate(MAX_RESULTS);
+    ranked.into_iter().map(|(_, file)| file).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn paths<'a>(ranked: &[RankedFile<'a>]) -> Vec<&'a str> {
+        ranked.iter().map(|f| f.path).collect()
+    }
+
+    #[test]
+    fn when_query_chars_appear_in_order_should_match() {
+        let (_, positions) = fuzzy_match("smr", "src/main.rs").unwrap();
+
+        assert_eq!(positions, vec![0, 4, 9]);
+        assert_eq!(fuzzy_match("rsm", "src/main.rs"), None);
+    }
+
+    #[test]
+    fn when_ranking_should_prefer_file_names_and_word_starts() {
+        let files = vec![
+            "docs/main_notes.md".to_string(),
+            "src/tui/views/file_browser.rs".to_string(),
+            "src/tui/file_tree.rs".to_string(),
+            "Cargo.toml".to_string(),
+        ];
+
+        assert_eq!(
+            paths(&rank("filetree", &files)),
+            vec!["src/tui/file_tree.rs"]
+        );
+        assert_eq!(
+            paths(&rank("fb", &files))[0],
+            "src/tui/views/file_browser.rs"
+        );
+        assert_eq!(paths(&rank("", &files)).len(), 4);
+    }
+
+    #[test]
+    fn when_indexing_should_list_nested_files_skipping_ignored() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("src/deep/er")).unwrap();
+        fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        fs::write(dir.path().join("src/deep/er/mod.rs"), "").unwrap();
+        fs::write(dir.path().join("target/debug/app"), "").unwrap();
+        fs::write(dir.path().join(".env"), "").unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        let filter = IgnoreFilter::new(vec!["target".to_string()], false);
+
+        let mut index = FileIndex::spawn(dir.path(), filter.clone());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !index.poll() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert!(!index.is_indexing());
+        assert!(index.covers(dir.path(), &filter));
+        assert_eq!(index.files(), ["Cargo.toml", "src/deep/er/mod.rs"]);
+    }
+}
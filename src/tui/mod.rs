@@ -6,13 +6,23 @@
 #![allow(unused_imports)]
 
 mod app;
+mod capture;
+mod discovery;
 mod file_tree;
+mod finder;
+mod palette;
+mod preview;
 mod runner;
+mod search;
+mod startup;
 mod terminal;
+mod theme;
 pub mod views;
+mod vim;
 
-pub use app::{AppState, View};
-pub use file_tree::{FileNode, FileTree};
+pub use app::{AppState, Popup, Toast, ToastLevel, View};
+pub use file_tree::{FileNode, FileTree, IgnoreFilter};
+pub use preview::{load_preview, FilePreview};
 pub use runner::run;
-pub use terminal::{init, poll_event, restore, InputEvent, Tui};
-pub use views::WorkspacesView;
+pub use terminal::{init, poll_event, InputEvent, TerminalGuard, Tui};
+pub use views::{ToastView, WorkspacesView};
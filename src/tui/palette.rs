@@ -0,0 +1,321 @@
+//! Command palette.
+//!
+//! Lists everything that can be run from the panel in one place: the
+//! selected project's actions and command bar items, then the panel's
+//! built-in commands. Entries are matched with the fuzzy matching of the
+//! jump-to-file popup, and the ones run recently come first; the history is
+//! kept in `~/.gz-claude/palette.json`, shared by all Zellij sessions.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::tui::finder::fuzzy_match;
+use crate::tui::terminal::InputEvent;
+
+/// File storing the entries run from the palette.
+const HISTORY_FILE: &str = "palette.json";
+
+/// Maximum number of entries remembered.
+pub const MAX_HISTORY: usize = 20;
+
+/// Score of the most recently run entry, lowered by one per older entry, so
+/// recent entries win over similar matches.
+const RECENT_BONUS: i64 = 40;
+
+/// Where a palette entry comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteKind {
+    /// An action of the selected project.
+    Action,
+    /// A command bar item of the selected project.
+    Command,
+    /// A built-in command of the panel.
+    Panel,
+}
+
+impl PaletteKind {
+    /// Returns the label of the kind, shown next to the entry.
+    pub fn label(self) -> &'static str {
+        match self {
+            PaletteKind::Action => "action",
+            PaletteKind::Command => "command",
+            PaletteKind::Panel => "panel",
+        }
+    }
+}
+
+/// What running a palette entry does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteCommand {
+    /// Handles an input event, as if its key was pressed.
+    Key(InputEvent),
+    /// Runs the command bar item at an index.
+    CommandBarItem(usize),
+    /// Opens the configuration file in the editor.
+    OpenConfig,
+    /// Resolves the current project's merge conflicts with `git mergetool`.
+    Mergetool,
+}
+
+/// An entry of the command palette.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteEntry {
+    /// Where the entry comes from.
+    pub kind: PaletteKind,
+    /// The name the entry is matched on.
+    pub label: String,
+    /// The key doing the same, empty if there is none.
+    pub key: String,
+    /// What running the entry does.
+    pub command: PaletteCommand,
+}
+
+impl PaletteEntry {
+    /// Creates an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Where the entry comes from
+    /// * `label` - The name the entry is matched on
+    /// * `key` - The key doing the same, empty if there is none
+    /// * `command` - What running the entry does
+    pub fn new(
+        kind: PaletteKind,
+        label: impl Into<String>,
+        key: impl Into<String>,
+        command: PaletteCommand,
+    ) -> Self {
+        Self {
+            kind,
+            label: label.into(),
+            key: key.into(),
+            command,
+        }
+    }
+
+    /// Returns the entry's id in the history, e.g. "action:Claude".
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.kind.label(), self.label)
+    }
+}
+
+/// An entry matching the query of the palette.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedEntry {
+    /// The index of the entry.
+    pub index: usize,
+    /// The char positions of the matched characters of its label.
+    pub positions: Vec<usize>,
+    /// Whether it was run recently.
+    pub recent: bool,
+}
+
+/// Orders the entries matching a query, best first.
+///
+/// Recently run entries get a bonus, the more recent the larger. With an
+/// empty query, they come first, most recent first, followed by the other
+/// entries in order.
+///
+/// # Arguments
+///
+/// * `query` - The characters typed
+/// * `entries` - The entries of the palette
+/// * `history` - The entries run recently
+pub fn rank(query: &str, entries: &[PaletteEntry], history: &PaletteHistory) -> Vec<RankedEntry> {
+    let mut ranked: Vec<(i64, RankedEntry)> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let (score, positions) = if query.trim().is_empty() {
+                (0, Vec::new())
+            } else {
+                fuzzy_match(query, &entry.label)?
+            };
+            let recency = history.position(&entry.id());
+            let bonus = recency.map_or(0, |position| RECENT_BONUS - position as i64);
+            let ranked = RankedEntry {
+                index,
+                positions,
+                recent: recency.is_some(),
+            };
+            Some((score + bonus, ranked))
+        })
+        .collect();
+    // The sort is stable, so ties keep the order of the entries
+    ranked.sort_by(|(a, _), (b, _)| b.cmp(a));
+    ranked.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Entries run from the palette, most recent first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaletteHistory {
+    #[serde(default)]
+    entries: Vec<String>,
+}
+
+impl PaletteHistory {
+    /// Returns the path to the history store.
+    pub fn default_path() -> PathBuf {
+        Config::default_dir().join(HISTORY_FILE)
+    }
+
+    /// Loads the history from the default store.
+    ///
+    /// # Returns
+    ///
+    /// The history, empty if the store doesn't exist or is invalid.
+    pub fn load() -> Self {
+        Self::load_from(&Self::default_path())
+    }
+
+    /// Loads the history from a file.
+    fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the history to the default store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::default_path())
+    }
+
+    /// Saves the history to a file, replacing it atomically.
+    fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Records an entry as the most recently run one.
+    ///
+    /// The entry moves to the front if it was already listed; the oldest
+    /// entries are dropped beyond `MAX_HISTORY`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the entry, see `PaletteEntry::id`
+    pub fn record(&mut self, id: &str) {
+        self.entries.retain(|entry| entry != id);
+        self.entries.insert(0, id.to_string());
+        self.entries.truncate(MAX_HISTORY);
+    }
+
+    /// Returns how recently an entry was run: 0 for the most recent one,
+    /// None if it isn't in the history.
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| entry == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entries() -> Vec<PaletteEntry> {
+        vec![
+            PaletteEntry::new(
+                PaletteKind::Action,
+                "Claude",
+                "c",
+                PaletteCommand::Key(InputEvent::Action('c')),
+            ),
+            PaletteEntry::new(
+                PaletteKind::Command,
+                "Cargo test",
+                "",
+                PaletteCommand::CommandBarItem(0),
+            ),
+            PaletteEntry::new(
+                PaletteKind::Panel,
+                "Restart pane",
+                "R",
+                PaletteCommand::Key(InputEvent::RestartPane),
+            ),
+            PaletteEntry::new(
+                PaletteKind::Panel,
+                "Open config",
+                "",
+                PaletteCommand::OpenConfig,
+            ),
+        ]
+    }
+
+    fn labels(entries: &[PaletteEntry], ranked: &[RankedEntry]) -> Vec<String> {
+        ranked
+            .iter()
+            .map(|r| entries[r.index].label.clone())
+            .collect()
+    }
+
+    #[test]
+    fn when_query_is_empty_should_list_recent_entries_first() {
+        let entries = entries();
+        let mut history = PaletteHistory::default();
+        history.record("panel:Restart pane");
+        history.record("command:Cargo test");
+
+        let ranked = rank("", &entries, &history);
+
+        assert_eq!(
+            labels(&entries, &ranked),
+            vec!["Cargo test", "Restart pane", "Claude", "Open config"]
+        );
+        assert!(ranked[0].recent && !ranked[2].recent);
+    }
+
+    #[test]
+    fn when_typing_should_match_labels_fuzzily_and_prefer_recent_ones() {
+        let entries = entries();
+        let history = PaletteHistory::default();
+
+        let ranked = rank("rsp", &entries, &history);
+        assert_eq!(labels(&entries, &ranked), vec!["Restart pane"]);
+        assert_eq!(ranked[0].positions, vec![0, 2, 8]);
+
+        let mut history = PaletteHistory::default();
+        assert_eq!(
+            labels(&entries, &rank("c", &entries, &history))[0],
+            "Claude"
+        );
+        history.record("command:Cargo test");
+        assert_eq!(
+            labels(&entries, &rank("c", &entries, &history))[0],
+            "Cargo test"
+        );
+    }
+
+    #[test]
+    fn when_history_is_saved_should_load_it_most_recent_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("palette.json");
+        let mut history = PaletteHistory::default();
+        for i in 0..MAX_HISTORY + 2 {
+            history.record(&format!("panel:{}", i));
+        }
+        history.record("panel:3");
+        history.save_to(&path).unwrap();
+
+        let loaded = PaletteHistory::load_from(&path);
+
+        assert_eq!(loaded.position("panel:3"), Some(0));
+        assert_eq!(loaded.position("panel:21"), Some(1));
+        assert_eq!(loaded.position("panel:1"), None);
+    }
+}
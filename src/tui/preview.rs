@@ -0,0 +1,225 @@
+//! File preview for the file browser.
+//!
+//! Loads the first lines of a file and highlights them with syntect. Binary
+//! files and images are detected and shown as placeholders instead.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+
+/// Maximum number of lines shown in the preview.
+pub const PREVIEW_LINES: usize = 200;
+
+/// Number of leading bytes inspected to detect binary files.
+const BINARY_SNIFF_BYTES: u64 = 8192;
+
+/// Theme used to highlight previews.
+const PREVIEW_THEME: &str = "base16-ocean.dark";
+
+/// Extensions of files shown with an image placeholder.
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "webp", "ico", "tif", "tiff", "avif",
+];
+
+/// Preview of a file selected in the file browser.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilePreview {
+    /// Highlighted text lines.
+    Text {
+        /// The first lines of the file, highlighted.
+        lines: Vec<Line<'static>>,
+        /// Whether the file has more lines than shown.
+        truncated: bool,
+    },
+    /// An image file, with its size in bytes.
+    Image(u64),
+    /// A binary file, with its size in bytes.
+    Binary(u64),
+    /// A directory.
+    Directory,
+    /// The file could not be read.
+    Unreadable(String),
+}
+
+/// Loads the preview of a file.
+///
+/// # Arguments
+///
+/// * `path` - The file or directory to preview
+///
+/// # Returns
+///
+/// The preview; errors are reported as `FilePreview::Unreadable`.
+pub fn load_preview(path: &Path) -> FilePreview {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => return FilePreview::Unreadable(e.to_string()),
+    };
+    if metadata.is_dir() {
+        return FilePreview::Directory;
+    }
+    if is_image(path) {
+        return FilePreview::Image(metadata.len());
+    }
+
+    match read_lines(path) {
+        Ok(None) => FilePreview::Binary(metadata.len()),
+        Ok(Some((lines, truncated))) => FilePreview::Text {
+            lines: highlight(path, &lines),
+            truncated,
+        },
+        Err(e) => FilePreview::Unreadable(e.to_string()),
+    }
+}
+
+/// Returns whether the file extension is a known image format.
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Reads the first lines of a text file, keeping their line endings.
+///
+/// Returns None if the file looks binary (contains a NUL byte near the start),
+/// otherwise the lines and whether more lines follow.
+fn read_lines(path: &Path) -> std::io::Result<Option<(Vec<String>, bool)>> {
+    let mut head = Vec::new();
+    File::open(path)?
+        .take(BINARY_SNIFF_BYTES)
+        .read_to_end(&mut head)?;
+    if head.contains(&0) {
+        return Ok(None);
+    }
+
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut lines = Vec::new();
+    let mut buffer = Vec::new();
+    while lines.len() < PREVIEW_LINES {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            return Ok(Some((lines, false)));
+        }
+        lines.push(String::from_utf8_lossy(&buffer).into_owned());
+    }
+    let truncated = !reader.fill_buf()?.is_empty();
+    Ok(Some((lines, truncated)))
+}
+
+/// Returns the shared syntax definitions.
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// Returns the shared highlighting theme.
+fn theme() -> &'static Theme {
+    static THEME: OnceLock<Theme> = OnceLock::new();
+    THEME.get_or_init(|| {
+        ThemeSet::load_defaults()
+            .themes
+            .remove(PREVIEW_THEME)
+            .unwrap_or_default()
+    })
+}
+
+/// Highlights lines using the syntax matching the file name or first line.
+///
+/// Lines that fail to highlight are shown unstyled.
+fn highlight(path: &Path, lines: &[String]) -> Vec<Line<'static>> {
+    let syntaxes = syntax_set();
+    let syntax = syntaxes
+        .find_syntax_for_file(path)
+        .ok()
+        .flatten()
+        .or_else(|| {
+            lines
+                .first()
+                .and_then(|first| syntaxes.find_syntax_by_first_line(first))
+        })
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, theme());
+
+    lines
+        .iter()
+        .map(|line| match highlighter.highlight_line(line, syntaxes) {
+            Ok(regions) => Line::from(
+                regions
+                    .into_iter()
+                    .map(|(style, text)| {
+                        let fg = style.foreground;
+                        Span::styled(
+                            clean(text),
+                            Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b)),
+                        )
+                    })
+                    .collect::<Vec<Span>>(),
+            ),
+            Err(_) => Line::from(clean(line)),
+        })
+        .collect()
+}
+
+/// Strips line endings and expands tabs for display.
+fn clean(text: &str) -> String {
+    text.trim_end_matches(['\n', '\r']).replace('\t', "    ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn when_previewing_source_file_should_highlight_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("main.rs");
+        fs::write(&path, "fn main() {\n\tprintln!(\"hi\");\n}\n").unwrap();
+
+        let FilePreview::Text { lines, truncated } = load_preview(&path) else {
+            panic!("expected a text preview");
+        };
+
+        assert_eq!(lines.len(), 3);
+        assert!(!truncated);
+        assert_eq!(lines[1].to_string(), "    println!(\"hi\");");
+        assert!(lines[0].spans.len() > 1);
+    }
+
+    #[test]
+    fn when_file_is_longer_than_preview_should_truncate() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("long.txt");
+        fs::write(&path, "line\n".repeat(PREVIEW_LINES + 1)).unwrap();
+
+        let FilePreview::Text { lines, truncated } = load_preview(&path) else {
+            panic!("expected a text preview");
+        };
+
+        assert_eq!(lines.len(), PREVIEW_LINES);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn when_previewing_binary_or_image_should_return_placeholder() {
+        let dir = TempDir::new().unwrap();
+        let binary = dir.path().join("app.bin");
+        let image = dir.path().join("logo.PNG");
+        fs::write(&binary, [0x7f, b'E', b'L', b'F', 0, 1]).unwrap();
+        fs::write(&image, "not really a png").unwrap();
+
+        assert_eq!(load_preview(&binary), FilePreview::Binary(6));
+        assert_eq!(load_preview(&image), FilePreview::Image(16));
+        assert_eq!(load_preview(dir.path()), FilePreview::Directory);
+    }
+}
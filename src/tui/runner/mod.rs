@@ -0,0 +1,5609 @@
+//! Main TUI runner that orchestrates terminal initialization, event loop, and cleanup.
+//!
+//! This module ties together all TUI components: terminal management, application state,
+//! view rendering, and input handling. The pickers (menus, finders and lists
+//! chosen from) live in `pickers`, and the prompts asking for a line of input
+//! or a confirmation in `prompts`.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::arguments::{self, ArgumentHistory};
+use crate::claude::activity::TranscriptMonitor;
+use crate::claude::idle::IdleMonitor;
+use crate::claude::{self, Conversation};
+use crate::config::{
+    Action, Config, GitUiConfig, HookMode, McpServer, NavigationMode, NotificationEvent,
+    PanePlacement, Project, ProjectEnter, ZellijIntegration,
+};
+use crate::error::{ConfigError, Result, ZellijError};
+use crate::git::blame::{self, Blame};
+use crate::git::clone::{CloneJob, CloneStatus};
+use crate::git::fetch::FetchMonitor;
+use crate::git::refresh::{GitRefresh, RefreshTarget};
+use crate::git::{GitCache, GitCompare, GitInfo};
+use crate::health::{HealthMonitor, HealthStatus};
+use crate::hooks;
+use crate::logs;
+use crate::mcp::{self, McpSelection};
+use crate::recent::RecentFiles;
+use crate::remote::{RemoteMonitor, RemoteState};
+use crate::secrets::{self, SecretStore};
+use crate::session::{GroupPane, PaneGroup, PaneInfo, Session, SessionFocus, SessionLoad};
+use crate::tui::app::{AppState, ArgumentTarget, PromptKind, ToastLevel, View};
+use crate::tui::capture::CommandCapture;
+use crate::tui::discovery::DiscoveryWatcher;
+use crate::tui::file_tree::IgnoreFilter;
+use crate::tui::finder::{self, FileIndex};
+use crate::tui::palette::{self, PaletteCommand, PaletteEntry, PaletteHistory, PaletteKind};
+use crate::tui::preview::{load_preview, FilePreview};
+use crate::tui::search::ProjectSearch;
+use crate::tui::startup::StartupChecks;
+use crate::tui::terminal::{init, poll_event, InputEvent, Tui};
+use crate::tui::theme;
+use crate::tui::views::status_bar::{Mode, STATUS_BAR_HEIGHT};
+use crate::tui::views::{
+    BlameView, CapturedOutputView, CommandBar, CommandPaletteView, ConversationsView,
+    FileBrowserView, FileFinderView, Header, McpServersView, PanelStats, PopupView, Progress,
+    ProjectMenuView, ProjectsView, PromptView, PullRequestsView, RecentFilesView,
+    SearchResultsView, StatusBar, ToastView, WorkspaceSwitcherView, WorkspacesView,
+};
+use crate::tui::vim::VimOutcome;
+use crate::util::{self, glyphs};
+use crate::zellij::{
+    with_runner, CallPolicy, DryRunRunner, FocusPoll, FocusTarget, PaneExecutor, PaneJobId,
+    WebFocusManager,
+};
+
+mod pickers;
+mod prompts;
+
+use pickers::{
+    handle_command_palette_input, handle_file_finder_input, handle_mcp_servers_input,
+    handle_project_menu_input, handle_pull_requests_input, handle_recent_files_input,
+    handle_search_results_input, handle_workspace_switcher_input, mcp_servers, open_file_finder,
+    open_search_prompt, palette_entries, poll_file_index, poll_search, project_menu,
+    show_mcp_servers, show_project_menu, show_pull_requests, start_search, PICKERS,
+};
+use prompts::{ask_argument, discovered_project, handle_prompt_input, poll_discovery, recall};
+
+/// Maximum time between two redraws, for time-based UI such as relative ages.
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Time between two frames of the spinner, while work runs in the background.
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often the runtime stats of `ui.stats` are refreshed.
+const STATS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time between two reads of the transcripts of Claude panes.
+const TRANSCRIPT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long a command bar item with `capture` set may run before it is killed.
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+// Thread-local session state for the TUI.
+thread_local! {
+    static SESSION: RefCell<Option<Session>> = const { RefCell::new(None) };
+    static MONITORS: RefCell<Monitors> = RefCell::new(Monitors::new());
+    static WORK: RefCell<BackgroundWork> = RefCell::new(BackgroundWork::default());
+    static MAIN_PANE_USED: RefCell<bool> = const { RefCell::new(false) };
+    static GIT_CACHE: RefCell<Option<GitCache>> = const { RefCell::new(None) };
+    static VIEWS: RefCell<ViewData> = const { RefCell::new(ViewData::new()) };
+    static WEB_FOCUS: RefCell<WebFocusManager> = RefCell::new(WebFocusManager::new());
+    static STARTUP: RefCell<Option<StartupChecks>> = const { RefCell::new(None) };
+    static CLONES: RefCell<HashMap<PathBuf, CloneJob>> = RefCell::new(HashMap::new());
+    static REFRESH: RefCell<Option<GitRefresh>> = const { RefCell::new(None) };
+    static PANE_JOBS: RefCell<PaneJobs> = RefCell::new(PaneJobs::default());
+    static SESSION_FILE: RefCell<SessionFile> = const { RefCell::new(SessionFile::new()) };
+}
+
+/// The cached preview of a file: its path, modification time and preview.
+type PreviewEntry = (PathBuf, Option<SystemTime>, FilePreview);
+
+/// An action running in the background.
+struct BackgroundAction {
+    /// The directory of the project, under which the outcome is kept.
+    project_path: PathBuf,
+    /// The key of the action.
+    key: String,
+    /// The logs directory of the project.
+    logs_dir: PathBuf,
+    /// The running command.
+    capture: CommandCapture,
+}
+
+/// The monitors polled by the main loop, and what they last reported.
+struct Monitors {
+    /// The health checks of the projects, once one is configured.
+    health: Option<HealthMonitor>,
+    /// The CI and pull request state of the projects, once tracked.
+    remote: Option<RemoteMonitor>,
+    /// The periodic and requested fetches.
+    fetches: FetchMonitor,
+    /// The projects already reported as behind their upstream.
+    behind_notified: HashSet<PathBuf>,
+    /// The Claude panes waiting for input.
+    idle: IdleMonitor,
+    /// The transcripts of Claude panes, for their latest line.
+    transcripts: TranscriptMonitor,
+    /// The polling of the pane Zellij focuses.
+    focus_poll: FocusPoll,
+    /// The project whose pane Zellij focused last.
+    zellij_focus: Option<PathBuf>,
+}
+
+impl Monitors {
+    /// Creates the monitors, with nothing tracked yet.
+    fn new() -> Self {
+        Self {
+            health: None,
+            remote: None,
+            fetches: FetchMonitor::new(),
+            behind_notified: HashSet::new(),
+            idle: IdleMonitor::new(),
+            transcripts: TranscriptMonitor::new(),
+            focus_poll: FocusPoll::new(),
+            zellij_focus: None,
+        }
+    }
+}
+
+/// What the detail views show, loaded when first shown and kept between
+/// frames.
+struct ViewData {
+    /// The runtime stats shown with `ui.stats`, with when they were taken.
+    stats: Option<(Instant, PanelStats)>,
+    /// The conversations of the selected project, by its directory.
+    conversations: Option<(PathBuf, Vec<Conversation>)>,
+    /// The preview of the selected file.
+    preview: Option<PreviewEntry>,
+    /// The recently changed files of the selected project.
+    recent: Option<RecentFiles>,
+    /// The blame of the selected file.
+    blame: Option<Blame>,
+}
+
+impl ViewData {
+    /// Creates the view data, with nothing loaded yet.
+    const fn new() -> Self {
+        Self {
+            stats: None,
+            conversations: None,
+            preview: None,
+            recent: None,
+            blame: None,
+        }
+    }
+}
+
+/// The floating panes opened in the background.
+#[derive(Default)]
+struct PaneJobs {
+    /// The executor opening them, once one was needed.
+    executor: Option<PaneExecutor>,
+    /// What runs once each of them opened.
+    callbacks: HashMap<PaneJobId, PaneCallback>,
+}
+
+/// What the panel knows of the file its session state is saved in.
+struct SessionFile {
+    /// When the file was last written, as last seen by the panel.
+    saved: Option<SystemTime>,
+    /// The backup of a damaged state, until restoring it is confirmed or
+    /// declined.
+    backup: Option<Session>,
+}
+
+impl SessionFile {
+    /// Creates the session file state, with nothing known yet.
+    const fn new() -> Self {
+        Self {
+            saved: None,
+            backup: None,
+        }
+    }
+}
+
+/// Commands run by the panel itself rather than in a pane.
+#[derive(Default)]
+struct BackgroundWork {
+    /// The command bar item whose output is shown, with `capture` set.
+    capture: Option<CommandCapture>,
+    /// The actions running with `background` set.
+    background: Vec<BackgroundAction>,
+    /// Whether the last run of each background action passed, by project.
+    outcomes: HashMap<PathBuf, BTreeMap<String, bool>>,
+    /// The hooks running in the background.
+    pending_hooks: Vec<PendingHook>,
+}
+
+/// Runs once a floating pane opened in the background, with its id.
+type PaneCallback = Box<dyn FnOnce(&mut AppState, Option<String>)>;
+
+/// Runs once a hook run in the background succeeded.
+type HookCallback = Box<dyn FnOnce(&mut AppState)>;
+
+/// A hook running in the background, with what waits for it to succeed.
+type PendingHook = (JoinHandle<Result<()>>, Option<HookCallback>);
+
+/// Runs the TUI application with the given configuration.
+///
+/// Initializes the terminal, creates application state, runs the main event loop,
+/// and restores the terminal on exit or error.
+///
+/// # Arguments
+///
+/// * `config` - Reference to the application configuration
+/// * `fast_boot` - Whether project paths were not validated before starting;
+///   they're then checked in the background after the first frame
+/// * `dry_run` - Whether actions and commands start previewed instead of run
+///
+/// # Returns
+///
+/// Ok(()) on successful execution, or an error if terminal operations fail.
+///
+/// # Errors
+///
+/// Returns an error if terminal initialization or event polling fails.
+pub fn run(config: &Config, fast_boot: bool, dry_run: bool) -> Result<()> {
+    // Initialize or load the state of the current Zellij session
+    let zellij_session = Session::current_name();
+    if let Some(mut active) = crate::zellij::list_sessions() {
+        // Never collect our own state, even if Zellij doesn't list it yet
+        active.push(zellij_session.clone());
+        Session::garbage_collect(&active);
+    }
+    let mut damaged = None;
+    let mut unreadable = None;
+    let session = match Session::open(&zellij_session) {
+        SessionLoad::Loaded(session) => session,
+        SessionLoad::Missing => Session::new(zellij_session),
+        SessionLoad::Damaged {
+            error,
+            kept_as,
+            backup,
+        } => {
+            damaged = Some((error, kept_as, backup));
+            Session::new(zellij_session)
+        }
+        SessionLoad::Unreadable { error } => {
+            unreadable = Some(error);
+            Session::new(zellij_session)
+        }
+    };
+
+    SESSION.with(|s| {
+        *s.borrow_mut() = Some(session);
+    });
+    record_session_saved();
+    configure_zellij_integration(config);
+    // Commands for the main pane target it by id, also from `session import`
+    let main_pane = crate::zellij::layout_main_pane();
+    let main_pane_name = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .and_then(|session| session.main_pane_name().map(str::to_string))
+    });
+    crate::zellij::configure_main_pane(main_pane.clone(), main_pane_name);
+    if main_pane.is_some() {
+        update_session(|s| s.main_pane = main_pane);
+    }
+    glyphs::configure(config.ui.ascii_only);
+    // Actions can't be typed into a main pane that doesn't run a shell
+    MAIN_PANE_USED.with(|m| *m.borrow_mut() = !config.layout.main_runs_shell());
+
+    // The terminal is restored when the guard drops, also on errors and panics
+    let (_guard, mut terminal) = init()?;
+    let mut state = AppState::new();
+    state.set_dry_run(dry_run);
+    let panel = SESSION.with(|s| s.borrow().as_ref().map(|session| session.panel.clone()));
+    if let Some(panel) = panel {
+        state.restore_panel_state(panel);
+        clamp_selection(&mut state, config);
+    }
+    if let Some(warnings) = config_warnings(config) {
+        state.show_popup("Configuration warnings", warnings);
+    }
+    if let Some(error) = unreadable {
+        state.show_popup(
+            "Session state unreadable",
+            format!(
+                "The session state could not be read:\n{}\n\n\
+                 The panel starts without it; the file was left in place.",
+                error
+            ),
+        );
+    }
+    if let Some((error, kept_as, backup)) = damaged {
+        report_damaged_session(&mut state, &error, &kept_as, backup);
+    }
+    if let Some(port) = config.state_endpoint.port {
+        if let Err(e) =
+            crate::api::spawn(port, Session::current_name(), config.global.git_info_level)
+        {
+            state.show_toast(
+                format!("State endpoint not started on port {}: {}", port, e),
+                ToastLevel::Error,
+            );
+        }
+    }
+
+    let result = run_loop(&mut terminal, &mut state, config, fast_boot);
+
+    // Save session on exit, with the position of the view left
+    state.remember_position();
+    let panel = state.panel_state();
+    SESSION.with(|s| {
+        if let Some(session) = s.borrow_mut().as_mut() {
+            session.panel = panel;
+            let _ = session.save();
+        }
+    });
+
+    result
+}
+
+/// Returns the runtime stats of the panel when `ui.stats` is set.
+///
+/// They are taken again every `STATS_INTERVAL`, not on every frame, since
+/// reading the memory may start a process.
+fn panel_stats(config: &Config) -> Option<PanelStats> {
+    if !config.ui.stats {
+        return None;
+    }
+    let fresh = VIEWS.with(|v| {
+        v.borrow()
+            .stats
+            .as_ref()
+            .filter(|(taken, _)| taken.elapsed() < STATS_INTERVAL)
+            .map(|(_, stats)| stats.clone())
+    });
+    if fresh.is_some() {
+        return fresh;
+    }
+    let (git_entries, git_oldest) = GIT_CACHE.with(|c| {
+        c.borrow().as_ref().map_or((0, None), |cache| {
+            (cache.entry_count(), cache.oldest_entry_age())
+        })
+    });
+    let stats = PanelStats {
+        panes: SESSION.with(|s| s.borrow().as_ref().map_or(0, |s| s.panes.len())),
+        workspaces: config.workspace.len(),
+        projects: config.projects().len(),
+        git_entries,
+        git_oldest,
+        memory: crate::util::memory::resident_memory(),
+    };
+    VIEWS.with(|v| v.borrow_mut().stats = Some((Instant::now(), stats.clone())));
+    Some(stats)
+}
+
+/// Tells the user the session state could not be read.
+///
+/// With a backup, asks whether to restore it; the choice is handled by
+/// `handle_prompt_input`. Without one, the panel starts fresh.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `error` - Why the state file could not be read
+/// * `kept_as` - Where the damaged file was moved
+/// * `backup` - The last readable state, if any
+fn report_damaged_session(
+    state: &mut AppState,
+    error: &str,
+    kept_as: &Path,
+    backup: Option<Session>,
+) {
+    let Some(backup) = backup else {
+        state.show_popup(
+            "Session state damaged",
+            format!(
+                "The session state could not be read and there is no backup:\n{}\n\n\
+                 The panel starts fresh; the damaged file was kept as\n{}",
+                error,
+                kept_as.display()
+            ),
+        );
+        return;
+    };
+    let label = format!(
+        "Session state damaged ({}). Restore the backup with {} pane(s)?",
+        error,
+        backup.panes.len()
+    );
+    SESSION_FILE.with(|f| f.borrow_mut().backup = Some(backup));
+    state.open_prompt(PromptKind::ConfirmRestoreSession, label, "");
+}
+
+/// Replaces the session state with its backup, keeping the session name.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+fn restore_session_backup(state: &mut AppState, config: &Config) {
+    let Some(mut backup) = SESSION_FILE.with(|f| f.borrow_mut().backup.take()) else {
+        return;
+    };
+    let Some(name) = SESSION.with(|s| s.borrow().as_ref().map(|s| s.zellij_session.clone())) else {
+        return;
+    };
+    backup.zellij_session = name;
+    let saved = backup.save();
+
+    let main_used =
+        !config.layout.main_runs_shell() || backup.panes.values().any(|pane| !pane.floating);
+    MAIN_PANE_USED.with(|m| *m.borrow_mut() = main_used);
+    state.restore_panel_state(backup.panel.clone());
+    clamp_selection(state, config);
+    let message = format!("Restored {} pane(s) from the backup", backup.panes.len());
+    SESSION.with(|s| *s.borrow_mut() = Some(backup));
+    record_session_saved();
+    match saved {
+        Ok(()) => state.show_toast(message, ToastLevel::Info),
+        Err(e) => state.show_toast(format!("Failed to save session: {}", e), ToastLevel::Error),
+    }
+}
+
+/// Lists the lint warnings of the configuration for the startup screen.
+///
+/// # Returns
+///
+/// One warning per line followed by a hint, or None if there are none.
+fn config_warnings(config: &Config) -> Option<String> {
+    let warnings = config.lint();
+    if warnings.is_empty() {
+        return None;
+    }
+    let mut lines: Vec<String> = warnings
+        .iter()
+        .map(|w| format!("{} {}", glyphs::glyph("•", "-"), w))
+        .collect();
+    lines.push(String::new());
+    lines.push("Run 'gz-claude config lint' to list them again.".to_string());
+    Some(lines.join("\n"))
+}
+
+/// Main event loop that handles rendering and input.
+///
+/// Runs until `state.should_quit` is true. Each iteration:
+/// 1. Collects finished background hooks, captured commands, clones and
+///    fetches, expires old toasts, invalidates git cache entries whose
+///    repositories changed on disk, schedules project health checks and
+///    periodic fetches, watches Claude panes for idleness, reads their
+///    latest transcript lines and offers new directories of `discover`
+///    directories as projects
+/// 2. Draws the current view, only if something changed or the tick elapsed;
+///    with fast boot, startup checks are started after the first frame
+/// 3. Polls for input events (100ms timeout)
+/// 4. Handles any received input and marks the screen for redraw
+/// 5. Reloads the configuration if the input edited it
+///
+/// # Arguments
+///
+/// * `terminal` - Mutable reference to the terminal
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `fast_boot` - Whether to run the deferred startup checks
+///
+/// # Returns
+///
+/// Ok(()) when the user quits, or an error if rendering or event polling fails.
+fn run_loop(
+    terminal: &mut Tui,
+    state: &mut AppState,
+    config: &Config,
+    fast_boot: bool,
+) -> Result<()> {
+    let mut last_draw = Instant::now();
+    let mut reloaded: Option<Config> = None;
+    let mut startup_pending = fast_boot;
+
+    while !state.should_quit() {
+        let config = reloaded.as_ref().unwrap_or(config);
+        let hooks_changed = collect_finished_hooks(state);
+        state.clear_expired_toast();
+        let git_changed = GIT_CACHE.with(|c| {
+            c.borrow_mut()
+                .as_mut()
+                .is_some_and(|cache| cache.process_watch_events())
+        });
+        let health_changed = poll_health_checks(config);
+        let remote_changed = poll_remote_status(config);
+        let startup_changed = poll_startup_checks(state, config);
+        let capture_changed = poll_captured_output(config);
+        let background_changed = poll_background_actions(state, config);
+        let panes_changed = poll_pane_operations(state);
+        let search_changed = poll_search();
+        let index_changed = poll_file_index();
+        let clones_changed = poll_clones(state);
+        let fetches_changed = poll_fetches(state, config);
+        let idle_changed = poll_idle_panes(state, config);
+        let transcripts_changed = poll_transcripts();
+        let refresh_changed = poll_git_refresh(state);
+        let session_changed = poll_session_file(config);
+        let pick_changed = poll_web_pick(state);
+        let focus_changed = poll_zellij_focus(state, config);
+        let discovery_changed = poll_discovery(state, config);
+        if git_changed
+            || hooks_changed
+            || health_changed
+            || remote_changed
+            || startup_changed
+            || capture_changed
+            || background_changed
+            || panes_changed
+            || search_changed
+            || index_changed
+            || clones_changed
+            || fetches_changed
+            || idle_changed
+            || transcripts_changed
+            || refresh_changed
+            || session_changed
+            || pick_changed
+            || focus_changed
+            || discovery_changed
+            || last_draw.elapsed() >= TICK_INTERVAL
+        {
+            state.mark_dirty();
+        }
+        if last_draw.elapsed() >= SPINNER_INTERVAL && !background_activity().is_empty() {
+            state.advance_spinner();
+        }
+
+        if state.take_dirty() {
+            // Files created or deleted on disk may have moved the selected node
+            sync_selected_node(state, config, true);
+            terminal.draw(|frame| {
+                let area = frame.area();
+                render_current_view(frame, area, state, config);
+                theme::apply(frame.buffer_mut(), config.ui.theme);
+            })?;
+            last_draw = Instant::now();
+
+            if startup_pending {
+                STARTUP.with(|s| *s.borrow_mut() = Some(StartupChecks::spawn(config)));
+                startup_pending = false;
+            }
+        }
+
+        let text_input = state.prompt().is_some()
+            || state.file_finder().is_some()
+            || state.command_palette().is_some();
+        if let Some(event) = poll_event(100, text_input)? {
+            let view = state.current_view().clone();
+            let selected = state.selected_index();
+            handle_input(state, config, event);
+            let moved = *state.current_view() != view || state.selected_index() != selected;
+            if *state.current_view() != view {
+                clamp_selection(state, config);
+            }
+            // Expanding, collapsing or filtering keeps the same node selected
+            sync_selected_node(state, config, !moved);
+            sync_session_focus(state, config);
+            sync_panel_state(state);
+            state.mark_dirty();
+        }
+
+        if state.take_config_changed() {
+            match Config::load() {
+                Ok(config) => {
+                    configure_zellij_integration(&config);
+                    glyphs::configure(config.ui.ascii_only);
+                    leave_missing_project(state, &config);
+                    reloaded = Some(config);
+                }
+                Err(e) => {
+                    crate::notify::notify(
+                        &config.notifications,
+                        NotificationEvent::ConfigReloadFailed,
+                        &format!("Configuration reload failed: {}", e),
+                    );
+                    state.show_toast(e.to_string(), ToastLevel::Error);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Routes pane operations through the gz-claude plugin when configured,
+/// and bounds how long Zellij CLI calls may take.
+fn configure_zellij_integration(config: &Config) {
+    let zellij = &config.global.zellij;
+    let plugin = match zellij.integration {
+        ZellijIntegration::Plugin => Some(zellij.plugin.as_str()),
+        ZellijIntegration::Cli => None,
+    };
+    crate::zellij::configure_plugin(plugin);
+    crate::zellij::configure_calls(CallPolicy {
+        timeout: zellij.command_timeout(),
+        retries: zellij.command_retries,
+    });
+}
+
+/// Renders the appropriate view based on the current application state.
+///
+/// Matches on the current view and creates the appropriate view component
+/// to render to the frame. If the command bar is visible, splits the area
+/// to show the command bar at the bottom.
+///
+/// # Arguments
+///
+/// * `frame` - The terminal frame to render to
+/// * `area` - The rectangular area to render within
+/// * `state` - Reference to the application state
+/// * `config` - Reference to the application configuration
+fn render_current_view(frame: &mut Frame, area: Rect, state: &mut AppState, config: &Config) {
+    // Calculate areas for main view and optional command bar
+    let (main_area, command_bar_area) = if state.is_command_bar_visible() {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(area);
+        (chunks[0], Some(chunks[1]))
+    } else {
+        (area, None)
+    };
+
+    // Render the header above the main view
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .split(main_area);
+    let (header_area, main_area) = (chunks[0], chunks[1]);
+
+    // Keep the bottom of the main view for the status bar
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(STATUS_BAR_HEIGHT)])
+        .split(main_area);
+    let (main_area, status_area) = (chunks[0], chunks[1]);
+    let mut selected_path = None;
+    let mut tree_offset = None;
+    let mut hints = Vec::new();
+    let mut total = 0;
+
+    // Render main view
+    match state.current_view() {
+        View::Workspaces => {
+            let view = WorkspacesView::new(config, state.selected_index());
+            view.render(frame, main_area);
+            hints = view.hints();
+            total = config.workspace.len();
+        }
+        View::Projects { workspace_id } => {
+            let git_info = config
+                .workspace
+                .get(workspace_id)
+                .map(|w| {
+                    w.projects
+                        .iter()
+                        .map(|p| cached_git_info(config, p))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let health = config
+                .workspace
+                .get(workspace_id)
+                .map(|w| w.projects.iter().map(|p| health_status(&p.path)).collect())
+                .unwrap_or_default();
+            let remote = config
+                .workspace
+                .get(workspace_id)
+                .map(|w| w.projects.iter().map(|p| remote_state(&p.path)).collect())
+                .unwrap_or_default();
+            let clones = config
+                .workspace
+                .get(workspace_id)
+                .map(|w| w.projects.iter().map(clone_status).collect())
+                .unwrap_or_default();
+            let attention = config
+                .workspace
+                .get(workspace_id)
+                .map(|w| {
+                    w.projects
+                        .iter()
+                        .map(|p| needs_attention(&p.path))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let snippets = config
+                .workspace
+                .get(workspace_id)
+                .map(|w| {
+                    w.projects
+                        .iter()
+                        .map(|p| transcript_snippet(&p.path))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let outcomes = config
+                .workspace
+                .get(workspace_id)
+                .map(|w| {
+                    w.projects
+                        .iter()
+                        .map(|p| action_outcomes(&p.path))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let focused = MONITORS.with(|m| {
+                let focused = &m.borrow().zellij_focus;
+                let path = focused.as_deref()?;
+                config
+                    .workspace
+                    .get(workspace_id)?
+                    .projects
+                    .iter()
+                    .position(|p| p.path == path)
+            });
+            let view =
+                ProjectsView::with_git_info(config, workspace_id, state.selected_index(), git_info)
+                    .with_health(health)
+                    .with_remote(remote)
+                    .with_clones(clones)
+                    .with_attention(attention)
+                    .with_snippets(snippets)
+                    .with_focused(focused)
+                    .with_outcomes(outcomes);
+            view.render(frame, main_area);
+            hints = view.hints();
+            total = config
+                .workspace
+                .get(workspace_id)
+                .map(|w| w.projects.len())
+                .unwrap_or(0);
+        }
+        View::FileBrowser { workspace_id, .. } => {
+            let project_index = current_project_position(state, config).map(|(_, index)| index);
+            // A project removed since is rendered empty, until the view is left
+            let view = file_browser_view(state, config, workspace_id, project_index)
+                .with_scroll_offset(state.scroll_offset());
+            let root_git = view
+                .project()
+                .map(|project| root_git_info(config, project))
+                .unwrap_or_default();
+            let view = view.with_root_git(root_git);
+            selected_path = view.selected_path();
+            hints = view.hints();
+            total = view.visible_count();
+            let preview = if state.show_preview() {
+                selected_path.as_deref().map(cached_preview)
+            } else {
+                None
+            };
+            tree_offset = Some(view.with_preview(preview).render(frame, main_area));
+        }
+        View::Conversations { .. } => {
+            if let Some(project) = current_project(state, config) {
+                with_conversations(&project.path, |conversations| {
+                    let view = ConversationsView::new(conversations, state.selected_index());
+                    view.render(frame, main_area);
+                    hints = view.hints();
+                    total = conversations.len();
+                });
+            }
+        }
+    }
+    if let Some(offset) = tree_offset {
+        state.set_scroll_offset(offset);
+    }
+    let activity = background_activity()
+        .iter()
+        .map(|progress| progress.text(state.spinner_tick()))
+        .collect();
+    view_header(state, config, selected_path.as_deref())
+        .with_dry_run(state.dry_run())
+        .with_activity(activity)
+        .render(frame, header_area);
+
+    // Render command bar if visible
+    if let Some(bar_area) = command_bar_area {
+        let commands = get_command_bar_items(state, config);
+        let command_bar = CommandBar::new(&commands, state.command_bar_selected());
+        command_bar.render(frame, bar_area);
+    }
+
+    // Render the status bar with the view's hints below the main view
+    let pending = state.pending_keys();
+    StatusBar::new(status_mode(state), hints)
+        .with_pending(&pending)
+        .with_position(state.selected_index(), total)
+        .with_notification(state.last_notification())
+        .with_stats(panel_stats(config).as_ref())
+        .render(frame, status_area);
+
+    // Render toast over the bottom of the main view
+    if let Some(toast) = state.toast() {
+        ToastView::new(toast).render(frame, main_area);
+    }
+
+    // Render prompt over the bottom of the main view
+    if let Some(prompt) = state.prompt() {
+        PromptView::new(prompt).render(frame, main_area);
+    }
+
+    // Render the workspace switcher over the current view
+    if let Some(selected) = state.workspace_switcher() {
+        WorkspaceSwitcherView::new(config, current_workspace_id(state), selected)
+            .render(frame, area);
+    }
+
+    // Render the recent files of the current project over the current view
+    if let Some(selected) = state.recent_files() {
+        if let Some(project) = current_project(state, config) {
+            let files = recent_files(&project.path);
+            RecentFilesView::new(&project.path, &files, selected).render(frame, area);
+        }
+    }
+
+    // Render the MCP servers of the current project over the current view
+    if let Some(selected) = state.mcp_servers() {
+        if let Some((project, servers)) = mcp_servers(state, config) {
+            McpServersView::new(&project.name, &servers, selected).render(frame, area);
+        }
+    }
+
+    // Render the menu of the selected project over the current view
+    if let Some(selected) = state.project_menu() {
+        if let Some((project, entries)) = project_menu(state, config) {
+            let entries: Vec<(&str, &str)> = entries
+                .iter()
+                .map(|(key, label, _)| (key.as_str(), label.as_str()))
+                .collect();
+            ProjectMenuView::new(&project.name, &entries, selected).render(frame, area);
+        }
+    }
+
+    // Render the pull requests of the current project over the current view
+    if let Some(selected) = state.pull_requests() {
+        if let Some(project) = current_project(state, config) {
+            if let Some(RemoteState::Ready(status)) = remote_state(&project.path) {
+                PullRequestsView::new(&project.name, &status, selected).render(frame, area);
+            }
+        }
+    }
+
+    // Render the results of the last search over the current view
+    if let Some(selected) = state.search_results() {
+        if let Some(project) = current_project(state, config) {
+            PICKERS.with(|p| {
+                if let Some(search) = p.borrow().search.as_ref() {
+                    SearchResultsView::new(&project.path, search, selected).render(frame, area);
+                }
+            });
+        }
+    }
+
+    // Render the jump-to-file popup over the current view
+    if let Some(finder) = state.file_finder() {
+        if let Some(project) = current_project(state, config) {
+            PICKERS.with(|p| {
+                if let Some(index) = p.borrow().file_index.as_ref() {
+                    let ranked = finder::rank(&finder.query, index.files());
+                    FileFinderView::new(
+                        &project.name,
+                        &finder.query,
+                        &ranked,
+                        finder.selected,
+                        index.is_indexing(),
+                    )
+                    .render(frame, area);
+                }
+            });
+        }
+    }
+
+    // Render the command palette over the current view
+    if let Some(palette) = state.command_palette() {
+        let entries = palette_entries(state, config);
+        let ranked = palette::rank(&palette.query, &entries, &PaletteHistory::load());
+        CommandPaletteView::new(&palette.query, &entries, &ranked, palette.selected)
+            .render(frame, area);
+    }
+
+    // Render the blame of the selected file over the current view
+    if let Some(scroll) = state.blame() {
+        VIEWS.with(|v| {
+            if let Some(blame) = v.borrow().blame.as_ref() {
+                BlameView::new(blame, scroll).render(frame, area);
+            }
+        });
+    }
+
+    // Render the output of the last captured command over the current view
+    if let Some(scroll) = state.captured_output() {
+        WORK.with(|w| {
+            if let Some(capture) = w.borrow().capture.as_ref() {
+                CapturedOutputView::new(capture, scroll).render(frame, area);
+            }
+        });
+    }
+
+    // Render popup over everything else
+    if let Some(popup) = state.popup() {
+        PopupView::new(popup).render(frame, area);
+    }
+}
+
+/// Builds the breadcrumb header of the current view.
+///
+/// Project views show the project's git summary and panes; the workspace
+/// views count the panes of every project they list.
+///
+/// # Arguments
+///
+/// * `state` - Reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `selected_path` - The entry selected in the file browser, shown as the
+///   path inside the project
+fn view_header(state: &AppState, config: &Config, selected_path: Option<&Path>) -> Header {
+    let (workspace_id, leaf) = match state.current_view() {
+        View::Workspaces => {
+            return Header::new(vec!["Workspaces".to_string()])
+                .with_pane_count(pane_count(|_| true))
+        }
+        View::Projects { workspace_id } => (workspace_id, None),
+        View::FileBrowser { workspace_id, .. } => (workspace_id, None),
+        View::Conversations { workspace_id, .. } => (workspace_id, Some("Conversations")),
+    };
+    let Some(workspace) = config.workspace.get(workspace_id) else {
+        return Header::default();
+    };
+
+    let mut crumbs = vec![workspace.name.clone()];
+    let project = match state.current_view() {
+        View::Projects { .. } => None,
+        _ => current_project(state, config),
+    };
+    let Some(project) = project else {
+        let in_workspace = |path: &Path| workspace.projects.iter().any(|p| p.path == path);
+        return Header::new(crumbs).with_pane_count(pane_count(in_workspace));
+    };
+    crumbs.push(project.name.clone());
+    if let Some(inside) = selected_path.and_then(|p| p.strip_prefix(&project.path).ok()) {
+        crumbs.extend(
+            inside
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string()),
+        );
+    }
+    crumbs.extend(leaf.map(String::from));
+
+    let info = cached_git_info(config, project);
+    let git = info.as_ref().map(|info| info.format_standard());
+    let alert = info.and_then(|info| info.operation_badge());
+    Header::new(crumbs)
+        .with_git(git)
+        .with_alert(alert)
+        .with_pane_count(pane_count(|path| path == project.path))
+}
+
+/// Lists the operations running in the background, shown with a spinner in
+/// the header: the startup checks, a git refresh, clones, health checks,
+/// background actions, panes being opened and a file search.
+fn background_activity() -> Vec<Progress> {
+    let mut activity = Vec::new();
+    if STARTUP.with(|s| s.borrow().as_ref().is_some_and(|c| c.is_running())) {
+        activity.push(Progress::new("startup checks"));
+    }
+    if let Some((done, total)) = REFRESH.with(|r| r.borrow().as_ref().map(|job| job.progress())) {
+        activity.push(Progress::new("git").with_count(done, total));
+    }
+    match CLONES.with(|c| c.borrow().len()) {
+        0 => {}
+        1 => activity.push(Progress::new("cloning")),
+        n => activity.push(Progress::new(format!("cloning {}", n))),
+    }
+    match MONITORS.with(|m| m.borrow().health.as_ref().map_or(0, |h| h.running_count())) {
+        0 => {}
+        1 => activity.push(Progress::new("checking")),
+        n => activity.push(Progress::new(format!("checking {}", n))),
+    }
+    match WORK.with(|w| w.borrow().background.len()) {
+        0 => {}
+        1 => activity.push(Progress::new("running")),
+        n => activity.push(Progress::new(format!("running {}", n))),
+    }
+    let pre_hooks = WORK.with(|w| {
+        w.borrow()
+            .pending_hooks
+            .iter()
+            .filter(|(_, then)| then.is_some())
+            .count()
+    });
+    match pre_hooks {
+        0 => {}
+        1 => activity.push(Progress::new("pre hook")),
+        n => activity.push(Progress::new(format!("pre hooks {}", n))),
+    }
+    match PANE_JOBS.with(|j| {
+        j.borrow()
+            .executor
+            .as_ref()
+            .map_or(0, PaneExecutor::pending)
+    }) {
+        0 => {}
+        1 => activity.push(Progress::new("opening pane")),
+        n => activity.push(Progress::new(format!("opening {} panes", n))),
+    }
+    if PICKERS.with(|p| {
+        p.borrow()
+            .search
+            .as_ref()
+            .is_some_and(|search| search.is_running())
+    }) {
+        activity.push(Progress::new("search"));
+    }
+    activity
+}
+
+/// Returns how many panes of the session belong to projects in scope.
+fn pane_count(in_scope: impl Fn(&Path) -> bool) -> usize {
+    SESSION.with(|s| {
+        s.borrow().as_ref().map_or(0, |session| {
+            session.panes.keys().filter(|p| in_scope(p)).count()
+        })
+    })
+}
+
+/// Returns the git information for a project from the shared git cache.
+///
+/// The cache is created on first use and watches each accessed repository,
+/// so git information is only recomputed when the repository changes.
+/// While the startup checks or a refresh compute it in the background, only
+/// cached entries are returned.
+///
+/// # Arguments
+///
+/// * `config` - Reference to the application configuration
+/// * `project` - The project, compared to its resolved base branch and remote
+///
+/// # Returns
+///
+/// The git information, or None if the path is not a git repository.
+fn cached_git_info(config: &Config, project: &Project) -> Option<GitInfo> {
+    cached_git_info_at(config, &project.path, &GitCompare::resolve(config, project))
+}
+
+/// Returns the git information of the roots of a project that are
+/// repositories of their own, e.g. submodules, from the shared git cache.
+///
+/// # Arguments
+///
+/// * `config` - Reference to the application configuration
+/// * `project` - The project, whose base branch and remote the roots are compared to
+fn root_git_info(config: &Config, project: &Project) -> Vec<(PathBuf, GitInfo)> {
+    let compare = GitCompare::resolve(config, project);
+    project
+        .root_paths()
+        .into_iter()
+        .filter(|path| crate::git::is_repository_root(path))
+        .filter_map(|path| {
+            let info = cached_git_info_at(config, &path, &compare)?;
+            Some((path, info))
+        })
+        .collect()
+}
+
+/// Returns the git information of a repository from the shared git cache;
+/// see `cached_git_info`.
+fn cached_git_info_at(config: &Config, path: &Path, compare: &GitCompare) -> Option<GitInfo> {
+    let deferred = STARTUP.with(|s| s.borrow().as_ref().is_some_and(|c| c.is_running()))
+        || REFRESH.with(|r| r.borrow().is_some());
+    GIT_CACHE.with(|c| {
+        let mut cache = c.borrow_mut();
+        let cache =
+            cache.get_or_insert_with(|| GitCache::with_watcher(config.global.git_info_level));
+        if deferred {
+            // The startup checks or a refresh are computing it in the background
+            cache.peek(path)
+        } else {
+            cache.get_compared(path, compare)
+        }
+    })
+}
+
+/// Collects the result of the deferred startup checks, if they finished.
+///
+/// The computed git information fills the cache and bad project paths are
+/// reported as an error toast.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+///
+/// # Returns
+///
+/// True if the checks finished during this call.
+fn poll_startup_checks(state: &mut AppState, config: &Config) -> bool {
+    let Some(report) = STARTUP.with(|s| s.borrow_mut().as_mut().and_then(|c| c.poll())) else {
+        return false;
+    };
+
+    GIT_CACHE.with(|c| {
+        let mut cache = c.borrow_mut();
+        let cache =
+            cache.get_or_insert_with(|| GitCache::with_watcher(config.global.git_info_level));
+        for (path, info) in report.git_info {
+            cache.insert(path, info);
+        }
+    });
+
+    match report.path_problems.as_slice() {
+        [] => {}
+        [problem] => state.show_toast(problem.to_string(), ToastLevel::Error),
+        [first, rest @ ..] => state.show_toast(
+            format!("{} (and {} more project paths)", first, rest.len()),
+            ToastLevel::Error,
+        ),
+    }
+    true
+}
+
+/// Returns the preview of a file, reusing the last one while it is unchanged.
+///
+/// Only the most recent preview is kept, keyed by path and modification time,
+/// so moving the selection or editing the file loads a fresh preview.
+///
+/// # Arguments
+///
+/// * `path` - The selected file or directory
+///
+/// # Returns
+///
+/// The preview of the file.
+fn cached_preview(path: &Path) -> FilePreview {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    VIEWS.with(|v| {
+        let cached = &mut v.borrow_mut().preview;
+        match cached.as_ref() {
+            Some((cached_path, cached_modified, preview))
+                if cached_path == path && *cached_modified == modified =>
+            {
+                preview.clone()
+            }
+            _ => {
+                let preview = load_preview(path);
+                *cached = Some((path.to_path_buf(), modified, preview.clone()));
+                preview
+            }
+        }
+    })
+}
+
+/// Runs a closure with the Claude Code conversations of a project.
+///
+/// Transcripts are parsed once per project and kept until another project's
+/// conversations are requested or the cache is cleared with refresh.
+///
+/// # Arguments
+///
+/// * `project_path` - The project directory
+/// * `f` - Closure receiving the project's conversations, newest first
+///
+/// # Returns
+///
+/// The closure's result.
+fn with_conversations<R>(project_path: &Path, f: impl FnOnce(&[Conversation]) -> R) -> R {
+    VIEWS.with(|v| {
+        let cached = &mut v.borrow_mut().conversations;
+        if cached.as_ref().map(|(path, _)| path.as_path()) != Some(project_path) {
+            *cached = Some((
+                project_path.to_path_buf(),
+                claude::list_conversations(project_path),
+            ));
+        }
+        let conversations = cached.as_ref().map(|(_, c)| c.as_slice()).unwrap_or(&[]);
+        f(conversations)
+    })
+}
+
+/// Collects finished health checks and starts the ones that are due.
+///
+/// Checks of every configured project run, not only the visible workspace,
+/// so badges are ready when switching workspaces.
+///
+/// Returns true if a displayed status changed.
+fn poll_health_checks(config: &Config) -> bool {
+    let checks = config
+        .workspace
+        .values()
+        .flat_map(|w| w.projects.iter())
+        .filter_map(|p| p.check.as_ref().map(|check| (p.path.as_path(), check)));
+
+    MONITORS.with(|m| {
+        m.borrow_mut()
+            .health
+            .get_or_insert_with(HealthMonitor::new)
+            .poll(checks)
+    })
+}
+
+/// Returns the latest health check status of a project, if it has a check.
+fn health_status(project_path: &Path) -> Option<HealthStatus> {
+    MONITORS.with(|m| {
+        m.borrow()
+            .health
+            .as_ref()
+            .and_then(|monitor| monitor.status(project_path).cloned())
+    })
+}
+
+/// Collects finished remote fetches and starts the ones whose TTL expired.
+///
+/// Does nothing unless `global.remote` is configured. Like health checks,
+/// every configured project is fetched so badges are ready when switching
+/// workspaces.
+///
+/// Returns true if a displayed state changed.
+fn poll_remote_status(config: &Config) -> bool {
+    let Some(remote) = config.global.remote.as_ref() else {
+        return false;
+    };
+    let projects = config
+        .workspace
+        .values()
+        .flat_map(|w| w.projects.iter())
+        .map(|p| p.path.as_path());
+
+    MONITORS.with(|m| {
+        m.borrow_mut()
+            .remote
+            .get_or_insert_with(RemoteMonitor::new)
+            .poll(projects, remote)
+    })
+}
+
+/// Returns the clone status of a project, if its directory doesn't exist
+/// and it has a repository to clone.
+fn clone_status(project: &Project) -> Option<CloneStatus> {
+    let cloning = CLONES.with(|c| c.borrow().get(&project.path).map(|job| job.progress()));
+    match cloning {
+        Some(progress) => Some(CloneStatus::Cloning(progress)),
+        None if project.needs_clone() => Some(CloneStatus::NotCloned),
+        None => None,
+    }
+}
+
+/// Collects finished clones and reports their outcome as toasts.
+///
+/// Returns true while clones are running, so their progress is redrawn.
+fn poll_clones(state: &mut AppState) -> bool {
+    let (finished, running) = CLONES.with(|c| {
+        let mut clones = c.borrow_mut();
+        let finished: Vec<(PathBuf, Result<()>)> = clones
+            .iter_mut()
+            .filter_map(|(path, job)| job.poll().map(|result| (path.clone(), result)))
+            .collect();
+        for (path, _) in &finished {
+            clones.remove(path);
+        }
+        (finished, !clones.is_empty())
+    });
+
+    for (path, result) in &finished {
+        GIT_CACHE.with(|c| {
+            if let Some(cache) = c.borrow_mut().as_mut() {
+                cache.invalidate(path);
+            }
+        });
+        match result {
+            Ok(()) => state.show_toast(format!("Cloned into {}", path.display()), ToastLevel::Info),
+            Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+        }
+    }
+    running || !finished.is_empty()
+}
+
+/// Starts cloning the repository of a project whose directory doesn't exist.
+fn start_clone(state: &mut AppState, config: &Config, project_index: usize) {
+    let Some(project) = current_workspace_id(state)
+        .and_then(|id| config.workspace.get(id))
+        .and_then(|w| w.projects.get(project_index))
+    else {
+        return;
+    };
+    let (Some(repo), true) = (project.repo.clone(), project.needs_clone()) else {
+        return;
+    };
+
+    let job = CloneJob::spawn(repo, project.path.clone());
+    CLONES.with(|c| c.borrow_mut().insert(project.path.clone(), job));
+    state.show_toast(format!("Cloning {}", project.name), ToastLevel::Info);
+}
+
+/// Collects finished fetches and starts the periodic ones that are due.
+///
+/// Every configured project with an auto-fetch interval is fetched, not only
+/// the visible workspace. Failures of periodic fetches are not reported, so
+/// an offline laptop doesn't toast on every interval.
+///
+/// Returns true if a fetch finished.
+fn poll_fetches(state: &mut AppState, config: &Config) -> bool {
+    let periodic = config
+        .projects()
+        .into_iter()
+        .filter(|p| !p.needs_clone())
+        .filter_map(|p| config.resolve_auto_fetch(p).map(|i| (p.path.as_path(), i)));
+    let finished = MONITORS.with(|m| m.borrow_mut().fetches.poll(periodic));
+
+    for fetch in &finished {
+        GIT_CACHE.with(|c| {
+            if let Some(cache) = c.borrow_mut().as_mut() {
+                cache.invalidate(&fetch.path);
+            }
+        });
+        if fetch.result.is_ok() {
+            notify_if_behind(config, &fetch.path);
+        }
+        if !fetch.requested {
+            continue;
+        }
+        match &fetch.result {
+            Ok(remote) => state.show_toast(
+                format!("Fetched {} in {}", remote, fetch.path.display()),
+                ToastLevel::Info,
+            ),
+            Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+        }
+    }
+    !finished.is_empty()
+}
+
+/// Notifies when a fetch left a project more than `behind_warn` commits
+/// behind its upstream.
+///
+/// A project is notified once until it catches up again.
+///
+/// # Arguments
+///
+/// * `config` - Reference to the application configuration
+/// * `path` - The directory of the fetched project
+fn notify_if_behind(config: &Config, path: &Path) {
+    if !config.notifications.notifies(NotificationEvent::GitBehind) {
+        return;
+    }
+    let Some(project) = config.projects().into_iter().find(|p| p.path == path) else {
+        return;
+    };
+    let behind = cached_git_info(config, project).map_or(0, |info| info.behind);
+    let threshold = config.resolve_behind_warn(project);
+
+    let newly_behind = MONITORS.with(|m| {
+        let notified = &mut m.borrow_mut().behind_notified;
+        if behind > threshold {
+            notified.insert(path.to_path_buf())
+        } else {
+            notified.remove(path);
+            false
+        }
+    });
+    if newly_behind {
+        crate::notify::notify(
+            &config.notifications,
+            NotificationEvent::GitBehind,
+            &format!("{} is {} commits behind its upstream", project.name, behind),
+        );
+    }
+}
+
+/// Starts fetching the remote of the current project.
+fn fetch_current_project(state: &mut AppState, config: &Config) {
+    let Some(project) = current_project(state, config) else {
+        return;
+    };
+    if project.needs_clone() {
+        let message = format!("{} is not cloned yet", project.name);
+        state.show_toast(message, ToastLevel::Info);
+        return;
+    }
+
+    let message = if MONITORS.with(|m| m.borrow_mut().fetches.request(&project.path)) {
+        format!("Fetching {}", project.name)
+    } else {
+        format!("{} is already being fetched", project.name)
+    };
+    state.show_toast(message, ToastLevel::Info);
+}
+
+/// Reads the screens of Claude panes and flags the ones waiting for input.
+///
+/// Only panes whose Zellij id is known are watched, which leaves out the
+/// main pane. A pane that goes idle is announced with a toast and, when
+/// enabled, a desktop notification.
+///
+/// Returns true if a pane started or stopped needing attention.
+fn poll_idle_panes(state: &mut AppState, config: &Config) -> bool {
+    let idle = &config.global.claude_idle;
+    if !idle.enabled {
+        return false;
+    }
+    let panes: Vec<(PathBuf, String)> = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .map(|session| {
+                session
+                    .panes
+                    .iter()
+                    .filter(|(_, pane)| pane.is_claude())
+                    .filter_map(|(path, pane)| Some((path.clone(), pane.pane_id.clone()?)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+    let went_idle = MONITORS.with(|m| {
+        m.borrow_mut().idle.poll(
+            panes.iter().map(|(path, id)| (path.as_path(), id.as_str())),
+            Duration::from_secs(idle.interval_secs),
+            crate::zellij::dump_pane,
+        )
+    });
+
+    for path in &went_idle {
+        let name = config
+            .projects()
+            .into_iter()
+            .find(|p| &p.path == path)
+            .map_or_else(|| path.display().to_string(), |p| p.name.clone());
+        let message = format!("Claude is waiting for input in {}", name);
+        if idle.desktop_notification {
+            util::notify::notify("gz-claude", &message);
+        }
+        crate::notify::notify(
+            &config.notifications,
+            NotificationEvent::ClaudeIdle,
+            &message,
+        );
+        state.show_toast(message, ToastLevel::Info);
+    }
+    sync_attention()
+}
+
+/// Reads the latest transcript line of projects with a Claude pane.
+///
+/// Returns true if a line changed.
+fn poll_transcripts() -> bool {
+    let projects: Vec<PathBuf> = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .map(|session| {
+                session
+                    .panes
+                    .iter()
+                    .filter(|(_, pane)| pane.is_claude())
+                    .map(|(path, _)| path.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+    MONITORS.with(|m| {
+        m.borrow_mut()
+            .transcripts
+            .poll(projects.iter().map(PathBuf::as_path), TRANSCRIPT_INTERVAL)
+    })
+}
+
+/// Returns the latest transcript line of a project's Claude pane.
+fn transcript_snippet(project_path: &Path) -> Option<String> {
+    MONITORS.with(|m| {
+        m.borrow()
+            .transcripts
+            .snippet(project_path)
+            .map(String::from)
+    })
+}
+
+/// Tracks the project whose pane Zellij focuses.
+///
+/// The clients are listed in the background every `focus_interval_ms`. A
+/// project pane getting the focus counts as looking at it, so its attention
+/// badge is cleared; with `follow_focus`, the project is also selected in the
+/// Projects view. While the panel itself has the focus, the project looked at
+/// last stays marked.
+///
+/// Returns true if the focused project changed.
+fn poll_zellij_focus(state: &mut AppState, config: &Config) -> bool {
+    let Some(interval) = config.global.zellij.focus_interval() else {
+        return false;
+    };
+    if crate::zellij::ensure_in_session().is_err() {
+        return false;
+    }
+    let Some(focused) = MONITORS.with(|m| {
+        m.borrow_mut()
+            .focus_poll
+            .poll(interval, crate::zellij::focused_panes)
+    }) else {
+        return false;
+    };
+
+    let project = SESSION.with(|s| {
+        let session = s.borrow();
+        let session = session.as_ref()?;
+        focused
+            .iter()
+            .find_map(|pane_id| session.project_of_pane(pane_id))
+            .map(Path::to_path_buf)
+    });
+    let own_pane = crate::zellij::own_pane();
+    if project.is_none() && own_pane.is_some_and(|own| focused.contains(&own)) {
+        return false;
+    }
+    let changed = MONITORS.with(|m| {
+        let current = &mut m.borrow_mut().zellij_focus;
+        let changed = *current != project;
+        *current = project.clone();
+        changed
+    });
+    if !changed {
+        return false;
+    }
+
+    if let Some(path) = &project {
+        if MONITORS.with(|m| m.borrow_mut().idle.acknowledge(path)) {
+            sync_attention();
+        }
+        if config.global.zellij.follow_focus {
+            select_project_at(state, config, path);
+        }
+    }
+    true
+}
+
+/// Selects a project of the workspace shown in the Projects view, unless a
+/// prompt or popup is open over it.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `project_path` - The directory of the project to select
+fn select_project_at(state: &mut AppState, config: &Config, project_path: &Path) {
+    let View::Projects { workspace_id } = state.current_view() else {
+        return;
+    };
+    if state.prompt().is_some() || state.popup().is_some() || state.is_command_bar_visible() {
+        return;
+    }
+    let index = config
+        .workspace
+        .get(workspace_id)
+        .and_then(|w| w.projects.iter().position(|p| p.path == project_path));
+    if let Some(index) = index {
+        state.set_selected_index(index);
+        sync_session_focus(state, config);
+        sync_panel_state(state);
+    }
+}
+
+/// Returns whether a project's Claude pane waits for input unseen.
+fn needs_attention(project_path: &Path) -> bool {
+    MONITORS.with(|m| m.borrow().idle.needs_attention(project_path))
+}
+
+/// Copies the attention flags of the idle monitor to the session, where the
+/// top bar reads them.
+///
+/// Returns true if a flag changed.
+fn sync_attention() -> bool {
+    let changed = SESSION.with(|s| {
+        s.borrow().as_ref().is_some_and(|session| {
+            session
+                .panes
+                .iter()
+                .any(|(path, pane)| pane.needs_attention != needs_attention(path))
+        })
+    });
+    if changed {
+        update_session(|session| {
+            for (path, pane) in session.panes.iter_mut() {
+                pane.needs_attention = needs_attention(path);
+            }
+        });
+    }
+    changed
+}
+
+/// Starts recomputing the git info of every project in the current
+/// workspace, in parallel.
+///
+/// Cached entries are kept until their new value arrives, so the view
+/// doesn't block or go blank meanwhile.
+fn refresh_workspace_git_info(state: &mut AppState, config: &Config) {
+    let Some(workspace) = current_workspace_id(state).and_then(|id| config.workspace.get(id))
+    else {
+        return;
+    };
+    if REFRESH.with(|r| r.borrow().is_some()) {
+        state.show_toast("Git info is already being refreshed", ToastLevel::Info);
+        return;
+    }
+
+    let targets: Vec<RefreshTarget> = workspace
+        .projects
+        .iter()
+        .filter(|p| !p.needs_clone())
+        .map(|p| (p.path.clone(), GitCompare::resolve(config, p)))
+        .collect();
+    let refresh = GitRefresh::spawn(
+        targets,
+        config.global.git_info_level,
+        GitRefresh::default_limit(),
+    );
+    REFRESH.with(|r| *r.borrow_mut() = Some(refresh));
+    poll_git_refresh(state);
+}
+
+/// Stores the git info computed by a running refresh in the git cache and
+/// reports when it finished; its progress is shown in the header.
+///
+/// Returns true if git info arrived or the refresh finished.
+fn poll_git_refresh(state: &mut AppState) -> bool {
+    let Some((results, (done, _), finished)) = REFRESH.with(|r| {
+        let mut refresh = r.borrow_mut();
+        let job = refresh.as_mut()?;
+        let results = job.poll();
+        let outcome = (results, job.progress(), job.is_finished());
+        if outcome.2 {
+            *refresh = None;
+        }
+        Some(outcome)
+    }) else {
+        return false;
+    };
+
+    let changed = !results.is_empty() || finished;
+    GIT_CACHE.with(|c| {
+        if let Some(cache) = c.borrow_mut().as_mut() {
+            for (path, info) in results {
+                cache.insert(path, info);
+            }
+        }
+    });
+    // The progress is shown in the header while it runs
+    if finished {
+        state.show_toast(
+            format!("Refreshed git info of {} projects", done),
+            ToastLevel::Info,
+        );
+    }
+    changed
+}
+
+/// Collects the outcome of the captured command if it just finished, and
+/// notifies that it finished.
+///
+/// Returns true if the captured output changed.
+fn poll_captured_output(config: &Config) -> bool {
+    let finished = WORK.with(|w| {
+        let capture = &mut w.borrow_mut().capture;
+        let capture = capture.as_mut()?;
+        if !capture.poll() {
+            return None;
+        }
+        let status = capture.result().map_or("", |result| result.status.as_str());
+        Some(format!("{} finished ({})", capture.title(), status))
+    });
+    let Some(message) = finished else {
+        return false;
+    };
+    crate::notify::notify(
+        &config.notifications,
+        NotificationEvent::CommandFinished,
+        &message,
+    );
+    true
+}
+
+/// Collects the background actions that finished: writes their output to
+/// the project's logs, reports their exit status as a toast and keeps it
+/// for the badge after the action's icon.
+///
+/// Returns true if an action finished.
+fn poll_background_actions(state: &mut AppState, config: &Config) -> bool {
+    let finished: Vec<BackgroundAction> = WORK.with(|w| {
+        let actions = &mut w.borrow_mut().background;
+        actions.iter_mut().for_each(|action| {
+            action.capture.poll();
+        });
+        let (running, finished) = actions
+            .drain(..)
+            .partition(|action| action.capture.is_running());
+        *actions = running;
+        finished
+    });
+
+    for action in &finished {
+        let capture = &action.capture;
+        let Some(result) = capture.result() else {
+            continue;
+        };
+        WORK.with(|w| {
+            w.borrow_mut()
+                .outcomes
+                .entry(action.project_path.clone())
+                .or_default()
+                .insert(action.key.clone(), result.success)
+        });
+        let (verb, level) = if result.success {
+            ("finished", ToastLevel::Info)
+        } else {
+            ("failed", ToastLevel::Error)
+        };
+        let message = format!("{} {} ({})", capture.title(), verb, result.status);
+        crate::notify::notify(
+            &config.notifications,
+            NotificationEvent::CommandFinished,
+            &message,
+        );
+        match logs::write_log(&action.logs_dir, capture.title(), &result.lines) {
+            Ok(_) => state.show_toast(message, level),
+            Err(e) => state.show_toast(
+                format!("{}; its output could not be logged: {}", message, e),
+                ToastLevel::Error,
+            ),
+        }
+    }
+    !finished.is_empty()
+}
+
+/// Returns whether the last background run of each action of a project
+/// passed, by action key.
+fn action_outcomes(project_path: &Path) -> BTreeMap<String, bool> {
+    WORK.with(|w| {
+        w.borrow()
+            .outcomes
+            .get(project_path)
+            .cloned()
+            .unwrap_or_default()
+    })
+}
+
+/// Returns the number of output lines of the captured command.
+fn captured_line_count() -> usize {
+    WORK.with(|w| {
+        w.borrow()
+            .capture
+            .as_ref()
+            .and_then(|capture| capture.result())
+            .map_or(0, |result| result.lines.len())
+    })
+}
+
+/// Returns the latest remote state of a project, if it has been fetched.
+fn remote_state(project_path: &Path) -> Option<RemoteState> {
+    MONITORS.with(|m| {
+        m.borrow()
+            .remote
+            .as_ref()
+            .and_then(|monitor| monitor.state(project_path).cloned())
+    })
+}
+
+/// Reports the results of background hooks that have finished.
+///
+/// Finished hooks are removed from the pending list; failures are surfaced
+/// as error toasts, and what waited for a hook that succeeded runs.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+///
+/// # Returns
+///
+/// True if a hook finished.
+fn collect_finished_hooks(state: &mut AppState) -> bool {
+    let finished: Vec<_> = WORK.with(|w| {
+        let pending = &mut w.borrow_mut().pending_hooks;
+        let (finished, running) = pending.drain(..).partition(|(h, _)| h.is_finished());
+        *pending = running;
+        finished
+    });
+
+    let changed = !finished.is_empty();
+    for (handle, then) in finished {
+        match handle.join() {
+            Ok(Err(e)) => state.show_toast(e.to_string(), ToastLevel::Error),
+            Err(_) => state.show_toast("Hook panicked", ToastLevel::Error),
+            Ok(Ok(())) => {
+                if let Some(then) = then {
+                    then(state);
+                }
+            }
+        }
+    }
+    changed
+}
+
+/// Runs a hook on a background thread, reported by `collect_finished_hooks`.
+///
+/// # Arguments
+///
+/// * `cwd` - The directory the hook runs in
+/// * `hook` - The shell command
+/// * `then` - Runs once the hook succeeded, e.g. opening the pane a pre hook
+///   prepares
+fn spawn_background_hook(cwd: PathBuf, hook: String, then: Option<HookCallback>) {
+    let handle = hooks::spawn_hook(cwd, hook);
+    WORK.with(|w| w.borrow_mut().pending_hooks.push((handle, then)));
+}
+
+/// Handles input events by updating the application state.
+///
+/// Processes navigation (up/down), selection (enter), back navigation,
+/// quit requests, and other actions. When the command bar is visible,
+/// intercepts relevant events for command bar navigation.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+fn handle_input(state: &mut AppState, config: &Config, event: InputEvent) {
+    // Any key dismisses an open popup
+    if state.popup().is_some() && event != InputEvent::Resize {
+        state.close_popup();
+        return;
+    }
+
+    // Handle command bar mode separately
+    if state.is_command_bar_visible() {
+        handle_command_bar_input(state, config, event);
+        return;
+    }
+
+    if state.workspace_switcher().is_some() {
+        handle_workspace_switcher_input(state, config, event);
+        return;
+    }
+
+    if state.recent_files().is_some() {
+        handle_recent_files_input(state, config, event);
+        return;
+    }
+
+    if state.mcp_servers().is_some() {
+        handle_mcp_servers_input(state, config, event);
+        return;
+    }
+
+    if state.project_menu().is_some() {
+        handle_project_menu_input(state, config, event);
+        return;
+    }
+
+    if state.pull_requests().is_some() {
+        handle_pull_requests_input(state, config, event);
+        return;
+    }
+
+    if state.captured_output().is_some() {
+        handle_captured_output_input(state, event);
+        return;
+    }
+
+    if state.blame().is_some() {
+        handle_blame_input(state, event);
+        return;
+    }
+
+    if state.search_results().is_some() {
+        handle_search_results_input(state, config, event);
+        return;
+    }
+
+    if state.file_finder().is_some() {
+        handle_file_finder_input(state, config, event);
+        return;
+    }
+
+    if state.command_palette().is_some() {
+        handle_command_palette_input(state, config, event);
+        return;
+    }
+
+    if state.prompt().is_some() {
+        handle_prompt_input(state, config, event);
+        return;
+    }
+
+    let event = if config.global.navigation == NavigationMode::Vim {
+        match state.vim_keys_mut().feed(event) {
+            VimOutcome::Pending => return,
+            VimOutcome::Move(motion) => {
+                let len = get_max_index(state, config);
+                state.set_selected_index(motion.apply(state.selected_index(), len));
+                return;
+            }
+            VimOutcome::Event(event) => event,
+        }
+    } else {
+        event
+    };
+
+    handle_event(state, config, event);
+}
+
+/// Handles an input event of the current view, once no popup, prompt or
+/// vim prefix has taken it.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+fn handle_event(state: &mut AppState, config: &Config, event: InputEvent) {
+    match event {
+        InputEvent::Up => {
+            let current = state.selected_index();
+            if current > 0 {
+                state.set_selected_index(current - 1);
+            }
+        }
+        InputEvent::Down => {
+            let current = state.selected_index();
+            let max_index = get_max_index(state, config);
+            if max_index > 0 && current < max_index - 1 {
+                state.set_selected_index(current + 1);
+            }
+        }
+        InputEvent::Right | InputEvent::ProjectMenu => {
+            if matches!(state.current_view(), View::Projects { .. }) {
+                show_project_menu(state, config);
+            }
+        }
+        InputEvent::Left => {
+            // Not used in normal mode
+        }
+        InputEvent::Resize => {
+            // Only requires a redraw, done by the event loop
+        }
+        InputEvent::Enter => {
+            handle_enter(state, config, false);
+        }
+        InputEvent::AltEnter => {
+            handle_enter(state, config, true);
+        }
+        InputEvent::Back => {
+            navigate_back(state, config);
+        }
+        InputEvent::Quit => {
+            if matches!(state.current_view(), View::Workspaces) {
+                state.quit();
+            } else {
+                navigate_back(state, config);
+            }
+        }
+        InputEvent::Refresh => {
+            // Git info is normally refreshed by the watcher; 'r' forces a full reload.
+            GIT_CACHE.with(|c| {
+                if let Some(cache) = c.borrow_mut().as_mut() {
+                    cache.invalidate_all();
+                }
+            });
+            VIEWS.with(|v| v.borrow_mut().conversations = None);
+            MONITORS.with(|m| {
+                if let Some(monitor) = m.borrow_mut().health.as_mut() {
+                    monitor.reschedule_all();
+                }
+            });
+            MONITORS.with(|m| {
+                if let Some(monitor) = m.borrow_mut().remote.as_mut() {
+                    monitor.reschedule_all();
+                }
+            });
+        }
+        InputEvent::HealthDetails => {
+            show_health_details(state, config);
+        }
+        InputEvent::GitUi => {
+            open_git_ui(state, config);
+        }
+        InputEvent::OpenShell => {
+            open_shell_at_selection(state, config);
+        }
+        InputEvent::OpenLatestLog => {
+            open_latest_log(state, config);
+        }
+        InputEvent::Search => {
+            if matches!(state.current_view(), View::FileBrowser { .. }) {
+                open_search_prompt(state);
+            }
+        }
+        InputEvent::FileFinder => {
+            open_file_finder(state, config);
+        }
+        InputEvent::CommandPalette => {
+            state.open_command_palette();
+        }
+        InputEvent::WorkspaceSwitcher => {
+            if can_switch_workspace(state) {
+                let view = WorkspacesView::new(config, 0);
+                let current = current_workspace_id(state);
+                let selected = view
+                    .workspace_ids()
+                    .iter()
+                    .position(|id| Some(*id) == current);
+                state.open_workspace_switcher(selected.unwrap_or(0));
+            }
+        }
+        InputEvent::SwitchWorkspace(index) => {
+            if can_switch_workspace(state) {
+                switch_to_workspace_at(state, config, index);
+            }
+        }
+        InputEvent::RecentFiles => {
+            if let Some(project) = current_project(state, config) {
+                if recent_files(&project.path).is_empty() {
+                    let message = format!("No recent files in {}", project.name);
+                    state.show_toast(message, ToastLevel::Info);
+                } else {
+                    state.open_recent_files();
+                }
+            }
+        }
+        InputEvent::PullRequests => {
+            show_pull_requests(state, config);
+        }
+        InputEvent::McpServers => {
+            show_mcp_servers(state, config);
+        }
+        InputEvent::RevealInFileManager => {
+            reveal_selection(state, config);
+        }
+        InputEvent::OpenRemote => {
+            open_remote_in_browser(state, config);
+        }
+        InputEvent::Blame => {
+            show_blame(state, config);
+        }
+        InputEvent::Fetch => {
+            fetch_current_project(state, config);
+        }
+        InputEvent::RefreshAll => {
+            refresh_workspace_git_info(state, config);
+        }
+        InputEvent::CycleWebFocus => {
+            if !matches!(state.current_view(), View::Workspaces) {
+                cycle_web_focus(state, config);
+            }
+        }
+        InputEvent::KillPane => {
+            stop_project_pane(state, config, false);
+        }
+        InputEvent::RestartPane => {
+            stop_project_pane(state, config, true);
+        }
+        InputEvent::ZoomMainPane => {
+            zoom_main_pane(state);
+        }
+        InputEvent::ToggleEmbedPane => {
+            toggle_embed_project_pane(state, config);
+        }
+        InputEvent::ArrangePanes => {
+            if !matches!(state.current_view(), View::Workspaces) {
+                arrange_panes_in_tabs(state, config);
+            }
+        }
+        InputEvent::ToggleDryRun => {
+            let dry_run = !state.dry_run();
+            state.set_dry_run(dry_run);
+            let message = if dry_run {
+                "Dry run on: actions and commands are previewed"
+            } else {
+                "Dry run off"
+            };
+            state.show_toast(message, ToastLevel::Info);
+        }
+        InputEvent::AddProject => {
+            if let View::Projects { workspace_id } = state.current_view() {
+                // A directory just discovered is offered first
+                let name = discovered_project(config, workspace_id)
+                    .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+                    .unwrap_or_default();
+                state.open_prompt(PromptKind::ProjectName, "Project name:", name);
+            }
+        }
+        InputEvent::NewTask => {
+            if let View::Projects { workspace_id } = state.current_view() {
+                let project_index = state.selected_index();
+                if let Some(project) = config
+                    .workspace
+                    .get(workspace_id)
+                    .and_then(|w| w.projects.get(project_index))
+                {
+                    let label = format!("New task for '{}':", project.name);
+                    state.open_prompt(PromptKind::TaskName { project_index }, label, "");
+                }
+            }
+        }
+        InputEvent::RemoveProject => {
+            if let View::Projects { workspace_id } = state.current_view() {
+                let project_index = state.selected_index();
+                if let Some(project) = config
+                    .workspace
+                    .get(workspace_id)
+                    .and_then(|w| w.projects.get(project_index))
+                {
+                    let label = format!("Remove project '{}' from the config?", project.name);
+                    let kind = PromptKind::ConfirmRemove {
+                        project_index,
+                        name: project.name.clone(),
+                        path: project.path.clone(),
+                    };
+                    state.open_prompt(kind, label, "");
+                }
+            }
+        }
+        InputEvent::Char(_) | InputEvent::DeleteChar | InputEvent::Complete => {
+            // Only produced while a prompt reads text
+        }
+        InputEvent::TogglePreview => {
+            if matches!(state.current_view(), View::FileBrowser { .. }) {
+                state.toggle_preview();
+            }
+        }
+        InputEvent::ToggleIgnored => {
+            if matches!(state.current_view(), View::FileBrowser { .. }) {
+                state.toggle_show_ignored();
+            }
+        }
+        InputEvent::Conversations => {
+            if let View::Projects { workspace_id } = state.current_view() {
+                if let Some(project_id) = config.project_id(workspace_id, state.selected_index()) {
+                    state.navigate_to_conversations(project_id);
+                }
+            }
+        }
+        InputEvent::ToggleCommandBar => {
+            // Only allow command bar in Projects and FileBrowser views
+            if !matches!(state.current_view(), View::Workspaces) {
+                state.toggle_command_bar();
+            }
+        }
+        InputEvent::Yank => {
+            let text = yank_text(state, config);
+            copy_with_toast(state, text);
+        }
+        InputEvent::YankWebUrl => {
+            copy_with_toast(state, crate::zellij::load_web_url());
+        }
+        InputEvent::Action(key) => {
+            handle_action(state, config, key, &[]);
+        }
+    }
+}
+
+/// Builds the file browser view of a project as displayed.
+///
+/// Uses the expanded directories, ignored entries setting and recent files,
+/// so selection indices match what's on screen.
+///
+/// # Arguments
+///
+/// * `state` - Reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `workspace_id` - The workspace containing the project
+/// * `project_index` - The index of the project within the workspace, None
+///   if it was removed
+fn file_browser_view<'a>(
+    state: &AppState,
+    config: &'a Config,
+    workspace_id: &'a str,
+    project_index: Option<usize>,
+) -> FileBrowserView<'a> {
+    let view = FileBrowserView::with_expanded(
+        config,
+        workspace_id,
+        // Out of range when the project was removed, so the view is empty
+        project_index.unwrap_or(usize::MAX),
+        state.selected_index(),
+        state.expanded_dirs(),
+        state.show_ignored(),
+    );
+    let recent = view
+        .project()
+        .map(|p| recent_files(&p.path))
+        .unwrap_or_default();
+    view.with_recent(recent)
+}
+
+/// Returns the recent files of a project that still exist, most recent first.
+fn recent_files(project_path: &Path) -> Vec<PathBuf> {
+    VIEWS.with(|v| {
+        v.borrow_mut()
+            .recent
+            .get_or_insert_with(RecentFiles::load)
+            .files(project_path)
+            .iter()
+            .filter(|f| f.is_file())
+            .cloned()
+            .collect()
+    })
+}
+
+/// Opens a file in the editor and records it as recent for the project.
+///
+/// The recent files are reloaded before recording, so files opened from
+/// other sessions are kept. Failures are reported as toasts.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `project_path` - The project the file belongs to
+/// * `file_path` - The file to open
+/// * `line` - The line to open the file at, if any
+///
+/// # Returns
+///
+/// True if the editor was opened.
+fn open_in_editor(
+    state: &mut AppState,
+    config: &Config,
+    project_path: &Path,
+    file_path: &Path,
+    line: Option<u32>,
+) -> bool {
+    let editor = &config.global.editor;
+    let opened = match line {
+        Some(line) => crate::zellij::open_file_in_editor_at(project_path, editor, file_path, line),
+        None => crate::zellij::open_file_in_editor(project_path, editor, file_path),
+    };
+    if let Err(e) = opened {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+        return false;
+    }
+
+    let mut recent = RecentFiles::load();
+    recent.record(project_path, file_path);
+    if let Err(e) = recent.save() {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+    }
+    VIEWS.with(|v| v.borrow_mut().recent = Some(recent));
+    true
+}
+
+/// Returns the workspace and index of the project selected in the Projects
+/// view or open in another view.
+///
+/// A project open in another view is found by its id, so it is found at its
+/// current position after the configuration was reloaded; None if it was
+/// removed or renamed since.
+fn current_project_position<'a>(state: &'a AppState, config: &Config) -> Option<(&'a str, usize)> {
+    match state.current_view() {
+        View::Workspaces => None,
+        View::Projects { workspace_id } => Some((workspace_id, state.selected_index())),
+        View::FileBrowser {
+            workspace_id,
+            project_id,
+        }
+        | View::Conversations {
+            workspace_id,
+            project_id,
+        } => Some((
+            workspace_id,
+            config.project_index(workspace_id, *project_id)?,
+        )),
+    }
+}
+
+/// Navigates back one level, selecting the project left at its current
+/// position when going back to the Projects view.
+fn navigate_back(state: &mut AppState, config: &Config) {
+    let project_index = match state.current_view() {
+        View::Projects { .. } | View::Workspaces => None,
+        _ => current_project_position(state, config).map(|(_, index)| index),
+    };
+    state.navigate_back();
+    if let Some(index) = project_index {
+        state.set_selected_index(index);
+    }
+}
+
+/// Leaves the view of a project that was removed or renamed in the
+/// configuration, e.g. after it was reloaded.
+fn leave_missing_project(state: &mut AppState, config: &Config) {
+    let missing = matches!(
+        state.current_view(),
+        View::FileBrowser { .. } | View::Conversations { .. }
+    ) && current_project_position(state, config).is_none();
+    if missing {
+        state.navigate_back();
+        clamp_selection(state, config);
+    }
+}
+
+/// Returns the project selected in the Projects view or open in another view.
+fn current_project<'a>(state: &AppState, config: &'a Config) -> Option<&'a Project> {
+    let (workspace_id, project_index) = current_project_position(state, config)?;
+    config
+        .workspace
+        .get(workspace_id)?
+        .projects
+        .get(project_index)
+}
+
+/// Opens the configuration file in the editor.
+fn edit_config(state: &mut AppState, config: &Config) {
+    let config_path = Config::default_path();
+    let dir = config_path.parent().unwrap_or(Path::new("."));
+    if let Err(e) = crate::zellij::open_file_in_editor(dir, &config.global.editor, &config_path) {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+    }
+}
+
+/// Shows the current selection in the system file manager.
+///
+/// Reveals the selected file or directory in the file browser, and the
+/// project directory in the other project views.
+fn reveal_selection(state: &mut AppState, config: &Config) {
+    let path = match state.current_view() {
+        View::FileBrowser { workspace_id, .. } => {
+            let project_index = current_project_position(state, config).map(|(_, index)| index);
+            file_browser_view(state, config, workspace_id, project_index).selected_path()
+        }
+        _ => None,
+    };
+    let Some(path) = path.or_else(|| current_project(state, config).map(|p| p.path.clone())) else {
+        return;
+    };
+
+    if let Err(e) = util::reveal_path(&path) {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+    }
+}
+
+/// Opens the web page of the current project's `origin` repository.
+fn open_remote_in_browser(state: &mut AppState, config: &Config) {
+    let Some(project) = current_project(state, config) else {
+        return;
+    };
+    let Some(origin) = crate::git::origin_url(&project.path) else {
+        let message = format!("No 'origin' remote in {}", project.name);
+        state.show_toast(message, ToastLevel::Error);
+        return;
+    };
+    let Some(url) = crate::remote::web_url(&origin) else {
+        let message = format!("Cannot open '{}' in a browser", origin);
+        state.show_toast(message, ToastLevel::Error);
+        return;
+    };
+
+    match util::open_url(&url) {
+        Ok(()) => state.show_toast(format!("Opened {}", url), ToastLevel::Info),
+        Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+    }
+}
+
+/// Returns whether the current view allows switching workspaces directly.
+fn can_switch_workspace(state: &AppState) -> bool {
+    matches!(
+        state.current_view(),
+        View::Projects { .. } | View::FileBrowser { .. }
+    )
+}
+
+/// Returns the identifier of the workspace currently open, if any.
+fn current_workspace_id(state: &AppState) -> Option<&str> {
+    match state.current_view() {
+        View::Workspaces => None,
+        View::Projects { workspace_id }
+        | View::FileBrowser { workspace_id, .. }
+        | View::Conversations { workspace_id, .. } => Some(workspace_id),
+    }
+}
+
+/// Switches to the workspace at the given position of the Workspaces view.
+///
+/// Positions past the last workspace are ignored.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `index` - Zero-based position of the workspace, sorted alphabetically
+fn switch_to_workspace_at(state: &mut AppState, config: &Config, index: usize) {
+    let view = WorkspacesView::new(config, 0);
+    let Some(workspace_id) = view.workspace_ids().get(index).map(|id| id.to_string()) else {
+        return;
+    };
+    state.switch_workspace(workspace_id);
+}
+
+/// Opens the configured git UI in a floating pane.
+///
+/// Uses the selected project in the Projects view and the current project
+/// elsewhere. In the FileBrowser view, the selected file or directory is
+/// passed to the git UI as a filter.
+fn open_git_ui(state: &mut AppState, config: &Config) {
+    let Some((workspace_id, project_index)) = current_project_position(state, config) else {
+        return;
+    };
+    let Some(project) = config
+        .workspace
+        .get(workspace_id)
+        .and_then(|w| w.projects.get(project_index))
+    else {
+        return;
+    };
+
+    let selected = match state.current_view() {
+        View::FileBrowser { .. } => {
+            file_browser_view(state, config, workspace_id, Some(project_index)).selected_path()
+        }
+        _ => None,
+    };
+
+    let command = git_ui_command(&config.global.git_ui, &project.path, selected.as_deref());
+    let pane_name = project_pane_name(config, workspace_id, project, "Git");
+    open_floating_pane(state, &pane_name, &command, |_, _| {});
+}
+
+/// Opens the most recent log file of the current project in a floating pane.
+///
+/// The log is paged with `less -R`, starting at its end, so the colors
+/// recorded by `script` are kept. Shows a toast when the project has no logs.
+fn open_latest_log(state: &mut AppState, config: &Config) {
+    let Some(workspace_id) = current_workspace_id(state).map(str::to_string) else {
+        return;
+    };
+    let Some(project) = current_project(state, config) else {
+        return;
+    };
+
+    let Some(log) = logs::latest_log(&logs::project_logs_dir(&workspace_id, &project.name)) else {
+        state.show_toast(format!("No logs for {}", project.name), ToastLevel::Info);
+        return;
+    };
+    let command = format!("less -R +G {}", util::shell::quote(&log.to_string_lossy()));
+    let pane_name = project_pane_name(config, &workspace_id, project, "Log");
+    open_floating_pane(state, &pane_name, &command, |_, _| {});
+}
+
+/// Returns whether a project has unresolved merge conflicts, as last seen
+/// by the git cache.
+fn has_conflicts(config: &Config, project: &Project) -> bool {
+    cached_git_info(config, project).is_some_and(|info| info.conflicted_count > 0)
+}
+
+/// Runs `git mergetool` on the current project in a floating pane, to
+/// resolve the conflicts of a merge or rebase in progress.
+fn open_mergetool(state: &mut AppState, config: &Config) {
+    let Some(workspace_id) = current_workspace_id(state).map(str::to_string) else {
+        return;
+    };
+    let Some(project) = current_project(state, config) else {
+        return;
+    };
+
+    let command = format!(
+        "cd {} && git mergetool",
+        util::shell::quote(&project.path.to_string_lossy())
+    );
+    let pane_name = project_pane_name(config, &workspace_id, project, "Mergetool");
+    open_floating_pane(state, &pane_name, &command, |_, _| {});
+}
+
+/// Opens a shell in a floating pane, in the directory of the selected file.
+///
+/// Only available in the FileBrowser view: a selected directory is used as
+/// is, a selected file opens its parent directory. The pane is registered in
+/// the session under that directory, unless it is the project root and the
+/// project already has a pane. In a dry run, the pane is previewed instead.
+fn open_shell_at_selection(state: &mut AppState, config: &Config) {
+    let View::FileBrowser { workspace_id, .. } = state.current_view() else {
+        return;
+    };
+    let project_index = current_project_position(state, config).map(|(_, index)| index);
+    let view = file_browser_view(state, config, workspace_id, project_index);
+    let Some(project) = view.project() else {
+        return;
+    };
+    let dir = match view.selected_path() {
+        Some(path) if view.selected_is_file() => path
+            .parent()
+            .map_or_else(|| project.path.clone(), Path::to_path_buf),
+        Some(path) => path,
+        None => project.path.clone(),
+    };
+
+    let relative = dir
+        .strip_prefix(&project.path)
+        .ok()
+        .filter(|path| !path.as_os_str().is_empty());
+    let label = match relative {
+        Some(relative) => format!("Shell {}", relative.display()),
+        None => "Shell".to_string(),
+    };
+    let pane_name = project_pane_name(config, workspace_id, project, &label);
+    let command = project.wrap_env(&shell_command(&config.global.shell_program(), &dir));
+
+    if state.dry_run() {
+        let mut lines = vec![format!("Command: {}", command), String::new()];
+        lines.extend(dry_run_invocations(|| {
+            crate::zellij::run_in_floating_pane(&pane_name, &command).map(|_| ())
+        }));
+        state.show_popup(format!("Dry run: {}", pane_name), lines.join("\n"));
+        return;
+    }
+
+    let is_project_dir = dir == project.path;
+    open_floating_pane(
+        state,
+        &pane_name.clone(),
+        &command.clone(),
+        move |_, pane_id| {
+            let taken = SESSION.with(|s| {
+                s.borrow()
+                    .as_ref()
+                    .is_some_and(|session| session.get_pane(&dir).is_some())
+            });
+            if !is_project_dir || !taken {
+                update_session(|s| s.register_floating_pane(dir, pane_name, command, pane_id));
+            }
+        },
+    );
+}
+
+/// Builds the command that opens a shell in a directory.
+///
+/// # Arguments
+///
+/// * `shell` - The shell program
+/// * `dir` - The directory the shell starts in
+fn shell_command(shell: &str, dir: &Path) -> String {
+    format!(
+        "cd {} && exec {}",
+        util::shell::quote(&dir.to_string_lossy()),
+        shell
+    )
+}
+
+/// Builds the shell command that opens the git UI for a project.
+///
+/// # Arguments
+///
+/// * `git_ui` - The git UI configuration
+/// * `project_path` - The project directory, used as working directory
+/// * `selected` - The selected file or directory, passed relative to the
+///   project with the filter flag (ignored for the project root itself)
+fn git_ui_command(git_ui: &GitUiConfig, project_path: &Path, selected: Option<&Path>) -> String {
+    let mut command = format!(
+        "cd {} && {}",
+        util::shell::quote(&project_path.to_string_lossy()),
+        git_ui.command
+    );
+
+    let relative = selected
+        .and_then(|path| path.strip_prefix(project_path).ok())
+        .filter(|path| !path.as_os_str().is_empty());
+    if let (Some(flag), Some(relative)) = (&git_ui.filter_flag, relative) {
+        command.push_str(&format!(
+            " {} {}",
+            flag,
+            util::shell::quote(&relative.to_string_lossy())
+        ));
+    }
+
+    command
+}
+
+/// Shows the health check result of the selected project.
+///
+/// Failing checks open a popup with their output; other states are reported
+/// with a toast. Only available in the Projects view.
+fn show_health_details(state: &mut AppState, config: &Config) {
+    let View::Projects { workspace_id } = state.current_view() else {
+        return;
+    };
+    let Some(project) = config
+        .workspace
+        .get(workspace_id)
+        .and_then(|w| w.projects.get(state.selected_index()))
+    else {
+        return;
+    };
+
+    match health_status(&project.path) {
+        Some(HealthStatus::Failing(output)) => {
+            let title = format!("{} - health check", project.name);
+            state.show_popup(title, output);
+        }
+        Some(HealthStatus::Passing) => state.show_toast("Health check passing", ToastLevel::Info),
+        Some(HealthStatus::Pending) => state.show_toast("Health check running", ToastLevel::Info),
+        None => state.show_toast("No health check configured", ToastLevel::Info),
+    }
+}
+
+/// Returns the context-appropriate text to copy for the current selection.
+///
+/// - Workspaces view: the selected workspace name
+/// - Projects view: the selected project path
+/// - FileBrowser view: the selected file or directory path
+/// - Conversations view: the selected conversation's session id
+///
+/// # Arguments
+///
+/// * `state` - Reference to the application state
+/// * `config` - Reference to the application configuration
+///
+/// # Returns
+///
+/// The text to copy, or None if nothing is selected.
+fn yank_text(state: &AppState, config: &Config) -> Option<String> {
+    match state.current_view() {
+        View::Workspaces => {
+            let view = WorkspacesView::new(config, state.selected_index());
+            let workspace_id = *view.workspace_ids().get(state.selected_index())?;
+            config.workspace.get(workspace_id).map(|w| w.name.clone())
+        }
+        View::Projects { workspace_id } => config
+            .workspace
+            .get(workspace_id)
+            .and_then(|w| w.projects.get(state.selected_index()))
+            .map(|p| p.path.display().to_string()),
+        View::FileBrowser { workspace_id, .. } => {
+            let (_, project_index) = current_project_position(state, config)?;
+            let view = file_browser_view(state, config, workspace_id, Some(project_index));
+            view.selected_path().map(|p| p.display().to_string())
+        }
+        View::Conversations { .. } => {
+            let project = current_project(state, config)?;
+            with_conversations(&project.path, |conversations| {
+                conversations
+                    .get(state.selected_index())
+                    .map(|c| c.session_id.clone())
+            })
+        }
+    }
+}
+
+/// Copies text to the clipboard and reports the outcome as a toast.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `text` - The text to copy, or None if there is nothing to copy
+fn copy_with_toast(state: &mut AppState, text: Option<String>) {
+    let Some(text) = text else {
+        state.show_toast("Nothing to copy", ToastLevel::Error);
+        return;
+    };
+
+    match crate::util::copy_to_clipboard(&text) {
+        Ok(()) => state.show_toast(format!("Copied: {}", text), ToastLevel::Info),
+        Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+    }
+}
+
+/// Handles input events when the command bar is visible.
+///
+/// Processes horizontal navigation (left/right), command execution (enter),
+/// and closing the command bar (esc/back/colon).
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+fn handle_command_bar_input(state: &mut AppState, config: &Config, event: InputEvent) {
+    let commands = get_command_bar_items(state, config);
+    let max = commands.len();
+
+    match event {
+        InputEvent::Left => {
+            state.command_bar_select_prev(max);
+        }
+        InputEvent::Right => {
+            state.command_bar_select_next(max);
+        }
+        InputEvent::Enter => {
+            execute_command_bar_item(state, config);
+            state.hide_command_bar();
+        }
+        InputEvent::Back | InputEvent::ToggleCommandBar => {
+            state.hide_command_bar();
+        }
+        InputEvent::Quit => {
+            state.hide_command_bar();
+        }
+        // Ignore other events while command bar is visible
+        _ => {}
+    }
+}
+
+/// Returns the command bar items for the current view.
+///
+/// Resolves commands based on inheritance (global -> workspace -> project).
+///
+/// # Arguments
+///
+/// * `state` - Reference to the application state
+/// * `config` - Reference to the application configuration
+///
+/// # Returns
+///
+/// A vector of command bar items for the current context.
+fn get_command_bar_items(state: &AppState, config: &Config) -> Vec<crate::config::CommandBarItem> {
+    match current_project_position(state, config) {
+        Some((workspace_id, project_index)) => {
+            config.resolve_command_bar(workspace_id, project_index)
+        }
+        None => vec![],
+    }
+}
+
+/// Executes the currently selected command bar item.
+///
+/// Gets the selected command and runs it in a floating Zellij pane. Secret
+/// references are resolved first; failures are shown as error toasts. In a
+/// dry run, the command is previewed instead.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+fn execute_command_bar_item(state: &mut AppState, config: &Config) {
+    let index = state.command_bar_selected();
+    run_command_bar_item(state, config, index, &[]);
+}
+
+/// Runs a command bar item, first asking for the arguments of its
+/// `{prompt:Message}` placeholders that haven't been answered.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `index` - The index of the item in the command bar
+/// * `answers` - The answers to the item's messages given so far
+fn run_command_bar_item(state: &mut AppState, config: &Config, index: usize, answers: &[String]) {
+    let commands = get_command_bar_items(state, config);
+
+    if let Some(cmd) = commands.get(index) {
+        let messages = arguments::messages(&cmd.command);
+        if !state.dry_run() && answers.len() < messages.len() {
+            let message = messages[answers.len()].clone();
+            let target = ArgumentTarget::CommandBarItem(index);
+            ask_argument(state, target, message, answers.to_vec());
+            return;
+        }
+
+        let project = current_project(state, config);
+        let project_pane =
+            current_workspace_id(state)
+                .zip(project)
+                .map(|(workspace_id, project)| {
+                    project_pane_name(config, workspace_id, project, &cmd.name)
+                });
+        let pane_name = project_pane.unwrap_or_else(|| cmd.name.clone());
+        if state.dry_run() {
+            // Secret references are shown, not resolved
+            let cwd = project.map(|p| p.path.as_path());
+            preview_command_bar_item(state, &pane_name, &cmd.command, cmd.capture, cwd);
+            return;
+        }
+        let command = match command_with_secrets(&arguments::fill(&cmd.command, answers)) {
+            Ok(command) => command,
+            Err(e) => {
+                state.show_toast(e.to_string(), ToastLevel::Error);
+                return;
+            }
+        };
+
+        if cmd.capture {
+            let cwd = project
+                .map(|p| p.path.clone())
+                .or_else(|| std::env::current_dir().ok())
+                .unwrap_or_default();
+            // Replacing the previous capture kills its command if still running
+            let capture = CommandCapture::spawn(pane_name, cwd, command, Some(CAPTURE_TIMEOUT));
+            WORK.with(|w| w.borrow_mut().capture = Some(capture));
+            state.open_captured_output();
+            return;
+        }
+
+        open_floating_pane(state, &pane_name, &command, |_, _| {});
+    }
+}
+
+/// Handles input events when the captured output popup is open.
+///
+/// Up/down scroll one line, left/right scroll a page, and esc, enter or 'q'
+/// close the popup. A command still running keeps running in the background.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `event` - The input event to handle
+fn handle_captured_output_input(state: &mut AppState, event: InputEvent) {
+    /// Lines scrolled by left/right.
+    const PAGE: usize = 20;
+
+    let Some(scroll) = state.captured_output() else {
+        return;
+    };
+    let last = captured_line_count().saturating_sub(1);
+
+    match event {
+        InputEvent::Up => state.set_captured_output_scroll(scroll.saturating_sub(1)),
+        InputEvent::Down => state.set_captured_output_scroll((scroll + 1).min(last)),
+        InputEvent::Left => state.set_captured_output_scroll(scroll.saturating_sub(PAGE)),
+        InputEvent::Right => state.set_captured_output_scroll((scroll + PAGE).min(last)),
+        InputEvent::Back | InputEvent::Enter | InputEvent::Quit => {
+            // Dropping the capture kills its command if it is still running
+            WORK.with(|w| w.borrow_mut().capture.take());
+            state.close_captured_output();
+        }
+        _ => {}
+    }
+}
+
+/// Shows the blame of the file selected in the file browser.
+fn show_blame(state: &mut AppState, config: &Config) {
+    let View::FileBrowser { workspace_id, .. } = state.current_view() else {
+        return;
+    };
+    let project_index = current_project_position(state, config).map(|(_, index)| index);
+    let view = file_browser_view(state, config, workspace_id, project_index);
+    let Some(file_path) = view.selected_path().filter(|_| view.selected_is_file()) else {
+        state.show_toast("Select a file to blame", ToastLevel::Info);
+        return;
+    };
+
+    match blame::blame_file(&file_path) {
+        Ok(blame) => {
+            VIEWS.with(|v| v.borrow_mut().blame = Some(blame));
+            state.open_blame();
+        }
+        Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+    }
+}
+
+/// Handles input events when the blame popup is open.
+///
+/// Up/down scroll by a line, left/right by a page, and esc, enter or 'q'
+/// close the popup.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `event` - The input event to handle
+fn handle_blame_input(state: &mut AppState, event: InputEvent) {
+    /// Lines scrolled by left/right.
+    const PAGE: usize = 20;
+
+    let Some(scroll) = state.blame() else {
+        return;
+    };
+    let count = VIEWS.with(|v| {
+        v.borrow()
+            .blame
+            .as_ref()
+            .map_or(0, |blame| BlameView::new(blame, 0).lines().len())
+    });
+    let last = count.saturating_sub(1);
+
+    match event {
+        InputEvent::Up => state.set_blame_scroll(scroll.saturating_sub(1)),
+        InputEvent::Down => state.set_blame_scroll((scroll + 1).min(last)),
+        InputEvent::Left => state.set_blame_scroll(scroll.saturating_sub(PAGE)),
+        InputEvent::Right => state.set_blame_scroll((scroll + PAGE).min(last)),
+        InputEvent::Back | InputEvent::Enter | InputEvent::Quit | InputEvent::Blame => {
+            state.close_blame();
+            VIEWS.with(|v| v.borrow_mut().blame = None);
+        }
+        _ => {}
+    }
+}
+
+/// Returns the command to run for a command bar item.
+///
+/// Commands referencing secrets are resolved and written to a private
+/// script, so the pane runs `sh <script>` and secret values never appear in
+/// its command line.
+///
+/// # Errors
+///
+/// Returns an error if a secret cannot be read or the script cannot be written.
+fn command_with_secrets(command: &str) -> Result<String> {
+    if !secrets::has_references(command) {
+        return Ok(command.to_string());
+    }
+    let resolved = SecretStore::new().resolve(command)?;
+    let script = secrets::write_script(&resolved)?;
+    Ok(format!(
+        "sh {}",
+        util::shell::quote(&script.to_string_lossy())
+    ))
+}
+
+/// Returns the maximum index for the current view.
+///
+/// The maximum index is the count of items in the current list view,
+/// used for bounds checking during navigation.
+///
+/// # Arguments
+///
+/// * `state` - Reference to the application state
+/// * `config` - Reference to the application configuration
+///
+/// # Returns
+///
+/// The count of items in the current view.
+fn get_max_index(state: &AppState, config: &Config) -> usize {
+    match state.current_view() {
+        View::Workspaces => config.workspace.len(),
+        View::Projects { workspace_id } => config
+            .workspace
+            .get(workspace_id)
+            .map(|w| w.projects.len())
+            .unwrap_or(0),
+        View::FileBrowser { workspace_id, .. } => {
+            let project_index = current_project_position(state, config).map(|(_, index)| index);
+            let view = file_browser_view(state, config, workspace_id, project_index);
+            view.visible_count()
+        }
+        View::Conversations { .. } => current_project(state, config)
+            .map(|p| with_conversations(&p.path, |c| c.len()))
+            .unwrap_or(0),
+    }
+}
+
+/// Returns the mode shown by the status bar: command while the command bar
+/// has the keys, search while a search is typed or its results are shown.
+fn status_mode(state: &AppState) -> Mode {
+    let searching = matches!(
+        state.prompt().map(|p| &p.kind),
+        Some(PromptKind::SearchQuery)
+    );
+    if state.is_command_bar_visible() {
+        Mode::Command
+    } else if searching
+        || state.file_finder().is_some()
+        || state.command_palette().is_some()
+        || state.search_results().is_some()
+    {
+        Mode::Search
+    } else {
+        Mode::Normal
+    }
+}
+
+/// Handles the Enter key press based on the current view.
+///
+/// - Workspaces view: navigates to the selected workspace's projects
+/// - Projects view: navigates to the selected project's file browser, or
+///   runs its `default_action` when `global.project_enter` is "action";
+///   offers to clone it if its directory doesn't exist and it has a `repo`
+/// - FileBrowser view: opens the selected file or toggles the selected directory
+/// - Conversations view: resumes the selected conversation in a floating pane
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `alternate` - Alt-Enter: swaps browsing and the default action
+fn handle_enter(state: &mut AppState, config: &Config, alternate: bool) {
+    match state.current_view() {
+        View::Workspaces => {
+            let view = WorkspacesView::new(config, state.selected_index());
+            let workspace_ids = view.workspace_ids();
+            if let Some(workspace_id) = workspace_ids.get(state.selected_index()) {
+                state.navigate_to_workspace(workspace_id.to_string());
+            }
+        }
+        View::Projects { workspace_id } => {
+            let project_index = state.selected_index();
+            let project = config
+                .workspace
+                .get(workspace_id)
+                .and_then(|w| w.projects.get(project_index));
+            match project.and_then(clone_status) {
+                Some(CloneStatus::Cloning(_)) => {
+                    state.show_toast("Still cloning, please wait", ToastLevel::Info);
+                }
+                Some(CloneStatus::NotCloned) => {
+                    if let Some(project) = project {
+                        let label = format!(
+                            "Clone {} into {}?",
+                            project.repo.as_deref().unwrap_or_default(),
+                            project.path.display()
+                        );
+                        state.open_prompt(PromptKind::ConfirmClone { project_index }, label, "");
+                    }
+                }
+                None => {
+                    let run_default =
+                        (config.global.project_enter == ProjectEnter::Action) != alternate;
+                    match config.resolve_default_action(workspace_id, project_index) {
+                        Some(key) if run_default => {
+                            run_default_action(state, config, project_index, key)
+                        }
+                        None if run_default && alternate => state.show_toast(
+                            "No default_action is set for this project",
+                            ToastLevel::Error,
+                        ),
+                        _ => {
+                            if let Some(project_id) = config.project_id(workspace_id, project_index)
+                            {
+                                state.navigate_to_project(project_id);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        View::FileBrowser { workspace_id, .. } => {
+            let workspace_id = workspace_id.clone();
+            let project_index = current_project_position(state, config).map(|(_, index)| index);
+            let view = file_browser_view(state, config, &workspace_id, project_index);
+
+            if let (Some(bookmark), Some(project)) = (view.selected_bookmark(), view.project()) {
+                // Jump to the bookmarked directory in the tree
+                let bookmark = bookmark.to_path_buf();
+                if !bookmark.is_dir() {
+                    let message = format!("{} doesn't exist", bookmark.display());
+                    state.show_toast(message, ToastLevel::Error);
+                    return;
+                }
+                let root = project.path.clone();
+                state.expand_dir_path(&root, &bookmark);
+                let view = file_browser_view(state, config, &workspace_id, project_index);
+                if let Some(index) = view.index_of(&bookmark) {
+                    state.set_selected_index(index);
+                }
+            } else if view.selected_is_file() {
+                // Open the file in editor
+                let (Some(file_path), Some(project)) = (view.selected_path(), view.project())
+                else {
+                    return;
+                };
+                let from_recent = view.selected_is_recent();
+                if open_in_editor(state, config, &project.path, &file_path, None) {
+                    // Keep the opened file selected: it's now the first recent file,
+                    // and tree nodes may have shifted
+                    let view = file_browser_view(state, config, &workspace_id, project_index);
+                    let index = if from_recent {
+                        None
+                    } else {
+                        view.index_of(&file_path)
+                    };
+                    state.set_selected_index(index.unwrap_or(0));
+                }
+            } else if let Some(dir_path) = view.selected_path() {
+                // Toggle directory expand/collapse
+                state.toggle_dir_expanded(dir_path);
+            }
+        }
+        View::Conversations { workspace_id, .. } => {
+            let Some(project) = current_project(state, config) else {
+                return;
+            };
+            let Some(session_id) = with_conversations(&project.path, |conversations| {
+                conversations
+                    .get(state.selected_index())
+                    .map(|c| c.session_id.clone())
+            }) else {
+                return;
+            };
+
+            let command = project.wrap_env(&claude::resume_command(&project.path, &session_id));
+            let pane_name = project_pane_name(
+                config,
+                workspace_id,
+                project,
+                &format!("Resume {}", session_id.chars().take(8).collect::<String>()),
+            );
+            let project_path = project.path.clone();
+            open_floating_pane(state, &pane_name, &command, move |state, pane_id| {
+                present_pane(state, &project_path, pane_id.map(FocusTarget::Floating));
+                state.show_toast("Resuming conversation", ToastLevel::Info);
+            });
+        }
+    }
+}
+
+/// Runs the default action of a project of the Projects view.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `project_index` - The index of the selected project
+/// * `key` - The key of the default action
+fn run_default_action(state: &mut AppState, config: &Config, project_index: usize, key: &str) {
+    let View::Projects { workspace_id } = state.current_view() else {
+        return;
+    };
+    let defined = config
+        .resolve_actions(workspace_id, project_index)
+        .contains_key(key);
+    match key.chars().next() {
+        Some(key) if defined => handle_action(state, config, key, &[]),
+        _ => state.show_toast(
+            format!("The default action '{}' is not defined", key),
+            ToastLevel::Error,
+        ),
+    }
+}
+
+/// Handles action key presses by executing Zellij commands.
+///
+/// Resolves actions based on inheritance (global -> workspace -> project),
+/// finds the action matching the pressed key, and opens a new Zellij pane
+/// with the configured command. Tracks panes in session state.
+///
+/// Actions are only available in project-scoped views. In the
+/// Workspaces view, this function returns early without action.
+///
+/// Pre/post hooks are either chained into the pane command or, in background
+/// mode, executed in the project directory on background threads: the pane
+/// opens once the pre hook succeeded, then the post hook runs. Hook failures
+/// are reported as toasts. In a dry run, the action is previewed
+/// instead.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `key` - The action key that was pressed
+fn handle_action(state: &mut AppState, config: &Config, key: char, answers: &[String]) {
+    let Some((workspace_id, project_index)) = current_project_position(state, config) else {
+        return;
+    };
+
+    let actions = config.resolve_actions(workspace_id, project_index);
+    let key_str = key.to_string();
+
+    if let Some(action) = actions.get(&key_str) {
+        if let Some(project) = config
+            .workspace
+            .get(workspace_id)
+            .and_then(|ws| ws.projects.get(project_index))
+        {
+            if !action.panes.is_empty() {
+                let workspace_id = workspace_id.to_string();
+                open_pane_group(state, config, &workspace_id, project, action);
+                return;
+            }
+
+            // Arguments are asked for one at a time; a dry run shows the placeholders
+            let messages = arguments::messages(&action.command);
+            if !state.dry_run() && answers.len() < messages.len() {
+                let message = messages[answers.len()].clone();
+                ask_argument(
+                    state,
+                    ArgumentTarget::Action(key),
+                    message,
+                    answers.to_vec(),
+                );
+                return;
+            }
+
+            let project_path = project.path.clone();
+            let pane_name = project_pane_name(config, workspace_id, project, &action.name);
+            let resolved = project.action_dir(action).and_then(|dir| {
+                let command = arguments::fill(&action.command, answers);
+                let command = project.expand_bookmarks(&command)?;
+                let command = project.claude_command(action, &command)?;
+                Ok((dir, command))
+            });
+            let (action_dir, command) = match resolved {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    state.show_toast(e.to_string(), ToastLevel::Error);
+                    return;
+                }
+            };
+            if action.background {
+                let command =
+                    hooks::chain_hooks(action, &format!("{} {}", command, action_dir.display()));
+                let command = project.wrap_env(&command);
+                let logs_dir = logs::project_logs_dir(workspace_id, &project.name);
+                run_background_action(state, project, key, action, action_dir, command, logs_dir);
+                return;
+            }
+            let dry_run = state.dry_run();
+            let command =
+                match mcp::prepare_command(config, workspace_id, project_index, &command, !dry_run)
+                {
+                    Ok(command) => command,
+                    Err(e) => {
+                        state.show_toast(e.to_string(), ToastLevel::Error);
+                        return;
+                    }
+                };
+            let mut full_command =
+                hooks::wrap_command(action, &format!("{} {}", command, action_dir.display()));
+            if action.log {
+                let logs_dir = logs::project_logs_dir(workspace_id, &project.name);
+                full_command = logs::wrap_command(&full_command, &logs_dir, &action.name);
+            }
+            let full_command = project.wrap_env(&full_command);
+
+            if state.dry_run() {
+                preview_action(state, action, &action_dir, &pane_name, &full_command);
+                return;
+            }
+
+            let background_hooks = action.hook_mode == HookMode::Background;
+            let post = action.post.clone().filter(|_| background_hooks);
+            let pre = action.pre.clone().filter(|_| background_hooks);
+            let hooks_dir = action_dir.clone();
+            let open = move |state: &mut AppState| {
+                open_project_pane(state, &project_path, pane_name, full_command);
+                if let Some(post) = post {
+                    spawn_background_hook(action_dir, post, None);
+                }
+            };
+            // The pane waits for the pre hook without blocking the panel
+            match pre {
+                Some(pre) => spawn_background_hook(hooks_dir, pre, Some(Box::new(open))),
+                None => open(state),
+            }
+        }
+    }
+}
+
+/// Runs an action with `background` set, without a pane.
+///
+/// The command runs headlessly in the action's directory, with its hooks
+/// chained around it; `poll_background_actions` reports how it went. In a
+/// dry run, the command is previewed instead.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `project` - The project the action runs for
+/// * `key` - The key of the action
+/// * `action` - The action
+/// * `action_dir` - The directory the command runs in
+/// * `command` - The shell command, hooks included
+/// * `logs_dir` - The directory of the project's logs
+fn run_background_action(
+    state: &mut AppState,
+    project: &Project,
+    key: char,
+    action: &Action,
+    action_dir: PathBuf,
+    command: String,
+    logs_dir: PathBuf,
+) {
+    if state.dry_run() {
+        let lines = [
+            format!("Runs in the background in {}", action_dir.display()),
+            format!("Command: {}", command),
+            format!("Output: {}", logs_dir.display()),
+        ];
+        state.show_popup(format!("Dry run: {}", action.name), lines.join("\n"));
+        return;
+    }
+
+    let capture = CommandCapture::spawn(action.name.clone(), action_dir, command, None);
+    WORK.with(|w| {
+        w.borrow_mut().background.push(BackgroundAction {
+            project_path: project.path.clone(),
+            key: key.to_string(),
+            logs_dir,
+            capture,
+        })
+    });
+    state.show_toast(
+        format!("Running {} in the background", action.name),
+        ToastLevel::Info,
+    );
+}
+
+/// Opens a pane for a project and registers it in the session.
+///
+/// The first pane goes to the main pane, later ones to floating panes; either
+/// is presented to the web client. Failures are reported as toasts.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `project_path` - The directory the pane is registered for
+/// * `pane_name` - The name of the pane
+/// * `full_command` - The command the pane runs
+fn open_project_pane(
+    state: &mut AppState,
+    project_path: &Path,
+    pane_name: String,
+    full_command: String,
+) {
+    // Check if main pane is already used
+    let main_used = MAIN_PANE_USED.with(|m| *m.borrow());
+
+    if !main_used {
+        // First project goes to main pane, presented to the web client
+        match crate::zellij::run_in_main_pane(&pane_name, &full_command) {
+            Ok(()) => {
+                MAIN_PANE_USED.with(|m| *m.borrow_mut() = true);
+                update_session(|s| {
+                    s.register_pane(project_path.to_path_buf(), pane_name, full_command)
+                });
+                present_pane(state, project_path, Some(FocusTarget::Main));
+            }
+            Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+        }
+    } else {
+        // Subsequent projects go to floating panes, presented to the web client
+        let project_path = project_path.to_path_buf();
+        open_floating_pane(
+            state,
+            &pane_name.clone(),
+            &full_command.clone(),
+            move |state, pane_id| {
+                update_session(|s| {
+                    s.register_floating_pane(
+                        project_path.clone(),
+                        pane_name,
+                        full_command,
+                        pane_id.clone(),
+                    )
+                });
+                present_pane(state, &project_path, pane_id.map(FocusTarget::Floating));
+            },
+        );
+    }
+}
+
+/// Opens the panes of a composite action as a group.
+///
+/// Each pane runs its command in the project directory, or the action's
+/// `cwd`, and is named `{workspace}/{project} · {action}: {pane}`. The panes
+/// opened are registered together, so that closing or restarting the
+/// project's pane acts on all of them; if one fails to open, the ones before
+/// it are still registered. Hooks always run in the background, around the
+/// whole group. Focus returns to the panel's tab when a pane opened in a tab
+/// of its own.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `workspace_id` - The workspace containing the project
+/// * `project` - The project the panes run in
+/// * `action` - The composite action
+fn open_pane_group(
+    state: &mut AppState,
+    config: &Config,
+    workspace_id: &str,
+    project: &Project,
+    action: &Action,
+) {
+    let resolved = project
+        .action_dir(action)
+        .and_then(|dir| Ok((dir, group_panes(config, workspace_id, project, action)?)));
+    let (action_dir, panes) = match resolved {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            state.show_toast(e.to_string(), ToastLevel::Error);
+            return;
+        }
+    };
+
+    if state.dry_run() {
+        let mut lines: Vec<String> = panes
+            .iter()
+            .map(|pane| format!("Pane: {} ({:?})", pane.pane_name, pane.placement))
+            .collect();
+        lines.push(String::new());
+        lines.extend(dry_run_invocations(|| open_group_panes(panes).1));
+        state.show_popup(format!("Dry run: {}", action.name), lines.join("\n"));
+        return;
+    }
+
+    let open = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .is_some_and(|session| session.get_group(&project.path).is_some())
+    });
+    if open {
+        state.show_toast(
+            format!("A pane group is already open for {}", project.name),
+            ToastLevel::Error,
+        );
+        return;
+    }
+
+    let project_path = project.path.clone();
+    let action_name = action.name.clone();
+    let post = action.post.clone();
+    let hooks_dir = action_dir.clone();
+    let open = move |state: &mut AppState| {
+        let (opened, result) = open_group_panes(panes);
+        let count = opened.len();
+        if count > 0 {
+            update_session(|s| {
+                s.register_group(
+                    project_path,
+                    PaneGroup {
+                        action: action_name.clone(),
+                        panes: opened,
+                    },
+                )
+            });
+        }
+        match result {
+            Ok(()) => state.show_toast(
+                format!("Opened {} panes for {}", count, action_name),
+                ToastLevel::Info,
+            ),
+            Err(e) => {
+                state.show_toast(e.to_string(), ToastLevel::Error);
+                return;
+            }
+        }
+
+        if let Some(post) = post {
+            spawn_background_hook(action_dir, post, None);
+        }
+    };
+    match action.pre.clone() {
+        Some(pre) => spawn_background_hook(hooks_dir, pre, Some(Box::new(open))),
+        None => open(state),
+    }
+}
+
+/// Builds the panes of a composite action for a project, not yet opened.
+///
+/// # Arguments
+///
+/// * `config` - Reference to the application configuration
+/// * `workspace_id` - The workspace containing the project
+/// * `project` - The project the panes run in
+/// * `action` - The composite action
+///
+/// # Errors
+///
+/// Returns `ConfigError::UnknownBookmark` if the action's `cwd` or a pane
+/// command names a bookmark the project doesn't have.
+fn group_panes(
+    config: &Config,
+    workspace_id: &str,
+    project: &Project,
+    action: &Action,
+) -> std::result::Result<Vec<GroupPane>, ConfigError> {
+    let cwd = util::shell::quote(&project.action_dir(action)?.to_string_lossy());
+    let logs_dir = logs::project_logs_dir(workspace_id, &project.name);
+    action
+        .panes
+        .iter()
+        .map(|template| {
+            let mut command = project.expand_bookmarks(&template.command)?;
+            if action.log {
+                let name = format!("{} {}", action.name, template.name);
+                command = logs::wrap_command(&command, &logs_dir, &name);
+            }
+            Ok(GroupPane {
+                pane_name: project_pane_name(
+                    config,
+                    workspace_id,
+                    project,
+                    &format!("{}: {}", action.name, template.name),
+                ),
+                command: project.wrap_env(&format!("cd {} && {}", cwd, command)),
+                placement: template.placement,
+                pane_id: None,
+            })
+        })
+        .collect()
+}
+
+/// Opens the panes of a group in order, stopping at the first failure.
+///
+/// Returns to the first tab, where the panel is, once a pane was moved to
+/// a tab of its own.
+///
+/// # Arguments
+///
+/// * `panes` - The panes to open
+///
+/// # Returns
+///
+/// The panes opened, with their ids, and the error that stopped opening.
+fn open_group_panes(panes: Vec<GroupPane>) -> (Vec<GroupPane>, Result<()>) {
+    let mut opened = Vec::with_capacity(panes.len());
+    let mut result = Ok(());
+    for mut pane in panes {
+        let pane_id = match pane.placement {
+            PanePlacement::Floating => {
+                crate::zellij::run_in_floating_pane(&pane.pane_name, &pane.command)
+            }
+            PanePlacement::Down => {
+                crate::zellij::run_in_split_pane(&pane.pane_name, &pane.command, "down")
+            }
+            PanePlacement::Right => {
+                crate::zellij::run_in_split_pane(&pane.pane_name, &pane.command, "right")
+            }
+            PanePlacement::Tab => crate::zellij::run_in_new_tab(&pane.pane_name, &pane.command),
+        };
+        match pane_id {
+            Ok(pane_id) => {
+                pane.pane_id = pane_id;
+                opened.push(pane);
+            }
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    if opened.iter().any(|p| p.placement == PanePlacement::Tab) {
+        result = result.and(crate::zellij::go_to_tab(1));
+    }
+    (opened, result)
+}
+
+/// Stops the pane group of a project, optionally opening it again.
+///
+/// Every pane of the group is closed by id; the group is forgotten even if
+/// some fail to close, since they have most likely been closed from Zellij.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `project` - The project the group belongs to
+/// * `group` - The group to stop
+/// * `restart` - Whether to open the panes again after closing them
+fn stop_pane_group(state: &mut AppState, project: &Project, group: PaneGroup, restart: bool) {
+    update_session(|s| {
+        s.remove_group(&project.path);
+    });
+    let mut failure = None;
+    for pane in &group.panes {
+        let closed = match &pane.pane_id {
+            Some(pane_id) => crate::zellij::close_pane(pane_id),
+            None => Err(ZellijError::UnknownPaneId {
+                pane_name: pane.pane_name.clone(),
+            }
+            .into()),
+        };
+        if let Err(e) = closed {
+            failure.get_or_insert(e);
+        }
+    }
+    if let Some(e) = failure {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+        return;
+    }
+
+    if !restart {
+        state.show_toast(
+            format!("Closed {} panes of {}", group.panes.len(), group.action),
+            ToastLevel::Info,
+        );
+        return;
+    }
+
+    let (opened, result) = open_group_panes(group.panes);
+    if !opened.is_empty() {
+        update_session(|s| {
+            s.register_group(
+                project.path.clone(),
+                PaneGroup {
+                    action: group.action.clone(),
+                    panes: opened,
+                },
+            )
+        });
+    }
+    match result {
+        Ok(()) => state.show_toast(format!("Restarted {}", group.action), ToastLevel::Info),
+        Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+    }
+}
+
+/// Shows what running an action would do in a popup, without running it.
+///
+/// Lists the pane the command would open, the command with chained hooks,
+/// background hooks and the exact Zellij invocations. Nothing is started and
+/// the session is left unchanged.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `action` - The action that would run
+/// * `project_path` - The directory the action runs in: the project or its
+///   `cwd`
+/// * `pane_name` - The name the pane would get
+/// * `full_command` - The command the pane would run
+fn preview_action(
+    state: &mut AppState,
+    action: &crate::config::Action,
+    project_path: &Path,
+    pane_name: &str,
+    full_command: &str,
+) {
+    let main_used = MAIN_PANE_USED.with(|m| *m.borrow());
+    let placement = if main_used { "floating" } else { "main pane" };
+    let mut lines = vec![
+        format!("Pane: {} ({})", pane_name, placement),
+        format!("Command: {}", full_command),
+    ];
+    if action.hook_mode == HookMode::Background {
+        let hooks = [("Pre hook", &action.pre), ("Post hook", &action.post)];
+        for (label, hook) in hooks {
+            if let Some(hook) = hook {
+                lines.push(format!(
+                    "{}: {}  (in {})",
+                    label,
+                    hook,
+                    project_path.display()
+                ));
+            }
+        }
+    }
+    lines.push(String::new());
+    lines.extend(dry_run_invocations(|| {
+        if main_used {
+            crate::zellij::run_in_floating_pane(pane_name, full_command).map(|_| ())
+        } else {
+            crate::zellij::run_in_main_pane(pane_name, full_command)
+        }
+    }));
+    state.show_popup(format!("Dry run: {}", action.name), lines.join("\n"));
+}
+
+/// Shows what running a command bar item would do in a popup, without
+/// running it.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `pane_name` - The name the pane would get
+/// * `command` - The command, with secret references left unresolved
+/// * `capture` - Whether the output would be captured instead of shown in a pane
+/// * `cwd` - The project directory, if a project is selected
+fn preview_command_bar_item(
+    state: &mut AppState,
+    pane_name: &str,
+    command: &str,
+    capture: bool,
+    cwd: Option<&Path>,
+) {
+    let mut lines = vec![format!("Command: {}", command), String::new()];
+    if capture {
+        let dir = cwd
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default();
+        lines.push(format!(
+            "sh -c {}  (in {})",
+            util::shell::quote(command),
+            dir.display()
+        ));
+        lines.push("Output is captured into a popup".to_string());
+    } else {
+        lines.extend(dry_run_invocations(|| {
+            crate::zellij::run_in_floating_pane(pane_name, command).map(|_| ())
+        }));
+    }
+    state.show_popup(format!("Dry run: {}", pane_name), lines.join("\n"));
+}
+
+/// Opens a floating pane in the background, so the panel keeps drawing
+/// while Zellij starts it.
+///
+/// Panes open in the order they were asked for. Once the pane is open,
+/// `on_opened` runs with its id; a failure is shown as an error toast.
+/// Under a runner installed with `with_runner`, e.g. in tests, the pane is
+/// opened right away, since the runner only applies to this thread.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `pane_name` - The name shown in the pane frame
+/// * `command` - The shell command to run
+/// * `on_opened` - Runs with the id of the pane once it's open
+fn open_floating_pane(
+    state: &mut AppState,
+    pane_name: &str,
+    command: &str,
+    on_opened: impl FnOnce(&mut AppState, Option<String>) + 'static,
+) {
+    if crate::zellij::has_runner() {
+        match crate::zellij::run_in_floating_pane(pane_name, command) {
+            Ok(pane_id) => on_opened(state, pane_id),
+            Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+        }
+        return;
+    }
+
+    let (pane_name, command) = (pane_name.to_string(), command.to_string());
+    let id = PANE_JOBS.with(|j| {
+        j.borrow_mut()
+            .executor
+            .get_or_insert_with(PaneExecutor::spawn)
+            .submit(move || crate::zellij::run_in_floating_pane(&pane_name, &command))
+    });
+    PANE_JOBS.with(|j| j.borrow_mut().callbacks.insert(id, Box::new(on_opened)));
+}
+
+/// Runs the callbacks of the floating panes opened in the background since
+/// the last call, and reports the ones that failed to open.
+///
+/// Returns true if a pane operation finished.
+fn poll_pane_operations(state: &mut AppState) -> bool {
+    let finished = PANE_JOBS.with(|j| j.borrow_mut().executor.as_mut().map(PaneExecutor::poll));
+    let Some(finished) = finished.filter(|finished| !finished.is_empty()) else {
+        return false;
+    };
+    for (id, outcome) in finished {
+        let callback = PANE_JOBS.with(|j| j.borrow_mut().callbacks.remove(&id));
+        match outcome {
+            Ok(pane_id) => {
+                if let Some(callback) = callback {
+                    callback(state, pane_id);
+                }
+            }
+            Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+        }
+    }
+    true
+}
+
+/// Runs a closure recording the Zellij invocations it makes instead of
+/// running them.
+///
+/// # Returns
+///
+/// The recorded command lines, followed by the error the closure would have
+/// reported, if any.
+fn dry_run_invocations(f: impl FnOnce() -> Result<()>) -> Vec<String> {
+    let runner = Rc::new(DryRunRunner::new());
+    let result = with_runner(runner.clone(), f);
+    let mut lines = runner.invocations();
+    if lines.is_empty() {
+        lines.push("No Zellij command would run".to_string());
+    }
+    if let Err(e) = result {
+        lines.push(format!("Would fail: {}", e));
+    }
+    lines
+}
+
+/// Builds the name of a pane opened for a project.
+///
+/// # Arguments
+///
+/// * `config` - Reference to the application configuration
+/// * `workspace_id` - The workspace containing the project
+/// * `project` - The project the pane runs in
+/// * `action` - What runs in the pane
+///
+/// # Returns
+///
+/// The pane name, as `{workspace}/{project} · {action}`.
+fn project_pane_name(
+    config: &Config,
+    workspace_id: &str,
+    project: &Project,
+    action: &str,
+) -> String {
+    let workspace = config
+        .workspace
+        .get(workspace_id)
+        .map_or(workspace_id, |w| w.name.as_str());
+    Session::pane_name(workspace, &project.name, action)
+}
+
+/// Fullscreens a pane for the web client, restoring the previously presented one.
+///
+/// If the pane can't be addressed, the previous pane is still restored so
+/// that it doesn't hide the new one. Failures are reported as toasts. A
+/// Claude pane waiting for input no longer needs attention once presented.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `project_path` - The project the pane belongs to
+/// * `target` - The pane to present, None if Zellij didn't report its id
+fn present_pane(state: &mut AppState, project_path: &Path, target: Option<FocusTarget>) {
+    if MONITORS.with(|m| m.borrow_mut().idle.acknowledge(project_path)) {
+        sync_attention();
+    }
+    let presented = WEB_FOCUS.with(|f| {
+        let mut manager = f.borrow_mut();
+        match target {
+            Some(target) => manager.present(project_path, target),
+            None => manager.release(),
+        }
+    });
+    if let Err(e) = presented {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+    }
+}
+
+/// Presents the next project pane to the web client.
+///
+/// Cycles through the projects with a registered pane in configuration
+/// order (workspaces in display order), starting after the presented one.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+fn cycle_web_focus(state: &mut AppState, config: &Config) {
+    let workspace_ids = config.workspace_ids();
+    let panes: Vec<(PathBuf, PaneInfo)> = SESSION.with(|s| {
+        let session = s.borrow();
+        let Some(session) = session.as_ref() else {
+            return Vec::new();
+        };
+        workspace_ids
+            .iter()
+            .flat_map(|id| &config.workspace[*id].projects)
+            .filter_map(|project| {
+                session
+                    .get_pane(&project.path)
+                    .map(|pane| (project.path.clone(), pane.clone()))
+            })
+            .collect()
+    });
+    let Some(next) = next_web_focus(
+        &panes,
+        WEB_FOCUS
+            .with(|f| f.borrow().presented().map(Path::to_path_buf))
+            .as_deref(),
+    ) else {
+        state.show_toast("No project panes to present", ToastLevel::Error);
+        return;
+    };
+
+    let (path, pane) = &panes[next];
+    match web_focus_target(pane) {
+        Ok(target) => present_pane(state, path, Some(target)),
+        Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+    }
+}
+
+/// Returns how a registered pane is presented to the web client.
+///
+/// # Errors
+///
+/// Returns `ZellijError::UnknownPaneId` for a floating pane whose id Zellij
+/// didn't report.
+fn web_focus_target(pane: &PaneInfo) -> std::result::Result<FocusTarget, ZellijError> {
+    if !pane.floating {
+        return Ok(FocusTarget::Main);
+    }
+    pane.pane_id
+        .clone()
+        .map(FocusTarget::Floating)
+        .ok_or_else(|| ZellijError::UnknownPaneId {
+            pane_name: pane.pane_name.clone(),
+        })
+}
+
+/// Presents the pane picked from the web client's pane picker, if any.
+///
+/// Returns true if a pick was handled.
+fn poll_web_pick(state: &mut AppState) -> bool {
+    let Some(name) = SESSION.with(|s| s.borrow().as_ref().map(|s| s.zellij_session.clone())) else {
+        return false;
+    };
+    let Some(project_path) = crate::zellij::take_web_pick(&name) else {
+        return false;
+    };
+    let pane = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .and_then(|session| session.get_pane(&project_path).cloned())
+    });
+    match pane.map(|pane| web_focus_target(&pane)) {
+        Some(Ok(target)) => present_pane(state, &project_path, Some(target)),
+        Some(Err(e)) => state.show_toast(e.to_string(), ToastLevel::Error),
+        None => state.show_toast(
+            format!("No pane is open for {}", project_path.display()),
+            ToastLevel::Error,
+        ),
+    }
+    true
+}
+
+/// Toggles fullscreen of the main pane.
+///
+/// Goes through the web focus manager, so a presented floating pane is
+/// restored first and the main pane counts as presented while zoomed.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+fn zoom_main_pane(state: &mut AppState) {
+    let main_project = SESSION.with(|s| {
+        s.borrow().as_ref().and_then(|session| {
+            session
+                .panes
+                .iter()
+                .find(|(_, pane)| !pane.floating)
+                .map(|(path, _)| path.clone())
+        })
+    });
+    let project_path = main_project.unwrap_or_default();
+    match WEB_FOCUS.with(|f| f.borrow_mut().toggle(&project_path, FocusTarget::Main)) {
+        Ok(true) => state.show_toast("Zoomed the main pane", ToastLevel::Info),
+        Ok(false) => state.show_toast("Restored the main pane", ToastLevel::Info),
+        Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+    }
+}
+
+/// Moves the selected project's floating pane into the tiled layout, or
+/// back to floating. Only available in the Projects view.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+fn toggle_embed_project_pane(state: &mut AppState, config: &Config) {
+    let View::Projects { workspace_id } = state.current_view() else {
+        return;
+    };
+    let Some(project) = config
+        .workspace
+        .get(workspace_id)
+        .and_then(|w| w.projects.get(state.selected_index()))
+    else {
+        return;
+    };
+    let pane = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .and_then(|session| session.get_pane(&project.path).cloned())
+    });
+    let Some(pane) = pane else {
+        state.show_toast(
+            format!("{} has no open pane", project.name),
+            ToastLevel::Error,
+        );
+        return;
+    };
+    if !pane.floating {
+        state.show_toast("The main pane is already embedded", ToastLevel::Error);
+        return;
+    }
+    let Some(pane_id) = pane.pane_id.as_deref() else {
+        state.show_toast(
+            ZellijError::UnknownPaneId {
+                pane_name: pane.pane_name.clone(),
+            }
+            .to_string(),
+            ToastLevel::Error,
+        );
+        return;
+    };
+
+    if let Err(e) = crate::zellij::toggle_pane_embedded(pane_id) {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+        return;
+    }
+    let embedded = !pane.embedded;
+    update_session(|s| {
+        if let Some(pane) = s.panes.get_mut(&project.path) {
+            pane.embedded = embedded;
+        }
+    });
+    let message = if embedded {
+        format!("Embedded the pane of {}", project.name)
+    } else {
+        format!("Floated the pane of {}", project.name)
+    };
+    state.show_toast(message, ToastLevel::Info);
+}
+
+/// Moves every project pane opened from the panel, except the main pane,
+/// to a tab of its own named after the pane, then returns to the panel's
+/// tab.
+///
+/// Panes are taken in the order of the workspaces and their projects.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+fn arrange_panes_in_tabs(state: &mut AppState, config: &Config) {
+    let workspace_ids = config.workspace_ids();
+    let panes: Vec<(PathBuf, PaneInfo)> = SESSION.with(|s| {
+        let session = s.borrow();
+        let Some(session) = session.as_ref() else {
+            return Vec::new();
+        };
+        workspace_ids
+            .iter()
+            .flat_map(|id| &config.workspace[*id].projects)
+            .filter_map(|project| {
+                session
+                    .get_pane(&project.path)
+                    .filter(|pane| pane.floating && pane.pane_id.is_some())
+                    .map(|pane| (project.path.clone(), pane.clone()))
+            })
+            .collect()
+    });
+    if panes.is_empty() {
+        state.show_toast("No floating project panes to arrange", ToastLevel::Error);
+        return;
+    }
+
+    // Fullscreen doesn't follow a pane to another tab
+    if let Err(e) = WEB_FOCUS.with(|f| f.borrow_mut().release()) {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+        return;
+    }
+    let mut moved = 0;
+    let mut failure = None;
+    for (path, pane) in &panes {
+        let Some(pane_id) = pane.pane_id.as_deref() else {
+            continue;
+        };
+        let result = if pane.embedded {
+            Ok(())
+        } else {
+            crate::zellij::toggle_pane_embedded(pane_id)
+        }
+        .and_then(|()| crate::zellij::move_pane_to_new_tab(pane_id, &pane.pane_name));
+        match result {
+            Ok(()) => {
+                moved += 1;
+                update_session(|s| {
+                    if let Some(pane) = s.panes.get_mut(path) {
+                        pane.embedded = true;
+                    }
+                });
+            }
+            Err(e) => {
+                failure = Some(e);
+                break;
+            }
+        }
+    }
+    let returned = crate::zellij::go_to_tab(1);
+
+    match failure.or(returned.err()) {
+        Some(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+        None => state.show_toast(
+            format!("Moved {} panes to their own tabs", moved),
+            ToastLevel::Info,
+        ),
+    }
+}
+
+/// Returns the index of the pane to present after the presented project.
+///
+/// # Arguments
+///
+/// * `panes` - The project panes in cycling order
+/// * `presented` - The path of the presented project, if any
+///
+/// # Returns
+///
+/// The index of the next pane, wrapping around, or None if there are no panes.
+fn next_web_focus(panes: &[(PathBuf, PaneInfo)], presented: Option<&Path>) -> Option<usize> {
+    if panes.is_empty() {
+        return None;
+    }
+    let current = presented.and_then(|p| panes.iter().position(|(path, _)| path == p));
+    Some(current.map_or(0, |i| (i + 1) % panes.len()))
+}
+
+/// Publishes the workspace and project shown in the panel for the top bar.
+///
+/// The session is only saved when the focus changed.
+fn sync_session_focus(state: &AppState, config: &Config) {
+    let focus = current_workspace_id(state).map(|workspace_id| SessionFocus {
+        workspace_id: workspace_id.to_string(),
+        project_path: current_project(state, config).map(|p| p.path.clone()),
+    });
+    let changed = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .is_some_and(|session| session.focus != focus)
+    });
+    if changed {
+        update_session(|session| session.focus = focus);
+    }
+}
+
+/// Saves the positions and expanded directories of the panel to the
+/// session state when they changed.
+fn sync_panel_state(state: &AppState) {
+    let panel = state.panel_state();
+    let changed = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .is_some_and(|session| session.panel != panel)
+    });
+    if changed {
+        update_session(|session| session.panel = panel);
+    }
+}
+
+/// Keeps the file browser selection on a node rather than an index.
+///
+/// When following, the selection moves to the recorded node, or to its
+/// closest visible ancestor if it was collapsed, hidden or deleted. The node
+/// at the resulting selection is then recorded.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `follow` - Whether the selection follows the recorded node; false after
+///   the user moved the selection
+fn sync_selected_node(state: &mut AppState, config: &Config, follow: bool) {
+    let View::FileBrowser { workspace_id, .. } = state.current_view().clone() else {
+        state.set_selected_node(None);
+        return;
+    };
+    let project_index = current_project_position(state, config).map(|(_, index)| index);
+    let view = file_browser_view(state, config, &workspace_id, project_index);
+
+    let followed = state.selected_node().filter(|_| follow).and_then(|node| {
+        node.ancestors()
+            .find_map(|path| view.index_of(path).map(|index| (index, path.to_path_buf())))
+    });
+    match followed {
+        Some((index, path)) => {
+            state.set_selected_index(index);
+            state.set_selected_node(Some(path));
+        }
+        None => state.set_selected_node(view.selected_node()),
+    }
+}
+
+/// Moves the selection onto the last item of the current view when a
+/// restored position is past the end, e.g. after projects were removed.
+fn clamp_selection(state: &mut AppState, config: &Config) {
+    let count = get_max_index(state, config);
+    if state.selected_index() >= count {
+        state.set_selected_index(count.saturating_sub(1));
+    }
+}
+
+/// Applies a change to the session state and saves it.
+///
+/// # Arguments
+///
+/// * `f` - Closure modifying the session
+fn update_session(f: impl FnOnce(&mut Session)) {
+    SESSION.with(|s| {
+        if let Some(session) = s.borrow_mut().as_mut() {
+            f(session);
+            let _ = session.save();
+        }
+    });
+    record_session_saved();
+}
+
+/// Remembers when the session state file was last written by this panel.
+fn record_session_saved() {
+    let modified = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .and_then(|session| Session::modified(&session.zellij_session))
+    });
+    SESSION_FILE.with(|f| f.borrow_mut().saved = modified);
+}
+
+/// Reloads the session state when another process wrote it, e.g.
+/// `gz-claude session import`.
+///
+/// Returns true if the session was reloaded.
+fn poll_session_file(config: &Config) -> bool {
+    let Some(name) = SESSION.with(|s| s.borrow().as_ref().map(|s| s.zellij_session.clone())) else {
+        return false;
+    };
+    let modified = Session::modified(&name);
+    if SESSION_FILE.with(|f| f.borrow().saved == modified) {
+        return false;
+    }
+    SESSION_FILE.with(|f| f.borrow_mut().saved = modified);
+    let Some(session) = Session::load(&name) else {
+        return false;
+    };
+
+    let main_used =
+        !config.layout.main_runs_shell() || session.panes.values().any(|pane| !pane.floating);
+    MAIN_PANE_USED.with(|m| *m.borrow_mut() = main_used);
+    SESSION.with(|s| *s.borrow_mut() = Some(session));
+    true
+}
+
+/// Stops the registered pane of the selected project, optionally relaunching it.
+///
+/// A pane group opened by a composite action is stopped as a whole, before
+/// any single pane of the project.
+/// Floating panes are closed by id; the main pane is part of the layout, so
+/// its command is interrupted instead. The registration is dropped even if
+/// closing fails, since the pane has most likely been closed from Zellij.
+/// On restart, the recorded command is launched again in the same kind of
+/// pane and registered anew. Only available in the Projects view.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `restart` - Whether to relaunch the pane's command after stopping it
+fn stop_project_pane(state: &mut AppState, config: &Config, restart: bool) {
+    let View::Projects { workspace_id } = state.current_view() else {
+        return;
+    };
+    let Some(project) = config
+        .workspace
+        .get(workspace_id)
+        .and_then(|w| w.projects.get(state.selected_index()))
+    else {
+        return;
+    };
+
+    let group = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .and_then(|session| session.get_group(&project.path).cloned())
+    });
+    if let Some(group) = group {
+        stop_pane_group(state, project, group, restart);
+        return;
+    }
+
+    let pane = SESSION.with(|s| {
+        s.borrow()
+            .as_ref()
+            .and_then(|session| session.get_pane(&project.path).cloned())
+    });
+    let Some(pane) = pane else {
+        state.show_toast(
+            format!("No pane running for {}", project.name),
+            ToastLevel::Error,
+        );
+        return;
+    };
+
+    let stopped = if pane.floating {
+        match &pane.pane_id {
+            Some(pane_id) => crate::zellij::close_pane(pane_id),
+            None => Err(ZellijError::UnknownPaneId {
+                pane_name: pane.pane_name.clone(),
+            }
+            .into()),
+        }
+    } else {
+        crate::zellij::interrupt_main_pane()
+    };
+    update_session(|s| s.remove_pane(&project.path));
+    WEB_FOCUS.with(|f| f.borrow_mut().forget(&project.path));
+    if !pane.floating {
+        MAIN_PANE_USED.with(|m| *m.borrow_mut() = false);
+    }
+    if let Err(e) = stopped {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+        return;
+    }
+
+    if !restart {
+        state.show_toast(
+            format!("Closed pane for {}", project.name),
+            ToastLevel::Info,
+        );
+        return;
+    }
+
+    // Claude reads the MCP servers toggled since the launch
+    if let Err(e) = mcp::refresh_config(config, workspace_id, state.selected_index(), &pane.command)
+    {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+        return;
+    }
+    let relaunched = if pane.floating {
+        crate::zellij::run_in_floating_pane(&pane.pane_name, &pane.command).map(|id| {
+            update_session(|s| {
+                s.register_floating_pane(
+                    project.path.clone(),
+                    pane.pane_name.clone(),
+                    pane.command.clone(),
+                    id,
+                )
+            })
+        })
+    } else {
+        crate::zellij::run_in_main_pane(&pane.pane_name, &pane.command).map(|()| {
+            MAIN_PANE_USED.with(|m| *m.borrow_mut() = true);
+            update_session(|s| {
+                s.register_pane(
+                    project.path.clone(),
+                    pane.pane_name.clone(),
+                    pane.command.clone(),
+                )
+            });
+        })
+    };
+    match relaunched {
+        Ok(()) => state.show_toast(format!("Restarted {}", project.name), ToastLevel::Info),
+        Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GlobalConfig, ProjectEnv, WebClientConfig, Workspace};
+    use indexmap::IndexMap;
+    use std::collections::HashMap;
+
+    pub(super) fn create_test_config() -> Config {
+        let mut workspaces = IndexMap::new();
+        workspaces.insert(
+            "workspace-a".to_string(),
+            Workspace {
+                name: "Workspace A".to_string(),
+                order: None,
+                description: None,
+                actions: HashMap::new(),
+                command_bar: vec![],
+                projects: vec![],
+                working_set: Vec::new(),
+                discover: None,
+                default_action: None,
+                mcp: Default::default(),
+            },
+        );
+        workspaces.insert(
+            "workspace-b".to_string(),
+            Workspace {
+                name: "Workspace B".to_string(),
+                order: None,
+                description: None,
+                actions: HashMap::new(),
+                command_bar: vec![],
+                projects: vec![],
+                working_set: Vec::new(),
+                discover: None,
+                default_action: None,
+                mcp: Default::default(),
+            },
+        );
+
+        Config {
+            global: GlobalConfig {
+                editor: "$EDITOR".to_string(),
+                shell: "$SHELL".to_string(),
+                git_info_level: Default::default(),
+                file_browser: Default::default(),
+                key_conflicts: Default::default(),
+                git_ui: Default::default(),
+                git: Default::default(),
+                remote: None,
+                zellij: Default::default(),
+                navigation: Default::default(),
+                claude_idle: Default::default(),
+                task: Default::default(),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                project_enter: Default::default(),
+                mcp: Default::default(),
+            },
+            web_client: WebClientConfig::default(),
+            top_bar: Default::default(),
+            layout: Default::default(),
+            notifications: Default::default(),
+            ui: Default::default(),
+            state_endpoint: Default::default(),
+            workspace: workspaces,
+        }
+    }
+
+    #[test]
+    fn when_using_vim_navigation_should_apply_counts_and_jumps() {
+        let mut config = create_test_config();
+        config.global.navigation = NavigationMode::Vim;
+        let projects = &mut config.workspace.get_mut("workspace-a").unwrap().projects;
+        for index in 0..12 {
+            projects.push(crate::config::Project {
+                name: format!("p{}", index),
+                path: PathBuf::from(format!("/tmp/p{}", index)),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        }
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::SwitchWorkspace(4));
+        assert_eq!(state.pending_keys(), "5");
+        handle_input(&mut state, &config, InputEvent::Down);
+        assert_eq!(state.selected_index(), 5);
+        assert!(matches!(
+            state.current_view(),
+            View::Projects { workspace_id } if workspace_id == "workspace-a"
+        ));
+
+        handle_input(&mut state, &config, InputEvent::GitUi);
+        assert_eq!(state.selected_index(), 11);
+
+        handle_input(&mut state, &config, InputEvent::Action('g'));
+        handle_input(&mut state, &config, InputEvent::Action('g'));
+        assert_eq!(state.selected_index(), 0);
+    }
+
+    #[test]
+    fn when_handling_up_input_should_decrease_index() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.set_selected_index(2);
+
+        handle_input(&mut state, &config, InputEvent::Up);
+
+        assert_eq!(state.selected_index(), 1);
+    }
+
+    #[test]
+    fn when_handling_up_at_zero_should_stay_at_zero() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.set_selected_index(0);
+
+        handle_input(&mut state, &config, InputEvent::Up);
+
+        assert_eq!(state.selected_index(), 0);
+    }
+
+    #[test]
+    fn when_handling_quit_at_workspaces_should_set_should_quit() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+
+        handle_input(&mut state, &config, InputEvent::Quit);
+
+        assert!(state.should_quit());
+    }
+
+    #[test]
+    fn when_handling_quit_at_projects_should_navigate_back() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::Quit);
+
+        assert!(!state.should_quit());
+        assert_eq!(*state.current_view(), View::Workspaces);
+    }
+
+    #[test]
+    fn when_handling_action_at_workspaces_should_do_nothing() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+
+        // Verify we're at Workspaces view
+        assert_eq!(*state.current_view(), View::Workspaces);
+
+        // Call handle_action directly - should return early without panicking
+        handle_action(&mut state, &config, 'c', &[]);
+
+        // State should remain unchanged
+        assert_eq!(*state.current_view(), View::Workspaces);
+        assert_eq!(state.selected_index(), 0);
+        assert!(!state.should_quit());
+    }
+
+    #[test]
+    fn when_yanking_at_workspaces_should_return_workspace_name() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.set_selected_index(1);
+
+        assert_eq!(yank_text(&state, &config), Some("Workspace B".to_string()));
+    }
+
+    #[test]
+    fn when_yanking_at_empty_projects_should_return_none() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        assert_eq!(yank_text(&state, &config), None);
+    }
+
+    #[test]
+    fn when_toggling_command_bar_at_workspaces_should_not_open() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+
+        handle_input(&mut state, &config, InputEvent::ToggleCommandBar);
+
+        assert!(!state.is_command_bar_visible());
+    }
+
+    #[test]
+    fn when_toggling_command_bar_at_projects_should_open() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::ToggleCommandBar);
+
+        assert!(state.is_command_bar_visible());
+    }
+
+    #[test]
+    fn when_pressing_esc_with_command_bar_open_should_close() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.toggle_command_bar();
+        assert!(state.is_command_bar_visible());
+
+        handle_input(&mut state, &config, InputEvent::Back);
+
+        assert!(!state.is_command_bar_visible());
+    }
+
+    #[test]
+    fn when_navigating_command_bar_should_change_selection() {
+        let config = create_test_config_with_command_bar();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.toggle_command_bar();
+
+        handle_input(&mut state, &config, InputEvent::Right);
+
+        assert_eq!(state.command_bar_selected(), 1);
+    }
+
+    #[test]
+    fn when_executing_captured_command_should_show_its_output() {
+        let mut config = create_test_config_with_command_bar();
+        // Items are sorted by key, so "d" (Deploy) is selected first
+        config.global.command_bar[1].command = "printf 'one\\ntwo\\n'".to_string();
+        config.global.command_bar[1].capture = true;
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.toggle_command_bar();
+
+        handle_input(&mut state, &config, InputEvent::Enter);
+
+        assert!(!state.is_command_bar_visible());
+        assert_eq!(state.captured_output(), Some(0));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !poll_captured_output(&config) {
+            assert!(Instant::now() < deadline, "captured command did not finish");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(captured_line_count(), 2);
+
+        handle_input(&mut state, &config, InputEvent::Down);
+        handle_input(&mut state, &config, InputEvent::Down);
+        assert_eq!(state.captured_output(), Some(1));
+
+        handle_input(&mut state, &config, InputEvent::Back);
+        assert!(state.captured_output().is_none());
+    }
+
+    fn create_test_config_with_command_bar() -> Config {
+        use crate::config::CommandBarItem;
+
+        let mut workspaces = IndexMap::new();
+        workspaces.insert(
+            "workspace-a".to_string(),
+            Workspace {
+                name: "Workspace A".to_string(),
+                order: None,
+                description: None,
+                actions: HashMap::new(),
+                command_bar: vec![],
+                projects: vec![],
+                working_set: Vec::new(),
+                discover: None,
+                default_action: None,
+                mcp: Default::default(),
+            },
+        );
+
+        Config {
+            global: GlobalConfig {
+                editor: "$EDITOR".to_string(),
+                shell: "$SHELL".to_string(),
+                git_info_level: Default::default(),
+                file_browser: Default::default(),
+                key_conflicts: Default::default(),
+                git_ui: Default::default(),
+                git: Default::default(),
+                remote: None,
+                zellij: Default::default(),
+                navigation: Default::default(),
+                claude_idle: Default::default(),
+                task: Default::default(),
+                actions: HashMap::new(),
+                command_bar: vec![
+                    CommandBarItem {
+                        key: "p".to_string(),
+                        name: "Pipeline".to_string(),
+                        command: "echo pipeline".to_string(),
+                        icon: Some("🚀".to_string()),
+                        capture: false,
+                    },
+                    CommandBarItem {
+                        key: "d".to_string(),
+                        name: "Deploy".to_string(),
+                        command: "echo deploy".to_string(),
+                        icon: None,
+                        capture: false,
+                    },
+                ],
+                project_enter: Default::default(),
+                mcp: Default::default(),
+            },
+            web_client: WebClientConfig::default(),
+            top_bar: Default::default(),
+            layout: Default::default(),
+            notifications: Default::default(),
+            ui: Default::default(),
+            state_endpoint: Default::default(),
+            workspace: workspaces,
+        }
+    }
+
+    #[test]
+    fn when_dry_running_command_bar_item_should_preview_invocation() {
+        let config = create_test_config_with_command_bar();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        handle_input(&mut state, &config, InputEvent::ToggleDryRun);
+        assert!(state.dry_run());
+
+        execute_command_bar_item(&mut state, &config);
+
+        let popup = state.popup().expect("preview popup");
+        assert_eq!(popup.title, "Dry run: Deploy");
+        assert!(
+            popup.body.contains("Command: echo deploy"),
+            "{}",
+            popup.body
+        );
+        assert!(popup.body.contains("zellij "), "{}", popup.body);
+    }
+
+    #[test]
+    fn when_dry_running_composite_action_should_preview_each_pane() {
+        use crate::config::PaneTemplate;
+
+        let mut config = create_test_config();
+        config.global.actions.insert(
+            "v".to_string(),
+            Action {
+                name: "dev".to_string(),
+                panes: vec![
+                    PaneTemplate {
+                        name: "Watch".to_string(),
+                        command: "cargo watch".to_string(),
+                        placement: PanePlacement::Down,
+                    },
+                    PaneTemplate {
+                        name: "Git".to_string(),
+                        command: "lazygit".to_string(),
+                        placement: PanePlacement::Tab,
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .push(crate::config::Project {
+                name: "api".to_string(),
+                path: PathBuf::from("/tmp/api"),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_dry_run(true);
+
+        handle_input(&mut state, &config, InputEvent::Action('v'));
+
+        let popup = state.popup().expect("preview popup");
+        assert_eq!(popup.title, "Dry run: dev");
+        assert!(
+            popup
+                .body
+                .contains("Pane: Workspace A/api · dev: Watch (Down)"),
+            "{}",
+            popup.body
+        );
+        assert!(popup.body.contains("--direction down"), "{}", popup.body);
+        assert!(popup.body.contains("break-pane"), "{}", popup.body);
+        assert!(popup.body.contains("go-to-tab 1"), "{}", popup.body);
+    }
+
+    #[test]
+    fn when_action_runs_in_bookmark_should_pass_bookmark_dir() {
+        let mut config = create_test_config();
+        config.global.actions.insert(
+            "t".to_string(),
+            Action {
+                name: "Test".to_string(),
+                command: "npm test --prefix {bookmark:web}".to_string(),
+                cwd: Some("{bookmark:api}".to_string()),
+                ..Default::default()
+            },
+        );
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .push(crate::config::Project {
+                name: "mono".to_string(),
+                path: PathBuf::from("/work/mono"),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: [
+                    ("api".to_string(), "services/api".to_string()),
+                    ("web".to_string(), "apps/web".to_string()),
+                ]
+                .into_iter()
+                .collect(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_dry_run(true);
+
+        handle_input(&mut state, &config, InputEvent::Action('t'));
+
+        let popup = state.popup().expect("preview popup");
+        assert!(
+            popup.body.contains(
+                "Command: npm test --prefix '/work/mono/apps/web' /work/mono/services/api"
+            ),
+            "{}",
+            popup.body
+        );
+    }
+
+    #[test]
+    fn when_selecting_bookmark_in_file_browser_should_jump_to_its_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("services/api/src")).unwrap();
+        std::fs::write(dir.path().join("README.md"), "").unwrap();
+        let mut config = create_test_config();
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .push(crate::config::Project {
+                name: "mono".to_string(),
+                path: dir.path().to_path_buf(),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: [("api".to_string(), "services/api".to_string())]
+                    .into_iter()
+                    .collect(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.navigate_to_project(config.project_id("workspace-a", 0).unwrap());
+
+        handle_input(&mut state, &config, InputEvent::Enter);
+
+        let api = dir.path().join("services/api");
+        assert!(state.is_dir_expanded(&dir.path().join("services")));
+        assert!(state.is_dir_expanded(&api));
+        let view = file_browser_view(&state, &config, "workspace-a", Some(0));
+        assert_eq!(view.selected_path(), Some(api));
+        assert!(view.selected_bookmark().is_none());
+    }
+
+    /// Returns the test config with a project at the given directory.
+    pub(super) fn config_with_project_at(path: &Path) -> Config {
+        let mut config = create_test_config();
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .push(crate::config::Project {
+                name: "tree".to_string(),
+                path: path.to_path_buf(),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        config
+    }
+
+    #[test]
+    fn when_projects_are_reordered_should_keep_showing_the_same_project() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = config_with_project_at(dir.path());
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.navigate_to_project(config.project_id("workspace-a", 0).unwrap());
+
+        // A project added before it moves it down
+        let mut reordered = config_with_project_at(dir.path());
+        let projects = &mut reordered.workspace.get_mut("workspace-a").unwrap().projects;
+        let mut other = config_with_project_at(dir.path());
+        let mut added = other
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .remove(0);
+        added.name = "other".to_string();
+        projects.insert(0, added);
+
+        leave_missing_project(&mut state, &reordered);
+        assert_eq!(current_project(&state, &reordered).unwrap().name, "tree");
+        navigate_back(&mut state, &reordered);
+        assert_eq!(state.selected_index(), 1);
+
+        state.navigate_to_project(reordered.project_id("workspace-a", 1).unwrap());
+        leave_missing_project(&mut state, &create_test_config());
+        assert!(matches!(state.current_view(), View::Projects { .. }));
+    }
+
+    #[test]
+    fn when_showing_ignored_entries_should_keep_the_same_node_selected() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join(".env"), "").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        let config = config_with_project_at(dir.path());
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.navigate_to_project(config.project_id("workspace-a", 0).unwrap());
+        state.set_selected_index(2);
+        sync_selected_node(&mut state, &config, false);
+
+        handle_input(&mut state, &config, InputEvent::ToggleIgnored);
+        sync_selected_node(&mut state, &config, true);
+
+        let notes = dir.path().join("notes.txt");
+        let view = file_browser_view(&state, &config, "workspace-a", Some(0));
+        assert_eq!(state.selected_index(), 3);
+        assert_eq!(view.selected_path(), Some(notes));
+    }
+
+    #[test]
+    fn when_selected_node_disappears_should_select_its_parent() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("main.rs"), "").unwrap();
+        std::fs::write(dir.path().join("README.md"), "").unwrap();
+        let config = config_with_project_at(dir.path());
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.navigate_to_project(config.project_id("workspace-a", 0).unwrap());
+        state.toggle_dir_expanded(src.clone());
+        state.set_selected_index(2);
+        sync_selected_node(&mut state, &config, false);
+        assert_eq!(state.selected_node(), Some(src.join("main.rs").as_path()));
+
+        std::fs::remove_file(src.join("main.rs")).unwrap();
+        sync_selected_node(&mut state, &config, true);
+
+        assert_eq!(state.selected_index(), 1);
+        assert_eq!(state.selected_node(), Some(src.as_path()));
+    }
+
+    #[test]
+    fn when_pressing_number_in_projects_should_switch_workspace() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::SwitchWorkspace(1));
+
+        assert_eq!(
+            *state.current_view(),
+            View::Projects {
+                workspace_id: "workspace-b".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn when_pressing_number_past_last_workspace_should_stay() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::SwitchWorkspace(5));
+
+        assert_eq!(
+            *state.current_view(),
+            View::Projects {
+                workspace_id: "workspace-a".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn when_pressing_number_at_workspaces_should_not_switch() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+
+        handle_input(&mut state, &config, InputEvent::SwitchWorkspace(0));
+
+        assert_eq!(*state.current_view(), View::Workspaces);
+    }
+
+    #[test]
+    fn when_cycling_web_focus_should_present_next_pane_and_wrap() {
+        let panes = vec![pane("api"), pane("web"), pane("db")];
+
+        assert_eq!(next_web_focus(&panes, None), Some(0));
+        assert_eq!(next_web_focus(&panes, Some(Path::new("/api"))), Some(1));
+        assert_eq!(next_web_focus(&panes, Some(Path::new("/db"))), Some(0));
+        assert_eq!(next_web_focus(&panes, Some(Path::new("/gone"))), Some(0));
+        assert_eq!(next_web_focus(&[], None), None);
+    }
+
+    #[test]
+    fn when_picked_pane_has_no_id_should_not_be_presentable() {
+        let (_, mut floating) = pane("api");
+        assert!(matches!(
+            web_focus_target(&floating),
+            Err(ZellijError::UnknownPaneId { .. })
+        ));
+
+        floating.pane_id = Some("terminal_4".to_string());
+        assert_eq!(
+            web_focus_target(&floating).unwrap(),
+            FocusTarget::Floating("terminal_4".to_string())
+        );
+        floating.floating = false;
+        assert_eq!(web_focus_target(&floating).unwrap(), FocusTarget::Main);
+    }
+
+    #[test]
+    fn when_entering_uncloned_project_should_confirm_clone() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = create_test_config();
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .push(crate::config::Project {
+                name: "api".to_string(),
+                path: dir.path().join("api"),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: Some("git@github.com:org/api.git".to_string()),
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::Enter);
+
+        assert!(matches!(state.current_view(), View::Projects { .. }));
+        let prompt = state.prompt().unwrap();
+        assert_eq!(prompt.kind, PromptKind::ConfirmClone { project_index: 0 });
+
+        handle_input(&mut state, &config, InputEvent::Char('n'));
+        assert!(state.prompt().is_none());
+        assert!(CLONES.with(|c| c.borrow().is_empty()));
+    }
+
+    #[test]
+    fn when_killing_project_without_pane_should_report_it() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = create_test_config();
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .push(crate::config::Project {
+                name: "api".to_string(),
+                path: dir.path().to_path_buf(),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::KillPane);
+
+        let toast = state.toast().unwrap();
+        assert_eq!(toast.message, "No pane running for api");
+        assert_eq!(toast.level, ToastLevel::Error);
+    }
+
+    #[test]
+    fn when_browsing_project_files_should_show_breadcrumb_to_selection() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = create_test_config();
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .push(crate::config::Project {
+                name: "api".to_string(),
+                path: dir.path().to_path_buf(),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        let mut state = AppState::new();
+
+        assert_eq!(
+            view_header(&state, &config, None),
+            Header::new(vec!["Workspaces".to_string()])
+        );
+
+        state.navigate_to_workspace("workspace-a".to_string());
+        assert_eq!(
+            view_header(&state, &config, None),
+            Header::new(vec!["Workspace A".to_string()])
+        );
+
+        state.navigate_to_project(config.project_id("workspace-a", 0).unwrap());
+        let selected = dir.path().join("src").join("main.rs");
+        assert_eq!(
+            view_header(&state, &config, Some(&selected)),
+            Header::new(
+                ["Workspace A", "api", "src", "main.rs"]
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn when_searching_in_file_browser_should_prompt_then_show_results() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = create_test_config();
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .push(crate::config::Project {
+                name: "api".to_string(),
+                path: dir.path().to_path_buf(),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.navigate_to_project(config.project_id("workspace-a", 0).unwrap());
+
+        handle_input(&mut state, &config, InputEvent::Search);
+        assert_eq!(
+            state.prompt().map(|p| &p.kind),
+            Some(&PromptKind::SearchQuery)
+        );
+        handle_input(&mut state, &config, InputEvent::Enter);
+        assert!(state.prompt().is_some(), "empty queries keep the prompt");
+
+        for c in "fn main".chars() {
+            handle_input(&mut state, &config, InputEvent::Char(c));
+        }
+        handle_input(&mut state, &config, InputEvent::Enter);
+
+        assert!(state.prompt().is_none());
+        assert_eq!(state.search_results(), Some(0));
+        assert_eq!(
+            PICKERS.with(|p| p.borrow().search.as_ref().map(|s| s.query().to_string())),
+            Some("fn main".to_string())
+        );
+
+        handle_input(&mut state, &config, InputEvent::Back);
+        assert_eq!(state.search_results(), None);
+    }
+
+    #[test]
+    fn when_opening_recent_files_without_any_should_report_it() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = create_test_config();
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .push(crate::config::Project {
+                name: "api".to_string(),
+                path: dir.path().to_path_buf(),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::RecentFiles);
+
+        assert_eq!(state.recent_files(), None);
+        assert_eq!(state.toast().unwrap().message, "No recent files in api");
+    }
+
+    #[test]
+    fn when_dry_running_claude_action_should_pass_the_mcp_config() {
+        let mut config = config_with_project_at(Path::new("/tmp/api"));
+        config.global.actions.insert(
+            "c".to_string(),
+            Action {
+                name: "Claude".to_string(),
+                command: "claude".to_string(),
+                ..Default::default()
+            },
+        );
+        config.global.mcp.insert(
+            "github".to_string(),
+            serde_json::from_str(r#"{ "command": "github-mcp" }"#).unwrap(),
+        );
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_selected_index(config.workspace["workspace-a"].projects.len() - 1);
+        state.set_dry_run(true);
+
+        handle_input(&mut state, &config, InputEvent::Action('c'));
+
+        let popup = state.popup().expect("dry run popup");
+        let path = mcp::config_path("tree");
+        assert!(
+            popup.body.contains(&format!(
+                "claude --mcp-config {} --strict-mcp-config /tmp/api",
+                util::shell::quote(&path.to_string_lossy())
+            )),
+            "{}",
+            popup.body
+        );
+    }
+
+    #[test]
+    fn when_dry_running_action_of_project_with_env_should_wrap_it() {
+        let mut config = config_with_project_at(Path::new("/tmp/api"));
+        config.global.actions.insert(
+            "t".to_string(),
+            Action {
+                name: "Tests".to_string(),
+                command: "cargo test".to_string(),
+                ..Default::default()
+            },
+        );
+        let last = config.workspace["workspace-a"].projects.len() - 1;
+        config.workspace.get_mut("workspace-a").unwrap().projects[last].env =
+            Some(ProjectEnv::Enabled(true));
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_selected_index(last);
+        state.set_dry_run(true);
+
+        handle_input(&mut state, &config, InputEvent::Action('t'));
+
+        let popup = state.popup().expect("dry run popup");
+        assert!(
+            popup
+                .body
+                .contains("direnv exec '/tmp/api' sh -c 'cargo test /tmp/api'"),
+            "{}",
+            popup.body
+        );
+    }
+
+    #[test]
+    fn when_following_focus_should_select_the_focused_project_unless_a_prompt_is_open() {
+        let config = config_with_project_at(Path::new("/tmp/api"));
+        let last = config.workspace["workspace-a"].projects.len() - 1;
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.open_prompt(PromptKind::ProjectName, "Project name:", "");
+
+        select_project_at(&mut state, &config, Path::new("/tmp/api"));
+        assert_eq!(state.selected_index(), 0);
+
+        state.close_prompt();
+        select_project_at(&mut state, &config, Path::new("/tmp/api"));
+        assert_eq!(state.selected_index(), last);
+    }
+
+    #[test]
+    fn when_pressing_alt_enter_on_project_should_run_default_action() {
+        let config = config_with_default_action("c");
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_dry_run(true);
+
+        handle_input(&mut state, &config, InputEvent::AltEnter);
+
+        let popup = state.popup().expect("preview popup");
+        assert_eq!(popup.title, "Dry run: Claude");
+        assert!(matches!(state.current_view(), View::Projects { .. }));
+    }
+
+    #[test]
+    fn when_project_enter_is_action_should_swap_enter_and_alt_enter() {
+        let mut config = config_with_default_action("c");
+        config.global.project_enter = ProjectEnter::Action;
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_dry_run(true);
+
+        handle_input(&mut state, &config, InputEvent::Enter);
+        assert!(state.popup().is_some());
+        state.close_popup();
+        handle_input(&mut state, &config, InputEvent::AltEnter);
+
+        assert!(state.popup().is_none());
+        assert!(matches!(state.current_view(), View::FileBrowser { .. }));
+    }
+
+    #[test]
+    fn when_default_action_is_undefined_should_show_error() {
+        let config = config_with_default_action("x");
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::AltEnter);
+
+        assert!(state.popup().is_none());
+        assert_eq!(state.toast().unwrap().level, ToastLevel::Error);
+        assert!(matches!(state.current_view(), View::Projects { .. }));
+    }
+
+    fn config_with_background_action(path: &Path) -> Config {
+        let mut config = config_with_project_at(path);
+        config.global.actions.insert(
+            "f".to_string(),
+            Action {
+                name: "Fetch".to_string(),
+                command: "git fetch".to_string(),
+                pre: Some("echo start".to_string()),
+                background: true,
+                ..Default::default()
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn when_previewing_background_action_should_show_command_without_pane() {
+        let config = config_with_background_action(Path::new("/tmp/api"));
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_dry_run(true);
+
+        handle_input(&mut state, &config, InputEvent::Action('f'));
+
+        let popup = state.popup().expect("preview popup");
+        assert!(popup.body.contains("Runs in the background in /tmp/api"));
+        assert!(popup
+            .body
+            .contains("Command: echo start && git fetch /tmp/api"));
+        assert!(!popup.body.contains("Pane:"));
+    }
+
+    #[test]
+    fn when_running_background_action_should_not_open_a_pane() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = config_with_background_action(dir.path());
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::Action('f'));
+
+        assert!(!MAIN_PANE_USED.with(|m| *m.borrow()));
+        assert!(background_activity()
+            .iter()
+            .any(|progress| progress.text(0).contains("running")));
+        assert_eq!(
+            state.toast().map(|t| t.message.as_str()),
+            Some("Running Fetch in the background")
+        );
+    }
+
+    #[test]
+    fn when_background_action_finishes_should_keep_its_outcome() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = config_with_project_at(dir.path());
+        let mut state = AppState::new();
+        for (key, command) in [("t", "exit 3"), ("l", "true")] {
+            WORK.with(|w| {
+                w.borrow_mut().background.push(BackgroundAction {
+                    project_path: dir.path().to_path_buf(),
+                    key: key.to_string(),
+                    logs_dir: dir.path().join("logs"),
+                    capture: CommandCapture::spawn(
+                        key,
+                        dir.path().to_path_buf(),
+                        command.into(),
+                        None,
+                    ),
+                })
+            });
+        }
+
+        for _ in 0..100 {
+            poll_background_actions(&mut state, &config);
+            if WORK.with(|w| w.borrow().background.is_empty()) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let outcomes = action_outcomes(dir.path());
+        assert_eq!(outcomes.get("t"), Some(&false));
+        assert_eq!(outcomes.get("l"), Some(&true));
+    }
+
+    fn config_with_argument_action() -> Config {
+        let mut config = config_with_project_at(Path::new("/tmp/api"));
+        config.global.actions.insert(
+            "b".to_string(),
+            Action {
+                name: "Checkout".to_string(),
+                command: "git checkout {prompt:Branch name}".to_string(),
+                ..Default::default()
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn when_action_has_argument_placeholder_should_ask_for_it() {
+        let config = config_with_argument_action();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::Action('b'));
+
+        let prompt = state.prompt().expect("argument prompt");
+        assert_eq!(prompt.label, "Branch name:");
+        assert_eq!(
+            prompt.kind,
+            PromptKind::Argument {
+                target: ArgumentTarget::Action('b'),
+                message: "Branch name".to_string(),
+                answers: vec![],
+                recalled: None,
+            }
+        );
+
+        handle_input(&mut state, &config, InputEvent::Back);
+
+        assert!(state.prompt().is_none());
+        assert!(state.popup().is_none());
+    }
+
+    #[test]
+    fn when_arguments_are_answered_should_run_the_filled_command() {
+        let config = config_with_argument_action();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_dry_run(true);
+
+        handle_action(&mut state, &config, 'b', &["feature/x".to_string()]);
+
+        let popup = state.popup().expect("preview popup");
+        assert!(
+            popup.body.contains("git checkout 'feature/x' /tmp/api"),
+            "{}",
+            popup.body
+        );
+    }
+
+    #[test]
+    fn when_building_git_ui_command_from_file_browser_should_pass_relative_filter() {
+        let git_ui = GitUiConfig::default();
+        let project = Path::new("/work/app");
+
+        let command = git_ui_command(&git_ui, project, Some(Path::new("/work/app/src/main.rs")));
+
+        assert_eq!(command, "cd '/work/app' && lazygit -f 'src/main.rs'");
+    }
+
+    #[test]
+    fn when_building_shell_command_should_cd_into_directory() {
+        assert_eq!(
+            shell_command("zsh", Path::new("/work/my app/src")),
+            "cd '/work/my app/src' && exec zsh"
+        );
+    }
+
+    #[test]
+    fn when_config_has_lint_warnings_should_list_them_for_the_startup_screen() {
+        let config = create_test_config();
+
+        let warnings = config_warnings(&config).unwrap();
+
+        assert!(warnings.starts_with(
+            "• workspace 'workspace-a' has no projects\n• workspace 'workspace-b' has no projects\n"
+        ));
+        assert!(warnings.ends_with("Run 'gz-claude config lint' to list them again."));
+    }
+
+    #[test]
+    fn when_building_git_ui_command_for_project_root_should_not_filter() {
+        let git_ui = GitUiConfig {
+            command: "gitui".to_string(),
+            filter_flag: None,
+        };
+        let project = Path::new("/work/app");
+
+        assert_eq!(
+            git_ui_command(&git_ui, project, Some(project)),
+            "cd '/work/app' && gitui"
+        );
+        assert_eq!(
+            git_ui_command(&GitUiConfig::default(), project, Some(project)),
+            "cd '/work/app' && lazygit"
+        );
+    }
+
+    fn pane(name: &str) -> (PathBuf, PaneInfo) {
+        (
+            PathBuf::from(format!("/{}", name)),
+            PaneInfo {
+                pane_name: name.to_string(),
+                command: "claude".to_string(),
+                floating: true,
+                pane_id: None,
+                needs_attention: false,
+                embedded: false,
+            },
+        )
+    }
+
+    pub(super) fn config_with_default_action(key: &str) -> Config {
+        let mut config = config_with_project_at(Path::new("/tmp/api"));
+        config.global.actions.insert(
+            "c".to_string(),
+            Action {
+                name: "Claude".to_string(),
+                command: "claude".to_string(),
+                ..Default::default()
+            },
+        );
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .default_action = Some(key.to_string());
+        config
+    }
+}
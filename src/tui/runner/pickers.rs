@@ -0,0 +1,1003 @@
+//! Pickers of the TUI: overlays listing entries to choose from, such as the
+//! project menu, the MCP servers of a project, recent files, search results,
+//! the file finder, the command palette, pull requests and the workspace
+//! switcher. Each has a function opening it and one handling its input.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use super::*;
+
+/// What the pickers keep while they are open.
+#[derive(Default)]
+pub(super) struct PickerState {
+    /// The MCP servers toggled in the popup, loaded when it opens.
+    pub(super) mcp: Option<McpSelection>,
+    /// The search of the project's files, running or finished.
+    pub(super) search: Option<ProjectSearch>,
+    /// The files of the project, indexed for the file finder.
+    pub(super) file_index: Option<FileIndex>,
+}
+
+thread_local! {
+    pub(super) static PICKERS: RefCell<PickerState> = RefCell::new(PickerState::default());
+}
+
+/// An MCP server of a project: its name, the server and whether it is enabled
+/// for the next Claude launch.
+pub(super) type McpEntry = (String, McpServer, bool);
+
+/// What an entry of the project menu does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MenuCommand {
+    /// Handles an input event, as if its key was pressed.
+    Key(InputEvent),
+    /// Opens the project's entry of the configuration file in the editor.
+    EditConfig,
+    /// Resolves the project's merge conflicts with `git mergetool`.
+    Mergetool,
+}
+
+/// An entry of the project menu: the key doing the same, if any, its label
+/// and what it does.
+pub(super) type MenuEntry = (String, String, MenuCommand);
+
+/// Returns the current project with its MCP servers, by name, and whether
+/// each is enabled for the next Claude launch.
+pub(super) fn mcp_servers<'a>(
+    state: &AppState,
+    config: &'a Config,
+) -> Option<(&'a Project, Vec<McpEntry>)> {
+    let (workspace_id, project_index) = current_project_position(state, config)?;
+    let project = current_project(state, config)?;
+    let servers = PICKERS.with(|p| {
+        let mut pickers = p.borrow_mut();
+        let selection = pickers.mcp.get_or_insert_with(McpSelection::load);
+        config
+            .resolve_mcp_servers(workspace_id, project_index)
+            .into_iter()
+            .map(|(name, server)| {
+                let enabled = selection.is_enabled(&project.path, &name, &server);
+                (name, server, enabled)
+            })
+            .collect()
+    });
+    Some((project, servers))
+}
+
+/// Opens the MCP servers popup of the current project, reloading the
+/// servers toggled from other sessions.
+pub(super) fn show_mcp_servers(state: &mut AppState, config: &Config) {
+    PICKERS.with(|p| p.borrow_mut().mcp = Some(McpSelection::load()));
+    let Some((project, servers)) = mcp_servers(state, config) else {
+        return;
+    };
+    if servers.is_empty() {
+        let message = format!("No MCP servers configured for {}", project.name);
+        state.show_toast(message, ToastLevel::Info);
+    } else {
+        state.open_mcp_servers();
+    }
+}
+
+/// Handles input events when the MCP servers popup is open.
+///
+/// Up/down move the selection, enter or space enable or disable the server
+/// for the next Claude launch, and esc, 'q' or 'M' close the popup.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+pub(super) fn handle_mcp_servers_input(state: &mut AppState, config: &Config, event: InputEvent) {
+    let Some(selected) = state.mcp_servers() else {
+        return;
+    };
+    let Some((project, servers)) = mcp_servers(state, config) else {
+        state.close_mcp_servers();
+        return;
+    };
+
+    match event {
+        InputEvent::Up => {
+            state.set_mcp_servers_selected(selected.saturating_sub(1));
+        }
+        InputEvent::Down if selected + 1 < servers.len() => {
+            state.set_mcp_servers_selected(selected + 1);
+        }
+        InputEvent::Enter | InputEvent::Action(' ') => {
+            let Some((name, _, enabled)) = servers.get(selected) else {
+                return;
+            };
+            // Reloaded first, so servers toggled from other sessions are kept
+            let mut selection = McpSelection::load();
+            selection.set(&project.path, name, !enabled);
+            if let Err(e) = selection.save() {
+                state.show_toast(e.to_string(), ToastLevel::Error);
+            }
+            PICKERS.with(|p| p.borrow_mut().mcp = Some(selection));
+        }
+        InputEvent::Back | InputEvent::Quit | InputEvent::McpServers => {
+            state.close_mcp_servers();
+        }
+        // Ignore other events while the popup is open
+        _ => {}
+    }
+}
+
+/// Returns the project selected in the Projects view with the entries of
+/// its menu: its actions, then the built-in commands that apply to it.
+pub(super) fn project_menu<'a>(
+    state: &AppState,
+    config: &'a Config,
+) -> Option<(&'a Project, Vec<MenuEntry>)> {
+    let View::Projects { workspace_id } = state.current_view() else {
+        return None;
+    };
+    let project_index = state.selected_index();
+    let project = config
+        .workspace
+        .get(workspace_id)?
+        .projects
+        .get(project_index)?;
+
+    let mut actions: Vec<(String, Action)> = config
+        .resolve_actions(workspace_id, project_index)
+        .into_iter()
+        .collect();
+    actions.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut entries: Vec<MenuEntry> = actions
+        .into_iter()
+        .filter_map(|(key, action)| {
+            let c = key.chars().next()?;
+            Some((key, action.name, MenuCommand::Key(InputEvent::Action(c))))
+        })
+        .collect();
+
+    let (browse_key, browse) = if config.global.project_enter == ProjectEnter::Action {
+        ("Alt-Enter", InputEvent::AltEnter)
+    } else {
+        ("Enter", InputEvent::Enter)
+    };
+    let git_ui_key = if config.global.navigation == NavigationMode::Vim {
+        "gs"
+    } else {
+        "G"
+    };
+    let mut builtins = vec![
+        (browse_key, "Browse files", browse),
+        ("C", "Claude conversations", InputEvent::Conversations),
+        ("n", "New task", InputEvent::NewTask),
+        (git_ui_key, "Git UI", InputEvent::GitUi),
+        ("F", "Fetch remote", InputEvent::Fetch),
+    ];
+    if config.global.remote.is_some() {
+        builtins.push(("P", "Pull requests", InputEvent::PullRequests));
+    }
+    if config.has_mcp_servers() {
+        builtins.push(("M", "MCP servers", InputEvent::McpServers));
+    }
+    builtins.extend([
+        ("y", "Copy path", InputEvent::Yank),
+        (
+            "O",
+            "Reveal in file manager",
+            InputEvent::RevealInFileManager,
+        ),
+        ("B", "Open remote", InputEvent::OpenRemote),
+        ("L", "Open latest log", InputEvent::OpenLatestLog),
+        ("x", "Kill pane", InputEvent::KillPane),
+        ("R", "Restart pane", InputEvent::RestartPane),
+    ]);
+    entries.extend(
+        builtins.into_iter().map(|(key, label, event)| {
+            (key.to_string(), label.to_string(), MenuCommand::Key(event))
+        }),
+    );
+    if has_conflicts(config, project) {
+        entries.push((
+            String::new(),
+            "Open mergetool".to_string(),
+            MenuCommand::Mergetool,
+        ));
+    }
+    entries.push((
+        String::new(),
+        "Edit config entry".to_string(),
+        MenuCommand::EditConfig,
+    ));
+    entries.push((
+        "D".to_string(),
+        "Remove project".to_string(),
+        MenuCommand::Key(InputEvent::RemoveProject),
+    ));
+    Some((project, entries))
+}
+
+/// Opens the menu of the project selected in the Projects view.
+pub(super) fn show_project_menu(state: &mut AppState, config: &Config) {
+    if project_menu(state, config).is_some() {
+        state.open_project_menu();
+    }
+}
+
+/// Handles input events when the project menu is open.
+///
+/// Up/down move the selection, enter closes the menu and runs the selected
+/// entry, and esc, 'q', 'm' or left close the menu.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+pub(super) fn handle_project_menu_input(state: &mut AppState, config: &Config, event: InputEvent) {
+    let Some(selected) = state.project_menu() else {
+        return;
+    };
+    let Some((project, entries)) = project_menu(state, config) else {
+        state.close_project_menu();
+        return;
+    };
+
+    match event {
+        InputEvent::Up => {
+            state.set_project_menu_selected(selected.saturating_sub(1));
+        }
+        InputEvent::Down if selected + 1 < entries.len() => {
+            state.set_project_menu_selected(selected + 1);
+        }
+        InputEvent::Enter | InputEvent::Right => {
+            let Some((_, _, command)) = entries.get(selected) else {
+                return;
+            };
+            let command = *command;
+            let project_name = project.name.clone();
+            state.close_project_menu();
+            match command {
+                MenuCommand::Key(event) => handle_event(state, config, event),
+                MenuCommand::EditConfig => edit_project_config(state, config, &project_name),
+                MenuCommand::Mergetool => open_mergetool(state, config),
+            }
+        }
+        InputEvent::Back | InputEvent::Quit | InputEvent::Left | InputEvent::ProjectMenu => {
+            state.close_project_menu();
+        }
+        // Ignore other events while the menu is open
+        _ => {}
+    }
+}
+
+/// Opens the configuration file in the editor at the entry of a project of
+/// the current workspace, or at its top when the entry can't be found.
+fn edit_project_config(state: &mut AppState, config: &Config, project_name: &str) {
+    let Some(workspace_id) = current_workspace_id(state) else {
+        return;
+    };
+    let config_path = Config::default_path();
+    let line = Config::project_line(&config_path, workspace_id, project_name);
+    let dir = config_path.parent().unwrap_or(Path::new("."));
+    let editor = &config.global.editor;
+    let opened = match line {
+        Some(line) => crate::zellij::open_file_in_editor_at(dir, editor, &config_path, line),
+        None => crate::zellij::open_file_in_editor(dir, editor, &config_path),
+    };
+    if let Err(e) = opened {
+        state.show_toast(e.to_string(), ToastLevel::Error);
+    }
+}
+
+/// Handles input events when the recent files popup is open.
+///
+/// Up/down move the selection, enter opens the file in the editor, and
+/// esc, 'q' or 'o' close the popup.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+pub(super) fn handle_recent_files_input(state: &mut AppState, config: &Config, event: InputEvent) {
+    let Some(selected) = state.recent_files() else {
+        return;
+    };
+    let Some(project) = current_project(state, config) else {
+        state.close_recent_files();
+        return;
+    };
+    let files = recent_files(&project.path);
+
+    match event {
+        InputEvent::Up => {
+            state.set_recent_files_selected(selected.saturating_sub(1));
+        }
+        InputEvent::Down if selected + 1 < files.len() => {
+            state.set_recent_files_selected(selected + 1);
+        }
+        InputEvent::Enter => {
+            state.close_recent_files();
+            if let Some(file_path) = files.get(selected) {
+                let opened = open_in_editor(state, config, &project.path, file_path, None);
+                if opened && matches!(state.current_view(), View::FileBrowser { .. }) {
+                    // The opened file is now the first recent file
+                    state.set_selected_index(0);
+                }
+            }
+        }
+        InputEvent::Back | InputEvent::Quit | InputEvent::RecentFiles => {
+            state.close_recent_files();
+        }
+        // Ignore other events while the popup is open
+        _ => {}
+    }
+}
+
+/// Opens the prompt asking for the text to search, filled with the last query.
+pub(super) fn open_search_prompt(state: &mut AppState) {
+    let query = PICKERS.with(|p| {
+        p.borrow()
+            .search
+            .as_ref()
+            .map(|search| search.query().to_string())
+            .unwrap_or_default()
+    });
+    state.open_prompt(PromptKind::SearchQuery, "Search:", query);
+}
+
+/// Starts searching the files of the current project and shows the results.
+///
+/// A previous search still running is stopped. An empty query keeps the
+/// prompt open.
+pub(super) fn start_search(state: &mut AppState, config: &Config, query: &str) {
+    if query.trim().is_empty() {
+        state.show_toast("Search text cannot be empty", ToastLevel::Error);
+        return;
+    }
+    let Some(project) = current_project(state, config) else {
+        return;
+    };
+    let search = ProjectSearch::spawn(&project.path, query);
+    PICKERS.with(|p| p.borrow_mut().search = Some(search));
+    state.close_prompt();
+    state.open_search_results();
+}
+
+/// Collects the matches of the running search.
+///
+/// Returns true if matches arrived or the search finished.
+pub(super) fn poll_search() -> bool {
+    PICKERS.with(|p| {
+        p.borrow_mut()
+            .search
+            .as_mut()
+            .is_some_and(|search| search.poll())
+    })
+}
+
+/// Handles input events when the search results popup is open.
+///
+/// Up/down move the selection, left/right move a page, enter opens the file
+/// in the editor at the matching line, '/' edits the query, and esc or 'q'
+/// close the popup, stopping the search.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+pub(super) fn handle_search_results_input(
+    state: &mut AppState,
+    config: &Config,
+    event: InputEvent,
+) {
+    /// Rows moved by left/right.
+    const PAGE: usize = 20;
+
+    let Some(selected) = state.search_results() else {
+        return;
+    };
+    let Some(project) = current_project(state, config) else {
+        state.close_search_results();
+        return;
+    };
+    let count = PICKERS.with(|p| p.borrow().search.as_ref().map_or(0, |s| s.matches().len()));
+    let last = count.saturating_sub(1);
+
+    match event {
+        InputEvent::Up => state.set_search_results_selected(selected.saturating_sub(1)),
+        InputEvent::Down => state.set_search_results_selected((selected + 1).min(last)),
+        InputEvent::Left => state.set_search_results_selected(selected.saturating_sub(PAGE)),
+        InputEvent::Right => state.set_search_results_selected((selected + PAGE).min(last)),
+        InputEvent::Enter => {
+            let found = PICKERS.with(|p| {
+                p.borrow()
+                    .search
+                    .as_ref()
+                    .and_then(|search| search.matches().get(selected).cloned())
+            });
+            if let Some(found) = found {
+                state.close_search_results();
+                open_in_editor(state, config, &project.path, &found.path, Some(found.line));
+            }
+        }
+        InputEvent::Search => {
+            state.close_search_results();
+            open_search_prompt(state);
+        }
+        InputEvent::Back | InputEvent::Quit => {
+            state.close_search_results();
+            PICKERS.with(|p| {
+                if let Some(search) = p.borrow_mut().search.as_mut() {
+                    search.stop();
+                }
+            });
+        }
+        // Ignore other events while the popup is open
+        _ => {}
+    }
+}
+
+/// Opens the jump-to-file popup for the current project.
+///
+/// The project's files are indexed in the background, with the ignore
+/// settings of the file browser. An index of the same project is kept and
+/// refreshed, so its files can be matched right away.
+pub(super) fn open_file_finder(state: &mut AppState, config: &Config) {
+    let Some((workspace_id, project_index)) = current_project_position(state, config) else {
+        return;
+    };
+    let Some(project) = config
+        .workspace
+        .get(workspace_id)
+        .and_then(|w| w.projects.get(project_index))
+    else {
+        return;
+    };
+    let filter = IgnoreFilter::new(
+        config.resolve_ignore_patterns(workspace_id, project_index),
+        state.show_ignored(),
+    );
+
+    PICKERS.with(|p| {
+        let index = &mut p.borrow_mut().file_index;
+        match index.as_mut() {
+            Some(index) if index.covers(&project.path, &filter) => index.refresh(),
+            _ => *index = Some(FileIndex::spawn(&project.path, filter)),
+        }
+    });
+    state.open_file_finder();
+}
+
+/// Collects the project files once indexing finished.
+///
+/// Returns true if the index changed.
+pub(super) fn poll_file_index() -> bool {
+    PICKERS.with(|p| {
+        p.borrow_mut()
+            .file_index
+            .as_mut()
+            .is_some_and(|index| index.poll())
+    })
+}
+
+/// Handles input events when the jump-to-file popup is open.
+///
+/// Typed characters edit the query, up/down move the selection, enter opens
+/// the selected file in the editor, and esc closes the popup.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+pub(super) fn handle_file_finder_input(state: &mut AppState, config: &Config, event: InputEvent) {
+    let Some(finder) = state.file_finder().cloned() else {
+        return;
+    };
+    // The selected file, as an absolute path, and the number of matches
+    let (selected, count) = PICKERS.with(|p| {
+        let pickers = p.borrow();
+        let Some(index) = pickers.file_index.as_ref() else {
+            return (None, 0);
+        };
+        let ranked = finder::rank(&finder.query, index.files());
+        let selected = ranked
+            .get(finder.selected)
+            .map(|file| index.root().join(file.path));
+        (selected, ranked.len())
+    });
+
+    match event {
+        InputEvent::Char(c) => {
+            if let Some(finder) = state.file_finder_mut() {
+                finder.query.push(c);
+                finder.selected = 0;
+            }
+        }
+        InputEvent::DeleteChar => {
+            if let Some(finder) = state.file_finder_mut() {
+                finder.query.pop();
+                finder.selected = 0;
+            }
+        }
+        InputEvent::Up => {
+            if let Some(finder) = state.file_finder_mut() {
+                finder.selected = finder.selected.saturating_sub(1);
+            }
+        }
+        InputEvent::Down => {
+            if let Some(finder) = state.file_finder_mut() {
+                finder.selected = (finder.selected + 1).min(count.saturating_sub(1));
+            }
+        }
+        InputEvent::Enter => {
+            let Some(file_path) = selected else {
+                return;
+            };
+            state.close_file_finder();
+            if let Some(project) = current_project(state, config) {
+                open_in_editor(state, config, &project.path, &file_path, None);
+            }
+        }
+        InputEvent::Back => state.close_file_finder(),
+        _ => {}
+    }
+}
+
+/// Returns the entries of the command palette: the actions and command bar
+/// items of the current project, if any, then the built-in commands that
+/// apply to the current view.
+pub(super) fn palette_entries(state: &AppState, config: &Config) -> Vec<PaletteEntry> {
+    let mut entries = Vec::new();
+    let position = current_project_position(state, config);
+
+    if let Some((workspace_id, project_index)) = position {
+        let mut actions: Vec<(String, Action)> = config
+            .resolve_actions(workspace_id, project_index)
+            .into_iter()
+            .collect();
+        actions.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.extend(actions.into_iter().filter_map(|(key, action)| {
+            let c = key.chars().next()?;
+            let command = PaletteCommand::Key(InputEvent::Action(c));
+            Some(PaletteEntry::new(
+                PaletteKind::Action,
+                action.name,
+                key,
+                command,
+            ))
+        }));
+    }
+    entries.extend(
+        get_command_bar_items(state, config)
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let command = PaletteCommand::CommandBarItem(index);
+                PaletteEntry::new(PaletteKind::Command, item.name, "", command)
+            }),
+    );
+
+    let view = state.current_view();
+    let in_projects = matches!(view, View::Projects { .. });
+    let git_ui_key = if config.global.navigation == NavigationMode::Vim {
+        "gs"
+    } else {
+        "G"
+    };
+    let mut builtins = vec![
+        ("r", "Refresh", InputEvent::Refresh),
+        ("N", "Toggle dry run", InputEvent::ToggleDryRun),
+    ];
+    if can_switch_workspace(state) {
+        builtins.push(("w", "Switch workspace", InputEvent::WorkspaceSwitcher));
+    }
+    if !matches!(view, View::Workspaces) {
+        builtins.extend([
+            ("Ctrl-R", "Refresh all git info", InputEvent::RefreshAll),
+            ("A", "Arrange panes", InputEvent::ArrangePanes),
+            ("z", "Zoom main pane", InputEvent::ZoomMainPane),
+        ]);
+    }
+    if position.is_some() {
+        builtins.extend([
+            ("Ctrl-T", "Go to file", InputEvent::FileFinder),
+            ("o", "Recent files", InputEvent::RecentFiles),
+            (git_ui_key, "Git UI", InputEvent::GitUi),
+            ("F", "Fetch remote", InputEvent::Fetch),
+            ("y", "Copy path", InputEvent::Yank),
+            ("L", "Open latest log", InputEvent::OpenLatestLog),
+            ("x", "Kill pane", InputEvent::KillPane),
+            ("R", "Restart pane", InputEvent::RestartPane),
+        ]);
+    }
+    if matches!(view, View::FileBrowser { .. }) {
+        builtins.extend([
+            ("/", "Search files", InputEvent::Search),
+            ("b", "Blame file", InputEvent::Blame),
+        ]);
+    }
+    if in_projects {
+        builtins.extend([
+            ("m", "Project menu", InputEvent::ProjectMenu),
+            ("C", "Claude conversations", InputEvent::Conversations),
+            ("n", "New task", InputEvent::NewTask),
+            ("a", "Add project", InputEvent::AddProject),
+        ]);
+    }
+    entries.extend(builtins.into_iter().map(|(key, label, event)| {
+        PaletteEntry::new(PaletteKind::Panel, label, key, PaletteCommand::Key(event))
+    }));
+    if current_project(state, config).is_some_and(|project| has_conflicts(config, project)) {
+        entries.push(PaletteEntry::new(
+            PaletteKind::Panel,
+            "Open mergetool",
+            "",
+            PaletteCommand::Mergetool,
+        ));
+    }
+    entries.push(PaletteEntry::new(
+        PaletteKind::Panel,
+        "Open config",
+        "",
+        PaletteCommand::OpenConfig,
+    ));
+    entries
+}
+
+/// Handles input events when the command palette is open.
+///
+/// Typed characters edit the query, up/down move the selection, enter
+/// closes the palette and runs the selected entry, recording it in the
+/// history, and esc closes the palette.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+pub(super) fn handle_command_palette_input(
+    state: &mut AppState,
+    config: &Config,
+    event: InputEvent,
+) {
+    let Some(palette) = state.command_palette().cloned() else {
+        return;
+    };
+    let entries = palette_entries(state, config);
+    let mut history = PaletteHistory::load();
+    let ranked = palette::rank(&palette.query, &entries, &history);
+
+    match event {
+        InputEvent::Char(c) => {
+            if let Some(palette) = state.command_palette_mut() {
+                palette.query.push(c);
+                palette.selected = 0;
+            }
+        }
+        InputEvent::DeleteChar => {
+            if let Some(palette) = state.command_palette_mut() {
+                palette.query.pop();
+                palette.selected = 0;
+            }
+        }
+        InputEvent::Up => {
+            if let Some(palette) = state.command_palette_mut() {
+                palette.selected = palette.selected.saturating_sub(1);
+            }
+        }
+        InputEvent::Down => {
+            if let Some(palette) = state.command_palette_mut() {
+                palette.selected = (palette.selected + 1).min(ranked.len().saturating_sub(1));
+            }
+        }
+        InputEvent::Enter => {
+            let Some(entry) = ranked.get(palette.selected).map(|r| &entries[r.index]) else {
+                return;
+            };
+            state.close_command_palette();
+            history.record(&entry.id());
+            // A history that can't be saved only loses the ordering
+            let _ = history.save();
+            match entry.command {
+                PaletteCommand::Key(event) => handle_event(state, config, event),
+                PaletteCommand::CommandBarItem(index) => {
+                    run_command_bar_item(state, config, index, &[])
+                }
+                PaletteCommand::OpenConfig => edit_config(state, config),
+                PaletteCommand::Mergetool => open_mergetool(state, config),
+            }
+        }
+        InputEvent::Back => state.close_command_palette(),
+        _ => {}
+    }
+}
+
+/// Opens the pull requests popup of the current project.
+///
+/// Fetch failures open a popup with the reason; other states without pull
+/// requests to list are reported with a toast.
+pub(super) fn show_pull_requests(state: &mut AppState, config: &Config) {
+    let Some(project) = current_project(state, config) else {
+        return;
+    };
+    if config.global.remote.is_none() {
+        state.show_toast("Remote integration is not configured", ToastLevel::Info);
+        return;
+    }
+
+    match remote_state(&project.path) {
+        Some(RemoteState::Ready(status)) if !status.pull_requests.is_empty() => {
+            state.open_pull_requests();
+        }
+        Some(RemoteState::Ready(_)) => {
+            let message = format!("No open pull requests in {}", project.name);
+            state.show_toast(message, ToastLevel::Info);
+        }
+        Some(RemoteState::Failed(reason)) => {
+            let title = format!("{} - pull requests", project.name);
+            state.show_popup(title, reason);
+        }
+        Some(RemoteState::Loading) | None => {
+            state.show_toast("Fetching pull requests", ToastLevel::Info);
+        }
+    }
+}
+
+/// Handles input events when the pull requests popup is open.
+///
+/// Up/down move the selection, enter opens the pull request in the browser,
+/// and esc, 'q' or 'P' close the popup.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+pub(super) fn handle_pull_requests_input(state: &mut AppState, config: &Config, event: InputEvent) {
+    let Some(selected) = state.pull_requests() else {
+        return;
+    };
+    let pull_requests = match current_project(state, config).and_then(|p| remote_state(&p.path)) {
+        Some(RemoteState::Ready(status)) => status.pull_requests,
+        _ => {
+            state.close_pull_requests();
+            return;
+        }
+    };
+
+    match event {
+        InputEvent::Up => {
+            state.set_pull_requests_selected(selected.saturating_sub(1));
+        }
+        InputEvent::Down if selected + 1 < pull_requests.len() => {
+            state.set_pull_requests_selected(selected + 1);
+        }
+        InputEvent::Enter => {
+            if let Some(pr) = pull_requests.get(selected) {
+                match util::open_url(&pr.url) {
+                    Ok(()) => state.close_pull_requests(),
+                    Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+                }
+            }
+        }
+        InputEvent::Back | InputEvent::Quit | InputEvent::PullRequests => {
+            state.close_pull_requests();
+        }
+        // Ignore other events while the popup is open
+        _ => {}
+    }
+}
+
+/// Handles input events when the workspace switcher is open.
+///
+/// Up/down move the selection, enter or a number key switches to the
+/// workspace, and esc, 'q' or 'w' close the switcher.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+pub(super) fn handle_workspace_switcher_input(
+    state: &mut AppState,
+    config: &Config,
+    event: InputEvent,
+) {
+    let Some(selected) = state.workspace_switcher() else {
+        return;
+    };
+
+    match event {
+        InputEvent::Up => {
+            state.set_workspace_switcher_selected(selected.saturating_sub(1));
+        }
+        InputEvent::Down if selected + 1 < config.workspace.len() => {
+            state.set_workspace_switcher_selected(selected + 1);
+        }
+        InputEvent::Enter => {
+            switch_to_workspace_at(state, config, selected);
+        }
+        InputEvent::SwitchWorkspace(index) => {
+            switch_to_workspace_at(state, config, index);
+        }
+        InputEvent::Back | InputEvent::Quit | InputEvent::WorkspaceSwitcher => {
+            state.close_workspace_switcher();
+        }
+        // Ignore other events while the switcher is open
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{
+        config_with_default_action, config_with_project_at, create_test_config,
+    };
+    use super::*;
+
+    #[test]
+    fn when_selecting_in_workspace_switcher_should_switch_workspace() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-b".to_string());
+
+        handle_input(&mut state, &config, InputEvent::WorkspaceSwitcher);
+        assert_eq!(state.workspace_switcher(), Some(1));
+
+        handle_input(&mut state, &config, InputEvent::Up);
+        handle_input(&mut state, &config, InputEvent::Enter);
+
+        assert_eq!(state.workspace_switcher(), None);
+        assert_eq!(
+            *state.current_view(),
+            View::Projects {
+                workspace_id: "workspace-a".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn when_pressing_esc_in_workspace_switcher_should_close_it() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        handle_input(&mut state, &config, InputEvent::WorkspaceSwitcher);
+
+        handle_input(&mut state, &config, InputEvent::Back);
+
+        assert_eq!(state.workspace_switcher(), None);
+        assert_eq!(
+            *state.current_view(),
+            View::Projects {
+                workspace_id: "workspace-a".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn when_typing_in_command_palette_should_match_commands_of_the_view() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+
+        handle_input(&mut state, &config, InputEvent::CommandPalette);
+        for c in "dry".chars() {
+            handle_input(&mut state, &config, InputEvent::Char(c));
+        }
+
+        let palette = state.command_palette().unwrap();
+        assert_eq!(palette.query, "dry");
+        let entries = palette_entries(&state, &config);
+        let ranked = palette::rank(&palette.query, &entries, &PaletteHistory::default());
+        assert_eq!(entries[ranked[0].index].label, "Toggle dry run");
+        assert!(entries.iter().all(|entry| entry.label != "Git UI"));
+
+        handle_input(&mut state, &config, InputEvent::Back);
+        assert!(state.command_palette().is_none());
+    }
+
+    #[test]
+    fn when_typing_in_file_finder_should_filter_indexed_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/tui/views")).unwrap();
+        std::fs::write(dir.path().join("src/tui/views/header.rs"), "").unwrap();
+        std::fs::write(dir.path().join("README.md"), "").unwrap();
+        let mut config = create_test_config();
+        config
+            .workspace
+            .get_mut("workspace-a")
+            .unwrap()
+            .projects
+            .push(crate::config::Project {
+                name: "api".to_string(),
+                path: dir.path().to_path_buf(),
+                actions: HashMap::new(),
+                command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
+            });
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::FileFinder);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !poll_file_index() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        for c in "hdr".chars() {
+            handle_input(&mut state, &config, InputEvent::Char(c));
+        }
+
+        let files = PICKERS.with(|p| {
+            let pickers = p.borrow();
+            let finder = state.file_finder().unwrap();
+            finder::rank(&finder.query, pickers.file_index.as_ref().unwrap().files())
+                .iter()
+                .map(|f| f.path.to_string())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(files, vec!["src/tui/views/header.rs"]);
+
+        handle_input(&mut state, &config, InputEvent::Back);
+        assert!(state.file_finder().is_none());
+    }
+
+    #[test]
+    fn when_running_project_menu_entry_should_close_menu_and_run_it() {
+        let mut config = config_with_project_at(Path::new("/tmp/api"));
+        config.global.actions.insert(
+            "c".to_string(),
+            Action {
+                name: "Claude".to_string(),
+                command: "claude".to_string(),
+                ..Default::default()
+            },
+        );
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_selected_index(config.workspace["workspace-a"].projects.len() - 1);
+        state.set_dry_run(true);
+
+        handle_input(&mut state, &config, InputEvent::ProjectMenu);
+        let (_, entries) = project_menu(&state, &config).unwrap();
+        assert_eq!(entries[0].1, "Claude");
+        assert!(entries
+            .iter()
+            .any(|(_, label, _)| label == "Edit config entry"));
+
+        handle_input(&mut state, &config, InputEvent::Enter);
+
+        assert_eq!(state.project_menu(), None);
+        assert!(state
+            .popup()
+            .expect("dry run popup")
+            .body
+            .contains("claude"));
+    }
+
+    #[test]
+    fn when_opening_mcp_servers_without_any_should_report_it() {
+        let config = config_with_project_at(Path::new("/tmp/api"));
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_selected_index(config.workspace["workspace-a"].projects.len() - 1);
+
+        handle_input(&mut state, &config, InputEvent::McpServers);
+
+        assert_eq!(state.mcp_servers(), None);
+        assert_eq!(
+            state.toast().unwrap().message,
+            "No MCP servers configured for tree"
+        );
+    }
+}
@@ -0,0 +1,597 @@
+//! Prompts of the TUI: single-line input and confirmations, and what their
+//! answers do, such as adding or removing a project, starting a task or
+//! filling an action's arguments. Projects found by workspace discovery are
+//! offered when a project is added.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use super::*;
+
+/// Directories found by watching the `discover` directories of the
+/// workspaces.
+#[derive(Default)]
+struct Discovery {
+    /// The watcher, created once a workspace has a `discover` directory.
+    watcher: Option<DiscoveryWatcher>,
+    /// The directories found, with their workspace, oldest first.
+    found: Vec<(String, PathBuf)>,
+}
+
+thread_local! {
+    static DISCOVERY: RefCell<Discovery> = RefCell::new(Discovery::default());
+}
+
+/// Handles input events while a prompt is displayed.
+///
+/// Typed characters edit the input, Tab completes directory paths, Enter
+/// moves to the next step or applies the edit, and Esc cancels. Any key other
+/// than 'y' cancels a removal or clone confirmation.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `event` - The input event to handle
+pub(super) fn handle_prompt_input(state: &mut AppState, config: &Config, event: InputEvent) {
+    let Some(prompt) = state.prompt_mut() else {
+        return;
+    };
+
+    if let PromptKind::ConfirmRemove { .. } | PromptKind::ConfirmClone { .. } = prompt.kind {
+        let kind = prompt.kind.clone();
+        match event {
+            InputEvent::Resize => {}
+            InputEvent::Char('y') => {
+                state.close_prompt();
+                match kind {
+                    PromptKind::ConfirmClone { project_index } => {
+                        start_clone(state, config, project_index)
+                    }
+                    PromptKind::ConfirmRemove {
+                        project_index,
+                        name,
+                        path,
+                    } => remove_project(state, config, project_index, &name, &path),
+                    _ => {}
+                }
+            }
+            _ => state.close_prompt(),
+        }
+        return;
+    }
+
+    if prompt.kind == PromptKind::ConfirmRestoreSession {
+        match event {
+            InputEvent::Resize => {}
+            InputEvent::Char('y') => {
+                state.close_prompt();
+                restore_session_backup(state, config);
+            }
+            _ => {
+                state.close_prompt();
+                SESSION_FILE.with(|f| f.borrow_mut().backup = None);
+                state.show_toast("Starting with a fresh session state", ToastLevel::Info);
+            }
+        }
+        return;
+    }
+
+    if let PromptKind::Argument {
+        target,
+        message,
+        mut answers,
+        recalled,
+    } = prompt.kind.clone()
+    {
+        match event {
+            InputEvent::Up | InputEvent::Down => {
+                let history = ArgumentHistory::load();
+                let recalled = recall(history.answers(&message), recalled, event == InputEvent::Up);
+                prompt.input = recalled
+                    .and_then(|index| history.answers(&message).get(index).cloned())
+                    .unwrap_or_default();
+                prompt.kind = PromptKind::Argument {
+                    target,
+                    message,
+                    answers,
+                    recalled,
+                };
+            }
+            InputEvent::Enter => {
+                let answer = prompt.input.clone();
+                let mut history = ArgumentHistory::load();
+                history.record(&message, &answer);
+                let _ = history.save();
+                answers.push(answer);
+                state.close_prompt();
+                match target {
+                    ArgumentTarget::Action(key) => handle_action(state, config, key, &answers),
+                    ArgumentTarget::CommandBarItem(index) => {
+                        run_command_bar_item(state, config, index, &answers)
+                    }
+                }
+            }
+            InputEvent::Back => state.close_prompt(),
+            InputEvent::Char(c) => prompt.input.push(c),
+            InputEvent::DeleteChar => {
+                prompt.input.pop();
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if let PromptKind::TaskWorktree {
+        project_index,
+        task,
+    } = prompt.kind.clone()
+    {
+        match event {
+            InputEvent::Char(c @ ('y' | 'n')) => {
+                state.close_prompt();
+                start_new_task(state, config, project_index, &task, c == 'y');
+            }
+            InputEvent::Back => state.close_prompt(),
+            _ => {}
+        }
+        return;
+    }
+
+    match event {
+        InputEvent::Char(c) => prompt.input.push(c),
+        InputEvent::DeleteChar => {
+            prompt.input.pop();
+        }
+        InputEvent::Complete => {
+            if matches!(prompt.kind, PromptKind::ProjectPath { .. }) {
+                prompt.input = util::path::complete_dir(&prompt.input);
+            }
+        }
+        InputEvent::Back => state.close_prompt(),
+        InputEvent::Enter => match prompt.kind.clone() {
+            PromptKind::ProjectName => {
+                let name = prompt.input.trim().to_string();
+                if name.is_empty() {
+                    state.show_toast("Project name cannot be empty", ToastLevel::Error);
+                } else {
+                    let path = current_workspace_id(state)
+                        .and_then(|workspace_id| discovered_project(config, workspace_id))
+                        .map(|dir| dir.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    state.open_prompt(PromptKind::ProjectPath { name }, "Project path:", path);
+                }
+            }
+            PromptKind::ProjectPath { name } => {
+                let path = util::path::expand_tilde(prompt.input.trim());
+                add_project(state, config, &name, &path);
+            }
+            PromptKind::SearchQuery => {
+                let query = prompt.input.clone();
+                start_search(state, config, &query);
+            }
+            PromptKind::TaskName { project_index } => {
+                let task = prompt.input.trim().to_string();
+                if crate::git::task::slug(&task).is_none() {
+                    state.show_toast("Task name needs letters or digits", ToastLevel::Error);
+                } else {
+                    let branch = task_branch(config, &task);
+                    let label = format!("Create a worktree for {}? (y/n)", branch);
+                    let kind = PromptKind::TaskWorktree {
+                        project_index,
+                        task,
+                    };
+                    state.open_prompt(kind, label, "");
+                }
+            }
+            PromptKind::ConfirmRemove { .. }
+            | PromptKind::ConfirmClone { .. }
+            | PromptKind::ConfirmRestoreSession
+            | PromptKind::TaskWorktree { .. }
+            | PromptKind::Argument { .. } => {}
+        },
+        _ => {}
+    }
+}
+
+/// Asks for the argument of a `{prompt:Message}` placeholder.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `target` - The command asking
+/// * `message` - The message of the placeholder
+/// * `answers` - The answers to the messages asked before
+pub(super) fn ask_argument(
+    state: &mut AppState,
+    target: ArgumentTarget,
+    message: String,
+    answers: Vec<String>,
+) {
+    let label = format!("{}:", message);
+    let kind = PromptKind::Argument {
+        target,
+        message,
+        answers,
+        recalled: None,
+    };
+    state.open_prompt(kind, label, "");
+}
+
+/// Moves through the answers given to a message before.
+///
+/// # Arguments
+///
+/// * `answers` - The answers, most recent first
+/// * `recalled` - The position of the answer shown, None for a new answer
+/// * `older` - Whether to move to an older answer (Up) or a newer one (Down)
+///
+/// # Returns
+///
+/// The position of the answer to show, None to go back to a new answer.
+pub(super) fn recall(answers: &[String], recalled: Option<usize>, older: bool) -> Option<usize> {
+    match (recalled, older) {
+        (_, true) if answers.is_empty() => None,
+        (None, true) => Some(0),
+        (Some(index), true) => Some((index + 1).min(answers.len() - 1)),
+        (None | Some(0), false) => None,
+        (Some(index), false) => Some(index - 1),
+    }
+}
+
+/// Returns the branch created for a task, e.g. `claude/fix-login`.
+fn task_branch(config: &Config, task: &str) -> String {
+    format!(
+        "{}{}",
+        config.global.task.branch_prefix,
+        crate::git::task::slug(task).unwrap_or_default()
+    )
+}
+
+/// Starts a Claude task on a project of the current workspace.
+///
+/// Creates the task's branch from HEAD, checked out in the project or in a
+/// worktree next to it, and opens a pane running the task command with the
+/// initial prompt there. In a dry run, the branch, directory and command are
+/// previewed and nothing is created.
+///
+/// # Arguments
+///
+/// * `state` - Mutable reference to the application state
+/// * `config` - Reference to the application configuration
+/// * `project_index` - The index of the project within the workspace
+/// * `task` - The task name, as typed
+/// * `worktree` - Whether the branch is checked out in a worktree
+fn start_new_task(
+    state: &mut AppState,
+    config: &Config,
+    project_index: usize,
+    task: &str,
+    worktree: bool,
+) {
+    let View::Projects { workspace_id } = state.current_view() else {
+        return;
+    };
+    let Some(project) = config
+        .workspace
+        .get(workspace_id)
+        .and_then(|w| w.projects.get(project_index))
+    else {
+        return;
+    };
+    let Some(slug) = crate::git::task::slug(task) else {
+        return;
+    };
+    let task_config = &config.global.task;
+    let branch = task_branch(config, task);
+    let worktree_dir = worktree.then(|| crate::git::task::worktree_path(&project.path, &slug));
+    let prompt = task_config.render_prompt(task, &branch, &project.name);
+    let pane_name = project_pane_name(config, workspace_id, project, &format!("Task {}", task));
+    let claude = match mcp::prepare_command(
+        config,
+        workspace_id,
+        project_index,
+        &task_config.command,
+        !state.dry_run(),
+    ) {
+        Ok(claude) => claude,
+        Err(e) => {
+            state.show_toast(e.to_string(), ToastLevel::Error);
+            return;
+        }
+    };
+    let task_command = |dir: &Path| {
+        project.wrap_env(&format!(
+            "cd {} && {} {}",
+            util::shell::quote(&dir.to_string_lossy()),
+            claude,
+            util::shell::quote(&prompt)
+        ))
+    };
+
+    if state.dry_run() {
+        let dir = worktree_dir.as_deref().unwrap_or(&project.path);
+        let checkout = match &worktree_dir {
+            Some(dir) => format!("Worktree: {}", dir.display()),
+            None => format!("Checked out in: {}", project.path.display()),
+        };
+        let command = task_command(dir);
+        let mut lines = vec![
+            format!("Branch: {}", branch),
+            checkout,
+            format!("Command: {}", command),
+            String::new(),
+        ];
+        let main_used = MAIN_PANE_USED.with(|m| *m.borrow());
+        lines.extend(dry_run_invocations(|| {
+            if main_used {
+                crate::zellij::run_in_floating_pane(&pane_name, &command).map(|_| ())
+            } else {
+                crate::zellij::run_in_main_pane(&pane_name, &command)
+            }
+        }));
+        state.show_popup(format!("Dry run: {}", pane_name), lines.join("\n"));
+        return;
+    }
+
+    match crate::git::task::start_task(&project.path, &branch, worktree_dir.as_deref()) {
+        Ok(dir) => {
+            // Shown first, so a pane that fails to open replaces it
+            state.show_toast(format!("Started {}", branch), ToastLevel::Info);
+            open_project_pane(state, &dir, pane_name, task_command(&dir));
+        }
+        Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+    }
+}
+
+/// Appends a project to the current workspace in the configuration file.
+///
+/// On success the prompt is closed, the configuration is reloaded and the new
+/// project is selected. On failure the prompt stays open so the input can be
+/// corrected.
+fn add_project(state: &mut AppState, config: &Config, name: &str, path: &Path) {
+    let View::Projects { workspace_id } = state.current_view() else {
+        return;
+    };
+    let project_count = config
+        .workspace
+        .get(workspace_id)
+        .map(|w| w.projects.len())
+        .unwrap_or(0);
+
+    match Config::add_project(&Config::default_path(), workspace_id, name, path) {
+        Ok(()) => {
+            state.close_prompt();
+            state.set_selected_index(project_count);
+            state.mark_config_changed();
+            state.show_toast(format!("Added project '{}'", name.trim()), ToastLevel::Info);
+        }
+        Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+    }
+}
+
+/// Watches the `discover` directories of the workspaces and offers the
+/// subdirectories created in them as projects, with a toast; `a` then
+/// fills in the add project prompt with the latest one.
+///
+/// Returns true if a directory was discovered.
+pub(super) fn poll_discovery(state: &mut AppState, config: &Config) -> bool {
+    let roots: Vec<(&String, PathBuf)> = config
+        .workspace
+        .iter()
+        .filter_map(|(id, w)| Some((id, util::path::expand_tilde(w.discover.as_deref()?))))
+        .collect();
+    if roots.is_empty() {
+        return false;
+    }
+    let found = DISCOVERY.with(|d| {
+        let mut discovery = d.borrow_mut();
+        let watcher = discovery.watcher.get_or_insert_with(DiscoveryWatcher::new);
+        for (workspace_id, root) in &roots {
+            watcher.watch(workspace_id, root);
+        }
+        watcher.drain_new()
+    });
+
+    let configured: HashSet<&Path> = config
+        .projects()
+        .into_iter()
+        .map(|p| p.path.as_path())
+        .collect();
+    let mut discovered = false;
+    for (workspace_id, dir) in found {
+        if configured.contains(dir.as_path()) {
+            continue;
+        }
+        let name = dir
+            .file_name()
+            .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        state.show_toast(
+            format!("New project detected: {} — press a to add", name),
+            ToastLevel::Info,
+        );
+        DISCOVERY.with(|d| d.borrow_mut().found.push((workspace_id, dir)));
+        discovered = true;
+    }
+    discovered
+}
+
+/// Returns the latest directory discovered for a workspace that isn't one
+/// of its projects yet.
+pub(super) fn discovered_project(config: &Config, workspace_id: &str) -> Option<PathBuf> {
+    let projects = &config.workspace.get(workspace_id)?.projects;
+    DISCOVERY.with(|d| {
+        d.borrow()
+            .found
+            .iter()
+            .rev()
+            .filter(|(id, _)| id == workspace_id)
+            .map(|(_, dir)| dir)
+            .find(|dir| !projects.iter().any(|p| &p.path == *dir))
+            .cloned()
+    })
+}
+
+/// Removes a project of the current workspace from the configuration file.
+fn remove_project(
+    state: &mut AppState,
+    config: &Config,
+    project_index: usize,
+    name: &str,
+    path: &Path,
+) {
+    let View::Projects { workspace_id } = state.current_view() else {
+        return;
+    };
+    let Some(workspace) = config.workspace.get(workspace_id) else {
+        return;
+    };
+    let remaining = workspace.projects.len().saturating_sub(1);
+
+    match Config::remove_project(&Config::default_path(), workspace_id, name, path) {
+        Ok(()) => {
+            state.set_selected_index(project_index.min(remaining.saturating_sub(1)));
+            state.mark_config_changed();
+            state.show_toast(format!("Removed project '{}'", name), ToastLevel::Info);
+        }
+        Err(e) => state.show_toast(e.to_string(), ToastLevel::Error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{config_with_project_at, create_test_config};
+    use super::*;
+
+    #[test]
+    fn when_entering_project_name_should_ask_for_path() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::AddProject);
+        for c in "api".chars() {
+            handle_input(&mut state, &config, InputEvent::Char(c));
+        }
+        handle_input(&mut state, &config, InputEvent::Enter);
+
+        let prompt = state.prompt().unwrap();
+        assert_eq!(
+            prompt.kind,
+            PromptKind::ProjectPath {
+                name: "api".to_string()
+            }
+        );
+        assert_eq!(prompt.input, "");
+    }
+
+    #[test]
+    fn when_directory_was_discovered_should_fill_in_the_new_project() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        DISCOVERY.with(|d| {
+            d.borrow_mut()
+                .found
+                .push(("workspace-a".to_string(), PathBuf::from("/work/billing")))
+        });
+
+        handle_input(&mut state, &config, InputEvent::AddProject);
+        assert_eq!(state.prompt().unwrap().input, "billing");
+        handle_input(&mut state, &config, InputEvent::Enter);
+
+        let prompt = state.prompt().unwrap();
+        assert_eq!(
+            prompt.kind,
+            PromptKind::ProjectPath {
+                name: "billing".to_string()
+            }
+        );
+        assert_eq!(prompt.input, "/work/billing");
+    }
+
+    #[test]
+    fn when_entering_empty_project_name_should_keep_prompt() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+
+        handle_input(&mut state, &config, InputEvent::AddProject);
+        handle_input(&mut state, &config, InputEvent::Char(' '));
+        handle_input(&mut state, &config, InputEvent::Enter);
+
+        assert_eq!(state.prompt().unwrap().kind, PromptKind::ProjectName);
+        assert!(state.toast().is_some());
+    }
+
+    #[test]
+    fn when_starting_task_in_dry_run_should_preview_branch_worktree_and_command() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join("api");
+        let config = config_with_project_at(&project);
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        state.set_selected_index(config.workspace["workspace-a"].projects.len() - 1);
+        state.set_dry_run(true);
+
+        handle_input(&mut state, &config, InputEvent::NewTask);
+        for c in "Fix login".chars() {
+            handle_input(&mut state, &config, InputEvent::Char(c));
+        }
+        handle_input(&mut state, &config, InputEvent::Enter);
+
+        let prompt = state.prompt().unwrap();
+        assert!(
+            prompt.label.contains("claude/fix-login"),
+            "{}",
+            prompt.label
+        );
+        assert_eq!(
+            prompt.kind,
+            PromptKind::TaskWorktree {
+                project_index: config.workspace["workspace-a"].projects.len() - 1,
+                task: "Fix login".to_string()
+            }
+        );
+
+        handle_input(&mut state, &config, InputEvent::Char('y'));
+
+        assert!(state.prompt().is_none());
+        let popup = state.popup().expect("dry run popup");
+        let worktree = dir.path().join("api-fix-login");
+        assert!(
+            popup.body.contains("Branch: claude/fix-login"),
+            "{}",
+            popup.body
+        );
+        assert!(popup
+            .body
+            .contains(&format!("Worktree: {}", worktree.display())));
+        assert!(popup.body.contains("claude 'Fix login'"), "{}", popup.body);
+        assert!(!worktree.exists());
+    }
+
+    #[test]
+    fn when_recalling_answers_should_move_through_the_history() {
+        let answers = vec!["dev".to_string(), "main".to_string()];
+
+        assert_eq!(recall(&answers, None, true), Some(0));
+        assert_eq!(recall(&answers, Some(0), true), Some(1));
+        assert_eq!(recall(&answers, Some(1), true), Some(1));
+        assert_eq!(recall(&answers, Some(1), false), Some(0));
+        assert_eq!(recall(&answers, Some(0), false), None);
+        assert_eq!(recall(&[], None, true), None);
+    }
+
+    #[test]
+    fn when_pressing_esc_in_prompt_should_cancel() {
+        let config = create_test_config();
+        let mut state = AppState::new();
+        state.navigate_to_workspace("workspace-a".to_string());
+        handle_input(&mut state, &config, InputEvent::AddProject);
+
+        handle_input(&mut state, &config, InputEvent::Back);
+
+        assert!(state.prompt().is_none());
+        assert!(!state.take_config_changed());
+    }
+}
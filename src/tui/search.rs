@@ -0,0 +1,257 @@
+//! Project-wide content search with ripgrep.
+//!
+//! `ProjectSearch` runs `rg --json` in a project on a background thread and
+//! streams its matches over a channel, so the results popup fills in while
+//! the search is still running.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+use serde_json::Value;
+
+/// Maximum number of matches kept; the search stops once reached.
+pub const MAX_MATCHES: usize = 1_000;
+
+/// A line matching the search query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// The file containing the match.
+    pub path: PathBuf,
+    /// The line number, starting at 1.
+    pub line: u32,
+    /// The matching line, without its line ending.
+    pub text: String,
+}
+
+/// A message sent by the search thread.
+enum Update {
+    Match(SearchMatch),
+    Failed(String),
+}
+
+/// A search running, or run, in a project.
+pub struct ProjectSearch {
+    query: String,
+    matches: Vec<SearchMatch>,
+    receiver: Receiver<Update>,
+    cancelled: Arc<AtomicBool>,
+    running: bool,
+    error: Option<String>,
+}
+
+impl ProjectSearch {
+    /// Starts searching the files of a project for a pattern.
+    ///
+    /// The query is a ripgrep regular expression, case-insensitive unless it
+    /// has uppercase letters. Ignored files are skipped like ripgrep does.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The directory to search
+    /// * `query` - The pattern to search for
+    pub fn spawn(project_path: &Path, query: &str) -> Self {
+        let mut cmd = Command::new("rg");
+        cmd.args(["--json", "--smart-case", "--"])
+            .arg(query)
+            .arg(project_path);
+        Self::spawn_command(query, cmd)
+    }
+
+    /// Starts a search running `cmd`, which must print ripgrep JSON lines.
+    fn spawn_command(query: &str, mut cmd: Command) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let stop = cancelled.clone();
+
+        thread::spawn(move || {
+            let child = cmd
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn();
+            let mut child = match child {
+                Ok(child) => child,
+                Err(e) => {
+                    let message = if e.kind() == std::io::ErrorKind::NotFound {
+                        "ripgrep (rg) not found".to_string()
+                    } else {
+                        format!("Failed to run rg: {}", e)
+                    };
+                    let _ = sender.send(Update::Failed(message));
+                    return;
+                }
+            };
+
+            let mut sent = 0;
+            if let Some(stdout) = child.stdout.take() {
+                for line in BufReader::new(stdout).lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    if stop.load(Ordering::Relaxed) || sent >= MAX_MATCHES {
+                        break;
+                    }
+                    if let Some(found) = parse_match(&line) {
+                        if sender.send(Update::Match(found)).is_err() {
+                            break;
+                        }
+                        sent += 1;
+                    }
+                }
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+        });
+
+        Self {
+            query: query.to_string(),
+            matches: Vec::new(),
+            receiver,
+            cancelled,
+            running: true,
+            error: None,
+        }
+    }
+
+    /// Returns the query searched for.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the matches found so far, in ripgrep's order.
+    pub fn matches(&self) -> &[SearchMatch] {
+        &self.matches
+    }
+
+    /// Returns whether the search is still running.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Returns why the search failed, if it did.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Stops ripgrep, keeping the matches found so far.
+    pub fn stop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Collects the matches found since the last poll.
+    ///
+    /// # Returns
+    ///
+    /// True if matches arrived or the search finished.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Update::Match(found)) => self.matches.push(found),
+                Ok(Update::Failed(message)) => self.error = Some(message),
+                Err(TryRecvError::Empty) => return changed,
+                Err(TryRecvError::Disconnected) => {
+                    let finished = self.running;
+                    self.running = false;
+                    return changed || finished;
+                }
+            }
+            changed = true;
+        }
+    }
+}
+
+impl Drop for ProjectSearch {
+    fn drop(&mut self) {
+        // Stop ripgrep when the results are discarded
+        self.stop();
+    }
+}
+
+/// Parses a line of `rg --json` output.
+///
+/// # Returns
+///
+/// The match, or None for other messages (begin, end, context, summary).
+pub fn parse_match(line: &str) -> Option<SearchMatch> {
+    let message: Value = serde_json::from_str(line).ok()?;
+    if message["type"] != "match" {
+        return None;
+    }
+    let data = &message["data"];
+    let path = data["path"]["text"].as_str()?;
+    let line_number = data["line_number"].as_u64()?;
+    let text = data["lines"]["text"].as_str().unwrap_or_default();
+    Some(SearchMatch {
+        path: PathBuf::from(path),
+        line: u32::try_from(line_number).ok()?,
+        text: text.trim_end_matches(['\n', '\r']).replace('\t', "    "),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    const MATCH: &str = r#"{"type":"match","data":{"path":{"text":"/work/api/src/main.rs"},"lines":{"text":"\tfn main() {\n"},"line_number":12,"absolute_offset":100,"submatches":[{"match":{"text":"main"},"start":4,"end":8}]}}"#;
+
+    fn wait_until_done(search: &mut ProjectSearch) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while search.is_running() && Instant::now() < deadline {
+            search.poll();
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn when_parsing_match_should_return_path_line_and_text() {
+        assert_eq!(
+            parse_match(MATCH),
+            Some(SearchMatch {
+                path: PathBuf::from("/work/api/src/main.rs"),
+                line: 12,
+                text: "    fn main() {".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn when_parsing_other_messages_should_skip_them() {
+        let begin = r#"{"type":"begin","data":{"path":{"text":"/work/api/src/main.rs"}}}"#;
+
+        assert_eq!(parse_match(begin), None);
+        assert_eq!(parse_match("not json"), None);
+    }
+
+    #[test]
+    fn when_search_prints_matches_should_stream_them() {
+        let mut cmd = Command::new("printf");
+        cmd.args(["%s\n", MATCH, MATCH]);
+        let mut search = ProjectSearch::spawn_command("main", cmd);
+
+        wait_until_done(&mut search);
+
+        assert!(!search.is_running());
+        assert_eq!(search.matches().len(), 2);
+        assert_eq!(search.error(), None);
+    }
+
+    #[test]
+    fn when_rg_is_missing_should_report_it() {
+        let cmd = Command::new("gz-claude-missing-rg");
+        let mut search = ProjectSearch::spawn_command("main", cmd);
+
+        wait_until_done(&mut search);
+
+        assert_eq!(search.error(), Some("ripgrep (rg) not found"));
+        assert!(search.matches().is_empty());
+    }
+}
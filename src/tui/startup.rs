@@ -0,0 +1,145 @@
+//! Deferred startup checks for fast panel boot.
+//!
+//! With `panel --fast`, project paths are not checked before the TUI starts.
+//! `StartupChecks` checks them on a background thread after the first frame
+//! and computes the projects' git information on the way, so the Projects
+//! view doesn't block on it later.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::path::PathBuf;
+use std::thread::{self, JoinHandle};
+
+use crate::config::{check_project_path, Config};
+use crate::error::ConfigError;
+use crate::git::{get_git_info_compared, GitCompare, GitInfo};
+
+/// The outcome of the deferred startup checks.
+#[derive(Debug, Default)]
+pub struct StartupReport {
+    /// Project paths that don't exist (and can't be cloned) or aren't directories.
+    pub path_problems: Vec<ConfigError>,
+    /// Git information of the projects whose path is valid.
+    pub git_info: Vec<(PathBuf, Option<GitInfo>)>,
+}
+
+/// Startup checks running in the background.
+pub struct StartupChecks {
+    handle: Option<JoinHandle<StartupReport>>,
+}
+
+impl StartupChecks {
+    /// Starts checking every project of the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Reference to the application configuration
+    pub fn spawn(config: &Config) -> Self {
+        let level = config.global.git_info_level;
+        let projects: Vec<(PathBuf, GitCompare, bool)> = config
+            .projects()
+            .into_iter()
+            .map(|p| {
+                (
+                    p.path.clone(),
+                    GitCompare::resolve(config, p),
+                    p.repo.is_some(),
+                )
+            })
+            .collect();
+
+        let handle = thread::spawn(move || {
+            let mut report = StartupReport::default();
+            for (path, compare, has_repo) in projects {
+                // Missing projects with a repository are offered to be cloned
+                if has_repo && !path.exists() {
+                    continue;
+                }
+                match check_project_path(&path) {
+                    Ok(()) => {
+                        let info = get_git_info_compared(&path, level, &compare);
+                        report.git_info.push((path, info));
+                    }
+                    Err(e) => report.path_problems.push(e),
+                }
+            }
+            report
+        });
+
+        Self {
+            handle: Some(handle),
+        }
+    }
+
+    /// Returns whether the checks are still running.
+    pub fn is_running(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// Takes the report once the checks have finished.
+    ///
+    /// # Returns
+    ///
+    /// The report the first time it's called after the checks finished,
+    /// None otherwise.
+    pub fn poll(&mut self) -> Option<StartupReport> {
+        if !self.handle.as_ref()?.is_finished() {
+            return None;
+        }
+        // A panicking check reports nothing rather than bringing the TUI down
+        Some(self.handle.take()?.join().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn wait_for_report(checks: &mut StartupChecks) -> StartupReport {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            if let Some(report) = checks.poll() {
+                return report;
+            }
+            assert!(Instant::now() < deadline, "startup checks did not finish");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn when_checking_projects_should_report_bad_paths_and_git_info() {
+        let dir = TempDir::new().unwrap();
+        let content = format!(
+            r#"{{
+                "global": {{}},
+                "workspace": {{
+                    "test": {{
+                        "name": "Test",
+                        "projects": [
+                            {{ "name": "ok", "path": "{}" }},
+                            {{ "name": "gone", "path": "/nonexistent/gz-claude/project" }}
+                        ]
+                    }}
+                }}
+            }}"#,
+            dir.path().display()
+        );
+        let config: Config = serde_json::from_str(&content).unwrap();
+        let mut checks = StartupChecks::spawn(&config);
+
+        let report = wait_for_report(&mut checks);
+
+        assert!(!checks.is_running());
+        assert_eq!(report.path_problems.len(), 1);
+        assert!(matches!(
+            report.path_problems[0],
+            ConfigError::PathNotFound { .. }
+        ));
+        assert_eq!(report.git_info.len(), 1);
+        assert_eq!(report.git_info[0].0, dir.path());
+        assert!(report.git_info[0].1.is_none());
+        assert!(checks.poll().is_none());
+    }
+}
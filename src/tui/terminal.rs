@@ -36,6 +36,9 @@ pub enum InputEvent {
     Right,
     /// Confirm selection (Enter).
     Enter,
+    /// On a project, do what Enter doesn't: run its default action or
+    /// browse its files (Alt-Enter).
+    AltEnter,
     /// Navigate back (Esc or Backspace).
     Back,
     /// Quit the application ('q').
@@ -44,45 +47,158 @@ pub enum InputEvent {
     Refresh,
     /// Toggle command bar visibility (':').
     ToggleCommandBar,
+    /// Copy context-appropriate text to the clipboard ('y').
+    Yank,
+    /// Copy the web client URL to the clipboard ('Y').
+    YankWebUrl,
+    /// Open the Claude Code conversations of the selected project ('C').
+    Conversations,
+    /// Show or hide hidden and ignored entries in the file browser ('H').
+    ToggleIgnored,
+    /// Show or hide the file preview in the file browser (Tab).
+    TogglePreview,
+    /// Show the output of the selected project's health check ('E').
+    HealthDetails,
+    /// Open the git UI for the selected project or file ('G').
+    GitUi,
+    /// Open the workspace switcher ('w').
+    WorkspaceSwitcher,
+    /// Jump to the workspace at the given zero-based position ('1'-'9').
+    SwitchWorkspace(usize),
+    /// Open the recent files of the selected project ('o').
+    RecentFiles,
+    /// Open the pull requests of the selected project ('P').
+    PullRequests,
+    /// Enable or disable the MCP servers of the selected project ('M').
+    McpServers,
+    /// Open the menu of everything that can be done with the selected
+    /// project ('m').
+    ProjectMenu,
+    /// Show the selected project or file in the file manager ('O').
+    RevealInFileManager,
+    /// Show the blame of the selected file ('b').
+    Blame,
+    /// Open the selected project's remote repository in the browser ('B').
+    OpenRemote,
+    /// Fetch the selected project's remote in the background ('F').
+    Fetch,
+    /// Recompute the git info of every project in the workspace (Ctrl-R).
+    RefreshAll,
+    /// Present the next project pane to the web client ('f').
+    CycleWebFocus,
+    /// Close the selected project's pane ('x').
+    KillPane,
+    /// Close and relaunch the selected project's pane ('R').
+    RestartPane,
+    /// Toggle fullscreen of the main pane ('z').
+    ZoomMainPane,
+    /// Move the selected project's floating pane into the layout, or back ('e').
+    ToggleEmbedPane,
+    /// Move every project pane to a tab of its own ('A').
+    ArrangePanes,
+    /// Start a Claude task on a new branch of the selected project ('n').
+    NewTask,
+    /// Turn dry run mode on or off ('N').
+    ToggleDryRun,
+    /// Open a shell in the directory of the selected file ('s').
+    OpenShell,
+    /// Open the most recent log file of the selected project ('L').
+    OpenLatestLog,
+    /// Search the contents of the project's files ('/').
+    Search,
+    /// Jump to a file of the project by fuzzy name (Ctrl-T).
+    FileFinder,
+    /// Open the command palette (Ctrl-K).
+    CommandPalette,
+    /// Add a project to the current workspace ('a').
+    AddProject,
+    /// Remove the selected project from the current workspace ('D').
+    RemoveProject,
+    /// A character typed into a prompt.
+    Char(char),
+    /// Delete the last character of a prompt (Backspace).
+    DeleteChar,
+    /// Complete the text of a prompt (Tab).
+    Complete,
+    /// The terminal was resized.
+    Resize,
     /// Custom action triggered by a character key.
     Action(char),
 }
 
+/// Keeps the terminal in raw mode, and optionally on the alternate screen,
+/// for as long as it lives.
+///
+/// The terminal is restored when the guard is dropped: on return, on an early
+/// `?` and while unwinding from a panic, so a failing loop never leaves the
+/// shell without echo.
+pub struct TerminalGuard {
+    alternate_screen: bool,
+}
+
+impl TerminalGuard {
+    /// Enables raw mode, for reading keys one by one.
+    ///
+    /// # Returns
+    ///
+    /// A guard that disables raw mode when dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if raw mode cannot be enabled.
+    pub fn raw_mode() -> Result<Self> {
+        enable_raw_mode()?;
+        Ok(Self {
+            alternate_screen: false,
+        })
+    }
+
+    /// Enables raw mode and enters the alternate screen, for a full screen UI.
+    ///
+    /// # Returns
+    ///
+    /// A guard that leaves the alternate screen and disables raw mode when
+    /// dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if raw mode or the alternate screen cannot be entered;
+    /// raw mode is disabled again in the latter case.
+    pub fn alternate_screen() -> Result<Self> {
+        let mut guard = Self::raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        guard.alternate_screen = true;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Restoring is best effort: there is nowhere to report a failure
+        let _ = disable_raw_mode();
+        if self.alternate_screen {
+            let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        }
+    }
+}
+
 /// Initializes the terminal for TUI rendering.
 ///
 /// Enters alternate screen mode and enables raw mode for direct keyboard input.
 ///
 /// # Returns
 ///
-/// A configured Terminal instance ready for rendering.
+/// The guard restoring the terminal when dropped, and a configured Terminal
+/// instance ready for rendering. Keep the guard alive while rendering.
 ///
 /// # Errors
 ///
 /// Returns an error if terminal initialization fails.
-pub fn init() -> Result<Tui> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
+pub fn init() -> Result<(TerminalGuard, Tui)> {
+    let guard = TerminalGuard::alternate_screen()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let terminal = Terminal::new(backend)?;
-    Ok(terminal)
-}
-
-/// Restores the terminal to its original state.
-///
-/// Leaves alternate screen mode and disables raw mode.
-///
-/// # Returns
-///
-/// Ok(()) on successful restoration.
-///
-/// # Errors
-///
-/// Returns an error if terminal restoration fails.
-pub fn restore() -> Result<()> {
-    disable_raw_mode()?;
-    execute!(io::stdout(), LeaveAlternateScreen)?;
-    Ok(())
+    Ok((guard, terminal))
 }
 
 /// Polls for input events with a timeout.
@@ -90,21 +206,28 @@ pub fn restore() -> Result<()> {
 /// Non-blocking poll that returns immediately if no event is available
 /// within the specified timeout.
 ///
+/// In text input mode, keys are read as text instead of being mapped to
+/// bindings.
+///
 /// # Arguments
 ///
 /// * `timeout_ms` - Maximum time to wait for an event in milliseconds
+/// * `text_input` - Whether a prompt is reading text
 ///
 /// # Returns
 ///
-/// Some(InputEvent) if an event was received, None if timeout occurred.
+/// Some(InputEvent) if a key or resize event was received, None if timeout occurred.
 ///
 /// # Errors
 ///
 /// Returns an error if event polling fails.
-pub fn poll_event(timeout_ms: u64) -> Result<Option<InputEvent>> {
+pub fn poll_event(timeout_ms: u64, text_input: bool) -> Result<Option<InputEvent>> {
     if event::poll(Duration::from_millis(timeout_ms))? {
-        if let Event::Key(key_event) = event::read()? {
-            return Ok(key_to_event(key_event));
+        match event::read()? {
+            Event::Key(key_event) if text_input => return Ok(text_key_to_event(key_event)),
+            Event::Key(key_event) => return Ok(key_to_event(key_event)),
+            Event::Resize(_, _) => return Ok(Some(InputEvent::Resize)),
+            _ => {}
         }
     }
     Ok(None)
@@ -127,8 +250,19 @@ fn key_to_event(key: KeyEvent) -> Option<InputEvent> {
         KeyCode::Down => Some(InputEvent::Down),
         KeyCode::Left => Some(InputEvent::Left),
         KeyCode::Right => Some(InputEvent::Right),
+        KeyCode::Enter if key.modifiers == KeyModifiers::ALT => Some(InputEvent::AltEnter),
         KeyCode::Enter => Some(InputEvent::Enter),
         KeyCode::Esc | KeyCode::Backspace => Some(InputEvent::Back),
+        KeyCode::Tab => Some(InputEvent::TogglePreview),
+        KeyCode::Char('r') if key.modifiers == KeyModifiers::CONTROL => {
+            Some(InputEvent::RefreshAll)
+        }
+        KeyCode::Char('t') if key.modifiers == KeyModifiers::CONTROL => {
+            Some(InputEvent::FileFinder)
+        }
+        KeyCode::Char('k') if key.modifiers == KeyModifiers::CONTROL => {
+            Some(InputEvent::CommandPalette)
+        }
         KeyCode::Char(c) => {
             if key.modifiers.is_empty() || key.modifiers == KeyModifiers::SHIFT {
                 match c {
@@ -139,6 +273,35 @@ fn key_to_event(key: KeyEvent) -> Option<InputEvent> {
                     'q' => Some(InputEvent::Quit),
                     'r' => Some(InputEvent::Refresh),
                     ':' => Some(InputEvent::ToggleCommandBar),
+                    'y' => Some(InputEvent::Yank),
+                    'Y' => Some(InputEvent::YankWebUrl),
+                    'C' => Some(InputEvent::Conversations),
+                    'H' => Some(InputEvent::ToggleIgnored),
+                    'E' => Some(InputEvent::HealthDetails),
+                    'G' => Some(InputEvent::GitUi),
+                    'w' => Some(InputEvent::WorkspaceSwitcher),
+                    'a' => Some(InputEvent::AddProject),
+                    'f' => Some(InputEvent::CycleWebFocus),
+                    'o' => Some(InputEvent::RecentFiles),
+                    'P' => Some(InputEvent::PullRequests),
+                    'M' => Some(InputEvent::McpServers),
+                    'm' => Some(InputEvent::ProjectMenu),
+                    'O' => Some(InputEvent::RevealInFileManager),
+                    'b' => Some(InputEvent::Blame),
+                    'B' => Some(InputEvent::OpenRemote),
+                    'F' => Some(InputEvent::Fetch),
+                    'x' => Some(InputEvent::KillPane),
+                    'R' => Some(InputEvent::RestartPane),
+                    'z' => Some(InputEvent::ZoomMainPane),
+                    'e' => Some(InputEvent::ToggleEmbedPane),
+                    'A' => Some(InputEvent::ArrangePanes),
+                    'n' => Some(InputEvent::NewTask),
+                    'N' => Some(InputEvent::ToggleDryRun),
+                    's' => Some(InputEvent::OpenShell),
+                    'L' => Some(InputEvent::OpenLatestLog),
+                    '/' => Some(InputEvent::Search),
+                    'D' => Some(InputEvent::RemoveProject),
+                    '1'..='9' => Some(InputEvent::SwitchWorkspace(c as usize - '1' as usize)),
                     _ => Some(InputEvent::Action(c)),
                 }
             } else {
@@ -149,6 +312,33 @@ fn key_to_event(key: KeyEvent) -> Option<InputEvent> {
     }
 }
 
+/// Converts a KeyEvent to an InputEvent while a prompt reads text.
+///
+/// Printable characters are returned as text; only Enter, Esc, Backspace,
+/// Tab and the up/down arrows keep a special meaning.
+///
+/// # Arguments
+///
+/// * `key` - The keyboard event to convert
+///
+/// # Returns
+///
+/// Some(InputEvent) for recognized keys, None for unhandled keys.
+fn text_key_to_event(key: KeyEvent) -> Option<InputEvent> {
+    match key.code {
+        KeyCode::Enter => Some(InputEvent::Enter),
+        KeyCode::Esc => Some(InputEvent::Back),
+        KeyCode::Backspace => Some(InputEvent::DeleteChar),
+        KeyCode::Tab => Some(InputEvent::Complete),
+        KeyCode::Up => Some(InputEvent::Up),
+        KeyCode::Down => Some(InputEvent::Down),
+        KeyCode::Char(c) if key.modifiers.is_empty() || key.modifiers == KeyModifiers::SHIFT => {
+            Some(InputEvent::Char(c))
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,15 +395,98 @@ mod tests {
         assert_eq!(key_to_event(r_key), Some(InputEvent::Refresh));
     }
 
+    #[test]
+    fn when_pressing_ctrl_r_should_return_refresh_all_event() {
+        let ctrl_r = create_key_event(KeyCode::Char('r'), KeyModifiers::CONTROL);
+
+        assert_eq!(key_to_event(ctrl_r), Some(InputEvent::RefreshAll));
+    }
+
+    #[test]
+    fn when_pressing_ctrl_t_should_open_file_finder() {
+        let ctrl_t = create_key_event(KeyCode::Char('t'), KeyModifiers::CONTROL);
+
+        assert_eq!(key_to_event(ctrl_t), Some(InputEvent::FileFinder));
+    }
+
+    #[test]
+    fn when_pressing_ctrl_k_should_open_command_palette() {
+        let ctrl_k = create_key_event(KeyCode::Char('k'), KeyModifiers::CONTROL);
+
+        assert_eq!(key_to_event(ctrl_k), Some(InputEvent::CommandPalette));
+    }
+
     #[test]
     fn when_pressing_other_char_should_return_action_event() {
-        let a_key = create_key_event(KeyCode::Char('a'), KeyModifiers::NONE);
-        let x_key = create_key_event(KeyCode::Char('x'), KeyModifiers::NONE);
+        let c_key = create_key_event(KeyCode::Char('c'), KeyModifiers::NONE);
+        let v_key = create_key_event(KeyCode::Char('v'), KeyModifiers::NONE);
+        let zero_key = create_key_event(KeyCode::Char('0'), KeyModifiers::NONE);
+
+        assert_eq!(key_to_event(c_key), Some(InputEvent::Action('c')));
+        assert_eq!(key_to_event(v_key), Some(InputEvent::Action('v')));
+        assert_eq!(key_to_event(zero_key), Some(InputEvent::Action('0')));
+    }
+
+    #[test]
+    fn when_pressing_pane_layout_keys_should_return_their_events() {
+        let z_key = create_key_event(KeyCode::Char('z'), KeyModifiers::NONE);
+        let e_key = create_key_event(KeyCode::Char('e'), KeyModifiers::NONE);
+        let a_key = create_key_event(KeyCode::Char('A'), KeyModifiers::SHIFT);
+
+        assert_eq!(key_to_event(z_key), Some(InputEvent::ZoomMainPane));
+        assert_eq!(key_to_event(e_key), Some(InputEvent::ToggleEmbedPane));
+        assert_eq!(key_to_event(a_key), Some(InputEvent::ArrangePanes));
+    }
+
+    #[test]
+    fn when_pressing_shift_n_should_toggle_dry_run() {
+        let n_key = create_key_event(KeyCode::Char('N'), KeyModifiers::SHIFT);
+
+        assert_eq!(key_to_event(n_key), Some(InputEvent::ToggleDryRun));
+    }
+
+    #[test]
+    fn when_pressing_alt_enter_should_return_alt_enter_event() {
+        let alt_enter = create_key_event(KeyCode::Enter, KeyModifiers::ALT);
+
+        assert_eq!(key_to_event(alt_enter), Some(InputEvent::AltEnter));
+    }
+
+    #[test]
+    fn when_pressing_n_should_start_new_task() {
+        let n_key = create_key_event(KeyCode::Char('n'), KeyModifiers::NONE);
+
+        assert_eq!(key_to_event(n_key), Some(InputEvent::NewTask));
+    }
+
+    #[test]
+    fn when_pressing_s_should_open_shell() {
+        let s_key = create_key_event(KeyCode::Char('s'), KeyModifiers::NONE);
+
+        assert_eq!(key_to_event(s_key), Some(InputEvent::OpenShell));
+    }
+
+    #[test]
+    fn when_pressing_shift_l_should_open_latest_log() {
+        let l_key = create_key_event(KeyCode::Char('L'), KeyModifiers::SHIFT);
+
+        assert_eq!(key_to_event(l_key), Some(InputEvent::OpenLatestLog));
+    }
+
+    #[test]
+    fn when_pressing_slash_should_search() {
+        let slash_key = create_key_event(KeyCode::Char('/'), KeyModifiers::NONE);
+
+        assert_eq!(key_to_event(slash_key), Some(InputEvent::Search));
+    }
+
+    #[test]
+    fn when_pressing_digit_should_return_switch_workspace_event() {
         let one_key = create_key_event(KeyCode::Char('1'), KeyModifiers::NONE);
+        let nine_key = create_key_event(KeyCode::Char('9'), KeyModifiers::NONE);
 
-        assert_eq!(key_to_event(a_key), Some(InputEvent::Action('a')));
-        assert_eq!(key_to_event(x_key), Some(InputEvent::Action('x')));
-        assert_eq!(key_to_event(one_key), Some(InputEvent::Action('1')));
+        assert_eq!(key_to_event(one_key), Some(InputEvent::SwitchWorkspace(0)));
+        assert_eq!(key_to_event(nine_key), Some(InputEvent::SwitchWorkspace(8)));
     }
 
     #[test]
@@ -234,10 +507,51 @@ mod tests {
         assert_eq!(key_to_event(l_key), Some(InputEvent::Right));
     }
 
+    #[test]
+    fn when_pressing_tab_should_return_toggle_preview_event() {
+        let tab_key = create_key_event(KeyCode::Tab, KeyModifiers::NONE);
+
+        assert_eq!(key_to_event(tab_key), Some(InputEvent::TogglePreview));
+    }
+
     #[test]
     fn when_pressing_colon_should_return_toggle_command_bar_event() {
         let colon_key = create_key_event(KeyCode::Char(':'), KeyModifiers::SHIFT);
 
         assert_eq!(key_to_event(colon_key), Some(InputEvent::ToggleCommandBar));
     }
+
+    #[test]
+    fn when_pressing_y_should_return_yank_events() {
+        let y_key = create_key_event(KeyCode::Char('y'), KeyModifiers::NONE);
+
+        let shift_y_key = create_key_event(KeyCode::Char('Y'), KeyModifiers::SHIFT);
+
+        assert_eq!(key_to_event(y_key), Some(InputEvent::Yank));
+        assert_eq!(key_to_event(shift_y_key), Some(InputEvent::YankWebUrl));
+    }
+
+    #[test]
+    fn when_reading_text_should_return_typed_characters() {
+        let k_key = create_key_event(KeyCode::Char('k'), KeyModifiers::NONE);
+        let backspace_key = create_key_event(KeyCode::Backspace, KeyModifiers::NONE);
+        let tab_key = create_key_event(KeyCode::Tab, KeyModifiers::NONE);
+
+        assert_eq!(text_key_to_event(k_key), Some(InputEvent::Char('k')));
+        assert_eq!(
+            text_key_to_event(backspace_key),
+            Some(InputEvent::DeleteChar)
+        );
+        assert_eq!(text_key_to_event(tab_key), Some(InputEvent::Complete));
+        let up_key = create_key_event(KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(text_key_to_event(up_key), Some(InputEvent::Up));
+    }
+
+    #[test]
+    fn when_pressing_reserved_key_should_not_trigger_action() {
+        for &c in crate::config::RESERVED_KEYS {
+            let event = key_to_event(create_key_event(KeyCode::Char(c), KeyModifiers::NONE));
+            assert_ne!(event, Some(InputEvent::Action(c)), "key '{}'", c);
+        }
+    }
 }
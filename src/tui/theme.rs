@@ -0,0 +1,87 @@
+//! Color presets of the panel.
+//!
+//! Views draw with the default colors; a preset other than the default
+//! remaps the colors of each rendered frame, so views don't need to know
+//! about it.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+
+use crate::config::ThemePreset;
+
+/// Remaps the colors of a rendered frame to a preset.
+///
+/// # Arguments
+///
+/// * `buffer` - The rendered frame
+/// * `preset` - The `ui.theme` setting
+pub fn apply(buffer: &mut Buffer, preset: ThemePreset) {
+    match preset {
+        ThemePreset::Default => {}
+        ThemePreset::HighContrast => {
+            for cell in buffer.content.iter_mut() {
+                cell.fg = high_contrast(cell.fg);
+            }
+        }
+    }
+}
+
+/// Returns the high contrast variant of a text color.
+///
+/// Grays become white and regular colors their bright variant; the
+/// terminal's default, black and true colors (file previews) are kept.
+///
+/// # Arguments
+///
+/// * `color` - The color drawn by the default preset
+pub fn high_contrast(color: Color) -> Color {
+    match color {
+        Color::DarkGray | Color::Gray => Color::White,
+        Color::Red => Color::LightRed,
+        Color::Green => Color::LightGreen,
+        Color::Yellow => Color::LightYellow,
+        Color::Blue => Color::LightBlue,
+        Color::Magenta => Color::LightMagenta,
+        Color::Cyan => Color::LightCyan,
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn when_high_contrast_should_brighten_text_colors() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 1));
+        buffer.set_string(0, 0, "a", Style::default().fg(Color::DarkGray));
+        buffer.set_string(1, 0, "b", Style::default().fg(Color::Cyan));
+        buffer.set_string(
+            2,
+            0,
+            "c",
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        );
+
+        apply(&mut buffer, ThemePreset::HighContrast);
+
+        assert_eq!(buffer.content[0].fg, Color::White);
+        assert_eq!(buffer.content[1].fg, Color::LightCyan);
+        assert_eq!(buffer.content[2].fg, Color::Black);
+        assert_eq!(buffer.content[2].bg, Color::Yellow);
+    }
+
+    #[test]
+    fn when_default_preset_should_keep_colors() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 1, 1));
+        buffer.set_string(0, 0, "a", Style::default().fg(Color::DarkGray));
+
+        apply(&mut buffer, ThemePreset::Default);
+
+        assert_eq!(buffer.content[0].fg, Color::DarkGray);
+    }
+}
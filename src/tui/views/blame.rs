@@ -0,0 +1,121 @@
+//! Blame popup component for the TUI.
+//!
+//! Shows the top contributors of a file, then a header per hunk with the
+//! lines it covers, its commit, author and age, scrolled from the top.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::git::blame::{Blame, BlameHunk};
+use crate::tui::views::popup::centered;
+use crate::util::format_age;
+use crate::util::glyphs::glyph;
+
+/// Percentage of the available width and height covered by the popup.
+const BLAME_PERCENT: u16 = 80;
+
+/// Number of contributors listed.
+const TOP_CONTRIBUTORS: usize = 5;
+
+/// View component for the blame popup.
+pub struct BlameView<'a> {
+    blame: &'a Blame,
+    scroll: usize,
+}
+
+impl<'a> BlameView<'a> {
+    /// Creates a new BlameView.
+    ///
+    /// # Arguments
+    ///
+    /// * `blame` - The blame of the file
+    /// * `scroll` - Index of the first line shown
+    pub fn new(blame: &'a Blame, scroll: usize) -> Self {
+        Self { blame, scroll }
+    }
+
+    /// Returns all the lines of the popup, before scrolling.
+    pub fn lines(&self) -> Vec<Line<'a>> {
+        let heading = Style::default().add_modifier(Modifier::BOLD);
+        let dim = Style::default().fg(Color::DarkGray);
+        let total = self.blame.line_count().max(1);
+
+        let mut lines = vec![Line::styled("Top contributors", heading)];
+        lines.extend(
+            self.blame
+                .contributors
+                .iter()
+                .take(TOP_CONTRIBUTORS)
+                .map(|contributor| {
+                    Line::from(vec![
+                        Span::raw(format!("  {:<24}", contributor.name)),
+                        Span::styled(
+                            format!(
+                                "{} lines ({}%)",
+                                contributor.lines,
+                                contributor.lines * 100 / total
+                            ),
+                            dim,
+                        ),
+                    ])
+                }),
+        );
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("Hunks", heading));
+        lines.extend(self.blame.hunks.iter().map(hunk_line));
+        lines
+    }
+
+    /// Renders the popup centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the popup is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered(area, BLAME_PERCENT);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(
+                " Blame {} {} ",
+                glyph("·", "-"),
+                self.blame.path.display()
+            ))
+            .title_bottom(format!(" {}: scroll  Esc: close ", glyph("↑/↓", "Up/Down")))
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+
+        let lines: Vec<Line> = self.lines().into_iter().skip(self.scroll).collect();
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+    }
+}
+
+/// Returns the header line of a hunk, e.g. "L12-30  1a2b3c4  Ana  3d ago  Fix login".
+fn hunk_line(hunk: &BlameHunk) -> Line<'static> {
+    let end = hunk.start_line + hunk.lines.saturating_sub(1);
+    let range = if end > hunk.start_line {
+        format!("L{}-{}", hunk.start_line, end)
+    } else {
+        format!("L{}", hunk.start_line)
+    };
+    let (commit, color) = match &hunk.commit {
+        Some(commit) => (commit.clone(), Color::Yellow),
+        None => ("-------".to_string(), Color::Green),
+    };
+    let age = hunk.time.map(format_age).unwrap_or_default();
+    Line::from(vec![
+        Span::styled(format!("  {:<10}", range), Style::default().fg(Color::Cyan)),
+        Span::styled(format!("{}  ", commit), Style::default().fg(color)),
+        Span::styled(format!("{:<20}", hunk.author), Style::default().fg(color)),
+        Span::styled(format!("{:<10}", age), Style::default().fg(Color::DarkGray)),
+        Span::raw(hunk.summary.clone()),
+    ])
+}
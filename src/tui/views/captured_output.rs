@@ -0,0 +1,95 @@
+//! Captured output popup component for the TUI.
+//!
+//! Shows the output of a command bar item run with `capture` set, scrolled
+//! from the top.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::tui::capture::CommandCapture;
+use crate::tui::views::popup::centered;
+use crate::util::glyphs::glyph;
+
+/// Percentage of the available width and height covered by the popup.
+const CAPTURED_OUTPUT_PERCENT: u16 = 80;
+
+/// View component for the captured output popup.
+pub struct CapturedOutputView<'a> {
+    capture: &'a CommandCapture,
+    scroll: usize,
+}
+
+impl<'a> CapturedOutputView<'a> {
+    /// Creates a new CapturedOutputView.
+    ///
+    /// # Arguments
+    ///
+    /// * `capture` - The captured command
+    /// * `scroll` - Index of the first output line shown
+    ///
+    /// # Returns
+    ///
+    /// A new CapturedOutputView instance.
+    pub fn new(capture: &'a CommandCapture, scroll: usize) -> Self {
+        Self { capture, scroll }
+    }
+
+    /// Renders the popup centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the popup is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered(area, CAPTURED_OUTPUT_PERCENT);
+
+        let (status, color, lines): (&str, Color, Vec<Line>) = match self.capture.result() {
+            None => (glyph("running…", "running..."), Color::Cyan, Vec::new()),
+            Some(result) => {
+                let color = if result.success {
+                    Color::Green
+                } else {
+                    Color::Red
+                };
+                let lines = if result.lines.is_empty() {
+                    vec![Line::styled(
+                        "(no output)",
+                        Style::default().fg(Color::DarkGray),
+                    )]
+                } else {
+                    result
+                        .lines
+                        .iter()
+                        .skip(self.scroll)
+                        .map(|line| Line::raw(line.as_str()))
+                        .collect()
+                };
+                (result.status.as_str(), color, lines)
+            }
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(color))
+            .title(format!(
+                " {} {} {} ",
+                self.capture.title(),
+                glyph("·", "-"),
+                status
+            ))
+            .title_bottom(format!(" {}: scroll  Esc: close ", glyph("↑/↓", "Up/Down")))
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+    }
+}
@@ -0,0 +1,193 @@
+//! Column layout of list rows.
+//!
+//! Widths are measured in terminal columns, not chars: emoji and CJK take
+//! two columns, combining marks none. Text longer than its column is cut at a
+//! grapheme boundary and ends with an ellipsis, so a long project name or a
+//! row of icons never runs past the panel and gets clipped mid-symbol.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use ratatui::text::Span;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::util::glyphs::glyph;
+
+/// Columns between two columns of a row.
+pub const GAP: usize = 2;
+
+/// Narrowest the first column gets before the columns after it are dropped.
+pub const MIN_FIRST_WIDTH: usize = 8;
+
+/// Marks text cut to fit its column.
+fn ellipsis() -> &'static str {
+    glyph("…", "...")
+}
+
+/// Returns the number of terminal columns a text takes.
+///
+/// # Arguments
+///
+/// * `text` - The text to measure
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Cuts a text to a number of terminal columns, ending it with an ellipsis
+/// when it doesn't fit.
+///
+/// # Arguments
+///
+/// * `text` - The text to cut
+/// * `width` - The columns available
+///
+/// # Returns
+///
+/// The text as is if it fits, otherwise its longest prefix of whole
+/// graphemes that fits with the ellipsis. Widths narrower than the ellipsis
+/// get the prefix alone.
+pub fn truncate(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    let ellipsis = if display_width(ellipsis()) <= width {
+        ellipsis()
+    } else {
+        ""
+    };
+    let budget = width - display_width(ellipsis);
+    let mut used = 0;
+    let mut truncated = String::new();
+    for grapheme in text.graphemes(true) {
+        let grapheme_width = display_width(grapheme);
+        if used + grapheme_width > budget {
+            break;
+        }
+        used += grapheme_width;
+        truncated.push_str(grapheme);
+    }
+    truncated.truncate(truncated.trim_end().len());
+    truncated.push_str(ellipsis);
+    truncated
+}
+
+/// Returns the widths of the columns of a list, given the width of their
+/// widest cell.
+///
+/// The first column shrinks down to `MIN_FIRST_WIDTH` before the columns
+/// after it are dropped, last first; columns with nothing to show take no
+/// room.
+///
+/// # Arguments
+///
+/// * `natural` - The width of the widest cell of each column
+/// * `available` - The columns of the row
+///
+/// # Returns
+///
+/// The width of each column, 0 for the columns dropped or empty.
+pub fn fit_columns(natural: &[usize], available: usize) -> Vec<usize> {
+    let Some((&first, rest)) = natural.split_first() else {
+        return Vec::new();
+    };
+    let mut kept = rest.len();
+    loop {
+        let others: usize = rest[..kept]
+            .iter()
+            .filter(|width| **width > 0)
+            .map(|width| width + GAP)
+            .sum();
+        if kept == 0 || first.min(MIN_FIRST_WIDTH) + others <= available {
+            let mut widths = vec![first.min(available.saturating_sub(others))];
+            widths.extend(&rest[..kept]);
+            widths.resize(natural.len(), 0);
+            return widths;
+        }
+        kept -= 1;
+    }
+}
+
+/// Fits the styled pieces of a cell in its column: cut with an ellipsis
+/// when too wide, padded with spaces when narrower.
+///
+/// # Arguments
+///
+/// * `spans` - The pieces of the cell, in order
+/// * `width` - The width of the column
+pub fn fit_spans(spans: Vec<Span<'_>>, width: usize) -> Vec<Span<'_>> {
+    let mut used = 0;
+    let mut fitted = Vec::new();
+    let total: usize = spans.iter().map(|span| span.width()).sum();
+    for span in spans {
+        let span_width = span.width();
+        if total <= width || used + span_width < width {
+            used += span_width;
+            fitted.push(span);
+            continue;
+        }
+        // The trailing space makes a piece that fills the column exactly,
+        // with more after it, end with the ellipsis too
+        let content = truncate(&format!("{} ", span.content), width - used);
+        used += display_width(&content);
+        fitted.push(Span::styled(content, span.style));
+        break;
+    }
+    if used < width {
+        fitted.push(Span::raw(" ".repeat(width - used)));
+    }
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_measuring_emoji_should_count_two_columns() {
+        assert_eq!(display_width("api"), 3);
+        assert_eq!(display_width("🚀 api"), 6);
+    }
+
+    #[test]
+    fn when_text_fits_should_keep_it() {
+        assert_eq!(truncate("gz-claude", 9), "gz-claude");
+    }
+
+    #[test]
+    fn when_text_is_too_wide_should_cut_it_with_ellipsis() {
+        assert_eq!(truncate("gz-claude", 6), "gz-cl…");
+        assert_eq!(truncate("my project", 4), "my…");
+        assert_eq!(truncate("🚀🚀🚀", 4), "🚀…");
+        assert_eq!(truncate("🚀🚀🚀", 0), "");
+    }
+
+    #[test]
+    fn when_columns_fit_should_keep_natural_widths() {
+        assert_eq!(fit_columns(&[10, 6, 5], 40), vec![10, 6, 5]);
+        assert_eq!(fit_columns(&[10, 0, 5], 17), vec![10, 0, 5]);
+    }
+
+    #[test]
+    fn when_row_is_narrow_should_shrink_first_column_then_drop_the_last() {
+        assert_eq!(fit_columns(&[30, 6, 5], 30), vec![15, 6, 5]);
+        assert_eq!(fit_columns(&[30, 6, 5], 20), vec![12, 6, 0]);
+        assert_eq!(fit_columns(&[30, 6, 5], 10), vec![10, 0, 0]);
+        assert_eq!(fit_columns(&[30, 6, 5], 4), vec![4, 0, 0]);
+    }
+
+    #[test]
+    fn when_fitting_spans_should_cut_and_pad_to_the_column() {
+        let text = |spans: Vec<Span>| {
+            spans
+                .iter()
+                .map(|s| s.content.to_string())
+                .collect::<String>()
+        };
+
+        let padded = fit_spans(vec![Span::raw("main"), Span::raw(" +2")], 9);
+        assert_eq!(text(padded), "main +2  ");
+
+        let cut = fit_spans(vec![Span::raw("main"), Span::raw(" +2 ~3")], 7);
+        assert_eq!(text(cut), "main +…");
+    }
+}
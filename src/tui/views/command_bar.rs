@@ -16,6 +16,7 @@ use ratatui::{
 };
 
 use crate::config::CommandBarItem;
+use crate::util::glyphs;
 
 /// View component for displaying a command bar at the bottom of the TUI.
 ///
@@ -76,9 +77,12 @@ impl<'a> CommandBar<'a> {
             return;
         }
 
-        let mut spans = vec![
-            Span::styled(": ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        ];
+        let mut spans = vec![Span::styled(
+            ": ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )];
 
         for (index, item) in self.commands.iter().enumerate() {
             let is_selected = index == self.selected;
@@ -98,7 +102,7 @@ impl<'a> CommandBar<'a> {
             };
 
             // Build the command display: [icon name] or [name]
-            let display = if let Some(icon) = &item.icon {
+            let display = if let Some(icon) = item.icon.as_deref().and_then(glyphs::icon) {
                 format!("[{} {}]", icon, item.name)
             } else {
                 format!("[{}]", item.name)
@@ -131,12 +135,14 @@ mod tests {
                 name: "Pipeline".to_string(),
                 command: "gitlab-pipeline".to_string(),
                 icon: Some("🚀".to_string()),
+                capture: false,
             },
             CommandBarItem {
                 key: "d".to_string(),
                 name: "Deploy".to_string(),
                 command: "deploy-status".to_string(),
                 icon: None,
+                capture: false,
             },
         ]
     }
@@ -0,0 +1,132 @@
+//! Command palette popup component for the TUI.
+//!
+//! Shows the fuzzy query being typed above the entries that match it, best
+//! match first, with the matched characters highlighted, the key doing the
+//! same and where each entry comes from.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::tui::palette::{PaletteEntry, RankedEntry};
+use crate::tui::views::popup::centered;
+use crate::util::glyphs::glyph;
+
+/// Percentage of the available width and height covered by the popup.
+const COMMAND_PALETTE_PERCENT: u16 = 80;
+
+/// View component for the command palette popup.
+pub struct CommandPaletteView<'a> {
+    query: &'a str,
+    entries: &'a [PaletteEntry],
+    ranked: &'a [RankedEntry],
+    selected: usize,
+}
+
+impl<'a> CommandPaletteView<'a> {
+    /// Creates a new CommandPaletteView.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The fuzzy query typed so far
+    /// * `entries` - The entries of the palette
+    /// * `ranked` - The entries matching the query, best first
+    /// * `selected` - Index of the selected row
+    pub fn new(
+        query: &'a str,
+        entries: &'a [PaletteEntry],
+        ranked: &'a [RankedEntry],
+        selected: usize,
+    ) -> Self {
+        Self {
+            query,
+            entries,
+            ranked,
+            selected,
+        }
+    }
+
+    /// Returns the line of an entry, with the matched characters highlighted.
+    fn entry_line(&self, row: usize, ranked: &RankedEntry) -> Line<'a> {
+        let entry = &self.entries[ranked.index];
+        let selected = row == self.selected;
+        let base = if selected {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let matched = base.fg(Color::Cyan).add_modifier(Modifier::UNDERLINED);
+        let dim = Style::default().fg(Color::DarkGray);
+
+        let mut spans = vec![Span::styled(if selected { "> " } else { "  " }, base)];
+        spans.extend(entry.label.chars().enumerate().map(|(i, c)| {
+            let style = if ranked.positions.contains(&i) {
+                matched
+            } else {
+                base
+            };
+            Span::styled(c.to_string(), style)
+        }));
+        if !entry.key.is_empty() {
+            spans.push(Span::styled(format!("  {}", entry.key), dim));
+        }
+        let origin = if ranked.recent {
+            format!("  {}, recent", entry.kind.label())
+        } else {
+            format!("  {}", entry.kind.label())
+        };
+        spans.push(Span::styled(origin, dim.add_modifier(Modifier::ITALIC)));
+        Line::from(spans)
+    }
+
+    /// Renders the popup centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the popup is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered(area, COMMAND_PALETTE_PERCENT);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" Command palette ({} shown) ", self.ranked.len()))
+            .title_bottom(format!(
+                " {}: select  Enter: run  Esc: close ",
+                glyph("↑/↓", "Up/Down")
+            ))
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+        let inner = block.inner(popup_area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(inner);
+
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::raw(self.query),
+            Span::styled(glyph("█", "_"), Style::default().fg(Color::DarkGray)),
+        ]));
+        frame.render_widget(input, chunks[0]);
+
+        let items: Vec<ListItem> = self
+            .ranked
+            .iter()
+            .enumerate()
+            .map(|(row, ranked)| ListItem::new(self.entry_line(row, ranked)))
+            .collect();
+        let mut list_state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(List::new(items), chunks[1], &mut list_state);
+    }
+}
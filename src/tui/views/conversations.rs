@@ -0,0 +1,151 @@
+//! Conversations view component for the TUI.
+//!
+//! Lists the Claude Code conversations of a project with title, age and turn
+//! count, and previews the last messages of the selected conversation.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::claude::{Conversation, Role};
+use crate::tui::views::status_bar::Hint;
+use crate::util::format_age;
+use crate::util::glyphs::glyph;
+
+/// View component for browsing a project's Claude Code conversations.
+pub struct ConversationsView<'a> {
+    conversations: &'a [Conversation],
+    selected: usize,
+}
+
+impl<'a> ConversationsView<'a> {
+    /// Creates a new ConversationsView.
+    ///
+    /// # Arguments
+    ///
+    /// * `conversations` - The project's conversations, newest first
+    /// * `selected` - Index of the currently selected conversation
+    ///
+    /// # Returns
+    ///
+    /// A new ConversationsView instance.
+    pub fn new(conversations: &'a [Conversation], selected: usize) -> Self {
+        Self {
+            conversations,
+            selected,
+        }
+    }
+
+    /// Returns the currently selected conversation, if any.
+    pub fn selected_conversation(&self) -> Option<&Conversation> {
+        self.conversations.get(self.selected)
+    }
+
+    /// Renders the conversations view to the terminal frame.
+    ///
+    /// The layout consists of two areas, below the header:
+    /// - List area (flexible): displays conversation titles with age and turns
+    /// - Preview area (flexible): displays the last messages of the selection
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The rectangular area to render within
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Min(3)])
+            .split(area);
+
+        self.render_list(frame, chunks[0]);
+        self.render_preview(frame, chunks[1]);
+    }
+
+    /// Renders the list of conversations with selection highlighting.
+    fn render_list(&self, frame: &mut Frame, area: Rect) {
+        if self.conversations.is_empty() {
+            let empty = Paragraph::new("  No conversations found")
+                .style(Style::default().fg(Color::DarkGray));
+            frame.render_widget(empty, area);
+            return;
+        }
+
+        let items: Vec<ListItem> = self
+            .conversations
+            .iter()
+            .enumerate()
+            .map(|(index, conversation)| {
+                let meta = format!(
+                    "  {} {} {} turns",
+                    format_age(conversation.modified),
+                    glyph("·", "-"),
+                    conversation.turn_count
+                );
+
+                if index == self.selected {
+                    let highlight = Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD);
+                    ListItem::new(Line::from(vec![
+                        Span::styled("> ", highlight),
+                        Span::styled(conversation.title.as_str(), highlight),
+                        Span::styled(meta, Style::default().fg(Color::DarkGray)),
+                    ]))
+                } else {
+                    ListItem::new(Line::from(vec![
+                        Span::raw("  "),
+                        Span::raw(conversation.title.as_str()),
+                        Span::styled(meta, Style::default().fg(Color::DarkGray)),
+                    ]))
+                }
+            })
+            .collect();
+
+        frame.render_widget(List::new(items), area);
+    }
+
+    /// Renders the last messages of the selected conversation.
+    fn render_preview(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = self
+            .selected_conversation()
+            .map(|conversation| {
+                conversation
+                    .last_messages
+                    .iter()
+                    .map(|message| {
+                        let (label, color) = match message.role {
+                            Role::User => ("you: ", Color::Green),
+                            Role::Assistant => ("claude: ", Color::Magenta),
+                        };
+                        Line::from(vec![
+                            Span::styled(
+                                label,
+                                Style::default().fg(color).add_modifier(Modifier::BOLD),
+                            ),
+                            Span::raw(message.text.replace('\n', " ")),
+                        ])
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let preview = Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::TOP).title("Preview"));
+
+        frame.render_widget(preview, area);
+    }
+
+    /// Returns the key hints of the view, for the status bar.
+    pub fn hints(&self) -> Vec<Hint> {
+        vec![Hint::new("Enter", "resume"), Hint::new("Esc", "back")]
+    }
+}
@@ -1,7 +1,10 @@
 //! File browser view component for the TUI.
 //!
 //! Displays a file tree with navigation, expand/collapse functionality,
-//! git information, and action icons.
+//! git information, and action icons. The project's bookmarks and recently
+//! opened files are listed in "Bookmarks" and "Recent" sections above the
+//! tree. Projects with `roots` list those directories as the top-level
+//! entries of the tree, with their own git status when they're repositories.
 //!
 //! @author waabox(waabox[at]gmail[dot]com)
 
@@ -11,33 +14,49 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Frame,
 };
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::config::{Action, Config, Project};
-use crate::git::{get_git_info, GitInfo};
-use crate::tui::file_tree::FileTree;
+use crate::git::GitInfo;
+use crate::tui::file_tree::{FileTree, IgnoreFilter};
+use crate::tui::preview::FilePreview;
+use crate::tui::views::columns;
+use crate::tui::views::projects::severity_color;
+use crate::tui::views::status_bar::Hint;
+use crate::util::glyphs;
+
+/// Maximum number of recent files listed above the file tree.
+const RECENT_SECTION_LEN: usize = 5;
 
 /// View component for displaying and navigating a file tree within a project.
 ///
 /// Renders a hierarchical file tree with expand/collapse functionality for directories,
 /// git information in the title, and action icons in the help area.
+///
+/// Selection indices cover the bookmarks first, then the recent files, then
+/// the visible tree nodes.
 pub struct FileBrowserView<'a> {
     config: &'a Config,
     workspace_id: &'a str,
     project_index: usize,
     selected: usize,
     file_tree: Option<FileTree>,
-    git_info: Option<GitInfo>,
+    show_ignored: bool,
+    preview: Option<FilePreview>,
+    bookmarks: Vec<(String, PathBuf)>,
+    recent: Vec<PathBuf>,
+    root_git: Vec<(PathBuf, GitInfo)>,
+    scroll_offset: usize,
 }
 
 impl<'a> FileBrowserView<'a> {
     /// Creates a new FileBrowserView with the given configuration, workspace, project, and selection.
     ///
-    /// Loads the file tree from the project path and git information during construction.
+    /// Loads the file tree from the project path during construction.
     ///
     /// # Arguments
     ///
@@ -48,7 +67,7 @@ impl<'a> FileBrowserView<'a> {
     ///
     /// # Returns
     ///
-    /// A new FileBrowserView instance with pre-loaded file tree and git information.
+    /// A new FileBrowserView instance with a pre-loaded file tree.
     pub fn new(
         config: &'a Config,
         workspace_id: &'a str,
@@ -61,13 +80,16 @@ impl<'a> FileBrowserView<'a> {
             project_index,
             selected,
             &HashSet::new(),
+            false,
         )
     }
 
     /// Creates a new FileBrowserView with pre-expanded directories.
     ///
-    /// Loads the file tree from the project path with the specified directories expanded,
-    /// and git information during construction.
+    /// Loads the file tree from the project path, or from the project's roots
+    /// when it has some, with the specified directories expanded, hiding
+    /// entries that match the project's ignore patterns unless
+    /// `show_ignored` is set.
     ///
     /// # Arguments
     ///
@@ -76,24 +98,43 @@ impl<'a> FileBrowserView<'a> {
     /// * `project_index` - The index of the project within the workspace
     /// * `selected` - Index of the currently selected item in the file tree
     /// * `expanded_dirs` - Set of directory paths that should be expanded
+    /// * `show_ignored` - Whether hidden and ignored entries are shown
     ///
     /// # Returns
     ///
-    /// A new FileBrowserView instance with pre-loaded file tree and git information.
+    /// A new FileBrowserView instance with a pre-loaded file tree.
     pub fn with_expanded(
         config: &'a Config,
         workspace_id: &'a str,
         project_index: usize,
         selected: usize,
         expanded_dirs: &HashSet<PathBuf>,
+        show_ignored: bool,
     ) -> Self {
         let project = config
             .workspace
             .get(workspace_id)
             .and_then(|w| w.projects.get(project_index));
 
-        let file_tree = project.and_then(|p| FileTree::with_expanded(&p.path, expanded_dirs));
-        let git_info = project.and_then(|p| get_git_info(&p.path, config.global.git_info_level));
+        let filter = IgnoreFilter::new(
+            config.resolve_ignore_patterns(workspace_id, project_index),
+            show_ignored,
+        );
+        let file_tree = project.and_then(|p| {
+            if p.roots.is_empty() {
+                FileTree::with_filter(&p.path, expanded_dirs, &filter)
+            } else {
+                FileTree::with_roots(&p.path, &p.root_paths(), expanded_dirs, &filter)
+            }
+        });
+        let bookmarks = project
+            .map(|p| {
+                p.bookmark_paths()
+                    .into_iter()
+                    .map(|(name, path)| (name.to_string(), path))
+                    .collect()
+            })
+            .unwrap_or_default();
 
         Self {
             config,
@@ -101,10 +142,135 @@ impl<'a> FileBrowserView<'a> {
             project_index,
             selected,
             file_tree,
-            git_info,
+            show_ignored,
+            preview: None,
+            bookmarks,
+            recent: Vec::new(),
+            root_git: Vec::new(),
+            scroll_offset: 0,
         }
     }
 
+    /// Sets the git information shown next to the project's roots that are
+    /// repositories of their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_git` - The directory of each such root, with its git information
+    ///
+    /// # Returns
+    ///
+    /// The view with the roots' git information set.
+    pub fn with_root_git(mut self, root_git: Vec<(PathBuf, GitInfo)>) -> Self {
+        self.root_git = root_git;
+        self
+    }
+
+    /// Sets the first file tree node shown.
+    ///
+    /// The offset is moved when needed to keep the selection visible.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The index of the first node shown
+    ///
+    /// # Returns
+    ///
+    /// The view with the scroll offset set.
+    pub fn with_scroll_offset(mut self, offset: usize) -> Self {
+        self.scroll_offset = offset;
+        self
+    }
+
+    /// Sets the recent files listed above the file tree.
+    ///
+    /// Only the first few are listed; the selection indices of the tree
+    /// nodes shift by the number of listed files.
+    ///
+    /// # Arguments
+    ///
+    /// * `recent` - The project's recent files, most recent first
+    ///
+    /// # Returns
+    ///
+    /// The view with the recent files set.
+    pub fn with_recent(mut self, mut recent: Vec<PathBuf>) -> Self {
+        recent.truncate(RECENT_SECTION_LEN);
+        self.recent = recent;
+        self
+    }
+
+    /// Returns the index of the selected node within the file tree.
+    ///
+    /// # Returns
+    ///
+    /// None if a recent file is selected.
+    fn tree_index(&self) -> Option<usize> {
+        self.selected
+            .checked_sub(self.bookmarks.len() + self.recent.len())
+    }
+
+    /// Returns the index of the selected recent file, if one is selected.
+    fn recent_index(&self) -> Option<usize> {
+        let index = self.selected.checked_sub(self.bookmarks.len())?;
+        (index < self.recent.len()).then_some(index)
+    }
+
+    /// Returns whether a recent file is selected rather than a tree node.
+    pub fn selected_is_recent(&self) -> bool {
+        self.recent_index().is_some()
+    }
+
+    /// Returns the directory of the selected bookmark, if one is selected.
+    pub fn selected_bookmark(&self) -> Option<&Path> {
+        self.bookmarks
+            .get(self.selected)
+            .map(|(_, path)| path.as_path())
+    }
+
+    /// Returns the selection index of a visible tree node.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the node
+    ///
+    /// # Returns
+    ///
+    /// The index to select, or None if no visible node has the path.
+    pub fn index_of(&self, path: &Path) -> Option<usize> {
+        self.file_tree
+            .as_ref()?
+            .index_of(path)
+            .map(|i| i + self.bookmarks.len() + self.recent.len())
+    }
+
+    /// Returns the path of the selected tree node.
+    ///
+    /// # Returns
+    ///
+    /// None if a bookmark or recent file is selected, or no node is.
+    pub fn selected_node(&self) -> Option<PathBuf> {
+        let index = self.tree_index()?;
+        self.file_tree
+            .as_ref()
+            .and_then(|ft| ft.get_visible_node(index))
+            .map(|node| node.path.clone())
+    }
+
+    /// Sets the preview shown next to the file tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `preview` - The preview of the selected file, or None to hide the pane
+    ///
+    /// # Returns
+    ///
+    /// The view with the preview set.
+    pub fn with_preview(mut self, preview: Option<FilePreview>) -> Self {
+        self.preview = preview;
+        self
+    }
+
     /// Returns a reference to the project being displayed.
     ///
     /// # Returns
@@ -134,16 +300,20 @@ impl<'a> FileBrowserView<'a> {
         sorted
     }
 
-    /// Returns the number of currently visible nodes in the file tree.
+    /// Returns the number of selectable entries: recent files and visible tree nodes.
     ///
     /// # Returns
     ///
-    /// The count of visible nodes, or 0 if no file tree is loaded.
+    /// The count of entries; only the bookmarks and recent files if no file
+    /// tree is loaded.
     pub fn visible_count(&self) -> usize {
-        self.file_tree
-            .as_ref()
-            .map(|ft| ft.visible_count())
-            .unwrap_or(0)
+        self.bookmarks.len()
+            + self.recent.len()
+            + self
+                .file_tree
+                .as_ref()
+                .map(|ft| ft.visible_count())
+                .unwrap_or(0)
     }
 
     /// Toggles the expand/collapse state of the selected item.
@@ -151,8 +321,11 @@ impl<'a> FileBrowserView<'a> {
     /// If the selected item is a directory, it will be expanded or collapsed.
     /// Does nothing if the selected item is a file or no file tree is loaded.
     pub fn toggle_selected(&mut self) {
+        let Some(index) = self.tree_index() else {
+            return;
+        };
         if let Some(ref mut file_tree) = self.file_tree {
-            file_tree.toggle_at(self.selected);
+            file_tree.toggle_at(index);
         }
     }
 
@@ -160,11 +333,18 @@ impl<'a> FileBrowserView<'a> {
     ///
     /// # Returns
     ///
-    /// True if the selected item is a file, false if it's a directory or no file tree is loaded.
+    /// True if the selected item is a file or a recent file, false if it's a directory, a
+    /// bookmark or no file tree is loaded.
     pub fn selected_is_file(&self) -> bool {
+        if self.selected_bookmark().is_some() {
+            return false;
+        }
+        let Some(index) = self.tree_index() else {
+            return true;
+        };
         self.file_tree
             .as_ref()
-            .and_then(|ft| ft.get_visible_node(self.selected))
+            .and_then(|ft| ft.get_visible_node(index))
             .map(|node| !node.is_dir)
             .unwrap_or(false)
     }
@@ -173,94 +353,216 @@ impl<'a> FileBrowserView<'a> {
     ///
     /// # Returns
     ///
-    /// Some path if a file tree is loaded and selection is valid, None otherwise.
+    /// Some path if a bookmark or recent file is selected, or a file tree is loaded and
+    /// selection is valid, None otherwise.
     pub fn selected_path(&self) -> Option<PathBuf> {
+        if let Some(bookmark) = self.selected_bookmark() {
+            return Some(bookmark.to_path_buf());
+        }
+        let Some(index) = self.tree_index() else {
+            return self
+                .recent_index()
+                .and_then(|index| self.recent.get(index))
+                .cloned();
+        };
         self.file_tree
             .as_ref()
-            .and_then(|ft| ft.get_visible_node(self.selected))
+            .and_then(|ft| ft.get_visible_node(index))
             .map(|node| node.path.clone())
     }
 
-    /// Refreshes the git information for the current project.
-    ///
-    /// Call this method when the git status of the project may have changed.
-    pub fn refresh_git_info(&mut self) {
-        self.git_info = self
-            .project()
-            .and_then(|p| get_git_info(&p.path, self.config.global.git_info_level));
-    }
-
     /// Renders the file browser view to the terminal frame.
     ///
-    /// The layout consists of three areas:
-    /// - Title area (3 lines): displays "{project.name}  {git_info}" header
-    /// - File tree area (flexible): displays file tree with indentation and icons
-    /// - Help area (3 lines): displays action shortcuts and navigation hints
+    /// The layout consists of two areas, below the header:
+    /// - File tree area (flexible): displays the bookmarks and recent files, if any,
+    ///   then the file tree with indentation and icons
     ///
     /// # Arguments
     ///
     /// * `frame` - The terminal frame to render to
     /// * `area` - The rectangular area to render within
-    pub fn render(&self, frame: &mut Frame, area: Rect) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),
-                Constraint::Min(1),
-                Constraint::Length(3),
-            ])
-            .split(area);
-
-        self.render_title(frame, chunks[0]);
-        self.render_file_tree(frame, chunks[1]);
-        self.render_help(frame, chunks[2]);
+    ///
+    /// # Returns
+    ///
+    /// The scroll offset of the file tree, moved to keep the selection visible.
+    pub fn render(&self, frame: &mut Frame, area: Rect) -> usize {
+        match &self.preview {
+            Some(preview) => {
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                    .split(area);
+                self.render_preview(frame, columns[1], preview);
+                self.render_files(frame, columns[0])
+            }
+            None => self.render_files(frame, area),
+        }
     }
 
-    /// Renders the title area with project name and git info.
-    fn render_title(&self, frame: &mut Frame, area: Rect) {
-        let project_name = self
-            .project()
-            .map(|p| p.name.as_str())
-            .unwrap_or("Unknown Project");
-
-        let git_info_text = self
-            .git_info
-            .as_ref()
-            .map(|info| info.format_standard())
+    /// Renders the preview of the selected file.
+    fn render_preview(&self, frame: &mut Frame, area: Rect, preview: &FilePreview) {
+        let title = self
+            .selected_path()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
             .unwrap_or_default();
+        let block = Block::default()
+            .borders(Borders::LEFT)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(format!(" {} ", title));
+
+        let placeholder = |text: String| {
+            vec![Line::from(Span::styled(
+                text,
+                Style::default().fg(Color::DarkGray),
+            ))]
+        };
+        let lines = match preview {
+            FilePreview::Text { lines, truncated } => {
+                let mut lines = lines.clone();
+                if *truncated {
+                    lines.extend(placeholder("...".to_string()));
+                }
+                lines
+            }
+            FilePreview::Image(size) => placeholder(format!("[image, {} bytes]", size)),
+            FilePreview::Binary(size) => placeholder(format!("[binary file, {} bytes]", size)),
+            FilePreview::Directory => placeholder("[directory]".to_string()),
+            FilePreview::Unreadable(error) => placeholder(format!("[unreadable: {}]", error)),
+        };
 
-        let mut spans = vec![Span::styled(
-            project_name,
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )];
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
 
-        if !git_info_text.is_empty() {
-            spans.push(Span::styled(
-                format!("  {}", git_info_text),
-                Style::default().fg(Color::DarkGray),
-            ));
+    /// Renders the bookmarks and recent files sections, if any, above the
+    /// file tree.
+    fn render_files(&self, frame: &mut Frame, area: Rect) -> usize {
+        let root = self.project().map(|p| p.path.as_path());
+        let relative = |path: &Path| {
+            root.and_then(|root| path.strip_prefix(root).ok())
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        };
+        // Bookmark names in a column, so their paths line up
+        let name_width = self
+            .bookmarks
+            .iter()
+            .map(|(name, _)| columns::display_width(name))
+            .max()
+            .unwrap_or(0);
+        let bookmarks: Vec<String> = self
+            .bookmarks
+            .iter()
+            .map(|(name, path)| {
+                let padding = name_width - columns::display_width(name) + columns::GAP;
+                format!("{}{}{}", name, " ".repeat(padding), relative(path))
+            })
+            .collect();
+        let recent: Vec<String> = self.recent.iter().map(|path| relative(path)).collect();
+
+        let sections: Vec<(&str, Vec<String>, usize)> = [
+            ("Bookmarks", bookmarks, 0),
+            ("Recent", recent, self.bookmarks.len()),
+        ]
+        .into_iter()
+        .filter(|(_, entries, _)| !entries.is_empty())
+        .collect();
+        if sections.is_empty() {
+            return self.render_file_tree(frame, area);
         }
 
-        let title =
-            Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::BOTTOM));
+        let mut constraints: Vec<Constraint> = sections
+            .iter()
+            .map(|(_, entries, _)| Constraint::Length(entries.len() as u16 + 2))
+            .collect();
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area);
+        for (chunk, (title, entries, first_index)) in chunks.iter().zip(&sections) {
+            self.render_section(frame, *chunk, title, entries, *first_index);
+        }
+        self.render_file_tree(frame, chunks[sections.len()])
+    }
+
+    /// Renders a section listed above the file tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The rectangular area to render within
+    /// * `title` - The section title
+    /// * `entries` - The lines of the section
+    /// * `first_index` - The selection index of the first entry
+    fn render_section(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        entries: &[String],
+        first_index: usize,
+    ) {
+        let width = (area.width as usize).saturating_sub(2);
+        let items: Vec<ListItem> = entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let entry = columns::truncate(entry, width);
+                if first_index + index == self.selected {
+                    let style = Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD);
+                    ListItem::new(Line::from(vec![
+                        Span::styled("> ", style),
+                        Span::styled(entry, style),
+                    ]))
+                } else {
+                    ListItem::new(Line::from(format!("  {}", entry)))
+                }
+            })
+            .collect();
 
-        frame.render_widget(title, area);
+        let block = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(Span::styled(
+                title.to_string(),
+                Style::default().fg(Color::DarkGray),
+            ));
+        frame.render_widget(List::new(items).block(block), area);
     }
 
-    /// Renders the file tree list with indentation and expand/collapse icons.
-    fn render_file_tree(&self, frame: &mut Frame, area: Rect) {
+    /// Renders the file tree list with indentation and expand/collapse icons,
+    /// scrolled to keep the selection visible, and returns its scroll offset.
+    fn render_file_tree(&self, frame: &mut Frame, area: Rect) -> usize {
         let Some(ref file_tree) = self.file_tree else {
             let list = List::new(Vec::<ListItem>::new());
             frame.render_widget(list, area);
-            return;
+            return 0;
         };
 
+        let width = (area.width as usize).saturating_sub(2);
+        let behind_warn = self
+            .project()
+            .map_or(0, |p| self.config.resolve_behind_warn(p));
         let items: Vec<ListItem> = (0..file_tree.visible_count())
             .filter_map(|index| {
                 let node = file_tree.get_visible_node(index)?;
-                let is_selected = index == self.selected;
+                let is_selected = Some(index) == self.tree_index();
+
+                // Roots that are repositories show their own git status
+                let git = self
+                    .root_git
+                    .iter()
+                    .find(|(path, _)| *path == node.path)
+                    .map(|(_, info)| {
+                        Span::styled(
+                            format!("  {}", info.format_minimal()),
+                            Style::default().fg(severity_color(info.severity(behind_warn))),
+                        )
+                    });
+                let width = width.saturating_sub(git.as_ref().map_or(0, Span::width));
 
                 // Build indentation based on depth
                 let indent = "  ".repeat(node.depth);
@@ -276,11 +578,12 @@ impl<'a> FileBrowserView<'a> {
                     "  "
                 };
 
-                // Build the display line
+                // Build the display line, cut to the width of the list
                 let prefix = if is_selected { "> " } else { "  " };
+                let entry = columns::truncate(&format!("{}{}{}", indent, icon, &node.name), width);
 
-                if is_selected {
-                    let line = Line::from(vec![
+                let mut spans = if is_selected {
+                    vec![
                         Span::styled(
                             prefix,
                             Style::default()
@@ -288,45 +591,59 @@ impl<'a> FileBrowserView<'a> {
                                 .add_modifier(Modifier::BOLD),
                         ),
                         Span::styled(
-                            format!("{}{}{}", indent, icon, &node.name),
+                            entry,
                             Style::default()
                                 .fg(Color::Yellow)
                                 .add_modifier(Modifier::BOLD),
                         ),
-                    ]);
-                    Some(ListItem::new(line))
+                    ]
                 } else {
-                    let line = Line::from(vec![
-                        Span::raw(prefix),
-                        Span::raw(format!("{}{}{}", indent, icon, &node.name)),
-                    ]);
-                    Some(ListItem::new(line))
-                }
+                    vec![Span::raw(prefix), Span::raw(entry)]
+                };
+                spans.extend(git);
+                Some(ListItem::new(Line::from(spans)))
             })
             .collect();
 
-        let list = List::new(items);
-        frame.render_widget(list, area);
+        let last = file_tree.visible_count().saturating_sub(1);
+        let mut list_state = ListState::default()
+            .with_offset(self.scroll_offset.min(last))
+            .with_selected(self.tree_index());
+        frame.render_stateful_widget(List::new(items), area, &mut list_state);
+        list_state.offset()
     }
 
-    /// Renders the help area with action shortcuts and navigation hints.
-    fn render_help(&self, frame: &mut Frame, area: Rect) {
-        let actions = self.resolved_actions();
-        let action_hints: Vec<String> = actions
+    /// Returns the key hints of the view, for the status bar: actions
+    /// first, then navigation.
+    pub fn hints(&self) -> Vec<Hint> {
+        let mut hints: Vec<Hint> = self
+            .resolved_actions()
             .iter()
             .map(|(key, action)| {
-                let icon = action.icon.as_deref().unwrap_or("");
-                format!("{}{}:{}", icon, key, action.name)
+                let icon = action.icon.as_deref().and_then(glyphs::icon);
+                Hint::new(format!("{}{}", icon.unwrap_or(""), key), &action.name)
             })
             .collect();
-
-        let help_text = format!("{}  Enter: open/expand  Esc: back", action_hints.join("  "));
-
-        let help = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::DarkGray))
-            .block(Block::default().borders(Borders::TOP));
-
-        frame.render_widget(help, area);
+        let ignored = if self.show_ignored {
+            "hide ignored"
+        } else {
+            "show ignored"
+        };
+        hints.extend([
+            Hint::new("Enter", "open/expand"),
+            Hint::new("H", ignored),
+            Hint::new("Tab", "preview"),
+            Hint::new("/", "search"),
+            Hint::new("^T", "go to file"),
+            Hint::new("s", "shell"),
+            Hint::new("b", "blame"),
+            Hint::new("o", "recent"),
+            Hint::new("L", "log"),
+            Hint::new("O", "reveal"),
+            Hint::new("w", "workspaces"),
+            Hint::new("Esc", "back"),
+        ]);
+        hints
     }
 }
 
@@ -334,6 +651,7 @@ impl<'a> FileBrowserView<'a> {
 mod tests {
     use super::*;
     use crate::config::{GlobalConfig, WebClientConfig, Workspace};
+    use indexmap::IndexMap;
     use std::collections::HashMap;
     use tempfile::TempDir;
 
@@ -345,6 +663,7 @@ mod tests {
                 name: "Claude".to_string(),
                 command: "claude".to_string(),
                 icon: Some("C".to_string()),
+                ..Default::default()
             },
         );
 
@@ -353,27 +672,62 @@ mod tests {
             path: project_path,
             actions: HashMap::new(),
             command_bar: vec![],
+            file_browser: None,
+            check: None,
+            git: None,
+            repo: None,
+            bookmarks: Default::default(),
+            description: None,
+            default_action: None,
+            roots: Vec::new(),
+            mcp: Default::default(),
+            env: None,
+            autostart: false,
+            sandboxed: false,
         }];
 
-        let mut workspaces = HashMap::new();
+        let mut workspaces = IndexMap::new();
         workspaces.insert(
             "test-workspace".to_string(),
             Workspace {
                 name: "Test Workspace".to_string(),
+                order: None,
+                description: None,
                 actions: HashMap::new(),
                 command_bar: vec![],
                 projects,
+                working_set: Vec::new(),
+                discover: None,
+                default_action: None,
+                mcp: Default::default(),
             },
         );
 
         Config {
             global: GlobalConfig {
                 editor: "$EDITOR".to_string(),
+                shell: "$SHELL".to_string(),
                 git_info_level: Default::default(),
+                file_browser: Default::default(),
+                key_conflicts: Default::default(),
+                git_ui: Default::default(),
+                git: Default::default(),
+                remote: None,
+                zellij: Default::default(),
+                navigation: Default::default(),
+                claude_idle: Default::default(),
+                task: Default::default(),
                 actions: global_actions,
                 command_bar: vec![],
+                project_enter: Default::default(),
+                mcp: Default::default(),
             },
             web_client: WebClientConfig::default(),
+            top_bar: Default::default(),
+            layout: Default::default(),
+            notifications: Default::default(),
+            ui: Default::default(),
+            state_endpoint: Default::default(),
             workspace: workspaces,
         }
     }
@@ -401,6 +755,74 @@ mod tests {
         assert!(view.visible_count() > 0);
     }
 
+    #[test]
+    fn when_listing_recent_files_should_select_them_before_tree_nodes() {
+        let temp_dir = setup_test_project_dir();
+        let config = create_test_config_with_project(temp_dir.path().to_path_buf());
+        let recent = temp_dir.path().join("src/main.rs");
+
+        let view =
+            FileBrowserView::new(&config, "test-workspace", 0, 0).with_recent(vec![recent.clone()]);
+        let tree_only = FileBrowserView::new(&config, "test-workspace", 0, 0);
+
+        assert_eq!(view.visible_count(), tree_only.visible_count() + 1);
+        assert!(view.selected_is_file());
+        assert_eq!(view.selected_path(), Some(recent));
+
+        let readme = temp_dir.path().join("README.md");
+        let index = view.index_of(&readme).unwrap();
+        assert_eq!(index, tree_only.index_of(&readme).unwrap() + 1);
+    }
+
+    #[test]
+    fn when_project_has_bookmarks_should_list_them_before_recent_files() {
+        let temp_dir = setup_test_project_dir();
+        let mut config = create_test_config_with_project(temp_dir.path().to_path_buf());
+        let project = &mut config.workspace.get_mut("test-workspace").unwrap().projects[0];
+        project
+            .bookmarks
+            .insert("code".to_string(), "src".to_string());
+        let recent = temp_dir.path().join("README.md");
+
+        let view =
+            FileBrowserView::new(&config, "test-workspace", 0, 0).with_recent(vec![recent.clone()]);
+        let after_bookmark =
+            FileBrowserView::new(&config, "test-workspace", 0, 1).with_recent(vec![recent.clone()]);
+
+        assert_eq!(
+            view.selected_bookmark(),
+            Some(temp_dir.path().join("src").as_path())
+        );
+        assert!(!view.selected_is_file());
+        assert!(!view.selected_is_recent());
+        assert_eq!(after_bookmark.selected_bookmark(), None);
+        assert!(after_bookmark.selected_is_recent());
+        assert_eq!(after_bookmark.selected_path(), Some(recent));
+        let src = temp_dir.path().join("src");
+        let plain = create_test_config_with_project(temp_dir.path().to_path_buf());
+        let tree_only = FileBrowserView::new(&plain, "test-workspace", 0, 0);
+        assert_eq!(view.index_of(&src), tree_only.index_of(&src).map(|i| i + 2));
+    }
+
+    #[test]
+    fn when_project_has_roots_should_list_them_as_top_level_entries() {
+        let temp_dir = setup_test_project_dir();
+        std::fs::create_dir_all(temp_dir.path().join("apps/web")).unwrap();
+        let mut config = create_test_config_with_project(temp_dir.path().to_path_buf());
+        let project = &mut config.workspace.get_mut("test-workspace").unwrap().projects[0];
+        project.roots = vec!["src".to_string(), "apps/web".to_string()];
+        let expanded: HashSet<PathBuf> = [temp_dir.path().join("src")].into_iter().collect();
+
+        let view =
+            FileBrowserView::with_expanded(&config, "test-workspace", 0, 0, &expanded, false);
+
+        assert_eq!(view.visible_count(), 3);
+        assert_eq!(view.selected_path(), Some(temp_dir.path().join("src")));
+        assert_eq!(view.index_of(&temp_dir.path().join("src/main.rs")), Some(1));
+        assert_eq!(view.index_of(&temp_dir.path().join("apps/web")), Some(2));
+        assert_eq!(view.index_of(&temp_dir.path().join("README.md")), None);
+    }
+
     #[test]
     fn when_getting_project_should_return_correct_project() {
         let temp_dir = setup_test_project_dir();
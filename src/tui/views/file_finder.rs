@@ -0,0 +1,140 @@
+//! Jump-to-file popup component for the TUI.
+//!
+//! Shows the fuzzy query being typed above the project files that match it,
+//! best match first, with the matched characters highlighted.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::tui::finder::RankedFile;
+use crate::tui::views::popup::centered;
+use crate::util::glyphs::glyph;
+
+/// Percentage of the available width and height covered by the popup.
+const FILE_FINDER_PERCENT: u16 = 80;
+
+/// View component for the jump-to-file popup.
+pub struct FileFinderView<'a> {
+    project_name: &'a str,
+    query: &'a str,
+    files: &'a [RankedFile<'a>],
+    selected: usize,
+    indexing: bool,
+}
+
+impl<'a> FileFinderView<'a> {
+    /// Creates a new FileFinderView.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_name` - The name of the project shown in the title
+    /// * `query` - The fuzzy query typed so far
+    /// * `files` - The matching files, best first
+    /// * `selected` - Index of the selected row
+    /// * `indexing` - Whether the project files are still being listed
+    ///
+    /// # Returns
+    ///
+    /// A new FileFinderView instance.
+    pub fn new(
+        project_name: &'a str,
+        query: &'a str,
+        files: &'a [RankedFile<'a>],
+        selected: usize,
+        indexing: bool,
+    ) -> Self {
+        Self {
+            project_name,
+            query,
+            files,
+            selected,
+            indexing,
+        }
+    }
+
+    /// Returns the line of a file, with the matched characters highlighted.
+    fn file_line(&self, index: usize, file: &RankedFile<'a>) -> Line<'a> {
+        let selected = index == self.selected;
+        let base = if selected {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let matched = base.fg(Color::Cyan).add_modifier(Modifier::UNDERLINED);
+
+        let mut spans = vec![Span::styled(if selected { "> " } else { "  " }, base)];
+        spans.extend(file.path.chars().enumerate().map(|(i, c)| {
+            let style = if file.positions.contains(&i) {
+                matched
+            } else {
+                base
+            };
+            Span::styled(c.to_string(), style)
+        }));
+        Line::from(spans)
+    }
+
+    /// Renders the popup centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the popup is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered(area, FILE_FINDER_PERCENT);
+        let status = if self.indexing {
+            format!(", indexing{}", glyph("…", "..."))
+        } else {
+            String::new()
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(
+                " Go to file in {} ({} shown{}) ",
+                self.project_name,
+                self.files.len(),
+                status
+            ))
+            .title_bottom(format!(
+                " {}: select  Enter: open  Esc: close ",
+                glyph("↑/↓", "Up/Down")
+            ))
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+        let inner = block.inner(popup_area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(inner);
+
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::raw(self.query),
+            Span::styled(glyph("█", "_"), Style::default().fg(Color::DarkGray)),
+        ]));
+        frame.render_widget(input, chunks[0]);
+
+        let items: Vec<ListItem> = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| ListItem::new(self.file_line(index, file)))
+            .collect();
+        let mut list_state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(List::new(items), chunks[1], &mut list_state);
+    }
+}
@@ -0,0 +1,287 @@
+//! Breadcrumb header shown above every view.
+//!
+//! Reads `Workspace ▸ Project ▸ path/inside/repo` on the left and the active
+//! git branch, with its status, and pane count on the right, after a
+//! `DRY RUN` mark while commands are only previewed and the operations
+//! running in the background. A merge or rebase left in progress is shown
+//! first, in red. When the crumbs don't fit, the ones between the first and
+//! the last are folded into `…`.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::util::glyphs::glyph;
+
+/// Separator between two crumbs.
+fn separator() -> &'static str {
+    glyph(" ▸ ", " > ")
+}
+
+/// Replaces the crumbs folded away when the header is too narrow.
+fn ellipsis() -> &'static str {
+    glyph("…", "...")
+}
+
+/// The header of the current view.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Header {
+    crumbs: Vec<String>,
+    git: Option<String>,
+    alert: Option<String>,
+    panes: usize,
+    dry_run: bool,
+    activity: Vec<String>,
+}
+
+impl Header {
+    /// Creates a header from its crumbs, outermost first.
+    ///
+    /// # Arguments
+    ///
+    /// * `crumbs` - The location of the view (e.g. workspace, project, path)
+    pub fn new(crumbs: Vec<String>) -> Self {
+        Self {
+            crumbs,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the git summary of the view's project, starting with its
+    /// branch (e.g. "main * | +0 -2 fetched 3h ago"), shown on the right.
+    pub fn with_git(mut self, git: Option<String>) -> Self {
+        self.git = git.filter(|g| !g.is_empty());
+        self
+    }
+
+    /// Sets an alert about the view's project, e.g. "MERGING (3 conflicts)",
+    /// shown first on the right, in red.
+    pub fn with_alert(mut self, alert: Option<String>) -> Self {
+        self.alert = alert;
+        self
+    }
+
+    /// Sets the number of panes open for the view's scope, shown on the right
+    /// when there are any.
+    pub fn with_pane_count(mut self, panes: usize) -> Self {
+        self.panes = panes;
+        self
+    }
+
+    /// Marks the panel as in dry run mode, shown first on the right.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Sets the operations running in the background, each with its
+    /// spinner frame (e.g. "⠹ git [███░░░░░] 3/8"), shown after the dry run
+    /// mark.
+    pub fn with_activity(mut self, activity: Vec<String>) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    /// Returns the right-hand side text: alert, dry run mark, background
+    /// operations, git summary and pane count.
+    pub fn status(&self) -> String {
+        match &self.alert {
+            Some(alert) if self.details().is_empty() => alert.clone(),
+            Some(alert) => format!("{}  {}", alert, self.details()),
+            None => self.details(),
+        }
+    }
+
+    /// Returns the right-hand side text after the alert.
+    fn details(&self) -> String {
+        let mut parts = Vec::new();
+        if self.dry_run {
+            parts.push("DRY RUN".to_string());
+        }
+        parts.extend(self.activity.iter().cloned());
+        if let Some(git) = &self.git {
+            parts.push(format!("{} {}", glyph("⎇", "git:"), git));
+        }
+        match self.panes {
+            0 => {}
+            1 => parts.push("1 pane".to_string()),
+            n => parts.push(format!("{} panes", n)),
+        }
+        parts.join("  ")
+    }
+
+    /// Returns the crumbs that fit in a width, folding the middle ones.
+    ///
+    /// The first and last crumbs are always kept; crumbs after the first are
+    /// replaced by `…` from the left until the rest fits.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The columns available for the crumbs
+    pub fn fit(&self, width: usize) -> Vec<&str> {
+        let mut crumbs: Vec<&str> = self.crumbs.iter().map(String::as_str).collect();
+        let mut folded = false;
+        while crumbs.len() > 2 && joined_width(&crumbs) > width {
+            let at = if folded { 2 } else { 1 };
+            if at >= crumbs.len() - 1 {
+                break;
+            }
+            if folded {
+                crumbs.remove(at);
+            } else {
+                crumbs[at] = ellipsis();
+                folded = true;
+            }
+        }
+        crumbs
+    }
+
+    /// Renders the header: crumbs on the left, status on the right, above a
+    /// bottom border.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The rectangular area to render within
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::BOTTOM);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let status = self.status();
+        let status_width = status.chars().count() as u16;
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(1), Constraint::Length(status_width)])
+            .split(inner);
+
+        let crumbs = self.fit(chunks[0].width.saturating_sub(1) as usize);
+        frame.render_widget(Paragraph::new(crumb_line(&crumbs)), chunks[0]);
+        let dim = Style::default().fg(Color::DarkGray);
+        let status = match &self.alert {
+            Some(alert) => {
+                let alert_style = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+                let mut spans = vec![Span::styled(alert.as_str(), alert_style)];
+                let details = self.details();
+                if !details.is_empty() {
+                    spans.push(Span::styled(format!("  {}", details), dim));
+                }
+                Line::from(spans)
+            }
+            None => Line::styled(status, dim),
+        };
+        frame.render_widget(Paragraph::new(status), chunks[1]);
+    }
+}
+
+/// Returns the width of crumbs joined with separators.
+fn joined_width(crumbs: &[&str]) -> usize {
+    let separators = crumbs.len().saturating_sub(1) * separator().chars().count();
+    crumbs.iter().map(|c| c.chars().count()).sum::<usize>() + separators
+}
+
+/// Styles crumbs: the last one stands out, the others and separators are dimmed.
+fn crumb_line<'a>(crumbs: &[&'a str]) -> Line<'a> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut spans = Vec::new();
+    for (index, crumb) in crumbs.iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::styled(separator(), dim));
+        }
+        let style = if index + 1 == crumbs.len() {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Cyan)
+        };
+        spans.push(Span::styled(*crumb, style));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(crumbs: &[&str]) -> Header {
+        Header::new(crumbs.iter().map(|c| c.to_string()).collect())
+    }
+
+    #[test]
+    fn when_crumbs_fit_should_keep_all_of_them() {
+        let header = header(&["Helios", "gateway", "src", "main.rs"]);
+
+        assert_eq!(header.fit(80), vec!["Helios", "gateway", "src", "main.rs"]);
+        assert_eq!(
+            crumb_line(&header.fit(80)).to_string(),
+            "Helios ▸ gateway ▸ src ▸ main.rs"
+        );
+    }
+
+    #[test]
+    fn when_crumbs_are_too_wide_should_fold_the_middle_ones() {
+        let header = header(&["Helios", "gateway", "src", "routes", "users.rs"]);
+
+        // "Helios ▸ … ▸ routes ▸ users.rs" is 30 columns
+        assert_eq!(header.fit(30), vec!["Helios", "…", "routes", "users.rs"]);
+        assert_eq!(header.fit(10), vec!["Helios", "…", "users.rs"]);
+    }
+
+    #[test]
+    fn when_there_are_two_crumbs_should_never_fold() {
+        assert_eq!(
+            header(&["Helios", "gateway"]).fit(1),
+            vec!["Helios", "gateway"]
+        );
+    }
+
+    #[test]
+    fn when_branch_and_panes_are_set_should_show_them_as_status() {
+        let header = header(&["Helios"])
+            .with_git(Some("main *".to_string()))
+            .with_pane_count(2);
+
+        assert_eq!(header.status(), "⎇ main *  2 panes");
+        assert_eq!(Header::default().with_pane_count(1).status(), "1 pane");
+        assert_eq!(Header::default().status(), "");
+        assert_eq!(
+            Header::default()
+                .with_dry_run(true)
+                .with_pane_count(1)
+                .status(),
+            "DRY RUN  1 pane"
+        );
+    }
+
+    #[test]
+    fn when_work_runs_in_background_should_show_it_after_dry_run_mark() {
+        let header = header(&["Helios"])
+            .with_dry_run(true)
+            .with_activity(vec!["| search".to_string()])
+            .with_git(Some("main".to_string()));
+
+        assert_eq!(header.status(), "DRY RUN  | search  ⎇ main");
+    }
+
+    #[test]
+    fn when_operation_is_in_progress_should_show_alert_first() {
+        let header = header(&["Helios"])
+            .with_alert(Some("MERGING (3 conflicts)".to_string()))
+            .with_git(Some("main *".to_string()));
+
+        assert_eq!(header.status(), "MERGING (3 conflicts)  ⎇ main *");
+        assert_eq!(
+            Header::default()
+                .with_alert(Some("REBASING".to_string()))
+                .status(),
+            "REBASING"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! MCP servers popup component for the TUI.
+//!
+//! Lists the MCP servers configured for a project, each checked when Claude
+//! gets it, so they can be enabled or disabled before the next launch.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+
+use crate::config::McpServer;
+use crate::tui::views::popup::centered;
+
+/// Percentage of the available width and height covered by the popup.
+const MCP_SERVERS_PERCENT: u16 = 80;
+
+/// Returns what a server runs: its command line, or its URL.
+fn describe(server: &McpServer) -> String {
+    match &server.command {
+        Some(command) => std::iter::once(command.as_str())
+            .chain(server.args.iter().map(String::as_str))
+            .collect::<Vec<&str>>()
+            .join(" "),
+        None => server.url.clone().unwrap_or_default(),
+    }
+}
+
+/// View component for the MCP servers popup.
+pub struct McpServersView<'a> {
+    project_name: &'a str,
+    servers: &'a [(String, McpServer, bool)],
+    selected: usize,
+}
+
+impl<'a> McpServersView<'a> {
+    /// Creates a new McpServersView.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_name` - The name of the project shown in the title
+    /// * `servers` - The servers by name, with whether each is enabled
+    /// * `selected` - Index of the selected row
+    ///
+    /// # Returns
+    ///
+    /// A new McpServersView instance.
+    pub fn new(
+        project_name: &'a str,
+        servers: &'a [(String, McpServer, bool)],
+        selected: usize,
+    ) -> Self {
+        Self {
+            project_name,
+            servers,
+            selected,
+        }
+    }
+
+    /// Renders the popup centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the popup is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered(area, MCP_SERVERS_PERCENT);
+        let name_width = self
+            .servers
+            .iter()
+            .map(|(name, _, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let items: Vec<ListItem> = self
+            .servers
+            .iter()
+            .enumerate()
+            .map(|(index, (name, server, enabled))| {
+                let style = if index == self.selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let prefix = if index == self.selected { "> " } else { "  " };
+                let check = if *enabled { "[x]" } else { "[ ]" };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{}{} {:<width$}  ", prefix, check, name, width = name_width),
+                        style,
+                    ),
+                    Span::styled(describe(server), Style::default().fg(Color::DarkGray)),
+                ]))
+            })
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" MCP servers of {} ", self.project_name))
+            .title_bottom(" Enter/Space: toggle for the next Claude launch  Esc: close ")
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(List::new(items).block(block), popup_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_describing_server_should_show_its_command_line_or_url() {
+        let local: McpServer =
+            serde_json::from_str(r#"{ "command": "npx", "args": ["-y", "github-mcp"] }"#).unwrap();
+        let remote: McpServer =
+            serde_json::from_str(r#"{ "url": "https://mcp.example.com" }"#).unwrap();
+
+        assert_eq!(describe(&local), "npx -y github-mcp");
+        assert_eq!(describe(&remote), "https://mcp.example.com");
+    }
+}
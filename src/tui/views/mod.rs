@@ -2,12 +2,50 @@
 //!
 //! @author waabox(waabox[at]gmail[dot]com)
 
+pub mod blame;
+pub mod captured_output;
+pub mod columns;
 pub mod command_bar;
+pub mod command_palette;
+pub mod conversations;
 pub mod file_browser;
+pub mod file_finder;
+pub mod header;
+pub mod mcp_servers;
+pub mod popup;
+pub mod progress;
+pub mod project_menu;
 pub mod projects;
+pub mod prompt;
+pub mod pull_requests;
+pub mod recent_files;
+pub mod search_results;
+pub mod status_bar;
+pub mod toast;
+pub mod workspace_switcher;
 pub mod workspaces;
 
+pub use blame::BlameView;
+pub use captured_output::CapturedOutputView;
 pub use command_bar::CommandBar;
+pub use command_palette::CommandPaletteView;
+pub use conversations::ConversationsView;
 pub use file_browser::FileBrowserView;
+pub use file_finder::FileFinderView;
+pub use header::Header;
+pub use mcp_servers::McpServersView;
+pub use popup::PopupView;
+pub use progress::Progress;
+pub use project_menu::ProjectMenuView;
 pub use projects::ProjectsView;
+pub use prompt::PromptView;
+pub use pull_requests::PullRequestsView;
+pub use recent_files::RecentFilesView;
+pub use search_results::SearchResultsView;
+pub use status_bar::{PanelStats, StatusBar};
+pub use toast::ToastView;
+pub use workspace_switcher::WorkspaceSwitcherView;
 pub use workspaces::WorkspacesView;
+
+#[cfg(test)]
+mod snapshot_tests;
@@ -0,0 +1,76 @@
+//! Popup component for the TUI.
+//!
+//! Displays a bordered, centered panel with wrapped text over the current view.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::tui::app::Popup;
+
+/// Percentage of the available width and height covered by the popup.
+const POPUP_PERCENT: u16 = 80;
+
+/// View component for displaying a popup.
+pub struct PopupView<'a> {
+    popup: &'a Popup,
+}
+
+impl<'a> PopupView<'a> {
+    /// Creates a new PopupView for the given popup.
+    ///
+    /// # Arguments
+    ///
+    /// * `popup` - The popup to display
+    ///
+    /// # Returns
+    ///
+    /// A new PopupView instance.
+    pub fn new(popup: &'a Popup) -> Self {
+        Self { popup }
+    }
+
+    /// Renders the popup centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the popup is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered(area, POPUP_PERCENT);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(format!(" {} ", self.popup.title))
+            .title_bottom(" any key: close ")
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+
+        let paragraph = Paragraph::new(self.popup.body.as_str())
+            .wrap(Wrap { trim: false })
+            .block(block);
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(paragraph, popup_area);
+    }
+}
+
+/// Returns a rectangle covering `percent` of the area, centered within it.
+pub(crate) fn centered(area: Rect, percent: u16) -> Rect {
+    let width = area.width * percent / 100;
+    let height = area.height * percent / 100;
+
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
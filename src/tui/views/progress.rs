@@ -0,0 +1,129 @@
+//! Spinner and progress bar shown while work runs in the background.
+//!
+//! The animation advances with the runner's spinner tick, which only moves
+//! while something is running, so an idle panel is never redrawn for it.
+//! Long operations (git refresh, clones, health checks, searches) are listed
+//! as `Progress` items in the header.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use crate::util::glyphs;
+
+/// Frames of the spinner.
+const FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Frames of the spinner with `ui.ascii_only`.
+const ASCII_FRAMES: &[&str] = &["|", "/", "-", "\\"];
+
+/// Columns of a progress bar, without its brackets.
+const BAR_WIDTH: usize = 8;
+
+/// Returns the spinner frame shown at a tick.
+///
+/// # Arguments
+///
+/// * `tick` - The runner's spinner tick
+pub fn spinner(tick: u64) -> &'static str {
+    let frames = if glyphs::ascii_only() {
+        ASCII_FRAMES
+    } else {
+        FRAMES
+    };
+    frames[(tick % frames.len() as u64) as usize]
+}
+
+/// Returns a progress bar such as `[███░░░░░]`.
+///
+/// # Arguments
+///
+/// * `done` - The units of work done
+/// * `total` - The units of work in all; an empty bar when 0
+/// * `width` - The columns of the bar, without its brackets
+pub fn bar(done: usize, total: usize, width: usize) -> String {
+    let filled = (done.min(total) * width).checked_div(total).unwrap_or(0);
+    format!(
+        "[{}{}]",
+        glyphs::glyph("█", "#").repeat(filled),
+        glyphs::glyph("░", "-").repeat(width - filled)
+    )
+}
+
+/// A background operation in progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    label: String,
+    count: Option<(usize, usize)>,
+}
+
+impl Progress {
+    /// Creates an operation whose completion is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - What runs (e.g. "search")
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            count: None,
+        }
+    }
+
+    /// Sets how much of the operation is done, shown as a bar and a count.
+    ///
+    /// # Arguments
+    ///
+    /// * `done` - The units of work done
+    /// * `total` - The units of work in all
+    pub fn with_count(mut self, done: usize, total: usize) -> Self {
+        self.count = Some((done, total));
+        self
+    }
+
+    /// Returns the text shown at a tick, e.g. `⠹ git [███░░░░░] 3/8`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick` - The runner's spinner tick
+    pub fn text(&self, tick: u64) -> String {
+        match self.count {
+            Some((done, total)) => format!(
+                "{} {} {} {}/{}",
+                spinner(tick),
+                self.label,
+                bar(done, total, BAR_WIDTH),
+                done,
+                total
+            ),
+            None => format!("{} {}", spinner(tick), self.label),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_ticking_should_cycle_through_frames() {
+        assert_eq!(spinner(0), "⠋");
+        assert_eq!(spinner(1), "⠙");
+        assert_eq!(spinner(FRAMES.len() as u64), "⠋");
+    }
+
+    #[test]
+    fn when_drawing_bar_should_fill_in_proportion() {
+        assert_eq!(bar(0, 4, 4), "[░░░░]");
+        assert_eq!(bar(1, 4, 4), "[█░░░]");
+        assert_eq!(bar(9, 4, 4), "[████]");
+        assert_eq!(bar(0, 0, 2), "[░░]");
+    }
+
+    #[test]
+    fn when_formatting_progress_should_show_spinner_bar_and_count() {
+        assert_eq!(Progress::new("search").text(2), "⠹ search");
+        assert_eq!(
+            Progress::new("git").with_count(2, 8).text(0),
+            "⠋ git [██░░░░░░] 2/8"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Project menu component for the TUI.
+//!
+//! Lists everything that can be done with the selected project, with the key
+//! that does it, so features can be found without memorizing keys.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+
+use crate::tui::views::popup::centered;
+
+/// Percentage of the available width and height covered by the menu.
+const MENU_PERCENT: u16 = 60;
+
+/// View component for the project menu popup.
+pub struct ProjectMenuView<'a> {
+    project_name: &'a str,
+    entries: &'a [(&'a str, &'a str)],
+    selected: usize,
+}
+
+impl<'a> ProjectMenuView<'a> {
+    /// Creates a new ProjectMenuView.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_name` - The name of the project shown in the title
+    /// * `entries` - The entries as (key, label), the key empty when none
+    /// * `selected` - Index of the selected row
+    ///
+    /// # Returns
+    ///
+    /// A new ProjectMenuView instance.
+    pub fn new(project_name: &'a str, entries: &'a [(&'a str, &'a str)], selected: usize) -> Self {
+        Self {
+            project_name,
+            entries,
+            selected,
+        }
+    }
+
+    /// Renders the menu centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the menu is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let menu_area = centered(area, MENU_PERCENT);
+        let key_width = self
+            .entries
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, (key, label))| {
+                let style = if index == self.selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let prefix = if index == self.selected { "> " } else { "  " };
+                ListItem::new(Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled(
+                        format!("{:<width$}  ", key, width = key_width),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(*label, style),
+                ]))
+            })
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", self.project_name))
+            .title_bottom(" Enter: run  Esc: close ")
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+
+        frame.render_widget(Clear, menu_area);
+        frame.render_widget(List::new(items).block(block), menu_area);
+    }
+}
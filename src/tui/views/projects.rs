@@ -7,6 +7,8 @@
 
 #![allow(dead_code)]
 
+use std::collections::BTreeMap;
+
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -15,8 +17,44 @@ use ratatui::{
     Frame,
 };
 
-use crate::config::{Action, Config, Workspace};
-use crate::git::{get_git_info, GitInfo};
+use crate::config::{Action, CompactMode, Config, Project, Workspace};
+use crate::git::clone::CloneStatus;
+use crate::git::{get_git_info_compared, GitCompare, GitInfo, GitSeverity};
+use crate::health::HealthStatus;
+use crate::remote::{PipelineStatus, RemoteState};
+use crate::tui::views::columns;
+use crate::tui::views::status_bar::Hint;
+use crate::tui::views::workspaces::with_description;
+use crate::util::glyphs::{self, glyph};
+
+/// Panels narrower than this draw the project list compactly, unless
+/// `ui.compact` says otherwise.
+const COMPACT_WIDTH: u16 = 44;
+
+/// Badge shown before projects whose Claude pane waits for input.
+fn attention_badge() -> &'static str {
+    glyph("🔔 ", "(!) ")
+}
+
+/// Badge shown before the project whose pane Zellij focuses.
+fn focus_badge() -> &'static str {
+    glyph("◉ ", "* ")
+}
+
+/// Badge after the icon of an action whose last background run passed.
+fn passed_badge() -> &'static str {
+    glyph("✓", "+")
+}
+
+/// Badge after the icon of an action whose last background run failed.
+fn failed_badge() -> &'static str {
+    glyph("✗", "x")
+}
+
+/// Marker before the latest line of a project's Claude conversation.
+fn snippet_marker() -> &'static str {
+    glyph("└", "`-")
+}
 
 /// View component for displaying and selecting projects within a workspace.
 ///
@@ -28,6 +66,13 @@ pub struct ProjectsView<'a> {
     workspace_id: &'a str,
     selected: usize,
     git_info_cache: Vec<Option<GitInfo>>,
+    health: Vec<Option<HealthStatus>>,
+    remote: Vec<Option<RemoteState>>,
+    clones: Vec<Option<CloneStatus>>,
+    attention: Vec<bool>,
+    snippets: Vec<Option<String>>,
+    focused: Option<usize>,
+    outcomes: Vec<BTreeMap<String, bool>>,
 }
 
 impl<'a> ProjectsView<'a> {
@@ -46,11 +91,183 @@ impl<'a> ProjectsView<'a> {
     /// A new ProjectsView instance with pre-loaded git information.
     pub fn new(config: &'a Config, workspace_id: &'a str, selected: usize) -> Self {
         let git_info_cache = Self::load_git_info(config, workspace_id);
+        Self::with_git_info(config, workspace_id, selected, git_info_cache)
+    }
+
+    /// Creates a new ProjectsView with pre-computed git information.
+    ///
+    /// Used by the runner to render from its git cache instead of querying
+    /// every repository on each frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Reference to the application configuration containing workspaces
+    /// * `workspace_id` - The identifier of the workspace to display
+    /// * `selected` - Index of the currently selected project
+    /// * `git_info_cache` - Git information for each project, in project order
+    ///
+    /// # Returns
+    ///
+    /// A new ProjectsView instance.
+    pub fn with_git_info(
+        config: &'a Config,
+        workspace_id: &'a str,
+        selected: usize,
+        git_info_cache: Vec<Option<GitInfo>>,
+    ) -> Self {
         Self {
             config,
             workspace_id,
             selected,
             git_info_cache,
+            health: Vec::new(),
+            remote: Vec::new(),
+            clones: Vec::new(),
+            attention: Vec::new(),
+            snippets: Vec::new(),
+            focused: None,
+            outcomes: Vec::new(),
+        }
+    }
+
+    /// Sets the health check status displayed next to each project.
+    ///
+    /// # Arguments
+    ///
+    /// * `health` - Health status for each project, in project order
+    ///
+    /// # Returns
+    ///
+    /// The view with the health statuses set.
+    pub fn with_health(mut self, health: Vec<Option<HealthStatus>>) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Sets the pull request and pipeline status displayed after each project.
+    ///
+    /// # Arguments
+    ///
+    /// * `remote` - Remote state for each project, in project order
+    ///
+    /// # Returns
+    ///
+    /// The view with the remote states set.
+    pub fn with_remote(mut self, remote: Vec<Option<RemoteState>>) -> Self {
+        self.remote = remote;
+        self
+    }
+
+    /// Sets the clone status displayed next to projects that aren't cloned yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `clones` - Clone status for each project, in project order
+    ///
+    /// # Returns
+    ///
+    /// The view with the clone statuses set.
+    pub fn with_clones(mut self, clones: Vec<Option<CloneStatus>>) -> Self {
+        self.clones = clones;
+        self
+    }
+
+    /// Sets which projects have a Claude pane waiting for input.
+    ///
+    /// # Arguments
+    ///
+    /// * `attention` - Whether each project needs attention, in project order
+    ///
+    /// # Returns
+    ///
+    /// The view with the attention flags set.
+    pub fn with_attention(mut self, attention: Vec<bool>) -> Self {
+        self.attention = attention;
+        self
+    }
+
+    /// Sets the latest line of each project's Claude conversation, shown
+    /// dimmed under the project.
+    ///
+    /// # Arguments
+    ///
+    /// * `snippets` - The latest transcript line of each project, in project order
+    ///
+    /// # Returns
+    ///
+    /// The view with the snippets set.
+    pub fn with_snippets(mut self, snippets: Vec<Option<String>>) -> Self {
+        self.snippets = snippets;
+        self
+    }
+
+    /// Sets the project whose pane Zellij focuses, marked in the list.
+    ///
+    /// # Arguments
+    ///
+    /// * `focused` - The index of the project, None if no project pane has
+    ///   the focus
+    ///
+    /// # Returns
+    ///
+    /// The view with the focused project set.
+    pub fn with_focused(mut self, focused: Option<usize>) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Sets whether the last background run of each action passed, shown as
+    /// a badge after its icon.
+    ///
+    /// # Arguments
+    ///
+    /// * `outcomes` - For each project, in project order, whether the last
+    ///   run of each action passed, by action key
+    ///
+    /// # Returns
+    ///
+    /// The view with the outcomes set.
+    pub fn with_outcomes(mut self, outcomes: Vec<BTreeMap<String, bool>>) -> Self {
+        self.outcomes = outcomes;
+        self
+    }
+
+    /// Returns the line showing a project's latest Claude snippet, if any.
+    fn snippet_line(&self, project_index: usize) -> Option<Line<'_>> {
+        let snippet = self.snippets.get(project_index)?.as_deref()?;
+        Some(Line::styled(
+            format!("    {} {}", snippet_marker(), snippet),
+            Style::default().fg(Color::DarkGray),
+        ))
+    }
+
+    /// Returns the clone badge of a project and its color.
+    fn clone_badge(&self, project_index: usize) -> Option<(String, Color)> {
+        let status = self.clones.get(project_index)?.as_ref()?;
+        let color = match status {
+            CloneStatus::NotCloned => Color::DarkGray,
+            CloneStatus::Cloning(_) => Color::Cyan,
+        };
+        Some((status.badge(), color))
+    }
+
+    /// Returns the remote badge of a project and its color.
+    ///
+    /// Failed fetches show a warning sign; the details are shown with 'P'.
+    fn remote_badge(&self, project_index: usize) -> Option<(String, Color)> {
+        match self.remote.get(project_index)?.as_ref()? {
+            RemoteState::Loading => None,
+            RemoteState::Failed(_) => Some((glyph("⚠", "?").to_string(), Color::DarkGray)),
+            RemoteState::Ready(status) => {
+                let badge = status.badge();
+                let color = match status.pipeline {
+                    Some(PipelineStatus::Success) => Color::Green,
+                    Some(PipelineStatus::Failed) => Color::Red,
+                    Some(PipelineStatus::Running | PipelineStatus::Pending) => Color::Yellow,
+                    Some(PipelineStatus::Canceled) | None => Color::DarkGray,
+                };
+                (!badge.is_empty()).then_some((badge, color))
+            }
         }
     }
 
@@ -72,7 +289,13 @@ impl<'a> ProjectsView<'a> {
         workspace
             .projects
             .iter()
-            .map(|project| get_git_info(&project.path, config.global.git_info_level))
+            .map(|project| {
+                get_git_info_compared(
+                    &project.path,
+                    config.global.git_info_level,
+                    &GitCompare::resolve(config, project),
+                )
+            })
             .collect()
     }
 
@@ -129,49 +352,34 @@ impl<'a> ProjectsView<'a> {
 
     /// Renders the projects view to the terminal frame.
     ///
-    /// The layout consists of three areas:
-    /// - Title area (3 lines): displays "{workspace.name} - Projects" header with cyan styling
-    /// - List area (flexible): displays project names with git info and action icons
-    /// - Help area (3 lines): displays keyboard navigation hints and action shortcuts
+    /// Displays project names with git info and action icons; key hints are
+    /// shown by the status bar, see `hints`.
     ///
     /// # Arguments
     ///
     /// * `frame` - The terminal frame to render to
     /// * `area` - The rectangular area to render within
     pub fn render(&self, frame: &mut Frame, area: Rect) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),
-                Constraint::Min(1),
-                Constraint::Length(3),
-            ])
-            .split(area);
-
-        self.render_title(frame, chunks[0]);
-        self.render_list(frame, chunks[1]);
-        self.render_help(frame, chunks[2]);
-    }
-
-    /// Renders the title area with workspace name and "Projects" header.
-    fn render_title(&self, frame: &mut Frame, area: Rect) {
-        let title_text = self
-            .workspace()
-            .map(|w| format!("{} - Projects", w.name))
-            .unwrap_or_else(|| "Projects".to_string());
-
-        let title = Paragraph::new(title_text)
-            .style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .block(Block::default().borders(Borders::BOTTOM));
-
-        frame.render_widget(title, area);
+        self.render_list(frame, area);
+    }
+
+    /// Returns whether the list is drawn compactly at a width: without
+    /// action icons and with the git status as a single glyph.
+    fn is_compact(&self, width: u16) -> bool {
+        match self.config.ui.compact {
+            CompactMode::Auto => width < COMPACT_WIDTH,
+            CompactMode::Always => true,
+            CompactMode::Never => false,
+        }
     }
 
     /// Renders the list of projects with git info and action icons.
+    ///
+    /// Rows are laid out in columns: name, git status (with the operation,
+    /// clone and remote badges), then action icons. Compact rows, see `is_compact`,
+    /// have no icons and show the git status as a glyph. Narrow panels
+    /// truncate the names with an ellipsis, then drop the icons, then the
+    /// git status.
     fn render_list(&self, frame: &mut Frame, area: Rect) {
         let Some(workspace) = self.workspace() else {
             let list = List::new(Vec::<ListItem>::new());
@@ -179,67 +387,56 @@ impl<'a> ProjectsView<'a> {
             return;
         };
 
-        let items: Vec<ListItem> = workspace
+        let compact = self.is_compact(area.width);
+        let rows: Vec<[Vec<Span>; 3]> = workspace
             .projects
             .iter()
             .enumerate()
-            .map(|(index, project)| {
-                let git_info_text = self
-                    .git_info_cache
-                    .get(index)
-                    .and_then(|opt| opt.as_ref())
-                    .map(|info| info.format_minimal())
-                    .unwrap_or_default();
-
-                let icons = self.collect_action_icons(index);
-
-                if index == self.selected {
-                    let mut spans = vec![
-                        Span::styled(
-                            "> ",
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::styled(
-                            &project.name,
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                    ];
-
-                    if !git_info_text.is_empty() {
-                        spans.push(Span::styled(
-                            format!("  {}", git_info_text),
-                            Style::default().fg(Color::DarkGray),
-                        ));
-                    }
-
-                    if !icons.is_empty() {
-                        spans.push(Span::styled(
-                            format!("  {}", icons),
-                            Style::default().fg(Color::Yellow),
-                        ));
-                    }
+            .map(|(index, project)| self.row_cells(index, project, compact))
+            .collect();
+        let natural: Vec<usize> = (0..3)
+            .map(|column| {
+                rows.iter()
+                    .map(|cells| cells[column].iter().map(Span::width).sum())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let widths = columns::fit_columns(&natural, (area.width as usize).saturating_sub(2));
 
-                    ListItem::new(Line::from(spans))
+        let items: Vec<ListItem> = rows
+            .into_iter()
+            .enumerate()
+            .map(|(index, cells)| {
+                let selected = index == self.selected;
+                let mut spans = vec![if selected {
+                    Span::styled(
+                        "> ",
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
                 } else {
-                    let mut spans = vec![Span::raw("  "), Span::raw(&project.name)];
-
-                    if !git_info_text.is_empty() {
-                        spans.push(Span::styled(
-                            format!("  {}", git_info_text),
-                            Style::default().fg(Color::DarkGray),
-                        ));
+                    Span::raw("  ")
+                }];
+                for (column, (cell, width)) in cells.into_iter().zip(&widths).enumerate() {
+                    if *width == 0 {
+                        continue;
                     }
-
-                    if !icons.is_empty() {
-                        spans.push(Span::raw(format!("  {}", icons)));
+                    if column > 0 {
+                        spans.push(Span::raw(" ".repeat(columns::GAP)));
                     }
-
-                    ListItem::new(Line::from(spans))
+                    spans.extend(columns::fit_spans(cell, *width));
                 }
+
+                let mut lines = if selected {
+                    let description = workspace.projects[index].description.as_deref();
+                    with_description(Line::from(spans), description)
+                } else {
+                    vec![Line::from(spans)]
+                };
+                lines.extend(self.snippet_line(index));
+                ListItem::new(lines)
             })
             .collect();
 
@@ -247,40 +444,181 @@ impl<'a> ProjectsView<'a> {
         frame.render_widget(list, area);
     }
 
-    /// Collects action icons for a specific project.
-    fn collect_action_icons(&self, project_index: usize) -> String {
+    /// Returns the cells of a project's row: its name with the focus, health
+    /// and attention badges, its git status with the operation, clone and
+    /// remote badges, and its action icons.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the project
+    /// * `project` - The project
+    /// * `compact` - Whether the git status is a glyph and icons are left out
+    fn row_cells(&self, index: usize, project: &'a Project, compact: bool) -> [Vec<Span<'a>>; 3] {
+        let selected = index == self.selected;
+        let highlight = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+
+        let mut badge = self
+            .health
+            .get(index)
+            .and_then(|opt| opt.as_ref())
+            .map(|status| format!("{} ", status.badge()))
+            .unwrap_or_default();
+        if self.attention.get(index).copied().unwrap_or(false) {
+            badge.insert_str(0, attention_badge());
+        }
+        if self.focused == Some(index) {
+            badge.insert_str(0, focus_badge());
+        }
+        let name_style = if selected {
+            highlight
+        } else {
+            Style::default()
+        };
+        let name = vec![
+            Span::raw(badge),
+            Span::styled(project.name.as_str(), name_style),
+        ];
+
+        let git_info = self.git_info_cache.get(index).and_then(|opt| opt.as_ref());
+        let git_style = Style::default().fg(git_info.map_or(Color::DarkGray, |info| {
+            severity_color(info.severity(self.config.resolve_behind_warn(project)))
+        }));
+        let mut status: Vec<Span> = Vec::new();
+        // A merge or rebase left in progress needs attention, even when compact
+        let operation = git_info
+            .and_then(|info| info.operation_badge())
+            .map(|text| {
+                (
+                    text,
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )
+            });
+        let git = git_info
+            .map(|info| {
+                let text = if compact {
+                    info.format_glyph().to_string()
+                } else {
+                    info.format_minimal()
+                };
+                (text, git_style)
+            })
+            .filter(|(text, _)| !text.is_empty());
+        let clone = self
+            .clone_badge(index)
+            .map(|(text, color)| (text, Style::default().fg(color)));
+        let remote = self
+            .remote_badge(index)
+            .map(|(text, color)| (text, Style::default().fg(color)));
+        for (text, style) in [operation, git, clone, remote].into_iter().flatten() {
+            if !status.is_empty() {
+                status.push(Span::raw("  "));
+            }
+            status.push(Span::styled(text, style));
+        }
+
+        let icon_style = if selected {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        let mut icons: Vec<Span> = Vec::new();
+        if !compact {
+            for (icon, outcome) in self.collect_action_icons(index) {
+                if !icons.is_empty() {
+                    icons.push(Span::raw(" "));
+                }
+                icons.push(Span::styled(icon, icon_style));
+                match outcome {
+                    Some(true) => icons.push(Span::styled(
+                        passed_badge(),
+                        Style::default().fg(Color::Green),
+                    )),
+                    Some(false) => icons.push(Span::styled(
+                        failed_badge(),
+                        Style::default().fg(Color::Red),
+                    )),
+                    None => {}
+                }
+            }
+        }
+
+        [name, status, icons]
+    }
+
+    /// Collects the action icons of a project, sorted by key, with whether
+    /// the last background run of each action passed.
+    ///
+    /// Actions without an icon are shown by their key once they ran in the
+    /// background, so their badge has something to follow.
+    fn collect_action_icons(&self, project_index: usize) -> Vec<(String, Option<bool>)> {
         let actions = self
             .config
             .resolve_actions(self.workspace_id, project_index);
         let mut sorted: Vec<(&String, &Action)> = actions.iter().collect();
         sorted.sort_by(|a, b| a.0.cmp(b.0));
+        let outcomes = self.outcomes.get(project_index);
 
         sorted
             .iter()
-            .filter_map(|(_, action)| action.icon.as_ref())
-            .cloned()
-            .collect::<Vec<String>>()
-            .join(" ")
-    }
-
-    /// Renders the help area with keyboard navigation hints and action shortcuts.
-    fn render_help(&self, frame: &mut Frame, area: Rect) {
-        let actions = self.resolved_actions();
-        let action_hints: Vec<String> = actions
-            .iter()
-            .map(|(key, action)| {
-                let icon = action.icon.as_deref().unwrap_or("");
-                format!("{}{}: {}", icon, key, action.name)
+            .filter_map(|(key, action)| {
+                let outcome = outcomes.and_then(|o| o.get(*key)).copied();
+                let icon = action.icon.as_deref().and_then(glyphs::icon);
+                match (icon, outcome) {
+                    (Some(icon), _) => Some((icon.to_string(), outcome)),
+                    (None, Some(_)) => Some((key.to_string(), outcome)),
+                    (None, None) => None,
+                }
             })
-            .collect();
-
-        let help_text = format!("Enter: browse  {}  Esc: back", action_hints.join("  "));
+            .collect()
+    }
 
-        let help = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::DarkGray))
-            .block(Block::default().borders(Borders::TOP));
+    /// Returns the key hints of the view, for the status bar: browsing and
+    /// actions first, then the less used keys.
+    pub fn hints(&self) -> Vec<Hint> {
+        let mut hints = vec![
+            Hint::new("Enter/Alt-Enter", "browse/default action"),
+            Hint::new("m", "menu"),
+        ];
+        hints.extend(self.resolved_actions().iter().map(|(key, action)| {
+            let icon = action.icon.as_deref().and_then(glyphs::icon);
+            Hint::new(format!("{}{}", icon.unwrap_or(""), key), &action.name)
+        }));
+        hints.extend([
+            Hint::new("x/R", "kill/restart"),
+            Hint::new("f", "web focus"),
+            Hint::new("z/e/A", "zoom/embed/tabs"),
+            Hint::new("N", "dry run"),
+        ]);
+        if self.config.global.remote.is_some() {
+            hints.push(Hint::new("P", "pull requests"));
+        }
+        if self.config.has_mcp_servers() {
+            hints.push(Hint::new("M", "MCP servers"));
+        }
+        hints.extend([
+            Hint::new("O/B", "reveal/remote"),
+            Hint::new("L", "log"),
+            Hint::new("F", "fetch"),
+            Hint::new("^R", "refresh all"),
+            Hint::new("n", "new task"),
+            Hint::new("a", "add"),
+            Hint::new("D", "remove"),
+            Hint::new("w", "workspaces"),
+            Hint::new("Esc", "back"),
+        ]);
+        hints
+    }
+}
 
-        frame.render_widget(help, area);
+/// Returns the color of a project's git summary: green when clean, yellow
+/// with uncommitted changes, red with conflicts or far behind upstream.
+pub(crate) fn severity_color(severity: GitSeverity) -> Color {
+    match severity {
+        GitSeverity::Clean => Color::Green,
+        GitSeverity::Dirty => Color::Yellow,
+        GitSeverity::Risky => Color::Red,
     }
 }
 
@@ -288,6 +626,8 @@ impl<'a> ProjectsView<'a> {
 mod tests {
     use super::*;
     use crate::config::{GlobalConfig, Project, WebClientConfig};
+    use indexmap::IndexMap;
+    use ratatui::{backend::TestBackend, Terminal};
     use std::collections::HashMap;
     use std::path::PathBuf;
 
@@ -299,6 +639,7 @@ mod tests {
                 name: "Claude".to_string(),
                 command: "claude".to_string(),
                 icon: Some("C".to_string()),
+                ..Default::default()
             },
         );
 
@@ -309,6 +650,7 @@ mod tests {
                 name: "Tests".to_string(),
                 command: "cargo test".to_string(),
                 icon: Some("T".to_string()),
+                ..Default::default()
             },
         );
 
@@ -318,64 +660,146 @@ mod tests {
                 path: PathBuf::from("/tmp/alpha"),
                 actions: HashMap::new(),
                 command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
             },
             Project {
                 name: "Project Beta".to_string(),
                 path: PathBuf::from("/tmp/beta"),
                 actions: HashMap::new(),
                 command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
             },
             Project {
                 name: "Project Gamma".to_string(),
                 path: PathBuf::from("/tmp/gamma"),
                 actions: HashMap::new(),
                 command_bar: vec![],
+                file_browser: None,
+                check: None,
+                git: None,
+                repo: None,
+                bookmarks: Default::default(),
+                description: None,
+                default_action: None,
+                roots: Vec::new(),
+                mcp: Default::default(),
+                env: None,
+                autostart: false,
+                sandboxed: false,
             },
         ];
 
-        let mut workspaces = HashMap::new();
+        let mut workspaces = IndexMap::new();
         workspaces.insert(
             "fanki".to_string(),
             Workspace {
                 name: "Fanki".to_string(),
+                order: None,
+                description: None,
                 actions: workspace_actions,
                 command_bar: vec![],
                 projects,
+                working_set: Vec::new(),
+                discover: None,
+                default_action: None,
+                mcp: Default::default(),
             },
         );
 
         Config {
             global: GlobalConfig {
                 editor: "$EDITOR".to_string(),
+                shell: "$SHELL".to_string(),
                 git_info_level: Default::default(),
+                file_browser: Default::default(),
+                key_conflicts: Default::default(),
+                git_ui: Default::default(),
+                git: Default::default(),
+                remote: None,
+                zellij: Default::default(),
+                navigation: Default::default(),
+                claude_idle: Default::default(),
+                task: Default::default(),
                 actions: global_actions,
                 command_bar: vec![],
+                project_enter: Default::default(),
+                mcp: Default::default(),
             },
             web_client: WebClientConfig::default(),
+            top_bar: Default::default(),
+            layout: Default::default(),
+            notifications: Default::default(),
+            ui: Default::default(),
+            state_endpoint: Default::default(),
             workspace: workspaces,
         }
     }
 
     fn create_empty_workspace_config() -> Config {
-        let mut workspaces = HashMap::new();
+        let mut workspaces = IndexMap::new();
         workspaces.insert(
             "empty".to_string(),
             Workspace {
                 name: "Empty Workspace".to_string(),
+                order: None,
+                description: None,
                 actions: HashMap::new(),
                 command_bar: vec![],
                 projects: vec![],
+                working_set: Vec::new(),
+                discover: None,
+                default_action: None,
+                mcp: Default::default(),
             },
         );
 
         Config {
             global: GlobalConfig {
                 editor: "$EDITOR".to_string(),
+                shell: "$SHELL".to_string(),
                 git_info_level: Default::default(),
+                file_browser: Default::default(),
+                key_conflicts: Default::default(),
+                git_ui: Default::default(),
+                git: Default::default(),
+                remote: None,
+                zellij: Default::default(),
+                navigation: Default::default(),
+                claude_idle: Default::default(),
+                task: Default::default(),
                 actions: HashMap::new(),
                 command_bar: vec![],
+                project_enter: Default::default(),
+                mcp: Default::default(),
             },
             web_client: WebClientConfig::default(),
+            top_bar: Default::default(),
+            layout: Default::default(),
+            notifications: Default::default(),
+            ui: Default::default(),
+            state_endpoint: Default::default(),
             workspace: workspaces,
         }
     }
@@ -410,6 +834,134 @@ mod tests {
         assert_eq!(actions.len(), 2);
     }
 
+    #[test]
+    fn when_project_pane_has_focus_should_mark_its_row() {
+        let config = create_test_config_with_projects();
+        let projects = &config.workspace["fanki"].projects;
+        let view = ProjectsView::with_git_info(&config, "fanki", 0, Vec::new())
+            .with_attention(vec![false, true, false])
+            .with_focused(Some(1));
+
+        let name = |index: usize| -> String {
+            view.row_cells(index, &projects[index], false)[0]
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect()
+        };
+
+        assert_eq!(name(1), format!("◉ 🔔 {}", projects[1].name));
+        assert_eq!(name(0), projects[0].name);
+    }
+
+    #[test]
+    fn when_actions_ran_in_background_should_show_their_outcome_after_the_icon() {
+        let config = create_test_config_with_projects();
+        let projects = &config.workspace["fanki"].projects;
+        let outcomes = vec![
+            BTreeMap::from([("t".to_string(), false)]),
+            BTreeMap::from([("c".to_string(), true), ("x".to_string(), true)]),
+        ];
+        let view =
+            ProjectsView::with_git_info(&config, "fanki", 0, Vec::new()).with_outcomes(outcomes);
+
+        let icons = |index: usize| -> String {
+            view.row_cells(index, &projects[index], false)[2]
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect()
+        };
+
+        assert_eq!(icons(0), "C T✗");
+        assert_eq!(icons(1), "C✓ T");
+        assert_eq!(icons(2), "C T");
+    }
+
+    /// Renders the projects of the test config into a terminal of a width,
+    /// returning its lines without trailing spaces.
+    fn render_snapshot(config: &Config, width: u16) -> Vec<String> {
+        let git_info = vec![
+            Some(GitInfo {
+                branch: Some("main".to_string()),
+                is_dirty: true,
+                ..Default::default()
+            }),
+            Some(GitInfo {
+                branch: Some("develop".to_string()),
+                behind: 2,
+                ..Default::default()
+            }),
+            None,
+        ];
+        let view = ProjectsView::with_git_info(config, "fanki", 0, git_info);
+        let mut terminal = Terminal::new(TestBackend::new(width, 3)).unwrap();
+        terminal
+            .draw(|frame| view.render(frame, frame.area()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                let line: String = (0..width).map(|x| buffer[(x, y)].symbol()).collect();
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn when_panel_is_wide_should_show_branches_and_icons() {
+        let config = create_test_config_with_projects();
+
+        assert_eq!(
+            render_snapshot(&config, 60),
+            vec![
+                "> Project Alpha  main *   C T",
+                "  Project Beta   develop  C T",
+                "  Project Gamma           C T",
+            ]
+        );
+    }
+
+    #[test]
+    fn when_panel_is_narrow_should_show_git_glyphs_without_icons() {
+        let config = create_test_config_with_projects();
+
+        assert_eq!(
+            render_snapshot(&config, 40),
+            vec![
+                "> Project Alpha  ●",
+                "  Project Beta   ↓",
+                "  Project Gamma",
+            ]
+        );
+        assert_eq!(
+            render_snapshot(&config, 16),
+            vec!["> Project Al…  ●", "  Project Be…  ↓", "  Project Ga…"]
+        );
+    }
+
+    #[test]
+    fn when_compact_mode_is_set_should_ignore_the_width() {
+        let mut config = create_test_config_with_projects();
+        config.ui.compact = CompactMode::Always;
+        assert_eq!(
+            render_snapshot(&config, 60),
+            vec![
+                "> Project Alpha  ●",
+                "  Project Beta   ↓",
+                "  Project Gamma",
+            ]
+        );
+
+        config.ui.compact = CompactMode::Never;
+        assert_eq!(
+            render_snapshot(&config, 40),
+            vec![
+                "> Project Alpha  main *   C T",
+                "  Project Beta   develop  C T",
+                "  Project Gamma           C T",
+            ]
+        );
+    }
+
     #[test]
     fn when_workspace_not_found_should_return_empty() {
         let config = create_empty_workspace_config();
@@ -0,0 +1,99 @@
+//! Prompt component for the TUI.
+//!
+//! Displays a single-line text input over the bottom of the current view.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::tui::app::{Prompt, PromptKind};
+
+/// Height of the prompt, including its borders.
+const PROMPT_HEIGHT: u16 = 3;
+
+/// View component for displaying a prompt.
+pub struct PromptView<'a> {
+    prompt: &'a Prompt,
+}
+
+impl<'a> PromptView<'a> {
+    /// Creates a new PromptView for the given prompt.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The prompt to display
+    ///
+    /// # Returns
+    ///
+    /// A new PromptView instance.
+    pub fn new(prompt: &'a Prompt) -> Self {
+        Self { prompt }
+    }
+
+    /// Returns the key hints shown in the bottom border.
+    fn hints(&self) -> &'static str {
+        match self.prompt.kind {
+            PromptKind::ProjectName => " Enter: next  Esc: cancel ",
+            PromptKind::ProjectPath { .. } => " Tab: complete  Enter: add  Esc: cancel ",
+            PromptKind::ConfirmRemove { .. } => " y: remove  any other key: cancel ",
+            PromptKind::ConfirmClone { .. } => " y: clone  any other key: cancel ",
+            PromptKind::SearchQuery => " Enter: search  Esc: cancel ",
+            PromptKind::ConfirmRestoreSession => " y: restore backup  any other key: start fresh ",
+            PromptKind::TaskName { .. } => " Enter: next  Esc: cancel ",
+            PromptKind::TaskWorktree { .. } => " y: worktree  n: project checkout  Esc: cancel ",
+            PromptKind::Argument { .. } => " Up/Down: history  Enter: next  Esc: cancel ",
+        }
+    }
+
+    /// Renders the prompt over the last lines of the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area whose bottom lines the prompt covers
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let height = PROMPT_HEIGHT.min(area.height);
+        let prompt_area = Rect {
+            x: area.x,
+            y: area.y + area.height - height,
+            width: area.width,
+            height,
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title_bottom(self.hints());
+
+        let mut spans = vec![Span::styled(
+            format!("{} ", self.prompt.label),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )];
+        if !matches!(
+            self.prompt.kind,
+            PromptKind::ConfirmRemove { .. }
+                | PromptKind::ConfirmClone { .. }
+                | PromptKind::ConfirmRestoreSession
+                | PromptKind::TaskWorktree { .. }
+        ) {
+            spans.push(Span::raw(self.prompt.input.as_str()));
+            spans.push(Span::styled(
+                " ",
+                Style::default().add_modifier(Modifier::REVERSED),
+            ));
+        }
+
+        frame.render_widget(Clear, prompt_area);
+        frame.render_widget(Paragraph::new(Line::from(spans)).block(block), prompt_area);
+    }
+}
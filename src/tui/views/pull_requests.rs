@@ -0,0 +1,118 @@
+//! Pull requests popup component for the TUI.
+//!
+//! Lists the open pull requests (or merge requests) of a project's remote
+//! repository so one can be opened in the browser.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+
+use crate::remote::RemoteStatus;
+use crate::tui::views::popup::centered;
+use crate::util::glyphs::glyph;
+
+/// Percentage of the available width and height covered by the popup.
+const PULL_REQUESTS_PERCENT: u16 = 80;
+
+/// View component for the pull requests popup.
+///
+/// Pull requests from the current branch come first and are marked with `*`.
+pub struct PullRequestsView<'a> {
+    project_name: &'a str,
+    status: &'a RemoteStatus,
+    selected: usize,
+}
+
+impl<'a> PullRequestsView<'a> {
+    /// Creates a new PullRequestsView.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_name` - The name of the project shown in the title
+    /// * `status` - The project's remote status
+    /// * `selected` - Index of the selected row
+    ///
+    /// # Returns
+    ///
+    /// A new PullRequestsView instance.
+    pub fn new(project_name: &'a str, status: &'a RemoteStatus, selected: usize) -> Self {
+        Self {
+            project_name,
+            status,
+            selected,
+        }
+    }
+
+    /// Renders the popup centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the popup is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered(area, PULL_REQUESTS_PERCENT);
+
+        let items: Vec<ListItem> = self
+            .status
+            .pull_requests
+            .iter()
+            .enumerate()
+            .map(|(index, pr)| {
+                let style = if index == self.selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let prefix = if index == self.selected { "> " } else { "  " };
+                let marker = if pr.branch == self.status.branch {
+                    "*"
+                } else {
+                    " "
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{}{}#{} {}", prefix, marker, pr.number, pr.title),
+                        style,
+                    ),
+                    Span::styled(
+                        format!("  @{} ({})", pr.author, pr.branch),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let pipeline = self
+            .status
+            .pipeline
+            .map(|p| format!("pipeline {} {}", p.badge(), p.label()))
+            .unwrap_or_else(|| "no pipeline".to_string());
+        let title = format!(
+            " {} - pull requests {} {}: {} ",
+            self.project_name,
+            glyph("·", "-"),
+            self.status.branch,
+            pipeline
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(title)
+            .title_bottom(" Enter: open in browser  Esc: close ")
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(List::new(items).block(block), popup_area);
+    }
+}
@@ -0,0 +1,96 @@
+//! Recent files quick-open component for the TUI.
+//!
+//! Displays the files recently opened in a project in a popup so one can be
+//! opened again without navigating the file tree.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+use std::path::{Path, PathBuf};
+
+use crate::tui::views::popup::centered;
+
+/// Percentage of the available width and height covered by the popup.
+const RECENT_FILES_PERCENT: u16 = 80;
+
+/// View component for the recent files popup.
+///
+/// Lists the files most recent first, relative to the project root.
+pub struct RecentFilesView<'a> {
+    project_path: &'a Path,
+    files: &'a [PathBuf],
+    selected: usize,
+}
+
+impl<'a> RecentFilesView<'a> {
+    /// Creates a new RecentFilesView.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project directory the files are shown relative to
+    /// * `files` - The recent files, most recent first
+    /// * `selected` - Index of the selected row
+    ///
+    /// # Returns
+    ///
+    /// A new RecentFilesView instance.
+    pub fn new(project_path: &'a Path, files: &'a [PathBuf], selected: usize) -> Self {
+        Self {
+            project_path,
+            files,
+            selected,
+        }
+    }
+
+    /// Renders the popup centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the popup is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered(area, RECENT_FILES_PERCENT);
+
+        let items: Vec<ListItem> = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(index, path)| {
+                let name = path
+                    .strip_prefix(self.project_path)
+                    .unwrap_or(path)
+                    .display();
+                let style = if index == self.selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let prefix = if index == self.selected { "> " } else { "  " };
+                ListItem::new(Line::from(Span::styled(
+                    format!("{}{}", prefix, name),
+                    style,
+                )))
+            })
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(" Recent files ")
+            .title_bottom(" Enter: open  Esc: close ")
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(List::new(items).block(block), popup_area);
+    }
+}
@@ -0,0 +1,129 @@
+//! Search results popup component for the TUI.
+//!
+//! Lists the lines of a project's files matching a content search, filled in
+//! as ripgrep finds them, so one can be opened in the editor at its line.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+use std::path::Path;
+
+use crate::tui::search::{ProjectSearch, MAX_MATCHES};
+use crate::tui::views::popup::centered;
+use crate::util::glyphs::glyph;
+
+/// Percentage of the available width and height covered by the popup.
+const SEARCH_RESULTS_PERCENT: u16 = 80;
+
+/// View component for the search results popup.
+///
+/// Each match shows its file relative to the project, its line number and
+/// the matching line. The list scrolls to keep the selection visible.
+pub struct SearchResultsView<'a> {
+    project_path: &'a Path,
+    search: &'a ProjectSearch,
+    selected: usize,
+}
+
+impl<'a> SearchResultsView<'a> {
+    /// Creates a new SearchResultsView.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project directory the files are shown relative to
+    /// * `search` - The search whose matches are listed
+    /// * `selected` - Index of the selected row
+    ///
+    /// # Returns
+    ///
+    /// A new SearchResultsView instance.
+    pub fn new(project_path: &'a Path, search: &'a ProjectSearch, selected: usize) -> Self {
+        Self {
+            project_path,
+            search,
+            selected,
+        }
+    }
+
+    /// Returns the popup title: the query and how many matches were found.
+    pub fn title(&self) -> String {
+        let count = self.search.matches().len();
+        let status = if self.search.is_running() {
+            format!("{} matches, searching{}", count, glyph("…", "..."))
+        } else if count >= MAX_MATCHES {
+            format!("first {} matches", count)
+        } else {
+            format!("{} matches", count)
+        };
+        format!(" Search: {} ({}) ", self.search.query(), status)
+    }
+
+    /// Renders the popup centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the popup is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered(area, SEARCH_RESULTS_PERCENT);
+
+        let mut items: Vec<ListItem> = self
+            .search
+            .matches()
+            .iter()
+            .enumerate()
+            .map(|(index, found)| {
+                let name = found
+                    .path
+                    .strip_prefix(self.project_path)
+                    .unwrap_or(&found.path)
+                    .display();
+                let style = if index == self.selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let prefix = if index == self.selected { "> " } else { "  " };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{}{}:{}", prefix, name, found.line), style),
+                    Span::styled(
+                        format!("  {}", found.text.trim()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            })
+            .collect();
+        if let Some(error) = self.search.error() {
+            items.push(ListItem::new(Line::styled(
+                format!("  {}", error),
+                Style::default().fg(Color::Red),
+            )));
+        } else if items.is_empty() && !self.search.is_running() {
+            items.push(ListItem::new(Line::styled(
+                "  No matches",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(self.title())
+            .title_bottom(" Enter: open  /: new search  Esc: close ")
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+
+        let mut list_state = ListState::default().with_selected(Some(self.selected));
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(List::new(items).block(block), popup_area, &mut list_state);
+    }
+}
@@ -0,0 +1,153 @@
+//! Snapshot tests of the views' layout.
+//!
+//! Each view is drawn into a `TestBackend` terminal and its text compared to
+//! a snapshot under `snapshots/`, so a change of layout shows up as a diff.
+//! Review changes with `cargo insta review`.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use insta::assert_snapshot;
+use ratatui::{backend::TestBackend, Frame, Terminal};
+use tempfile::TempDir;
+
+use crate::config::{CommandBarItem, Config};
+use crate::git::GitInfo;
+use crate::tui::views::{CommandBar, FileBrowserView, ProjectsView, WorkspacesView};
+
+/// Draws into a terminal of a size and returns its text, one line per row
+/// without trailing spaces.
+fn render(width: u16, height: u16, draw: impl FnOnce(&mut Frame)) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(draw).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..height)
+        .map(|y| {
+            let line: String = (0..width).map(|x| buffer[(x, y)].symbol()).collect();
+            line.trim_end().to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// A configuration with a short and a long workspace, projects with long
+/// names, actions with icons and command bar items.
+fn config(project_root: &Path) -> Config {
+    let config = serde_json::json!({
+        "global": {
+            "actions": {
+                "c": { "name": "Claude", "command": "claude", "icon": "C" },
+                "t": { "name": "Tests", "command": "cargo test", "icon": "T" }
+            },
+            "command_bar": [
+                { "key": "b", "name": "Build", "command": "cargo build", "icon": "B" },
+                { "key": "l", "name": "Lint", "command": "cargo clippy" },
+                { "key": "d", "name": "Deploy to staging", "command": "make deploy" }
+            ]
+        },
+        "workspace": {
+            "shop": {
+                "name": "Shop",
+                "description": "Services of the payments team",
+                "projects": [
+                    { "name": "api", "path": project_root, "description": "Public API" },
+                    { "name": "checkout-service-with-a-very-long-name", "path": "/tmp/checkout" },
+                    { "name": "web", "path": "/tmp/web" }
+                ]
+            },
+            "internal-tools-and-infrastructure": {
+                "name": "Internal tools and infrastructure of the platform team",
+                "projects": []
+            }
+        }
+    });
+    serde_json::from_value(config).unwrap()
+}
+
+/// Git information of the `shop` projects: dirty, behind upstream, and not
+/// a repository.
+fn shop_git_info() -> Vec<Option<GitInfo>> {
+    vec![
+        Some(GitInfo {
+            branch: Some("main".to_string()),
+            is_dirty: true,
+            ..Default::default()
+        }),
+        Some(GitInfo {
+            branch: Some("feature/refund-partial-payments".to_string()),
+            behind: 3,
+            has_upstream: true,
+            ..Default::default()
+        }),
+        None,
+    ]
+}
+
+/// Creates the files of the project browsed in the file browser.
+fn create_project(dir: &Path) {
+    fs::create_dir_all(dir.join("src/handlers")).unwrap();
+    fs::write(dir.join("Cargo.toml"), "").unwrap();
+    fs::write(dir.join("README.md"), "").unwrap();
+    fs::write(dir.join("src/main.rs"), "").unwrap();
+    fs::write(dir.join("src/handlers/payments_and_refunds.rs"), "").unwrap();
+}
+
+#[test]
+fn when_rendering_workspaces_should_match_snapshot() {
+    let config = config(Path::new("/tmp/api"));
+
+    for (selected, width) in [(0, 60), (1, 60), (1, 30)] {
+        let view = WorkspacesView::new(&config, selected);
+        let text = render(width, 6, |frame| view.render(frame, frame.area()));
+        assert_snapshot!(format!("workspaces_{}_at_{}", selected, width), text);
+    }
+}
+
+#[test]
+fn when_rendering_projects_should_match_snapshot() {
+    let config = config(Path::new("/tmp/api"));
+
+    for (selected, width) in [(0, 80), (1, 80), (2, 50), (1, 40)] {
+        let view = ProjectsView::with_git_info(&config, "shop", selected, shop_git_info());
+        let text = render(width, 5, |frame| view.render(frame, frame.area()));
+        assert_snapshot!(format!("projects_{}_at_{}", selected, width), text);
+    }
+}
+
+#[test]
+fn when_rendering_file_browser_should_match_snapshot() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path().join("api");
+    create_project(&project_root);
+    let config = config(&project_root);
+    let expanded: HashSet<_> = [project_root.join("src"), project_root.join("src/handlers")]
+        .into_iter()
+        .collect();
+
+    for (selected, width) in [(0, 60), (4, 60), (4, 24)] {
+        let view = FileBrowserView::with_expanded(&config, "shop", 0, selected, &expanded, false);
+        let text = render(width, 8, |frame| {
+            view.render(frame, frame.area());
+        });
+        assert_snapshot!(format!("file_browser_{}_at_{}", selected, width), text);
+    }
+}
+
+#[test]
+fn when_rendering_command_bar_should_match_snapshot() {
+    let config = config(Path::new("/tmp/api"));
+    let commands: &[CommandBarItem] = &config.global.command_bar;
+
+    for (selected, width) in [(0, 60), (2, 60), (2, 20)] {
+        let view = CommandBar::new(commands, selected);
+        let text = render(width, 1, |frame| view.render(frame, frame.area()));
+        assert_snapshot!(format!("command_bar_{}_at_{}", selected, width), text);
+    }
+    let text = render(40, 1, |frame| {
+        CommandBar::new(&[], 0).render(frame, frame.area())
+    });
+    assert_snapshot!("command_bar_empty", text);
+}
@@ -0,0 +1,411 @@
+//! Status bar component for the TUI.
+//!
+//! Shown at the bottom of every view. Its top border carries the input mode,
+//! the pending vim count, the position of the selection and the last
+//! notification, and on the right, with `ui.stats`, the panel's runtime
+//! stats; below it, the view's key hints are packed into the lines available,
+//! dropping the last ones when the panel is too narrow.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::time::Duration;
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::tui::app::ToastLevel;
+use crate::tui::views::columns;
+use crate::util::glyphs::glyph;
+use crate::util::memory::format_bytes;
+use crate::util::time::format_duration;
+
+/// Height of the status bar: its border and two lines of hints.
+pub const STATUS_BAR_HEIGHT: u16 = 3;
+
+/// Panels narrower than this show the mode by its initial.
+const NARROW_WIDTH: usize = 40;
+
+/// Columns between two hints.
+const HINT_GAP: &str = "  ";
+
+/// What keys are being typed into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Keys navigate the view and run actions.
+    Normal,
+    /// Keys move through the command bar.
+    Command,
+    /// Keys are typed into a search.
+    Search,
+}
+
+impl Mode {
+    /// Returns the label of the mode, or its initial in narrow panels.
+    fn label(self, narrow: bool) -> &'static str {
+        match (self, narrow) {
+            (Mode::Normal, false) => "NORMAL",
+            (Mode::Command, false) => "COMMAND",
+            (Mode::Search, false) => "SEARCH",
+            (Mode::Normal, true) => "N",
+            (Mode::Command, true) => "C",
+            (Mode::Search, true) => "S",
+        }
+    }
+
+    /// Returns the background color of the mode's badge.
+    fn color(self) -> Color {
+        match self {
+            Mode::Normal => Color::Blue,
+            Mode::Command => Color::Magenta,
+            Mode::Search => Color::Green,
+        }
+    }
+}
+
+/// A key and what it does, e.g. "Enter" and "browse".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    /// The key, e.g. "Enter" or "x/R".
+    pub key: String,
+    /// What the key does.
+    pub label: String,
+}
+
+impl Hint {
+    /// Creates a hint.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key, e.g. "Enter"
+    /// * `label` - What the key does, e.g. "browse"
+    pub fn new(key: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            label: label.into(),
+        }
+    }
+
+    /// Returns the hint as shown, e.g. "Enter: browse".
+    fn text(&self) -> String {
+        format!("{}: {}", self.key, self.label)
+    }
+}
+
+/// Runtime stats of the panel, to tell why it grows sluggish.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PanelStats {
+    /// Panes registered in the session.
+    pub panes: usize,
+    /// Workspaces of the configuration.
+    pub workspaces: usize,
+    /// Projects of all workspaces.
+    pub projects: usize,
+    /// Projects with cached git information.
+    pub git_entries: usize,
+    /// How long ago the oldest cached git information was computed.
+    pub git_oldest: Option<Duration>,
+    /// Resident memory of the panel process, in bytes.
+    pub memory: Option<u64>,
+}
+
+impl PanelStats {
+    /// Returns the stats as shown, e.g. "3 panes · 2 ws/9 proj · git 9 ≤45s · 18 MB".
+    pub fn text(&self) -> String {
+        let mut parts = vec![
+            format!("{} panes", self.panes),
+            format!("{} ws/{} proj", self.workspaces, self.projects),
+        ];
+        let git = match self.git_oldest {
+            Some(age) => format!(
+                "git {} {}{}",
+                self.git_entries,
+                glyph("≤", "<="),
+                format_duration(age)
+            ),
+            None => format!("git {}", self.git_entries),
+        };
+        parts.push(git);
+        if let Some(memory) = self.memory {
+            parts.push(format_bytes(memory));
+        }
+        parts.join(glyph(" · ", " | "))
+    }
+}
+
+/// Packs hints into lines, in order, dropping the ones that don't fit.
+///
+/// # Arguments
+///
+/// * `hints` - The hints, most useful first
+/// * `width` - The columns of a line
+/// * `lines` - The number of lines available
+///
+/// # Returns
+///
+/// The lines; the last one ends with an ellipsis when hints were dropped.
+pub fn pack_hints(hints: &[Hint], width: usize, lines: usize) -> Vec<String> {
+    let mut packed: Vec<String> = Vec::new();
+    let mut dropped = false;
+    for hint in hints {
+        let text = hint.text();
+        let fits = |line: &String| {
+            columns::display_width(line) + HINT_GAP.len() + columns::display_width(&text) <= width
+        };
+        let count = packed.len();
+        match packed.last_mut() {
+            Some(line) if fits(line) => {
+                line.push_str(HINT_GAP);
+                line.push_str(&text);
+            }
+            _ if count < lines && columns::display_width(&text) <= width => packed.push(text),
+            _ => {
+                dropped = true;
+                break;
+            }
+        }
+    }
+    if dropped {
+        let ellipsis = glyph("…", "...");
+        if let Some(line) = packed.last_mut() {
+            if columns::display_width(line) + 1 + columns::display_width(ellipsis) > width {
+                *line = columns::truncate(line, width);
+            } else {
+                line.push(' ');
+                line.push_str(ellipsis);
+            }
+        }
+    }
+    packed
+}
+
+/// View component for the status bar.
+pub struct StatusBar<'a> {
+    mode: Mode,
+    hints: Vec<Hint>,
+    pending: &'a str,
+    position: Option<(usize, usize)>,
+    notification: Option<(&'a str, ToastLevel)>,
+    stats: Option<String>,
+}
+
+impl<'a> StatusBar<'a> {
+    /// Creates a status bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - What keys are being typed into
+    /// * `hints` - The view's key hints, most useful first
+    pub fn new(mode: Mode, hints: Vec<Hint>) -> Self {
+        Self {
+            mode,
+            hints,
+            pending: "",
+            position: None,
+            notification: None,
+            stats: None,
+        }
+    }
+
+    /// Sets the vim count and prefix typed so far.
+    pub fn with_pending(mut self, pending: &'a str) -> Self {
+        self.pending = pending;
+        self
+    }
+
+    /// Sets the position of the selection, shown as e.g. "4/23"; nothing is
+    /// shown for empty lists.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - The index of the selection
+    /// * `total` - The number of items in the list
+    pub fn with_position(mut self, selected: usize, total: usize) -> Self {
+        self.position = (total > 0).then(|| (selected.min(total - 1) + 1, total));
+        self
+    }
+
+    /// Sets the last notification, shown until another replaces it.
+    pub fn with_notification(mut self, notification: Option<(&'a str, ToastLevel)>) -> Self {
+        self.notification = notification;
+        self
+    }
+
+    /// Sets the runtime stats, shown on the right of the border when they
+    /// fit.
+    pub fn with_stats(mut self, stats: Option<&PanelStats>) -> Self {
+        self.stats = stats.map(PanelStats::text);
+        self
+    }
+
+    /// Returns the stats shown on the right of the border, if they leave
+    /// room for the status.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The columns of the border
+    fn stats(&self, width: usize) -> Option<String> {
+        let stats = format!(" {} ", self.stats.as_deref()?);
+        let status = self.status(width).width();
+        (status + columns::display_width(&stats) < width).then_some(stats)
+    }
+
+    /// Returns the status shown on the border: mode, pending keys,
+    /// position and last notification, cut to a width.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The columns of the border
+    pub fn status(&self, width: usize) -> Line<'a> {
+        let narrow = width < NARROW_WIDTH;
+        let mut spans = vec![Span::styled(
+            format!(" {} ", self.mode.label(narrow)),
+            Style::default()
+                .fg(Color::Black)
+                .bg(self.mode.color())
+                .add_modifier(Modifier::BOLD),
+        )];
+        if !self.pending.is_empty() {
+            spans.push(Span::styled(
+                format!(" {}", self.pending),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        if let Some((selected, total)) = self.position {
+            spans.push(Span::styled(
+                format!(" {}/{}", selected, total),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+
+        let used: usize = spans.iter().map(Span::width).sum();
+        let room = width.saturating_sub(used + 2);
+        if let Some((message, level)) = self.notification.filter(|_| room > 0) {
+            let color = match level {
+                ToastLevel::Info => Color::Green,
+                ToastLevel::Error => Color::Red,
+            };
+            spans.push(Span::styled(
+                format!("  {}", columns::truncate(message, room)),
+                Style::default().fg(color),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    /// Renders the status bar over the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The rectangular area to render within
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let width = area.width as usize;
+        let mut block = Block::default()
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(Color::DarkGray));
+        match self.stats(width) {
+            Some(stats) => {
+                // The notification gives way to the stats
+                let room = width - columns::display_width(&stats);
+                block = block
+                    .title(self.status(room))
+                    .title(Line::from(stats).right_aligned());
+            }
+            None => block = block.title(self.status(width)),
+        }
+        let lines = area.height.saturating_sub(1) as usize;
+        let hints: Vec<Line> = pack_hints(&self.hints, width, lines)
+            .into_iter()
+            .map(Line::from)
+            .collect();
+        let paragraph = Paragraph::new(hints)
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+
+        frame.render_widget(paragraph, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hints() -> Vec<Hint> {
+        vec![
+            Hint::new("Enter", "browse"),
+            Hint::new("x/R", "kill/restart"),
+            Hint::new("Esc", "back"),
+        ]
+    }
+
+    fn text(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn when_hints_fit_should_pack_them_on_one_line() {
+        assert_eq!(
+            pack_hints(&hints(), 80, 2),
+            vec!["Enter: browse  x/R: kill/restart  Esc: back"]
+        );
+    }
+
+    #[test]
+    fn when_panel_is_narrow_should_wrap_then_drop_the_last_hints() {
+        assert_eq!(
+            pack_hints(&hints(), 32, 2),
+            vec!["Enter: browse  x/R: kill/restart", "Esc: back"]
+        );
+        assert_eq!(
+            pack_hints(&hints(), 20, 2),
+            vec!["Enter: browse", "x/R: kill/restart …"]
+        );
+    }
+
+    #[test]
+    fn when_rendering_status_should_show_mode_keys_position_and_notification() {
+        let bar = StatusBar::new(Mode::Normal, hints())
+            .with_pending("5")
+            .with_position(3, 23)
+            .with_notification(Some(("Cloned api", ToastLevel::Info)));
+
+        assert_eq!(text(&bar.status(80)), " NORMAL  5 4/23  Cloned api");
+        assert_eq!(text(&bar.status(20)), " N  5 4/23  Cloned…");
+    }
+
+    #[test]
+    fn when_stats_are_set_should_describe_panes_projects_git_and_memory() {
+        let stats = PanelStats {
+            panes: 3,
+            workspaces: 2,
+            projects: 9,
+            git_entries: 7,
+            git_oldest: Some(Duration::from_secs(45)),
+            memory: Some(18 * 1024 * 1024),
+        };
+
+        assert_eq!(stats.text(), "3 panes · 2 ws/9 proj · git 7 ≤45s · 18 MB");
+
+        let bar = StatusBar::new(Mode::Normal, hints()).with_stats(Some(&stats));
+        assert_eq!(
+            bar.stats(80).as_deref(),
+            Some(" 3 panes · 2 ws/9 proj · git 7 ≤45s · 18 MB ")
+        );
+        assert_eq!(bar.stats(40), None);
+    }
+
+    #[test]
+    fn when_list_is_empty_should_not_show_position() {
+        let bar = StatusBar::new(Mode::Search, Vec::new()).with_position(0, 0);
+
+        assert_eq!(text(&bar.status(80)), " SEARCH ");
+    }
+}
@@ -0,0 +1,73 @@
+//! Toast notification component for the TUI.
+//!
+//! Displays a short-lived, single-line message over the bottom of the current
+//! view, colored by severity.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use crate::tui::app::{Toast, ToastLevel};
+
+/// View component for displaying a toast notification.
+pub struct ToastView<'a> {
+    toast: &'a Toast,
+}
+
+impl<'a> ToastView<'a> {
+    /// Creates a new ToastView for the given toast.
+    ///
+    /// # Arguments
+    ///
+    /// * `toast` - The toast notification to display
+    ///
+    /// # Returns
+    ///
+    /// A new ToastView instance.
+    pub fn new(toast: &'a Toast) -> Self {
+        Self { toast }
+    }
+
+    /// Returns the style used for the toast based on its level.
+    fn style(&self) -> Style {
+        let color = match self.toast.level {
+            ToastLevel::Info => Color::Green,
+            ToastLevel::Error => Color::Red,
+        };
+        Style::default()
+            .fg(Color::Black)
+            .bg(color)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Renders the toast over the last line of the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area whose bottom line the toast covers
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        if area.height == 0 {
+            return;
+        }
+
+        let toast_area = Rect {
+            x: area.x,
+            y: area.y + area.height - 1,
+            width: area.width,
+            height: 1,
+        };
+
+        let paragraph = Paragraph::new(format!(" {} ", self.toast.message)).style(self.style());
+
+        frame.render_widget(Clear, toast_area);
+        frame.render_widget(paragraph, toast_area);
+    }
+}
@@ -0,0 +1,116 @@
+//! Workspace switcher component for the TUI.
+//!
+//! Displays the numbered list of workspaces in a popup so the user can jump
+//! to another workspace without going back to the Workspaces view.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+#![allow(dead_code)]
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+
+use crate::config::Config;
+use crate::tui::views::popup::centered;
+use crate::tui::views::WorkspacesView;
+
+/// Percentage of the available width and height covered by the switcher.
+const SWITCHER_PERCENT: u16 = 60;
+
+/// View component for the workspace switcher popup.
+///
+/// Lists workspaces in the same order as the Workspaces view, prefixed with
+/// their number shortcut, and marks the workspace currently open.
+pub struct WorkspaceSwitcherView<'a> {
+    config: &'a Config,
+    current: Option<&'a str>,
+    selected: usize,
+}
+
+impl<'a> WorkspaceSwitcherView<'a> {
+    /// Creates a new WorkspaceSwitcherView.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Reference to the application configuration
+    /// * `current` - The identifier of the workspace currently open, if any
+    /// * `selected` - Index of the selected row
+    ///
+    /// # Returns
+    ///
+    /// A new WorkspaceSwitcherView instance.
+    pub fn new(config: &'a Config, current: Option<&'a str>, selected: usize) -> Self {
+        Self {
+            config,
+            current,
+            selected,
+        }
+    }
+
+    /// Builds the label of a row: number shortcut, name, and current marker.
+    fn label(&self, index: usize, workspace_id: &str) -> String {
+        let number = if index < 9 {
+            format!("{}", index + 1)
+        } else {
+            " ".to_string()
+        };
+        let name = self
+            .config
+            .workspace
+            .get(workspace_id)
+            .map(|w| w.name.as_str())
+            .unwrap_or(workspace_id);
+        let marker = if self.current == Some(workspace_id) {
+            " (current)"
+        } else {
+            ""
+        };
+        format!("{}  {}{}", number, name, marker)
+    }
+
+    /// Renders the switcher centered within the given area.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The terminal frame to render to
+    /// * `area` - The area the switcher is centered in
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let switcher_area = centered(area, SWITCHER_PERCENT);
+
+        let workspaces = WorkspacesView::new(self.config, 0);
+        let items: Vec<ListItem> = workspaces
+            .workspace_ids()
+            .into_iter()
+            .enumerate()
+            .map(|(index, workspace_id)| {
+                let style = if index == self.selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let prefix = if index == self.selected { "> " } else { "  " };
+                ListItem::new(Line::from(Span::styled(
+                    format!("{}{}", prefix, self.label(index, workspace_id)),
+                    style,
+                )))
+            })
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(" Switch workspace ")
+            .title_bottom(" Enter/1-9: switch  Esc: close ")
+            .title_style(Style::default().add_modifier(Modifier::BOLD));
+
+        frame.render_widget(Clear, switcher_area);
+        frame.render_widget(List::new(items).block(block), switcher_area);
+    }
+}
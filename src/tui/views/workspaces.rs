@@ -16,6 +16,7 @@ use ratatui::{
 };
 
 use crate::config::Config;
+use crate::tui::views::status_bar::Hint;
 
 /// View component for displaying and selecting workspaces.
 ///
@@ -42,18 +43,20 @@ impl<'a> WorkspacesView<'a> {
         Self { config, selected }
     }
 
-    /// Returns the sorted list of workspace identifiers.
+    /// Returns the workspace identifiers in display order.
     ///
-    /// Workspace IDs are sorted alphabetically to ensure consistent ordering
-    /// across renders and sessions.
+    /// Workspaces are ordered by their `order`, then as defined, see
+    /// `Config::workspace_ids`.
     ///
     /// # Returns
     ///
-    /// A vector of workspace ID references sorted alphabetically.
+    /// A vector of workspace ID references in display order.
     pub fn workspace_ids(&self) -> Vec<&str> {
-        let mut ids: Vec<&str> = self.config.workspace.keys().map(|s| s.as_str()).collect();
-        ids.sort();
-        ids
+        self.config
+            .workspace_ids()
+            .into_iter()
+            .map(|s| s.as_str())
+            .collect()
     }
 
     /// Returns the number of workspaces in the configuration.
@@ -76,41 +79,15 @@ impl<'a> WorkspacesView<'a> {
 
     /// Renders the workspaces view to the terminal frame.
     ///
-    /// The layout consists of three areas:
-    /// - Title area (3 lines): displays "Workspaces" header with cyan styling
-    /// - List area (flexible): displays workspace names with selection highlighting
-    /// - Help area (3 lines): displays keyboard navigation hints
+    /// Displays workspace names with selection highlighting; key hints are
+    /// shown by the status bar, see `hints`.
     ///
     /// # Arguments
     ///
     /// * `frame` - The terminal frame to render to
     /// * `area` - The rectangular area to render within
     pub fn render(&self, frame: &mut Frame, area: Rect) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),
-                Constraint::Min(1),
-                Constraint::Length(3),
-            ])
-            .split(area);
-
-        self.render_title(frame, chunks[0]);
-        self.render_list(frame, chunks[1]);
-        self.render_help(frame, chunks[2]);
-    }
-
-    /// Renders the title area with "Workspaces" header.
-    fn render_title(&self, frame: &mut Frame, area: Rect) {
-        let title = Paragraph::new("Workspaces")
-            .style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .block(Block::default().borders(Borders::BOTTOM));
-
-        frame.render_widget(title, area);
+        self.render_list(frame, area);
     }
 
     /// Renders the list of workspaces with selection highlighting.
@@ -125,6 +102,7 @@ impl<'a> WorkspacesView<'a> {
                 let display_name = workspace.map(|w| w.name.as_str()).unwrap_or(*id);
 
                 if index == self.selected {
+                    let description = workspace.and_then(|w| w.description.as_deref());
                     let line = Line::from(vec![
                         Span::styled(
                             "> ",
@@ -139,7 +117,7 @@ impl<'a> WorkspacesView<'a> {
                                 .add_modifier(Modifier::BOLD),
                         ),
                     ]);
-                    ListItem::new(line)
+                    ListItem::new(with_description(line, description))
                 } else {
                     ListItem::new(Line::from(format!("  {}", display_name)))
                 }
@@ -150,51 +128,92 @@ impl<'a> WorkspacesView<'a> {
         frame.render_widget(list, area);
     }
 
-    /// Renders the help area with keyboard navigation hints.
-    fn render_help(&self, frame: &mut Frame, area: Rect) {
-        let help_text = Paragraph::new("Enter: select  q: quit")
-            .style(Style::default().fg(Color::DarkGray))
-            .block(Block::default().borders(Borders::TOP));
+    /// Returns the key hints of the view, for the status bar.
+    pub fn hints(&self) -> Vec<Hint> {
+        vec![Hint::new("Enter", "select"), Hint::new("q", "quit")]
+    }
+}
 
-        frame.render_widget(help_text, area);
+/// Returns the lines of a selected list item: its line, followed by its
+/// description dimmed on a second line when it has one.
+pub(crate) fn with_description<'a>(line: Line<'a>, description: Option<&'a str>) -> Vec<Line<'a>> {
+    let mut lines = vec![line];
+    if let Some(description) = description.filter(|d| !d.trim().is_empty()) {
+        lines.push(Line::styled(
+            format!("    {}", description.trim()),
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+        ));
     }
+    lines
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::{GlobalConfig, Workspace};
+    use indexmap::IndexMap;
     use std::collections::HashMap;
 
     fn create_test_config() -> Config {
-        let mut workspaces = HashMap::new();
+        let mut workspaces = IndexMap::new();
         workspaces.insert(
             "fanki".to_string(),
             Workspace {
                 name: "Fanki".to_string(),
+                order: None,
+                description: None,
                 actions: HashMap::new(),
                 command_bar: vec![],
                 projects: vec![],
+                working_set: Vec::new(),
+                discover: None,
+                default_action: None,
+                mcp: Default::default(),
             },
         );
         workspaces.insert(
             "helios".to_string(),
             Workspace {
                 name: "Helios".to_string(),
+                order: None,
+                description: None,
                 actions: HashMap::new(),
                 command_bar: vec![],
                 projects: vec![],
+                working_set: Vec::new(),
+                discover: None,
+                default_action: None,
+                mcp: Default::default(),
             },
         );
 
         Config {
             global: GlobalConfig {
                 editor: "$EDITOR".to_string(),
+                shell: "$SHELL".to_string(),
                 git_info_level: Default::default(),
+                file_browser: Default::default(),
+                key_conflicts: Default::default(),
+                git_ui: Default::default(),
+                git: Default::default(),
+                remote: None,
+                zellij: Default::default(),
+                navigation: Default::default(),
+                claude_idle: Default::default(),
+                task: Default::default(),
                 actions: HashMap::new(),
                 command_bar: vec![],
+                project_enter: Default::default(),
+                mcp: Default::default(),
             },
             web_client: Default::default(),
+            top_bar: Default::default(),
+            layout: Default::default(),
+            notifications: Default::default(),
+            ui: Default::default(),
+            state_endpoint: Default::default(),
             workspace: workspaces,
         }
     }
@@ -203,12 +222,29 @@ mod tests {
         Config {
             global: GlobalConfig {
                 editor: "$EDITOR".to_string(),
+                shell: "$SHELL".to_string(),
                 git_info_level: Default::default(),
+                file_browser: Default::default(),
+                key_conflicts: Default::default(),
+                git_ui: Default::default(),
+                git: Default::default(),
+                remote: None,
+                zellij: Default::default(),
+                navigation: Default::default(),
+                claude_idle: Default::default(),
+                task: Default::default(),
                 actions: HashMap::new(),
                 command_bar: vec![],
+                project_enter: Default::default(),
+                mcp: Default::default(),
             },
             web_client: Default::default(),
-            workspace: HashMap::new(),
+            top_bar: Default::default(),
+            layout: Default::default(),
+            notifications: Default::default(),
+            ui: Default::default(),
+            state_endpoint: Default::default(),
+            workspace: IndexMap::new(),
         }
     }
 
@@ -222,6 +258,15 @@ mod tests {
         assert_eq!(ids, vec!["fanki", "helios"]);
     }
 
+    #[test]
+    fn when_workspace_has_order_should_list_it_first() {
+        let mut config = create_test_config();
+        config.workspace["helios"].order = Some(1);
+        let view = WorkspacesView::new(&config, 0);
+
+        assert_eq!(view.workspace_ids(), vec!["helios", "fanki"]);
+    }
+
     #[test]
     fn when_getting_len_should_return_workspace_count() {
         let config = create_test_config();
@@ -241,4 +286,22 @@ mod tests {
         assert_eq!(view.len(), 0);
         assert!(view.workspace_ids().is_empty());
     }
+
+    #[test]
+    fn when_item_has_description_should_add_dimmed_second_line() {
+        let lines = with_description(
+            Line::from("> helios-gateway"),
+            Some("  Public API gateway "),
+        );
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].to_string(), "    Public API gateway");
+        assert_eq!(lines[1].style.fg, Some(Color::DarkGray));
+    }
+
+    #[test]
+    fn when_item_has_blank_or_no_description_should_keep_one_line() {
+        assert_eq!(with_description(Line::from("a"), None).len(), 1);
+        assert_eq!(with_description(Line::from("a"), Some("  ")).len(), 1);
+    }
 }
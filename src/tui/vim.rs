@@ -0,0 +1,280 @@
+//! Vim-style count prefixes and `gg`/`G` navigation.
+//!
+//! With `"navigation": "vim"`, digits build a count applied to the next
+//! motion (`5j`, `3k`), `gg` jumps to the first item and `G` to the last
+//! one; a count before `gg` or `G` jumps to that item (`12G`). The keys
+//! arrive as the regular input events (digits as `SwitchWorkspace`, `g` as
+//! an action, `G` as `GitUi`) and are reinterpreted here, so the git UI
+//! moves to `gs` in this mode.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use crate::tui::terminal::InputEvent;
+
+/// Largest count kept; further digits are ignored.
+const MAX_COUNT: usize = 99_999;
+
+/// A cursor movement in a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// Move up by the given number of items.
+    Up(usize),
+    /// Move down by the given number of items.
+    Down(usize),
+    /// Jump to the first item.
+    First,
+    /// Jump to the last item.
+    Last,
+    /// Jump to the given one-based item.
+    Line(usize),
+}
+
+impl Motion {
+    /// Applies the motion to a selection.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - The selected index
+    /// * `len` - The number of items in the list
+    ///
+    /// # Returns
+    ///
+    /// The new selected index, clamped to the list.
+    pub fn apply(self, selected: usize, len: usize) -> usize {
+        let last = len.saturating_sub(1);
+        match self {
+            Motion::Up(count) => selected.saturating_sub(count),
+            Motion::Down(count) => selected.saturating_add(count).min(last),
+            Motion::First => 0,
+            Motion::Last => last,
+            Motion::Line(line) => line.saturating_sub(1).min(last),
+        }
+    }
+}
+
+/// What a key resolves to once vim-style prefixes are taken into account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VimOutcome {
+    /// The key starts or extends a prefix; wait for the next one.
+    Pending,
+    /// The key moves the selection.
+    Move(Motion),
+    /// The key is handled as usual.
+    Event(InputEvent),
+}
+
+/// Keys typed so far towards a motion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VimKeys {
+    count: Option<usize>,
+    g: bool,
+}
+
+impl VimKeys {
+    /// Creates an empty prefix.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the prefix typed so far (e.g. "12g"), empty if there is none.
+    pub fn pending(&self) -> String {
+        let count = self.count.map(|c| c.to_string()).unwrap_or_default();
+        format!("{}{}", count, if self.g { "g" } else { "" })
+    }
+
+    /// Discards the prefix typed so far.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Feeds a key to the prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The input event of the key
+    ///
+    /// # Returns
+    ///
+    /// Whether the key is part of a prefix, completes a motion, or must be
+    /// handled as usual. Esc discards a pending prefix.
+    pub fn feed(&mut self, event: InputEvent) -> VimOutcome {
+        if self.g {
+            let count = self.count;
+            self.reset();
+            return match event {
+                InputEvent::Action('g') => {
+                    VimOutcome::Move(count.map_or(Motion::First, Motion::Line))
+                }
+                InputEvent::OpenShell => VimOutcome::Event(InputEvent::GitUi),
+                // Unknown `g` sequences are dropped, like in vim
+                _ => VimOutcome::Pending,
+            };
+        }
+
+        match event {
+            InputEvent::SwitchWorkspace(index) => {
+                self.push_digit(index + 1);
+                VimOutcome::Pending
+            }
+            InputEvent::Action('0') if self.count.is_some() => {
+                self.push_digit(0);
+                VimOutcome::Pending
+            }
+            InputEvent::Action('g') => {
+                self.g = true;
+                VimOutcome::Pending
+            }
+            InputEvent::GitUi => {
+                let count = self.count.take();
+                VimOutcome::Move(count.map_or(Motion::Last, Motion::Line))
+            }
+            InputEvent::Up | InputEvent::Down if self.count.is_some() => {
+                let count = self.count.take().unwrap_or(1);
+                VimOutcome::Move(if event == InputEvent::Up {
+                    Motion::Up(count)
+                } else {
+                    Motion::Down(count)
+                })
+            }
+            InputEvent::Resize => VimOutcome::Event(event),
+            InputEvent::Back if self.count.is_some() => {
+                self.reset();
+                VimOutcome::Pending
+            }
+            event => {
+                self.reset();
+                VimOutcome::Event(event)
+            }
+        }
+    }
+
+    fn push_digit(&mut self, digit: usize) {
+        let count = self.count.unwrap_or(0).saturating_mul(10) + digit;
+        self.count = Some(count.min(MAX_COUNT));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_all(keys: &mut VimKeys, events: &[InputEvent]) -> Vec<VimOutcome> {
+        events.iter().map(|e| keys.feed(*e)).collect()
+    }
+
+    #[test]
+    fn when_typing_count_before_j_should_move_down_by_count() {
+        let mut keys = VimKeys::new();
+
+        let outcomes = feed_all(
+            &mut keys,
+            &[InputEvent::SwitchWorkspace(4), InputEvent::Down],
+        );
+
+        assert_eq!(
+            outcomes,
+            vec![VimOutcome::Pending, VimOutcome::Move(Motion::Down(5))]
+        );
+        assert_eq!(keys.pending(), "");
+    }
+
+    #[test]
+    fn when_typing_multi_digit_count_should_accumulate_with_zero() {
+        let mut keys = VimKeys::new();
+
+        feed_all(
+            &mut keys,
+            &[InputEvent::SwitchWorkspace(0), InputEvent::Action('0')],
+        );
+        assert_eq!(keys.pending(), "10");
+
+        assert_eq!(keys.feed(InputEvent::Up), VimOutcome::Move(Motion::Up(10)));
+    }
+
+    #[test]
+    fn when_typing_gg_should_jump_to_first_or_counted_line() {
+        let mut keys = VimKeys::new();
+
+        let outcomes = feed_all(
+            &mut keys,
+            &[InputEvent::Action('g'), InputEvent::Action('g')],
+        );
+        assert_eq!(outcomes[1], VimOutcome::Move(Motion::First));
+
+        let outcomes = feed_all(
+            &mut keys,
+            &[
+                InputEvent::SwitchWorkspace(2),
+                InputEvent::Action('g'),
+                InputEvent::Action('g'),
+            ],
+        );
+        assert_eq!(outcomes[2], VimOutcome::Move(Motion::Line(3)));
+    }
+
+    #[test]
+    fn when_typing_capital_g_should_jump_to_last_or_counted_line() {
+        let mut keys = VimKeys::new();
+
+        assert_eq!(keys.feed(InputEvent::GitUi), VimOutcome::Move(Motion::Last));
+
+        keys.feed(InputEvent::SwitchWorkspace(6));
+        assert_eq!(
+            keys.feed(InputEvent::GitUi),
+            VimOutcome::Move(Motion::Line(7))
+        );
+    }
+
+    #[test]
+    fn when_typing_gs_should_open_git_ui() {
+        let mut keys = VimKeys::new();
+
+        keys.feed(InputEvent::Action('g'));
+
+        assert_eq!(
+            keys.feed(InputEvent::OpenShell),
+            VimOutcome::Event(InputEvent::GitUi)
+        );
+    }
+
+    #[test]
+    fn when_pressing_esc_with_count_should_discard_it() {
+        let mut keys = VimKeys::new();
+        keys.feed(InputEvent::SwitchWorkspace(2));
+
+        assert_eq!(keys.feed(InputEvent::Back), VimOutcome::Pending);
+        assert_eq!(keys.pending(), "");
+        assert_eq!(
+            keys.feed(InputEvent::Back),
+            VimOutcome::Event(InputEvent::Back)
+        );
+    }
+
+    #[test]
+    fn when_typing_other_key_should_pass_it_through() {
+        let mut keys = VimKeys::new();
+        keys.feed(InputEvent::SwitchWorkspace(2));
+
+        assert_eq!(
+            keys.feed(InputEvent::Enter),
+            VimOutcome::Event(InputEvent::Enter)
+        );
+        assert_eq!(
+            keys.feed(InputEvent::Action('0')),
+            VimOutcome::Event(InputEvent::Action('0'))
+        );
+        assert_eq!(
+            keys.feed(InputEvent::Down),
+            VimOutcome::Event(InputEvent::Down)
+        );
+    }
+
+    #[test]
+    fn when_applying_motion_should_clamp_to_list() {
+        assert_eq!(Motion::Down(5).apply(8, 10), 9);
+        assert_eq!(Motion::Up(5).apply(3, 10), 0);
+        assert_eq!(Motion::Line(4).apply(0, 10), 3);
+        assert_eq!(Motion::Line(40).apply(0, 10), 9);
+        assert_eq!(Motion::Last.apply(0, 0), 0);
+    }
+}
@@ -0,0 +1,201 @@
+//! Cross-platform clipboard support.
+//!
+//! Copies text using the first available platform tool (pbcopy, clip.exe,
+//! wl-copy, xclip, xsel) and falls back to the OSC 52 terminal escape
+//! sequence, which also works over SSH and inside Zellij.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::error::{GzClaudeError, Result};
+
+/// An external program able to receive clipboard contents on stdin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardBackend {
+    /// The program to execute.
+    pub program: &'static str,
+    /// Arguments passed to the program.
+    pub args: &'static [&'static str],
+}
+
+impl ClipboardBackend {
+    const fn new(program: &'static str, args: &'static [&'static str]) -> Self {
+        Self { program, args }
+    }
+}
+
+/// Returns the clipboard backends to try, in order, for a platform.
+///
+/// # Arguments
+///
+/// * `os` - The target operating system (as in `std::env::consts::OS`)
+/// * `wayland` - Whether a Wayland session is active
+///
+/// # Returns
+///
+/// The ordered list of candidate backends.
+pub fn backends_for(os: &str, wayland: bool) -> Vec<ClipboardBackend> {
+    match os {
+        "macos" => vec![ClipboardBackend::new("pbcopy", &[])],
+        "windows" => vec![ClipboardBackend::new("clip.exe", &[])],
+        _ => {
+            let mut backends = Vec::new();
+            if wayland {
+                backends.push(ClipboardBackend::new("wl-copy", &[]));
+            }
+            backends.push(ClipboardBackend::new("xclip", &["-selection", "clipboard"]));
+            backends.push(ClipboardBackend::new("xsel", &["--clipboard", "--input"]));
+            // WSL exposes the Windows clipboard through clip.exe
+            backends.push(ClipboardBackend::new("clip.exe", &[]));
+            backends
+        }
+    }
+}
+
+/// Copies text to the system clipboard.
+///
+/// Tries each platform backend in order and falls back to OSC 52 when none
+/// of them is available.
+///
+/// # Arguments
+///
+/// * `text` - The text to copy
+///
+/// # Returns
+///
+/// Ok(()) if the text was handed to a clipboard backend.
+///
+/// # Errors
+///
+/// Returns `GzClaudeError::Clipboard` if every backend failed, including OSC 52.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+
+    for backend in backends_for(std::env::consts::OS, wayland) {
+        if copy_with_backend(&backend, text).is_ok() {
+            return Ok(());
+        }
+    }
+
+    copy_with_osc52(text)
+}
+
+/// Copies text by piping it into a backend program.
+fn copy_with_backend(backend: &ClipboardBackend, text: &str) -> Result<()> {
+    let mut child = Command::new(backend.program)
+        .args(backend.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            GzClaudeError::Clipboard(format!("Failed to run {}: {}", backend.program, e))
+        })?;
+
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin.write_all(text.as_bytes()).map_err(|e| {
+            GzClaudeError::Clipboard(format!("Failed to write to {}: {}", backend.program, e))
+        })?;
+    }
+
+    let status = child.wait().map_err(|e| {
+        GzClaudeError::Clipboard(format!("Failed to wait for {}: {}", backend.program, e))
+    })?;
+
+    if !status.success() {
+        return Err(GzClaudeError::Clipboard(format!(
+            "{} exited with status: {}",
+            backend.program, status
+        )));
+    }
+
+    Ok(())
+}
+
+/// Builds the OSC 52 escape sequence that sets the clipboard to `text`.
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1B]52;c;{}\x07", base64_encode(text.as_bytes()))
+}
+
+/// Copies text by emitting an OSC 52 escape sequence to the terminal.
+fn copy_with_osc52(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout
+        .write_all(osc52_sequence(text).as_bytes())
+        .and_then(|_| stdout.flush())
+        .map_err(|e| GzClaudeError::Clipboard(format!("Failed to write OSC 52: {}", e)))
+}
+
+/// Encodes bytes as standard base64 with padding.
+fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+
+        encoded.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        encoded.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        encoded.push(if chunk.len() > 1 {
+            ALPHABET[(n >> 6) as usize & 63] as char
+        } else {
+            '='
+        });
+        encoded.push(if chunk.len() > 2 {
+            ALPHABET[n as usize & 63] as char
+        } else {
+            '='
+        });
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_on_macos_should_use_pbcopy() {
+        let backends = backends_for("macos", false);
+
+        assert_eq!(backends.len(), 1);
+        assert_eq!(backends[0].program, "pbcopy");
+    }
+
+    #[test]
+    fn when_on_linux_with_wayland_should_prefer_wl_copy() {
+        let backends = backends_for("linux", true);
+
+        assert_eq!(backends[0].program, "wl-copy");
+        assert!(backends.iter().any(|b| b.program == "xclip"));
+    }
+
+    #[test]
+    fn when_on_linux_without_wayland_should_skip_wl_copy() {
+        let backends = backends_for("linux", false);
+
+        assert!(backends.iter().all(|b| b.program != "wl-copy"));
+        assert_eq!(backends[0].program, "xclip");
+    }
+
+    #[test]
+    fn when_encoding_base64_should_pad_correctly() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn when_building_osc52_sequence_should_wrap_encoded_text() {
+        assert_eq!(osc52_sequence("hi"), "\x1B]52;c;aGk=\x07");
+    }
+}
@@ -0,0 +1,168 @@
+//! Line diffs of small text files.
+//!
+//! Used to show how a generated file differs from the one on disk. The diff
+//! is computed with a longest common subsequence table, which is fine for
+//! files of a few hundred lines such as Zellij layouts.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+/// Returns the differences between two texts in unified format.
+///
+/// Changed lines are prefixed with `-` (only in `old`) or `+` (only in
+/// `new`), surrounded by up to `context` unchanged lines prefixed with a
+/// space. Hunks start with a `@@ -a,b +c,d @@` line.
+///
+/// # Arguments
+///
+/// * `old` - The current text
+/// * `new` - The text replacing it
+/// * `context` - The unchanged lines shown around each change
+///
+/// # Returns
+///
+/// The diff, or None if the texts have the same lines.
+pub fn unified(old: &str, new: &str, context: usize) -> Option<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&old, &new);
+    if ops.iter().all(|op| matches!(op, Op::Same(..))) {
+        return None;
+    }
+
+    // Unchanged lines further than `context` from any change are left out
+    let changed: Vec<usize> = (0..ops.len())
+        .filter(|&i| !matches!(ops[i], Op::Same(..)))
+        .collect();
+    let shown = |i: usize| changed.iter().any(|&c| c.abs_diff(i) <= context);
+
+    let mut out = String::new();
+    let mut i = 0;
+    while i < ops.len() {
+        if !shown(i) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < ops.len() && shown(i) {
+            i += 1;
+        }
+        let hunk = &ops[start..i];
+        let (old_start, new_start) = positions(&ops[..start]);
+        let old_len = hunk.iter().filter(|op| !matches!(op, Op::Added(_))).count();
+        let new_len = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Removed(_)))
+            .count();
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            hunk_start(old_start, old_len),
+            old_len,
+            hunk_start(new_start, new_len),
+            new_len
+        ));
+        for op in hunk {
+            let line = match op {
+                Op::Same(line) => format!(" {}", line),
+                Op::Removed(line) => format!("-{}", line),
+                Op::Added(line) => format!("+{}", line),
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    Some(out)
+}
+
+/// An edit turning the old lines into the new ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op<'a> {
+    /// A line of both texts.
+    Same(&'a str),
+    /// A line only in the old text.
+    Removed(&'a str),
+    /// A line only in the new text.
+    Added(&'a str),
+}
+
+/// Returns how many old and new lines the edits cover.
+fn positions(ops: &[Op]) -> (usize, usize) {
+    let old = ops.iter().filter(|op| !matches!(op, Op::Added(_))).count();
+    let new = ops
+        .iter()
+        .filter(|op| !matches!(op, Op::Removed(_)))
+        .count();
+    (old, new)
+}
+
+/// Returns the line number a hunk starts at: the first line it covers, or
+/// the line before an empty range, as in `diff -u`.
+fn hunk_start(lines_before: usize, len: usize) -> usize {
+    if len == 0 {
+        lines_before
+    } else {
+        lines_before + 1
+    }
+}
+
+/// Computes the edits turning `old` into `new`, keeping the most lines.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Op<'a>> {
+    // common[i][j]: length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push(Op::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            ops.push(Op::Removed(old[i]));
+            i += 1;
+        } else {
+            ops.push(Op::Added(new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|line| Op::Removed(line)));
+    ops.extend(new[j..].iter().map(|line| Op::Added(line)));
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_texts_have_same_lines_should_return_none() {
+        assert_eq!(unified("a\nb\n", "a\nb", 3), None);
+    }
+
+    #[test]
+    fn when_line_changes_should_show_it_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n";
+        let new = "1\n2\n3\nfour\n5\n6\n7\n";
+
+        let diff = unified(old, new, 1).unwrap();
+
+        assert_eq!(diff, "@@ -3,3 +3,3 @@\n 3\n-4\n+four\n 5\n");
+    }
+
+    #[test]
+    fn when_changes_are_apart_should_split_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\n";
+        let new = "a\nb\nc\nd\ne\nf\ng\n";
+        let removed_first = unified(old, &new[2..], 0).unwrap();
+
+        assert_eq!(removed_first, "@@ -1,1 +0,0 @@\n-a\n@@ -6,0 +6,1 @@\n+g\n");
+    }
+}
@@ -0,0 +1,95 @@
+//! Icons and symbols with ASCII fallbacks.
+//!
+//! With `ui.ascii_only`, views draw ASCII instead of emoji and symbols that
+//! some terminal fonts lack: a missing glyph renders as a box and may take a
+//! different number of columns, breaking the alignment of lists.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ASCII_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables the ASCII fallbacks for everything drawn from now on.
+///
+/// # Arguments
+///
+/// * `ascii_only` - The `ui.ascii_only` setting
+pub fn configure(ascii_only: bool) {
+    ASCII_ONLY.store(ascii_only, Ordering::Relaxed);
+}
+
+/// Returns whether the ASCII fallbacks are enabled.
+pub fn ascii_only() -> bool {
+    ASCII_ONLY.load(Ordering::Relaxed)
+}
+
+/// Returns a symbol, or its ASCII fallback when `ui.ascii_only` is set.
+///
+/// # Arguments
+///
+/// * `unicode` - The symbol drawn by default
+/// * `ascii` - The fallback
+pub fn glyph(unicode: &'static str, ascii: &'static str) -> &'static str {
+    pick(ascii_only(), unicode, ascii)
+}
+
+/// Chooses between a symbol and its ASCII fallback.
+///
+/// # Arguments
+///
+/// * `ascii_only` - Whether the fallback is used
+/// * `unicode` - The symbol drawn by default
+/// * `ascii` - The fallback
+pub fn pick<'a>(ascii_only: bool, unicode: &'a str, ascii: &'a str) -> &'a str {
+    if ascii_only {
+        ascii
+    } else {
+        unicode
+    }
+}
+
+/// Returns a user-configured icon as it should be drawn.
+///
+/// # Arguments
+///
+/// * `icon` - The icon of an action or command bar item
+///
+/// # Returns
+///
+/// The icon, or None when `ui.ascii_only` is set and it isn't ASCII.
+pub fn icon(icon: &str) -> Option<&str> {
+    visible_icon(ascii_only(), icon)
+}
+
+/// Returns an icon unless the ASCII fallbacks hide it.
+///
+/// # Arguments
+///
+/// * `ascii_only` - Whether non-ASCII icons are hidden
+/// * `icon` - The configured icon
+pub fn visible_icon(ascii_only: bool, icon: &str) -> Option<&str> {
+    if ascii_only && !icon.is_ascii() {
+        None
+    } else {
+        Some(icon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_ascii_only_should_pick_the_fallback() {
+        assert_eq!(pick(false, "✔", "ok"), "✔");
+        assert_eq!(pick(true, "✔", "ok"), "ok");
+    }
+
+    #[test]
+    fn when_ascii_only_should_hide_non_ascii_icons() {
+        assert_eq!(visible_icon(true, "🤖"), None);
+        assert_eq!(visible_icon(true, "[c]"), Some("[c]"));
+        assert_eq!(visible_icon(false, "🤖"), Some("🤖"));
+    }
+}
@@ -0,0 +1,76 @@
+//! Memory used by the panel process.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::fs;
+use std::process::Command;
+
+/// Returns the resident memory of the panel process, in bytes.
+///
+/// Read from `/proc/self/status` on Linux, and from `ps` elsewhere.
+///
+/// # Returns
+///
+/// The resident set size, or None if it can't be read.
+pub fn resident_memory() -> Option<u64> {
+    if let Ok(status) = fs::read_to_string("/proc/self/status") {
+        return parse_vm_rss(&status);
+    }
+    let output = Command::new("ps")
+        .args(["-o", "rss=", "-p", &std::process::id().to_string()])
+        .output()
+        .ok()?;
+    let kilobytes: u64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Parses the resident set size out of a `/proc/<pid>/status` file.
+///
+/// # Returns
+///
+/// The `VmRSS` value in bytes, or None if the line is missing.
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line
+        .trim_start_matches("VmRSS:")
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Formats a size in bytes with the largest fitting unit, e.g. "18 MB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes;
+    let mut unit = 0;
+    while size >= 1024 && unit < UNITS.len() - 1 {
+        size /= 1024;
+        unit += 1;
+    }
+    format!("{} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_parsing_proc_status_should_read_resident_set_size() {
+        let status = "Name:\tgz-claude\nVmPeak:\t  40000 kB\nVmRSS:\t   18432 kB\nThreads:\t4\n";
+
+        assert_eq!(parse_vm_rss(status), Some(18432 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tgz-claude\n"), None);
+    }
+
+    #[test]
+    fn when_formatting_bytes_should_use_largest_unit() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(18 * 1024 * 1024 + 5), "18 MB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3 GB");
+    }
+}
@@ -0,0 +1,17 @@
+//! Shared utilities used across gz-claude modules.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+pub mod clipboard;
+pub mod diff;
+pub mod glyphs;
+pub mod memory;
+pub mod notify;
+pub mod opener;
+pub mod path;
+pub mod shell;
+pub mod time;
+
+pub use clipboard::copy_to_clipboard;
+pub use opener::{open_url, reveal_path};
+pub use time::format_age;
@@ -0,0 +1,103 @@
+//! Desktop notifications.
+//!
+//! Uses `osascript` on macOS and `notify-send` elsewhere; Windows has no
+//! command-line notifier, so nothing is shown there.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::process::{Command, Stdio};
+
+use super::opener::OpenCommand;
+
+/// Returns the command that shows a desktop notification.
+///
+/// # Arguments
+///
+/// * `os` - The target operating system (as in `std::env::consts::OS`)
+/// * `title` - The notification title
+/// * `body` - The notification text
+///
+/// # Returns
+///
+/// The command, or None if the platform has no notifier.
+pub fn notification_command(os: &str, title: &str, body: &str) -> Option<OpenCommand> {
+    match os {
+        "macos" => {
+            let script = format!(
+                "display notification \"{}\" with title \"{}\"",
+                applescript_escape(body),
+                applescript_escape(title)
+            );
+            Some(OpenCommand {
+                program: "osascript",
+                args: vec!["-e".into(), script.into()],
+            })
+        }
+        "windows" => None,
+        _ => Some(OpenCommand {
+            program: "notify-send",
+            args: vec![title.into(), body.into()],
+        }),
+    }
+}
+
+/// Shows a desktop notification without waiting for it.
+///
+/// Failures are ignored: a missing notifier must not disturb the TUI.
+///
+/// # Arguments
+///
+/// * `title` - The notification title
+/// * `body` - The notification text
+pub fn notify(title: &str, body: &str) {
+    let Some(command) = notification_command(std::env::consts::OS, title, body) else {
+        return;
+    };
+    let child = Command::new(command.program)
+        .args(&command.args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    // Reap the notifier in the background so it doesn't linger as a zombie
+    if let Ok(mut child) = child {
+        std::thread::spawn(move || child.wait());
+    }
+}
+
+/// Escapes text for an AppleScript string literal.
+fn applescript_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: &OpenCommand) -> Vec<&str> {
+        command.args.iter().map(|a| a.to_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn when_notifying_on_linux_should_use_notify_send() {
+        let command = notification_command("linux", "gz-claude", "api is waiting").unwrap();
+
+        assert_eq!(command.program, "notify-send");
+        assert_eq!(args(&command), vec!["gz-claude", "api is waiting"]);
+    }
+
+    #[test]
+    fn when_notifying_on_macos_should_escape_the_script() {
+        let command = notification_command("macos", "gz-claude", "say \"hi\"").unwrap();
+
+        assert_eq!(command.program, "osascript");
+        assert_eq!(
+            args(&command),
+            vec![
+                "-e",
+                "display notification \"say \\\"hi\\\"\" with title \"gz-claude\""
+            ]
+        );
+        assert!(notification_command("windows", "gz-claude", "hi").is_none());
+    }
+}
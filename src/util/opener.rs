@@ -0,0 +1,166 @@
+//! Opening URLs and revealing files with the platform's default applications.
+//!
+//! Uses `open` on macOS, `explorer.exe` on Windows and `xdg-open` elsewhere.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::error::{GzClaudeError, Result};
+
+/// A command that hands a target to the platform's default application.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenCommand {
+    /// The program to execute.
+    pub program: &'static str,
+    /// Arguments passed to the program.
+    pub args: Vec<OsString>,
+}
+
+impl OpenCommand {
+    fn new(program: &'static str, args: Vec<OsString>) -> Self {
+        Self { program, args }
+    }
+}
+
+/// Returns the command that opens a URL in the default browser.
+///
+/// # Arguments
+///
+/// * `os` - The target operating system (as in `std::env::consts::OS`)
+/// * `url` - The URL to open
+pub fn open_url_command(os: &str, url: &str) -> OpenCommand {
+    let program = match os {
+        "macos" => "open",
+        "windows" => "explorer.exe",
+        _ => "xdg-open",
+    };
+    OpenCommand::new(program, vec![url.into()])
+}
+
+/// Returns the command that shows a file or directory in the file manager.
+///
+/// Directories are opened; files are selected in their parent directory where
+/// the platform supports it (Finder, Explorer), otherwise the parent directory
+/// is opened.
+///
+/// # Arguments
+///
+/// * `os` - The target operating system (as in `std::env::consts::OS`)
+/// * `path` - The file or directory to reveal
+/// * `is_dir` - Whether `path` is a directory
+pub fn reveal_command(os: &str, path: &Path, is_dir: bool) -> OpenCommand {
+    match os {
+        "macos" if is_dir => OpenCommand::new("open", vec![path.into()]),
+        "macos" => OpenCommand::new("open", vec!["-R".into(), path.into()]),
+        "windows" if is_dir => OpenCommand::new("explorer.exe", vec![path.into()]),
+        "windows" => {
+            let mut select = OsString::from("/select,");
+            select.push(path);
+            OpenCommand::new("explorer.exe", vec![select])
+        }
+        _ => {
+            let dir = if is_dir {
+                path
+            } else {
+                path.parent().unwrap_or(path)
+            };
+            OpenCommand::new("xdg-open", vec![dir.into()])
+        }
+    }
+}
+
+/// Opens a URL in the default web browser.
+///
+/// # Arguments
+///
+/// * `url` - The URL to open
+///
+/// # Errors
+///
+/// Returns an error if the opener cannot be run or exits with a failure.
+pub fn open_url(url: &str) -> Result<()> {
+    run(&open_url_command(std::env::consts::OS, url))
+}
+
+/// Shows a file or directory in the system file manager.
+///
+/// # Arguments
+///
+/// * `path` - The file or directory to reveal
+///
+/// # Errors
+///
+/// Returns an error if the opener cannot be run or exits with a failure.
+pub fn reveal_path(path: &Path) -> Result<()> {
+    run(&reveal_command(std::env::consts::OS, path, path.is_dir()))
+}
+
+/// Runs an opener with its output discarded so it does not draw over the TUI.
+fn run(command: &OpenCommand) -> Result<()> {
+    let status = Command::new(command.program)
+        .args(&command.args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| GzClaudeError::Opener(format!("Failed to run {}: {}", command.program, e)))?;
+
+    // explorer.exe exits with 1 even when it succeeds
+    if status.success() || command.program == "explorer.exe" {
+        Ok(())
+    } else {
+        Err(GzClaudeError::Opener(format!(
+            "{} exited with {}",
+            command.program, status
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: &OpenCommand) -> Vec<&str> {
+        command.args.iter().map(|a| a.to_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn when_opening_url_should_use_platform_opener() {
+        let url = "https://github.com/o/r";
+
+        assert_eq!(open_url_command("macos", url).program, "open");
+        assert_eq!(open_url_command("windows", url).program, "explorer.exe");
+        let linux = open_url_command("linux", url);
+        assert_eq!(linux.program, "xdg-open");
+        assert_eq!(args(&linux), vec![url]);
+    }
+
+    #[test]
+    fn when_revealing_file_on_macos_should_select_it_in_finder() {
+        let command = reveal_command("macos", Path::new("/p/src/main.rs"), false);
+
+        assert_eq!(command.program, "open");
+        assert_eq!(args(&command), vec!["-R", "/p/src/main.rs"]);
+    }
+
+    #[test]
+    fn when_revealing_file_on_windows_should_select_it_in_explorer() {
+        let command = reveal_command("windows", Path::new("/p/main.rs"), false);
+
+        assert_eq!(command.program, "explorer.exe");
+        assert_eq!(args(&command), vec!["/select,/p/main.rs"]);
+    }
+
+    #[test]
+    fn when_revealing_on_linux_should_open_directory_or_parent() {
+        let file = reveal_command("linux", Path::new("/p/src/main.rs"), false);
+        let dir = reveal_command("linux", Path::new("/p/src"), true);
+
+        assert_eq!(file.program, "xdg-open");
+        assert_eq!(args(&file), vec!["/p/src"]);
+        assert_eq!(args(&dir), vec!["/p/src"]);
+    }
+}
@@ -0,0 +1,131 @@
+//! Path input helpers for prompts.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::fs;
+use std::path::{is_separator, PathBuf};
+
+/// Expands a leading `~` to the home directory.
+///
+/// # Arguments
+///
+/// * `input` - The path as typed by the user
+///
+/// # Returns
+///
+/// The path with `~` or `~/` (`~\` on Windows) replaced by the home
+/// directory, unchanged otherwise.
+pub fn expand_tilde(input: &str) -> PathBuf {
+    let rest = match input.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(is_separator) => rest,
+        _ => return PathBuf::from(input),
+    };
+    match dirs::home_dir() {
+        Some(home) => home.join(rest.trim_start_matches(is_separator)),
+        None => PathBuf::from(input),
+    }
+}
+
+/// Completes a partially typed directory path.
+///
+/// Directories in the parent of the input whose names start with the typed
+/// prefix are candidates. A single candidate is completed with a trailing
+/// slash; several candidates are completed to their longest common prefix.
+/// Hidden directories are only offered when the prefix starts with a dot.
+///
+/// # Arguments
+///
+/// * `input` - The path typed so far, possibly starting with `~`
+///
+/// # Returns
+///
+/// The completed input, or the input unchanged if nothing matches.
+pub fn complete_dir(input: &str) -> String {
+    let (dir_part, prefix) = match input.rfind(is_separator) {
+        Some(pos) => (&input[..=pos], &input[pos + 1..]),
+        None => ("", input),
+    };
+    let search_dir = if dir_part.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_tilde(dir_part)
+    };
+
+    let Ok(entries) = fs::read_dir(&search_dir) else {
+        return input.to_string();
+    };
+    let mut candidates: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter(|name| name.starts_with(prefix))
+        .filter(|name| prefix.starts_with('.') || !name.starts_with('.'))
+        .collect();
+    candidates.sort();
+
+    match candidates.as_slice() {
+        [] => input.to_string(),
+        [only] => format!("{}{}/", dir_part, only),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.as_str(), |common, name| {
+                let len = common
+                    .char_indices()
+                    .zip(name.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .last()
+                    .map_or(0, |((i, a), _)| i + a.len_utf8());
+                &common[..len]
+            });
+            format!("{}{}", dir_part, common)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn when_single_directory_matches_should_complete_with_slash() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("projects")).unwrap();
+        fs::write(dir.path().join("profile.txt"), "").unwrap();
+        let base = format!("{}/", dir.path().display());
+
+        assert_eq!(
+            complete_dir(&format!("{}pro", base)),
+            format!("{}projects/", base)
+        );
+    }
+
+    #[test]
+    fn when_several_directories_match_should_complete_common_prefix() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("gz-claude")).unwrap();
+        fs::create_dir(dir.path().join("gz-core")).unwrap();
+        fs::create_dir(dir.path().join(".gz-hidden")).unwrap();
+        let base = format!("{}/", dir.path().display());
+
+        assert_eq!(complete_dir(&format!("{}g", base)), format!("{}gz-c", base));
+        assert_eq!(complete_dir(&format!("{}x", base)), format!("{}x", base));
+    }
+
+    #[test]
+    fn when_expanding_tilde_should_use_home_directory() {
+        let home = dirs::home_dir().unwrap();
+
+        assert_eq!(expand_tilde("~/code"), home.join("code"));
+        assert_eq!(expand_tilde("/tmp"), PathBuf::from("/tmp"));
+        assert_eq!(expand_tilde("~user"), PathBuf::from("~user"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn when_expanding_tilde_with_backslash_should_use_home_directory() {
+        let home = dirs::home_dir().unwrap();
+
+        assert_eq!(expand_tilde("~\\code"), home.join("code"));
+        assert_eq!(complete_dir("C:\\nowhere\\x"), "C:\\nowhere\\x");
+    }
+}
@@ -0,0 +1,67 @@
+//! Shell command construction helpers.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::process::{Child, Command, Stdio};
+
+/// Quotes a string for safe use as a single POSIX shell word.
+///
+/// Wraps the value in single quotes and escapes embedded single quotes.
+///
+/// # Arguments
+///
+/// * `value` - The raw value (e.g. a file path)
+///
+/// # Returns
+///
+/// The quoted value, e.g. `it's` becomes `'it'\''s'`.
+pub fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Builds a command running a script with `sh -c`, in a process group of
+/// its own so `kill` can stop whatever the script started too.
+///
+/// # Arguments
+///
+/// * `script` - The shell script to run
+pub fn command(script: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(script);
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    cmd
+}
+
+/// Kills a command built by `command`, along with the processes it
+/// started, and reaps it.
+///
+/// # Arguments
+///
+/// * `child` - The running command
+pub fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", child.id())])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_quoting_should_wrap_and_escape_single_quotes() {
+        assert_eq!(quote("src/main.rs"), "'src/main.rs'");
+        assert_eq!(quote("it's here"), r"'it'\''s here'");
+    }
+}
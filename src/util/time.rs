@@ -0,0 +1,61 @@
+//! Human-readable times.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::time::{Duration, SystemTime};
+
+/// Formats the time elapsed since a moment as a short human-readable age.
+///
+/// # Arguments
+///
+/// * `time` - The moment in the past
+///
+/// # Returns
+///
+/// A string like "just now", "5m ago", "3h ago" or "2d ago".
+pub fn format_age(time: SystemTime) -> String {
+    let elapsed = SystemTime::now()
+        .duration_since(time)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+
+    match elapsed {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", elapsed / 60),
+        3600..=86399 => format!("{}h ago", elapsed / 3600),
+        _ => format!("{}d ago", elapsed / 86400),
+    }
+}
+
+/// Formats a duration compactly with its largest unit, e.g. "45s" or "3m".
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_formatting_age_should_use_largest_unit() {
+        let now = SystemTime::now();
+
+        assert_eq!(format_age(now), "just now");
+        assert_eq!(format_age(now - Duration::from_secs(300)), "5m ago");
+        assert_eq!(format_age(now - Duration::from_secs(7200)), "2h ago");
+        assert_eq!(format_age(now - Duration::from_secs(200_000)), "2d ago");
+    }
+
+    #[test]
+    fn when_formatting_duration_should_use_largest_unit() {
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(300)), "5m");
+        assert_eq!(format_duration(Duration::from_secs(7200)), "2h");
+    }
+}
@@ -0,0 +1,88 @@
+//! Panes focused by the clients attached to the session.
+//!
+//! Zellij doesn't tell the panel when focus moves, so the clients are listed
+//! periodically with `zellij action list-clients`, in a background thread so
+//! a slow call never delays the panel.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+
+/// Lists the focused panes in the background, at most once per interval.
+#[derive(Debug, Default)]
+pub struct FocusPoll {
+    running: Option<JoinHandle<Result<Vec<String>>>>,
+    last_started: Option<Instant>,
+}
+
+impl FocusPoll {
+    /// Creates a poll that hasn't listed the clients yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects a finished listing and starts the next one when it is due.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Time between two listings
+    /// * `list` - Lists the pane focused by each client, see `focused_panes`
+    ///
+    /// # Returns
+    ///
+    /// The focused panes, when a listing finished since the last poll; a
+    /// failed listing returns None.
+    pub fn poll(
+        &mut self,
+        interval: Duration,
+        list: fn() -> Result<Vec<String>>,
+    ) -> Option<Vec<String>> {
+        let finished = match &self.running {
+            Some(handle) if handle.is_finished() => self
+                .running
+                .take()
+                .and_then(|handle| handle.join().ok()?.ok()),
+            _ => None,
+        };
+
+        let now = Instant::now();
+        let due = self
+            .last_started
+            .map_or(true, |started| now.duration_since(started) >= interval);
+        if self.running.is_none() && due {
+            self.last_started = Some(now);
+            self.running = Some(thread::spawn(list));
+        }
+
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing() -> Result<Vec<String>> {
+        Ok(vec!["terminal_3".to_string()])
+    }
+
+    #[test]
+    fn when_listing_finishes_should_return_it_once_and_wait_for_the_interval() {
+        let mut poll = FocusPoll::new();
+
+        assert_eq!(poll.poll(Duration::from_secs(60), listing), None);
+        while !poll.running.as_ref().unwrap().is_finished() {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(
+            poll.poll(Duration::from_secs(60), listing),
+            Some(vec!["terminal_3".to_string()])
+        );
+        assert!(poll.running.is_none());
+        assert_eq!(poll.poll(Duration::from_secs(60), listing), None);
+    }
+}
@@ -5,15 +5,256 @@
 //!
 //! @author waabox(waabox[at]gmail[dot]com)
 
+use std::ffi::OsString;
 use std::path::Path;
 use std::process::Command;
+use std::sync::Mutex;
 
-use crate::error::{GzClaudeError, Result};
+use super::layout::{focused_pane_name, MAIN_PANE_NAME};
+use super::pipe::{self, PipeRequest};
+use super::process::{self, ensure_in_session};
+use crate::error::{GzClaudeError, Result, ZellijError};
+
+/// Environment variable set by Zellij to the id of the pane a process runs in.
+const PANE_ID_ENV: &str = "ZELLIJ_PANE_ID";
+
+/// The main pane, set by `configure_main_pane`.
+static MAIN_PANE: Mutex<Option<MainPane>> = Mutex::new(None);
+
+/// The pane commands for the main pane are written to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MainPane {
+    /// The Zellij pane id, e.g. "terminal_2".
+    id: String,
+    /// The name the pane has, checked in the layout before writing to it.
+    name: String,
+}
+
+/// Sets the pane that commands sent to the main pane are written to.
+///
+/// # Arguments
+///
+/// * `pane_id` - The Zellij pane id of the main pane (e.g. "terminal_2"),
+///   None if unknown
+/// * `pane_name` - The name gz-claude gave the pane, None for the one the
+///   layout gives it
+pub fn configure_main_pane(pane_id: Option<String>, pane_name: Option<String>) {
+    *MAIN_PANE.lock().unwrap_or_else(|e| e.into_inner()) = pane_id.map(|id| MainPane {
+        id,
+        name: pane_name.unwrap_or_else(|| MAIN_PANE_NAME.to_string()),
+    });
+}
+
+/// Returns the pane id the main pane should have, as laid out by the
+/// gz-claude layout.
+///
+/// The layout opens the main pane right after the panel, so Zellij gives it
+/// the terminal id following the panel's. Zellij doesn't report pane ids in
+/// its layout, so this is a guess: commands are only written to the pane once
+/// the layout shows it focused under the main pane's name. Only meaningful
+/// when called from the panel.
+///
+/// # Returns
+///
+/// The pane id, or None if not running in a Zellij pane.
+pub fn layout_main_pane() -> Option<String> {
+    let panel: u32 = std::env::var(PANE_ID_ENV).ok()?.parse().ok()?;
+    Some(format!("terminal_{}", panel + 1))
+}
+
+/// Returns the pane id of the pane this process runs in.
+pub fn own_pane() -> Option<String> {
+    let id = std::env::var(PANE_ID_ENV).ok()?;
+    Some(format!("terminal_{}", id))
+}
+
+/// Returns the main pane.
+///
+/// # Errors
+///
+/// `ZellijError::MainPaneUnknown` if the main pane was never configured.
+fn main_pane() -> Result<MainPane> {
+    MAIN_PANE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .ok_or_else(|| ZellijError::MainPaneUnknown.into())
+}
+
+/// Records the name the main pane was renamed to.
+///
+/// # Arguments
+///
+/// * `pane_id` - The pane renamed; nothing changes if it's not the main pane
+/// * `pane_name` - Its new name
+fn rename_main(pane_id: &str, pane_name: &str) {
+    if let Some(main) = MAIN_PANE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_mut()
+        .filter(|main| main.id == pane_id)
+    {
+        main.name = pane_name.to_string();
+    }
+}
+
+/// Focuses the main pane by its id, then checks it's the main pane.
+///
+/// # Errors
+///
+/// - `ZellijError::MainPaneUnknown` if the main pane was never configured
+/// - `ZellijError::MainPaneNotFound` if Zellij doesn't focus it, e.g.
+///   because it was closed, or the focused pane doesn't have its name
+/// - `ZellijError::CommandFailed` if focusing, listing the clients or
+///   dumping the layout fails
+pub(super) fn focus_main() -> Result<()> {
+    focus_main_checked(&main_pane()?)
+}
+
+/// Focuses a main pane by its id and checks the layout shows it focused
+/// under its name.
+///
+/// The id is guessed from the layout (see `layout_main_pane`), and layouts
+/// edited by hand or panels opened in a split don't have the pane the guess
+/// points to, so the name tells whether it's really the main pane.
+///
+/// # Errors
+///
+/// See `focus_main`.
+fn focus_main_checked(main: &MainPane) -> Result<()> {
+    focus_pane_checked(&main.id).map_err(as_main_pane)?;
+    // Nothing runs in a dry run, so there's nothing to check
+    if process::is_dry_run() {
+        return Ok(());
+    }
+
+    let dump = process::run(
+        "zellij action dump-layout",
+        Command::new("zellij").args(["action", "dump-layout"]),
+    )?;
+    if focused_pane_name(&String::from_utf8_lossy(&dump.stdout)).as_deref() == Some(&main.name) {
+        Ok(())
+    } else {
+        Err(ZellijError::MainPaneNotFound {
+            pane_id: main.id.clone(),
+        }
+        .into())
+    }
+}
+
+/// Reports a pane that couldn't be focused as the main pane.
+fn as_main_pane(error: GzClaudeError) -> GzClaudeError {
+    match error {
+        GzClaudeError::Zellij(ZellijError::PaneNotFound { pane_id }) => {
+            ZellijError::MainPaneNotFound { pane_id }.into()
+        }
+        error => error,
+    }
+}
+
+/// Focuses a pane by its id and checks a client now shows it focused.
+///
+/// Zellij ignores ids of panes that don't exist, which would leave another
+/// pane focused and receiving what is meant for this one.
+///
+/// # Arguments
+///
+/// * `pane_id` - The Zellij pane id
+///
+/// # Errors
+///
+/// - `ZellijError::PaneNotFound` if no client shows the pane focused
+/// - `ZellijError::CommandFailed` if focusing or listing the clients fails
+pub(super) fn focus_pane_checked(pane_id: &str) -> Result<()> {
+    process::run(
+        "zellij action focus-pane-id",
+        Command::new("zellij").args(["action", "focus-pane-id", pane_id]),
+    )?;
+    // Nothing runs in a dry run, so there's nothing to check
+    if process::is_dry_run() {
+        return Ok(());
+    }
+
+    if focused_panes()?.iter().any(|focused| focused == pane_id) {
+        Ok(())
+    } else {
+        Err(ZellijError::PaneNotFound {
+            pane_id: pane_id.to_string(),
+        }
+        .into())
+    }
+}
+
+/// Focuses the pane gz-claude runs in again, if known.
+///
+/// Failures are ignored: the operation it follows already happened.
+fn return_focus(return_to: Option<&str>) {
+    if let Some(return_to) = return_to {
+        let _ =
+            process::status(Command::new("zellij").args(["action", "focus-pane-id", return_to]));
+    }
+}
+
+/// Returns the panes focused by the clients attached to the session.
+///
+/// # Returns
+///
+/// The pane id (e.g. "terminal_3") focused by each client, in client order.
+///
+/// # Errors
+///
+/// Returns `ZellijError::CommandFailed` if listing the clients fails.
+pub fn focused_panes() -> Result<Vec<String>> {
+    // CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND, one line per client
+    let clients = process::run(
+        "zellij action list-clients",
+        Command::new("zellij").args(["action", "list-clients"]),
+    )?;
+    Ok(String::from_utf8_lossy(&clients.stdout)
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Writes to the main pane, then focuses the pane gz-claude runs in.
+///
+/// # Arguments
+///
+/// * `main` - The main pane, focused and checked first
+/// * `pane_name` - The name given to the pane first, if any; a failed rename
+///   doesn't stop the write
+/// * `write` - The `zellij action` writing to the pane and its argument
+/// * `return_to` - The pane focused afterwards, if known
+fn write_to_main(
+    main: &MainPane,
+    pane_name: Option<&str>,
+    write: [&str; 2],
+    return_to: Option<&str>,
+) -> Result<()> {
+    focus_main_checked(main)?;
+    if let Some(pane_name) = pane_name.filter(|name| *name != main.name) {
+        if rename_focused_pane(pane_name).is_ok() {
+            rename_main(&main.id, pane_name);
+        }
+    }
+    let written = process::run_once(
+        &format!("zellij action {}", write[0]),
+        Command::new("zellij").arg("action").args(write),
+    );
+    return_focus(return_to);
+    written.map(|_| ())
+}
 
 /// Starts a new Zellij session with the gz-claude layout.
 ///
-/// Executes `zellij --layout gz-claude` to launch Zellij with the pre-configured
-/// workspace layout that includes the gz-claude panel.
+/// Executes `zellij --session <name> --layout gz-claude` to launch Zellij with
+/// the pre-configured workspace layout that includes the gz-claude panel.
+///
+/// # Arguments
+///
+/// * `session_name` - Name of the new session; it must not exist yet
 ///
 /// # Returns
 ///
@@ -21,33 +262,59 @@ use crate::error::{GzClaudeError, Result};
 ///
 /// # Errors
 ///
-/// Returns `GzClaudeError::Zellij` if:
-/// - Zellij is not installed or not in PATH
-/// - The gz-claude layout file does not exist
-/// - The Zellij process fails to start or exits with an error
+/// - `ZellijError::NotInstalled` if Zellij is not installed or not in PATH
+/// - `ZellijError::CommandFailed` if Zellij exits with an error (e.g. the
+///   gz-claude layout file does not exist)
 ///
 /// # Example
 ///
 /// ```no_run
 /// use gz_claude::zellij::start_zellij;
 ///
-/// match start_zellij() {
+/// match start_zellij("gz-claude") {
 ///     Ok(()) => println!("Zellij session started"),
 ///     Err(e) => eprintln!("Failed to start Zellij: {}", e),
 /// }
 /// ```
-pub fn start_zellij() -> Result<()> {
-    let output = Command::new("zellij")
-        .arg("--layout")
-        .arg("gz-claude")
-        .status()
-        .map_err(|e| GzClaudeError::Zellij(format!("Failed to execute zellij: {}", e)))?;
+pub fn start_zellij(session_name: &str) -> Result<()> {
+    // Zellij takes over the terminal, so stderr is not captured here
+    let status = process::status(Command::new("zellij").args([
+        "--session",
+        session_name,
+        "--layout",
+        "gz-claude",
+    ]))
+    .map_err(process::spawn_error)?;
+
+    if !status.success() {
+        return Err(process::command_failed("zellij", status, &[]).into());
+    }
+
+    Ok(())
+}
 
-    if !output.success() {
-        return Err(GzClaudeError::Zellij(format!(
-            "Zellij exited with status: {}",
-            output
-        )));
+/// Starts a new Zellij session with a layout file.
+///
+/// # Arguments
+///
+/// * `session_name` - The name of the session to create
+/// * `layout` - The KDL layout file, e.g. a working set layout
+///
+/// # Errors
+///
+/// - `ZellijError::NotInstalled` if Zellij is not installed or not in PATH
+/// - `ZellijError::CommandFailed` if Zellij exits with an error
+pub fn start_zellij_with_layout(session_name: &str, layout: &Path) -> Result<()> {
+    // Zellij takes over the terminal, so stderr is not captured here
+    let status = process::status(
+        Command::new("zellij")
+            .args(["--session", session_name, "--layout"])
+            .arg(layout),
+    )
+    .map_err(process::spawn_error)?;
+
+    if !status.success() {
+        return Err(process::command_failed("zellij", status, &[]).into());
     }
 
     Ok(())
@@ -69,10 +336,10 @@ pub fn start_zellij() -> Result<()> {
 ///
 /// # Errors
 ///
-/// Returns `GzClaudeError::Zellij` if:
-/// - Not running inside a Zellij session
-/// - The Zellij action command fails
-/// - The specified working directory is invalid
+/// - `ZellijError::EmptyCommand` if the command is empty
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::NotInstalled` if Zellij is not installed
+/// - `ZellijError::CommandFailed` if the Zellij action fails (e.g. invalid working directory)
 ///
 /// # Example
 ///
@@ -90,10 +357,9 @@ pub fn open_pane(cwd: &Path, command: &str) -> Result<()> {
     let command_parts: Vec<&str> = command.split_whitespace().collect();
 
     if command_parts.is_empty() {
-        return Err(GzClaudeError::Zellij(
-            "Cannot open pane with empty command".to_string(),
-        ));
+        return Err(ZellijError::EmptyCommand.into());
     }
+    ensure_in_session()?;
 
     let mut cmd = Command::new("zellij");
     cmd.arg("action")
@@ -106,95 +372,81 @@ pub fn open_pane(cwd: &Path, command: &str) -> Result<()> {
         cmd.arg(part);
     }
 
-    let output = cmd
-        .status()
-        .map_err(|e| GzClaudeError::Zellij(format!("Failed to execute zellij action: {}", e)))?;
-
-    if !output.success() {
-        return Err(GzClaudeError::Zellij(format!(
-            "Zellij action failed with status: {}",
-            output
-        )));
-    }
-
+    process::run_once("zellij action new-pane", &mut cmd)?;
     Ok(())
 }
 
-/// Runs a command in a floating pane.
-///
-/// Creates a floating pane with the specified command. Floating panes
-/// appear over the layout and don't have splitting restrictions.
-/// User can toggle between floating/embedded with Ctrl+p w.
-///
-/// # Arguments
-///
-/// * `pane_name` - Unique name for the pane (for tracking)
-/// * `command` - The full command string to execute (e.g., "claude /path/to/project")
-///
-/// # Returns
+/// Runs a command in the main (central) pane by writing to the terminal.
 ///
-/// Returns `Ok(())` if the pane is created successfully.
+/// The main pane is focused by its id (see `configure_main_pane`), and the
+/// command is only written once Zellij reports it focused and the layout
+/// shows it under the main pane's name, so it never lands in another pane.
+/// The pane is renamed to `pane_name` first; a failed rename doesn't stop
+/// the command. Focus returns to gz-claude afterwards.
+/// Goes through the gz-claude plugin when it is enabled and loaded.
 ///
 /// # Errors
 ///
-/// Returns `GzClaudeError::Zellij` if any Zellij action fails.
-/// Runs a command in the main (central) pane by writing to the terminal.
-///
-/// If `fullscreen` is true, the pane will be toggled to fullscreen mode
-/// for optimal viewing from the web client.
-pub fn run_in_main_pane(command: &str, fullscreen: bool) -> Result<()> {
+/// - `ZellijError::EmptyCommand` if the command is empty
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::MainPaneUnknown` or `ZellijError::MainPaneNotFound` if the
+///   main pane can't be found
+/// - `ZellijError::CommandFailed` if focusing or writing the command fails
+pub fn run_in_main_pane(pane_name: &str, command: &str) -> Result<()> {
     if command.trim().is_empty() {
-        return Err(GzClaudeError::Zellij(
-            "Cannot run empty command".to_string(),
-        ));
+        return Err(ZellijError::EmptyCommand.into());
     }
+    ensure_in_session()?;
 
-    // Move focus to the right pane
-    Command::new("zellij")
-        .args(["action", "move-focus", "right"])
-        .status()
-        .map_err(|e| GzClaudeError::Zellij(format!("Failed to move focus: {}", e)))?;
-
-    // Small delay
-    std::thread::sleep(std::time::Duration::from_millis(50));
+    let request = PipeRequest::RunInMainPane {
+        name: pane_name.to_string(),
+        command: command.to_string(),
+    };
+    if let Some(result) = pipe::try_send(&request) {
+        // The plugin renamed the pane too
+        if let (Ok(_), Ok(main)) = (&result, main_pane()) {
+            rename_main(&main.id, pane_name);
+        }
+        return result.map(|_| ());
+    }
 
     // Write the command to the terminal (with newline to execute)
     let cmd_with_newline = format!("{}\n", command);
-    Command::new("zellij")
-        .args(["action", "write-chars", &cmd_with_newline])
-        .status()
-        .map_err(|e| GzClaudeError::Zellij(format!("Failed to write command: {}", e)))?;
-
-    if fullscreen {
-        // Toggle fullscreen for web client viewing
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        let _ = Command::new("zellij")
-            .args(["action", "toggle-fullscreen"])
-            .status();
-        // Don't move focus back - stay on the fullscreen pane
-    } else {
-        // Move focus back to gz-claude
-        std::thread::sleep(std::time::Duration::from_millis(50));
-        let _ = Command::new("zellij")
-            .args(["action", "move-focus", "left"])
-            .status();
-    }
-
-    Ok(())
+    write_to_main(
+        &main_pane()?,
+        Some(pane_name),
+        ["write-chars", &cmd_with_newline],
+        own_pane().as_deref(),
+    )
 }
 
 /// Runs a command in a floating pane.
 ///
-/// If `fullscreen` is true, the pane will be toggled to fullscreen mode
-/// for optimal viewing from the web client.
-pub fn run_in_floating_pane(pane_name: &str, command: &str, fullscreen: bool) -> Result<()> {
+/// The command is executed through `sh -c`, so shell syntax such as quoting
+/// and `&&` chaining behaves the same as in the main pane.
+///
+/// # Returns
+///
+/// The id of the new pane (e.g. "terminal_3") if Zellij reports it.
+///
+/// # Errors
+///
+/// - `ZellijError::EmptyCommand` if the command is empty
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::CommandFailed` if `zellij run` fails
+pub fn run_in_floating_pane(pane_name: &str, command: &str) -> Result<Option<String>> {
     if command.trim().is_empty() {
-        return Err(GzClaudeError::Zellij(
-            "Cannot run empty command".to_string(),
-        ));
+        return Err(ZellijError::EmptyCommand.into());
     }
+    ensure_in_session()?;
 
-    let command_parts: Vec<&str> = command.split_whitespace().collect();
+    let request = PipeRequest::RunFloating {
+        name: pane_name.to_string(),
+        command: command.to_string(),
+    };
+    if let Some(result) = pipe::try_send(&request) {
+        return result.map(|response| response.pane_id);
+    }
 
     let mut cmd = Command::new("zellij");
     cmd.arg("run")
@@ -205,75 +457,331 @@ pub fn run_in_floating_pane(pane_name: &str, command: &str, fullscreen: bool) ->
         .arg("80%")
         .arg("--name")
         .arg(pane_name)
-        .arg("--");
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg(command);
 
-    for part in &command_parts {
-        cmd.arg(part);
-    }
+    let output = process::run_once("zellij run", &mut cmd)?;
+    let pane_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
 
-    let output = cmd
-        .status()
-        .map_err(|e| GzClaudeError::Zellij(format!("Failed to open floating pane: {}", e)))?;
+    Ok(Some(pane_id).filter(|id| !id.is_empty()))
+}
 
-    if !output.success() {
-        return Err(GzClaudeError::Zellij(format!(
-            "Zellij run failed with status: {}",
-            output
-        )));
+/// Runs a command in a new pane splitting the focused one.
+///
+/// The command is executed through `sh -c`, like in floating panes.
+///
+/// # Arguments
+///
+/// * `pane_name` - The name shown in the pane frame
+/// * `command` - The shell command to run
+/// * `direction` - Where the new pane goes: "down" or "right"
+///
+/// # Returns
+///
+/// The id of the new pane (e.g. "terminal_3") if Zellij reports it.
+///
+/// # Errors
+///
+/// - `ZellijError::EmptyCommand` if the command is empty
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::CommandFailed` if `zellij run` fails
+pub fn run_in_split_pane(
+    pane_name: &str,
+    command: &str,
+    direction: &str,
+) -> Result<Option<String>> {
+    if command.trim().is_empty() {
+        return Err(ZellijError::EmptyCommand.into());
     }
+    ensure_in_session()?;
 
-    if fullscreen {
-        // Toggle fullscreen for web client viewing
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        let _ = Command::new("zellij")
-            .args(["action", "toggle-fullscreen"])
-            .status();
-    }
+    let mut cmd = Command::new("zellij");
+    cmd.arg("run")
+        .arg("--direction")
+        .arg(direction)
+        .arg("--name")
+        .arg(pane_name)
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg(command);
 
-    Ok(())
+    let output = process::run_once("zellij run", &mut cmd)?;
+    let pane_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    Ok(Some(pane_id).filter(|id| !id.is_empty()))
 }
 
-/// Focus an existing pane by moving to the right.
+/// Runs a command in a pane of a new tab named after the pane.
+///
+/// The pane is opened floating, embedded, then moved to its own tab; focus
+/// follows it. In a dry run, where Zellij reports no pane id, the moves are
+/// shown for a placeholder id.
+///
+/// # Arguments
 ///
-/// Since Zellij doesn't support focus-by-name directly, this just
-/// moves focus to the right pane.
+/// * `pane_name` - The name of the pane and its tab
+/// * `command` - The shell command to run
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` if focus moved successfully.
-pub fn focus_main_pane() -> Result<()> {
-    let output = Command::new("zellij")
-        .args(["action", "move-focus", "right"])
-        .status()
-        .map_err(|e| GzClaudeError::Zellij(format!("Failed to move focus: {}", e)))?;
-
-    if !output.success() {
-        return Err(GzClaudeError::Zellij(
-            "Failed to focus main pane".to_string(),
-        ));
-    }
+/// The id of the new pane.
+///
+/// # Errors
+///
+/// - `ZellijError::EmptyCommand` if the command is empty
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::UnknownPaneId` if Zellij doesn't report the pane id
+/// - `ZellijError::CommandFailed` if opening or moving the pane fails
+pub fn run_in_new_tab(pane_name: &str, command: &str) -> Result<Option<String>> {
+    let pane_id = match run_in_floating_pane(pane_name, command)? {
+        Some(pane_id) => pane_id,
+        None if process::is_dry_run() => "<pane id>".to_string(),
+        None => {
+            return Err(ZellijError::UnknownPaneId {
+                pane_name: pane_name.to_string(),
+            }
+            .into())
+        }
+    };
+    toggle_pane_embedded(&pane_id)?;
+    move_pane_to_new_tab(&pane_id, pane_name)?;
+    Ok(Some(pane_id))
+}
 
+/// Renames the focused pane.
+///
+/// # Arguments
+///
+/// * `pane_name` - The new name shown in the pane frame
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::CommandFailed` if `zellij action rename-pane` fails
+pub fn rename_focused_pane(pane_name: &str) -> Result<()> {
+    ensure_in_session()?;
+    process::run(
+        "zellij action rename-pane",
+        Command::new("zellij").args(["action", "rename-pane", pane_name]),
+    )?;
     Ok(())
 }
 
-/// Toggle fullscreen mode for the currently focused pane.
+/// Focuses a pane by its id.
+///
+/// # Arguments
+///
+/// * `pane_id` - The Zellij pane id returned when the pane was opened
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::PaneNotFound` if Zellij doesn't focus it, e.g. because it
+///   was closed
+/// - `ZellijError::CommandFailed` if focusing or listing the clients fails
+pub fn focus_pane(pane_id: &str) -> Result<()> {
+    ensure_in_session()?;
+    focus_pane_checked(pane_id)
+}
+
+/// Renames a pane by its id, then focuses the pane gz-claude runs in.
+///
+/// Zellij only renames the focused pane, so the pane is focused first and
+/// left alone if it can't be.
 ///
-/// This is useful for the web client to show only the Claude terminal.
-pub fn toggle_fullscreen() -> Result<()> {
-    let output = Command::new("zellij")
-        .args(["action", "toggle-fullscreen"])
-        .status()
-        .map_err(|e| GzClaudeError::Zellij(format!("Failed to toggle fullscreen: {}", e)))?;
+/// # Arguments
+///
+/// * `pane_id` - The Zellij pane id returned when the pane was opened
+/// * `pane_name` - The new name shown in the pane frame
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::PaneNotFound` if Zellij doesn't focus it
+/// - `ZellijError::CommandFailed` if focusing or renaming the pane fails
+pub fn rename_pane(pane_id: &str, pane_name: &str) -> Result<()> {
+    ensure_in_session()?;
+    focus_pane_checked(pane_id)?;
+    let renamed = process::run(
+        "zellij action rename-pane",
+        Command::new("zellij").args(["action", "rename-pane", pane_name]),
+    );
+    return_focus(own_pane().as_deref());
+    renamed.map(|_| ())
+}
 
-    if !output.success() {
-        return Err(GzClaudeError::Zellij(
-            "Failed to toggle fullscreen".to_string(),
-        ));
+/// Closes a pane by its id.
+///
+/// # Arguments
+///
+/// * `pane_id` - The Zellij pane id returned when the pane was opened
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::CommandFailed` if `zellij action close-pane` fails
+pub fn close_pane(pane_id: &str) -> Result<()> {
+    ensure_in_session()?;
+    let request = PipeRequest::ClosePane {
+        pane_id: pane_id.to_string(),
+    };
+    if let Some(result) = pipe::try_send(&request) {
+        return result.map(|_| ());
     }
+    process::run(
+        "zellij action close-pane",
+        Command::new("zellij").args(["action", "close-pane", "--pane-id", pane_id]),
+    )?;
+    Ok(())
+}
+
+/// Moves a pane between floating and embedded (tiled) in its tab.
+///
+/// # Arguments
+///
+/// * `pane_id` - The Zellij pane id returned when the pane was opened
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::PaneNotFound` if Zellij doesn't focus it
+/// - `ZellijError::CommandFailed` if focusing or moving the pane fails
+pub fn toggle_pane_embedded(pane_id: &str) -> Result<()> {
+    ensure_in_session()?;
+    focus_pane_checked(pane_id)?;
+    process::run_once(
+        "zellij action toggle-pane-embed-or-floating",
+        Command::new("zellij").args(["action", "toggle-pane-embed-or-floating"]),
+    )?;
+    Ok(())
+}
 
+/// Moves an embedded pane to a new tab of its own, named after it.
+///
+/// Focus follows the pane to the new tab.
+///
+/// # Arguments
+///
+/// * `pane_id` - The Zellij pane id returned when the pane was opened
+/// * `tab_name` - The name of the new tab
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::PaneNotFound` if Zellij doesn't focus it
+/// - `ZellijError::CommandFailed` if focusing, moving the pane or renaming
+///   the tab fails
+pub fn move_pane_to_new_tab(pane_id: &str, tab_name: &str) -> Result<()> {
+    ensure_in_session()?;
+    focus_pane_checked(pane_id)?;
+    process::run_once(
+        "zellij action break-pane",
+        Command::new("zellij").args(["action", "break-pane"]),
+    )?;
+    process::run(
+        "zellij action rename-tab",
+        Command::new("zellij").args(["action", "rename-tab", tab_name]),
+    )?;
+    Ok(())
+}
+
+/// Switches to a tab by position.
+///
+/// # Arguments
+///
+/// * `index` - The one-based position of the tab
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::CommandFailed` if `zellij action go-to-tab` fails
+pub fn go_to_tab(index: usize) -> Result<()> {
+    ensure_in_session()?;
+    process::run(
+        "zellij action go-to-tab",
+        Command::new("zellij").args(["action", "go-to-tab", &index.to_string()]),
+    )?;
     Ok(())
 }
 
+/// Returns the text currently shown in a pane.
+///
+/// Zellij writes the pane's viewport to a temporary file, which is read and
+/// removed.
+///
+/// # Arguments
+///
+/// * `pane_id` - The Zellij pane id returned when the pane was opened
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::CommandFailed` if `zellij action dump-screen` fails
+/// - `GzClaudeError::Io` if the dump can't be read
+pub fn dump_pane(pane_id: &str) -> Result<String> {
+    ensure_in_session()?;
+    let dump = std::env::temp_dir().join(format!(
+        "gz-claude-screen-{}-{}.txt",
+        std::process::id(),
+        pane_id
+    ));
+    process::run(
+        "zellij action dump-screen",
+        Command::new("zellij")
+            .args(["action", "dump-screen", "--pane-id", pane_id])
+            .arg(&dump),
+    )?;
+    let screen = std::fs::read_to_string(&dump);
+    let _ = std::fs::remove_file(&dump);
+    Ok(screen?)
+}
+
+/// Stops the command running in the main pane by sending it Ctrl-C.
+///
+/// The main pane is part of the layout, so it's interrupted rather than
+/// closed, and gets the name the layout gives it back. Focus returns to
+/// gz-claude afterwards.
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::MainPaneUnknown` or `ZellijError::MainPaneNotFound` if the
+///   main pane can't be found
+/// - `ZellijError::CommandFailed` if focusing or writing to the pane fails
+pub fn interrupt_main_pane() -> Result<()> {
+    ensure_in_session()?;
+    if let Some(result) = pipe::try_send(&PipeRequest::InterruptMainPane) {
+        return result.map(|_| ());
+    }
+    write_to_main(
+        &main_pane()?,
+        Some(MAIN_PANE_NAME),
+        ["write", "3"],
+        own_pane().as_deref(),
+    )
+}
+
+/// Focuses the main pane by its id, unless the gz-claude plugin is loaded.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if focus moved successfully.
+///
+/// # Errors
+///
+/// - `ZellijError::MainPaneUnknown` or `ZellijError::MainPaneNotFound` if the
+///   main pane can't be found
+/// - `ZellijError::CommandFailed` if focusing the pane fails
+pub fn focus_main_pane() -> Result<()> {
+    if let Some(result) = pipe::try_send(&PipeRequest::FocusMainPane) {
+        return result.map(|_| ());
+    }
+    focus_main()
+}
+
 /// Opens a file in an editor within a new Zellij pane.
 ///
 /// Creates a new pane in the current Zellij session and opens the specified file
@@ -292,10 +800,9 @@ pub fn toggle_fullscreen() -> Result<()> {
 ///
 /// # Errors
 ///
-/// Returns `GzClaudeError::Zellij` if:
-/// - Not running inside a Zellij session
-/// - The Zellij action command fails
-/// - The specified working directory or file path is invalid
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::NotInstalled` if Zellij is not installed
+/// - `ZellijError::CommandFailed` if the Zellij action fails (e.g. invalid paths)
 ///
 /// # Example
 ///
@@ -319,42 +826,550 @@ pub fn toggle_fullscreen() -> Result<()> {
 /// }
 /// ```
 pub fn open_file_in_editor(cwd: &Path, editor: &str, file_path: &Path) -> Result<()> {
+    open_editor_pane(cwd, editor, file_path, None)
+}
+
+/// Opens a file in an editor within a new Zellij pane, at a given line.
+///
+/// Editors that take a `file:line` argument (VS Code, Cursor, Helix, Zed)
+/// get one; the others get `+line` before the file, as vim, nano and emacs
+/// expect.
+///
+/// # Arguments
+///
+/// * `cwd` - The working directory for the new pane
+/// * `editor` - The editor command to use (use "$EDITOR" to resolve from environment)
+/// * `file_path` - The path to the file to open
+/// * `line` - The line to open the file at, starting at 1
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if not running inside a Zellij session
+/// - `ZellijError::CommandFailed` if the Zellij action fails
+pub fn open_file_in_editor_at(cwd: &Path, editor: &str, file_path: &Path, line: u32) -> Result<()> {
+    open_editor_pane(cwd, editor, file_path, Some(line))
+}
+
+/// Opens a file in an editor within a new Zellij pane, at a line if given.
+fn open_editor_pane(cwd: &Path, editor: &str, file_path: &Path, line: Option<u32>) -> Result<()> {
     let resolved_editor = if editor == "$EDITOR" {
         std::env::var("EDITOR").unwrap_or_else(|_| "vim".to_string())
     } else {
         editor.to_string()
     };
 
-    let output = Command::new("zellij")
-        .arg("action")
-        .arg("new-pane")
-        .arg("--cwd")
-        .arg(cwd)
-        .arg("--")
-        .arg(&resolved_editor)
-        .arg(file_path)
-        .status()
-        .map_err(|e| GzClaudeError::Zellij(format!("Failed to execute zellij action: {}", e)))?;
-
-    if !output.success() {
-        return Err(GzClaudeError::Zellij(format!(
-            "Zellij action failed with status: {}",
-            output
-        )));
-    }
+    ensure_in_session()?;
 
+    process::run_once(
+        "zellij action new-pane",
+        Command::new("zellij")
+            .arg("action")
+            .arg("new-pane")
+            .arg("--cwd")
+            .arg(cwd)
+            .arg("--")
+            .arg(&resolved_editor)
+            .args(editor_file_args(&resolved_editor, file_path, line)),
+    )?;
     Ok(())
 }
 
+/// Returns the arguments that make an editor open a file at a line.
+///
+/// # Arguments
+///
+/// * `editor` - The editor command
+/// * `file_path` - The path to the file to open
+/// * `line` - The line to open the file at, if any
+fn editor_file_args(editor: &str, file_path: &Path, line: Option<u32>) -> Vec<OsString> {
+    let Some(line) = line else {
+        return vec![file_path.into()];
+    };
+    let program = Path::new(editor)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match program.as_str() {
+        "code" | "codium" | "cursor" => vec![
+            "--goto".into(),
+            format!("{}:{}", file_path.display(), line).into(),
+        ],
+        "hx" | "helix" | "zed" => vec![format!("{}:{}", file_path.display(), line).into()],
+        _ => vec![format!("+{}", line).into(), file_path.into()],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use std::rc::Rc;
+
+    use crate::error::GzClaudeError;
+    use crate::zellij::process::{argv, with_runner, MockRunner};
+
+    #[test]
+    fn when_running_empty_command_in_main_pane_should_fail() {
+        // Empty commands are rejected before any Zellij invocation happens,
+        // so this can run without a Zellij session.
+        assert!(matches!(
+            run_in_main_pane("pane", "   "),
+            Err(GzClaudeError::Zellij(ZellijError::EmptyCommand))
+        ));
+        assert!(matches!(
+            run_in_floating_pane("pane", ""),
+            Err(GzClaudeError::Zellij(ZellijError::EmptyCommand))
+        ));
+    }
+
+    #[test]
+    fn when_focused_pane_is_listed_should_accept_focus() {
+        let runner = Rc::new(MockRunner::new().with_stdout(
+            &["zellij", "action", "list-clients"],
+            "CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND\n1         terminal_2     bash\n",
+        ));
+
+        with_runner(runner.clone(), || focus_pane_checked("terminal_2")).unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![
+                argv(&["zellij", "action", "focus-pane-id", "terminal_2"]),
+                argv(&["zellij", "action", "list-clients"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn when_listing_clients_should_return_the_pane_each_focuses() {
+        let runner = Rc::new(MockRunner::new().with_stdout(
+            &["zellij", "action", "list-clients"],
+            "CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND\n1  terminal_2  bash\n2  terminal_5  claude\n",
+        ));
+
+        let focused = with_runner(runner, focused_panes).unwrap();
+
+        assert_eq!(focused, vec!["terminal_2", "terminal_5"]);
+    }
+
     #[test]
-    fn when_command_module_loaded_should_compile() {
-        // This test verifies that the module compiles correctly and all functions
-        // are properly defined. Actual Zellij command execution cannot be tested
-        // without a running Zellij session.
-        assert!(true);
+    fn when_dumping_pane_should_target_it_by_id() {
+        let runner = Rc::new(MockRunner::new());
+
+        // The mock doesn't write the dump, so reading it fails
+        let result = with_runner(runner.clone(), || dump_pane("terminal_4"));
+
+        assert!(matches!(result, Err(GzClaudeError::Io(_))));
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(
+            calls[0][..5],
+            argv(&["zellij", "action", "dump-screen", "--pane-id", "terminal_4"])
+        );
+        assert!(calls[0][5].ends_with("-terminal_4.txt"));
+    }
+
+    #[test]
+    fn when_embedding_pane_should_focus_it_first() {
+        let runner = Rc::new(focusing("terminal_4"));
+
+        with_runner(runner.clone(), || toggle_pane_embedded("terminal_4")).unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![
+                argv(&["zellij", "action", "focus-pane-id", "terminal_4"]),
+                argv(&["zellij", "action", "list-clients"]),
+                argv(&["zellij", "action", "toggle-pane-embed-or-floating"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn when_moving_pane_to_tab_should_break_it_out_and_name_the_tab() {
+        let runner = Rc::new(focusing("terminal_4"));
+
+        with_runner(runner.clone(), || {
+            move_pane_to_new_tab("terminal_4", "Work/api · Claude")
+        })
+        .unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![
+                argv(&["zellij", "action", "focus-pane-id", "terminal_4"]),
+                argv(&["zellij", "action", "list-clients"]),
+                argv(&["zellij", "action", "break-pane"]),
+                argv(&["zellij", "action", "rename-tab", "Work/api · Claude"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn when_starting_zellij_should_name_session_and_use_layout() {
+        let runner = Rc::new(MockRunner::new());
+
+        with_runner(runner.clone(), || start_zellij("work")).unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![argv(&[
+                "zellij",
+                "--session",
+                "work",
+                "--layout",
+                "gz-claude"
+            ])]
+        );
+    }
+
+    #[test]
+    fn when_starting_zellij_with_layout_file_should_pass_its_path() {
+        let runner = Rc::new(MockRunner::new());
+
+        with_runner(runner.clone(), || {
+            start_zellij_with_layout("gz-claude-work", Path::new("/layouts/gz-claude-work.kdl"))
+        })
+        .unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![argv(&[
+                "zellij",
+                "--session",
+                "gz-claude-work",
+                "--layout",
+                "/layouts/gz-claude-work.kdl"
+            ])]
+        );
+    }
+
+    #[test]
+    fn when_opening_pane_should_pass_cwd_and_split_command() {
+        let runner = Rc::new(MockRunner::new());
+
+        with_runner(runner.clone(), || {
+            open_pane(Path::new("/work/api"), "cargo build --release")
+        })
+        .unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![argv(&[
+                "zellij",
+                "action",
+                "new-pane",
+                "--cwd",
+                "/work/api",
+                "--",
+                "cargo",
+                "build",
+                "--release",
+            ])]
+        );
+    }
+
+    #[test]
+    fn when_not_in_session_should_fail_without_running_zellij() {
+        let runner = Rc::new(MockRunner::new().outside_session());
+
+        let result = with_runner(runner.clone(), || open_pane(Path::new("/work"), "ls"));
+
+        assert!(matches!(
+            result,
+            Err(GzClaudeError::Zellij(ZellijError::NotInSession))
+        ));
+        assert!(runner.calls().is_empty());
+    }
+
+    /// A runner reporting `pane_id` as the focused pane of the only client.
+    fn focusing(pane_id: &str) -> MockRunner {
+        MockRunner::new().with_stdout(
+            &["zellij", "action", "list-clients"],
+            &format!(
+                "CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND\n1 {} bash\n",
+                pane_id
+            ),
+        )
+    }
+
+    /// A runner reporting `pane_id` as the focused pane of the only client,
+    /// named `pane_name` in the layout.
+    fn focusing_named(pane_id: &str, pane_name: &str) -> MockRunner {
+        focusing(pane_id).with_stdout(
+            &["zellij", "action", "dump-layout"],
+            &format!(
+                "layout {{\n    tab name=\"Tab #1\" focus=true {{\n        pane command=\"bash\" name=\"{}\" focus=true\n    }}\n}}\n",
+                pane_name
+            ),
+        )
+    }
+
+    fn main(pane_id: &str) -> MainPane {
+        MainPane {
+            id: pane_id.to_string(),
+            name: MAIN_PANE_NAME.to_string(),
+        }
+    }
+
+    #[test]
+    fn when_writing_to_main_pane_should_focus_check_rename_write_and_return() {
+        let runner = Rc::new(focusing_named("terminal_2", "main"));
+
+        with_runner(runner.clone(), || {
+            write_to_main(
+                &main("terminal_2"),
+                Some("api"),
+                ["write-chars", "cargo test\n"],
+                Some("terminal_1"),
+            )
+        })
+        .unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![
+                argv(&["zellij", "action", "focus-pane-id", "terminal_2"]),
+                argv(&["zellij", "action", "list-clients"]),
+                argv(&["zellij", "action", "dump-layout"]),
+                argv(&["zellij", "action", "rename-pane", "api"]),
+                argv(&["zellij", "action", "write-chars", "cargo test\n"]),
+                argv(&["zellij", "action", "focus-pane-id", "terminal_1"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn when_rename_fails_should_still_write_to_main_pane() {
+        let runner = Rc::new(focusing_named("terminal_2", "main").with_failure(
+            &["zellij", "action", "rename-pane"],
+            1,
+            "unknown action",
+        ));
+
+        with_runner(runner.clone(), || {
+            write_to_main(
+                &main("terminal_2"),
+                Some("api"),
+                ["write-chars", "ls\n"],
+                None,
+            )
+        })
+        .unwrap();
+
+        assert_eq!(
+            runner.calls()[4],
+            argv(&["zellij", "action", "write-chars", "ls\n"])
+        );
+    }
+
+    #[test]
+    fn when_main_pane_is_not_focused_should_write_nothing() {
+        let runner = Rc::new(focusing_named("terminal_1", "main"));
+
+        let result = with_runner(runner.clone(), || {
+            write_to_main(
+                &main("terminal_2"),
+                None,
+                ["write", "3"],
+                Some("terminal_1"),
+            )
+        });
+
+        assert!(matches!(
+            result,
+            Err(GzClaudeError::Zellij(ZellijError::MainPaneNotFound { .. }))
+        ));
+        assert_eq!(runner.calls().len(), 2);
+    }
+
+    #[test]
+    fn when_focused_pane_is_not_named_main_should_write_nothing() {
+        // The guessed id points at another pane, e.g. in an edited layout
+        let runner = Rc::new(focusing_named("terminal_2", "Work/web · Claude"));
+
+        let result = with_runner(runner.clone(), || {
+            write_to_main(
+                &main("terminal_2"),
+                None,
+                ["write-chars", "rm -rf target\n"],
+                None,
+            )
+        });
+
+        assert!(matches!(
+            result,
+            Err(GzClaudeError::Zellij(ZellijError::MainPaneNotFound { .. }))
+        ));
+        assert!(!runner
+            .calls()
+            .iter()
+            .any(|call| call.get(2).is_some_and(|action| action == "write-chars")));
+    }
+
+    #[test]
+    fn when_running_in_floating_pane_should_wrap_command_in_shell() {
+        let runner = Rc::new(MockRunner::new().with_stdout(&["zellij", "run"], "terminal_4\n"));
+
+        let pane_id = with_runner(runner.clone(), || {
+            run_in_floating_pane("logs", "tail -f a && b")
+        })
+        .unwrap();
+
+        assert_eq!(pane_id.as_deref(), Some("terminal_4"));
+        assert_eq!(
+            runner.calls(),
+            vec![argv(&[
+                "zellij",
+                "run",
+                "--floating",
+                "--width",
+                "80%",
+                "--height",
+                "80%",
+                "--name",
+                "logs",
+                "--",
+                "sh",
+                "-c",
+                "tail -f a && b",
+            ])]
+        );
+    }
+
+    #[test]
+    fn when_running_in_split_pane_should_pass_direction() {
+        let runner = Rc::new(MockRunner::new().with_stdout(&["zellij", "run"], "terminal_5\n"));
+
+        let pane_id = with_runner(runner.clone(), || {
+            run_in_split_pane("watch", "cargo watch", "down")
+        })
+        .unwrap();
+
+        assert_eq!(pane_id.as_deref(), Some("terminal_5"));
+        assert_eq!(
+            runner.calls(),
+            vec![argv(&[
+                "zellij",
+                "run",
+                "--direction",
+                "down",
+                "--name",
+                "watch",
+                "--",
+                "sh",
+                "-c",
+                "cargo watch",
+            ])]
+        );
+    }
+
+    #[test]
+    fn when_running_in_new_tab_should_move_floating_pane_to_tab() {
+        let runner =
+            Rc::new(focusing("terminal_6").with_stdout(&["zellij", "run"], "terminal_6\n"));
+
+        let pane_id = with_runner(runner.clone(), || run_in_new_tab("git", "lazygit")).unwrap();
+
+        assert_eq!(pane_id.as_deref(), Some("terminal_6"));
+        let calls = runner.calls();
+        assert_eq!(
+            calls[1..],
+            [
+                argv(&["zellij", "action", "focus-pane-id", "terminal_6"]),
+                argv(&["zellij", "action", "list-clients"]),
+                argv(&["zellij", "action", "toggle-pane-embed-or-floating"]),
+                argv(&["zellij", "action", "focus-pane-id", "terminal_6"]),
+                argv(&["zellij", "action", "list-clients"]),
+                argv(&["zellij", "action", "break-pane"]),
+                argv(&["zellij", "action", "rename-tab", "git"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn when_renaming_pane_should_focus_it_first_and_only_if_found() {
+        let runner = Rc::new(focusing("terminal_4"));
+
+        with_runner(runner.clone(), || rename_pane("terminal_4", "api · Claude")).unwrap();
+        let missing = with_runner(Rc::new(focusing("terminal_1")), || {
+            rename_pane("terminal_4", "api · Claude")
+        });
+
+        assert_eq!(
+            runner.calls()[..3],
+            [
+                argv(&["zellij", "action", "focus-pane-id", "terminal_4"]),
+                argv(&["zellij", "action", "list-clients"]),
+                argv(&["zellij", "action", "rename-pane", "api · Claude"]),
+            ]
+        );
+        assert!(matches!(
+            missing,
+            Err(GzClaudeError::Zellij(ZellijError::PaneNotFound { .. }))
+        ));
+    }
+
+    #[test]
+    fn when_closing_pane_should_address_it_by_id() {
+        let runner = Rc::new(MockRunner::new());
+
+        with_runner(runner.clone(), || close_pane("terminal_4")).unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![argv(&[
+                "zellij",
+                "action",
+                "close-pane",
+                "--pane-id",
+                "terminal_4"
+            ])]
+        );
+    }
+
+    #[test]
+    fn when_opening_file_in_editor_should_run_editor_in_new_pane() {
+        let runner = Rc::new(MockRunner::new());
+
+        with_runner(runner.clone(), || {
+            open_file_in_editor(Path::new("/work"), "nvim", Path::new("/work/src/main.rs"))
+        })
+        .unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![argv(&[
+                "zellij",
+                "action",
+                "new-pane",
+                "--cwd",
+                "/work",
+                "--",
+                "nvim",
+                "/work/src/main.rs",
+            ])]
+        );
+    }
+
+    #[test]
+    fn when_opening_file_at_line_should_pass_line_the_editor_way() {
+        let file = Path::new("/work/src/main.rs");
+
+        assert_eq!(
+            editor_file_args("/usr/bin/nvim", file, Some(12)),
+            vec![OsString::from("+12"), OsString::from("/work/src/main.rs")]
+        );
+        assert_eq!(
+            editor_file_args("code", file, Some(12)),
+            vec![
+                OsString::from("--goto"),
+                OsString::from("/work/src/main.rs:12")
+            ]
+        );
+        assert_eq!(
+            editor_file_args("hx", file, Some(12)),
+            vec![OsString::from("/work/src/main.rs:12")]
+        );
     }
 }
@@ -0,0 +1,154 @@
+//! Pane operations run off the panel's thread.
+//!
+//! Opening a pane waits for `zellij run` to return, long enough that a few in
+//! a row make the panel stutter. The executor runs the operations queued on a
+//! thread of its own, one at a time and in order, so Zellij sees them as if
+//! they were run in place, and hands back each outcome when polled.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+
+use crate::error::{Result, ZellijError};
+
+/// Identifies an operation queued on the executor.
+pub type PaneJobId = u64;
+
+/// The outcome of a pane operation: the id of the pane it opened, if Zellij
+/// reported one.
+pub type PaneOutcome = Result<Option<String>>;
+
+/// A queued operation.
+type Job = Box<dyn FnOnce() -> PaneOutcome + Send>;
+
+/// Runs pane operations in order on a thread of its own.
+pub struct PaneExecutor {
+    jobs: Sender<(PaneJobId, Job)>,
+    outcomes: Receiver<(PaneJobId, PaneOutcome)>,
+    pending: VecDeque<PaneJobId>,
+    next_id: PaneJobId,
+}
+
+impl PaneExecutor {
+    /// Starts the executor's thread.
+    pub fn spawn() -> Self {
+        let (jobs, queue) = mpsc::channel::<(PaneJobId, Job)>();
+        let (sender, outcomes) = mpsc::channel();
+        thread::spawn(move || {
+            for (id, job) in queue {
+                if sender.send((id, job())).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            jobs,
+            outcomes,
+            pending: VecDeque::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Queues an operation, run after the ones queued before it.
+    ///
+    /// The operation runs on the executor's thread, so commands go to the
+    /// `SystemRunner` whatever runner the caller installed.
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The operation
+    ///
+    /// # Returns
+    ///
+    /// The id its outcome is reported with.
+    pub fn submit(&mut self, job: impl FnOnce() -> PaneOutcome + Send + 'static) -> PaneJobId {
+        let id = self.next_id;
+        self.next_id += 1;
+        // A stopped thread is reported by the next poll
+        let _ = self.jobs.send((id, Box::new(job)));
+        self.pending.push_back(id);
+        id
+    }
+
+    /// Collects the outcomes of the operations finished since the last call.
+    ///
+    /// # Returns
+    ///
+    /// The finished operations with their outcome, in the order they were
+    /// queued. If the executor's thread stopped, the operations it left are
+    /// reported as failed.
+    pub fn poll(&mut self) -> Vec<(PaneJobId, PaneOutcome)> {
+        let mut finished = Vec::new();
+        loop {
+            match self.outcomes.try_recv() {
+                Ok((id, outcome)) => {
+                    self.pending.retain(|pending| *pending != id);
+                    finished.push((id, outcome));
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    finished.extend(
+                        self.pending
+                            .drain(..)
+                            .map(|id| (id, Err(ZellijError::ExecutorStopped.into()))),
+                    );
+                    break;
+                }
+            }
+        }
+        finished
+    }
+
+    /// Returns the number of operations queued or running.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn when_operations_are_queued_should_run_them_in_order_and_report_each() {
+        let mut executor = PaneExecutor::spawn();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut ids = Vec::new();
+        for name in ["api", "web", "db"] {
+            let order = Arc::clone(&order);
+            ids.push(executor.submit(move || {
+                thread::sleep(Duration::from_millis(5));
+                order.lock().unwrap().push(name);
+                if name == "web" {
+                    Err(ZellijError::EmptyCommand.into())
+                } else {
+                    Ok(Some(format!("terminal_{}", name)))
+                }
+            }));
+        }
+        assert_eq!(executor.pending(), 3);
+
+        let mut finished = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while finished.len() < 3 {
+            assert!(Instant::now() < deadline, "operations did not finish");
+            finished.extend(executor.poll());
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(*order.lock().unwrap(), vec!["api", "web", "db"]);
+        let reported: Vec<PaneJobId> = finished.iter().map(|(id, _)| *id).collect();
+        assert_eq!(reported, ids);
+        assert_eq!(
+            finished[0].1.as_ref().unwrap().as_deref(),
+            Some("terminal_api")
+        );
+        assert!(finished[1].1.is_err());
+        assert_eq!(executor.pending(), 0);
+    }
+}
@@ -0,0 +1,293 @@
+//! Fullscreen management for the web client.
+//!
+//! The web client (typically a phone) shows whatever pane is fullscreened in
+//! the session. `WebFocusManager` keeps track of that pane so that at most one
+//! pane is fullscreened: the current one is restored before another one is
+//! presented.
+//!
+//! Zellij can't be queried for the fullscreen state, so the manager assumes
+//! it is the only one toggling fullscreen.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use super::pipe::{self, PipeRequest};
+use super::process::{self, ensure_in_session};
+use crate::error::Result;
+
+/// Time given to Zellij to settle a focus change before toggling fullscreen.
+const SETTLE_DELAY: Duration = Duration::from_millis(100);
+
+/// A pane that can be presented to the web client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusTarget {
+    /// The main pane of the layout, to the right of the panel.
+    Main,
+    /// A floating pane, identified by its Zellij pane id.
+    Floating(String),
+}
+
+/// A single Zellij operation performed by the manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FocusStep {
+    /// Focus a pane.
+    Focus(FocusTarget),
+    /// Toggle fullscreen of the focused pane.
+    ToggleFullscreen,
+}
+
+/// Tracks which project's pane is fullscreened for the web client.
+#[derive(Debug, Default)]
+pub struct WebFocusManager {
+    /// The presented project and its pane.
+    presented: Option<(PathBuf, FocusTarget)>,
+}
+
+impl WebFocusManager {
+    /// Creates a manager with no pane presented.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the path of the project whose pane is presented, if any.
+    pub fn presented(&self) -> Option<&Path> {
+        self.presented.as_ref().map(|(path, _)| path.as_path())
+    }
+
+    /// Fullscreens a project's pane, restoring the one presented before.
+    ///
+    /// Focus stays on the presented pane.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project the pane belongs to
+    /// * `target` - The pane to present
+    ///
+    /// # Errors
+    ///
+    /// - `ZellijError::NotInSession` if not running inside a Zellij session
+    /// - `ZellijError::CommandFailed` if focusing or toggling fullscreen fails
+    pub fn present(&mut self, project_path: &Path, target: FocusTarget) -> Result<()> {
+        let steps = self.present_steps(&target);
+        if steps.is_empty() {
+            return Ok(());
+        }
+        ensure_in_session()?;
+
+        // Whatever happens below, the previous pane is no longer tracked
+        self.presented = None;
+        run_steps(&steps)?;
+        self.presented = Some((project_path.to_path_buf(), target));
+        Ok(())
+    }
+
+    /// Fullscreens a project's pane, or restores it if it is the one
+    /// presented.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project the pane belongs to
+    /// * `target` - The pane to toggle
+    ///
+    /// # Returns
+    ///
+    /// True if the pane is now fullscreened.
+    ///
+    /// # Errors
+    ///
+    /// - `ZellijError::NotInSession` if not running inside a Zellij session
+    /// - `ZellijError::CommandFailed` if focusing or toggling fullscreen fails
+    pub fn toggle(&mut self, project_path: &Path, target: FocusTarget) -> Result<bool> {
+        if self.is_presenting(&target) {
+            self.release()?;
+            Ok(false)
+        } else {
+            self.present(project_path, target)?;
+            Ok(true)
+        }
+    }
+
+    /// Returns whether a pane is the presented one.
+    fn is_presenting(&self, target: &FocusTarget) -> bool {
+        matches!(&self.presented, Some((_, current)) if current == target)
+    }
+
+    /// Restores the presented pane from fullscreen, if any.
+    ///
+    /// # Errors
+    ///
+    /// - `ZellijError::NotInSession` if not running inside a Zellij session
+    /// - `ZellijError::CommandFailed` if focusing or toggling fullscreen fails
+    pub fn release(&mut self) -> Result<()> {
+        let steps = self.release_steps();
+        if steps.is_empty() {
+            return Ok(());
+        }
+        ensure_in_session()?;
+
+        self.presented = None;
+        run_steps(&steps)
+    }
+
+    /// Stops tracking a project's pane, e.g. because it was closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - The project the pane belongs to
+    pub fn forget(&mut self, project_path: &Path) {
+        if self.presented() == Some(project_path) {
+            self.presented = None;
+        }
+    }
+
+    /// The steps restoring the presented pane from fullscreen.
+    fn release_steps(&self) -> Vec<FocusStep> {
+        match &self.presented {
+            Some((_, current)) => vec![
+                FocusStep::Focus(current.clone()),
+                FocusStep::ToggleFullscreen,
+            ],
+            None => Vec::new(),
+        }
+    }
+
+    /// The steps presenting a pane; empty if it's already presented.
+    fn present_steps(&self, target: &FocusTarget) -> Vec<FocusStep> {
+        if self.is_presenting(target) {
+            return Vec::new();
+        }
+        let mut steps = self.release_steps();
+        steps.push(FocusStep::Focus(target.clone()));
+        steps.push(FocusStep::ToggleFullscreen);
+        steps
+    }
+}
+
+/// Returns the plugin message equivalent to a focus step.
+fn plugin_request(step: &FocusStep) -> PipeRequest {
+    match step {
+        FocusStep::Focus(FocusTarget::Main) => PipeRequest::FocusMainPane,
+        FocusStep::Focus(FocusTarget::Floating(pane_id)) => PipeRequest::FocusPane {
+            pane_id: pane_id.clone(),
+        },
+        FocusStep::ToggleFullscreen => PipeRequest::ToggleFullscreen,
+    }
+}
+
+/// Runs focus steps against the gz-claude plugin, or the Zellij CLI when it
+/// is not loaded.
+fn run_steps(steps: &[FocusStep]) -> Result<()> {
+    for step in steps {
+        if let Some(result) = pipe::try_send(&plugin_request(step)) {
+            result?;
+            continue;
+        }
+        match step {
+            FocusStep::Focus(FocusTarget::Main) => super::commands::focus_main()?,
+            FocusStep::Focus(FocusTarget::Floating(pane_id)) => {
+                super::commands::focus_pane_checked(pane_id)?
+            }
+            FocusStep::ToggleFullscreen => {
+                std::thread::sleep(SETTLE_DELAY);
+                process::run_once(
+                    "zellij action toggle-fullscreen",
+                    Command::new("zellij").args(["action", "toggle-fullscreen"]),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn presenting(path: &str, target: FocusTarget) -> WebFocusManager {
+        WebFocusManager {
+            presented: Some((PathBuf::from(path), target)),
+        }
+    }
+
+    #[test]
+    fn when_nothing_is_presented_should_only_fullscreen_target() {
+        let manager = WebFocusManager::new();
+
+        let steps = manager.present_steps(&FocusTarget::Main);
+
+        assert_eq!(
+            steps,
+            vec![
+                FocusStep::Focus(FocusTarget::Main),
+                FocusStep::ToggleFullscreen
+            ]
+        );
+    }
+
+    #[test]
+    fn when_switching_pane_should_restore_current_one_first() {
+        let manager = presenting("/api", FocusTarget::Main);
+
+        let steps = manager.present_steps(&FocusTarget::Floating("terminal_2".to_string()));
+
+        assert_eq!(
+            steps,
+            vec![
+                FocusStep::Focus(FocusTarget::Main),
+                FocusStep::ToggleFullscreen,
+                FocusStep::Focus(FocusTarget::Floating("terminal_2".to_string())),
+                FocusStep::ToggleFullscreen,
+            ]
+        );
+    }
+
+    #[test]
+    fn when_presenting_current_pane_should_do_nothing() {
+        let manager = presenting("/api", FocusTarget::Floating("terminal_2".to_string()));
+
+        let steps = manager.present_steps(&FocusTarget::Floating("terminal_2".to_string()));
+
+        assert!(steps.is_empty());
+    }
+
+    #[test]
+    fn when_toggling_presented_pane_should_restore_it() {
+        use crate::zellij::process::{argv, with_runner, MockRunner};
+        use std::rc::Rc;
+        let runner = Rc::new(MockRunner::new().with_stdout(
+            &["zellij", "action", "list-clients"],
+            "CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND\n1 terminal_4 claude\n",
+        ));
+        let pane = FocusTarget::Floating("terminal_4".to_string());
+        let mut manager = presenting("/api", pane.clone());
+
+        let presented =
+            with_runner(runner.clone(), || manager.toggle(Path::new("/api"), pane)).unwrap();
+
+        assert!(!presented);
+        assert_eq!(manager.presented(), None);
+        assert_eq!(
+            runner.calls(),
+            vec![
+                argv(&["zellij", "action", "focus-pane-id", "terminal_4"]),
+                argv(&["zellij", "action", "list-clients"]),
+                argv(&["zellij", "action", "toggle-fullscreen"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn when_forgetting_presented_project_should_release_nothing() {
+        let mut manager = presenting("/api", FocusTarget::Main);
+
+        manager.forget(Path::new("/web"));
+        assert_eq!(manager.presented(), Some(Path::new("/api")));
+
+        manager.forget(Path::new("/api"));
+        assert_eq!(manager.presented(), None);
+        assert!(manager.release_steps().is_empty());
+    }
+}
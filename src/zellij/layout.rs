@@ -6,16 +6,33 @@
 //! @author waabox(waabox[at]gmail[dot]com)
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::config::LayoutConfig;
 use crate::error::Result;
+use crate::util::path::expand_tilde;
+
+/// The name the layout gives the main pane, which tells it from other panes
+/// in `zellij action dump-layout`.
+pub const MAIN_PANE_NAME: &str = "main";
+
+/// The main pane of `LAYOUT_TEMPLATE`, replaced by `render_layout`.
+const MAIN_PANE: &str = "        pane name=\"main\" focus=true command=\"bash\"\n";
+
+/// Start of the first line of a layout file written by gz-claude, followed by
+/// the checksum of the rest of the file.
+const GENERATED_MARKER: &str = "// Generated by gz-claude, checksum ";
+
+/// End of the first line of a layout file written by gz-claude.
+const GENERATED_NOTE: &str = ". Edit it and gz-claude stops regenerating it.";
 
 /// KDL layout template for the gz-claude Zellij workspace.
 ///
 /// This layout creates a four-row structure:
 /// - Row 1: Tab bar plugin (borderless, 1 row)
 /// - Row 2: gz-claude top bar with web URL (borderless, 1 row)
-/// - Row 3: Vertical split with gz-claude panel (40 cols) and focused shell
+/// - Row 3: Vertical split with gz-claude panel (40 cols) and focused shell,
+///   named `MAIN_PANE_NAME`
 /// - Row 4: Status bar plugin (borderless, 1 row)
 pub const LAYOUT_TEMPLATE: &str = r#"layout {
     pane size=1 borderless=true {
@@ -28,9 +45,9 @@ pub const LAYOUT_TEMPLATE: &str = r#"layout {
 
     pane split_direction="vertical" {
         pane size=40 command="gz-claude" {
-            args "panel"
+            args "panel" "--fast"
         }
-        pane focus=true command="bash"
+        pane name="main" focus=true command="bash"
     }
 
     pane size=1 borderless=true {
@@ -69,6 +86,22 @@ pub fn layout_path() -> PathBuf {
     layouts_dir().join("gz-claude.kdl")
 }
 
+/// Returns the path to the working set layout of a workspace.
+///
+/// The layout file is located at
+/// `~/.config/zellij/layouts/gz-claude-<workspace>.kdl`.
+///
+/// # Arguments
+///
+/// * `workspace_id` - The workspace id
+///
+/// # Returns
+///
+/// Returns a `PathBuf` pointing to the working set layout file.
+pub fn working_set_layout_path(workspace_id: &str) -> PathBuf {
+    layouts_dir().join(format!("gz-claude-{}.kdl", workspace_id))
+}
+
 /// Checks whether the gz-claude layout file exists.
 ///
 /// # Returns
@@ -88,15 +121,212 @@ pub fn layout_exists() -> bool {
     layout_path().exists()
 }
 
-/// Generates the gz-claude Zellij layout file.
+/// Renders the gz-claude layout with the configured main pane.
+///
+/// # Arguments
+///
+/// * `layout` - The `layout` configuration: the main pane's command and
+///   starting directory
+///
+/// # Returns
+///
+/// The KDL layout.
+pub fn render_layout(layout: &LayoutConfig) -> String {
+    let cwd = layout
+        .main_cwd
+        .as_deref()
+        .filter(|cwd| !cwd.trim().is_empty())
+        .map(|cwd| expand_tilde(cwd.trim()));
+    let pane = command_pane(
+        "        ",
+        &format!("pane name={} focus=true", kdl_string(MAIN_PANE_NAME)),
+        layout.main_command(),
+        None,
+        cwd.as_deref(),
+    );
+
+    LAYOUT_TEMPLATE.replace(MAIN_PANE, &pane)
+}
+
+/// A tab of a working set layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutTab {
+    /// The tab name, usually the project name.
+    pub name: String,
+    /// The directory the tab's pane starts in.
+    pub cwd: PathBuf,
+    /// The command of the tab's pane, with its arguments split on whitespace.
+    pub command: String,
+    /// Passed unsplit after the arguments of `command`, e.g. the script of
+    /// `sh -c`.
+    pub script: Option<String>,
+}
+
+/// Renders a working set layout with one tab per project.
+///
+/// Every tab keeps the tab bar and status bar of the gz-claude layout; the
+/// first tab is focused.
+///
+/// # Arguments
+///
+/// * `tabs` - The tabs, in order
+///
+/// # Returns
+///
+/// The KDL layout.
+pub fn render_working_set_layout(tabs: &[LayoutTab]) -> String {
+    let mut kdl = String::from(
+        "layout {\n    default_tab_template {\n        pane size=1 borderless=true {\n            plugin location=\"zellij:tab-bar\"\n        }\n        children\n        pane size=1 borderless=true {\n            plugin location=\"zellij:status-bar\"\n        }\n    }\n",
+    );
+    for (index, tab) in tabs.iter().enumerate() {
+        let focus = if index == 0 { " focus=true" } else { "" };
+        kdl.push_str(&format!(
+            "\n    tab name={}{} {{\n",
+            kdl_string(&tab.name),
+            focus
+        ));
+        kdl.push_str(&command_pane(
+            "        ",
+            "pane",
+            &tab.command,
+            tab.script.as_deref(),
+            Some(&tab.cwd),
+        ));
+        kdl.push_str("    }\n");
+    }
+    kdl.push_str("}\n");
+    kdl
+}
+
+/// Renders a pane running a command.
+///
+/// # Arguments
+///
+/// * `indent` - The indentation of the pane node
+/// * `node` - The pane node and its leading attributes, e.g. "pane focus=true"
+/// * `command` - The command, with its arguments split on whitespace
+/// * `script` - An argument passed unsplit after those of `command`
+/// * `cwd` - The directory the pane starts in, if any
+fn command_pane(
+    indent: &str,
+    node: &str,
+    command: &str,
+    script: Option<&str>,
+    cwd: Option<&Path>,
+) -> String {
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or("bash");
+    let args: Vec<String> = words.chain(script).map(kdl_string).collect();
+
+    let mut pane = format!("{}{} command={}", indent, node, kdl_string(program));
+    if let Some(cwd) = cwd {
+        pane.push_str(&format!(" cwd={}", kdl_string(&cwd.to_string_lossy())));
+    }
+    if args.is_empty() {
+        pane.push('\n');
+    } else {
+        pane.push_str(&format!(
+            " {{\n{}    args {}\n{}}}\n",
+            indent,
+            args.join(" "),
+            indent
+        ));
+    }
+    pane
+}
+
+/// Quotes a value as a KDL string.
+fn kdl_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Renders the gz-claude layout file: the layout preceded by a comment with
+/// its checksum, which tells a file gz-claude wrote from one edited by hand.
+///
+/// # Arguments
+///
+/// * `layout` - The `layout` configuration, rendered into the main pane
+///
+/// # Returns
+///
+/// The content of the layout file.
+pub fn layout_file(layout: &LayoutConfig) -> String {
+    let body = render_layout(layout);
+    format!(
+        "{}{:016x}{}\n{}",
+        GENERATED_MARKER,
+        checksum(&body),
+        GENERATED_NOTE,
+        body
+    )
+}
+
+/// How the installed layout file compares to the one gz-claude generates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutStatus {
+    /// No layout file is installed.
+    Missing,
+    /// The installed file is the generated one.
+    Current,
+    /// The installed file was written by gz-claude from another
+    /// configuration or template, and can be regenerated.
+    Outdated,
+    /// The installed file was edited by hand, and is kept.
+    Edited,
+}
+
+/// Compares an installed layout file to the one gz-claude generates.
+///
+/// A file is outdated when its checksum still matches its content, or, for
+/// files written before the checksum was added, when it's the generated
+/// layout without it. Anything else was edited.
+///
+/// # Arguments
+///
+/// * `installed` - The content of the installed file, None if missing
+/// * `layout` - The `layout` configuration
+pub fn layout_status(installed: Option<&str>, layout: &LayoutConfig) -> LayoutStatus {
+    let Some(installed) = installed else {
+        return LayoutStatus::Missing;
+    };
+    if installed == layout_file(layout) {
+        return LayoutStatus::Current;
+    }
+
+    let untouched = match installed.split_once('\n') {
+        Some((first, body)) if first.starts_with(GENERATED_MARKER) => first
+            .strip_prefix(GENERATED_MARKER)
+            .and_then(|rest| rest.strip_suffix(GENERATED_NOTE))
+            .is_some_and(|sum| sum == format!("{:016x}", checksum(body))),
+        _ => installed == render_layout(layout),
+    };
+    if untouched {
+        LayoutStatus::Outdated
+    } else {
+        LayoutStatus::Edited
+    }
+}
+
+/// Returns the FNV-1a hash of a text, which stays the same across builds.
+fn checksum(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Generates the gz-claude Zellij layout file unless it was edited by hand.
 ///
 /// Creates the layouts directory if it does not exist and writes the KDL layout
-/// template to `~/.config/zellij/layouts/gz-claude.kdl`.
+/// to `~/.config/zellij/layouts/gz-claude.kdl` when the file is missing or
+/// outdated. An edited file is left as it is.
+///
+/// # Arguments
+///
+/// * `layout` - The `layout` configuration, rendered into the main pane
 ///
 /// # Returns
 ///
-/// Returns `Ok(PathBuf)` containing the path to the generated layout file on success,
-/// or an error if directory creation or file writing fails.
+/// Returns `Ok(LayoutStatus)` with the status of the file before generating it.
 ///
 /// # Errors
 ///
@@ -107,26 +337,263 @@ pub fn layout_exists() -> bool {
 /// # Example
 ///
 /// ```no_run
-/// use gz_claude::zellij::generate_layout;
+/// use gz_claude::zellij::{generate_layout, LayoutStatus};
 ///
-/// match generate_layout() {
-///     Ok(path) => println!("Layout generated at: {}", path.display()),
+/// match generate_layout(&Default::default()) {
+///     Ok(LayoutStatus::Edited) => println!("Keeping the edited layout"),
+///     Ok(_) => println!("Layout generated"),
 ///     Err(e) => eprintln!("Failed to generate layout: {}", e),
 /// }
 /// ```
-pub fn generate_layout() -> Result<PathBuf> {
-    let dir = layouts_dir();
-    fs::create_dir_all(&dir)?;
+pub fn generate_layout(layout: &LayoutConfig) -> Result<LayoutStatus> {
+    let installed = fs::read_to_string(layout_path()).ok();
+    let status = layout_status(installed.as_deref(), layout);
+    if matches!(status, LayoutStatus::Missing | LayoutStatus::Outdated) {
+        write_layout(layout)?;
+    }
+    Ok(status)
+}
+
+/// Writes the gz-claude Zellij layout file, even over an edited one.
+///
+/// # Arguments
+///
+/// * `layout` - The `layout` configuration, rendered into the main pane
+///
+/// # Returns
+///
+/// Returns `Ok(PathBuf)` containing the path to the layout file.
+///
+/// # Errors
+///
+/// Returns an error if the layouts directory cannot be created or the layout
+/// file cannot be written.
+pub fn write_layout(layout: &LayoutConfig) -> Result<PathBuf> {
+    fs::create_dir_all(layouts_dir())?;
 
     let path = layout_path();
-    fs::write(&path, LAYOUT_TEMPLATE)?;
+    fs::write(&path, layout_file(layout))?;
+
+    Ok(path)
+}
+
+/// Generates the working set layout file of a workspace.
+///
+/// # Arguments
+///
+/// * `workspace_id` - The workspace id, used to name the layout file
+/// * `tabs` - The tabs of the working set, in order
+///
+/// # Returns
+///
+/// Returns `Ok(PathBuf)` containing the path to the generated layout file.
+///
+/// # Errors
+///
+/// Returns an error if the layouts directory cannot be created or the layout
+/// file cannot be written.
+pub fn generate_working_set_layout(workspace_id: &str, tabs: &[LayoutTab]) -> Result<PathBuf> {
+    fs::create_dir_all(layouts_dir())?;
+
+    let path = working_set_layout_path(workspace_id);
+    fs::write(&path, render_working_set_layout(tabs))?;
 
     Ok(path)
 }
 
+/// The focused panes of a tab of a dumped layout.
+#[derive(Default)]
+struct DumpedTab {
+    /// Whether the tab is the focused one.
+    focused: bool,
+    /// Whether the tab hides its floating panes.
+    hide_floating: bool,
+    /// The name of the focused tiled pane, None for a pane without name.
+    tiled: Option<Option<String>>,
+    /// The name of the focused floating pane, None for a pane without name.
+    floating: Option<Option<String>>,
+}
+
+/// Returns the name of the pane focused in the output of
+/// `zellij action dump-layout`.
+///
+/// The dump marks the focused tab, and the focused pane of each tab, with
+/// `focus=true`; the floating pane wins when the tab shows floating panes.
+/// Zellij doesn't dump pane ids, so the name is what tells panes apart.
+///
+/// # Arguments
+///
+/// * `dump` - The dumped layout
+///
+/// # Returns
+///
+/// The name, or None if no pane is focused or the focused pane has no name.
+pub fn focused_pane_name(dump: &str) -> Option<String> {
+    let mut tabs: Vec<DumpedTab> = Vec::new();
+    // The nodes whose children are being read, outermost first
+    let mut open: Vec<String> = Vec::new();
+    for line in dump.lines() {
+        let tokens = kdl_tokens(line);
+        let Some(node) = tokens.first() else {
+            continue;
+        };
+        if node == "}" {
+            open.pop();
+            continue;
+        }
+        let attribute = |key: &str| {
+            tokens
+                .iter()
+                .find_map(|token| token.strip_prefix(key)?.strip_prefix('='))
+                .map(kdl_unquote)
+        };
+        let focus = attribute("focus").as_deref() == Some("true");
+
+        if node == "tab" && open.len() == 1 {
+            tabs.push(DumpedTab {
+                focused: focus,
+                hide_floating: attribute("hide_floating_panes").as_deref() == Some("true"),
+                ..DumpedTab::default()
+            });
+        } else if node == "pane" && focus && open.get(1).is_some_and(|n| n == "tab") {
+            if let Some(tab) = tabs.last_mut() {
+                let name = attribute("name");
+                if open.iter().any(|n| n == "floating_panes") {
+                    tab.floating.get_or_insert(name);
+                } else {
+                    tab.tiled.get_or_insert(name);
+                }
+            }
+        }
+        if tokens.last().is_some_and(|token| token == "{") {
+            open.push(node.clone());
+        }
+    }
+
+    let tab = tabs.iter().find(|tab| tab.focused).or(tabs.first())?;
+    let floating = tab.floating.clone().filter(|_| !tab.hide_floating);
+    floating.or_else(|| tab.tiled.clone()).flatten()
+}
+
+/// Splits a line of KDL on whitespace outside of strings.
+fn kdl_tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut in_string = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                token.push(c);
+                token.extend(chars.next());
+            }
+            '"' => {
+                in_string = !in_string;
+                token.push(c);
+            }
+            c if c.is_whitespace() && !in_string => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Reads a KDL value, unquoting it if it's a string.
+fn kdl_unquote(value: &str) -> String {
+    let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+    let mut unquoted = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unquoted.extend(chars.next());
+        } else {
+            unquoted.push(c);
+        }
+    }
+    unquoted
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    /// Output of `zellij action dump-layout` for a session of the layout,
+    /// with a floating pane opened and hidden again.
+    const DUMP: &str = r#"layout {
+    cwd "/home/ana/work"
+    tab name="Tab #1" focus=true hide_floating_panes=true {
+        pane size=1 borderless=true {
+            plugin location="zellij:tab-bar"
+        }
+        pane command="gz-claude" size=1 borderless=true {
+            args "top-bar"
+            start_suspended true
+        }
+        pane split_direction="vertical" {
+            pane command="gz-claude" size=40 {
+                args "panel" "--fast"
+                start_suspended true
+            }
+            pane command="bash" name="main" focus=true size="80%"
+        }
+        pane size=1 borderless=true {
+            plugin location="zellij:status-bar"
+        }
+        floating_panes {
+            pane command="lazygit" name="Work/api · \"Git\"" focus=true {
+                height 31
+                width 143
+                x 35
+                y 8
+            }
+        }
+    }
+    tab name="logs" {
+        pane command="tail" focus=true {
+            args "-f" "app.log"
+        }
+    }
+    new_tab_template {
+        pane size=1 borderless=true {
+            plugin location="zellij:tab-bar"
+        }
+        pane focus=true name="template"
+        pane size=1 borderless=true {
+            plugin location="zellij:status-bar"
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn when_layout_is_dumped_should_find_the_focused_pane_by_name() {
+        assert_eq!(focused_pane_name(DUMP).as_deref(), Some(MAIN_PANE_NAME));
+
+        let floating = DUMP.replace("hide_floating_panes=true", "hide_floating_panes=false");
+        assert_eq!(
+            focused_pane_name(&floating).as_deref(),
+            Some("Work/api · \"Git\"")
+        );
+    }
+
+    #[test]
+    fn when_focused_pane_has_no_name_should_find_none() {
+        let other_tab = DUMP
+            .replace("tab name=\"Tab #1\" focus=true", "tab name=\"Tab #1\"")
+            .replace("tab name=\"logs\"", "tab name=\"logs\" focus=true");
+
+        assert_eq!(focused_pane_name(&other_tab), None);
+        assert_eq!(focused_pane_name("layout {\n}\n"), None);
+    }
     use tempfile::TempDir;
 
     #[test]
@@ -151,11 +618,131 @@ mod tests {
         assert!(LAYOUT_TEMPLATE.contains("plugin location=\"zellij:status-bar\""));
         assert!(LAYOUT_TEMPLATE.contains("split_direction=\"vertical\""));
         assert!(LAYOUT_TEMPLATE.contains("command=\"gz-claude\""));
-        assert!(LAYOUT_TEMPLATE.contains("args \"panel\""));
+        assert!(LAYOUT_TEMPLATE.contains("args \"panel\" \"--fast\""));
         assert!(LAYOUT_TEMPLATE.contains("args \"top-bar\""));
         assert!(LAYOUT_TEMPLATE.contains("command=\"bash\""));
         assert!(LAYOUT_TEMPLATE.contains("focus=true"));
         assert!(LAYOUT_TEMPLATE.contains("size=40"));
         assert!(LAYOUT_TEMPLATE.contains("borderless=true"));
+        assert!(LAYOUT_TEMPLATE.contains(MAIN_PANE));
+    }
+
+    #[test]
+    fn when_main_pane_is_not_configured_should_render_template() {
+        assert_eq!(render_layout(&LayoutConfig::default()), LAYOUT_TEMPLATE);
+    }
+
+    #[test]
+    fn when_main_pane_is_configured_should_render_command_args_and_cwd() {
+        let layout = LayoutConfig {
+            main_command: Some("claude --model \"opus\"".to_string()),
+            main_cwd: Some("/work/api".to_string()),
+        };
+
+        let rendered = render_layout(&layout);
+
+        assert!(rendered.contains(
+            "        pane name=\"main\" focus=true command=\"claude\" cwd=\"/work/api\" {\n            args \"--model\" \"\\\"opus\\\"\"\n        }\n"
+        ), "{}", rendered);
+        assert!(!rendered.contains("command=\"bash\""));
+        assert!(rendered.contains("args \"panel\" \"--fast\""));
+    }
+
+    #[test]
+    fn when_main_pane_is_a_shell_should_render_it_without_args() {
+        let layout = LayoutConfig {
+            main_command: Some("zsh".to_string()),
+            main_cwd: None,
+        };
+
+        assert!(render_layout(&layout)
+            .contains("        pane name=\"main\" focus=true command=\"zsh\"\n"));
+    }
+
+    #[test]
+    fn when_layout_file_is_generated_should_be_current() {
+        let layout = LayoutConfig::default();
+        let file = layout_file(&layout);
+
+        assert!(file.starts_with(GENERATED_MARKER));
+        assert!(file.ends_with(LAYOUT_TEMPLATE));
+        assert_eq!(layout_status(Some(&file), &layout), LayoutStatus::Current);
+        assert_eq!(layout_status(None, &layout), LayoutStatus::Missing);
+    }
+
+    #[test]
+    fn when_generated_for_another_config_should_be_outdated() {
+        let other = LayoutConfig {
+            main_command: Some("zsh".to_string()),
+            main_cwd: None,
+        };
+
+        let status = layout_status(Some(&layout_file(&other)), &LayoutConfig::default());
+
+        assert_eq!(status, LayoutStatus::Outdated);
+    }
+
+    #[test]
+    fn when_written_before_checksums_should_be_outdated_if_unchanged() {
+        let layout = LayoutConfig::default();
+
+        assert_eq!(
+            layout_status(Some(LAYOUT_TEMPLATE), &layout),
+            LayoutStatus::Outdated
+        );
+        assert_eq!(
+            layout_status(Some("layout {\n    pane\n}\n"), &layout),
+            LayoutStatus::Edited
+        );
+    }
+
+    #[test]
+    fn when_generated_file_is_edited_should_be_kept() {
+        let layout = LayoutConfig::default();
+        let edited = layout_file(&layout).replace("size=40", "size=60");
+
+        assert_eq!(layout_status(Some(&edited), &layout), LayoutStatus::Edited);
+    }
+
+    #[test]
+    fn when_rendering_working_set_should_open_one_tab_per_project() {
+        let tabs = vec![
+            LayoutTab {
+                name: "api".to_string(),
+                cwd: PathBuf::from("/work/api"),
+                command: "claude".to_string(),
+                script: None,
+            },
+            LayoutTab {
+                name: "web".to_string(),
+                cwd: PathBuf::from("/work/web"),
+                command: "zsh -l".to_string(),
+                script: None,
+            },
+            LayoutTab {
+                name: "api · Tests".to_string(),
+                cwd: PathBuf::from("/work/api"),
+                command: "sh -c".to_string(),
+                script: Some("cargo test \"$X\" /work/api".to_string()),
+            },
+        ];
+
+        let rendered = render_working_set_layout(&tabs);
+
+        assert!(rendered.starts_with("layout {\n    default_tab_template {\n"));
+        assert!(rendered.contains("plugin location=\"zellij:tab-bar\""));
+        assert!(rendered.contains("        children\n"));
+        assert!(rendered.contains(
+            "    tab name=\"api\" focus=true {\n        pane command=\"claude\" cwd=\"/work/api\"\n    }\n"
+        ), "{}", rendered);
+        assert!(rendered.contains(
+            "    tab name=\"web\" {\n        pane command=\"zsh\" cwd=\"/work/web\" {\n            args \"-l\"\n        }\n    }\n"
+        ), "{}", rendered);
+        assert!(
+            rendered.contains("            args \"-c\" \"cargo test \\\"$X\\\" /work/api\"\n"),
+            "{}",
+            rendered
+        );
+        assert!(rendered.ends_with("}\n"));
     }
 }
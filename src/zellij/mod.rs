@@ -9,11 +9,39 @@
 #![allow(dead_code, unused_imports)]
 
 mod check;
+mod clients;
 mod commands;
+mod executor;
+mod focus;
 mod layout;
+mod pipe;
+mod process;
+mod session;
 mod web;
 
-pub use check::{is_zellij_installed, zellij_version};
-pub use commands::{focus_main_pane, open_file_in_editor, open_pane, run_in_floating_pane, run_in_main_pane, start_zellij};
-pub use layout::{generate_layout, layout_exists, layout_path, layouts_dir, LAYOUT_TEMPLATE};
-pub use web::{clear_web_url, copy_to_clipboard, create_web_token, ensure_ssl_certs, get_local_ip, load_web_url, save_web_url, start_web_server, web_url};
+pub use check::{is_zellij_installed, list_sessions, zellij_version};
+pub use clients::FocusPoll;
+pub use commands::{
+    close_pane, configure_main_pane, dump_pane, focus_main_pane, focus_pane, focused_panes,
+    go_to_tab, interrupt_main_pane, layout_main_pane, move_pane_to_new_tab, open_file_in_editor,
+    open_file_in_editor_at, open_pane, own_pane, rename_focused_pane, rename_pane,
+    run_in_floating_pane, run_in_main_pane, run_in_new_tab, run_in_split_pane, start_zellij,
+    start_zellij_with_layout, toggle_pane_embedded,
+};
+pub use executor::{PaneExecutor, PaneJobId, PaneOutcome};
+pub use focus::{FocusTarget, WebFocusManager};
+pub use layout::{
+    generate_layout, generate_working_set_layout, layout_exists, layout_file, layout_path,
+    layout_status, layouts_dir, render_layout, render_working_set_layout, working_set_layout_path,
+    write_layout, LayoutStatus, LayoutTab, LAYOUT_TEMPLATE,
+};
+pub use pipe::{configure_plugin, plugin_active, PipeRequest, PipeResponse, PIPE_NAME};
+pub use process::{
+    configure_calls, ensure_in_session, has_runner, with_runner, CallPolicy, DryRunRunner,
+};
+pub use session::{attach_session, kill_session, plan_session, unique_session_name, SessionPlan};
+pub use web::{
+    clear_web_url, create_web_token, ensure_ssl_certs, find_web_port, get_local_ip, load_web_url,
+    plain_http_address, resolve_tls, save_web_pick, save_web_url, ssl_certs_exist,
+    start_web_server, take_web_pick, web_token, web_url, WebPort, WebTlsFiles,
+};
@@ -0,0 +1,334 @@
+//! Zellij plugin integration over `zellij pipe`.
+//!
+//! With `global.zellij.integration` set to `"plugin"`, pane operations are
+//! sent as JSON messages to the gz-claude plugin, which acts on panes by id
+//! instead of moving focus by direction and typing into the focused pane.
+//! When the plugin cannot be reached it is marked unavailable for the rest of
+//! the run and callers fall back to the CLI.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::process;
+use crate::error::{Result, ZellijError};
+use crate::util::path::expand_tilde;
+
+/// Name of the pipe the plugin listens on.
+pub const PIPE_NAME: &str = "gz-claude";
+
+/// How long a message may wait for the plugin's reply.
+const PIPE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Interval between checks of whether `zellij pipe` has exited.
+const PIPE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A message sent to the gz-claude plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PipeRequest {
+    /// Checks that the plugin is loaded and answering.
+    Ping,
+    /// Renames the main pane and runs a command in it.
+    RunInMainPane { name: String, command: String },
+    /// Opens a floating pane running `sh -c <command>`; replies with its id.
+    RunFloating { name: String, command: String },
+    /// Focuses the main pane.
+    FocusMainPane,
+    /// Focuses a pane by id (e.g. "terminal_3").
+    FocusPane { pane_id: String },
+    /// Toggles fullscreen of the focused pane.
+    ToggleFullscreen,
+    /// Sends Ctrl-C to the main pane.
+    InterruptMainPane,
+    /// Closes a pane by id.
+    ClosePane { pane_id: String },
+}
+
+impl PipeRequest {
+    /// Returns a short description of the operation, used in errors.
+    fn operation(&self) -> &'static str {
+        match self {
+            PipeRequest::Ping => "answer a ping",
+            PipeRequest::RunInMainPane { .. } => "run the command in the main pane",
+            PipeRequest::RunFloating { .. } => "open the floating pane",
+            PipeRequest::FocusMainPane => "focus the main pane",
+            PipeRequest::FocusPane { .. } => "focus the pane",
+            PipeRequest::ToggleFullscreen => "toggle fullscreen",
+            PipeRequest::InterruptMainPane => "interrupt the main pane",
+            PipeRequest::ClosePane { .. } => "close the pane",
+        }
+    }
+}
+
+/// The plugin's reply, written to the pipe's output.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct PipeResponse {
+    /// Whether the operation succeeded.
+    pub ok: bool,
+    /// Why the operation failed.
+    #[serde(default)]
+    pub error: Option<String>,
+    /// The id of a pane opened by the operation.
+    #[serde(default)]
+    pub pane_id: Option<String>,
+}
+
+/// Whether the plugin is used, and what is known about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PluginState {
+    /// The CLI integration is configured.
+    Disabled,
+    /// Configured but not contacted yet.
+    Unprobed(String),
+    /// Answered a ping.
+    Available(String),
+    /// Could not be reached; the CLI is used for the rest of the run.
+    Unavailable,
+}
+
+static PLUGIN: Mutex<PluginState> = Mutex::new(PluginState::Disabled);
+
+/// Enables or disables the plugin integration.
+///
+/// The plugin is contacted lazily, on the first pane operation.
+///
+/// # Arguments
+///
+/// * `plugin` - The plugin location for `zellij pipe --plugin`, or None to use the CLI
+pub fn configure_plugin(plugin: Option<&str>) {
+    let state = match plugin {
+        Some(url) => PluginState::Unprobed(resolve_plugin_url(url)),
+        None => PluginState::Disabled,
+    };
+    *PLUGIN.lock().unwrap_or_else(|e| e.into_inner()) = state;
+}
+
+/// Expands a leading `~` in a `file:` plugin location.
+///
+/// Other locations (`zellij:`, `https:`, aliases) are returned unchanged.
+pub fn resolve_plugin_url(url: &str) -> String {
+    match url.strip_prefix("file:") {
+        Some(path) if path.starts_with('~') => {
+            format!("file:{}", expand_tilde(path).display())
+        }
+        _ => url.to_string(),
+    }
+}
+
+/// Returns true if pane operations currently go through the plugin.
+pub fn plugin_active() -> bool {
+    matches!(
+        *PLUGIN.lock().unwrap_or_else(|e| e.into_inner()),
+        PluginState::Available(_)
+    )
+}
+
+/// Sends a message to the plugin if it is enabled and reachable.
+///
+/// Pings the plugin on first use. Transport failures (no `zellij`, plugin not
+/// found, no reply in time) mark the plugin unavailable. In a dry run, the
+/// message is only recorded and reported as sent.
+///
+/// # Returns
+///
+/// None if the caller must fall back to the CLI, otherwise the plugin's reply.
+///
+/// # Errors
+///
+/// - `ZellijError::PluginFailed` if the plugin reports that the operation failed
+pub fn try_send(request: &PipeRequest) -> Option<Result<PipeResponse>> {
+    let mut state = PLUGIN.lock().unwrap_or_else(|e| e.into_inner());
+
+    if process::is_dry_run() {
+        // Record the message without probing, so the plugin state is kept
+        return match &*state {
+            PluginState::Disabled | PluginState::Unavailable => None,
+            PluginState::Available(url) | PluginState::Unprobed(url) => {
+                let _ = process::spawn(Command::new("zellij").args(pipe_args(url, request)));
+                Some(Ok(PipeResponse {
+                    ok: true,
+                    ..Default::default()
+                }))
+            }
+        };
+    }
+
+    let url = match &*state {
+        PluginState::Disabled | PluginState::Unavailable => return None,
+        PluginState::Available(url) => url.clone(),
+        PluginState::Unprobed(url) => {
+            let url = url.clone();
+            match send(&url, &PipeRequest::Ping) {
+                Some(response) if response.ok => {
+                    *state = PluginState::Available(url.clone());
+                    url
+                }
+                _ => {
+                    *state = PluginState::Unavailable;
+                    return None;
+                }
+            }
+        }
+    };
+
+    let Some(response) = send(&url, request) else {
+        *state = PluginState::Unavailable;
+        return None;
+    };
+    if response.ok {
+        Some(Ok(response))
+    } else {
+        Some(Err(ZellijError::PluginFailed {
+            operation: request.operation().to_string(),
+            message: response
+                .error
+                .unwrap_or_else(|| "no reason given".to_string()),
+        }
+        .into()))
+    }
+}
+
+/// Builds the arguments of the `zellij` invocation that sends a message.
+///
+/// # Arguments
+///
+/// * `plugin` - The plugin location
+/// * `request` - The message to send
+pub fn pipe_args(plugin: &str, request: &PipeRequest) -> Vec<String> {
+    let payload = serde_json::to_string(request).unwrap_or_default();
+    vec![
+        "pipe".to_string(),
+        "--plugin".to_string(),
+        plugin.to_string(),
+        "--name".to_string(),
+        PIPE_NAME.to_string(),
+        "--".to_string(),
+        payload,
+    ]
+}
+
+/// Sends a message and waits for the plugin's reply.
+///
+/// Returns None on any transport failure: `zellij pipe` cannot run, exits
+/// with an error, times out or replies with something that is not a response.
+fn send(plugin: &str, request: &PipeRequest) -> Option<PipeResponse> {
+    let mut child = process::spawn(
+        Command::new("zellij")
+            .args(pipe_args(plugin, request))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null()),
+    )
+    .ok()?;
+
+    let deadline = Instant::now() + PIPE_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(PIPE_POLL_INTERVAL),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+    if !status.success() {
+        return None;
+    }
+
+    let mut output = String::new();
+    child.stdout.take()?.read_to_string(&mut output).ok()?;
+    parse_response(&output)
+}
+
+/// Parses the last non-empty line of the pipe's output as a response.
+fn parse_response(output: &str) -> Option<PipeResponse> {
+    let line = output.lines().map(str::trim).rfind(|l| !l.is_empty())?;
+    serde_json::from_str(line).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_serializing_requests_should_tag_them_with_op() {
+        let run = PipeRequest::RunInMainPane {
+            name: "api · Claude".to_string(),
+            command: "claude".to_string(),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&PipeRequest::Ping).unwrap(),
+            r#"{"op":"ping"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&run).unwrap(),
+            r#"{"op":"run_in_main_pane","name":"api · Claude","command":"claude"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&PipeRequest::ClosePane {
+                pane_id: "terminal_3".to_string()
+            })
+            .unwrap(),
+            r#"{"op":"close_pane","pane_id":"terminal_3"}"#
+        );
+    }
+
+    #[test]
+    fn when_building_pipe_args_should_target_plugin_and_pipe_name() {
+        let args = pipe_args("file:/p/gz-claude.wasm", &PipeRequest::FocusMainPane);
+
+        assert_eq!(
+            args,
+            vec![
+                "pipe",
+                "--plugin",
+                "file:/p/gz-claude.wasm",
+                "--name",
+                "gz-claude",
+                "--",
+                r#"{"op":"focus_main_pane"}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn when_parsing_response_should_use_last_line() {
+        let response =
+            parse_response("loading\n{\"ok\":true,\"pane_id\":\"terminal_4\"}\n").unwrap();
+
+        assert!(response.ok);
+        assert_eq!(response.pane_id.as_deref(), Some("terminal_4"));
+        assert_eq!(parse_response(""), None);
+        assert_eq!(parse_response("not json"), None);
+    }
+
+    #[test]
+    fn when_resolving_plugin_url_should_expand_home_only_for_files() {
+        let home = dirs::home_dir().unwrap();
+
+        assert_eq!(
+            resolve_plugin_url("file:~/plugins/gz-claude.wasm"),
+            format!("file:{}", home.join("plugins/gz-claude.wasm").display())
+        );
+        assert_eq!(resolve_plugin_url("file:/p/x.wasm"), "file:/p/x.wasm");
+        assert_eq!(resolve_plugin_url("gz-claude"), "gz-claude");
+    }
+
+    #[test]
+    fn when_plugin_is_disabled_should_fall_back_to_cli() {
+        configure_plugin(None);
+
+        assert!(try_send(&PipeRequest::Ping).is_none());
+        assert!(!plugin_active());
+    }
+}
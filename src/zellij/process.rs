@@ -0,0 +1,726 @@
+//! Process helpers for invoking the Zellij CLI.
+//!
+//! Maps spawn failures and non-zero exits to `ZellijError` variants so callers
+//! can tell a missing binary from a missing session or a failed action.
+//!
+//! Every external program of the Zellij integration is run through a
+//! `CommandRunner`. The `SystemRunner` runs them; tests install a
+//! `MockRunner` with `with_runner` to assert the exact argv produced, and dry
+//! runs install a `DryRunRunner` to show it.
+//!
+//! Captured runs are killed once they exceed the configured timeout, so a
+//! busy Zellij server can't freeze the panel, and `run` retries them a
+//! limited number of times.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::cell::RefCell;
+use std::io::{self, Read};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{GzClaudeError, Result, ZellijError};
+
+/// Environment variable set by Zellij inside its sessions.
+const SESSION_ENV: &str = "ZELLIJ";
+
+/// Time between two checks of whether a command exited.
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Pause before the first retry; each further retry waits one more step.
+const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// How captured commands are bounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallPolicy {
+    /// Time a captured command may run before it's killed; None waits forever.
+    pub timeout: Option<Duration>,
+    /// Times a command that timed out is run again by `run`.
+    pub retries: u32,
+}
+
+impl CallPolicy {
+    /// The policy used until `configure_calls` is called.
+    pub const DEFAULT: Self = Self {
+        timeout: Some(Duration::from_secs(5)),
+        retries: 1,
+    };
+}
+
+impl Default for CallPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static POLICY: Mutex<CallPolicy> = Mutex::new(CallPolicy::DEFAULT);
+
+/// Sets the timeout and retries of the commands run from now on.
+///
+/// # Arguments
+///
+/// * `policy` - The timeout and retries to apply
+pub fn configure_calls(policy: CallPolicy) {
+    *POLICY.lock().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+/// Returns the timeout and retries currently applied.
+fn policy() -> CallPolicy {
+    *POLICY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Runs the external programs of the Zellij integration.
+pub trait CommandRunner {
+    /// Runs a command to completion, capturing its output.
+    fn output(&self, cmd: &mut Command) -> io::Result<Output>;
+
+    /// Runs a command to completion with the inherited terminal.
+    fn status(&self, cmd: &mut Command) -> io::Result<ExitStatus>;
+
+    /// Starts a command without waiting for it.
+    fn spawn(&self, cmd: &mut Command) -> io::Result<Child>;
+
+    /// Returns whether the process runs inside a Zellij session.
+    fn in_session(&self) -> bool {
+        std::env::var_os(SESSION_ENV).is_some()
+    }
+
+    /// Returns whether commands are only recorded, not run.
+    fn dry_run(&self) -> bool {
+        false
+    }
+}
+
+/// Runs commands as real processes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn output(&self, cmd: &mut Command) -> io::Result<Output> {
+        output_within(cmd, policy().timeout)
+    }
+
+    fn status(&self, cmd: &mut Command) -> io::Result<ExitStatus> {
+        cmd.status()
+    }
+
+    fn spawn(&self, cmd: &mut Command) -> io::Result<Child> {
+        cmd.spawn()
+    }
+}
+
+/// Runs a command to completion, capturing its output, killing it if it
+/// runs longer than `timeout`.
+///
+/// Stdin is closed, as with `Command::output`.
+///
+/// # Arguments
+///
+/// * `cmd` - The command to run
+/// * `timeout` - How long to wait, or None to wait until it exits
+///
+/// # Errors
+///
+/// Returns an `io::ErrorKind::TimedOut` error if the command was killed.
+pub fn output_within(cmd: &mut Command, timeout: Option<Duration>) -> io::Result<Output> {
+    let Some(timeout) = timeout else {
+        return cmd.output();
+    };
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain the pipes while waiting, so a chatty command can't block on them
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("no answer within {} ms", timeout.as_millis()),
+            ));
+        }
+        thread::sleep(EXIT_POLL_INTERVAL);
+    };
+
+    let collect = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    };
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+/// Reads a pipe to its end on a thread of its own.
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = pipe.read_to_end(&mut bytes);
+        bytes
+    })
+}
+
+/// A runner that records the command line of each command instead of
+/// running it.
+///
+/// Commands succeed with empty output; commands that would be started in
+/// the background fail, since there is no process to hand back. The runner
+/// reports being inside a Zellij session, so invocations can be previewed
+/// from anywhere.
+#[derive(Debug, Default)]
+pub struct DryRunRunner {
+    invocations: RefCell<Vec<String>>,
+}
+
+impl DryRunRunner {
+    /// Creates a runner that recorded nothing yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the command lines recorded so far, in order.
+    pub fn invocations(&self) -> Vec<String> {
+        self.invocations.borrow().clone()
+    }
+
+    fn record(&self, cmd: &Command) {
+        self.invocations.borrow_mut().push(command_line(cmd));
+    }
+}
+
+impl CommandRunner for DryRunRunner {
+    fn output(&self, cmd: &mut Command) -> io::Result<Output> {
+        self.record(cmd);
+        Ok(Output {
+            status: exit_status(0),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        })
+    }
+
+    fn status(&self, cmd: &mut Command) -> io::Result<ExitStatus> {
+        self.record(cmd);
+        Ok(exit_status(0))
+    }
+
+    fn spawn(&self, cmd: &mut Command) -> io::Result<Child> {
+        self.record(cmd);
+        Err(io::Error::other("not started: dry run"))
+    }
+
+    fn in_session(&self) -> bool {
+        true
+    }
+
+    fn dry_run(&self) -> bool {
+        true
+    }
+}
+
+/// Formats a command as it would be typed in a shell, arguments quoted
+/// when needed, followed by its working directory if set.
+///
+/// # Arguments
+///
+/// * `cmd` - The command to format
+pub fn command_line(cmd: &Command) -> String {
+    let mut parts = vec![shell_word(&cmd.get_program().to_string_lossy())];
+    parts.extend(cmd.get_args().map(|a| shell_word(&a.to_string_lossy())));
+    let mut line = parts.join(" ");
+    if let Some(dir) = cmd.get_current_dir() {
+        line.push_str(&format!("  (in {})", dir.display()));
+    }
+    line
+}
+
+/// Quotes a word for a shell unless it only has safe characters.
+fn shell_word(word: &str) -> String {
+    let safe = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if safe {
+        word.to_string()
+    } else {
+        crate::util::shell::quote(word)
+    }
+}
+
+thread_local! {
+    static RUNNER: RefCell<Option<Rc<dyn CommandRunner>>> = const { RefCell::new(None) };
+}
+
+/// Runs a closure with commands of the current thread going to `runner`.
+///
+/// The previous runner is restored afterwards, even if the closure panics.
+///
+/// # Arguments
+///
+/// * `runner` - The runner used instead of the `SystemRunner`
+/// * `f` - The closure to run
+///
+/// # Returns
+///
+/// The closure's result.
+pub fn with_runner<R>(runner: Rc<dyn CommandRunner>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Rc<dyn CommandRunner>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            RUNNER.with(|r| *r.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(RUNNER.with(|r| r.borrow_mut().replace(runner)));
+    f()
+}
+
+/// Returns whether commands of the current thread go to a runner installed
+/// with `with_runner`, e.g. in a dry run, rather than the `SystemRunner`.
+pub fn has_runner() -> bool {
+    RUNNER.with(|r| r.borrow().is_some())
+}
+
+/// Returns the runner of the current thread.
+fn runner() -> Rc<dyn CommandRunner> {
+    RUNNER
+        .with(|r| r.borrow().clone())
+        .unwrap_or_else(|| Rc::new(SystemRunner))
+}
+
+/// Runs a command to completion through the current runner, capturing its output.
+pub fn output(cmd: &mut Command) -> io::Result<Output> {
+    runner().output(cmd)
+}
+
+/// Runs a command through the current runner with the inherited terminal.
+pub fn status(cmd: &mut Command) -> io::Result<ExitStatus> {
+    runner().status(cmd)
+}
+
+/// Starts a command through the current runner without waiting for it.
+pub fn spawn(cmd: &mut Command) -> io::Result<Child> {
+    runner().spawn(cmd)
+}
+
+/// Returns whether commands of the current thread are only recorded.
+pub fn is_dry_run() -> bool {
+    runner().dry_run()
+}
+
+/// Checks that the process runs inside a Zellij session.
+///
+/// # Errors
+///
+/// - `ZellijError::NotInSession` if the `ZELLIJ` environment variable is not set
+pub fn ensure_in_session() -> Result<()> {
+    if runner().in_session() {
+        Ok(())
+    } else {
+        Err(ZellijError::NotInSession.into())
+    }
+}
+
+/// Runs a command to completion, capturing its output.
+///
+/// A run that times out is retried as configured by `configure_calls`,
+/// waiting a little longer before each retry. Commands that must not run
+/// twice, such as those typing into a pane, use `run_once` instead.
+///
+/// # Arguments
+///
+/// * `action` - Human-readable name of the operation, used in errors
+/// * `cmd` - The command to run
+///
+/// # Returns
+///
+/// The captured output of a successful run.
+///
+/// # Errors
+///
+/// - `ZellijError::NotInstalled` if the program cannot be found
+/// - `ZellijError::TimedOut` if the last attempt timed out
+/// - `ZellijError::CommandFailed` if it exits with a non-zero status
+pub fn run(action: &str, cmd: &mut Command) -> Result<Output> {
+    let retries = policy().retries;
+    let mut attempt = 0;
+    loop {
+        match run_once(action, cmd) {
+            Err(GzClaudeError::Zellij(ZellijError::TimedOut { .. })) if attempt < retries => {
+                attempt += 1;
+                if !is_dry_run() {
+                    thread::sleep(RETRY_DELAY * attempt);
+                }
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Runs a command to completion once, capturing its output.
+///
+/// # Arguments
+///
+/// * `action` - Human-readable name of the operation, used in errors
+/// * `cmd` - The command to run
+///
+/// # Returns
+///
+/// The captured output of a successful run.
+///
+/// # Errors
+///
+/// - `ZellijError::NotInstalled` if the program cannot be found
+/// - `ZellijError::TimedOut` if it was killed for running too long
+/// - `ZellijError::CommandFailed` if it exits with a non-zero status
+pub fn run_once(action: &str, cmd: &mut Command) -> Result<Output> {
+    let output = output(cmd).map_err(|e| match e.kind() {
+        io::ErrorKind::TimedOut => ZellijError::TimedOut {
+            action: action.to_string(),
+            millis: policy().timeout.map_or(0, |t| t.as_millis() as u64),
+        }
+        .into(),
+        _ => spawn_error(e),
+    })?;
+
+    if !output.status.success() {
+        return Err(command_failed(action, output.status, &output.stderr).into());
+    }
+
+    Ok(output)
+}
+
+/// Converts a spawn failure into an error.
+///
+/// A missing binary becomes `ZellijError::NotInstalled`; other failures are
+/// reported as I/O errors.
+pub fn spawn_error(error: io::Error) -> GzClaudeError {
+    if error.kind() == io::ErrorKind::NotFound {
+        ZellijError::NotInstalled.into()
+    } else {
+        error.into()
+    }
+}
+
+/// Builds a `CommandFailed` error from an exit status and captured stderr.
+///
+/// Only the last non-empty stderr line is kept, which is where the CLI
+/// reports the actual cause.
+///
+/// # Arguments
+///
+/// * `action` - Human-readable name of the operation
+/// * `status` - The exit status of the process
+/// * `stderr` - The raw captured stderr
+pub fn command_failed(action: &str, status: ExitStatus, stderr: &[u8]) -> ZellijError {
+    let stderr = String::from_utf8_lossy(stderr);
+    let last_line = stderr
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or("");
+
+    ZellijError::CommandFailed {
+        action: action.to_string(),
+        status: status.to_string(),
+        stderr: last_line.to_string(),
+    }
+}
+
+/// A runner that records the argv of each command instead of running it.
+///
+/// Commands succeed with empty output unless a reply was registered for a
+/// prefix of their argv.
+#[cfg(test)]
+#[derive(Debug)]
+pub struct MockRunner {
+    calls: RefCell<Vec<Vec<String>>>,
+    replies: Vec<(Vec<String>, MockReply)>,
+    in_session: bool,
+}
+
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+struct MockReply {
+    code: i32,
+    stdout: String,
+    stderr: String,
+    timed_out: bool,
+}
+
+#[cfg(test)]
+impl MockRunner {
+    /// Creates a runner that reports being inside a Zellij session.
+    pub fn new() -> Self {
+        Self {
+            calls: RefCell::new(Vec::new()),
+            replies: Vec::new(),
+            in_session: true,
+        }
+    }
+
+    /// Makes the runner report being outside of a Zellij session.
+    pub fn outside_session(mut self) -> Self {
+        self.in_session = false;
+        self
+    }
+
+    /// Makes commands starting with `prefix` succeed with `stdout`.
+    pub fn with_stdout(mut self, prefix: &[&str], stdout: &str) -> Self {
+        let reply = MockReply {
+            stdout: stdout.to_string(),
+            ..Default::default()
+        };
+        self.replies.push((argv(prefix), reply));
+        self
+    }
+
+    /// Makes commands starting with `prefix` exit with `code` and `stderr`.
+    pub fn with_failure(mut self, prefix: &[&str], code: i32, stderr: &str) -> Self {
+        let reply = MockReply {
+            code,
+            stderr: stderr.to_string(),
+            ..Default::default()
+        };
+        self.replies.push((argv(prefix), reply));
+        self
+    }
+
+    /// Makes captured runs of commands starting with `prefix` time out.
+    pub fn with_timeout(mut self, prefix: &[&str]) -> Self {
+        let reply = MockReply {
+            timed_out: true,
+            ..Default::default()
+        };
+        self.replies.push((argv(prefix), reply));
+        self
+    }
+
+    /// Returns the argv of every command run so far, program first.
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.calls.borrow().clone()
+    }
+
+    fn record(&self, cmd: &Command) -> MockReply {
+        let mut call = vec![cmd.get_program().to_string_lossy().to_string()];
+        call.extend(cmd.get_args().map(|a| a.to_string_lossy().to_string()));
+        let reply = self
+            .replies
+            .iter()
+            .find(|(prefix, _)| call.starts_with(prefix))
+            .map(|(_, reply)| reply.clone())
+            .unwrap_or_default();
+        self.calls.borrow_mut().push(call);
+        reply
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for MockRunner {
+    fn output(&self, cmd: &mut Command) -> io::Result<Output> {
+        let reply = self.record(cmd);
+        if reply.timed_out {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+        }
+        Ok(Output {
+            status: exit_status(reply.code),
+            stdout: reply.stdout.into_bytes(),
+            stderr: reply.stderr.into_bytes(),
+        })
+    }
+
+    fn status(&self, cmd: &mut Command) -> io::Result<ExitStatus> {
+        Ok(exit_status(self.record(cmd).code))
+    }
+
+    fn spawn(&self, cmd: &mut Command) -> io::Result<Child> {
+        let reply = self.record(cmd);
+        Command::new("sh")
+            .args(["-c", &format!("exit {}", reply.code)])
+            .spawn()
+    }
+
+    fn in_session(&self) -> bool {
+        self.in_session
+    }
+}
+
+/// Converts string slices into an owned argv.
+#[cfg(test)]
+pub fn argv(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw(code << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_dry_running_should_record_quoted_command_lines() {
+        let runner = Rc::new(DryRunRunner::new());
+
+        let result = with_runner(runner.clone(), || {
+            assert!(is_dry_run());
+            run(
+                "zellij action write-chars",
+                Command::new("zellij")
+                    .args(["action", "write-chars", "claude 'fix it'\n"])
+                    .current_dir("/work/api"),
+            )
+        });
+
+        assert!(result.is_ok());
+        assert!(!is_dry_run());
+        assert_eq!(
+            runner.invocations(),
+            vec!["zellij action write-chars 'claude '\\''fix it'\\''\n'  (in /work/api)"]
+        );
+    }
+
+    #[test]
+    fn when_command_fails_should_capture_last_stderr_line() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo first >&2; echo 'real cause' >&2; exit 3"]);
+
+        let err = run("test action", &mut cmd).unwrap_err();
+
+        match err {
+            GzClaudeError::Zellij(ZellijError::CommandFailed { action, stderr, .. }) => {
+                assert_eq!(action, "test action");
+                assert_eq!(stderr, "real cause");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn when_command_runs_too_long_should_kill_it() {
+        let started = Instant::now();
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo partial; sleep 5"]);
+
+        let err = output_within(&mut cmd, Some(Duration::from_millis(100))).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
+    #[test]
+    fn when_command_finishes_in_time_should_capture_output() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo out; echo err >&2"]);
+
+        let output = output_within(&mut cmd, Some(Duration::from_secs(5))).unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+    }
+
+    #[test]
+    fn when_command_times_out_should_retry_then_report_it() {
+        let runner = Rc::new(MockRunner::new().with_timeout(&["zellij", "action"]));
+
+        let err = with_runner(runner.clone(), || {
+            run(
+                "zellij action close-pane",
+                Command::new("zellij").args(["action", "close-pane"]),
+            )
+        })
+        .unwrap_err();
+
+        let retries = policy().retries as usize;
+        assert_eq!(runner.calls().len(), retries + 1);
+        assert!(
+            matches!(&err, GzClaudeError::Zellij(ZellijError::TimedOut { action, .. }) if action == "zellij action close-pane"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn when_running_once_should_not_retry() {
+        let runner = Rc::new(MockRunner::new().with_timeout(&["zellij", "run"]));
+
+        let result = with_runner(runner.clone(), || {
+            run_once("zellij run", Command::new("zellij").arg("run"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(runner.calls().len(), 1);
+    }
+
+    #[test]
+    fn when_program_is_missing_should_report_not_installed() {
+        let mut cmd = Command::new("gz-claude-nonexistent-binary");
+
+        let err = run("missing", &mut cmd).unwrap_err();
+
+        assert!(matches!(
+            err,
+            GzClaudeError::Zellij(ZellijError::NotInstalled)
+        ));
+    }
+
+    #[test]
+    fn when_runner_is_installed_should_record_commands_and_restore_it() {
+        let runner = Rc::new(MockRunner::new().with_failure(&["zellij", "bad"], 2, "nope"));
+
+        let result = with_runner(runner.clone(), || {
+            run("first", Command::new("zellij").args(["ok", "--flag"])).unwrap();
+            run("second", Command::new("zellij").arg("bad"))
+        });
+
+        assert_eq!(
+            runner.calls(),
+            vec![argv(&["zellij", "ok", "--flag"]), argv(&["zellij", "bad"])]
+        );
+        assert!(matches!(
+            result,
+            Err(GzClaudeError::Zellij(ZellijError::CommandFailed { stderr, .. })) if stderr == "nope"
+        ));
+        // Outside the closure commands run for real again
+        assert!(run("echo", Command::new("sh").args(["-c", "true"])).is_ok());
+        assert_eq!(runner.calls().len(), 2);
+    }
+
+    #[test]
+    fn when_runner_is_outside_session_should_report_not_in_session() {
+        let runner = Rc::new(MockRunner::new().outside_session());
+
+        let result = with_runner(runner, ensure_in_session);
+
+        assert!(matches!(
+            result,
+            Err(GzClaudeError::Zellij(ZellijError::NotInSession))
+        ));
+    }
+}
@@ -0,0 +1,188 @@
+//! Starting, joining and replacing the gz-claude Zellij session.
+//!
+//! `gz-claude` runs in a named Zellij session. When that session already
+//! exists, the session policy decides whether to attach to it, restart it,
+//! or start another one with a unique name.
+//!
+//! @author waabox(waabox[at]gmail[dot]com)
+
+use std::process::Command;
+
+use super::process;
+use crate::config::SessionPolicy;
+use crate::error::Result;
+
+/// What to do to get the user into a gz-claude session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionPlan {
+    /// Start a new session with the given name.
+    Create(String),
+    /// Attach to the existing session with the given name.
+    Attach(String),
+    /// Kill the existing session with the given name, then start it again.
+    Restart(String),
+}
+
+/// Decides how to start the session given the existing sessions.
+///
+/// # Arguments
+///
+/// * `policy` - The session policy; `Ask` is treated as `Attach`, callers
+///   asking the user resolve it first
+/// * `name` - The configured session name
+/// * `existing` - The names of the existing Zellij sessions
+///
+/// # Returns
+///
+/// `Create` when no session has the name, otherwise the policy's plan.
+pub fn plan_session(policy: SessionPolicy, name: &str, existing: &[String]) -> SessionPlan {
+    if !existing.iter().any(|s| s == name) {
+        return SessionPlan::Create(name.to_string());
+    }
+    match policy {
+        SessionPolicy::Ask | SessionPolicy::Attach => SessionPlan::Attach(name.to_string()),
+        SessionPolicy::Restart => SessionPlan::Restart(name.to_string()),
+        SessionPolicy::New => SessionPlan::Create(unique_session_name(name, existing)),
+    }
+}
+
+/// Returns the first of `name`, `name-2`, `name-3`, ... not used by a session.
+pub fn unique_session_name(name: &str, existing: &[String]) -> String {
+    if !existing.iter().any(|s| s == name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| !existing.contains(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Attaches the terminal to an existing Zellij session.
+///
+/// Exited sessions are resurrected by Zellij.
+///
+/// # Errors
+///
+/// - `ZellijError::NotInstalled` if Zellij is not installed
+/// - `ZellijError::CommandFailed` if Zellij exits with an error
+pub fn attach_session(name: &str) -> Result<()> {
+    // Zellij takes over the terminal, so stderr is not captured here
+    let status = process::status(Command::new("zellij").args(["attach", name]))
+        .map_err(process::spawn_error)?;
+
+    if !status.success() {
+        return Err(process::command_failed("zellij attach", status, &[]).into());
+    }
+    Ok(())
+}
+
+/// Kills a Zellij session and deletes it, so its name can be reused.
+///
+/// # Errors
+///
+/// - `ZellijError::NotInstalled` if Zellij is not installed
+/// - `ZellijError::CommandFailed` if the session cannot be deleted
+pub fn kill_session(name: &str) -> Result<()> {
+    // An exited session can't be killed, only deleted
+    process::output(Command::new("zellij").args(["kill-session", name]))
+        .map_err(process::spawn_error)?;
+
+    let output = process::output(Command::new("zellij").args(["delete-session", name]))
+        .map_err(process::spawn_error)?;
+    if !output.status.success() {
+        return Err(process::command_failed(
+            "zellij delete-session",
+            output.status,
+            &output.stderr,
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    use crate::zellij::process::{argv, with_runner, MockRunner};
+
+    fn sessions(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn when_session_does_not_exist_should_create_it() {
+        let existing = sessions(&["other"]);
+
+        for policy in [
+            SessionPolicy::Ask,
+            SessionPolicy::Attach,
+            SessionPolicy::Restart,
+            SessionPolicy::New,
+        ] {
+            assert_eq!(
+                plan_session(policy, "gz-claude", &existing),
+                SessionPlan::Create("gz-claude".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn when_session_exists_should_follow_policy() {
+        let existing = sessions(&["gz-claude", "gz-claude-2"]);
+
+        assert_eq!(
+            plan_session(SessionPolicy::Attach, "gz-claude", &existing),
+            SessionPlan::Attach("gz-claude".to_string())
+        );
+        assert_eq!(
+            plan_session(SessionPolicy::Restart, "gz-claude", &existing),
+            SessionPlan::Restart("gz-claude".to_string())
+        );
+        assert_eq!(
+            plan_session(SessionPolicy::New, "gz-claude", &existing),
+            SessionPlan::Create("gz-claude-3".to_string())
+        );
+    }
+
+    #[test]
+    fn when_name_is_free_should_keep_it() {
+        assert_eq!(
+            unique_session_name("work", &sessions(&["gz-claude"])),
+            "work"
+        );
+        assert_eq!(unique_session_name("work", &sessions(&["work"])), "work-2");
+    }
+
+    #[test]
+    fn when_attaching_should_run_zellij_attach() {
+        let runner = Rc::new(MockRunner::new());
+
+        with_runner(runner.clone(), || attach_session("gz-claude")).unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![argv(&["zellij", "attach", "gz-claude"])]
+        );
+    }
+
+    #[test]
+    fn when_killing_exited_session_should_still_delete_it() {
+        let runner = Rc::new(MockRunner::new().with_failure(
+            &["zellij", "kill-session"],
+            1,
+            "No session named gz-claude found",
+        ));
+
+        with_runner(runner.clone(), || kill_session("gz-claude")).unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![
+                argv(&["zellij", "kill-session", "gz-claude"]),
+                argv(&["zellij", "delete-session", "gz-claude"]),
+            ]
+        );
+    }
+}
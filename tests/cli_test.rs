@@ -2,7 +2,7 @@
 //!
 //! @author waabox(waabox[at]gmail[dot]com)
 
-use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 use std::fs;
 use tempfile::TempDir;
@@ -46,7 +46,7 @@ fn when_running_without_config_should_create_example() {
     let temp_dir = TempDir::new().unwrap();
     let home = temp_dir.path();
 
-    let mut cmd = Command::cargo_bin("gz-claude").unwrap();
+    let mut cmd = cargo_bin_cmd!("gz-claude");
     cmd.env("HOME", home)
         .assert()
         .failure()
@@ -58,7 +58,7 @@ fn when_running_with_valid_config_and_no_zellij_should_show_error() {
     let temp_dir = TempDir::new().unwrap();
     setup_test_config(&temp_dir);
 
-    let mut cmd = Command::cargo_bin("gz-claude").unwrap();
+    let mut cmd = cargo_bin_cmd!("gz-claude");
     // Empty PATH to ensure Zellij is not found
     cmd.env("HOME", temp_dir.path())
         .env("PATH", "")
@@ -69,7 +69,7 @@ fn when_running_with_valid_config_and_no_zellij_should_show_error() {
 
 #[test]
 fn when_running_panel_outside_zellij_should_fail() {
-    let mut cmd = Command::cargo_bin("gz-claude").unwrap();
+    let mut cmd = cargo_bin_cmd!("gz-claude");
     cmd.arg("panel")
         .env_remove("ZELLIJ")
         .assert()
@@ -79,7 +79,7 @@ fn when_running_panel_outside_zellij_should_fail() {
 
 #[test]
 fn when_running_with_help_flag_should_show_help() {
-    let mut cmd = Command::cargo_bin("gz-claude").unwrap();
+    let mut cmd = cargo_bin_cmd!("gz-claude");
     cmd.arg("--help")
         .assert()
         .success()
@@ -88,16 +88,26 @@ fn when_running_with_help_flag_should_show_help() {
 
 #[test]
 fn when_running_with_version_flag_should_show_version() {
-    let mut cmd = Command::cargo_bin("gz-claude").unwrap();
+    let mut cmd = cargo_bin_cmd!("gz-claude");
     cmd.arg("--version")
         .assert()
         .success()
         .stdout(predicate::str::contains("gz-claude"));
 }
 
+#[test]
+fn when_setting_secret_with_invalid_name_should_fail() {
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["secret", "set", "not valid"])
+        .write_stdin("value\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid secret name 'not valid'"));
+}
+
 #[test]
 fn when_running_with_web_and_no_web_flags_should_fail() {
-    let mut cmd = Command::cargo_bin("gz-claude").unwrap();
+    let mut cmd = cargo_bin_cmd!("gz-claude");
     cmd.args(["--web", "--no-web"]).assert().failure();
 }
 
@@ -108,7 +118,7 @@ fn when_running_panel_with_valid_config_should_start_tui() {
     let temp_dir = TempDir::new().unwrap();
     setup_test_config(&temp_dir);
 
-    let mut cmd = Command::cargo_bin("gz-claude").unwrap();
+    let mut cmd = cargo_bin_cmd!("gz-claude");
     // Set ZELLIJ env to pretend we're inside Zellij.
     // But the TUI will fail to initialize without a real terminal,
     // so we just verify it gets past the Zellij check.
@@ -123,3 +133,469 @@ fn when_running_panel_with_valid_config_should_start_tui() {
     // Verify it does NOT contain the Zellij environment check error.
     assertion.stderr(predicate::str::contains("must be run inside Zellij").not());
 }
+
+#[test]
+fn when_cloning_missing_project_should_clone_its_repo() {
+    let temp_dir = TempDir::new().unwrap();
+    let origin = temp_dir.path().join("origin");
+    let repo = git2::Repository::init(&origin).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+        .unwrap();
+
+    let target = temp_dir.path().join("work").join("project");
+    let config_dir = temp_dir.path().join(".gz-claude");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config = serde_json::json!({
+        "version": 1,
+        "global": {},
+        "workspace": {
+            "test": {
+                "name": "Test",
+                "projects": [
+                    { "name": "Project", "path": target, "repo": origin }
+                ]
+            }
+        }
+    });
+    fs::write(config_dir.join("config.json"), config.to_string()).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.arg("clone")
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cloning Project"));
+
+    assert!(target.join(".git").exists());
+}
+
+#[test]
+fn when_cloning_unknown_project_should_fail() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join(".gz-claude");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.json"),
+        r#"{ "version": 1, "global": {}, "workspace": { "test": { "name": "Test", "projects": [] } } }"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["clone", "missing"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no project named 'missing'"));
+}
+
+#[test]
+fn when_dry_running_up_should_print_one_tab_per_working_set_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join(".gz-claude");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.json"),
+        r#"{ "version": 1, "global": { "shell": "zsh" }, "workspace": { "test": { "name": "Test",
+            "projects": [{ "name": "api", "path": "/tmp" }, { "name": "web", "path": "/tmp" }],
+            "working_set": ["api", { "project": "web", "run": "shell" }] } } }"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["up", "test", "--dry-run"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "tab name=\"api\" focus=true {\n        pane command=\"claude\"",
+        ))
+        .stdout(predicate::str::contains(
+            "tab name=\"web\" {\n        pane command=\"zsh\"",
+        ));
+}
+
+#[test]
+fn when_dry_running_up_should_add_a_tab_per_autostarted_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join(".gz-claude");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.json"),
+        r#"{ "version": 1, "global": {}, "workspace": { "test": { "name": "Test",
+            "actions": { "t": { "name": "Tests", "command": "cargo test" } },
+            "projects": [{ "name": "api", "path": "/tmp", "autostart": true, "default_action": "t" }] } } }"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["up", "test", "--dry-run"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "tab name=\"api · Tests\" focus=true {\n        pane command=\"sh\" cwd=\"/tmp\" {\n            args \"-c\" \"cargo test /tmp\"",
+        ));
+}
+
+#[test]
+fn when_running_up_for_unknown_workspace_should_fail() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join(".gz-claude");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.json"),
+        r#"{ "version": 1, "global": {}, "workspace": { "test": { "name": "Test", "projects": [] } } }"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["up", "missing"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no workspace 'missing'"));
+}
+
+#[test]
+fn when_dry_running_import_should_list_projects_from_gh() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join(".gz-claude");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.json"),
+        r#"{ "version": 1, "global": {}, "workspace": {} }"#,
+    )
+    .unwrap();
+    let bin_dir = temp_dir.path().join("bin");
+    fs::create_dir_all(&bin_dir).unwrap();
+    let gh = bin_dir.join("gh");
+    fs::write(
+        &gh,
+        r#"#!/bin/sh
+[ "$1" = "--version" ] && exit 0
+echo '[{"name":"api","description":"","isArchived":false,"sshUrl":"git@github.com:acme/api.git","url":"https://github.com/acme/api"},
+{"name":"old","description":"","isArchived":true,"sshUrl":"git@github.com:acme/old.git","url":"https://github.com/acme/old"}]'
+"#,
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&gh, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let path = format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args([
+        "import",
+        "github",
+        "--org",
+        "acme",
+        "--all",
+        "--root",
+        "/src/acme",
+        "--dry-run",
+    ])
+    .env("HOME", temp_dir.path())
+    .env("PATH", path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(
+        "Would add workspace 'acme' with 1 project(s)",
+    ))
+    .stdout(predicate::str::contains(
+        "api: /src/acme/api (clone git@github.com:acme/api.git)",
+    ))
+    .stdout(predicate::str::contains("old").not());
+}
+
+#[test]
+fn when_importing_into_existing_workspace_should_fail() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join(".gz-claude");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.json"),
+        r#"{ "version": 1, "global": {}, "workspace": { "acme": { "name": "Acme", "projects": [] } } }"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["import", "gitlab", "--group", "Acme"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("workspace 'acme' already exists"));
+}
+
+#[test]
+fn when_running_inside_zellij_should_refuse_to_nest() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.env("HOME", temp_dir.path())
+        .env("ZELLIJ", "0")
+        .env("ZELLIJ_SESSION_NAME", "gz-claude")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "already inside the Zellij session 'gz-claude'",
+        ));
+}
+
+#[test]
+fn when_session_policy_is_unknown_should_fail() {
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["--session-policy", "sometimes"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'sometimes'"));
+}
+
+#[test]
+fn when_converting_config_should_write_it_in_the_output_format() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join(".gz-claude");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config = serde_json::json!({
+        "version": 1,
+        "global": { "editor": "vim" },
+        "workspace": { "test": { "name": "Test", "projects": [] } }
+    });
+    fs::write(config_dir.join("config.json"), config.to_string()).unwrap();
+    let output = config_dir.join("config.yaml");
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["config", "convert"])
+        .arg(&output)
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("config.json takes precedence"));
+
+    let yaml = fs::read_to_string(&output).unwrap();
+    assert!(yaml.contains("editor: vim"), "{}", yaml);
+}
+
+#[test]
+fn when_linting_config_should_print_warnings() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join(".gz-claude");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config = serde_json::json!({
+        "version": 1,
+        "global": {},
+        "workspace": { "test": { "name": "Test", "projects": [] } }
+    });
+    fs::write(config_dir.join("config.json"), config.to_string()).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["config", "lint"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "warning: workspace 'test' has no projects",
+        ))
+        .stdout(predicate::str::contains("1 warning(s)"));
+}
+
+#[test]
+fn when_passing_config_path_should_use_it_instead_of_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = serde_json::json!({
+        "version": 1,
+        "global": {},
+        "workspace": { "personal": { "name": "Personal", "projects": [] } }
+    });
+    let config_path = temp_dir.path().join("personal.json");
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.arg("--config")
+        .arg(&config_path)
+        .args(["config", "lint"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "warning: workspace 'personal' has no projects",
+        ));
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["config", "lint"])
+        .env("HOME", temp_dir.path())
+        .env("GZ_CLAUDE_CONFIG", &config_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("workspace 'personal'"));
+}
+
+#[test]
+fn when_exporting_session_should_write_snapshot_with_main_pane_first() {
+    let temp_dir = TempDir::new().unwrap();
+    let sessions_dir = temp_dir.path().join(".gz-claude").join("sessions");
+    fs::create_dir_all(&sessions_dir).unwrap();
+    let state = serde_json::json!({
+        "zellij_session": "work",
+        "panes": {
+            "/work/web": {
+                "pane_name": "helios/web · Test",
+                "command": "cargo test",
+                "floating": true,
+                "pane_id": "terminal_4"
+            },
+            "/work/api": { "pane_name": "helios/api · Claude", "command": "claude" }
+        }
+    });
+    fs::write(sessions_dir.join("work.json"), state.to_string()).unwrap();
+    let output = temp_dir.path().join("snapshot.json");
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["session", "export", "--session", "work"])
+        .arg(&output)
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 2 panes"));
+
+    let snapshot: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    assert_eq!(snapshot["version"], 1);
+    assert_eq!(snapshot["panes"][0]["project_path"], "/work/api");
+    assert_eq!(snapshot["panes"][1]["command"], "cargo test");
+}
+
+#[test]
+fn when_importing_session_outside_zellij_should_fail() {
+    let temp_dir = TempDir::new().unwrap();
+    let snapshot = temp_dir.path().join("snapshot.json");
+    fs::write(
+        &snapshot,
+        r#"{ "version": 1, "zellij_session": "work", "panes": [] }"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["session", "import"])
+        .arg(&snapshot)
+        .env("HOME", temp_dir.path())
+        .env_remove("ZELLIJ")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "not running inside a Zellij session",
+        ));
+}
+
+fn write_layout_test_config(dir: &TempDir) {
+    let config_dir = dir.path().join(".gz-claude");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.json"),
+        r#"{ "version": 1, "global": {}, "workspace": { "test": { "name": "Test", "projects": [] } } }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn when_dumping_layout_should_print_or_write_the_generated_kdl() {
+    let temp_dir = TempDir::new().unwrap();
+    write_layout_test_config(&temp_dir);
+    let output = temp_dir.path().join("layout.kdl");
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["layout", "dump"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("args \"panel\""));
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["layout", "dump", "--output"])
+        .arg(&output)
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&output)
+        .unwrap()
+        .contains("args \"panel\""));
+}
+
+#[test]
+fn when_regenerating_edited_layout_should_require_force() {
+    let temp_dir = TempDir::new().unwrap();
+    write_layout_test_config(&temp_dir);
+    let layouts = temp_dir.path().join(".config/zellij/layouts");
+    fs::create_dir_all(&layouts).unwrap();
+    let installed = layouts.join("gz-claude.kdl");
+    fs::write(&installed, "layout {\n    pane\n}\n").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["layout", "inspect"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-    pane"));
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["layout", "regenerate"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use --force"));
+    assert_eq!(
+        fs::read_to_string(&installed).unwrap(),
+        "layout {\n    pane\n}\n"
+    );
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["layout", "regenerate", "--force"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&installed)
+        .unwrap()
+        .contains("args \"panel\""));
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["layout", "inspect"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("matches the generated layout"));
+}
+
+#[test]
+fn when_regenerating_layout_written_before_checksums_should_not_require_force() {
+    let temp_dir = TempDir::new().unwrap();
+    write_layout_test_config(&temp_dir);
+    let output = temp_dir.path().join("layout.kdl");
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["layout", "dump", "--output"])
+        .arg(&output)
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success();
+    let generated = fs::read_to_string(&output).unwrap();
+
+    // The generated layout without its checksum line
+    let layouts = temp_dir.path().join(".config/zellij/layouts");
+    fs::create_dir_all(&layouts).unwrap();
+    let installed = layouts.join("gz-claude.kdl");
+    fs::write(&installed, generated.split_once('\n').unwrap().1).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gz-claude");
+    cmd.args(["layout", "regenerate"])
+        .env("HOME", temp_dir.path())
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&installed).unwrap(), generated);
+}